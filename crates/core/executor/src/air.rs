@@ -126,6 +126,12 @@ pub enum RiscvAirId {
     Global = 43,
     /// The byte chip.
     Byte = 44,
+    /// The SHA-512 extend chip.
+    Sha512Extend = 45,
+    /// The SHA-512 compress chip.
+    Sha512Compress = 46,
+    /// The bls12-381 fp12 mul assign chip.
+    Bls12381Fp12MulAssign = 47,
 }
 
 impl RiscvAirId {
@@ -200,6 +206,9 @@ impl RiscvAirId {
             Self::MemoryLocal => "MemoryLocal",
             Self::Global => "Global",
             Self::Byte => "Byte",
+            Self::Sha512Extend => "Sha512Extend",
+            Self::Sha512Compress => "Sha512Compress",
+            Self::Bls12381Fp12MulAssign => "Bls12381Fp12MulAssign",
         }
     }
 }
@@ -0,0 +1,166 @@
+use std::{
+    borrow::Cow,
+    io::{self, BufWriter, Read, Write},
+    mem::take,
+    time::Instant,
+};
+
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+use sp1_stark::{air::PublicValues, SP1CoreOpts};
+use thiserror::Error;
+
+use crate::{state::ExecutionState, ExecutionError, Executor, InputBytes, Program};
+
+/// A callback returning the writer to save the checkpoint taken at the start of a shard to.
+pub type CheckpointWriter<'a> = Box<dyn FnMut(u32) -> io::Result<Box<dyn Write + 'a>> + Send + 'a>;
+
+/// The state needed to resume an execution at the start of a shard.
+///
+/// The inputs and the proofs to verify are not part of the checkpoint, only how far the program
+/// has read the inputs is: they are supplied again when resuming.
+#[derive(Serialize, Deserialize)]
+struct ExecutionCheckpoint<'b> {
+    /// The options of the execution, which determine where the shards end.
+    opts: SP1CoreOpts,
+    /// The state of the execution, with empty input and proof streams.
+    state: Cow<'b, ExecutionState>,
+    /// The number of inputs left to read, and the number of bytes left to read of the next one.
+    unread_inputs: (usize, usize),
+    /// The public values carried over to the next shards.
+    public_values: PublicValues<u32, u32>,
+    /// The open cycle tracker spans.
+    cycle_tracker: Cow<'b, HashMap<String, Vec<(u64, u32)>>>,
+    /// The output to stdout and stderr not printed yet.
+    io_buf: Cow<'b, HashMap<u32, String>>,
+}
+
+/// Errors that [`Executor::resume_from_checkpoint`] can throw.
+#[derive(Error, Debug)]
+pub enum CheckpointError {
+    /// The checkpoint could not be read.
+    #[error("failed to read the checkpoint: {0}")]
+    Deserialize(#[from] bincode::Error),
+
+    /// Fewer inputs were given than the checkpoint has left to read.
+    #[error("the checkpoint has {0} inputs left to read, but only {1} inputs were given")]
+    MissingInputs(usize, usize),
+
+    /// The input the checkpoint is reading is shorter than the part of it left to read.
+    #[error("the checkpoint has {0} bytes left to read of an input of {1} bytes")]
+    InputTooShort(usize, usize),
+}
+
+/// A writer counting the bytes written to it.
+struct CountingWriter<W> {
+    inner: W,
+    bytes: u64,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.bytes += len as u64;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<'a> Executor<'a> {
+    /// Saves a checkpoint of the execution every `interval` shards, to the writer returned by
+    /// `writer` for the shard the checkpoint resumes from.
+    ///
+    /// The checkpoints are taken at the start of shards `1 + interval`, `1 + 2 * interval`, and so
+    /// on, and can be resumed from with [`Executor::resume_from_checkpoint`]. Their number, size
+    /// and write time are recorded in [`ExecutionReport::checkpoints`]. If a checkpoint fails to
+    /// be written, the execution is aborted with [`ExecutionError::CheckpointFailed`].
+    ///
+    /// [`ExecutionReport::checkpoints`]: crate::ExecutionReport::checkpoints
+    pub fn set_checkpointing<W: Write + 'a>(
+        &mut self,
+        interval: u32,
+        mut writer: impl FnMut(u32) -> io::Result<W> + Send + 'a,
+    ) {
+        assert!(interval > 0, "the checkpoint interval must be positive");
+        let writer: CheckpointWriter<'a> = Box::new(move |shard| {
+            let writer: Box<dyn Write + 'a> = Box::new(writer(shard)?);
+            Ok(writer)
+        });
+        self.checkpointing = Some((interval, writer));
+    }
+
+    /// Resumes an execution from a checkpoint saved with [`Executor::set_checkpointing`].
+    ///
+    /// `stdin` is the whole input of the execution, of which the part the program already read is
+    /// skipped. The proofs to verify and the deferred proof slots are written again, as with
+    /// [`Executor::write_proof`] and [`Executor::write_deferred_proof_slot`]. Executing the program
+    /// then emits the same shards as the uninterrupted execution, from the shard the checkpoint was
+    /// taken at. The execution report only covers the resumed part of the execution.
+    pub fn resume_from_checkpoint<T: Clone + Into<InputBytes>>(
+        reader: impl Read,
+        program: Program,
+        stdin: &[T],
+    ) -> Result<Self, CheckpointError> {
+        let checkpoint: ExecutionCheckpoint<'_> = bincode::deserialize_from(reader)?;
+
+        let (num_unread, front_unread) = checkpoint.unread_inputs;
+        if stdin.len() < num_unread {
+            return Err(CheckpointError::MissingInputs(num_unread, stdin.len()));
+        }
+
+        let mut runtime = Self::recover(program, checkpoint.state.into_owned(), checkpoint.opts);
+        runtime.write_vecs(&stdin[stdin.len() - num_unread..]);
+        if let Some(front) = runtime.state.input_stream.front_mut() {
+            if front.len() < front_unread {
+                return Err(CheckpointError::InputTooShort(front_unread, front.len()));
+            }
+            front.advance(front.len() - front_unread);
+        }
+        runtime.record.public_values = checkpoint.public_values;
+        runtime.cycle_tracker = checkpoint.cycle_tracker.into_owned();
+        runtime.io_buf = checkpoint.io_buf.into_owned();
+        Ok(runtime)
+    }
+
+    /// Saves a checkpoint if the execution is at the start of a shard to take one at.
+    pub(crate) fn checkpoint_if_due(&mut self) -> Result<(), ExecutionError> {
+        let Some((interval, writer)) = &mut self.checkpointing else {
+            return Ok(());
+        };
+        let shard = self.state.current_shard;
+        if (shard - 1) % *interval != 0 {
+            return Ok(());
+        }
+
+        let start = Instant::now();
+
+        // The streams are supplied again when resuming, so they are left out of the checkpoint.
+        let input_stream = take(&mut self.state.input_stream);
+        let proof_stream = take(&mut self.state.proof_stream);
+        let unread_front = input_stream.front().map_or(0, |input| input.len());
+        let checkpoint = ExecutionCheckpoint {
+            opts: self.opts,
+            state: Cow::Borrowed(&self.state),
+            unread_inputs: (input_stream.len(), unread_front),
+            public_values: self.record.public_values,
+            cycle_tracker: Cow::Borrowed(&self.cycle_tracker),
+            io_buf: Cow::Borrowed(&self.io_buf),
+        };
+        let result = writer(shard).map_err(|e| e.to_string()).and_then(|writer| {
+            let mut writer = CountingWriter { inner: BufWriter::new(writer), bytes: 0 };
+            bincode::serialize_into(&mut writer, &checkpoint).map_err(|e| e.to_string())?;
+            writer.flush().map_err(|e| e.to_string())?;
+            Ok(writer.bytes)
+        });
+        self.state.input_stream = input_stream;
+        self.state.proof_stream = proof_stream;
+        let bytes = result.map_err(|e| ExecutionError::CheckpointFailed(shard, e))?;
+
+        self.report.checkpoints.record(bytes, start.elapsed());
+        tracing::debug!("saved a {bytes} byte checkpoint at shard {shard}");
+        Ok(())
+    }
+}
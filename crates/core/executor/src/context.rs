@@ -1,10 +1,11 @@
 use core::mem::take;
+use std::sync::Arc;
 
 use hashbrown::HashMap;
 
 use crate::{
     hook::{hookify, BoxedHook, HookEnv, HookRegistry},
-    subproof::SubproofVerifier,
+    subproof::{DeferredProofProvider, SubproofVerifier},
 };
 
 use sp1_primitives::consts::fd::LOWEST_ALLOWED_FD;
@@ -20,6 +21,9 @@ pub struct SP1Context<'a> {
     /// The verifier for verifying subproofs.
     pub subproof_verifier: Option<&'a dyn SubproofVerifier>,
 
+    /// The provider of the proofs of the deferred proof slots.
+    pub deferred_proof_provider: Option<Arc<dyn DeferredProofProvider + 'a>>,
+
     /// The maximum number of cpu cycles to use for execution.
     pub max_cycles: Option<u64>,
 
@@ -33,6 +37,7 @@ pub struct SP1ContextBuilder<'a> {
     no_default_hooks: bool,
     hook_registry_entries: Vec<(u32, BoxedHook<'a>)>,
     subproof_verifier: Option<&'a dyn SubproofVerifier>,
+    deferred_proof_provider: Option<Arc<dyn DeferredProofProvider + 'a>>,
     max_cycles: Option<u64>,
     deferred_proof_verification: bool,
 }
@@ -43,6 +48,7 @@ impl Default for SP1ContextBuilder<'_> {
             no_default_hooks: false,
             hook_registry_entries: Vec::new(),
             subproof_verifier: None,
+            deferred_proof_provider: None,
             max_cycles: None,
             // Always verify deferred proofs by default.
             deferred_proof_verification: true,
@@ -98,11 +104,13 @@ impl<'a> SP1ContextBuilder<'a> {
             });
 
         let subproof_verifier = take(&mut self.subproof_verifier);
+        let deferred_proof_provider = take(&mut self.deferred_proof_provider);
         let cycle_limit = take(&mut self.max_cycles);
         let deferred_proof_verification = take(&mut self.deferred_proof_verification);
         SP1Context {
             hook_registry,
             subproof_verifier,
+            deferred_proof_provider,
             max_cycles: cycle_limit,
             deferred_proof_verification,
         }
@@ -144,6 +152,18 @@ impl<'a> SP1ContextBuilder<'a> {
         self
     }
 
+    /// Add a provider of the proofs of the deferred proof slots.
+    ///
+    /// The provider is invoked when the program verifies the proof of a slot, so the proof can
+    /// still be in progress when the execution starts.
+    pub fn deferred_proof_provider(
+        &mut self,
+        provider: impl DeferredProofProvider + 'a,
+    ) -> &mut Self {
+        self.deferred_proof_provider = Some(Arc::new(provider));
+        self
+    }
+
     /// Set the maximum number of cpu cycles to use for execution.
     pub fn max_cycles(&mut self, max_cycles: u64) -> &mut Self {
         self.max_cycles = Some(max_cycles);
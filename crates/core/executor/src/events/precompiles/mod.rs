@@ -4,6 +4,8 @@ mod fptower;
 mod keccak256_permute;
 mod sha256_compress;
 mod sha256_extend;
+mod sha512_compress;
+mod sha512_extend;
 mod u256x2048_mul;
 mod uint256;
 
@@ -18,6 +20,8 @@ pub use keccak256_permute::*;
 use serde::{Deserialize, Serialize};
 pub use sha256_compress::*;
 pub use sha256_extend::*;
+pub use sha512_compress::*;
+pub use sha512_extend::*;
 use strum::{EnumIter, IntoEnumIterator};
 pub use u256x2048_mul::*;
 pub use uint256::*;
@@ -71,10 +75,16 @@ pub enum PrecompileEvent {
     Bls12381Fp2AddSub(Fp2AddSubEvent),
     /// Bls12-381 quadratic field mul precompile event.
     Bls12381Fp2Mul(Fp2MulEvent),
+    /// Bls12-381 degree 12 extension field mul precompile event.
+    Bls12381Fp12Mul(Fp12MulEvent),
     /// Uint256 mul precompile event.
     Uint256Mul(Uint256MulEvent),
     /// U256XU2048 mul precompile event.
     U256xU2048Mul(U256xU2048MulEvent),
+    /// Sha512 extend precompile event.
+    Sha512Extend(Sha512ExtendEvent),
+    /// Sha512 compress precompile event.
+    Sha512Compress(Sha512CompressEvent),
 }
 
 /// Trait to retrieve all the local memory events from a vec of precompile events.
@@ -95,6 +105,12 @@ impl PrecompileLocalMemory for Vec<(SyscallEvent, PrecompileEvent)> {
                 PrecompileEvent::ShaCompress(e) => {
                     iterators.push(e.local_mem_access.iter());
                 }
+                PrecompileEvent::Sha512Extend(e) => {
+                    iterators.push(e.local_mem_access.iter());
+                }
+                PrecompileEvent::Sha512Compress(e) => {
+                    iterators.push(e.local_mem_access.iter());
+                }
                 PrecompileEvent::KeccakPermute(e) => {
                     iterators.push(e.local_mem_access.iter());
                 }
@@ -135,6 +151,9 @@ impl PrecompileLocalMemory for Vec<(SyscallEvent, PrecompileEvent)> {
                 PrecompileEvent::Bls12381Fp2Mul(e) | PrecompileEvent::Bn254Fp2Mul(e) => {
                     iterators.push(e.local_mem_access.iter());
                 }
+                PrecompileEvent::Bls12381Fp12Mul(e) => {
+                    iterators.push(e.local_mem_access.iter());
+                }
             }
         }
 
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+use crate::events::{
+    memory::{MemoryReadRecord, MemoryWriteRecord},
+    MemoryLocalEvent,
+};
+
+/// SHA-512 Compress Event.
+///
+/// This event is emitted when a SHA-512 compress operation is performed. Each 64-bit word is
+/// stored in memory as two little-endian 32-bit words, low word first.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Sha512CompressEvent {
+    /// The shard number.
+    pub shard: u32,
+    /// The clock cycle.
+    pub clk: u32,
+    /// The pointer to the message schedule.
+    pub w_ptr: u32,
+    /// The pointer to the hash state.
+    pub h_ptr: u32,
+    /// The message schedule.
+    pub w: Vec<u64>,
+    /// The hash state before the compression.
+    pub h: [u64; 8],
+    /// The memory reads of the hash state.
+    pub h_read_records: [MemoryReadRecord; 16],
+    /// The memory reads of the message schedule.
+    pub w_i_read_records: Vec<[MemoryReadRecord; 2]>,
+    /// The memory writes of the hash state.
+    pub h_write_records: [MemoryWriteRecord; 16],
+    /// The local memory accesses.
+    pub local_mem_access: Vec<MemoryLocalEvent>,
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+use crate::events::{
+    memory::{MemoryReadRecord, MemoryWriteRecord},
+    MemoryLocalEvent,
+};
+
+/// SHA-512 Extend Event.
+///
+/// This event is emitted when a SHA-512 extend operation is performed. Each 64-bit word of the
+/// message schedule is stored in memory as two little-endian 32-bit words, so every access is a
+/// pair of memory records, low word first.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Sha512ExtendEvent {
+    /// The shard number.
+    pub shard: u32,
+    /// The clock cycle.
+    pub clk: u32,
+    /// The pointer to the message schedule.
+    pub w_ptr: u32,
+    /// The memory reads of w[i-15].
+    pub w_i_minus_15_reads: Vec<[MemoryReadRecord; 2]>,
+    /// The memory reads of w[i-2].
+    pub w_i_minus_2_reads: Vec<[MemoryReadRecord; 2]>,
+    /// The memory reads of w[i-16].
+    pub w_i_minus_16_reads: Vec<[MemoryReadRecord; 2]>,
+    /// The memory reads of w[i-7].
+    pub w_i_minus_7_reads: Vec<[MemoryReadRecord; 2]>,
+    /// The memory writes of w[i].
+    pub w_i_writes: Vec<[MemoryWriteRecord; 2]>,
+    /// The local memory accesses.
+    pub local_mem_access: Vec<MemoryLocalEvent>,
+}
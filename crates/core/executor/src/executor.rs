@@ -1,6 +1,10 @@
 #[cfg(feature = "profiling")]
 use std::{fs::File, io::BufWriter};
-use std::{str::FromStr, sync::Arc};
+use std::{
+    panic::{self, AssertUnwindSafe},
+    str::FromStr,
+    sync::Arc,
+};
 
 #[cfg(feature = "profiling")]
 use crate::profiler::Profiler;
@@ -8,12 +12,13 @@ use clap::ValueEnum;
 use enum_map::EnumMap;
 use hashbrown::HashMap;
 use serde::{Deserialize, Serialize};
-use sp1_primitives::consts::BABYBEAR_PRIME;
+use sp1_primitives::consts::{BABYBEAR_PRIME, PANIC_EXIT_CODE};
 use sp1_stark::{air::PublicValues, SP1CoreOpts};
 use strum::IntoEnumIterator;
 use thiserror::Error;
 
 use crate::{
+    checkpoint::CheckpointWriter,
     context::SP1Context,
     dependencies::{
         emit_auipc_dependency, emit_branch_dependencies, emit_divrem_dependencies,
@@ -26,13 +31,14 @@ use crate::{
         MemoryRecord, MemoryRecordEnum, MemoryWriteRecord, SyscallEvent,
         NUM_LOCAL_MEMORY_ENTRIES_PER_ROW_EXEC,
     },
+    guest_panic::{parse_panic_report, PanicLocation},
     hook::{HookEnv, HookRegistry},
     memory::{Entry, Memory},
     pad_rv32im_event_counts,
     record::{ExecutionRecord, MemoryAccessRecord},
     report::ExecutionReport,
     state::{ExecutionState, ForkState},
-    subproof::SubproofVerifier,
+    subproof::{DeferredProofProvider, SubproofVerifier},
     syscalls::{default_syscall_map, Syscall, SyscallCode, SyscallContext},
     CoreAirId, Instruction, MaximalShapes, Opcode, Program, Register, RiscvAirId,
 };
@@ -47,6 +53,12 @@ pub const UNUSED_PC: u32 = 1;
 /// The maximum number of instructions in a program.
 pub const MAX_PROGRAM_SIZE: usize = 1 << 22;
 
+/// The file descriptor of stdout.
+const FD_STDOUT: u32 = 1;
+
+/// The file descriptor of stderr.
+const FD_STDERR: u32 = 2;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 /// Whether to verify deferred proofs during execution.
 pub enum DeferredProofVerification {
@@ -88,6 +100,9 @@ pub struct Executor<'a> {
     /// Report of the program execution.
     pub report: ExecutionReport,
 
+    /// The start of the heap, right after the end of the program image.
+    pub heap_start: u32,
+
     /// The mode the executor is running in.
     pub executor_mode: ExecutorMode,
 
@@ -135,12 +150,28 @@ pub struct Executor<'a> {
     /// Local memory access events.
     pub local_memory_access: HashMap<u32, MemoryLocalEvent>,
 
-    /// A counter for the number of cycles that have been executed in certain functions.
-    pub cycle_tracker: HashMap<String, (u64, u32)>,
+    /// The start cycle and depth of the open cycle tracker spans, by span name. Spans of the same
+    /// name are nested in the order they were opened.
+    pub cycle_tracker: HashMap<String, Vec<(u64, u32)>>,
 
     /// A buffer for stdout and stderr IO.
     pub io_buf: HashMap<u32, String>,
 
+    /// The callbacks invoked with the bytes the program writes to stdout and stderr, by file
+    /// descriptor. See [`Executor::set_io_callbacks`].
+    pub io_callbacks: HashMap<u32, IoCallback<'a>>,
+
+    /// The bytes written to the file descriptors with an IO callback.
+    pub io_output: HashMap<u32, Vec<u8>>,
+
+    /// The bytes the program wrote to [`sp1_primitives::consts::fd::FD_PANIC`] to report a panic,
+    /// parsed when it halts with [`PANIC_EXIT_CODE`].
+    pub panic_report: Vec<u8>,
+
+    /// The error of a syscall that failed, e.g. because of a panicking IO callback, returned once
+    /// the syscall returns.
+    pub(crate) syscall_error: Option<ExecutionError>,
+
     /// The ZKVM program profiler.
     ///
     /// Keeps track of the number of cycles spent in each function.
@@ -156,6 +187,9 @@ pub struct Executor<'a> {
     /// Verifier used to sanity check `verify_sp1_proof` during runtime.
     pub subproof_verifier: Option<&'a dyn SubproofVerifier>,
 
+    /// Provider of the proofs of the deferred proof slots, fetched when the program verifies them.
+    pub deferred_proof_provider: Option<Arc<dyn DeferredProofProvider + 'a>>,
+
     /// Registry of hooks, to be invoked by writing to certain file descriptors.
     pub hook_registry: HookRegistry<'a>,
 
@@ -179,6 +213,22 @@ pub struct Executor<'a> {
 
     /// event counts for the current shard.
     pub event_counts: EnumMap<RiscvAirId, u64>,
+
+    /// The number of shards between two checkpoints, and the callback returning the writer to save
+    /// each checkpoint to. See [`Executor::set_checkpointing`].
+    pub checkpointing: Option<(u32, CheckpointWriter<'a>)>,
+}
+
+/// A callback invoked with the bytes the program writes to a file descriptor.
+pub type IoCallback<'a> = Box<dyn FnMut(&[u8]) + Send + 'a>;
+
+/// The output the program wrote to stdout and stderr.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GuestOutput {
+    /// The bytes written to stdout.
+    pub stdout: Vec<u8>,
+    /// The bytes written to stderr.
+    pub stderr: Vec<u8>,
 }
 
 /// The different modes the executor can run in.
@@ -212,6 +262,15 @@ pub enum ExecutionError {
     #[error("execution failed with exit code {0}")]
     HaltWithNonZeroExitCode(u32),
 
+    /// The program panicked, and reported the panic before halting.
+    #[error("the program panicked at {location}: {message}")]
+    GuestPanic {
+        /// The message of the panic.
+        message: String,
+        /// The location of the panic in the source of the program.
+        location: PanicLocation,
+    },
+
     /// The execution failed with an invalid memory access.
     #[error("invalid memory access for opcode {0} and address {1}")]
     InvalidMemoryAccess(Opcode, u32),
@@ -239,6 +298,24 @@ pub enum ExecutionError {
     /// The program ended in unconstrained mode.
     #[error("program ended in unconstrained mode")]
     EndInUnconstrained(),
+
+    /// The execution was aborted because the IO callback of a file descriptor panicked.
+    #[error("the IO callback for file descriptor {0} panicked: {1}")]
+    IoCallbackPanicked(u32, String),
+
+    /// The execution failed because the proof of a deferred proof slot was not provided when the
+    /// program verified it.
+    #[error("the proof of deferred proof slot {0} was not provided")]
+    UnresolvedDeferredProof(usize),
+
+    /// The execution was aborted because the checkpoint at the start of a shard failed to be saved.
+    #[error("failed to save the checkpoint at shard {0}: {1}")]
+    CheckpointFailed(u32, String),
+
+    /// The execution was aborted because the program wrote a request to a file descriptor with no
+    /// hook registered for it.
+    #[error("no hook is registered for file descriptor {0}")]
+    UnregisteredHook(u32),
 }
 
 impl<'a> Executor<'a> {
@@ -310,6 +387,10 @@ impl<'a> Executor<'a> {
 
         let hook_registry = context.hook_registry.unwrap_or_default();
 
+        // The heap starts after the last word of the program image, which includes the zeroed
+        // memory of the `.bss` section.
+        let heap_start = program.memory_image.keys().max().map_or(0, |addr| addr + 4);
+
         let costs: HashMap<String, usize> =
             serde_json::from_str(include_str!("./artifacts/rv32im_costs.json")).unwrap();
         let costs: HashMap<RiscvAirId, usize> =
@@ -325,6 +406,10 @@ impl<'a> Executor<'a> {
             shard_batch_size: opts.shard_batch_size as u32,
             cycle_tracker: HashMap::new(),
             io_buf: HashMap::new(),
+            io_callbacks: HashMap::new(),
+            io_output: HashMap::new(),
+            panic_report: Vec::new(),
+            syscall_error: None,
             #[cfg(feature = "profiling")]
             profiler: None,
             unconstrained: false,
@@ -334,9 +419,11 @@ impl<'a> Executor<'a> {
             emit_global_memory_events: true,
             max_syscall_cycles,
             report: ExecutionReport::default(),
+            heap_start,
             local_counts: LocalCounts::default(),
             print_report: false,
             subproof_verifier: context.subproof_verifier,
+            deferred_proof_provider: context.deferred_proof_provider,
             hook_registry,
             opts,
             max_cycles: context.max_cycles,
@@ -350,6 +437,7 @@ impl<'a> Executor<'a> {
             lde_size_check: false,
             lde_size_threshold: 0,
             event_counts: EnumMap::default(),
+            checkpointing: None,
         }
     }
 
@@ -367,6 +455,51 @@ impl<'a> Executor<'a> {
             .invoke_hook(self.hook_env(), buf))
     }
 
+    /// Sets the callbacks invoked with the bytes of each write of the program to stdout and
+    /// stderr, as the write syscalls are executed.
+    ///
+    /// The writes to these file descriptors are passed to the callbacks instead of being printed,
+    /// and are accumulated in [`Executor::io_output`]. If a callback panics, the execution is
+    /// aborted with [`ExecutionError::IoCallbackPanicked`].
+    pub fn set_io_callbacks(
+        &mut self,
+        on_stdout: impl FnMut(&[u8]) + Send + 'a,
+        on_stderr: impl FnMut(&[u8]) + Send + 'a,
+    ) {
+        self.io_callbacks.insert(FD_STDOUT, Box::new(on_stdout));
+        self.io_callbacks.insert(FD_STDERR, Box::new(on_stderr));
+    }
+
+    /// Takes the output accumulated for the file descriptors with an IO callback.
+    pub fn take_guest_output(&mut self) -> GuestOutput {
+        GuestOutput {
+            stdout: self.io_output.remove(&FD_STDOUT).unwrap_or_default(),
+            stderr: self.io_output.remove(&FD_STDERR).unwrap_or_default(),
+        }
+    }
+
+    /// Invokes the IO callback of the file descriptor `fd` with the written bytes, returning
+    /// whether there is a callback for `fd`.
+    pub(crate) fn invoke_io_callback(&mut self, fd: u32, bytes: &[u8]) -> bool {
+        if !self.io_callbacks.contains_key(&fd) {
+            return false;
+        }
+        self.io_output.entry(fd).or_default().extend_from_slice(bytes);
+
+        let callback = self.io_callbacks.get_mut(&fd).unwrap();
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| callback(bytes))) {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(ToString::to_string)
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            // The callback may be in an inconsistent state, so it is not invoked again.
+            self.io_callbacks.remove(&fd);
+            self.syscall_error = Some(ExecutionError::IoCallbackPanicked(fd, message));
+        }
+        true
+    }
+
     /// Prepare a `HookEnv` for use by hooks.
     #[must_use]
     pub fn hook_env<'b>(&'b self) -> HookEnv<'b, 'a> {
@@ -494,6 +627,10 @@ impl<'a> Executor<'a> {
             panic!("Invalid memory access: addr={addr}");
         }
 
+        if !self.unconstrained {
+            self.report.memory_stats.record_access(addr, self.heap_start);
+        }
+
         // Get the memory record entry.
         let entry = self.state.memory.page_table.entry(addr);
         if self.executor_mode == ExecutorMode::Checkpoint || self.unconstrained {
@@ -713,6 +850,10 @@ impl<'a> Executor<'a> {
             panic!("Invalid memory access: addr={addr}");
         }
 
+        if !self.unconstrained {
+            self.report.memory_stats.record_access(addr, self.heap_start);
+        }
+
         // Get the memory record entry.
         let entry = self.state.memory.page_table.entry(addr);
         if self.executor_mode == ExecutorMode::Checkpoint || self.unconstrained {
@@ -1542,7 +1683,8 @@ impl<'a> Executor<'a> {
         let b = self.rr_cpu(Register::X10, MemoryAccessPosition::B);
         let syscall = SyscallCode::from_u32(syscall_id);
 
-        if self.print_report && !self.unconstrained {
+        let report = self.print_report && !self.unconstrained;
+        if report {
             self.report.syscall_counts[syscall] += 1;
         }
 
@@ -1571,11 +1713,22 @@ impl<'a> Executor<'a> {
                 // register. If it returns None, we just keep the
                 // syscall_id in t0.
                 let res = syscall_impl.execute(&mut precompile_rt, syscall, b, c);
+                if let Some(error) = precompile_rt.rt.syscall_error.take() {
+                    return Err(error);
+                }
                 let a = if let Some(val) = res { val } else { syscall_id };
 
-                // If the syscall is `HALT` and the exit code is non-zero, return an error.
+                // If the syscall is `HALT` and the exit code is non-zero, return an error, with the
+                // panic the program reported if it halted because of it.
                 if syscall == SyscallCode::HALT && precompile_rt.exit_code != 0 {
-                    return Err(ExecutionError::HaltWithNonZeroExitCode(precompile_rt.exit_code));
+                    let exit_code = precompile_rt.exit_code;
+                    if exit_code == u32::from(PANIC_EXIT_CODE) {
+                        let report = parse_panic_report(&precompile_rt.rt.panic_report);
+                        if let Some((message, location)) = report {
+                            return Err(ExecutionError::GuestPanic { message, location });
+                        }
+                    }
+                    return Err(ExecutionError::HaltWithNonZeroExitCode(exit_code));
                 }
 
                 (a, precompile_rt.next_pc, syscall_impl.num_extra_cycles(), precompile_rt.exit_code)
@@ -1597,6 +1750,10 @@ impl<'a> Executor<'a> {
         let clk = self.state.clk;
         self.state.clk += precompile_cycles;
 
+        if report {
+            self.report.syscall_cycles[syscall] += 1 + u64::from(precompile_cycles);
+        }
+
         Ok((a, b, c, clk, precompile_next_pc, syscall, returned_exit_code))
     }
 
@@ -1730,6 +1887,7 @@ impl<'a> Executor<'a> {
                 self.state.current_shard += 1;
                 self.state.clk = 0;
                 self.bump_record();
+                self.checkpoint_if_due()?;
             }
         }
 
@@ -1808,6 +1966,9 @@ impl<'a> Executor<'a> {
         self.state.uninitialized_memory = uninitialized_memory;
 
         let done = tracing::debug_span!("execute").in_scope(|| self.execute())?;
+        // The deferred proofs resolved during the batch are needed to execute it again from the
+        // checkpoint.
+        checkpoint.proof_stream.clone_from(&self.state.proof_stream);
         // Create a checkpoint using `memory_checkpoint`. Just include all memory if `done` since we
         // need it all for MemoryFinalize.
         let next_pc = self.state.pc;
@@ -2219,17 +2380,21 @@ pub const fn align(addr: u32) -> u32 {
 #[cfg(test)]
 mod tests {
 
+    use sp1_primitives::consts::MAX_PANIC_MESSAGE_BYTES;
     use sp1_stark::SP1CoreOpts;
     use sp1_zkvm::syscalls::SHA_COMPRESS;
 
     use crate::programs::tests::{
-        fibonacci_program, panic_program, secp256r1_add_program, secp256r1_double_program,
-        simple_memory_program, simple_program, ssz_withdrawals_program, u256xu2048_mul_program,
+        cycle_tracker_nested_program, fibonacci_program, io_callbacks_program,
+        memory_stats_program, panic_message_program, panic_program, read_chunks_program,
+        secp256r1_add_program, secp256r1_double_program, simple_memory_program, simple_program,
+        ssz_withdrawals_program, u256xu2048_mul_program, verify_proof_program,
     };
+    use std::io::{BufReader, Seek, SeekFrom};
 
-    use crate::Register;
+    use crate::{syscalls::SyscallCode, Register};
 
-    use super::{Executor, Instruction, Opcode, Program};
+    use super::{ExecutionError, Executor, Instruction, Opcode, Program};
 
     fn _assert_send<T: Send>() {}
 
@@ -2253,6 +2418,157 @@ mod tests {
         runtime.run().unwrap();
     }
 
+    #[test]
+    fn test_cycle_tracker_report() {
+        let program = cycle_tracker_nested_program();
+        let mut runtime = Executor::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert!(runtime.cycle_tracker.is_empty());
+
+        let spans = &runtime.report.cycle_tracker;
+        let (outer, inner, fib) = (spans["outer"], spans["inner"], spans["fib"]);
+        assert_eq!(outer.invocations, 1);
+        assert_eq!(inner.invocations, 3);
+        // `fib(2)`, `fib(3)` and `fib(4)` make 3, 5 and 9 calls.
+        assert_eq!(fib.invocations, 17);
+
+        // Each span is contained in the spans it is nested in, and the nested invocations of `fib`
+        // are only counted once.
+        assert!(fib.total_cycles < inner.total_cycles);
+        assert!(inner.total_cycles < outer.total_cycles);
+        assert_eq!(outer.max_cycles, outer.total_cycles);
+        assert!(inner.max_cycles < inner.total_cycles);
+        assert!(fib.max_cycles < fib.total_cycles);
+
+        let report = &runtime.report;
+        assert!(report.syscall_counts[SyscallCode::WRITE] > 0);
+        assert_eq!(
+            report.syscall_cycles[SyscallCode::WRITE],
+            report.syscall_counts[SyscallCode::WRITE]
+        );
+    }
+
+    #[test]
+    fn test_io_callbacks() {
+        let program = io_callbacks_program();
+        let mut stdout_writes = Vec::new();
+        let mut stderr_writes = Vec::new();
+        let mut runtime = Executor::new(program, SP1CoreOpts::default());
+        runtime.set_io_callbacks(
+            |bytes| stdout_writes.push(bytes.to_vec()),
+            |bytes| stderr_writes.push(bytes.to_vec()),
+        );
+        runtime.run().unwrap();
+        let write_count = runtime.report.syscall_counts[SyscallCode::WRITE];
+        let output = runtime.take_guest_output();
+        drop(runtime);
+
+        // The callbacks are invoked once per write syscall, so at least once per line.
+        assert!(stdout_writes.len() >= 5);
+        assert_eq!((stdout_writes.len() + stderr_writes.len()) as u64, write_count);
+
+        let expected_stdout = (0..5).map(|i| format!("progress {i}\n")).collect::<String>();
+        assert_eq!(stdout_writes.concat(), expected_stdout.as_bytes());
+        assert_eq!(stderr_writes.concat(), b"done\n");
+        assert_eq!(output.stdout, expected_stdout.as_bytes());
+        assert_eq!(output.stderr, b"done\n");
+    }
+
+    #[test]
+    fn test_io_callback_panic() {
+        let program = io_callbacks_program();
+        let mut stdout_writes = 0;
+        let mut runtime = Executor::new(program, SP1CoreOpts::default());
+        runtime.set_io_callbacks(
+            |_| {
+                stdout_writes += 1;
+                panic!("callback failed");
+            },
+            |_| {},
+        );
+        let error = runtime.run().unwrap_err();
+        drop(runtime);
+
+        assert_eq!(error, ExecutionError::IoCallbackPanicked(1, "callback failed".to_string()));
+        assert_eq!(stdout_writes, 1);
+    }
+
+    #[test]
+    fn test_memory_stats() {
+        // The number of bytes the program allocates and writes.
+        const ALLOC_BYTES: u64 = 1 << 20;
+
+        let program = memory_stats_program();
+        let mut runtime = Executor::new(program.clone(), SP1CoreOpts::default());
+        runtime.run_fast().unwrap();
+        let stats = runtime.report.memory_stats.clone();
+        assert!(stats.peak_heap_bytes >= ALLOC_BYTES);
+        assert!(stats.peak_heap_bytes < 2 * ALLOC_BYTES);
+        assert!(stats.touched_bytes() >= ALLOC_BYTES);
+        assert!(stats.total_mem_ops >= ALLOC_BYTES / 4);
+
+        // The stats don't depend on whether the execution is traced.
+        let mut runtime = Executor::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(runtime.report.memory_stats, stats);
+    }
+
+    #[test]
+    fn test_resume_from_checkpoint() {
+        let program = read_chunks_program();
+        let input = (0..(1 << 17) + 1234).map(|i: u32| (i % 251) as u8).collect::<Vec<_>>();
+        let stdin = vec![input, bincode::serialize(&7u32).unwrap()];
+        let mut opts = SP1CoreOpts::default();
+        opts.shard_size = 1 << 16;
+
+        let mut checkpoints = Vec::new();
+        let mut runtime = Executor::new(program.clone(), opts);
+        runtime.write_vecs(&stdin);
+        runtime.set_checkpointing(4, |shard| {
+            let file = tempfile::tempfile()?;
+            checkpoints.push((shard, file.try_clone()?));
+            Ok(file)
+        });
+        runtime.run_fast().unwrap();
+        let registers = runtime.registers();
+        let public_values = runtime.state.public_values_stream.clone();
+        let stats = runtime.report.checkpoints;
+        drop(runtime);
+
+        // The checkpoints are taken at the start of every fourth shard.
+        let shards = checkpoints.iter().map(|&(shard, _)| shard).collect::<Vec<_>>();
+        assert!(shards.len() >= 2);
+        assert_eq!(shards, (1..=shards.len() as u32).map(|i| 1 + 4 * i).collect::<Vec<_>>());
+        assert_eq!(stats.count, checkpoints.len() as u64);
+        let total_bytes = checkpoints.iter().map(|(_, file)| file.metadata().unwrap().len()).sum();
+        assert_eq!(stats.total_bytes, total_bytes);
+
+        // Resuming from any checkpoint ends in the same state as the uninterrupted execution.
+        for (_, mut file) in checkpoints {
+            file.seek(SeekFrom::Start(0)).unwrap();
+            let mut runtime =
+                Executor::resume_from_checkpoint(BufReader::new(file), program.clone(), &stdin)
+                    .unwrap();
+            runtime.run_fast().unwrap();
+            assert_eq!(runtime.registers(), registers);
+            assert_eq!(runtime.state.public_values_stream, public_values);
+        }
+    }
+
+    #[test]
+    fn test_unresolved_deferred_proof() {
+        let program = verify_proof_program();
+        let mut runtime = Executor::new(program, SP1CoreOpts::default());
+        runtime.write_vecs(&[
+            bincode::serialize(&[0u32; 8]).unwrap(),
+            bincode::serialize(&vec![vec![1u8, 2, 3]]).unwrap(),
+        ]);
+        let slot = runtime.write_deferred_proof_slot();
+
+        // Without a provider, the program fails when it verifies the proof of the slot.
+        assert_eq!(runtime.run_fast(), Err(ExecutionError::UnresolvedDeferredProof(slot)));
+    }
+
     #[test]
     fn test_secp256r1_add_program_run() {
         let program = secp256r1_add_program();
@@ -2289,6 +2605,37 @@ mod tests {
         runtime.run().unwrap();
     }
 
+    #[test]
+    fn test_guest_panic() {
+        let run = |len: u32, replace_hook: bool| {
+            let mut runtime = Executor::new(panic_message_program(), SP1CoreOpts::default());
+            runtime.write_vecs(&[
+                bincode::serialize(&len).unwrap(),
+                bincode::serialize(&replace_hook).unwrap(),
+            ]);
+            runtime.run_fast()
+        };
+
+        let Err(ExecutionError::GuestPanic { message, location }) = run(5, false) else {
+            panic!("expected the program to report a panic");
+        };
+        assert_eq!(message, "message of 5 bytes: aaaaa");
+        assert!(location.file.ends_with("main.rs"));
+        assert_eq!(location.line, 12);
+
+        // Huge messages are truncated.
+        let Err(ExecutionError::GuestPanic { message, .. }) = run(10_000, false) else {
+            panic!("expected the program to report a panic");
+        };
+        assert_eq!(message.len(), MAX_PANIC_MESSAGE_BYTES);
+        assert!(message.starts_with("message of 10000 bytes: aaa"));
+
+        // Without the panic hook of the entrypoint, the program still fails, but without a report.
+        let result = run(5, true);
+        assert!(result.is_err());
+        assert!(!matches!(result, Err(ExecutionError::GuestPanic { .. })));
+    }
+
     #[test]
     fn test_add() {
         // main:
@@ -0,0 +1,279 @@
+use enum_map::EnumMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{syscalls::SyscallCode, ExecutionReport, Opcode};
+
+/// The gas weights of a precompile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrecompileGas {
+    /// The gas of an invocation of the precompile, on top of the gas of its `ecall` instruction.
+    pub weight: u64,
+    /// The number of invocations of the precompile that fit in a precompile shard.
+    pub events_per_shard: u64,
+}
+
+/// A table of weights used to compute the [`ProverGas`] of an execution.
+///
+/// The gas of an execution estimates the cost of proving it better than its cycle count, since
+/// the cost of a cycle depends on the chips its instruction or precompile uses, and every shard
+/// adds a fixed cost of recursion. The weights of a given version of the table never change: any
+/// change of a weight comes with a new version, so that gas values of the same version can be
+/// compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasTable {
+    /// The version of the table.
+    pub version: u32,
+    /// The gas of an ADD, SUB, XOR, OR or AND instruction.
+    pub alu: u64,
+    /// The gas of an SLL, SRL or SRA instruction.
+    pub shift: u64,
+    /// The gas of an SLT or SLTU instruction.
+    pub lt: u64,
+    /// The gas of a MUL, MULH, MULHU or MULHSU instruction.
+    pub mul: u64,
+    /// The gas of a DIV, DIVU, REM or REMU instruction.
+    pub divrem: u64,
+    /// The gas of a load instruction.
+    pub load: u64,
+    /// The gas of a store instruction.
+    pub store: u64,
+    /// The gas of a branch instruction.
+    pub branch: u64,
+    /// The gas of a JAL or JALR instruction.
+    pub jump: u64,
+    /// The gas of an AUIPC instruction.
+    pub auipc: u64,
+    /// The gas of an ECALL, EBREAK or UNIMP instruction.
+    pub ecall: u64,
+    /// The weights of the precompiles. The other syscalls only cost the gas of their `ecall`.
+    pub precompiles: &'static [(SyscallCode, PrecompileGas)],
+    /// The number of cycles of a core shard.
+    pub core_shard_cycles: u64,
+    /// The gas of a shard, on top of the gas of its instructions and precompiles.
+    pub shard: u64,
+}
+
+/// The default number of invocations of a precompile that fit in a precompile shard.
+const DEFERRED_SPLIT_THRESHOLD: u64 = 1 << 15;
+
+/// The precompile weights of [`GasTable::V1`].
+const PRECOMPILES_V1: &[(SyscallCode, PrecompileGas)] = &[
+    (SyscallCode::SHA_EXTEND, precompile_gas(250, 32 * DEFERRED_SPLIT_THRESHOLD / 48)),
+    (SyscallCode::SHA_COMPRESS, precompile_gas(400, 32 * DEFERRED_SPLIT_THRESHOLD / 80)),
+    (SyscallCode::SHA512_EXTEND, precompile_gas(500, 16 * DEFERRED_SPLIT_THRESHOLD / 64)),
+    (SyscallCode::SHA512_COMPRESS, precompile_gas(900, 16 * DEFERRED_SPLIT_THRESHOLD / 96)),
+    (SyscallCode::KECCAK_PERMUTE, precompile_gas(1_200, 8 * DEFERRED_SPLIT_THRESHOLD / 24)),
+    (SyscallCode::ED_ADD, precompile_gas(350, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::ED_DECOMPRESS, precompile_gas(350, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::SECP256K1_ADD, precompile_gas(350, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::SECP256K1_DOUBLE, precompile_gas(350, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::SECP256K1_DECOMPRESS, precompile_gas(350, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::SECP256R1_ADD, precompile_gas(350, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::SECP256R1_DOUBLE, precompile_gas(350, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::SECP256R1_DECOMPRESS, precompile_gas(350, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::BN254_ADD, precompile_gas(350, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::BN254_DOUBLE, precompile_gas(350, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::BLS12381_ADD, precompile_gas(500, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::BLS12381_DOUBLE, precompile_gas(500, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::BLS12381_DECOMPRESS, precompile_gas(500, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::UINT256_MUL, precompile_gas(100, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::U256XU2048_MUL, precompile_gas(900, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::BN254_FP_ADD, precompile_gas(60, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::BN254_FP_SUB, precompile_gas(60, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::BN254_FP_MUL, precompile_gas(60, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::BN254_FP2_ADD, precompile_gas(80, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::BN254_FP2_SUB, precompile_gas(80, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::BN254_FP2_MUL, precompile_gas(150, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::BLS12381_FP_ADD, precompile_gas(80, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::BLS12381_FP_SUB, precompile_gas(80, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::BLS12381_FP_MUL, precompile_gas(80, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::BLS12381_FP2_ADD, precompile_gas(110, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::BLS12381_FP2_SUB, precompile_gas(110, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::BLS12381_FP2_MUL, precompile_gas(200, DEFERRED_SPLIT_THRESHOLD)),
+    (SyscallCode::BLS12381_FP12_MUL, precompile_gas(1_400, DEFERRED_SPLIT_THRESHOLD / 8)),
+];
+
+const fn precompile_gas(weight: u64, events_per_shard: u64) -> PrecompileGas {
+    PrecompileGas { weight, events_per_shard }
+}
+
+impl GasTable {
+    /// The first version of the table.
+    pub const V1: Self = Self {
+        version: 1,
+        alu: 2,
+        shift: 3,
+        lt: 3,
+        mul: 4,
+        divrem: 8,
+        load: 4,
+        store: 4,
+        branch: 4,
+        jump: 3,
+        auipc: 2,
+        ecall: 2,
+        precompiles: PRECOMPILES_V1,
+        core_shard_cycles: 1 << 21,
+        shard: 500_000,
+    };
+
+    /// The latest version of the table, used by [`ExecutionReport::gas`].
+    pub const LATEST: Self = Self::V1;
+
+    /// The gas of an instruction with the given opcode.
+    #[must_use]
+    pub const fn opcode_weight(&self, opcode: Opcode) -> u64 {
+        match opcode {
+            Opcode::ADD | Opcode::SUB | Opcode::XOR | Opcode::OR | Opcode::AND => self.alu,
+            Opcode::SLL | Opcode::SRL | Opcode::SRA => self.shift,
+            Opcode::SLT | Opcode::SLTU => self.lt,
+            Opcode::MUL | Opcode::MULH | Opcode::MULHU | Opcode::MULHSU => self.mul,
+            Opcode::DIV | Opcode::DIVU | Opcode::REM | Opcode::REMU => self.divrem,
+            Opcode::LB | Opcode::LH | Opcode::LW | Opcode::LBU | Opcode::LHU => self.load,
+            Opcode::SB | Opcode::SH | Opcode::SW => self.store,
+            Opcode::BEQ | Opcode::BNE | Opcode::BLT | Opcode::BGE | Opcode::BLTU | Opcode::BGEU => {
+                self.branch
+            }
+            Opcode::JAL | Opcode::JALR => self.jump,
+            Opcode::AUIPC => self.auipc,
+            Opcode::ECALL | Opcode::EBREAK | Opcode::UNIMP => self.ecall,
+        }
+    }
+
+    /// The gas weights of the given syscall, if it is a precompile.
+    #[must_use]
+    pub fn precompile(&self, syscall: SyscallCode) -> Option<&PrecompileGas> {
+        self.precompiles.iter().find(|(code, _)| *code == syscall).map(|(_, gas)| gas)
+    }
+
+    /// Computes the gas of the execution described by the report.
+    ///
+    /// The number of shards is estimated from the number of cycles and of precompile invocations,
+    /// assuming the default shard sizes. The cost of the deferred proofs verified by the program is
+    /// not included.
+    #[must_use]
+    pub fn gas(&self, report: &ExecutionReport) -> ProverGas {
+        let instructions = report
+            .opcode_counts
+            .iter()
+            .map(|(opcode, count)| count * self.opcode_weight(opcode))
+            .sum();
+
+        // Precompiles sharing a chip, as given by `SyscallCode::count_map`, share their shards.
+        let mut precompiles = 0;
+        let mut chip_events = EnumMap::<SyscallCode, u64>::default();
+        for (syscall, &count) in report.syscall_counts.iter() {
+            if let Some(gas) = self.precompile(syscall) {
+                precompiles += count * gas.weight;
+                chip_events[syscall.count_map()] += count;
+            }
+        }
+        let precompile_shards: u64 = chip_events
+            .iter()
+            .filter_map(|(syscall, &count)| {
+                let gas = self.precompile(syscall)?;
+                Some(count.div_ceil(gas.events_per_shard))
+            })
+            .sum();
+        let core_shards = report.total_instruction_count().div_ceil(self.core_shard_cycles).max(1);
+        let shards = core_shards + precompile_shards;
+
+        ProverGas {
+            table_version: self.version,
+            instructions,
+            precompiles,
+            shards,
+            shard_overhead: shards * self.shard,
+        }
+    }
+}
+
+/// The gas of an execution, an estimate of the cost of proving it computed with a [`GasTable`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProverGas {
+    /// The version of the [`GasTable`] the gas was computed with.
+    pub table_version: u32,
+    /// The gas of the instructions.
+    pub instructions: u64,
+    /// The gas of the precompiles, on top of the gas of their `ecall` instructions.
+    pub precompiles: u64,
+    /// The estimated number of shards.
+    pub shards: u64,
+    /// The gas of the shards.
+    pub shard_overhead: u64,
+}
+
+impl ProverGas {
+    /// The total gas.
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.instructions + self.precompiles + self.shard_overhead
+    }
+}
+
+impl ExecutionReport {
+    /// Computes the gas of the execution with the latest [`GasTable`].
+    #[must_use]
+    pub fn gas(&self) -> ProverGas {
+        GasTable::LATEST.gas(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sp1_stark::SP1CoreOpts;
+
+    use super::*;
+    use crate::{
+        programs::tests::{simple_memory_program, simple_program},
+        Executor, Program,
+    };
+
+    fn execution_gas(program: Program) -> ProverGas {
+        let mut runtime = Executor::new(program, SP1CoreOpts::default());
+        runtime.run_fast().unwrap();
+        runtime.report.gas()
+    }
+
+    #[test]
+    fn test_gas_simple_program() {
+        // Three ADD instructions in a single shard.
+        let gas = execution_gas(simple_program());
+        assert_eq!(gas.table_version, 1);
+        assert_eq!(gas.total(), 500_006);
+    }
+
+    #[test]
+    fn test_gas_simple_memory_program() {
+        // Two ADD, 17 load and 9 store instructions in a single shard.
+        let gas = execution_gas(simple_memory_program());
+        assert_eq!(gas.total(), 500_108);
+    }
+
+    #[test]
+    fn test_gas_precompile_shards() {
+        let mut report = ExecutionReport::default();
+        report.opcode_counts[Opcode::ADD] = 1_000_000;
+        report.opcode_counts[Opcode::ECALL] = 20_000;
+        report.syscall_counts[SyscallCode::KECCAK_PERMUTE] = 20_000;
+
+        // One core shard and two keccak shards.
+        let gas = report.gas();
+        assert_eq!(gas.shards, 3);
+        assert_eq!(gas.total(), 2_000_000 + 40_000 + 24_000_000 + 1_500_000);
+    }
+
+    #[test]
+    fn test_gas_coalesced_precompiles() {
+        let mut report = ExecutionReport::default();
+        report.syscall_counts[SyscallCode::BN254_FP_ADD] = DEFERRED_SPLIT_THRESHOLD;
+        report.syscall_counts[SyscallCode::BN254_FP_MUL] = 1;
+        report.syscall_counts[SyscallCode::WRITE] = 5;
+
+        // The field operations share a chip, and thus two shards. Writes are not precompiles.
+        let gas = report.gas();
+        assert_eq!(gas.shards, 3);
+        assert_eq!(gas.precompiles, (DEFERRED_SPLIT_THRESHOLD + 1) * 60);
+        assert_eq!(gas.total(), (DEFERRED_SPLIT_THRESHOLD + 1) * 60 + 3 * 500_000);
+    }
+}
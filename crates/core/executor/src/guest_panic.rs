@@ -0,0 +1,89 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use sp1_primitives::consts::MAX_PANIC_MESSAGE_BYTES;
+
+/// The maximum number of bytes of a panic report that are kept, which leaves room for the header,
+/// the file and a message of the maximum length.
+const MAX_PANIC_REPORT_BYTES: usize = 16 + 2 * MAX_PANIC_MESSAGE_BYTES;
+
+/// The location in the source of the program where it panicked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PanicLocation {
+    /// The file the program panicked in.
+    pub file: String,
+    /// The line the program panicked at.
+    pub line: u32,
+    /// The column the program panicked at.
+    pub column: u32,
+}
+
+impl fmt::Display for PanicLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+/// Appends bytes the program wrote to [`sp1_primitives::consts::fd::FD_PANIC`] to `report`, up to
+/// [`MAX_PANIC_REPORT_BYTES`] bytes in total.
+pub(crate) fn append_panic_report(report: &mut Vec<u8>, bytes: &[u8]) {
+    let room = MAX_PANIC_REPORT_BYTES.saturating_sub(report.len());
+    report.extend_from_slice(&bytes[..bytes.len().min(room)]);
+}
+
+/// Parses the first panic report in the bytes the program wrote to
+/// [`sp1_primitives::consts::fd::FD_PANIC`], returning the message and location of the panic.
+///
+/// The message is truncated to [`MAX_PANIC_MESSAGE_BYTES`] bytes, or to the part of it that was
+/// kept. Returns `None` if the bytes do not hold the header and the file of a report.
+pub(crate) fn parse_panic_report(bytes: &[u8]) -> Option<(String, PanicLocation)> {
+    let field = |i: usize| {
+        let bytes = bytes.get(4 * i..4 * (i + 1))?;
+        Some(u32::from_le_bytes(bytes.try_into().unwrap()))
+    };
+    let (line, column) = (field(0)?, field(1)?);
+    let (file_len, message_len) = (field(2)? as usize, field(3)? as usize);
+
+    let file = bytes.get(16..16 + file_len)?;
+    let message = &bytes[16 + file_len..];
+    let message = &message[..message.len().min(message_len).min(MAX_PANIC_MESSAGE_BYTES)];
+
+    let location = PanicLocation { file: String::from_utf8_lossy(file).into_owned(), line, column };
+    Some((String::from_utf8_lossy(message).into_owned(), location))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(file: &str, message: &str) -> Vec<u8> {
+        let fields = [3u32, 7, file.len() as u32, message.len() as u32];
+        let mut report = fields.iter().flat_map(|field| field.to_le_bytes()).collect::<Vec<_>>();
+        report.extend_from_slice(file.as_bytes());
+        report.extend_from_slice(message.as_bytes());
+        report
+    }
+
+    #[test]
+    fn test_parse_panic_report() {
+        let (message, location) = parse_panic_report(&report("src/main.rs", "oops")).unwrap();
+        assert_eq!(message, "oops");
+        assert_eq!(location.to_string(), "src/main.rs:3:7");
+
+        // A report cut before the end of the file is not parsed.
+        assert!(parse_panic_report(&report("src/main.rs", "oops")[..20]).is_none());
+    }
+
+    #[test]
+    fn test_append_panic_report_is_bounded() {
+        let message = "a".repeat(10 * MAX_PANIC_MESSAGE_BYTES);
+        let mut bytes = Vec::new();
+        for chunk in report("src/main.rs", &message).chunks(1000) {
+            append_panic_report(&mut bytes, chunk);
+        }
+        assert_eq!(bytes.len(), MAX_PANIC_REPORT_BYTES);
+
+        let (parsed, _) = parse_panic_report(&bytes).unwrap();
+        assert_eq!(parsed, message[..MAX_PANIC_MESSAGE_BYTES]);
+    }
+}
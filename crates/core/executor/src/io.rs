@@ -1,11 +1,142 @@
-use std::io::Read;
+use std::{fmt, io::Read, ops::Deref, sync::Arc};
 
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
 use sp1_stark::{baby_bear_poseidon2::BabyBearPoseidon2, StarkVerifyingKey};
 
 use super::Executor;
 use crate::SP1ReduceProof;
 
+/// A chunk of bytes in the standard input stream.
+///
+/// Large inputs can be shared through an [`Arc`], so that they are referenced rather than copied
+/// when they are written to the stdin of a program or handed to the executor. Either way, the
+/// bytes are serialized the same as a `Vec<u8>`.
+///
+/// The bytes already read by a program reading the input in chunks are skipped, and are not part
+/// of the bytes the input dereferences to.
+#[derive(Clone)]
+pub struct InputBytes {
+    repr: InputBytesRepr,
+    start: usize,
+}
+
+#[derive(Clone)]
+enum InputBytesRepr {
+    Owned(Vec<u8>),
+    Shared(Arc<[u8]>),
+}
+
+impl InputBytes {
+    /// Whether the bytes are shared rather than owned.
+    #[must_use]
+    pub fn is_shared(&self) -> bool {
+        matches!(self.repr, InputBytesRepr::Shared(_))
+    }
+
+    /// Converts the bytes into a `Vec<u8>`, copying them if they are shared.
+    #[must_use]
+    pub fn into_vec(self) -> Vec<u8> {
+        match self.repr {
+            InputBytesRepr::Owned(mut bytes) => {
+                bytes.drain(..self.start);
+                bytes
+            }
+            InputBytesRepr::Shared(bytes) => bytes[self.start..].to_vec(),
+        }
+    }
+
+    /// Skips the first `len` bytes, without moving or copying the others.
+    pub(crate) fn advance(&mut self, len: usize) {
+        assert!(len <= self.len(), "advanced past the end of the input");
+        self.start += len;
+    }
+
+    const fn new(repr: InputBytesRepr) -> Self {
+        Self { repr, start: 0 }
+    }
+}
+
+impl Default for InputBytes {
+    fn default() -> Self {
+        Self::new(InputBytesRepr::Owned(Vec::new()))
+    }
+}
+
+impl Deref for InputBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        let bytes: &[u8] = match &self.repr {
+            InputBytesRepr::Owned(bytes) => bytes,
+            InputBytesRepr::Shared(bytes) => bytes,
+        };
+        &bytes[self.start..]
+    }
+}
+
+impl AsRef<[u8]> for InputBytes {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl From<Vec<u8>> for InputBytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::new(InputBytesRepr::Owned(bytes))
+    }
+}
+
+impl From<&[u8]> for InputBytes {
+    fn from(bytes: &[u8]) -> Self {
+        Self::new(InputBytesRepr::Owned(bytes.to_vec()))
+    }
+}
+
+impl From<Arc<[u8]>> for InputBytes {
+    fn from(bytes: Arc<[u8]>) -> Self {
+        Self::new(InputBytesRepr::Shared(bytes))
+    }
+}
+
+impl fmt::Debug for InputBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl PartialEq for InputBytes {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for InputBytes {}
+
+impl PartialEq<Vec<u8>> for InputBytes {
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        **self == **other
+    }
+}
+
+impl PartialEq<[u8]> for InputBytes {
+    fn eq(&self, other: &[u8]) -> bool {
+        **self == *other
+    }
+}
+
+impl Serialize for InputBytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Serialize as a sequence, like a `Vec<u8>`, to keep the encoding unchanged.
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for InputBytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<u8>::deserialize(deserializer).map(Self::from)
+    }
+}
+
 impl Read for Executor<'_> {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         self.read_public_values_slice(buf);
@@ -18,18 +149,25 @@ impl Executor<'_> {
     pub fn write_stdin<T: Serialize>(&mut self, input: &T) {
         let mut buf = Vec::new();
         bincode::serialize_into(&mut buf, input).expect("serialization failed");
-        self.state.input_stream.push_back(buf);
+        self.state.input_stream.push_back(buf.into());
     }
 
     /// Write a slice of bytes to the standard input stream.
     pub fn write_stdin_slice(&mut self, input: &[u8]) {
-        self.state.input_stream.push_back(input.to_vec());
+        self.state.input_stream.push_back(input.into());
+    }
+
+    /// Write shared bytes to the standard input stream, without copying them.
+    pub fn write_stdin_shared(&mut self, input: Arc<[u8]>) {
+        self.state.input_stream.push_back(input.into());
     }
 
     /// Write a slice of vecs to the standard input stream.
-    pub fn write_vecs(&mut self, inputs: &[Vec<u8>]) {
+    ///
+    /// Inputs that are [`InputBytes`] are cloned without copying the bytes they share.
+    pub fn write_vecs<T: Clone + Into<InputBytes>>(&mut self, inputs: &[T]) {
         for input in inputs {
-            self.state.input_stream.push_back(input.clone());
+            self.state.input_stream.push_back(input.clone().into());
         }
     }
 
@@ -39,7 +177,18 @@ impl Executor<'_> {
         proof: SP1ReduceProof<BabyBearPoseidon2>,
         vk: StarkVerifyingKey<BabyBearPoseidon2>,
     ) {
-        self.state.proof_stream.push((proof, vk));
+        self.state.proof_stream.push(Some((proof, vk)));
+    }
+
+    /// Reserve a slot in the proof stream for a proof that is not available yet, returning its
+    /// position in the proof stream.
+    ///
+    /// The proof is fetched from [`Executor::deferred_proof_provider`] when the program verifies
+    /// it. If the provider does not resolve the slot, the execution fails with
+    /// [`crate::ExecutionError::UnresolvedDeferredProof`].
+    pub fn write_deferred_proof_slot(&mut self) -> usize {
+        self.state.proof_stream.push(None);
+        self.state.proof_stream.len() - 1
     }
 
     /// Read a serializable public values from the public values stream.
@@ -21,12 +21,15 @@
 #![warn(missing_docs)]
 
 mod air;
+mod checkpoint;
 mod context;
 mod cost;
 mod dependencies;
 mod disassembler;
 pub mod events;
 mod executor;
+mod gas;
+mod guest_panic;
 mod hook;
 mod instruction;
 mod io;
@@ -45,11 +48,15 @@ pub mod syscalls;
 mod utils;
 
 pub use air::*;
+pub use checkpoint::*;
 pub use context::*;
 pub use cost::*;
 pub use executor::*;
+pub use gas::*;
+pub use guest_panic::PanicLocation;
 pub use hook::*;
 pub use instruction::*;
+pub use io::InputBytes;
 pub use opcode::*;
 pub use program::*;
 pub use record::*;
@@ -67,8 +74,9 @@ pub mod programs {
         use crate::{Instruction, Opcode, Program};
 
         pub use test_artifacts::{
-            FIBONACCI_ELF, PANIC_ELF, SECP256R1_ADD_ELF, SECP256R1_DOUBLE_ELF, SSZ_WITHDRAWALS_ELF,
-            U256XU2048_MUL_ELF,
+            CYCLE_TRACKER_NESTED_ELF, FIBONACCI_ELF, IO_CALLBACKS_ELF, MEMORY_STATS_ELF, PANIC_ELF,
+            PANIC_MESSAGE_ELF, READ_CHUNKS_ELF, SECP256R1_ADD_ELF, SECP256R1_DOUBLE_ELF,
+            SSZ_WITHDRAWALS_ELF, U256XU2048_MUL_ELF, VERIFY_PROOF_ELF,
         };
 
         #[must_use]
@@ -91,6 +99,56 @@ pub mod programs {
             Program::from(FIBONACCI_ELF).unwrap()
         }
 
+        /// Get the program with nested and repeated cycle tracker spans.
+        ///
+        /// # Panics
+        ///
+        /// This function will panic if the program fails to load.
+        #[must_use]
+        pub fn cycle_tracker_nested_program() -> Program {
+            Program::from(CYCLE_TRACKER_NESTED_ELF).unwrap()
+        }
+
+        /// Get the program printing its progress to stdout and stderr.
+        ///
+        /// # Panics
+        ///
+        /// This function will panic if the program fails to load.
+        #[must_use]
+        pub fn io_callbacks_program() -> Program {
+            Program::from(IO_CALLBACKS_ELF).unwrap()
+        }
+
+        /// Get the program allocating and writing one MiB on the heap.
+        ///
+        /// # Panics
+        ///
+        /// This function will panic if the program fails to load.
+        #[must_use]
+        pub fn memory_stats_program() -> Program {
+            Program::from(MEMORY_STATS_ELF).unwrap()
+        }
+
+        /// Get the program reading its first input in chunks.
+        ///
+        /// # Panics
+        ///
+        /// This function will panic if the program fails to load.
+        #[must_use]
+        pub fn read_chunks_program() -> Program {
+            Program::from(READ_CHUNKS_ELF).unwrap()
+        }
+
+        /// Get the program verifying proofs of a vkey for a list of committed inputs.
+        ///
+        /// # Panics
+        ///
+        /// This function will panic if the program fails to load.
+        #[must_use]
+        pub fn verify_proof_program() -> Program {
+            Program::from(VERIFY_PROOF_ELF).unwrap()
+        }
+
         /// Get the secp256r1 add program.
         ///
         /// # Panics
@@ -141,6 +199,16 @@ pub mod programs {
             Program::from(PANIC_ELF).unwrap()
         }
 
+        /// Get the program panicking with a formatted message of a given length.
+        ///
+        /// # Panics
+        ///
+        /// This function will panic if the program fails to load.
+        #[must_use]
+        pub fn panic_message_program() -> Program {
+            Program::from(PANIC_MESSAGE_ELF).unwrap()
+        }
+
         #[must_use]
         #[allow(clippy::unreadable_literal)]
         pub fn simple_memory_program() -> Program {
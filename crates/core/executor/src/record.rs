@@ -183,6 +183,9 @@ impl ExecutionRecord {
                 SyscallCode::KECCAK_PERMUTE => opts.keccak,
                 SyscallCode::SHA_EXTEND => opts.sha_extend,
                 SyscallCode::SHA_COMPRESS => opts.sha_compress,
+                SyscallCode::SHA512_EXTEND => opts.sha512_extend,
+                SyscallCode::SHA512_COMPRESS => opts.sha512_compress,
+                SyscallCode::BLS12381_FP12_MUL => opts.bls12381_fp12_mul,
                 _ => opts.deferred,
             };
 
@@ -1,10 +1,11 @@
 use std::{
+    collections::BTreeMap,
     fmt::{Display, Formatter, Result as FmtResult},
     ops::{Add, AddAssign},
+    time::Duration,
 };
 
 use enum_map::{EnumArray, EnumMap};
-use hashbrown::HashMap;
 
 use crate::{events::generate_execution_report, syscalls::SyscallCode, Opcode};
 
@@ -15,10 +16,20 @@ pub struct ExecutionReport {
     pub opcode_counts: Box<EnumMap<Opcode, u64>>,
     /// The syscall counts.
     pub syscall_counts: Box<EnumMap<SyscallCode, u64>>,
-    /// The cycle tracker counts.
-    pub cycle_tracker: HashMap<String, u64>,
+    /// The cycles spent in each syscall, counting the `ecall` instruction and the extra cycles
+    /// of the syscall.
+    pub syscall_cycles: Box<EnumMap<SyscallCode, u64>>,
+    /// The cycles spent in the spans annotated with `cycle-tracker-start` and `cycle-tracker-end`
+    /// (or their `report` variants), by span name.
+    pub cycle_tracker: BTreeMap<String, SpanStats>,
     /// The unique memory address counts.
     pub touched_memory_addresses: u64,
+    /// The memory footprint of the execution.
+    pub memory_stats: MemoryStats,
+    /// The checkpoints saved during the execution.
+    pub checkpoints: CheckpointStats,
+    /// The invocations of the hooks, by file descriptor.
+    pub hook_invocations: BTreeMap<u32, HookStats>,
 }
 
 impl ExecutionReport {
@@ -33,6 +44,181 @@ impl ExecutionReport {
     pub fn total_syscall_count(&self) -> u64 {
         self.syscall_counts.values().sum()
     }
+
+    /// Compute the total number of cycles spent in syscalls during the execution.
+    #[must_use]
+    pub fn total_syscall_cycles(&self) -> u64 {
+        self.syscall_cycles.values().sum()
+    }
+}
+
+/// The cycles spent in the invocations of a cycle tracker span.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpanStats {
+    /// The number of invocations of the span.
+    pub invocations: u64,
+    /// The total number of cycles spent in the span.
+    ///
+    /// The cycles of an invocation nested in an invocation of the same span, e.g. of a recursive
+    /// function, are already counted by the outer invocation and are not counted again.
+    pub total_cycles: u64,
+    /// The number of cycles of the longest invocation.
+    pub max_cycles: u64,
+}
+
+impl SpanStats {
+    /// Records an invocation of the span that took `cycles` cycles. `outermost` is whether the
+    /// invocation is not nested in another invocation of the same span.
+    pub(crate) fn record(&mut self, cycles: u64, outermost: bool) {
+        self.invocations += 1;
+        if outermost {
+            self.total_cycles += cycles;
+        }
+        self.max_cycles = self.max_cycles.max(cycles);
+    }
+}
+
+impl AddAssign for SpanStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.invocations += rhs.invocations;
+        self.total_cycles += rhs.total_cycles;
+        self.max_cycles = self.max_cycles.max(rhs.max_cycles);
+    }
+}
+
+/// The log2 of the size in bytes of the pages counted by [`MemoryStats::touched_pages`].
+pub const LOG_MEMORY_STATS_PAGE_SIZE: u32 = 12;
+
+/// The end of the heap: the start of the region the embedded allocator reserves for inputs.
+const HEAP_END: u32 = 0x3800_0000;
+
+/// The number of words of the bitmap of touched pages covering the whole address space.
+const PAGE_BITMAP_LEN: usize = 1 << (32 - LOG_MEMORY_STATS_PAGE_SIZE - 6);
+
+/// The memory footprint of an execution.
+///
+/// Only the memory reads and writes of the program and of the syscalls are counted. The accesses
+/// to the registers and those in unconstrained blocks are not.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// The number of distinct pages of `1 << LOG_MEMORY_STATS_PAGE_SIZE` bytes accessed.
+    pub touched_pages: u64,
+    /// The highest heap address accessed, as an offset from the start of the heap right after the
+    /// program image, including the accessed word.
+    pub peak_heap_bytes: u64,
+    /// The total number of memory reads and writes.
+    pub total_mem_ops: u64,
+    /// The bitmap of the touched pages, allocated on the first access.
+    pages: Vec<u64>,
+}
+
+impl MemoryStats {
+    /// Records an access to the word at `addr`, given the start of the heap.
+    #[inline]
+    pub(crate) fn record_access(&mut self, addr: u32, heap_start: u32) {
+        self.total_mem_ops += 1;
+
+        if self.pages.is_empty() {
+            self.pages = vec![0; PAGE_BITMAP_LEN];
+        }
+        let page = (addr >> LOG_MEMORY_STATS_PAGE_SIZE) as usize;
+        let mask = 1 << (page % 64);
+        let word = &mut self.pages[page / 64];
+        if *word & mask == 0 {
+            *word |= mask;
+            self.touched_pages += 1;
+        }
+
+        if (heap_start..HEAP_END).contains(&addr) {
+            self.peak_heap_bytes = self.peak_heap_bytes.max(u64::from(addr - heap_start) + 4);
+        }
+    }
+
+    /// The number of bytes of the touched pages.
+    #[must_use]
+    pub fn touched_bytes(&self) -> u64 {
+        self.touched_pages << LOG_MEMORY_STATS_PAGE_SIZE
+    }
+}
+
+impl AddAssign for MemoryStats {
+    /// Combines the stats of two parts of an execution. The pages touched by both are counted once.
+    fn add_assign(&mut self, rhs: Self) {
+        if self.pages.is_empty() {
+            self.pages = rhs.pages;
+        } else if !rhs.pages.is_empty() {
+            for (lhs, rhs) in self.pages.iter_mut().zip(rhs.pages) {
+                *lhs |= rhs;
+            }
+        }
+        self.touched_pages = self.pages.iter().map(|word| u64::from(word.count_ones())).sum();
+        self.peak_heap_bytes = self.peak_heap_bytes.max(rhs.peak_heap_bytes);
+        self.total_mem_ops += rhs.total_mem_ops;
+    }
+}
+
+/// The checkpoints saved during an execution. See [`Executor::set_checkpointing`].
+///
+/// [`Executor::set_checkpointing`]: crate::Executor::set_checkpointing
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckpointStats {
+    /// The number of checkpoints saved.
+    pub count: u64,
+    /// The total size in bytes of the checkpoints.
+    pub total_bytes: u64,
+    /// The size in bytes of the largest checkpoint.
+    pub max_bytes: u64,
+    /// The total time spent writing the checkpoints.
+    pub total_write_time: Duration,
+}
+
+impl CheckpointStats {
+    /// Records a checkpoint of `bytes` bytes that took `write_time` to write.
+    pub(crate) fn record(&mut self, bytes: u64, write_time: Duration) {
+        self.count += 1;
+        self.total_bytes += bytes;
+        self.max_bytes = self.max_bytes.max(bytes);
+        self.total_write_time += write_time;
+    }
+}
+
+impl AddAssign for CheckpointStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.count += rhs.count;
+        self.total_bytes += rhs.total_bytes;
+        self.max_bytes = self.max_bytes.max(rhs.max_bytes);
+        self.total_write_time += rhs.total_write_time;
+    }
+}
+
+/// The invocations of a hook. See [`Hook`].
+///
+/// [`Hook`]: crate::Hook
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HookStats {
+    /// The number of invocations of the hook.
+    pub invocations: u64,
+    /// The total size in bytes of the requests written by the program.
+    pub request_bytes: u64,
+    /// The total size in bytes of the responses written back to the input stream.
+    pub response_bytes: u64,
+}
+
+impl HookStats {
+    /// Records an invocation of the hook with the given request and response.
+    pub(crate) fn record(&mut self, request: &[u8], response: &[Vec<u8>]) {
+        self.invocations += 1;
+        self.request_bytes += request.len() as u64;
+        self.response_bytes += response.iter().map(|value| value.len() as u64).sum::<u64>();
+    }
+}
+
+impl AddAssign for HookStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.invocations += rhs.invocations;
+        self.request_bytes += rhs.request_bytes;
+        self.response_bytes += rhs.response_bytes;
+    }
 }
 
 /// Combines two `HashMap`s together. If a key is in both maps, the values are added together.
@@ -50,7 +236,16 @@ impl AddAssign for ExecutionReport {
     fn add_assign(&mut self, rhs: Self) {
         counts_add_assign(&mut self.opcode_counts, *rhs.opcode_counts);
         counts_add_assign(&mut self.syscall_counts, *rhs.syscall_counts);
+        counts_add_assign(&mut self.syscall_cycles, *rhs.syscall_cycles);
+        for (name, stats) in rhs.cycle_tracker {
+            *self.cycle_tracker.entry(name).or_default() += stats;
+        }
         self.touched_memory_addresses += rhs.touched_memory_addresses;
+        self.memory_stats += rhs.memory_stats;
+        self.checkpoints += rhs.checkpoints;
+        for (fd, stats) in rhs.hook_invocations {
+            *self.hook_invocations.entry(fd).or_default() += stats;
+        }
     }
 }
 
@@ -75,6 +270,150 @@ impl Display for ExecutionReport {
             writeln!(f, "  {line}")?;
         }
 
+        writeln!(f, "syscall cycles ({} total syscall cycles):", self.total_syscall_cycles())?;
+        for line in generate_execution_report(self.syscall_cycles.as_ref()) {
+            writeln!(f, "  {line}")?;
+        }
+
+        let stats = &self.memory_stats;
+        writeln!(f, "memory:")?;
+        writeln!(f, "  touched pages: {} ({} bytes)", stats.touched_pages, stats.touched_bytes())?;
+        writeln!(f, "  peak heap bytes: {}", stats.peak_heap_bytes)?;
+        writeln!(f, "  memory operations: {}", stats.total_mem_ops)?;
+
+        let checkpoints = &self.checkpoints;
+        if checkpoints.count > 0 {
+            writeln!(f, "checkpoints:")?;
+            writeln!(f, "  count: {}", checkpoints.count)?;
+            writeln!(f, "  total bytes: {}", checkpoints.total_bytes)?;
+            writeln!(f, "  max bytes: {}", checkpoints.max_bytes)?;
+            writeln!(f, "  write time: {:?}", checkpoints.total_write_time)?;
+        }
+
+        if !self.hook_invocations.is_empty() {
+            writeln!(f, "hooks (invocations, request bytes, response bytes):")?;
+            for (fd, stats) in &self.hook_invocations {
+                writeln!(
+                    f,
+                    "  fd {fd}: {} {} {}",
+                    stats.invocations, stats.request_bytes, stats.response_bytes
+                )?;
+            }
+        }
+
+        if !self.cycle_tracker.is_empty() {
+            writeln!(f, "cycle tracker spans (total cycles, invocations, max cycles):")?;
+            for line in span_table_lines(&self.cycle_tracker) {
+                writeln!(f, "    {line}")?;
+            }
+        }
+
         Ok(())
     }
 }
+
+/// Returns the rows of the table of cycle tracker spans, sorted first by total cycles (descending)
+/// and then by name (ascending), with right-justified columns of numbers.
+fn span_table_lines(spans: &BTreeMap<String, SpanStats>) -> Vec<String> {
+    let mut entries = spans.iter().collect::<Vec<_>>();
+    entries.sort_by(|a, b| b.1.total_cycles.cmp(&a.1.total_cycles).then_with(|| a.0.cmp(b.0)));
+
+    let width = |value: fn(&SpanStats) -> u64| {
+        entries.iter().map(|(_, stats)| value(stats).to_string().len()).max().unwrap_or_default()
+    };
+    let total_width = width(|stats| stats.total_cycles);
+    let invocations_width = width(|stats| stats.invocations);
+    let max_width = width(|stats| stats.max_cycles);
+
+    entries
+        .into_iter()
+        .map(|(name, stats)| {
+            format!(
+                "{:>total_width$} {:>invocations_width$} {:>max_width$} {name}",
+                stats.total_cycles, stats.invocations, stats.max_cycles
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_stats_record() {
+        let mut stats = SpanStats::default();
+        stats.record(10, true);
+        // A nested invocation of the same span is already counted by the outer one.
+        stats.record(4, false);
+        stats.record(30, true);
+        assert_eq!(stats, SpanStats { invocations: 3, total_cycles: 40, max_cycles: 30 });
+    }
+
+    #[test]
+    fn test_add_assign_merges_spans() {
+        let mut lhs = ExecutionReport::default();
+        lhs.cycle_tracker.insert(
+            "a".to_string(),
+            SpanStats { invocations: 1, total_cycles: 10, max_cycles: 10 },
+        );
+        let mut rhs = ExecutionReport::default();
+        rhs.cycle_tracker
+            .insert("a".to_string(), SpanStats { invocations: 2, total_cycles: 8, max_cycles: 5 });
+        rhs.cycle_tracker
+            .insert("b".to_string(), SpanStats { invocations: 1, total_cycles: 3, max_cycles: 3 });
+        rhs.syscall_cycles[SyscallCode::SHA_EXTEND] = 49;
+
+        lhs += rhs;
+        assert_eq!(
+            lhs.cycle_tracker["a"],
+            SpanStats { invocations: 3, total_cycles: 18, max_cycles: 10 }
+        );
+        assert_eq!(lhs.cycle_tracker["b"].invocations, 1);
+        assert_eq!(lhs.total_syscall_cycles(), 49);
+    }
+
+    #[test]
+    fn test_memory_stats() {
+        let heap_start = 0x0030_0000;
+        let mut lhs = MemoryStats::default();
+        lhs.record_access(0x0010_0000, heap_start);
+        lhs.record_access(0x0010_0ffc, heap_start);
+        lhs.record_access(heap_start + 0x1000, heap_start);
+        assert_eq!(lhs.touched_pages, 2);
+        assert_eq!(lhs.peak_heap_bytes, 0x1004);
+        assert_eq!(lhs.total_mem_ops, 3);
+
+        // Accesses to the reserved input region are not heap accesses.
+        let mut rhs = MemoryStats::default();
+        rhs.record_access(heap_start + 0x1000, heap_start);
+        rhs.record_access(HEAP_END, heap_start);
+        assert_eq!(rhs.peak_heap_bytes, 0x1004);
+
+        // The page touched by both parts is counted once.
+        lhs += rhs;
+        assert_eq!(lhs.touched_pages, 3);
+        assert_eq!(lhs.touched_bytes(), 3 * 4096);
+        assert_eq!(lhs.peak_heap_bytes, 0x1004);
+        assert_eq!(lhs.total_mem_ops, 5);
+    }
+
+    #[test]
+    fn test_display_sorts_spans() {
+        let mut report = ExecutionReport::default();
+        report.cycle_tracker.insert(
+            "small".to_string(),
+            SpanStats { invocations: 12, total_cycles: 7, max_cycles: 1 },
+        );
+        report.cycle_tracker.insert(
+            "big".to_string(),
+            SpanStats { invocations: 1, total_cycles: 1000, max_cycles: 1000 },
+        );
+
+        let display = report.to_string();
+        let spans =
+            display.split_once("cycle tracker spans").unwrap().1.lines().collect::<Vec<_>>();
+        assert_eq!(spans[1], "    1000  1 1000 big");
+        assert_eq!(spans[2], "       7 12    1 small");
+    }
+}
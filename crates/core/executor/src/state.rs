@@ -13,7 +13,7 @@ use crate::{
     memory::Memory,
     record::{ExecutionRecord, MemoryAccessRecord},
     syscalls::SyscallCode,
-    ExecutorMode, SP1ReduceProof,
+    ExecutorMode, InputBytes, SP1ReduceProof,
 };
 
 /// Holds data describing the current state of a program's execution.
@@ -43,11 +43,12 @@ pub struct ExecutionState {
     pub uninitialized_memory: Memory<u32>,
 
     /// A stream of input values (global to the entire program).
-    pub input_stream: VecDeque<Vec<u8>>,
+    pub input_stream: VecDeque<InputBytes>,
 
-    /// A stream of proofs (reduce vk, proof, verifying key) inputted to the program.
+    /// A stream of proofs (reduce vk, proof, verifying key) inputted to the program, with `None`
+    /// for the deferred proof slots whose proofs are not provided yet.
     pub proof_stream:
-        Vec<(SP1ReduceProof<BabyBearPoseidon2>, StarkVerifyingKey<BabyBearPoseidon2>)>,
+        Vec<Option<(SP1ReduceProof<BabyBearPoseidon2>, StarkVerifyingKey<BabyBearPoseidon2>)>>,
 
     /// A ptr to the current position in the proof stream, incremented after verifying a proof.
     pub proof_stream_ptr: usize,
@@ -23,6 +23,28 @@ pub trait SubproofVerifier: Sync + Send {
     ) -> Result<(), MachineVerificationError<BabyBearPoseidon2>>;
 }
 
+/// A deferred proof and the verifying key it is verified against.
+type DeferredProof = (SP1ReduceProof<BabyBearPoseidon2>, StarkVerifyingKey<BabyBearPoseidon2>);
+
+/// Provider of the deferred proofs that are not written to the proof stream up front, but fetched
+/// when the program verifies them. See [`crate::Executor::write_deferred_proof_slot`].
+pub trait DeferredProofProvider: Sync + Send {
+    /// Returns the proof and verifying key of the deferred proof slot at position `slot` in the
+    /// proof stream, or `None` if the slot is unresolved.
+    ///
+    /// The provider may block until the proof is available, e.g. while it is still being proven.
+    fn deferred_proof(&self, slot: usize) -> Option<DeferredProof>;
+}
+
+impl<F> DeferredProofProvider for F
+where
+    F: Fn(usize) -> Option<DeferredProof> + Sync + Send,
+{
+    fn deferred_proof(&self, slot: usize) -> Option<DeferredProof> {
+        self(slot)
+    }
+}
+
 /// A dummy verifier which does nothing.
 pub struct NoOpSubproofVerifier;
 
@@ -154,6 +154,15 @@ pub enum SyscallCode {
 
     /// Executes the `SECP256R1_DECOMPRESS` precompile.
     SECP256R1_DECOMPRESS = 0x00_00_01_2E,
+
+    /// Executes the `SHA512_EXTEND` precompile.
+    SHA512_EXTEND = 0x00_40_01_30,
+
+    /// Executes the `SHA512_COMPRESS` precompile.
+    SHA512_COMPRESS = 0x00_01_01_31,
+
+    /// Executes the `BLS12381_FP12_MUL` precompile.
+    BLS12381_FP12_MUL = 0x00_01_01_32,
 }
 
 impl SyscallCode {
@@ -200,6 +209,9 @@ impl SyscallCode {
             0x00_01_01_2C => SyscallCode::SECP256R1_ADD,
             0x00_00_01_2D => SyscallCode::SECP256R1_DOUBLE,
             0x00_00_01_2E => SyscallCode::SECP256R1_DECOMPRESS,
+            0x00_40_01_30 => SyscallCode::SHA512_EXTEND,
+            0x00_01_01_31 => SyscallCode::SHA512_COMPRESS,
+            0x00_01_01_32 => SyscallCode::BLS12381_FP12_MUL,
             _ => panic!("invalid syscall number: {value}"),
         }
     }
@@ -1,4 +1,5 @@
 use super::{Syscall, SyscallCode, SyscallContext};
+use crate::InputBytes;
 
 pub(crate) struct HintLenSyscall;
 
@@ -15,17 +16,30 @@ impl Syscall for HintLenSyscall {
     }
 }
 
+/// Reads the first `len` bytes of the next element of the input stream.
+///
+/// The element is popped from the stream once all its bytes are read, so that reading an element
+/// at once is unchanged, while reading fewer bytes lets a program stream over a large element in
+/// chunks. The length returned by [`HintLenSyscall`] is the number of bytes left to read.
 pub(crate) struct HintReadSyscall;
 
 impl Syscall for HintReadSyscall {
     fn execute(&self, ctx: &mut SyscallContext, _: SyscallCode, ptr: u32, len: u32) -> Option<u32> {
         panic_if_input_exhausted(ctx);
 
-        // SAFETY: The input stream is not empty, as checked above, so the back is not None
-        let vec = unsafe { ctx.rt.state.input_stream.pop_front().unwrap_unchecked() };
+        // SAFETY: The input stream is not empty, as checked above, so the front is not None
+        let front = unsafe { ctx.rt.state.input_stream.front_mut().unwrap_unchecked() };
+        assert!(len as usize <= front.len(), "hint input stream read length mismatch");
+        let vec = if len as usize == front.len() {
+            // SAFETY: The input stream is not empty, as checked above.
+            unsafe { ctx.rt.state.input_stream.pop_front().unwrap_unchecked() }
+        } else {
+            let chunk = InputBytes::from(&front[..len as usize]);
+            front.advance(len as usize);
+            chunk
+        };
 
         assert!(!ctx.rt.unconstrained, "hint read should not be used in a unconstrained block");
-        assert_eq!(vec.len() as u32, len, "hint input stream read length mismatch");
         assert_eq!(ptr % 4, 0, "hint read address not aligned to 4 bytes");
         // Iterate through the vec in 4-byte chunks
         for i in (0..len).step_by(4) {
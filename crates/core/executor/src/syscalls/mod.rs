@@ -21,6 +21,10 @@ use hashbrown::HashMap;
 pub use code::*;
 pub use context::*;
 use hint::{HintLenSyscall, HintReadSyscall};
+#[cfg(feature = "bls12381-fp12")]
+use precompiles::fptower::Fp12MulSyscall;
+#[cfg(feature = "sha512")]
+use precompiles::sha512::{compress::Sha512CompressSyscall, extend::Sha512ExtendSyscall};
 use precompiles::{
     edwards::{add::EdwardsAddAssignSyscall, decompress::EdwardsDecompressSyscall},
     fptower::{Fp2AddSubSyscall, Fp2MulSyscall, FpOpSyscall},
@@ -87,6 +91,12 @@ pub fn default_syscall_map() -> HashMap<SyscallCode, Arc<dyn Syscall>> {
 
     syscall_map.insert(SyscallCode::SHA_COMPRESS, Arc::new(Sha256CompressSyscall));
 
+    #[cfg(feature = "sha512")]
+    syscall_map.insert(SyscallCode::SHA512_EXTEND, Arc::new(Sha512ExtendSyscall));
+
+    #[cfg(feature = "sha512")]
+    syscall_map.insert(SyscallCode::SHA512_COMPRESS, Arc::new(Sha512CompressSyscall));
+
     syscall_map.insert(SyscallCode::ED_ADD, Arc::new(EdwardsAddAssignSyscall::<Ed25519>::new()));
 
     syscall_map.insert(
@@ -176,6 +186,9 @@ pub fn default_syscall_map() -> HashMap<SyscallCode, Arc<dyn Syscall>> {
     syscall_map
         .insert(SyscallCode::BLS12381_FP2_MUL, Arc::new(Fp2MulSyscall::<Bls12381BaseField>::new()));
 
+    #[cfg(feature = "bls12381-fp12")]
+    syscall_map.insert(SyscallCode::BLS12381_FP12_MUL, Arc::new(Fp12MulSyscall));
+
     syscall_map.insert(
         SyscallCode::BN254_FP_ADD,
         Arc::new(FpOpSyscall::<Bn254BaseField>::new(FieldOperation::Add)),
@@ -0,0 +1,108 @@
+use num::BigUint;
+use sp1_curves::{params::FieldParameters, weierstrass::bls12_381::Bls12381BaseField};
+
+use crate::{
+    events::{Fp12MulEvent, PrecompileEvent, BLS12381_FP12_WORDS},
+    syscalls::{Syscall, SyscallCode, SyscallContext},
+};
+
+/// The number of words of a BLS12-381 base field element.
+const FP_WORDS: usize = 12;
+
+/// The BLS12-381 Fp12 multiplication syscall.
+///
+/// Fp12 is represented as `Fp2[w] / (w^6 - (u + 1))`, where `Fp2 = Fp[u] / (u^2 + 1)`. An element
+/// is laid out in memory as the six Fp2 coefficients of `1, w, ..., w^5`, each as its two Fp
+/// coefficients of `1, u`, each as 12 little-endian words.
+pub struct Fp12MulSyscall;
+
+impl Syscall for Fp12MulSyscall {
+    fn execute(
+        &self,
+        rt: &mut SyscallContext,
+        syscall_code: SyscallCode,
+        arg1: u32,
+        arg2: u32,
+    ) -> Option<u32> {
+        let clk = rt.clk;
+        let x_ptr = arg1;
+        if x_ptr % 4 != 0 {
+            panic!();
+        }
+        let y_ptr = arg2;
+        if y_ptr % 4 != 0 {
+            panic!();
+        }
+
+        let x = rt.slice_unsafe(x_ptr, BLS12381_FP12_WORDS);
+        let (y_memory_records, y) = rt.mr_slice(y_ptr, BLS12381_FP12_WORDS);
+        rt.clk += 1;
+
+        let coefficients = |words: &[u32]| -> Vec<BigUint> {
+            words.chunks_exact(FP_WORDS).map(BigUint::from_slice).collect()
+        };
+        let product = fp12_mul(&coefficients(&x), &coefficients(&y));
+
+        // Each coefficient uses the same number of words, regardless of how many u32 digits are
+        // required to express it.
+        let mut result = Vec::with_capacity(BLS12381_FP12_WORDS);
+        for coefficient in product {
+            let mut words = coefficient.to_u32_digits();
+            words.resize(FP_WORDS, 0);
+            result.extend(words);
+        }
+        let x_memory_records = rt.mw_slice(x_ptr, &result);
+
+        let shard = rt.current_shard();
+        let event = Fp12MulEvent {
+            shard,
+            clk,
+            x_ptr,
+            x,
+            y_ptr,
+            y,
+            x_memory_records,
+            y_memory_records,
+            local_mem_access: rt.postprocess(),
+        };
+        let syscall_event =
+            rt.rt.syscall_event(clk, None, None, syscall_code, arg1, arg2, rt.next_pc);
+        rt.add_precompile_event(
+            syscall_code,
+            syscall_event,
+            PrecompileEvent::Bls12381Fp12Mul(event),
+        );
+        None
+    }
+
+    fn num_extra_cycles(&self) -> u32 {
+        1
+    }
+}
+
+/// Multiplies two Fp12 elements given by their 12 Fp coefficients, in the order of the memory
+/// layout of [`Fp12MulSyscall`].
+fn fp12_mul(x: &[BigUint], y: &[BigUint]) -> Vec<BigUint> {
+    let modulus = &Bls12381BaseField::modulus();
+    let fp2_mul = |a: (&BigUint, &BigUint), b: (&BigUint, &BigUint)| {
+        let c0 = (a.0 * b.0 % modulus + modulus - a.1 * b.1 % modulus) % modulus;
+        let c1 = (a.0 * b.1 + a.1 * b.0) % modulus;
+        (c0, c1)
+    };
+
+    let mut result = vec![BigUint::default(); 12];
+    for i in 0..6 {
+        for j in 0..6 {
+            let (mut c0, mut c1) = fp2_mul((&x[2 * i], &x[2 * i + 1]), (&y[2 * j], &y[2 * j + 1]));
+            let mut k = i + j;
+            // Reduce by w^6 = u + 1, where (c0 + c1 u)(u + 1) = (c0 - c1) + (c0 + c1) u.
+            if k >= 6 {
+                k -= 6;
+                (c0, c1) = ((&c0 + modulus - &c1) % modulus, (&c0 + &c1) % modulus);
+            }
+            result[2 * k] = (&result[2 * k] + c0) % modulus;
+            result[2 * k + 1] = (&result[2 * k + 1] + c1) % modulus;
+        }
+    }
+    result
+}
@@ -1,7 +1,11 @@
 mod fp;
+#[cfg(feature = "bls12381-fp12")]
+mod fp12_mul;
 mod fp2_addsub;
 mod fp2_mul;
 
 pub use fp::*;
+#[cfg(feature = "bls12381-fp12")]
+pub use fp12_mul::*;
 pub use fp2_addsub::*;
 pub use fp2_mul::*;
@@ -2,6 +2,8 @@ pub mod edwards;
 pub mod fptower;
 pub mod keccak256;
 pub mod sha256;
+#[cfg(feature = "sha512")]
+pub mod sha512;
 pub mod u256x2048_mul;
 pub mod uint256;
 pub mod weierstrass;
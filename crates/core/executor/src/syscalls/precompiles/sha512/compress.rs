@@ -0,0 +1,188 @@
+use super::{mr_u64, mw_u64};
+use crate::{
+    events::{PrecompileEvent, Sha512CompressEvent},
+    syscalls::{Syscall, SyscallCode, SyscallContext},
+};
+
+pub const SHA512_COMPRESS_K: [u64; 80] = [
+    0x428a2f98d728ae22,
+    0x7137449123ef65cd,
+    0xb5c0fbcfec4d3b2f,
+    0xe9b5dba58189dbbc,
+    0x3956c25bf348b538,
+    0x59f111f1b605d019,
+    0x923f82a4af194f9b,
+    0xab1c5ed5da6d8118,
+    0xd807aa98a3030242,
+    0x12835b0145706fbe,
+    0x243185be4ee4b28c,
+    0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f,
+    0x80deb1fe3b1696b1,
+    0x9bdc06a725c71235,
+    0xc19bf174cf692694,
+    0xe49b69c19ef14ad2,
+    0xefbe4786384f25e3,
+    0x0fc19dc68b8cd5b5,
+    0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275,
+    0x4a7484aa6ea6e483,
+    0x5cb0a9dcbd41fbd4,
+    0x76f988da831153b5,
+    0x983e5152ee66dfab,
+    0xa831c66d2db43210,
+    0xb00327c898fb213f,
+    0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2,
+    0xd5a79147930aa725,
+    0x06ca6351e003826f,
+    0x142929670a0e6e70,
+    0x27b70a8546d22ffc,
+    0x2e1b21385c26c926,
+    0x4d2c6dfc5ac42aed,
+    0x53380d139d95b3df,
+    0x650a73548baf63de,
+    0x766a0abb3c77b2a8,
+    0x81c2c92e47edaee6,
+    0x92722c851482353b,
+    0xa2bfe8a14cf10364,
+    0xa81a664bbc423001,
+    0xc24b8b70d0f89791,
+    0xc76c51a30654be30,
+    0xd192e819d6ef5218,
+    0xd69906245565a910,
+    0xf40e35855771202a,
+    0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8,
+    0x1e376c085141ab53,
+    0x2748774cdf8eeb99,
+    0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63,
+    0x4ed8aa4ae3418acb,
+    0x5b9cca4f7763e373,
+    0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc,
+    0x78a5636f43172f60,
+    0x84c87814a1f0ab72,
+    0x8cc702081a6439ec,
+    0x90befffa23631e28,
+    0xa4506cebde82bde9,
+    0xbef9a3f7b2c67915,
+    0xc67178f2e372532b,
+    0xca273eceea26619c,
+    0xd186b8c721c0c207,
+    0xeada7dd6cde0eb1e,
+    0xf57d4f7fee6ed178,
+    0x06f067aa72176fba,
+    0x0a637dc5a2c898a6,
+    0x113f9804bef90dae,
+    0x1b710b35131c471b,
+    0x28db77f523047d84,
+    0x32caab7b40c72493,
+    0x3c9ebe0a15c9bebc,
+    0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6,
+    0x597f299cfc657e2a,
+    0x5fcb6fab3ad6faec,
+    0x6c44198c4a475817,
+];
+
+pub(crate) struct Sha512CompressSyscall;
+
+impl Syscall for Sha512CompressSyscall {
+    fn num_extra_cycles(&self) -> u32 {
+        1
+    }
+
+    #[allow(clippy::too_many_lines)]
+    #[allow(clippy::many_single_char_names)]
+    fn execute(
+        &self,
+        rt: &mut SyscallContext,
+        syscall_code: SyscallCode,
+        arg1: u32,
+        arg2: u32,
+    ) -> Option<u32> {
+        let w_ptr = arg1;
+        let h_ptr = arg2;
+        assert_ne!(w_ptr, h_ptr);
+
+        let start_clk = rt.clk;
+        let mut h_read_records = Vec::with_capacity(16);
+        let mut w_i_read_records = Vec::with_capacity(80);
+        let mut h_write_records = Vec::with_capacity(16);
+
+        // Execute the "initialize" phase where we read in the h values.
+        let mut hx = [0u64; 8];
+        for i in 0..8 {
+            let (records, value) = mr_u64(rt, h_ptr + i as u32 * 8);
+            h_read_records.extend(records);
+            hx[i] = value;
+        }
+
+        let mut original_w = Vec::with_capacity(80);
+        // Execute the "compress" phase.
+        let mut a = hx[0];
+        let mut b = hx[1];
+        let mut c = hx[2];
+        let mut d = hx[3];
+        let mut e = hx[4];
+        let mut f = hx[5];
+        let mut g = hx[6];
+        let mut h = hx[7];
+        for i in 0..80 {
+            let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+            let ch = (e & f) ^ (!e & g);
+            let (records, w_i) = mr_u64(rt, w_ptr + i * 8);
+            original_w.push(w_i);
+            w_i_read_records.push(records);
+            let temp1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(SHA512_COMPRESS_K[i as usize])
+                .wrapping_add(w_i);
+            let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let temp2 = s0.wrapping_add(maj);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add(temp2);
+        }
+        // Increment the clk by 1 before writing to h, since we've already read h at the start_clk
+        // during the initialization phase.
+        rt.clk += 1;
+
+        // Execute the "finalize" phase.
+        let v = [a, b, c, d, e, f, g, h];
+        for i in 0..8 {
+            let records = mw_u64(rt, h_ptr + i as u32 * 8, hx[i].wrapping_add(v[i]));
+            h_write_records.extend(records);
+        }
+
+        // Push the SHA-512 compress event.
+        let shard = rt.current_shard();
+        let event = PrecompileEvent::Sha512Compress(Sha512CompressEvent {
+            shard,
+            clk: start_clk,
+            w_ptr,
+            h_ptr,
+            w: original_w,
+            h: hx,
+            h_read_records: h_read_records.try_into().unwrap(),
+            w_i_read_records,
+            h_write_records: h_write_records.try_into().unwrap(),
+            local_mem_access: rt.postprocess(),
+        });
+        let syscall_event =
+            rt.rt.syscall_event(start_clk, None, None, syscall_code, arg1, arg2, rt.next_pc);
+        rt.add_precompile_event(syscall_code, syscall_event, event);
+
+        None
+    }
+}
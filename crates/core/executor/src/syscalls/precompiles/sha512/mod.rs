@@ -0,0 +1,19 @@
+pub mod compress;
+pub mod extend;
+
+use crate::{
+    events::{MemoryReadRecord, MemoryWriteRecord},
+    syscalls::SyscallContext,
+};
+
+/// Reads the 64-bit word at `addr`, stored as two little-endian 32-bit words.
+fn mr_u64(rt: &mut SyscallContext, addr: u32) -> ([MemoryReadRecord; 2], u64) {
+    let (lo_record, lo) = rt.mr(addr);
+    let (hi_record, hi) = rt.mr(addr + 4);
+    ([lo_record, hi_record], u64::from(lo) | (u64::from(hi) << 32))
+}
+
+/// Writes the 64-bit word `value` at `addr` as two little-endian 32-bit words.
+fn mw_u64(rt: &mut SyscallContext, addr: u32, value: u64) -> [MemoryWriteRecord; 2] {
+    [rt.mw(addr, value as u32), rt.mw(addr + 4, (value >> 32) as u32)]
+}
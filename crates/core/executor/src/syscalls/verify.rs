@@ -1,4 +1,4 @@
-use crate::DeferredProofVerification;
+use crate::{DeferredProofVerification, ExecutionError};
 
 use super::{Syscall, SyscallCode, SyscallContext};
 
@@ -28,7 +28,16 @@ impl Syscall for VerifySyscall {
         if proof_index >= rt.state.proof_stream.len() {
             panic!("Not enough proofs were written to the runtime.");
         }
-        let (proof, proof_vk) = &rt.state.proof_stream[proof_index].clone();
+        if rt.state.proof_stream[proof_index].is_none() {
+            let proof =
+                rt.deferred_proof_provider.as_ref().and_then(|p| p.deferred_proof(proof_index));
+            if proof.is_none() {
+                rt.syscall_error = Some(ExecutionError::UnresolvedDeferredProof(proof_index));
+                return None;
+            }
+            rt.state.proof_stream[proof_index] = proof;
+        }
+        let (proof, proof_vk) = &rt.state.proof_stream[proof_index].clone().unwrap();
         rt.state.proof_stream_ptr += 1;
 
         let vkey_bytes: [u32; 8] = vkey.try_into().unwrap();
@@ -1,7 +1,7 @@
-use sp1_primitives::consts::fd::{FD_HINT, FD_PUBLIC_VALUES, LOWEST_ALLOWED_FD};
+use sp1_primitives::consts::fd::{FD_HINT, FD_PANIC, FD_PUBLIC_VALUES, LOWEST_ALLOWED_FD};
 use sp1_primitives::consts::num_to_comma_separated;
 
-use crate::{Executor, Register};
+use crate::{guest_panic::append_panic_report, ExecutionError, Executor, Register};
 
 use super::{Syscall, SyscallCode, SyscallContext};
 
@@ -11,12 +11,13 @@ impl Syscall for WriteSyscall {
     /// Handle writes to file descriptors during execution.
     ///
     /// If stdout (fd = 1):
-    /// - If the stream is a cycle tracker, either log the cycle tracker or accumulate it in the
-    ///   report.
+    /// - If the stream is a cycle tracker, log the cycle tracker and accumulate it in the report.
+    /// - Else, if there is an IO callback for stdout, invoke it with the stream.
     /// - Else, print the stream to stdout.
     ///
     /// If stderr (fd = 2):
-    /// - Print the stream to stderr.
+    /// - If there is an IO callback for stderr, invoke it with the stream.
+    /// - Else, print the stream to stderr.
     ///
     /// If fd = 3:
     /// - Update the public value stream.
@@ -24,9 +25,13 @@ impl Syscall for WriteSyscall {
     /// If fd = 4:
     /// - Update the input stream.
     ///
-    /// If the fd matches a hook in the hook registry, invoke the hook.
+    /// If the fd is `FD_PANIC`:
+    /// - Append to the panic report, parsed when the program halts.
     ///
-    /// Else, log a warning.
+    /// If the fd matches a hook in the hook registry, invoke the hook and record the invocation in
+    /// the report.
+    ///
+    /// Else, abort the execution with [`ExecutionError::UnregisteredHook`].
     #[allow(clippy::pedantic)]
     fn execute(
         &self,
@@ -48,18 +53,23 @@ impl Syscall for WriteSyscall {
             match parse_cycle_tracker_command(s) {
                 Some(command) => handle_cycle_tracker_command(rt, command),
                 None => {
-                    // If the string does not match any known command, print it to stdout.
-                    let flush_s = update_io_buf(ctx, fd, s);
-                    if !flush_s.is_empty() {
-                        flush_s.into_iter().for_each(|line| println!("stdout: {}", line));
+                    // If the string does not match any known command, pass it to the stdout
+                    // callback, or else print it to stdout.
+                    if !rt.invoke_io_callback(fd, slice) {
+                        let flush_s = update_io_buf(ctx, fd, s);
+                        if !flush_s.is_empty() {
+                            flush_s.into_iter().for_each(|line| println!("stdout: {}", line));
+                        }
                     }
                 }
             }
         } else if fd == 2 {
-            let s = core::str::from_utf8(slice).unwrap();
-            let flush_s = update_io_buf(ctx, fd, s);
-            if !flush_s.is_empty() {
-                flush_s.into_iter().for_each(|line| println!("stderr: {}", line));
+            if !rt.invoke_io_callback(fd, slice) {
+                let s = core::str::from_utf8(slice).unwrap();
+                let flush_s = update_io_buf(ctx, fd, s);
+                if !flush_s.is_empty() {
+                    flush_s.into_iter().for_each(|line| println!("stderr: {}", line));
+                }
             }
         } else if fd <= LOWEST_ALLOWED_FD {
             if std::env::var("SP1_ALLOW_DEPRECATED_HOOKS")
@@ -87,7 +97,7 @@ impl Syscall for WriteSyscall {
                     rt.state.public_values_stream.extend_from_slice(slice);
                     vec![]
                 } else if fd == INPUT {
-                    rt.state.input_stream.push_front(slice.to_vec());
+                    rt.state.input_stream.push_front(slice.into());
                     vec![]
                 } else {
                     vec![]
@@ -95,7 +105,7 @@ impl Syscall for WriteSyscall {
 
                 if !res.is_empty() {
                     for val in res.into_iter().rev() {
-                        rt.state.input_stream.push_front(val);
+                        rt.state.input_stream.push_front(val.into());
                     }
                 }
             } else {
@@ -108,19 +118,23 @@ impl Syscall for WriteSyscall {
         } else if fd == FD_PUBLIC_VALUES {
             rt.state.public_values_stream.extend_from_slice(slice);
         } else if fd == FD_HINT {
-            rt.state.input_stream.push_front(slice.to_vec());
+            rt.state.input_stream.push_front(slice.into());
+        } else if fd == FD_PANIC {
+            append_panic_report(&mut rt.panic_report, slice);
         } else if let Some(mut hook) = rt.hook_registry.get(fd) {
             let res = hook.invoke_hook(rt.hook_env(), slice);
+            drop(hook);
+            rt.report.hook_invocations.entry(fd).or_default().record(slice, &res);
 
             // Write the result back to the input stream.
             //
             // Note: The result is written in reverse order to the input stream to maintain the
             // order.
             for val in res.into_iter().rev() {
-                rt.state.input_stream.push_front(val);
+                rt.state.input_stream.push_front(val.into());
             }
         } else {
-            tracing::warn!("tried to write to unknown file descriptor {fd}");
+            rt.syscall_error = Some(ExecutionError::UnregisteredHook(fd));
         }
         None
     }
@@ -156,41 +170,39 @@ fn handle_cycle_tracker_command(rt: &mut Executor, command: CycleTrackerCommand)
         CycleTrackerCommand::Start(name) | CycleTrackerCommand::ReportStart(name) => {
             start_cycle_tracker(rt, &name);
         }
-        CycleTrackerCommand::End(name) => {
+        CycleTrackerCommand::End(name) | CycleTrackerCommand::ReportEnd(name) => {
             end_cycle_tracker(rt, &name);
         }
-        CycleTrackerCommand::ReportEnd(name) => {
-            // Attempt to end the cycle tracker and accumulate the total cycles in the fn_name's
-            // entry in the ExecutionReport.
-            if let Some(total_cycles) = end_cycle_tracker(rt, &name) {
-                rt.report
-                    .cycle_tracker
-                    .entry(name.to_string())
-                    .and_modify(|cycles| *cycles += total_cycles)
-                    .or_insert(total_cycles);
-            }
-        }
     }
 }
 
 /// Start tracking cycles for the given name at the specific depth and print out the log.
 fn start_cycle_tracker(rt: &mut Executor, name: &str) {
-    let depth = rt.cycle_tracker.len() as u32;
-    rt.cycle_tracker.insert(name.to_string(), (rt.state.global_clk, depth));
+    let depth = rt.cycle_tracker.values().map(Vec::len).sum::<usize>() as u32;
+    rt.cycle_tracker.entry(name.to_string()).or_default().push((rt.state.global_clk, depth));
     let padding = "│ ".repeat(depth as usize);
     tracing::info!("{}┌╴{}", padding, name);
 }
 
-/// End tracking cycles for the given name, print out the log, and return the total number of cycles
-/// in the span. If the name is not found in the cycle tracker cache, returns None.
-fn end_cycle_tracker(rt: &mut Executor, name: &str) -> Option<u64> {
-    if let Some((start, depth)) = rt.cycle_tracker.remove(name) {
-        let padding = "│ ".repeat(depth as usize);
-        let total_cycles = rt.state.global_clk - start;
-        tracing::info!("{}└╴{} cycles", padding, num_to_comma_separated(total_cycles));
-        return Some(total_cycles);
+/// End tracking cycles for the innermost open span of the given name, print out the log, and
+/// accumulate the cycles in the span's entry in the `ExecutionReport`. If there is no open span of
+/// the given name, does nothing.
+fn end_cycle_tracker(rt: &mut Executor, name: &str) {
+    let Some(spans) = rt.cycle_tracker.get_mut(name) else {
+        return;
+    };
+    let Some((start, depth)) = spans.pop() else {
+        return;
+    };
+    let outermost = spans.is_empty();
+    if outermost {
+        rt.cycle_tracker.remove(name);
     }
-    None
+
+    let padding = "│ ".repeat(depth as usize);
+    let total_cycles = rt.state.global_clk - start;
+    tracing::info!("{}└╴{} cycles", padding, num_to_comma_separated(total_cycles));
+    rt.report.cycle_tracker.entry(name.to_string()).or_default().record(total_cycles, outermost);
 }
 
 /// Update the io buffer for the given file descriptor with the given string.
@@ -1,5 +1,14 @@
+use std::{
+    borrow::Cow,
+    io::{self, Read},
+    sync::Arc,
+};
+
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use sp1_core_executor::SP1ReduceProof;
+use sp1_core_executor::{
+    subproof::DeferredProofProvider, ExecutionError, Executor, InputBytes, SP1ReduceProof,
+};
+use sp1_primitives::io::{NamedInput, TypedHint};
 use sp1_stark::{baby_bear_poseidon2::BabyBearPoseidon2, StarkVerifyingKey};
 
 /// Standard input for the prover.
@@ -7,20 +16,31 @@ use sp1_stark::{baby_bear_poseidon2::BabyBearPoseidon2, StarkVerifyingKey};
 pub struct SP1Stdin {
     /// Input stored as a vec of vec of bytes. It's stored this way because the read syscall reads
     /// a vec of bytes at a time.
-    pub buffer: Vec<Vec<u8>>,
+    pub buffer: Vec<InputBytes>,
     pub ptr: usize,
     pub proofs: Vec<(SP1ReduceProof<BabyBearPoseidon2>, StarkVerifyingKey<BabyBearPoseidon2>)>,
+    /// The positions in the proof stream of the deferred proof slots, whose proofs are provided
+    /// at prove time. See [`SP1Stdin::defer_proof_slot`].
+    ///
+    /// The slots are not serialized, as only a local prover can resolve them.
+    #[serde(skip)]
+    pub deferred_proof_slots: Vec<usize>,
 }
 
 impl SP1Stdin {
     /// Create a new `SP1Stdin`.
     pub const fn new() -> Self {
-        Self { buffer: Vec::new(), ptr: 0, proofs: Vec::new() }
+        Self { buffer: Vec::new(), ptr: 0, proofs: Vec::new(), deferred_proof_slots: Vec::new() }
     }
 
     /// Create a `SP1Stdin` from a slice of bytes.
     pub fn from(data: &[u8]) -> Self {
-        Self { buffer: vec![data.to_vec()], ptr: 0, proofs: Vec::new() }
+        Self {
+            buffer: vec![data.into()],
+            ptr: 0,
+            proofs: Vec::new(),
+            deferred_proof_slots: Vec::new(),
+        }
     }
 
     /// Read a value from the buffer.
@@ -41,16 +61,91 @@ impl SP1Stdin {
     pub fn write<T: Serialize>(&mut self, data: &T) {
         let mut tmp = Vec::new();
         bincode::serialize_into(&mut tmp, data).expect("serialization failed");
-        self.buffer.push(tmp);
+        self.buffer.push(tmp.into());
+    }
+
+    /// Write a value to the buffer, tagged with its type.
+    ///
+    /// The program must read it with `sp1_zkvm::io::read_hint`, which aborts the execution if the
+    /// value is read with another type.
+    pub fn write_hint<T: Serialize>(&mut self, data: &T) {
+        self.buffer.push(TypedHint::new(data).encode().into());
+    }
+
+    /// Write a value to the buffer under a name, tagged with its type.
+    ///
+    /// The program must read it with `sp1_zkvm::io::read_named` under the same name, which aborts
+    /// the execution if the inputs are read in another order or with another type.
+    pub fn write_named<T: Serialize>(&mut self, name: &str, data: &T) {
+        self.buffer.push(NamedInput::new(name, data).encode().into());
     }
 
     /// Write a slice of bytes to the buffer.
     pub fn write_slice(&mut self, slice: &[u8]) {
-        self.buffer.push(slice.to_vec());
+        self.buffer.push(slice.into());
     }
 
     pub fn write_vec(&mut self, vec: Vec<u8>) {
-        self.buffer.push(vec);
+        self.buffer.push(vec.into());
+    }
+
+    /// Write shared bytes to the buffer, without copying them.
+    ///
+    /// The program reads them like bytes written with [`SP1Stdin::write_slice`], e.g. with
+    /// `sp1_zkvm::io::read_vec`. Use this for large inputs, which are then only referenced until
+    /// they are serialized or read.
+    pub fn write_shared(&mut self, bytes: Arc<[u8]>) {
+        self.buffer.push(bytes.into());
+    }
+
+    /// Write the bytes of a reader to the buffer, as a single input.
+    ///
+    /// The program can read the input in chunks with `sp1_zkvm::io::read_chunks`, to stream over
+    /// it without reading it into a single buffer, or at once like any other input.
+    pub fn write_reader(&mut self, mut reader: impl Read) -> io::Result<()> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        self.buffer.push(bytes.into());
+        Ok(())
+    }
+
+    /// Returns a description of the inputs written to the buffer, in the order the program reads
+    /// them, e.g. to log them before proving.
+    pub fn describe(&self) -> Vec<StdinEntry> {
+        self.buffer
+            .iter()
+            .map(|bytes| {
+                if let Some(input) = NamedInput::decode(bytes) {
+                    StdinEntry::tagged(Some(input.name), &input.hint)
+                } else if let Some(hint) = TypedHint::decode(bytes) {
+                    StdinEntry::tagged(None, &hint)
+                } else {
+                    StdinEntry { name: None, len: bytes.len(), type_name: None, type_tag: None }
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the bincode encoding of the stdin as a sequence of segments.
+    ///
+    /// The segments reference the bytes in the buffer rather than copying them, so that large
+    /// inputs can be hashed or uploaded without materializing the whole encoding. Concatenated,
+    /// the segments are equal to `bincode::serialize(self)`.
+    pub fn encoded_segments(&self) -> Vec<Cow<'_, [u8]>> {
+        let mut segments = Vec::new();
+        let mut pending = (self.buffer.len() as u64).to_le_bytes().to_vec();
+        for bytes in &self.buffer {
+            pending.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+            if bytes.is_empty() {
+                continue;
+            }
+            segments.push(Cow::Owned(std::mem::take(&mut pending)));
+            segments.push(Cow::Borrowed(&bytes[..]));
+        }
+        bincode::serialize_into(&mut pending, &(self.ptr, &self.proofs))
+            .expect("serialization failed");
+        segments.push(Cow::Owned(pending));
+        segments
     }
 
     pub fn write_proof(
@@ -60,6 +155,102 @@ impl SP1Stdin {
     ) {
         self.proofs.push((proof, vk));
     }
+
+    /// Reserve a slot for a proof that is not available yet, returning the position of the proof
+    /// in the proof stream, i.e. the number of proofs the program verifies before it.
+    ///
+    /// The proof is provided at prove time, e.g. with `with_deferred_proof` on the prove builder
+    /// of the SDK, and only fetched when the program verifies it.
+    pub fn defer_proof_slot(&mut self) -> usize {
+        let slot = self.proofs.len() + self.deferred_proof_slots.len();
+        self.deferred_proof_slots.push(slot);
+        slot
+    }
+
+    /// Returns the proofs in the order the program verifies them, with `None` for the deferred
+    /// proof slots.
+    fn proof_stream(
+        &self,
+    ) -> impl Iterator<
+        Item = Option<&(SP1ReduceProof<BabyBearPoseidon2>, StarkVerifyingKey<BabyBearPoseidon2>)>,
+    > {
+        let mut proofs = self.proofs.iter();
+        (0..self.proofs.len() + self.deferred_proof_slots.len()).map(move |position| {
+            if self.deferred_proof_slots.contains(&position) {
+                None
+            } else {
+                proofs.next()
+            }
+        })
+    }
+
+    /// Writes the proofs and the deferred proof slots to the proof stream of `runtime`.
+    pub fn write_proof_stream(&self, runtime: &mut Executor) {
+        for proof in self.proof_stream() {
+            if let Some((proof, vk)) = proof {
+                runtime.write_proof(proof.clone(), vk.clone());
+            } else {
+                runtime.write_deferred_proof_slot();
+            }
+        }
+    }
+
+    /// Returns the proofs in the order the program verifies them, with the deferred proof slots
+    /// resolved by `provider`.
+    ///
+    /// Returns [`ExecutionError::UnresolvedDeferredProof`] for the first slot `provider` does not
+    /// resolve.
+    pub fn resolve_proofs(
+        &self,
+        provider: Option<&dyn DeferredProofProvider>,
+    ) -> Result<
+        Vec<(SP1ReduceProof<BabyBearPoseidon2>, StarkVerifyingKey<BabyBearPoseidon2>)>,
+        ExecutionError,
+    > {
+        self.proof_stream()
+            .enumerate()
+            .map(|(slot, proof)| match proof {
+                Some(proof) => Ok(proof.clone()),
+                None => provider
+                    .and_then(|provider| provider.deferred_proof(slot))
+                    .ok_or(ExecutionError::UnresolvedDeferredProof(slot)),
+            })
+            .collect()
+    }
+}
+
+/// An input written to a [`SP1Stdin`], as returned by [`SP1Stdin::describe`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StdinEntry {
+    /// The name of the input, if it was written with [`SP1Stdin::write_named`].
+    pub name: Option<String>,
+    /// The length of the encoded value in bytes.
+    pub len: usize,
+    /// The name of the type of the value, if the input is tagged with its type.
+    pub type_name: Option<String>,
+    /// The hash of the name of the type of the value, if the input is tagged with its type.
+    pub type_tag: Option<u64>,
+}
+
+impl StdinEntry {
+    fn tagged(name: Option<String>, hint: &TypedHint) -> Self {
+        Self {
+            name,
+            len: hint.payload.len(),
+            type_name: Some(hint.type_name.clone()),
+            type_tag: Some(hint.tag),
+        }
+    }
+}
+
+impl std::fmt::Display for StdinEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name.as_deref().unwrap_or("<unnamed>"))?;
+        if let Some(type_name) = &self.type_name {
+            write!(f, ": {type_name}")?;
+        }
+        write!(f, " ({} bytes)", self.len)
+    }
 }
 
 pub mod proof_serde {
@@ -98,3 +289,102 @@ pub mod proof_serde {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde::{Deserialize, Serialize};
+    use sp1_core_executor::SP1ReduceProof;
+    use sp1_stark::{baby_bear_poseidon2::BabyBearPoseidon2, StarkVerifyingKey};
+
+    use super::{SP1Stdin, StdinEntry};
+
+    /// The layout of [`SP1Stdin`] before inputs could be shared.
+    #[derive(Serialize, Deserialize)]
+    struct LegacyStdin {
+        buffer: Vec<Vec<u8>>,
+        ptr: usize,
+        proofs: Vec<(SP1ReduceProof<BabyBearPoseidon2>, StarkVerifyingKey<BabyBearPoseidon2>)>,
+    }
+
+    fn large_input() -> Vec<u8> {
+        (0..100_000u32).map(|i| i as u8).collect()
+    }
+
+    #[test]
+    fn test_shared_inputs_serialize_identically() {
+        let mut copied = SP1Stdin::new();
+        copied.write(&42u32);
+        copied.write_slice(&large_input());
+        copied.write_vec(vec![]);
+        copied.write_slice(&[1, 2, 3]);
+
+        let mut shared = SP1Stdin::new();
+        shared.write(&42u32);
+        shared.write_shared(Arc::from(large_input()));
+        shared.write_shared(Arc::from(Vec::new()));
+        shared.write_slice(&[1, 2, 3]);
+        assert!(shared.buffer[1].is_shared());
+
+        let legacy = LegacyStdin {
+            buffer: vec![bincode::serialize(&42u32).unwrap(), large_input(), vec![], vec![1, 2, 3]],
+            ptr: 0,
+            proofs: vec![],
+        };
+        let bytes = bincode::serialize(&legacy).unwrap();
+        assert_eq!(bincode::serialize(&copied).unwrap(), bytes);
+        assert_eq!(bincode::serialize(&shared).unwrap(), bytes);
+
+        let mut decoded: SP1Stdin = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(decoded.buffer, shared.buffer);
+        assert_eq!(decoded.read::<u32>(), 42);
+        let mut slice = vec![0; large_input().len()];
+        decoded.read_slice(&mut slice);
+        assert_eq!(slice, large_input());
+    }
+
+    #[test]
+    fn test_encoded_segments() {
+        let mut stdin = SP1Stdin::new();
+        assert_eq!(stdin.encoded_segments().concat(), bincode::serialize(&stdin).unwrap());
+
+        stdin.write(&42u32);
+        stdin.write_shared(Arc::from(large_input()));
+        stdin.write_vec(vec![]);
+        stdin.write_slice(&[1, 2, 3]);
+        stdin.ptr = 1;
+        let segments = stdin.encoded_segments();
+        assert_eq!(segments.concat(), bincode::serialize(&stdin).unwrap());
+
+        // The shared input is referenced rather than copied.
+        let shared = &stdin.buffer[1][..];
+        assert!(segments.iter().any(|segment| std::ptr::eq(&segment[..], shared)));
+    }
+
+    #[test]
+    fn test_describe() {
+        let mut stdin = SP1Stdin::new();
+        stdin.write_named("header", &5u32);
+        stdin.write_slice(&[1, 2, 3]);
+        stdin.write_hint(&7u64);
+
+        let entries = stdin.describe();
+        assert_eq!(
+            entries[0],
+            StdinEntry {
+                name: Some("header".to_string()),
+                len: 4,
+                type_name: Some("u32".to_string()),
+                type_tag: Some(sp1_primitives::io::type_tag("u32")),
+            }
+        );
+        assert_eq!(entries[1], StdinEntry { name: None, len: 3, type_name: None, type_tag: None });
+        assert_eq!(entries[2].name, None);
+        assert_eq!(entries[2].type_name.as_deref(), Some("u64"));
+        assert_eq!(
+            entries.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            ["header: u32 (4 bytes)", "<unnamed> (3 bytes)", "<unnamed>: u64 (8 bytes)"]
+        );
+    }
+}
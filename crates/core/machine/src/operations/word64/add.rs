@@ -0,0 +1,84 @@
+use p3_air::AirBuilder;
+use p3_field::{AbstractField, Field};
+use sp1_core_executor::events::ByteRecord;
+use sp1_derive::AlignedBorrow;
+use sp1_stark::air::SP1AirBuilder;
+
+use super::{Word64, WORD64_SIZE};
+use crate::air::WordAirBuilder;
+
+/// A set of columns needed to compute the add of two 64-bit words.
+#[derive(AlignedBorrow, Default, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Word64AddOperation<T> {
+    /// The result of `a + b`.
+    pub value: Word64<T>,
+
+    /// Trace.
+    pub carry: [T; WORD64_SIZE - 1],
+}
+
+impl<F: Field> Word64AddOperation<F> {
+    pub fn populate(&mut self, record: &mut impl ByteRecord, a_u64: u64, b_u64: u64) -> u64 {
+        let expected = a_u64.wrapping_add(b_u64);
+        self.value = Word64::from(expected);
+        let a = a_u64.to_le_bytes();
+        let b = b_u64.to_le_bytes();
+
+        let mut carry = 0u32;
+        for i in 0..WORD64_SIZE - 1 {
+            carry = u32::from((a[i] as u32) + (b[i] as u32) + carry > 255);
+            self.carry[i] = F::from_canonical_u32(carry);
+        }
+
+        // Range check
+        {
+            record.add_u8_range_checks(&a);
+            record.add_u8_range_checks(&b);
+            record.add_u8_range_checks(&expected.to_le_bytes());
+        }
+        expected
+    }
+
+    pub fn eval<AB: SP1AirBuilder>(
+        builder: &mut AB,
+        a: Word64<AB::Var>,
+        b: Word64<AB::Var>,
+        cols: Word64AddOperation<AB::Var>,
+        is_real: AB::Expr,
+    ) {
+        let one = AB::Expr::one();
+        let base = AB::F::from_canonical_u32(256);
+
+        let mut builder_is_real = builder.when(is_real.clone());
+
+        for i in 0..WORD64_SIZE {
+            // Assert that difference between the carried result and the non-carried result is
+            // either zero or the base.
+            let mut overflow = a[i] + b[i] - cols.value[i];
+            if i > 0 {
+                overflow = overflow.clone() + cols.carry[i - 1];
+            }
+            builder_is_real.assert_zero(overflow.clone() * (overflow.clone() - base));
+
+            if i < WORD64_SIZE - 1 {
+                // If the carry is one, then the overflow must be the base.
+                builder_is_real.assert_zero(cols.carry[i] * (overflow.clone() - base));
+
+                // If the carry is not one, then the overflow must be zero.
+                builder_is_real.assert_zero((cols.carry[i] - one.clone()) * overflow.clone());
+
+                // Assert that the carry is either zero or one.
+                builder_is_real.assert_bool(cols.carry[i]);
+            }
+        }
+        builder_is_real.assert_bool(is_real.clone());
+
+        // Range check each byte.
+        {
+            builder.slice_range_check_u8(&a.0, is_real.clone());
+            builder.slice_range_check_u8(&b.0, is_real.clone());
+            builder.slice_range_check_u8(&cols.value.0, is_real);
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use p3_air::AirBuilder;
+use p3_field::{AbstractField, Field};
+use sp1_core_executor::events::ByteRecord;
+use sp1_derive::AlignedBorrow;
+use sp1_stark::air::SP1AirBuilder;
+
+use super::{Word64, WORD64_SIZE};
+use crate::air::WordAirBuilder;
+
+/// A set of columns needed to compute the add of four 64-bit words.
+#[derive(AlignedBorrow, Default, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Word64Add4Operation<T> {
+    /// The result of `a + b + c + d`.
+    pub value: Word64<T>,
+
+    /// Indicates if the carry for the `i`th digit is 0.
+    pub is_carry_0: Word64<T>,
+
+    /// Indicates if the carry for the `i`th digit is 1.
+    pub is_carry_1: Word64<T>,
+
+    /// Indicates if the carry for the `i`th digit is 2.
+    pub is_carry_2: Word64<T>,
+
+    /// Indicates if the carry for the `i`th digit is 3. The carry when adding 4 words is at most
+    /// 3.
+    pub is_carry_3: Word64<T>,
+
+    /// The carry for the `i`th digit.
+    pub carry: Word64<T>,
+}
+
+impl<F: Field> Word64Add4Operation<F> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn populate(
+        &mut self,
+        record: &mut impl ByteRecord,
+        a_u64: u64,
+        b_u64: u64,
+        c_u64: u64,
+        d_u64: u64,
+    ) -> u64 {
+        let expected = a_u64.wrapping_add(b_u64).wrapping_add(c_u64).wrapping_add(d_u64);
+        self.value = Word64::from(expected);
+        let a = a_u64.to_le_bytes();
+        let b = b_u64.to_le_bytes();
+        let c = c_u64.to_le_bytes();
+        let d = d_u64.to_le_bytes();
+
+        let base = 256;
+        let mut carry = [0u8; WORD64_SIZE];
+        for i in 0..WORD64_SIZE {
+            let mut res = (a[i] as u32) + (b[i] as u32) + (c[i] as u32) + (d[i] as u32);
+            if i > 0 {
+                res += carry[i - 1] as u32;
+            }
+            carry[i] = (res / base) as u8;
+            self.is_carry_0[i] = F::from_bool(carry[i] == 0);
+            self.is_carry_1[i] = F::from_bool(carry[i] == 1);
+            self.is_carry_2[i] = F::from_bool(carry[i] == 2);
+            self.is_carry_3[i] = F::from_bool(carry[i] == 3);
+            self.carry[i] = F::from_canonical_u8(carry[i]);
+            debug_assert!(carry[i] <= 3);
+            debug_assert_eq!(self.value[i], F::from_canonical_u32(res % base));
+        }
+
+        // Range check.
+        {
+            record.add_u8_range_checks(&a);
+            record.add_u8_range_checks(&b);
+            record.add_u8_range_checks(&c);
+            record.add_u8_range_checks(&d);
+            record.add_u8_range_checks(&expected.to_le_bytes());
+        }
+        expected
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn eval<AB: SP1AirBuilder>(
+        builder: &mut AB,
+        a: Word64<AB::Var>,
+        b: Word64<AB::Var>,
+        c: Word64<AB::Var>,
+        d: Word64<AB::Var>,
+        is_real: AB::Var,
+        cols: Word64Add4Operation<AB::Var>,
+    ) {
+        // Range check each byte.
+        {
+            builder.slice_range_check_u8(&a.0, is_real);
+            builder.slice_range_check_u8(&b.0, is_real);
+            builder.slice_range_check_u8(&c.0, is_real);
+            builder.slice_range_check_u8(&d.0, is_real);
+            builder.slice_range_check_u8(&cols.value.0, is_real);
+        }
+
+        builder.assert_bool(is_real);
+        let mut builder_is_real = builder.when(is_real);
+
+        // Each value in is_carry_{0,1,2,3} is 0 or 1, and exactly one of them is 1 per digit.
+        {
+            for i in 0..WORD64_SIZE {
+                builder_is_real.assert_bool(cols.is_carry_0[i]);
+                builder_is_real.assert_bool(cols.is_carry_1[i]);
+                builder_is_real.assert_bool(cols.is_carry_2[i]);
+                builder_is_real.assert_bool(cols.is_carry_3[i]);
+                builder_is_real.assert_eq(
+                    cols.is_carry_0[i] +
+                        cols.is_carry_1[i] +
+                        cols.is_carry_2[i] +
+                        cols.is_carry_3[i],
+                    AB::Expr::one(),
+                );
+            }
+        }
+
+        // Calculates carry from is_carry_{0,1,2,3}.
+        {
+            let one = AB::Expr::one();
+            let two = AB::F::from_canonical_u32(2);
+            let three = AB::F::from_canonical_u32(3);
+
+            for i in 0..WORD64_SIZE {
+                builder_is_real.assert_eq(
+                    cols.carry[i],
+                    cols.is_carry_1[i] * one.clone() +
+                        cols.is_carry_2[i] * two +
+                        cols.is_carry_3[i] * three,
+                );
+            }
+        }
+
+        // Compare the sum and summands by looking at carry.
+        {
+            let base = AB::F::from_canonical_u32(256);
+            // For each limb, assert that difference between the carried result and the non-carried
+            // result is the product of carry and base.
+            for i in 0..WORD64_SIZE {
+                let mut overflow = a[i] + b[i] + c[i] + d[i] - cols.value[i];
+                if i > 0 {
+                    overflow = overflow.clone() + cols.carry[i - 1].into();
+                }
+                builder_is_real.assert_eq(cols.carry[i] * base, overflow.clone());
+            }
+        }
+    }
+}
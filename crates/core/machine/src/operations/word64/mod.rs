@@ -0,0 +1,103 @@
+//! Operations on 64-bit words, each represented as eight little-endian bytes.
+//!
+//! Memory is addressed in 32-bit words, so a 64-bit word is stored as two consecutive words, the
+//! low word first. [`Word64::from_words`] joins the values of the two memory accesses.
+
+mod add;
+mod add4;
+mod add5;
+mod and;
+mod fixed_rotate_right;
+mod fixed_shift_right;
+mod not;
+mod xor;
+
+pub use add::*;
+pub use add4::*;
+pub use add5::*;
+pub use and::*;
+pub use fixed_rotate_right::*;
+pub use fixed_shift_right::*;
+pub use not::*;
+pub use xor::*;
+
+use std::{
+    array::IntoIter,
+    ops::{Index, IndexMut},
+};
+
+use p3_field::{AbstractField, Field};
+use sp1_derive::AlignedBorrow;
+use sp1_primitives::consts::WORD_SIZE;
+use sp1_stark::Word;
+
+/// The number of bytes in a 64-bit word.
+pub const WORD64_SIZE: usize = 2 * WORD_SIZE;
+
+/// An array of eight bytes to represent a 64-bit value.
+#[derive(AlignedBorrow, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct Word64<T>(pub [T; WORD64_SIZE]);
+
+impl<T: Copy> Word64<T> {
+    /// Joins the low and the high 32-bit words of a 64-bit word.
+    pub fn from_words(lo: Word<T>, hi: Word<T>) -> Self {
+        Word64(std::array::from_fn(|i| if i < WORD_SIZE { lo[i] } else { hi[i - WORD_SIZE] }))
+    }
+
+    /// The low 32-bit word.
+    pub fn lo(&self) -> Word<T> {
+        Word(std::array::from_fn(|i| self.0[i]))
+    }
+
+    /// The high 32-bit word.
+    pub fn hi(&self) -> Word<T> {
+        Word(std::array::from_fn(|i| self.0[WORD_SIZE + i]))
+    }
+}
+
+impl<T> Word64<T> {
+    /// Applies `f` to each element of the word.
+    pub fn map<F, S>(self, f: F) -> Word64<S>
+    where
+        F: FnMut(T) -> S,
+    {
+        Word64(self.0.map(f))
+    }
+}
+
+impl<F: Field> Word64<F> {
+    /// Converts a word to a u64.
+    pub fn to_u64(&self) -> u64 {
+        u64::from_le_bytes(self.0.map(|x| x.to_string().parse::<u8>().unwrap()))
+    }
+}
+
+impl<T> Index<usize> for Word64<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+impl<T> IndexMut<usize> for Word64<T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+}
+
+impl<F: AbstractField> From<u64> for Word64<F> {
+    fn from(value: u64) -> Self {
+        Word64(value.to_le_bytes().map(F::from_canonical_u8))
+    }
+}
+
+impl<T> IntoIterator for Word64<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T, WORD64_SIZE>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
@@ -0,0 +1,348 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    mem::size_of,
+};
+
+use crate::{air::MemoryAirBuilder, utils::zeroed_f_vec};
+use itertools::Itertools;
+use num::{BigUint, One, Zero};
+use p3_air::{Air, BaseAir};
+use p3_field::{AbstractField, PrimeField32};
+use p3_matrix::{dense::RowMajorMatrix, Matrix};
+use sp1_core_executor::{
+    events::{ByteLookupEvent, ByteRecord, FieldOperation, PrecompileEvent, BLS12381_FP12_WORDS},
+    syscalls::SyscallCode,
+    ExecutionRecord, Program,
+};
+use sp1_curves::{
+    params::{FieldParameters, Limbs, NumLimbs},
+    weierstrass::bls12_381::Bls12381BaseField,
+};
+use sp1_derive::AlignedBorrow;
+use sp1_stark::air::{BaseAirBuilder, InteractionScope, MachineAir, Polynomial, SP1AirBuilder};
+
+use crate::{
+    memory::{value_as_limbs, MemoryCols, MemoryReadCols, MemoryWriteCols},
+    operations::field::{
+        field_inner_product::FieldInnerProductCols, field_op::FieldOpCols, range::FieldLtCols,
+    },
+    utils::{limbs_from_prev_access, pad_rows_fixed, words_to_bytes_le_vec},
+};
+
+/// The number of Fp coefficients of an Fp12 element.
+const NUM_COEFFS: usize = 12;
+
+/// The number of Fp2 coefficients of an Fp12 element.
+const NUM_FP2_COEFFS: usize = 6;
+
+/// The number of words of an Fp coefficient.
+const FP_WORDS: usize = BLS12381_FP12_WORDS / NUM_COEFFS;
+
+pub const NUM_FP12_MUL_COLS: usize = size_of::<Fp12MulAssignCols<u8>>();
+
+/// A set of columns for the BLS12-381 Fp12Mul operation.
+///
+/// Each of the 12 Fp coefficients of the product is the sum of six products of Fp2 elements,
+/// which is computed as a chain of inner products: the first one is over two terms, and each of
+/// the next ones adds two terms to the result of the previous one.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct Fp12MulAssignCols<T> {
+    pub is_real: T,
+    pub shard: T,
+    pub clk: T,
+    pub x_ptr: T,
+    pub y_ptr: T,
+    pub x_access: [MemoryWriteCols<T>; BLS12381_FP12_WORDS],
+    pub y_access: [MemoryReadCols<T>; BLS12381_FP12_WORDS],
+    /// `-b1` for each Fp2 coefficient `b0 + b1 u` of `y`.
+    pub(crate) y1_neg: [FieldOpCols<T, Bls12381BaseField>; NUM_FP2_COEFFS],
+    /// `b0 + b1` for each Fp2 coefficient `b0 + b1 u` of `y`.
+    pub(crate) y_sum: [FieldOpCols<T, Bls12381BaseField>; NUM_FP2_COEFFS],
+    /// `b0 - b1` for each Fp2 coefficient `b0 + b1 u` of `y`.
+    pub(crate) y_diff: [FieldOpCols<T, Bls12381BaseField>; NUM_FP2_COEFFS],
+    /// `-(b0 + b1)` for each Fp2 coefficient `b0 + b1 u` of `y`.
+    pub(crate) y_sum_neg: [FieldOpCols<T, Bls12381BaseField>; NUM_FP2_COEFFS],
+    pub(crate) products:
+        [[FieldInnerProductCols<T, Bls12381BaseField>; NUM_FP2_COEFFS]; NUM_COEFFS],
+    pub(crate) products_range: [FieldLtCols<T, Bls12381BaseField>; NUM_COEFFS],
+}
+
+/// A chip multiplying two BLS12-381 Fp12 elements in place.
+///
+/// An element of `Fp12 = Fp2[w] / (w^6 - (u + 1))` is laid out in memory as the six Fp2
+/// coefficients of `1, w, ..., w^5`, each as its two Fp coefficients of `1, u`.
+#[derive(Default)]
+pub struct Bls12381Fp12MulAssignChip;
+
+impl Bls12381Fp12MulAssignChip {
+    pub const fn new() -> Self {
+        Self
+    }
+
+    fn populate_field_ops<F: PrimeField32>(
+        blu_events: &mut Vec<ByteLookupEvent>,
+        cols: &mut Fp12MulAssignCols<F>,
+        x: &[BigUint],
+        y: &[BigUint],
+    ) {
+        let zero = BigUint::zero();
+        let mut y1_neg = Vec::with_capacity(NUM_FP2_COEFFS);
+        let mut y_sum = Vec::with_capacity(NUM_FP2_COEFFS);
+        let mut y_diff = Vec::with_capacity(NUM_FP2_COEFFS);
+        let mut y_sum_neg = Vec::with_capacity(NUM_FP2_COEFFS);
+        for j in 0..NUM_FP2_COEFFS {
+            let (b0, b1) = (&y[2 * j], &y[2 * j + 1]);
+            y1_neg.push(cols.y1_neg[j].populate(blu_events, &zero, b1, FieldOperation::Sub));
+            let sum = cols.y_sum[j].populate(blu_events, b0, b1, FieldOperation::Add);
+            y_diff.push(cols.y_diff[j].populate(blu_events, b0, b1, FieldOperation::Sub));
+            y_sum_neg.push(cols.y_sum_neg[j].populate(
+                blu_events,
+                &zero,
+                &sum,
+                FieldOperation::Sub,
+            ));
+            y_sum.push(sum);
+        }
+
+        for (n, (products, range)) in
+            cols.products.iter_mut().zip_eq(cols.products_range.iter_mut()).enumerate()
+        {
+            let mut acc = None;
+            for (i, product) in products.iter_mut().enumerate() {
+                let [b0, b1] = Self::y_terms(n, i, y, &y1_neg, &y_sum, &y_diff, &y_sum_neg);
+                let (a0, a1) = (x[2 * i].clone(), x[2 * i + 1].clone());
+                acc = Some(match acc {
+                    None => product.populate(blu_events, &[a0, a1], &[b0, b1]),
+                    Some(acc) => {
+                        product.populate(blu_events, &[acc, a0, a1], &[BigUint::one(), b0, b1])
+                    }
+                });
+            }
+            range.populate(blu_events, &acc.unwrap(), &Bls12381BaseField::modulus());
+        }
+    }
+
+    /// Returns the terms of `y` that the Fp2 coefficient `i` of `x` is multiplied with in the Fp
+    /// coefficient `n` of the product.
+    ///
+    /// The coefficient `a0 + a1 u` of `w^i` in `x` times the coefficient `b0 + b1 u` of `w^j` in
+    /// `y` is `(a0 b0 - a1 b1) + (a0 b1 + a1 b0) u`, and if `i + j >= 6` it is multiplied by
+    /// `w^6 = u + 1` to give `(a0 (b0 - b1) - a1 (b0 + b1)) + (a0 (b0 + b1) + a1 (b0 - b1)) u`.
+    #[allow(clippy::too_many_arguments)]
+    fn y_terms<T: Clone>(
+        n: usize,
+        i: usize,
+        y: &[T],
+        y1_neg: &[T],
+        y_sum: &[T],
+        y_diff: &[T],
+        y_sum_neg: &[T],
+    ) -> [T; 2] {
+        let k = n / 2;
+        let j = (k + NUM_FP2_COEFFS - i) % NUM_FP2_COEFFS;
+        match (n % 2 == 1, i > k) {
+            (false, false) => [y[2 * j].clone(), y1_neg[j].clone()],
+            (true, false) => [y[2 * j + 1].clone(), y[2 * j].clone()],
+            (false, true) => [y_diff[j].clone(), y_sum_neg[j].clone()],
+            (true, true) => [y_sum[j].clone(), y_diff[j].clone()],
+        }
+    }
+}
+
+impl<F: PrimeField32> MachineAir<F> for Bls12381Fp12MulAssignChip {
+    type Record = ExecutionRecord;
+
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "Bls12381Fp12MulAssign".to_string()
+    }
+
+    fn generate_trace(&self, input: &Self::Record, output: &mut Self::Record) -> RowMajorMatrix<F> {
+        let events = input.get_precompile_events(SyscallCode::BLS12381_FP12_MUL);
+
+        let mut rows = Vec::new();
+        let mut new_byte_lookup_events = Vec::new();
+
+        for (_, event) in events {
+            let event = match event {
+                PrecompileEvent::Bls12381Fp12Mul(event) => event,
+                _ => unreachable!(),
+            };
+
+            let mut row = zeroed_f_vec(NUM_FP12_MUL_COLS);
+            let cols: &mut Fp12MulAssignCols<F> = row.as_mut_slice().borrow_mut();
+
+            let coeffs = |words: &[u32]| {
+                words
+                    .chunks_exact(FP_WORDS)
+                    .map(|chunk| BigUint::from_bytes_le(&words_to_bytes_le_vec(chunk)))
+                    .collect_vec()
+            };
+            let x = coeffs(&event.x);
+            let y = coeffs(&event.y);
+
+            cols.is_real = F::one();
+            cols.shard = F::from_canonical_u32(event.shard);
+            cols.clk = F::from_canonical_u32(event.clk);
+            cols.x_ptr = F::from_canonical_u32(event.x_ptr);
+            cols.y_ptr = F::from_canonical_u32(event.y_ptr);
+
+            Self::populate_field_ops(&mut new_byte_lookup_events, cols, &x, &y);
+
+            // Populate the memory access columns.
+            for i in 0..cols.y_access.len() {
+                cols.y_access[i].populate(event.y_memory_records[i], &mut new_byte_lookup_events);
+            }
+            for i in 0..cols.x_access.len() {
+                cols.x_access[i].populate(event.x_memory_records[i], &mut new_byte_lookup_events);
+            }
+            rows.push(row);
+        }
+
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows_fixed(
+            &mut rows,
+            || {
+                let mut row = zeroed_f_vec(NUM_FP12_MUL_COLS);
+                let cols: &mut Fp12MulAssignCols<F> = row.as_mut_slice().borrow_mut();
+                let zero = vec![BigUint::zero(); NUM_COEFFS];
+                Self::populate_field_ops(&mut vec![], cols, &zero, &zero);
+                row
+            },
+            input.fixed_log2_rows::<F, _>(self),
+        );
+
+        // Convert the trace to a row major matrix.
+        RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_FP12_MUL_COLS)
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        if let Some(shape) = shard.shape.as_ref() {
+            shape.included::<F, _>(self)
+        } else {
+            !shard.get_precompile_events(SyscallCode::BLS12381_FP12_MUL).is_empty()
+        }
+    }
+
+    fn local_only(&self) -> bool {
+        true
+    }
+}
+
+impl<F> BaseAir<F> for Bls12381Fp12MulAssignChip {
+    fn width(&self) -> usize {
+        NUM_FP12_MUL_COLS
+    }
+}
+
+impl<AB> Air<AB> for Bls12381Fp12MulAssignChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Fp12MulAssignCols<AB::Var> = (*local).borrow();
+
+        let x = coeffs_from_prev_access::<AB, _>(&local.x_access);
+        let y = coeffs_from_prev_access::<AB, _>(&local.y_access);
+
+        let modulus_coeffs =
+            Bls12381BaseField::MODULUS.iter().map(|&limb| AB::Expr::from_canonical_u8(limb));
+        let p_modulus = Polynomial::from_iter(modulus_coeffs);
+        let p_zero = Polynomial::from_coefficients(&[AB::Expr::zero()]);
+        let p_one = Polynomial::from_coefficients(&[AB::Expr::one()]);
+
+        for j in 0..NUM_FP2_COEFFS {
+            let (b0, b1) = (&y[2 * j], &y[2 * j + 1]);
+            local.y1_neg[j].eval(builder, &p_zero, b1, FieldOperation::Sub, local.is_real);
+            local.y_sum[j].eval(builder, b0, b1, FieldOperation::Add, local.is_real);
+            local.y_diff[j].eval(builder, b0, b1, FieldOperation::Sub, local.is_real);
+            local.y_sum_neg[j].eval(
+                builder,
+                &p_zero,
+                &local.y_sum[j].result,
+                FieldOperation::Sub,
+                local.is_real,
+            );
+        }
+        let results =
+            |ops: &[FieldOpCols<AB::Var, Bls12381BaseField>]| -> Vec<Polynomial<AB::Expr>> {
+                ops.iter().map(|op| op.result.into()).collect()
+            };
+        let y1_neg = results(&local.y1_neg);
+        let y_sum = results(&local.y_sum);
+        let y_diff = results(&local.y_diff);
+        let y_sum_neg = results(&local.y_sum_neg);
+
+        for (n, (products, range)) in
+            local.products.iter().zip_eq(local.products_range.iter()).enumerate()
+        {
+            let mut acc: Option<Polynomial<AB::Expr>> = None;
+            for (i, product) in products.iter().enumerate() {
+                let [b0, b1] = Self::y_terms(n, i, &y, &y1_neg, &y_sum, &y_diff, &y_sum_neg);
+                let (a0, a1) = (x[2 * i].clone(), x[2 * i + 1].clone());
+                match acc {
+                    None => product.eval(builder, &[a0, a1], &[b0, b1], local.is_real),
+                    Some(acc) => product.eval(
+                        builder,
+                        &[acc, a0, a1],
+                        &[p_one.clone(), b0, b1],
+                        local.is_real,
+                    ),
+                }
+                acc = Some(product.result.into());
+            }
+
+            let result = products[NUM_FP2_COEFFS - 1].result;
+            builder.when(local.is_real).assert_all_eq(
+                result,
+                value_as_limbs(&local.x_access[n * FP_WORDS..(n + 1) * FP_WORDS]),
+            );
+            range.eval(builder, &result, &p_modulus, local.is_real);
+        }
+
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.clk.into(),
+            local.y_ptr,
+            &local.y_access,
+            local.is_real,
+        );
+        // x is written one cycle after y is read, so that x and y can be the same.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.clk + AB::F::from_canonical_u32(1),
+            local.x_ptr,
+            &local.x_access,
+            local.is_real,
+        );
+
+        builder.receive_syscall(
+            local.shard,
+            local.clk,
+            AB::F::from_canonical_u32(SyscallCode::BLS12381_FP12_MUL.syscall_id()),
+            local.x_ptr,
+            local.y_ptr,
+            local.is_real,
+            InteractionScope::Local,
+        );
+    }
+}
+
+/// Returns the Fp coefficients of an Fp12 element from its memory accesses.
+fn coeffs_from_prev_access<AB: SP1AirBuilder, M: MemoryCols<AB::Var>>(
+    access: &[M],
+) -> Vec<Polynomial<AB::Expr>> {
+    access
+        .chunks_exact(FP_WORDS)
+        .map(|chunk| {
+            let limbs: Limbs<AB::Var, <Bls12381BaseField as NumLimbs>::Limbs> =
+                limbs_from_prev_access(chunk);
+            limbs.into()
+        })
+        .collect()
+}
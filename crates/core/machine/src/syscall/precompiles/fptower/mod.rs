@@ -1,8 +1,10 @@
 mod fp;
+mod fp12_mul;
 mod fp2_addsub;
 mod fp2_mul;
 
 pub use fp::*;
+pub use fp12_mul::*;
 pub use fp2_addsub::*;
 pub use fp2_mul::*;
 
@@ -66,3 +68,127 @@ mod tests {
         utils::run_test::<CpuProver<_, _>>(program, stdin).unwrap();
     }
 }
+
+#[cfg(all(test, feature = "bls12381-fp12"))]
+mod fp12_tests {
+    use ark_bls12_381::{Bls12_381, Fq, Fq12, Fr, G1Affine, G2Affine};
+    use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+    use ark_ff::{BigInteger, One, PrimeField, UniformRand};
+    use sp1_stark::{CpuProver, SP1CoreOpts};
+
+    use sp1_core_executor::{Executor, Program};
+    use test_artifacts::{BLS12381_FP12_MUL_ELF, BLS12381_PAIRING_ELF};
+
+    use crate::{io::SP1Stdin, utils};
+
+    type PairingCase = (Vec<(Vec<u32>, Vec<u32>)>, bool);
+
+    fn fq_words(f: &Fq) -> Vec<u32> {
+        let bytes = f.into_bigint().to_bytes_le();
+        bytes.chunks_exact(4).map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap())).collect()
+    }
+
+    /// The words of an Fp12 element, by coefficient of `1, w, ..., w^5`.
+    fn fq12_words(f: &Fq12) -> Vec<u32> {
+        (0..6)
+            .flat_map(|k| {
+                let fq6 = if k % 2 == 0 { &f.c0 } else { &f.c1 };
+                let fq2 = [&fq6.c0, &fq6.c1, &fq6.c2][k / 2];
+                [fq_words(&fq2.c0), fq_words(&fq2.c1)].concat()
+            })
+            .collect()
+    }
+
+    fn g1_words(p: &G1Affine) -> Vec<u32> {
+        match p.xy() {
+            Some((x, y)) => [fq_words(x), fq_words(y)].concat(),
+            None => vec![0; 24],
+        }
+    }
+
+    fn g2_words(q: &G2Affine) -> Vec<u32> {
+        match q.xy() {
+            Some((x, y)) => {
+                [fq_words(&x.c0), fq_words(&x.c1), fq_words(&y.c0), fq_words(&y.c1)].concat()
+            }
+            None => vec![0; 48],
+        }
+    }
+
+    /// A pairing check of the given points, with the result computed by arkworks.
+    fn pairing_case(pairs: &[(G1Affine, G2Affine)]) -> PairingCase {
+        let expected =
+            Bls12_381::multi_pairing(pairs.iter().map(|(p, _)| *p), pairs.iter().map(|(_, q)| *q))
+                .0
+                .is_one();
+        (pairs.iter().map(|(p, q)| (g1_words(p), g2_words(q))).collect(), expected)
+    }
+
+    fn pairing_cases() -> Vec<PairingCase> {
+        let mut rng = rand::thread_rng();
+        let g1 = G1Affine::generator();
+        let g2 = G2Affine::generator();
+        let a = Fr::rand(&mut rng);
+        let b = Fr::rand(&mut rng);
+        let p = (g1 * a).into_affine();
+        let q = (g2 * b).into_affine();
+        let neg_ab_g1 = (g1 * -(a * b)).into_affine();
+
+        let cases = vec![
+            // e(a G1, b G2) e(-ab G1, G2) = 1.
+            pairing_case(&[(p, q), (neg_ab_g1, g2)]),
+            // e(a G1, b G2) e(-ab G1, 2 G2) != 1.
+            pairing_case(&[(p, q), (neg_ab_g1, (g2 * Fr::from(2u64)).into_affine())]),
+            // Pairs with a point at infinity are skipped.
+            pairing_case(&[(G1Affine::zero(), q), (p, G2Affine::zero())]),
+            pairing_case(&[(p, q), (G1Affine::zero(), g2)]),
+            pairing_case(&[]),
+        ];
+        assert_eq!(
+            cases.iter().map(|(_, expected)| *expected).collect::<Vec<_>>(),
+            [true, false, true, false, true]
+        );
+        cases
+    }
+
+    #[test]
+    fn test_bls12381_fp12_mul() {
+        utils::setup_logger();
+        let mut rng = rand::thread_rng();
+        let cases: Vec<(Vec<u32>, Vec<u32>, Vec<u32>)> = (0..4)
+            .map(|_| {
+                let x = Fq12::rand(&mut rng);
+                let y = Fq12::rand(&mut rng);
+                (fq12_words(&x), fq12_words(&y), fq12_words(&(x * y)))
+            })
+            .collect();
+
+        let program = Program::from(BLS12381_FP12_MUL_ELF).unwrap();
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&cases);
+        utils::run_test::<CpuProver<_, _>>(program, stdin).unwrap();
+    }
+
+    #[test]
+    fn test_bls12381_pairing_check_execute() {
+        utils::setup_logger();
+        let program = Program::from(BLS12381_PAIRING_ELF).unwrap();
+        for case in pairing_cases() {
+            let mut stdin = SP1Stdin::new();
+            stdin.write(&vec![case]);
+            let mut runtime = Executor::new(program.clone(), SP1CoreOpts::default());
+            runtime.write_vecs(&stdin.buffer);
+            runtime.run().unwrap();
+            assert!(runtime.state.global_clk < 10_000_000, "{} cycles", runtime.state.global_clk);
+        }
+    }
+
+    #[test]
+    fn test_bls12381_pairing_check() {
+        utils::setup_logger();
+        let program = Program::from(BLS12381_PAIRING_ELF).unwrap();
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&pairing_cases()[..1]);
+        utils::run_test::<CpuProver<_, _>>(program, stdin).unwrap();
+    }
+}
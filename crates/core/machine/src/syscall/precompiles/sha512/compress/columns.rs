@@ -0,0 +1,107 @@
+use std::mem::size_of;
+
+use sp1_derive::AlignedBorrow;
+
+use crate::{
+    memory::MemoryReadWriteCols,
+    operations::{
+        Word64, Word64Add5Operation, Word64AddOperation, Word64AndOperation,
+        Word64FixedRotateRightOperation, Word64NotOperation, Word64XorOperation,
+    },
+};
+
+pub const NUM_SHA512_COMPRESS_COLS: usize = size_of::<Sha512CompressCols<u8>>();
+
+/// A set of columns needed to compute the SHA-512 compression function.
+///
+/// Each sha512 compress syscall is processed over 96 rows, split into 12 octets. The first octet
+/// is for initialization, the next 10 octets are for compression, and the last octet is for
+/// finalize. Each row accesses a single 64-bit word, which takes two memory accesses.
+#[derive(AlignedBorrow, Default, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Sha512CompressCols<T> {
+    /// Inputs.
+    pub shard: T,
+    pub clk: T,
+    pub w_ptr: T,
+    pub h_ptr: T,
+
+    pub start: T,
+
+    /// Which cycle within the octet we are currently processing.
+    pub octet: [T; 8],
+
+    /// This will specify which octet we are currently processing.
+    ///  - The first octet is for initialize.
+    ///  - The next 10 octets are for compress.
+    ///  - The last octet is for finalize.
+    pub octet_num: [T; 12],
+
+    /// Memory accesses of the low and the high word. During init and compression, this is read
+    /// only. During finalize, this is used to write the result into memory.
+    pub mem: [MemoryReadWriteCols<T>; 2],
+    /// Address of the low word being written/read. During init and finalize, this is A-H. During
+    /// compression, this is w[i] being read only.
+    pub mem_addr: T,
+
+    pub a: Word64<T>,
+    pub b: Word64<T>,
+    pub c: Word64<T>,
+    pub d: Word64<T>,
+    pub e: Word64<T>,
+    pub f: Word64<T>,
+    pub g: Word64<T>,
+    pub h: Word64<T>,
+
+    /// Current value of K[i]. This is a constant array that loops around every 80 iterations.
+    pub k: Word64<T>,
+
+    pub e_rr_14: Word64FixedRotateRightOperation<T>,
+    pub e_rr_18: Word64FixedRotateRightOperation<T>,
+    pub e_rr_41: Word64FixedRotateRightOperation<T>,
+    pub s1_intermediate: Word64XorOperation<T>,
+    /// `S1 := (e rightrotate 14) xor (e rightrotate 18) xor (e rightrotate 41)`.
+    pub s1: Word64XorOperation<T>,
+
+    pub e_and_f: Word64AndOperation<T>,
+    pub e_not: Word64NotOperation<T>,
+    pub e_not_and_g: Word64AndOperation<T>,
+    /// `ch := (e and f) xor ((not e) and g)`.
+    pub ch: Word64XorOperation<T>,
+
+    /// `temp1 := h + S1 + ch + k[i] + w[i]`.
+    pub temp1: Word64Add5Operation<T>,
+
+    pub a_rr_28: Word64FixedRotateRightOperation<T>,
+    pub a_rr_34: Word64FixedRotateRightOperation<T>,
+    pub a_rr_39: Word64FixedRotateRightOperation<T>,
+    pub s0_intermediate: Word64XorOperation<T>,
+    /// `S0 := (a rightrotate 28) xor (a rightrotate 34) xor (a rightrotate 39)`.
+    pub s0: Word64XorOperation<T>,
+
+    pub a_and_b: Word64AndOperation<T>,
+    pub a_and_c: Word64AndOperation<T>,
+    pub b_and_c: Word64AndOperation<T>,
+    pub maj_intermediate: Word64XorOperation<T>,
+    /// `maj := (a and b) xor (a and c) xor (b and c)`.
+    pub maj: Word64XorOperation<T>,
+
+    /// `temp2 := S0 + maj`.
+    pub temp2: Word64AddOperation<T>,
+
+    /// The next value of `e` is `d + temp1`.
+    pub d_add_temp1: Word64AddOperation<T>,
+    /// The next value of `a` is `temp1 + temp2`.
+    pub temp1_add_temp2: Word64AddOperation<T>,
+
+    /// During finalize, this is one of a-h and is being written into `mem`.
+    pub finalized_operand: Word64<T>,
+    pub finalize_add: Word64AddOperation<T>,
+
+    pub is_initialize: T,
+    pub is_compression: T,
+    pub is_finalize: T,
+    pub is_last_row: T,
+
+    pub is_real: T,
+}
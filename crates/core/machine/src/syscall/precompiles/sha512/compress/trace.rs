@@ -0,0 +1,288 @@
+use std::borrow::BorrowMut;
+
+use hashbrown::HashMap;
+use itertools::Itertools;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_maybe_rayon::prelude::{ParallelIterator, ParallelSlice};
+use sp1_core_executor::{
+    events::{ByteLookupEvent, ByteRecord, PrecompileEvent, Sha512CompressEvent},
+    syscalls::SyscallCode,
+    ExecutionRecord, Program,
+};
+use sp1_stark::air::MachineAir;
+
+use super::{
+    columns::{Sha512CompressCols, NUM_SHA512_COMPRESS_COLS},
+    Sha512CompressChip, SHA512_COMPRESS_K,
+};
+use crate::{operations::Word64, utils::pad_rows_fixed};
+
+impl<F: PrimeField32> MachineAir<F> for Sha512CompressChip {
+    type Record = ExecutionRecord;
+
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "Sha512Compress".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        _: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let rows = Vec::new();
+
+        let mut wrapped_rows = Some(rows);
+        for (_, event) in input.get_precompile_events(SyscallCode::SHA512_COMPRESS) {
+            let event = if let PrecompileEvent::Sha512Compress(event) = event {
+                event
+            } else {
+                unreachable!()
+            };
+            self.event_to_rows(event, &mut wrapped_rows, &mut Vec::new());
+        }
+        let mut rows = wrapped_rows.unwrap();
+
+        let num_real_rows = rows.len();
+
+        pad_rows_fixed(
+            &mut rows,
+            || [F::zero(); NUM_SHA512_COMPRESS_COLS],
+            input.fixed_log2_rows::<F, _>(self),
+        );
+
+        // Set the octet_num and octet columns for the padded rows.
+        let mut octet_num = 0;
+        let mut octet = 0;
+        for row in rows[num_real_rows..].iter_mut() {
+            let cols: &mut Sha512CompressCols<F> = row.as_mut_slice().borrow_mut();
+            cols.octet_num[octet_num] = F::one();
+            cols.octet[octet] = F::one();
+
+            // If in the compression phase, set the k value.
+            if octet_num != 0 && octet_num != 11 {
+                let compression_idx = octet_num - 1;
+                let k_idx = compression_idx * 8 + octet;
+                cols.k = Word64::from(SHA512_COMPRESS_K[k_idx]);
+            }
+
+            octet = (octet + 1) % 8;
+            if octet == 0 {
+                octet_num = (octet_num + 1) % 12;
+            }
+
+            cols.is_last_row = cols.octet[7] * cols.octet_num[11];
+        }
+
+        // Convert the trace to a row major matrix.
+        RowMajorMatrix::new(
+            rows.into_iter().flatten().collect::<Vec<_>>(),
+            NUM_SHA512_COMPRESS_COLS,
+        )
+    }
+
+    fn generate_dependencies(&self, input: &Self::Record, output: &mut Self::Record) {
+        let events = input.get_precompile_events(SyscallCode::SHA512_COMPRESS);
+        let chunk_size = std::cmp::max(events.len() / num_cpus::get(), 1);
+
+        let blu_batches = events
+            .par_chunks(chunk_size)
+            .map(|events| {
+                let mut blu: HashMap<ByteLookupEvent, usize> = HashMap::new();
+                events.iter().for_each(|(_, event)| {
+                    let event = if let PrecompileEvent::Sha512Compress(event) = event {
+                        event
+                    } else {
+                        unreachable!()
+                    };
+                    self.event_to_rows::<F>(event, &mut None, &mut blu);
+                });
+                blu
+            })
+            .collect::<Vec<_>>();
+
+        output.add_byte_lookup_events_from_maps(blu_batches.iter().collect_vec());
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        if let Some(shape) = shard.shape.as_ref() {
+            shape.included::<F, _>(self)
+        } else {
+            !shard.get_precompile_events(SyscallCode::SHA512_COMPRESS).is_empty()
+        }
+    }
+}
+
+impl Sha512CompressChip {
+    fn event_to_rows<F: PrimeField32>(
+        &self,
+        event: &Sha512CompressEvent,
+        rows: &mut Option<Vec<[F; NUM_SHA512_COMPRESS_COLS]>>,
+        blu: &mut impl ByteRecord,
+    ) {
+        let og_h = event.h;
+
+        let mut octet_num_idx = 0;
+
+        // Load a, b, c, d, e, f, g, h.
+        for j in 0..8usize {
+            let mut row = [F::zero(); NUM_SHA512_COMPRESS_COLS];
+            let cols: &mut Sha512CompressCols<F> = row.as_mut_slice().borrow_mut();
+
+            cols.shard = F::from_canonical_u32(event.shard);
+            cols.clk = F::from_canonical_u32(event.clk);
+            cols.w_ptr = F::from_canonical_u32(event.w_ptr);
+            cols.h_ptr = F::from_canonical_u32(event.h_ptr);
+
+            cols.octet[j] = F::one();
+            cols.octet_num[octet_num_idx] = F::one();
+            cols.is_initialize = F::one();
+
+            cols.mem[0].populate_read(event.h_read_records[2 * j], blu);
+            cols.mem[1].populate_read(event.h_read_records[2 * j + 1], blu);
+            cols.mem_addr = F::from_canonical_u32(event.h_ptr + (j * 8) as u32);
+
+            cols.a = Word64::from(og_h[0]);
+            cols.b = Word64::from(og_h[1]);
+            cols.c = Word64::from(og_h[2]);
+            cols.d = Word64::from(og_h[3]);
+            cols.e = Word64::from(og_h[4]);
+            cols.f = Word64::from(og_h[5]);
+            cols.g = Word64::from(og_h[6]);
+            cols.h = Word64::from(og_h[7]);
+
+            cols.is_real = F::one();
+            cols.start = cols.is_real * cols.octet_num[0] * cols.octet[0];
+            if rows.as_ref().is_some() {
+                rows.as_mut().unwrap().push(row);
+            }
+        }
+
+        // Performs the compress operation.
+        let mut h_array = event.h;
+        for j in 0..80 {
+            if j % 8 == 0 {
+                octet_num_idx += 1;
+            }
+            let mut row = [F::zero(); NUM_SHA512_COMPRESS_COLS];
+            let cols: &mut Sha512CompressCols<F> = row.as_mut_slice().borrow_mut();
+
+            cols.k = Word64::from(SHA512_COMPRESS_K[j]);
+            cols.is_compression = F::one();
+            cols.octet[j % 8] = F::one();
+            cols.octet_num[octet_num_idx] = F::one();
+
+            cols.shard = F::from_canonical_u32(event.shard);
+            cols.clk = F::from_canonical_u32(event.clk);
+            cols.w_ptr = F::from_canonical_u32(event.w_ptr);
+            cols.h_ptr = F::from_canonical_u32(event.h_ptr);
+            cols.mem[0].populate_read(event.w_i_read_records[j][0], blu);
+            cols.mem[1].populate_read(event.w_i_read_records[j][1], blu);
+            cols.mem_addr = F::from_canonical_u32(event.w_ptr + (j * 8) as u32);
+
+            let a = h_array[0];
+            let b = h_array[1];
+            let c = h_array[2];
+            let d = h_array[3];
+            let e = h_array[4];
+            let f = h_array[5];
+            let g = h_array[6];
+            let h = h_array[7];
+            cols.a = Word64::from(a);
+            cols.b = Word64::from(b);
+            cols.c = Word64::from(c);
+            cols.d = Word64::from(d);
+            cols.e = Word64::from(e);
+            cols.f = Word64::from(f);
+            cols.g = Word64::from(g);
+            cols.h = Word64::from(h);
+
+            let e_rr_14 = cols.e_rr_14.populate(blu, e, 14);
+            let e_rr_18 = cols.e_rr_18.populate(blu, e, 18);
+            let e_rr_41 = cols.e_rr_41.populate(blu, e, 41);
+            let s1_intermediate = cols.s1_intermediate.populate(blu, e_rr_14, e_rr_18);
+            let s1 = cols.s1.populate(blu, s1_intermediate, e_rr_41);
+
+            let e_and_f = cols.e_and_f.populate(blu, e, f);
+            let e_not = cols.e_not.populate(blu, e);
+            let e_not_and_g = cols.e_not_and_g.populate(blu, e_not, g);
+            let ch = cols.ch.populate(blu, e_and_f, e_not_and_g);
+
+            let temp1 = cols.temp1.populate(blu, h, s1, ch, SHA512_COMPRESS_K[j], event.w[j]);
+
+            let a_rr_28 = cols.a_rr_28.populate(blu, a, 28);
+            let a_rr_34 = cols.a_rr_34.populate(blu, a, 34);
+            let a_rr_39 = cols.a_rr_39.populate(blu, a, 39);
+            let s0_intermediate = cols.s0_intermediate.populate(blu, a_rr_28, a_rr_34);
+            let s0 = cols.s0.populate(blu, s0_intermediate, a_rr_39);
+
+            let a_and_b = cols.a_and_b.populate(blu, a, b);
+            let a_and_c = cols.a_and_c.populate(blu, a, c);
+            let b_and_c = cols.b_and_c.populate(blu, b, c);
+            let maj_intermediate = cols.maj_intermediate.populate(blu, a_and_b, a_and_c);
+            let maj = cols.maj.populate(blu, maj_intermediate, b_and_c);
+
+            let temp2 = cols.temp2.populate(blu, s0, maj);
+
+            let d_add_temp1 = cols.d_add_temp1.populate(blu, d, temp1);
+            let temp1_add_temp2 = cols.temp1_add_temp2.populate(blu, temp1, temp2);
+
+            h_array[7] = g;
+            h_array[6] = f;
+            h_array[5] = e;
+            h_array[4] = d_add_temp1;
+            h_array[3] = c;
+            h_array[2] = b;
+            h_array[1] = a;
+            h_array[0] = temp1_add_temp2;
+
+            cols.is_real = F::one();
+            cols.start = cols.is_real * cols.octet_num[0] * cols.octet[0];
+
+            if rows.as_ref().is_some() {
+                rows.as_mut().unwrap().push(row);
+            }
+        }
+
+        octet_num_idx += 1;
+        // Store a, b, c, d, e, f, g, h.
+        for j in 0..8usize {
+            let mut row = [F::zero(); NUM_SHA512_COMPRESS_COLS];
+            let cols: &mut Sha512CompressCols<F> = row.as_mut_slice().borrow_mut();
+
+            cols.shard = F::from_canonical_u32(event.shard);
+            cols.clk = F::from_canonical_u32(event.clk);
+            cols.w_ptr = F::from_canonical_u32(event.w_ptr);
+            cols.h_ptr = F::from_canonical_u32(event.h_ptr);
+
+            cols.octet[j] = F::one();
+            cols.octet_num[octet_num_idx] = F::one();
+            cols.is_finalize = F::one();
+
+            cols.finalize_add.populate(blu, og_h[j], h_array[j]);
+            cols.mem[0].populate_write(event.h_write_records[2 * j], blu);
+            cols.mem[1].populate_write(event.h_write_records[2 * j + 1], blu);
+            cols.mem_addr = F::from_canonical_u32(event.h_ptr + (j * 8) as u32);
+
+            cols.a = Word64::from(h_array[0]);
+            cols.b = Word64::from(h_array[1]);
+            cols.c = Word64::from(h_array[2]);
+            cols.d = Word64::from(h_array[3]);
+            cols.e = Word64::from(h_array[4]);
+            cols.f = Word64::from(h_array[5]);
+            cols.g = Word64::from(h_array[6]);
+            cols.h = Word64::from(h_array[7]);
+            cols.finalized_operand = Word64::from(h_array[j]);
+
+            cols.is_real = F::one();
+            cols.is_last_row = cols.octet[7] * cols.octet_num[11];
+            cols.start = cols.is_real * cols.octet_num[0] * cols.octet[0];
+
+            if rows.as_ref().is_some() {
+                rows.as_mut().unwrap().push(row);
+            }
+        }
+    }
+}
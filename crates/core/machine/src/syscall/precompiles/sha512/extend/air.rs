@@ -0,0 +1,232 @@
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::AbstractField;
+use p3_matrix::Matrix;
+use sp1_core_executor::syscalls::SyscallCode;
+use sp1_stark::air::{InteractionScope, SP1AirBuilder};
+
+use super::{Sha512ExtendChip, Sha512ExtendCols, NUM_SHA512_EXTEND_COLS};
+use crate::{
+    air::MemoryAirBuilder,
+    memory::{MemoryCols, MemoryReadCols},
+    operations::{
+        Word64, Word64Add4Operation, Word64FixedRotateRightOperation,
+        Word64FixedShiftRightOperation, Word64XorOperation,
+    },
+};
+
+use core::borrow::Borrow;
+use sp1_stark::air::BaseAirBuilder;
+
+impl<F> BaseAir<F> for Sha512ExtendChip {
+    fn width(&self) -> usize {
+        NUM_SHA512_EXTEND_COLS
+    }
+}
+
+/// The 64-bit value read by a pair of memory accesses.
+fn read_value<T: Copy>(cols: &[MemoryReadCols<T>; 2]) -> Word64<T> {
+    Word64::from_words(*cols[0].value(), *cols[1].value())
+}
+
+impl<AB> Air<AB> for Sha512ExtendChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        // Initialize columns.
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let local: &Sha512ExtendCols<AB::Var> = (*local).borrow();
+        let next: &Sha512ExtendCols<AB::Var> = (*next).borrow();
+
+        let i_start = AB::F::from_canonical_u32(16);
+        let nb_bytes_in_word64 = AB::F::from_canonical_u32(8);
+
+        // Evaluate the control flags.
+        self.eval_flags(builder);
+
+        // Copy over the inputs until the result has been computed (every 64 rows).
+        builder
+            .when_transition()
+            .when_not(local.cycle_16_end.result * local.cycle_64[3])
+            .assert_eq(local.shard, next.shard);
+        builder
+            .when_transition()
+            .when_not(local.cycle_16_end.result * local.cycle_64[3])
+            .assert_eq(local.clk, next.clk);
+        builder
+            .when_transition()
+            .when_not(local.cycle_16_end.result * local.cycle_64[3])
+            .assert_eq(local.w_ptr, next.w_ptr);
+
+        // Read w[i-15].
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.clk + (local.i - i_start),
+            local.w_ptr + (local.i - AB::F::from_canonical_u32(15)) * nb_bytes_in_word64,
+            &local.w_i_minus_15,
+            local.is_real,
+        );
+
+        // Read w[i-2].
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.clk + (local.i - i_start),
+            local.w_ptr + (local.i - AB::F::from_canonical_u32(2)) * nb_bytes_in_word64,
+            &local.w_i_minus_2,
+            local.is_real,
+        );
+
+        // Read w[i-16].
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.clk + (local.i - i_start),
+            local.w_ptr + (local.i - AB::F::from_canonical_u32(16)) * nb_bytes_in_word64,
+            &local.w_i_minus_16,
+            local.is_real,
+        );
+
+        // Read w[i-7].
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.clk + (local.i - i_start),
+            local.w_ptr + (local.i - AB::F::from_canonical_u32(7)) * nb_bytes_in_word64,
+            &local.w_i_minus_7,
+            local.is_real,
+        );
+
+        let w_i_minus_15 = read_value(&local.w_i_minus_15);
+        let w_i_minus_2 = read_value(&local.w_i_minus_2);
+
+        // Compute `s0`.
+        // w[i-15] rightrotate 1.
+        Word64FixedRotateRightOperation::<AB::F>::eval(
+            builder,
+            w_i_minus_15,
+            1,
+            local.w_i_minus_15_rr_1,
+            local.is_real,
+        );
+        // w[i-15] rightrotate 8.
+        Word64FixedRotateRightOperation::<AB::F>::eval(
+            builder,
+            w_i_minus_15,
+            8,
+            local.w_i_minus_15_rr_8,
+            local.is_real,
+        );
+        // w[i-15] rightshift 7.
+        Word64FixedShiftRightOperation::<AB::F>::eval(
+            builder,
+            w_i_minus_15,
+            7,
+            local.w_i_minus_15_rs_7,
+            local.is_real,
+        );
+        // (w[i-15] rightrotate 1) xor (w[i-15] rightrotate 8)
+        Word64XorOperation::<AB::F>::eval(
+            builder,
+            local.w_i_minus_15_rr_1.value,
+            local.w_i_minus_15_rr_8.value,
+            local.s0_intermediate,
+            local.is_real,
+        );
+        // s0 := (w[i-15] rightrotate 1) xor (w[i-15] rightrotate 8) xor (w[i-15] rightshift 7)
+        Word64XorOperation::<AB::F>::eval(
+            builder,
+            local.s0_intermediate.value,
+            local.w_i_minus_15_rs_7.value,
+            local.s0,
+            local.is_real,
+        );
+
+        // Compute `s1`.
+        // w[i-2] rightrotate 19.
+        Word64FixedRotateRightOperation::<AB::F>::eval(
+            builder,
+            w_i_minus_2,
+            19,
+            local.w_i_minus_2_rr_19,
+            local.is_real,
+        );
+        // w[i-2] rightrotate 61.
+        Word64FixedRotateRightOperation::<AB::F>::eval(
+            builder,
+            w_i_minus_2,
+            61,
+            local.w_i_minus_2_rr_61,
+            local.is_real,
+        );
+        // w[i-2] rightshift 6.
+        Word64FixedShiftRightOperation::<AB::F>::eval(
+            builder,
+            w_i_minus_2,
+            6,
+            local.w_i_minus_2_rs_6,
+            local.is_real,
+        );
+        // (w[i-2] rightrotate 19) xor (w[i-2] rightrotate 61)
+        Word64XorOperation::<AB::F>::eval(
+            builder,
+            local.w_i_minus_2_rr_19.value,
+            local.w_i_minus_2_rr_61.value,
+            local.s1_intermediate,
+            local.is_real,
+        );
+        // s1 := (w[i-2] rightrotate 19) xor (w[i-2] rightrotate 61) xor (w[i-2] rightshift 6)
+        Word64XorOperation::<AB::F>::eval(
+            builder,
+            local.s1_intermediate.value,
+            local.w_i_minus_2_rs_6.value,
+            local.s1,
+            local.is_real,
+        );
+
+        // s2 := w[i-16] + s0 + w[i-7] + s1.
+        Word64Add4Operation::<AB::F>::eval(
+            builder,
+            read_value(&local.w_i_minus_16),
+            local.s0.value,
+            read_value(&local.w_i_minus_7),
+            local.s1.value,
+            local.is_real,
+            local.s2,
+        );
+
+        // Write `s2` to `w[i]`.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.clk + (local.i - i_start),
+            local.w_ptr + local.i * nb_bytes_in_word64,
+            &local.w_i,
+            local.is_real,
+        );
+
+        let w_i = Word64::from_words(*local.w_i[0].value(), *local.w_i[1].value());
+        builder.assert_all_eq(w_i, local.s2.value);
+
+        // Receive syscall event in first row of 64-cycle.
+        builder.receive_syscall(
+            local.shard,
+            local.clk,
+            AB::F::from_canonical_u32(SyscallCode::SHA512_EXTEND.syscall_id()),
+            local.w_ptr,
+            AB::Expr::zero(),
+            local.cycle_64_start,
+            InteractionScope::Local,
+        );
+
+        // Assert that is_real is a bool.
+        builder.assert_bool(local.is_real);
+
+        // Ensure that all rows in a 64 row cycle has the same `is_real` values.
+        builder
+            .when_transition()
+            .when_not(local.cycle_64_end)
+            .assert_eq(local.is_real, next.is_real);
+
+        // Assert that a real last row of the table ends a 64-cycle. Since each extend ecall is 64
+        // cycles, a table of only real rows has a power of 2 height, so it may have no padding.
+        builder.when_last_row().assert_eq(local.is_real, local.cycle_64_end);
+    }
+}
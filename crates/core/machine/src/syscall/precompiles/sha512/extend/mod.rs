@@ -0,0 +1,84 @@
+mod air;
+mod columns;
+mod flags;
+mod trace;
+
+pub use columns::*;
+
+/// Implements the SHA-512 extension operation which loops over i = \[16, 79\] and modifies w\[i\]
+/// in each iteration. The only input to the syscall is the 8byte-aligned pointer to the w array of
+/// 64-bit words.
+///
+/// In the AIR, each SHA-512 extend syscall takes up 64 rows, where each row corresponds to a single
+/// iteration of the loop.
+#[derive(Default)]
+pub struct Sha512ExtendChip;
+
+impl Sha512ExtendChip {
+    pub const fn new() -> Self {
+        Self {}
+    }
+}
+
+pub fn sha512_extend(w: &mut [u64]) {
+    for i in 16..80 {
+        let s0 = w[i - 15].rotate_right(1) ^ w[i - 15].rotate_right(8) ^ (w[i - 15] >> 7);
+        let s1 = w[i - 2].rotate_right(19) ^ w[i - 2].rotate_right(61) ^ (w[i - 2] >> 6);
+        w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+    }
+}
+
+#[cfg(all(test, feature = "sha512"))]
+pub mod extend_tests {
+
+    use sp1_core_executor::{syscalls::SyscallCode, Instruction, Opcode, Program};
+    use sp1_stark::CpuProver;
+    use test_artifacts::{SHA512_ELF, SHA512_EXTEND_ELF};
+
+    use crate::{
+        io::SP1Stdin,
+        utils::{self, run_test},
+    };
+
+    pub fn sha512_extend_program() -> Program {
+        let w_ptr = 100;
+        let mut instructions = vec![Instruction::new(Opcode::ADD, 29, 0, 5, false, true)];
+        for i in 0..160 {
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 30, 0, w_ptr + i * 4, false, true),
+                Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+            ]);
+        }
+        instructions.extend(vec![
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::SHA512_EXTEND as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, w_ptr, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 0, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_sha512_extend_prove() {
+        utils::setup_logger();
+        let program = sha512_extend_program();
+        let stdin = SP1Stdin::new();
+        run_test::<CpuProver<_, _>>(program, stdin).unwrap();
+    }
+
+    #[test]
+    fn test_sha512_program() {
+        utils::setup_logger();
+        let program = Program::from(SHA512_ELF).unwrap();
+        let stdin = SP1Stdin::new();
+        run_test::<CpuProver<_, _>>(program, stdin).unwrap();
+    }
+
+    #[test]
+    fn test_sha512_extend_program() {
+        utils::setup_logger();
+        let program = Program::from(SHA512_EXTEND_ELF).unwrap();
+        let stdin = SP1Stdin::new();
+        run_test::<CpuProver<_, _>>(program, stdin).unwrap();
+    }
+}
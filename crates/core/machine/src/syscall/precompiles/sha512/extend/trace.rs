@@ -0,0 +1,149 @@
+use hashbrown::HashMap;
+use itertools::Itertools;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_maybe_rayon::prelude::{ParallelIterator, ParallelSlice};
+use sp1_core_executor::{
+    events::{ByteLookupEvent, ByteRecord, PrecompileEvent, Sha512ExtendEvent},
+    syscalls::SyscallCode,
+    ExecutionRecord, Program,
+};
+use sp1_stark::air::MachineAir;
+use std::borrow::BorrowMut;
+
+use super::{Sha512ExtendChip, Sha512ExtendCols, NUM_SHA512_EXTEND_COLS};
+use crate::syscall::precompiles::sha512::u64_value;
+
+impl<F: PrimeField32> MachineAir<F> for Sha512ExtendChip {
+    type Record = ExecutionRecord;
+
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "Sha512Extend".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        _: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let rows = Vec::new();
+
+        let mut new_byte_lookup_events = Vec::new();
+        let mut wrapped_rows = Some(rows);
+        for (_, event) in input.get_precompile_events(SyscallCode::SHA512_EXTEND).iter() {
+            let event = if let PrecompileEvent::Sha512Extend(event) = event {
+                event
+            } else {
+                unreachable!()
+            };
+            self.event_to_rows(event, &mut wrapped_rows, &mut new_byte_lookup_events);
+        }
+
+        let mut rows = wrapped_rows.unwrap();
+        let nb_rows = rows.len();
+        let mut padded_nb_rows = nb_rows.next_power_of_two();
+        if padded_nb_rows == 2 || padded_nb_rows == 1 {
+            padded_nb_rows = 4;
+        }
+        for i in nb_rows..padded_nb_rows {
+            let mut row = [F::zero(); NUM_SHA512_EXTEND_COLS];
+            let cols: &mut Sha512ExtendCols<F> = row.as_mut_slice().borrow_mut();
+            cols.populate_flags(i);
+            rows.push(row);
+        }
+
+        // Convert the trace to a row major matrix.
+        RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_SHA512_EXTEND_COLS)
+    }
+
+    fn generate_dependencies(&self, input: &Self::Record, output: &mut Self::Record) {
+        let events = input.get_precompile_events(SyscallCode::SHA512_EXTEND);
+        let chunk_size = std::cmp::max(events.len() / num_cpus::get(), 1);
+
+        let blu_batches = events
+            .par_chunks(chunk_size)
+            .map(|events| {
+                let mut blu: HashMap<ByteLookupEvent, usize> = HashMap::new();
+                events.iter().for_each(|(_, event)| {
+                    let event = if let PrecompileEvent::Sha512Extend(event) = event {
+                        event
+                    } else {
+                        unreachable!()
+                    };
+                    self.event_to_rows::<F>(event, &mut None, &mut blu);
+                });
+                blu
+            })
+            .collect::<Vec<_>>();
+
+        output.add_byte_lookup_events_from_maps(blu_batches.iter().collect_vec());
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        if let Some(shape) = shard.shape.as_ref() {
+            shape.included::<F, _>(self)
+        } else {
+            !shard.get_precompile_events(SyscallCode::SHA512_EXTEND).is_empty()
+        }
+    }
+}
+
+impl Sha512ExtendChip {
+    fn event_to_rows<F: PrimeField32>(
+        &self,
+        event: &Sha512ExtendEvent,
+        rows: &mut Option<Vec<[F; NUM_SHA512_EXTEND_COLS]>>,
+        blu: &mut impl ByteRecord,
+    ) {
+        for j in 0..64usize {
+            let mut row = [F::zero(); NUM_SHA512_EXTEND_COLS];
+            let cols: &mut Sha512ExtendCols<F> = row.as_mut_slice().borrow_mut();
+            cols.is_real = F::one();
+            cols.populate_flags(j);
+            cols.shard = F::from_canonical_u32(event.shard);
+            cols.clk = F::from_canonical_u32(event.clk);
+            cols.w_ptr = F::from_canonical_u32(event.w_ptr);
+
+            for k in 0..2 {
+                cols.w_i_minus_15[k].populate(event.w_i_minus_15_reads[j][k], blu);
+                cols.w_i_minus_2[k].populate(event.w_i_minus_2_reads[j][k], blu);
+                cols.w_i_minus_16[k].populate(event.w_i_minus_16_reads[j][k], blu);
+                cols.w_i_minus_7[k].populate(event.w_i_minus_7_reads[j][k], blu);
+            }
+
+            // `s0 := (w[i-15] rightrotate 1) xor (w[i-15] rightrotate 8) xor (w[i-15] rightshift
+            // 7)`.
+            let w_i_minus_15 = u64_value(event.w_i_minus_15_reads[j].map(|record| record.value));
+            let w_i_minus_15_rr_1 = cols.w_i_minus_15_rr_1.populate(blu, w_i_minus_15, 1);
+            let w_i_minus_15_rr_8 = cols.w_i_minus_15_rr_8.populate(blu, w_i_minus_15, 8);
+            let w_i_minus_15_rs_7 = cols.w_i_minus_15_rs_7.populate(blu, w_i_minus_15, 7);
+            let s0_intermediate =
+                cols.s0_intermediate.populate(blu, w_i_minus_15_rr_1, w_i_minus_15_rr_8);
+            let s0 = cols.s0.populate(blu, s0_intermediate, w_i_minus_15_rs_7);
+
+            // `s1 := (w[i-2] rightrotate 19) xor (w[i-2] rightrotate 61) xor (w[i-2] rightshift
+            // 6)`.
+            let w_i_minus_2 = u64_value(event.w_i_minus_2_reads[j].map(|record| record.value));
+            let w_i_minus_2_rr_19 = cols.w_i_minus_2_rr_19.populate(blu, w_i_minus_2, 19);
+            let w_i_minus_2_rr_61 = cols.w_i_minus_2_rr_61.populate(blu, w_i_minus_2, 61);
+            let w_i_minus_2_rs_6 = cols.w_i_minus_2_rs_6.populate(blu, w_i_minus_2, 6);
+            let s1_intermediate =
+                cols.s1_intermediate.populate(blu, w_i_minus_2_rr_19, w_i_minus_2_rr_61);
+            let s1 = cols.s1.populate(blu, s1_intermediate, w_i_minus_2_rs_6);
+
+            // Compute `s2`.
+            let w_i_minus_7 = u64_value(event.w_i_minus_7_reads[j].map(|record| record.value));
+            let w_i_minus_16 = u64_value(event.w_i_minus_16_reads[j].map(|record| record.value));
+            cols.s2.populate(blu, w_i_minus_16, s0, w_i_minus_7, s1);
+
+            cols.w_i[0].populate(event.w_i_writes[j][0], blu);
+            cols.w_i[1].populate(event.w_i_writes[j][1], blu);
+
+            if rows.as_ref().is_some() {
+                rows.as_mut().unwrap().push(row);
+            }
+        }
+    }
+}
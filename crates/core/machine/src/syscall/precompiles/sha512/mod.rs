@@ -0,0 +1,10 @@
+mod compress;
+mod extend;
+
+pub use compress::*;
+pub use extend::*;
+
+/// Joins the low and the high 32-bit words of a 64-bit word.
+pub(crate) fn u64_value([lo, hi]: [u32; 2]) -> u64 {
+    u64::from(lo) | (u64::from(hi) << 32)
+}
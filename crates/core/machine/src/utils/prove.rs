@@ -1,13 +1,16 @@
 use p3_matrix::dense::RowMajorMatrix;
 use std::{
+    collections::BTreeMap,
     fs::File,
     io::{self, Seek, SeekFrom},
     str::FromStr,
     sync::{
+        atomic::{AtomicUsize, Ordering},
         mpsc::{channel, sync_channel, Sender},
         Arc, Mutex,
     },
     thread::ScopedJoinHandle,
+    time::Duration,
 };
 use web_time::Instant;
 
@@ -22,10 +25,7 @@ use thiserror::Error;
 use p3_field::PrimeField32;
 use sp1_stark::air::MachineAir;
 
-use crate::{
-    io::SP1Stdin,
-    utils::{chunk_vec, concurrency::TurnBasedSync},
-};
+use crate::{io::SP1Stdin, utils::concurrency::TurnBasedSync};
 use sp1_core_executor::{
     events::{format_table_line, sorted_table_lines},
     ExecutionState, RiscvAirId,
@@ -33,11 +33,12 @@ use sp1_core_executor::{
 
 use sp1_core_executor::{
     subproof::NoOpSubproofVerifier, ExecutionError, ExecutionRecord, ExecutionReport, Executor,
-    Program, SP1Context,
+    HookRegistry, Program, SP1Context,
 };
 use sp1_stark::{
-    air::PublicValues, shape::OrderedShape, Com, MachineProof, MachineProver, MachineRecord,
-    OpeningProof, PcsProverData, SP1CoreOpts, ShardProof, StarkGenericConfig, Val,
+    air::PublicValues, resident_memory, shape::OrderedShape, Com, MachineProof, MachineProver,
+    MachineRecord, NoopObserver, OpeningProof, PcsProverData, ProverObserver, ProvingReport,
+    SP1CoreOpts, ShardProof, ShardTiming, StarkGenericConfig, Val,
 };
 
 #[allow(clippy::too_many_arguments)]
@@ -61,7 +62,7 @@ where
 {
     let (proof_tx, proof_rx) = channel();
     let (shape_tx, shape_rx) = channel();
-    let (public_values, cycles) = prove_core_stream(
+    let (public_values, cycles, _) = prove_core_stream(
         prover,
         pk,
         program,
@@ -71,6 +72,7 @@ where
         shape_config,
         proof_tx,
         shape_tx,
+        &NoopObserver,
         malicious_trace_pv_generator,
     )?;
 
@@ -92,8 +94,9 @@ pub fn prove_core_stream<SC: StarkGenericConfig, P: MachineProver<SC, RiscvAir<S
     shape_config: Option<&CoreShapeConfig<SC::Val>>,
     proof_tx: Sender<ShardProof<SC>>,
     shape_and_done_tx: Sender<(OrderedShape, bool)>,
+    observer: &dyn ProverObserver,
     malicious_trace_pv_generator: Option<MaliciousTracePVGeneratorType<SC::Val, P>>, // This is used for failure test cases that generate malicious traces and public values.
-) -> Result<(Vec<u8>, u64), SP1CoreProverError>
+) -> Result<(Vec<u8>, u64, ProvingReport), SP1CoreProverError>
 where
     SC::Val: PrimeField32,
     SC::Challenger: 'static + Clone + Send,
@@ -107,10 +110,9 @@ where
         config.maximal_core_shapes(opts.shard_size.ilog2() as usize).into_iter().collect()
     });
     runtime.write_vecs(&stdin.buffer);
-    for proof in stdin.proofs.iter() {
-        let (proof, vk) = proof.clone();
-        runtime.write_proof(proof, vk);
-    }
+    stdin.write_proof_stream(&mut runtime);
+    // The hooks are invoked again when the checkpoints are traced.
+    let hook_registry = runtime.hook_registry.clone();
 
     #[cfg(feature = "debug")]
     let (all_records_tx, all_records_rx) = std::sync::mpsc::channel::<Vec<ExecutionRecord>>();
@@ -175,10 +177,11 @@ where
         let p2_record_gen_sync = Arc::new(TurnBasedSync::new());
         let p2_trace_gen_sync = Arc::new(TurnBasedSync::new());
         let (p2_records_and_traces_tx, p2_records_and_traces_rx) =
-            sync_channel::<(Vec<ExecutionRecord>, Vec<Vec<(String, RowMajorMatrix<Val<SC>>)>>)>(
-                opts.records_and_traces_channel_capacity,
+            sync_channel::<(usize, ExecutionRecord, Vec<(String, RowMajorMatrix<Val<SC>>)>)>(
+                opts.records_and_traces_channel_capacity * opts.shard_batch_size.max(1),
             );
         let p2_records_and_traces_tx = Arc::new(Mutex::new(p2_records_and_traces_tx));
+        let next_shard = Arc::new(AtomicUsize::new(0));
 
         let shape_tx = Arc::new(Mutex::new(shape_and_done_tx));
         let report_aggregate = Arc::new(Mutex::new(ExecutionReport::default()));
@@ -190,6 +193,7 @@ where
             let record_gen_sync = Arc::clone(&p2_record_gen_sync);
             let trace_gen_sync = Arc::clone(&p2_trace_gen_sync);
             let records_and_traces_tx = Arc::clone(&p2_records_and_traces_tx);
+            let next_shard = Arc::clone(&next_shard);
             let checkpoints_rx = Arc::clone(&checkpoints_rx);
 
             let shape_tx = Arc::clone(&shape_tx);
@@ -197,6 +201,7 @@ where
             let state = Arc::clone(&state);
             let deferred = Arc::clone(&deferred);
             let program = program.clone();
+            let hook_registry = hook_registry.clone();
             let span = tracing::Span::current().clone();
 
             #[cfg(feature = "debug")]
@@ -215,6 +220,7 @@ where
                                         &checkpoint,
                                         opts,
                                         shape_config,
+                                        hook_registry.clone(),
                                     )
                                 });
 
@@ -342,19 +348,16 @@ where
 
                             trace_gen_sync.wait_for_turn(index);
 
-                            // Send the records to the phase 2 prover.
-                            let chunked_records = chunk_vec(records, opts.shard_batch_size);
-                            let chunked_main_traces = chunk_vec(main_traces, opts.shard_batch_size);
-                            chunked_records
-                                .into_iter()
-                                .zip(chunked_main_traces.into_iter())
-                                .for_each(|(records, main_traces)| {
-                                    records_and_traces_tx
-                                        .lock()
-                                        .unwrap()
-                                        .send((records, main_traces))
-                                        .unwrap();
-                                });
+                            // Send the records to the phase 2 provers, numbering the shards in
+                            // the order of the turns.
+                            for (record, main_traces) in records.into_iter().zip(main_traces) {
+                                let shard = next_shard.fetch_add(1, Ordering::Relaxed);
+                                records_and_traces_tx
+                                    .lock()
+                                    .unwrap()
+                                    .send((shard, record, main_traces))
+                                    .unwrap();
+                            }
 
                             trace_gen_sync.advance_turn();
                         } else {
@@ -369,59 +372,63 @@ where
         #[cfg(feature = "debug")]
         drop(all_records_tx);
 
-        // Spawn the phase 2 prover thread.
-        let p2_prover_span = tracing::Span::current().clone();
-        let proof_tx = Arc::new(Mutex::new(proof_tx));
-        let p2_prover_handle = s.spawn(move || {
-            let _span = p2_prover_span.enter();
-            tracing::debug_span!("phase 2 prover").in_scope(|| {
-                for (records, traces) in p2_records_and_traces_rx.into_iter() {
-                    tracing::debug_span!("batch").in_scope(|| {
-                        let span = tracing::Span::current().clone();
-                        let proofs = records
-                            .into_par_iter()
-                            .zip(traces.into_par_iter())
-                            .map(|(record, main_traces)| {
-                                let _span = span.enter();
-
-                                let main_data = prover.commit(&record, main_traces);
-
-                                let opening_span = tracing::debug_span!("opening").entered();
-                                let proof =
-                                    prover.open(pk, main_data, &mut challenger.clone()).unwrap();
-                                opening_span.exit();
-
-                                #[cfg(debug_assertions)]
-                                {
-                                    if let Some(shape) = record.shape.as_ref() {
-                                        assert_eq!(
-                                            proof.shape(),
-                                            shape
-                                                .clone()
-                                                .into_iter()
-                                                .map(|(k, v)| (k.to_string(), v as usize))
-                                                .collect(),
-                                        );
-                                    }
-                                }
+        // Spawn the phase 2 provers. Each shard is proven by the first idle prover, so that a slow
+        // shard does not hold up the others, and the proofs are sent in shard order.
+        let p2_records_and_traces_rx = Arc::new(Mutex::new(p2_records_and_traces_rx));
+        let shard_proofs = Arc::new(Mutex::new(ShardProofs::new(proof_tx)));
+        let mut p2_prover_handles = Vec::new();
+        for _ in 0..opts.shard_concurrency.max(1) {
+            let records_and_traces_rx = Arc::clone(&p2_records_and_traces_rx);
+            let shard_proofs = Arc::clone(&shard_proofs);
+            let challenger = challenger.clone();
+            let span = tracing::Span::current().clone();
 
-                                rayon::spawn(move || {
-                                    drop(record);
-                                });
+            let handle = s.spawn(move || {
+                let _span = span.enter();
+                tracing::debug_span!("phase 2 prover").in_scope(|| loop {
+                    let received = { records_and_traces_rx.lock().unwrap().recv() };
+                    let Ok((shard, record, main_traces)) = received else {
+                        break;
+                    };
+
+                    let start = Instant::now();
+                    let proof = tracing::debug_span!("shard", shard).in_scope(|| {
+                        let main_data = prover.commit(&record, main_traces);
+
+                        let opening_span = tracing::debug_span!("opening").entered();
+                        let proof = prover.open(pk, main_data, &mut challenger.clone()).unwrap();
+                        opening_span.exit();
+
+                        #[cfg(debug_assertions)]
+                        {
+                            if let Some(shape) = record.shape.as_ref() {
+                                assert_eq!(
+                                    proof.shape(),
+                                    shape
+                                        .clone()
+                                        .into_iter()
+                                        .map(|(k, v)| (k.to_string(), v as usize))
+                                        .collect(),
+                                );
+                            }
+                        }
 
-                                proof
-                            })
-                            .collect::<Vec<_>>();
+                        proof
+                    });
+                    let timing = ShardTiming { shard, prove_time: start.elapsed() };
 
-                        // Send the batch of proofs to the channel.
-                        let proof_tx = proof_tx.lock().unwrap();
-                        for proof in proofs {
-                            proof_tx.send(proof).unwrap();
-                        }
+                    rayon::spawn(move || {
+                        drop(record);
                     });
-                }
+
+                    let memory = resident_memory();
+                    let num_proven = shard_proofs.lock().unwrap().insert(proof, timing, memory);
+                    observer.on_shard_proven(&timing, num_proven);
+                });
             });
-        });
+            p2_prover_handles.push(handle);
+        }
+        drop(p2_records_and_traces_rx);
 
         // Wait until the checkpoint generator handle has fully finished.
         let public_values_stream = checkpoint_generator_handle.join().unwrap().unwrap();
@@ -429,8 +436,13 @@ where
         // Wait until the records and traces have been fully generated for phase 2.
         p2_record_and_trace_gen_handles.into_iter().for_each(|handle| handle.join().unwrap());
 
-        // Wait until the phase 2 prover has finished.
-        p2_prover_handle.join().unwrap();
+        // Wait until the phase 2 provers have finished.
+        p2_prover_handles.into_iter().for_each(|handle| handle.join().unwrap());
+        let proving_report = Arc::into_inner(shard_proofs)
+            .unwrap()
+            .into_inner()
+            .unwrap()
+            .into_report(proving_start.elapsed());
 
         // Log some of the `ExecutionReport` information.
         let report_aggregate = report_aggregate.lock().unwrap();
@@ -440,6 +452,12 @@ where
             report_aggregate.total_syscall_count(),
             report_aggregate.touched_memory_addresses,
         );
+        tracing::info!(
+            "execution report (memory): touched_pages={}, peak_heap_bytes={}, total_mem_ops={}",
+            report_aggregate.memory_stats.touched_pages,
+            report_aggregate.memory_stats.peak_heap_bytes,
+            report_aggregate.memory_stats.total_mem_ops,
+        );
 
         // Print the opcode and syscall count tables like `du`: sorted by count (descending) and
         // with the count in the first column.
@@ -473,6 +491,13 @@ where
             proving_time,
             (cycles as f64 / (proving_time * 1000.0) as f64),
         );
+        tracing::info!(
+            "shards: proven={}, concurrency={}, peak_memory={}, utilization={:.2}",
+            proving_report.shard_timings.len(),
+            opts.shard_concurrency,
+            proving_report.peak_memory,
+            proving_report.utilization(opts.shard_concurrency),
+        );
 
         #[cfg(feature = "debug")]
         {
@@ -482,32 +507,75 @@ where
             prover.machine().debug_constraints(&pk_host, all_records, &mut challenger);
         }
 
-        Ok((public_values_stream, cycles))
+        Ok((public_values_stream, cycles, proving_report))
     })
 }
 
+/// The proofs of the shards proven so far, which are sent in shard order while the shards are
+/// proven in any order.
+struct ShardProofs<SC: StarkGenericConfig> {
+    proof_tx: Sender<ShardProof<SC>>,
+    /// The proofs waiting for the proofs of the previous shards to be sent.
+    pending: BTreeMap<usize, ShardProof<SC>>,
+    /// The index of the next shard whose proof is to be sent.
+    next_shard: usize,
+    timings: Vec<ShardTiming>,
+    peak_memory: u64,
+}
+
+impl<SC: StarkGenericConfig> ShardProofs<SC> {
+    fn new(proof_tx: Sender<ShardProof<SC>>) -> Self {
+        Self {
+            proof_tx,
+            pending: BTreeMap::new(),
+            next_shard: 0,
+            timings: Vec::new(),
+            peak_memory: 0,
+        }
+    }
+
+    /// Adds the proof of a shard, sending the proofs that are next in shard order, and returns the
+    /// number of shards proven so far.
+    fn insert(&mut self, proof: ShardProof<SC>, timing: ShardTiming, memory: u64) -> usize {
+        self.pending.insert(timing.shard, proof);
+        while let Some(proof) = self.pending.remove(&self.next_shard) {
+            self.proof_tx.send(proof).unwrap();
+            self.next_shard += 1;
+        }
+        self.timings.push(timing);
+        self.peak_memory = self.peak_memory.max(memory);
+        self.timings.len()
+    }
+
+    fn into_report(mut self, total_time: Duration) -> ProvingReport {
+        assert!(self.pending.is_empty(), "the proofs of some shards were not sent");
+        self.timings.sort_unstable_by_key(|timing| timing.shard);
+        ProvingReport { shard_timings: self.timings, peak_memory: self.peak_memory, total_time }
+    }
+}
+
 pub fn trace_checkpoint<SC: StarkGenericConfig>(
     program: Program,
     file: &File,
     opts: SP1CoreOpts,
     shape_config: Option<&CoreShapeConfig<SC::Val>>,
+    hook_registry: HookRegistry<'_>,
 ) -> (Vec<ExecutionRecord>, ExecutionReport)
 where
     <SC as StarkGenericConfig>::Val: PrimeField32,
 {
-    let noop = NoOpSubproofVerifier;
-
     let mut reader = std::io::BufReader::new(file);
     let state: ExecutionState =
         bincode::deserialize_from(&mut reader).expect("failed to deserialize state");
     let mut runtime = Executor::recover(program, state, opts);
+    runtime.hook_registry = hook_registry;
     runtime.maximal_shapes = shape_config.map(|config| {
         config.maximal_core_shapes(opts.shard_size.ilog2() as usize).into_iter().collect()
     });
 
     // We already passed the deferred proof verifier when creating checkpoints, so the proofs were
     // already verified. So here we use a noop verifier to not print any warnings.
-    runtime.subproof_verifier = Some(&noop);
+    runtime.subproof_verifier = Some(&NoOpSubproofVerifier);
 
     // Execute from the checkpoint.
     let (records, _) = runtime.execute_record(true).unwrap();
@@ -170,6 +170,20 @@ pub fn machine_air_derive(input: TokenStream) -> TokenStream {
                 }
             });
 
+            let generate_trace_into_arms = variants.iter().map(|(variant_name, field)| {
+                let field_ty = &field.ty;
+                quote! {
+                    #name::#variant_name(x) => <#field_ty as sp1_stark::air::MachineAir<F>>::generate_trace_into(x, input, output, target)
+                }
+            });
+
+            let generate_trace_col_major_arms = variants.iter().map(|(variant_name, field)| {
+                let field_ty = &field.ty;
+                quote! {
+                    #name::#variant_name(x) => <#field_ty as sp1_stark::air::MachineAir<F>>::generate_trace_col_major(x, input, output)
+                }
+            });
+
             let generate_dependencies_arms = variants.iter().map(|(variant_name, field)| {
                 let field_ty = &field.ty;
                 quote! {
@@ -184,6 +198,20 @@ pub fn machine_air_derive(input: TokenStream) -> TokenStream {
                 }
             });
 
+            let is_empty_shard_arms = variants.iter().map(|(variant_name, field)| {
+                let field_ty = &field.ty;
+                quote! {
+                    #name::#variant_name(x) => <#field_ty as sp1_stark::air::MachineAir<F>>::is_empty_shard(x, shard)
+                }
+            });
+
+            let is_empty_program_arms = variants.iter().map(|(variant_name, field)| {
+                let field_ty = &field.ty;
+                quote! {
+                    #name::#variant_name(x) => <#field_ty as sp1_stark::air::MachineAir<F>>::is_empty_program(x, program)
+                }
+            });
+
             let commit_scope_arms = variants.iter().map(|(variant_name, field)| {
                 let field_ty = &field.ty;
                 quote! {
@@ -198,6 +226,13 @@ pub fn machine_air_derive(input: TokenStream) -> TokenStream {
                 }
             });
 
+            let num_public_values_arms = variants.iter().map(|(variant_name, field)| {
+                let field_ty = &field.ty;
+                quote! {
+                    #name::#variant_name(x) => <#field_ty as sp1_stark::air::MachineAir<F>>::num_public_values(x)
+                }
+            });
+
             let machine_air = quote! {
                 impl #impl_generics sp1_stark::air::MachineAir<F> for #name #ty_generics #where_clause {
                     type Record = #execution_record_path;
@@ -235,6 +270,27 @@ pub fn machine_air_derive(input: TokenStream) -> TokenStream {
                         }
                     }
 
+                    fn generate_trace_into(
+                        &self,
+                        input: &#execution_record_path,
+                        output: &mut #execution_record_path,
+                        target: &mut sp1_stark::TraceBuffer<F>,
+                    ) {
+                        match self {
+                            #(#generate_trace_into_arms,)*
+                        }
+                    }
+
+                    fn generate_trace_col_major(
+                        &self,
+                        input: &#execution_record_path,
+                        output: &mut #execution_record_path,
+                    ) -> sp1_stark::ColMajorMatrix<F> {
+                        match self {
+                            #(#generate_trace_col_major_arms,)*
+                        }
+                    }
+
                     fn generate_dependencies(
                         &self,
                         input: &#execution_record_path,
@@ -251,6 +307,18 @@ pub fn machine_air_derive(input: TokenStream) -> TokenStream {
                         }
                     }
 
+                    fn is_empty_shard(&self, shard: &Self::Record) -> bool {
+                        match self {
+                            #(#is_empty_shard_arms,)*
+                        }
+                    }
+
+                    fn is_empty_program(&self, program: &Self::Program) -> bool {
+                        match self {
+                            #(#is_empty_program_arms,)*
+                        }
+                    }
+
                     fn commit_scope(&self) -> InteractionScope {
                         match self {
                             #(#commit_scope_arms,)*
@@ -262,6 +330,12 @@ pub fn machine_air_derive(input: TokenStream) -> TokenStream {
                             #(#local_only_arms,)*
                         }
                     }
+
+                    fn num_public_values(&self) -> usize {
+                        match self {
+                            #(#num_public_values_arms,)*
+                        }
+                    }
                 }
             };
 
@@ -6,7 +6,7 @@ use sp1_cuda::SP1CudaProver;
 use sp1_prover::HashableKey;
 use sp1_prover::{components::CpuProverComponents, ProverMode};
 use sp1_sdk::{self, Prover, ProverClient, SP1Context, SP1Prover, SP1Stdin};
-use sp1_stark::SP1ProverOpts;
+use sp1_stark::{NoopObserver, SP1ProverOpts};
 use test_artifacts::VERIFY_PROOF_ELF;
 
 #[derive(Parser, Clone)]
@@ -40,6 +40,8 @@ struct PerfResult {
     pub verify_shrink_duration: Duration,
     pub wrap_duration: Duration,
     pub verify_wrap_duration: Duration,
+    pub shard_utilization: f64,
+    pub peak_memory: u64,
 }
 
 pub fn time_operation<T, F: FnOnce() -> T>(operation: F) -> (T, std::time::Duration) {
@@ -69,8 +71,10 @@ fn main() {
 
             let cycles = report.expect("execution failed").1.total_instruction_count();
 
-            let (core_proof, prove_core_duration) = time_operation(|| {
-                prover.prove_core(&pk_d, program, &stdin, opts, context).unwrap()
+            let ((core_proof, proving_report), prove_core_duration) = time_operation(|| {
+                prover
+                    .prove_core_with_observer(&pk_d, program, &stdin, opts, context, &NoopObserver)
+                    .unwrap()
             });
 
             let (_, verify_core_duration) =
@@ -133,6 +137,8 @@ fn main() {
                 verify_shrink_duration,
                 wrap_duration,
                 verify_wrap_duration,
+                shard_utilization: proving_report.utilization(opts.core_opts.shard_concurrency),
+                peak_memory: proving_report.peak_memory,
             };
 
             println!("{:?}", result);
@@ -0,0 +1,94 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use clap::{command, Parser};
+use sp1_core_executor::{Executor, Program};
+use sp1_sdk::SP1Stdin;
+use sp1_stark::SP1CoreOpts;
+
+/// An allocator that keeps track of the peak number of allocated bytes.
+struct PeakAlloc {
+    current: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl PeakAlloc {
+    /// Resets the peak to the number of bytes currently allocated.
+    fn reset_peak(&self) {
+        self.peak.store(self.current.load(Ordering::SeqCst), Ordering::SeqCst);
+    }
+
+    /// The peak number of bytes allocated on top of the given baseline.
+    fn peak_above(&self, baseline: usize) -> usize {
+        self.peak.load(Ordering::SeqCst).saturating_sub(baseline)
+    }
+}
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let current = self.current.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            self.peak.fetch_max(current, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        self.current.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static ALLOC: PeakAlloc = PeakAlloc { current: AtomicUsize::new(0), peak: AtomicUsize::new(0) };
+
+#[derive(Parser, Clone)]
+#[command(about = "Measure the memory used to write a large input to the stdin of a program.")]
+struct PerfArgs {
+    /// The size of the input, in MiB.
+    #[arg(short, long, default_value_t = 1024)]
+    pub size_mib: usize,
+}
+
+/// Measures the peak memory allocated on top of the input while it is written to the stdin,
+/// encoded for upload and handed to the executor.
+fn measure(input: &Arc<[u8]>, write: impl FnOnce(&mut SP1Stdin, &Arc<[u8]>)) -> [usize; 3] {
+    let baseline = ALLOC.current.load(Ordering::SeqCst);
+    ALLOC.reset_peak();
+
+    let mut stdin = SP1Stdin::new();
+    write(&mut stdin, input);
+    let written = ALLOC.peak_above(baseline);
+
+    let segments = stdin.encoded_segments();
+    let encoded = ALLOC.peak_above(baseline);
+    drop(segments);
+
+    let mut executor = Executor::new(Program::new(vec![], 0, 0), SP1CoreOpts::default());
+    executor.write_vecs(&stdin.buffer);
+    let executed = ALLOC.peak_above(baseline);
+
+    [written, encoded, executed]
+}
+
+fn main() {
+    let args = PerfArgs::parse();
+    let input: Arc<[u8]> = (0..args.size_mib << 20).map(|i| i as u8).collect();
+
+    let copied = measure(&input, |stdin, input| stdin.write_slice(input));
+    let shared = measure(&input, |stdin, input| stdin.write_shared(input.clone()));
+
+    let mib = |bytes: usize| bytes as f64 / f64::from(1 << 20);
+    println!("peak memory above the {} MiB input:", args.size_mib);
+    for (name, [written, encoded, executed]) in [("write_slice", copied), ("write_shared", shared)]
+    {
+        println!(
+            "{name}: written {:.1} MiB, encoded {:.1} MiB, executor {:.1} MiB",
+            mib(written),
+            mib(encoded),
+            mib(executed)
+        );
+    }
+}
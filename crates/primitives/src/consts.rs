@@ -13,6 +13,15 @@ pub const LONG_WORD_SIZE: usize = 2 * WORD_SIZE;
 /// The Baby Bear prime.
 pub const BABYBEAR_PRIME: u32 = 0x78000001;
 
+/// The exit code the program halts with after reporting a panic to [`fd::FD_PANIC`].
+///
+/// The report is a header of the line, column, file length and message length of the panic, as
+/// little-endian `u32`s, followed by the file and the message.
+pub const PANIC_EXIT_CODE: u8 = 101;
+
+/// The maximum length of the message of a panic reported to [`fd::FD_PANIC`], in bytes.
+pub const MAX_PANIC_MESSAGE_BYTES: usize = 4096;
+
 pub mod fd {
     /// The minimum file descriptor.
     ///
@@ -55,6 +64,9 @@ pub mod fd {
 
         /// The file descriptor through which to access `hook_bls12_381_inverse`.
         pub const FD_BLS12_381_INVERSE: u32 = 9;
+
+        /// The file descriptor to which the program reports a panic before halting.
+        pub const FD_PANIC: u32 = 10;
     }
 }
 
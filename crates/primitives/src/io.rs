@@ -86,6 +86,131 @@ impl AsRef<[u8]> for SP1PublicValues {
     }
 }
 
+/// Hashes the serialization of a value with SHA-256, without buffering the serialized bytes.
+pub fn hash_serialized<T: Serialize>(value: &T) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    bincode::serialize_into(&mut hasher, value).expect("serialization failed");
+    hasher.finalize().into()
+}
+
+/// The bytes starting the inputs written with [`TypedHint::encode`].
+pub const TYPED_HINT_MAGIC: [u8; 4] = *b"SP1H";
+
+/// An input tagged with the type of the value it holds.
+///
+/// Typed hints are written with `SP1Stdin::write_hint` and read with `sp1_zkvm::io::read_hint`,
+/// which checks that the program reads the value with the type it was written with. The types are
+/// compared by name, so a type must be named the same on both sides, e.g. by defining it in a
+/// crate shared by the host and the program.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypedHint {
+    /// The tag of the type of the value, see [`type_tag`].
+    pub tag: u64,
+    /// The name of the type of the value, reported when the types do not match.
+    pub type_name: String,
+    /// The bincode encoding of the value.
+    pub payload: Vec<u8>,
+}
+
+impl TypedHint {
+    /// Create a typed hint holding `value`.
+    pub fn new<T: Serialize>(value: &T) -> Self {
+        let type_name = core::any::type_name::<T>();
+        Self {
+            tag: type_tag(type_name),
+            type_name: type_name.to_string(),
+            payload: bincode::serialize(value).expect("serialization failed"),
+        }
+    }
+
+    /// Encode the hint as an input: [`TYPED_HINT_MAGIC`], followed by the bincode encoding of the
+    /// hint, in which the type name and the payload are prefixed by their length.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = TYPED_HINT_MAGIC.to_vec();
+        bincode::serialize_into(&mut bytes, self).expect("serialization failed");
+        bytes
+    }
+
+    /// Decode an input encoded with [`TypedHint::encode`], or return `None` if it is not a typed
+    /// hint.
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        let encoded = bytes.strip_prefix(&TYPED_HINT_MAGIC)?;
+        bincode::deserialize(encoded).ok()
+    }
+
+    /// Deserialize the value of the hint, checking that it was written as a `T`.
+    pub fn value<T: DeserializeOwned>(&self) -> Result<T, TypedHintError> {
+        let expected = core::any::type_name::<T>();
+        if self.tag != type_tag(expected) {
+            return Err(TypedHintError::TypeMismatch {
+                expected: expected.to_string(),
+                found: self.type_name.clone(),
+            });
+        }
+        bincode::deserialize(&self.payload).map_err(|_| TypedHintError::Deserialize)
+    }
+}
+
+/// Errors that [`TypedHint::value`] can return.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypedHintError {
+    /// The hint was written with another type than the one it is read with.
+    TypeMismatch { expected: String, found: String },
+    /// The payload of the hint is not a valid encoding of its type.
+    Deserialize,
+}
+
+/// The bytes starting the inputs written with [`NamedInput::encode`].
+pub const NAMED_INPUT_MAGIC: [u8; 4] = *b"SP1N";
+
+/// An input tagged with a name and the type of the value it holds.
+///
+/// Named inputs are written with `SP1Stdin::write_named` and read with `sp1_zkvm::io::read_named`,
+/// which checks that the program reads the inputs under the names and types, and so in the order,
+/// they were written with. Unnamed inputs are not affected and can be interleaved with them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamedInput {
+    /// The name of the input.
+    pub name: String,
+    /// The value of the input, tagged with its type.
+    pub hint: TypedHint,
+}
+
+impl NamedInput {
+    /// Create a named input holding `value`.
+    pub fn new<T: Serialize>(name: &str, value: &T) -> Self {
+        Self { name: name.to_string(), hint: TypedHint::new(value) }
+    }
+
+    /// Encode the input: [`NAMED_INPUT_MAGIC`], followed by the bincode encoding of the input, in
+    /// which the name, the type name and the payload are prefixed by their length.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = NAMED_INPUT_MAGIC.to_vec();
+        bincode::serialize_into(&mut bytes, self).expect("serialization failed");
+        bytes
+    }
+
+    /// Decode an input encoded with [`NamedInput::encode`], or return `None` if it is not a named
+    /// input.
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        let encoded = bytes.strip_prefix(&NAMED_INPUT_MAGIC)?;
+        bincode::deserialize(encoded).ok()
+    }
+}
+
+/// The tag of a type in a [`TypedHint`]: the 64-bit FNV-1a hash of its name.
+pub const fn type_tag(type_name: &str) -> u64 {
+    let bytes = type_name.as_bytes();
+    let mut hash = 0xcbf29ce484222325u64;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+        i += 1;
+    }
+    hash
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -104,4 +229,56 @@ mod tests {
 
         assert_eq!(hash, expected_hash_biguint);
     }
+
+    #[test]
+    fn test_hash_serialized() {
+        let value = (1u32, vec![2u8, 3], "four".to_string());
+        let expected: [u8; 32] = Sha256::digest(bincode::serialize(&value).unwrap()).into();
+        assert_eq!(hash_serialized(&value), expected);
+        assert_ne!(hash_serialized(&value), hash_serialized(&(1u32, vec![2u8], "four")));
+    }
+
+    #[test]
+    fn test_typed_hint() {
+        let value = (7u64, "seven".to_string());
+        let encoded = TypedHint::new(&value).encode();
+        assert!(encoded.starts_with(&TYPED_HINT_MAGIC));
+
+        let hint = TypedHint::decode(&encoded).unwrap();
+        assert_eq!(hint.type_name, "(u64, alloc::string::String)");
+        assert_eq!(hint.value::<(u64, String)>(), Ok(value));
+        assert_eq!(
+            hint.value::<(u64, u64)>(),
+            Err(TypedHintError::TypeMismatch {
+                expected: "(u64, u64)".to_string(),
+                found: "(u64, alloc::string::String)".to_string(),
+            })
+        );
+
+        // Untagged inputs are not typed hints.
+        assert_eq!(TypedHint::decode(&bincode::serialize(&value).unwrap()), None);
+        assert_eq!(TypedHint::decode(&[]), None);
+    }
+
+    #[test]
+    fn test_named_input() {
+        let encoded = NamedInput::new("header", &5u32).encode();
+        assert!(encoded.starts_with(&NAMED_INPUT_MAGIC));
+
+        let input = NamedInput::decode(&encoded).unwrap();
+        assert_eq!(input.name, "header");
+        assert_eq!(input.hint.value::<u32>(), Ok(5));
+
+        // Neither typed hints nor unnamed inputs are named inputs.
+        assert_eq!(NamedInput::decode(&TypedHint::new(&5u32).encode()), None);
+        assert_eq!(NamedInput::decode(&bincode::serialize(&5u32).unwrap()), None);
+    }
+
+    #[test]
+    fn test_type_tag() {
+        // The FNV-1a test vectors.
+        assert_eq!(type_tag(""), 0xcbf29ce484222325);
+        assert_eq!(type_tag("a"), 0xaf63dc4c8601ec8c);
+        assert_ne!(type_tag("u32"), type_tag("u64"));
+    }
 }
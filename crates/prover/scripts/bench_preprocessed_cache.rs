@@ -0,0 +1,54 @@
+//! Compares the setup time of a batch of compress proofs with and without the cache of
+//! preprocessed traces.
+
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use p3_baby_bear::BabyBear;
+use sp1_core_machine::utils::setup_logger;
+use sp1_prover::{components::CpuProverComponents, CompressAir, InnerSC, SP1Prover};
+use sp1_recursion_core::RecursionProgram;
+use sp1_stark::StarkMachine;
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// The number of compress proofs in the batch.
+    #[clap(short, long, default_value_t = 16)]
+    batch_size: usize,
+}
+
+/// Sets up the program once per proof in the batch, returning the total time.
+fn time_setups(
+    machine: &StarkMachine<InnerSC, CompressAir<BabyBear>>,
+    program: &RecursionProgram<BabyBear>,
+    batch_size: usize,
+) -> Duration {
+    let start = Instant::now();
+    for _ in 0..batch_size {
+        let _ = machine.setup(program);
+    }
+    start.elapsed()
+}
+
+fn main() {
+    setup_logger();
+    let args = Args::parse();
+
+    let prover = SP1Prover::<CpuProverComponents>::new();
+    let program = prover
+        .join_programs_map
+        .values()
+        .next()
+        .expect("no compress programs, make sure SP1_DISABLE_PROGRAM_CACHE is not set")
+        .clone();
+
+    let uncached = CompressAir::compress_machine(InnerSC::default());
+    let cached = CompressAir::compress_machine(InnerSC::default()).with_preprocessed_cache(1);
+
+    let uncached_time = time_setups(&uncached, &program, args.batch_size);
+    let cached_time = time_setups(&cached, &program, args.batch_size);
+    println!("setup of {} compress proofs without cache: {:?}", args.batch_size, uncached_time);
+    println!("setup of {} compress proofs with cache: {:?}", args.batch_size, cached_time);
+    println!("speedup: {:.2}x", uncached_time.as_secs_f64() / cached_time.as_secs_f64());
+}
@@ -0,0 +1,24 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use sp1_core_machine::utils::setup_logger;
+use sp1_prover::artifacts::ArtifactManifest;
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// The directory of the released artifacts, with the artifacts of each kind in a
+    /// subdirectory named after it.
+    #[clap(short, long)]
+    build_dir: PathBuf,
+    #[clap(short, long, default_value = "crates/prover/circuit_artifacts.json")]
+    output: PathBuf,
+}
+
+pub fn main() {
+    setup_logger();
+    let args = Args::parse();
+    let manifest = ArtifactManifest::pin(&args.build_dir).unwrap();
+    let json = serde_json::to_string_pretty(&manifest).unwrap();
+    std::fs::write(&args.output, json + "\n").unwrap();
+}
@@ -58,9 +58,10 @@ fn main() {
 
         tracing::info!("proving leaves");
         let stdin = SP1Stdin {
-            buffer: vec![bincode::serialize::<u32>(&iterations).unwrap()],
+            buffer: vec![bincode::serialize::<u32>(&iterations).unwrap().into()],
             ptr: 0,
             proofs: vec![],
+            deferred_proof_slots: vec![],
         };
         let leaf_proving_start = Instant::now();
         let proof = prover
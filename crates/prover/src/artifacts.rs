@@ -0,0 +1,338 @@
+//! The Groth16 and PLONK circuit artifacts of this version of SP1.
+//!
+//! The hashes of the artifacts are pinned in a manifest compiled into the crate, so that a
+//! directory of artifacts provisioned ahead of time, or downloaded, can be checked before it is
+//! used to prove or verify.
+//!
+//! Every file of the manifest has a pinned hash. Only the verifying keys, which are shared with
+//! `sp1-verifier`, are in the manifest for now. The other files of
+//! [`CircuitArtifactsKind::files`] only have to exist until the manifest is regenerated with
+//! [`ArtifactManifest::pin`] from the released artifacts.
+
+use std::{
+    fmt,
+    fs::File,
+    io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::SP1_CIRCUIT_VERSION;
+
+/// The environment variable pointing at a directory of circuit artifacts provisioned ahead of
+/// time, with the artifacts of each kind in a subdirectory named after it, e.g. `<dir>/groth16`.
+///
+/// When it is set, the artifacts are never downloaded.
+pub const CIRCUIT_ARTIFACTS_DIR_ENV: &str = "SP1_CIRCUIT_ARTIFACTS_DIR";
+
+/// The manifest of the circuit artifacts of [`SP1_CIRCUIT_VERSION`].
+const MANIFEST_JSON: &str = include_str!("../circuit_artifacts.json");
+
+/// The kind of a set of circuit artifacts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CircuitArtifactsKind {
+    /// The artifacts of the Groth16 circuit.
+    Groth16,
+    /// The artifacts of the PLONK circuit.
+    Plonk,
+}
+
+impl CircuitArtifactsKind {
+    /// Every kind of circuit artifacts.
+    pub const ALL: [Self; 2] = [Self::Groth16, Self::Plonk];
+
+    /// The name of the kind, which is also the name of its subdirectory and tarball.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Groth16 => "groth16",
+            Self::Plonk => "plonk",
+        }
+    }
+
+    /// The names of the files of the artifacts of the kind.
+    pub fn files(self) -> [&'static str; 4] {
+        match self {
+            Self::Groth16 => {
+                ["constraints.json", "groth16_circuit.bin", "groth16_pk.bin", "groth16_vk.bin"]
+            }
+            Self::Plonk => {
+                ["constraints.json", "plonk_circuit.bin", "plonk_pk.bin", "plonk_vk.bin"]
+            }
+        }
+    }
+}
+
+impl fmt::Display for CircuitArtifactsKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A file of the circuit artifacts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactFile {
+    /// The kind of the artifacts the file belongs to.
+    pub kind: CircuitArtifactsKind,
+    /// The name of the file in the directory of its kind.
+    pub name: String,
+    /// The hex encoded SHA-256 hash of the file.
+    pub sha256: String,
+}
+
+/// The files of the circuit artifacts of a version of SP1, and their hashes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactManifest {
+    /// The version of SP1 the artifacts are for.
+    pub version: String,
+    /// The files of the artifacts.
+    pub files: Vec<ArtifactFile>,
+}
+
+impl ArtifactManifest {
+    /// The manifest compiled into the crate, for [`SP1_CIRCUIT_VERSION`].
+    pub fn current() -> Self {
+        serde_json::from_str(MANIFEST_JSON).expect("the circuit artifacts manifest is invalid")
+    }
+
+    /// Computes the manifest of the artifacts in `dir`, laid out as in
+    /// [`CIRCUIT_ARTIFACTS_DIR_ENV`], pinning the hash of every file of every kind.
+    ///
+    /// This is used to update the manifest when the artifacts of a new version are released.
+    pub fn pin(dir: impl AsRef<Path>) -> Result<Self, ArtifactsError> {
+        let dir = dir.as_ref();
+        let mut files = Vec::new();
+        for kind in CircuitArtifactsKind::ALL {
+            for name in kind.files() {
+                let path = dir.join(kind.name()).join(name);
+                if !path.exists() {
+                    return Err(ArtifactsError::Missing { path, expected: None });
+                }
+                files.push(ArtifactFile {
+                    kind,
+                    name: name.to_string(),
+                    sha256: sha256_file(&path)?,
+                });
+            }
+        }
+        Ok(Self { version: SP1_CIRCUIT_VERSION.to_string(), files })
+    }
+
+    /// The files of the artifacts of the given kind.
+    pub fn files_of(&self, kind: CircuitArtifactsKind) -> impl Iterator<Item = &ArtifactFile> {
+        self.files.iter().filter(move |file| file.kind == kind)
+    }
+
+    /// The files of [`CircuitArtifactsKind::files`] whose hash is not pinned by the manifest.
+    pub fn unpinned(&self) -> impl Iterator<Item = (CircuitArtifactsKind, &'static str)> + '_ {
+        CircuitArtifactsKind::ALL.into_iter().flat_map(move |kind| {
+            kind.files()
+                .into_iter()
+                .filter(move |name| !self.files_of(kind).any(|file| file.name == *name))
+                .map(move |name| (kind, name))
+        })
+    }
+
+    /// Checks that `dir` holds every file of the artifacts of the given kind, and that they match
+    /// their pinned hashes.
+    pub fn verify(
+        &self,
+        dir: impl AsRef<Path>,
+        kind: CircuitArtifactsKind,
+    ) -> Result<(), ArtifactsError> {
+        let dir = dir.as_ref();
+        for name in kind.files() {
+            let file = self.files_of(kind).find(|file| file.name == name);
+            let path = dir.join(name);
+            if !path.exists() {
+                return Err(ArtifactsError::Missing {
+                    path,
+                    expected: file.map(|file| file.sha256.clone()),
+                });
+            }
+            let Some(file) = file else {
+                continue;
+            };
+            let found = sha256_file(&path)?;
+            if found != file.sha256 {
+                return Err(ArtifactsError::Corrupted {
+                    path,
+                    expected: file.sha256.clone(),
+                    found,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An error that occurs when checking a directory of circuit artifacts.
+#[derive(Debug, Error)]
+pub enum ArtifactsError {
+    /// A file of the artifacts does not exist.
+    #[error(
+        "circuit artifacts missing: {} does not exist, expected sha256 {}",
+        .path.display(),
+        .expected.as_deref().unwrap_or("<unpinned>")
+    )]
+    Missing {
+        /// The path of the file.
+        path: PathBuf,
+        /// The pinned hash of the file.
+        expected: Option<String>,
+    },
+    /// A file of the artifacts does not match its pinned hash.
+    #[error(
+        "circuit artifacts corrupt: {} has sha256 {found}, expected sha256 {expected}",
+        .path.display()
+    )]
+    Corrupted {
+        /// The path of the file.
+        path: PathBuf,
+        /// The pinned hash of the file.
+        expected: String,
+        /// The hash of the file.
+        found: String,
+    },
+    /// A file of the artifacts could not be read.
+    #[error("failed to read the circuit artifacts: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Returns the directory set by [`CIRCUIT_ARTIFACTS_DIR_ENV`], if any.
+pub fn circuit_artifacts_dir_override() -> Option<PathBuf> {
+    std::env::var_os(CIRCUIT_ARTIFACTS_DIR_ENV).filter(|dir| !dir.is_empty()).map(PathBuf::from)
+}
+
+/// Checks that `dir` holds the circuit artifacts of every kind for [`SP1_CIRCUIT_VERSION`], laid
+/// out as in [`CIRCUIT_ARTIFACTS_DIR_ENV`], and returns the manifest they were checked against.
+pub fn verify_artifacts(dir: impl AsRef<Path>) -> Result<ArtifactManifest, ArtifactsError> {
+    let dir = dir.as_ref();
+    let manifest = ArtifactManifest::current();
+    for kind in CircuitArtifactsKind::ALL {
+        manifest.verify(dir.join(kind.name()), kind)?;
+    }
+    Ok(manifest)
+}
+
+/// Checks that `dir` holds the circuit artifacts of the given kind for [`SP1_CIRCUIT_VERSION`].
+pub fn verify_circuit_artifacts(
+    dir: impl AsRef<Path>,
+    kind: CircuitArtifactsKind,
+) -> Result<(), ArtifactsError> {
+    ArtifactManifest::current().verify(dir, kind)
+}
+
+/// Returns the hex encoded SHA-256 hash of a file, reading it in chunks since the proving keys
+/// take gigabytes.
+fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    /// Writes the pinned verifying keys, which are shared with `sp1-verifier`, and empty files for
+    /// the artifacts without a pinned hash.
+    fn provision(dir: &Path) {
+        for kind in CircuitArtifactsKind::ALL {
+            fs::create_dir_all(dir.join(kind.name())).unwrap();
+        }
+        fs::write(
+            dir.join("groth16/groth16_vk.bin"),
+            include_bytes!("../../verifier/bn254-vk/groth16_vk.bin"),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("plonk/plonk_vk.bin"),
+            include_bytes!("../../verifier/bn254-vk/plonk_vk.bin"),
+        )
+        .unwrap();
+        for (kind, name) in ArtifactManifest::current().unpinned() {
+            fs::write(dir.join(kind.name()).join(name), b"").unwrap();
+        }
+    }
+
+    #[test]
+    fn test_manifest_version() {
+        assert_eq!(ArtifactManifest::current().version, SP1_CIRCUIT_VERSION);
+    }
+
+    #[test]
+    #[ignore = "the hashes of the released v4.0.0-rc.3 artifacts other than the verifying keys \
+                are not pinned yet"]
+    fn test_manifest_pins_every_file() {
+        let unpinned = ArtifactManifest::current().unpinned().collect::<Vec<_>>();
+        assert!(unpinned.is_empty(), "unpinned circuit artifacts: {unpinned:?}");
+    }
+
+    #[test]
+    fn test_verify_corrupted_proving_key() {
+        let dir = tempfile::tempdir().unwrap();
+        for kind in CircuitArtifactsKind::ALL {
+            fs::create_dir_all(dir.path().join(kind.name())).unwrap();
+            for name in kind.files() {
+                fs::write(dir.path().join(kind.name()).join(name), name.repeat(64)).unwrap();
+            }
+        }
+        let manifest = ArtifactManifest::pin(dir.path()).unwrap();
+        assert_eq!(manifest.files.len(), 8);
+        assert_eq!(manifest.unpinned().count(), 0);
+        manifest.verify(dir.path().join("groth16"), CircuitArtifactsKind::Groth16).unwrap();
+
+        // Flipping a single byte of the proving key is reported with its pinned hash.
+        let pk_path = dir.path().join("groth16/groth16_pk.bin");
+        let mut pk = fs::read(&pk_path).unwrap();
+        pk[0] ^= 1;
+        fs::write(&pk_path, pk).unwrap();
+        match manifest.verify(dir.path().join("groth16"), CircuitArtifactsKind::Groth16) {
+            Err(ArtifactsError::Corrupted { path, expected, found }) => {
+                assert_eq!(path, pk_path);
+                let mut pinned = manifest.files_of(CircuitArtifactsKind::Groth16);
+                assert!(pinned.any(|file| file.name == "groth16_pk.bin" && file.sha256 == expected));
+                assert_ne!(expected, found);
+            }
+            other => panic!("expected a corrupted proving key, got {other:?}"),
+        }
+
+        // The other kind is unaffected.
+        manifest.verify(dir.path().join("plonk"), CircuitArtifactsKind::Plonk).unwrap();
+    }
+
+    #[test]
+    fn test_verify_artifacts() {
+        let dir = tempfile::tempdir().unwrap();
+        provision(dir.path());
+        assert_eq!(verify_artifacts(dir.path()).unwrap(), ArtifactManifest::current());
+        verify_circuit_artifacts(dir.path().join("plonk"), CircuitArtifactsKind::Plonk).unwrap();
+
+        // A corrupted file is reported with its pinned hash.
+        let vk_path = dir.path().join("groth16/groth16_vk.bin");
+        fs::write(&vk_path, b"corrupted").unwrap();
+        match verify_artifacts(dir.path()) {
+            Err(ArtifactsError::Corrupted { path, expected, .. }) => {
+                assert_eq!(path, vk_path);
+                assert_eq!(expected.len(), 64);
+            }
+            other => panic!("expected corrupted artifacts, got {other:?}"),
+        }
+
+        // So is a missing file.
+        fs::remove_file(&vk_path).unwrap();
+        assert!(matches!(
+            verify_circuit_artifacts(dir.path().join("groth16"), CircuitArtifactsKind::Groth16),
+            Err(ArtifactsError::Missing { expected: Some(_), .. })
+        ));
+
+        // The artifacts of each kind are checked independently.
+        verify_circuit_artifacts(dir.path().join("plonk"), CircuitArtifactsKind::Plonk).unwrap();
+    }
+}
@@ -11,6 +11,7 @@
 #![allow(clippy::new_without_default)]
 #![allow(clippy::collapsible_else_if)]
 
+pub mod artifacts;
 pub mod build;
 pub mod components;
 pub mod shapes;
@@ -38,7 +39,9 @@ use p3_baby_bear::BabyBear;
 use p3_field::{AbstractField, PrimeField, PrimeField32};
 use p3_matrix::dense::RowMajorMatrix;
 use shapes::SP1ProofShape;
-use sp1_core_executor::{ExecutionError, ExecutionReport, Executor, Program, SP1Context};
+use sp1_core_executor::{
+    ExecutionError, ExecutionReport, Executor, GuestOutput, Program, SP1Context,
+};
 use sp1_core_machine::{
     io::SP1Stdin,
     reduce::SP1ReduceProof,
@@ -76,7 +79,8 @@ use sp1_recursion_core::{
 pub use sp1_recursion_gnark_ffi::proof::{Groth16Bn254Proof, PlonkBn254Proof};
 use sp1_recursion_gnark_ffi::{groth16_bn254::Groth16Bn254Prover, plonk_bn254::PlonkBn254Prover};
 use sp1_stark::{
-    baby_bear_poseidon2::BabyBearPoseidon2, Challenge, MachineProver, SP1CoreOpts, SP1ProverOpts,
+    baby_bear_poseidon2::BabyBearPoseidon2, Challenge, MachineProver, NoopObserver,
+    ProverObserver, ProverStage, ProvingReport, SP1CoreOpts, SP1ProverOpts, ShardObserver,
     ShardProof, StarkGenericConfig, StarkVerifyingKey, Val, Word, DIGEST_SIZE,
 };
 use sp1_stark::{shape::OrderedShape, MachineProvingKey};
@@ -113,6 +117,8 @@ const SHRINK_DEGREE: usize = 3;
 const WRAP_DEGREE: usize = 9;
 
 const CORE_CACHE_SIZE: usize = 5;
+/// The number of recursion programs whose preprocessed traces are cached by each machine.
+const PREPROCESSED_CACHE_SIZE: usize = 4;
 pub const REDUCE_BATCH_SIZE: usize = 2;
 
 pub type CompressAir<F> = RecursionAir<F, COMPRESS_DEGREE>;
@@ -171,13 +177,28 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
         let core_machine = RiscvAir::machine(CoreSC::default());
         let core_prover = C::CoreProver::new(core_machine);
 
-        let compress_machine = CompressAir::compress_machine(InnerSC::default());
+        // Repeated setups of the same recursion program reuse its preprocessed traces.
+        let preprocessed_cache_size = env::var("PROVER_PREPROCESSED_CACHE_SIZE")
+            .ok()
+            .and_then(|size| size.parse().ok())
+            .unwrap_or(PREPROCESSED_CACHE_SIZE);
+
+        let mut compress_machine = CompressAir::compress_machine(InnerSC::default())
+            .with_preprocessed_cache(preprocessed_cache_size);
+        // Optionally bound the memory used by the concurrent trace generation of a shard.
+        if let Some(max_bytes) =
+            env::var("PROVER_MAX_TRACE_GEN_BYTES").ok().and_then(|bytes| bytes.parse().ok())
+        {
+            compress_machine = compress_machine.with_max_trace_gen_bytes(max_bytes);
+        }
         let compress_prover = C::CompressProver::new(compress_machine);
 
-        let shrink_machine = ShrinkAir::shrink_machine(InnerSC::compressed());
+        let shrink_machine = ShrinkAir::shrink_machine(InnerSC::compressed())
+            .with_preprocessed_cache(preprocessed_cache_size);
         let shrink_prover = C::ShrinkProver::new(shrink_machine);
 
-        let wrap_machine = WrapAir::wrap_machine(OuterSC::default());
+        let wrap_machine = WrapAir::wrap_machine(OuterSC::default())
+            .with_preprocessed_cache(preprocessed_cache_size);
         let wrap_prover = C::WrapProver::new(wrap_machine);
 
         let core_cache_size = NonZeroUsize::new(
@@ -288,28 +309,67 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
     }
 
     /// Generate a proof of an SP1 program with the specified inputs.
-    #[instrument(name = "execute", level = "info", skip_all)]
     pub fn execute<'a>(
         &'a self,
         elf: &[u8],
         stdin: &SP1Stdin,
         mut context: SP1Context<'a>,
     ) -> Result<(SP1PublicValues, ExecutionReport), ExecutionError> {
+        let (runtime, result) = self.execute_partial(elf, stdin, context);
+        result?;
+        Ok((SP1PublicValues::from(&runtime.state.public_values_stream), runtime.report))
+    }
+
+    /// Execute an SP1 program with the specified inputs, returning the executor along with the
+    /// result of the execution.
+    ///
+    /// If the execution fails, the executor is left in the state the execution stopped in, e.g.
+    /// to report how far a program exceeding its cycle limit got.
+    #[instrument(name = "execute", level = "info", skip_all)]
+    pub fn execute_partial<'a>(
+        &'a self,
+        elf: &[u8],
+        stdin: &SP1Stdin,
+        mut context: SP1Context<'a>,
+    ) -> (Executor<'a>, Result<(), ExecutionError>) {
         context.subproof_verifier = Some(self);
         let opts = SP1CoreOpts::default();
         let mut runtime = Executor::with_context_and_elf(opts, context, elf);
 
         runtime.write_vecs(&stdin.buffer);
-        for (proof, vkey) in stdin.proofs.iter() {
-            runtime.write_proof(proof.clone(), vkey.clone());
-        }
+        stdin.write_proof_stream(&mut runtime);
+        let result = runtime.run_fast();
+        (runtime, result)
+    }
+
+    /// Execute an SP1 program, invoking `on_stdout` and `on_stderr` with the bytes of each write of
+    /// the program to stdout and stderr as the writes happen.
+    ///
+    /// The output passed to the callbacks is also returned in full in the [`GuestOutput`]. If a
+    /// callback panics, the execution is aborted with [`ExecutionError::IoCallbackPanicked`].
+    #[instrument(name = "execute", level = "info", skip_all)]
+    pub fn execute_with_io_callbacks<'a>(
+        &'a self,
+        elf: &[u8],
+        stdin: &SP1Stdin,
+        mut context: SP1Context<'a>,
+        on_stdout: impl FnMut(&[u8]) + Send + 'a,
+        on_stderr: impl FnMut(&[u8]) + Send + 'a,
+    ) -> Result<(SP1PublicValues, ExecutionReport, GuestOutput), ExecutionError> {
+        context.subproof_verifier = Some(self);
+        let opts = SP1CoreOpts::default();
+        let mut runtime = Executor::with_context_and_elf(opts, context, elf);
+        runtime.set_io_callbacks(on_stdout, on_stderr);
+
+        runtime.write_vecs(&stdin.buffer);
+        stdin.write_proof_stream(&mut runtime);
         runtime.run_fast()?;
-        Ok((SP1PublicValues::from(&runtime.state.public_values_stream), runtime.report))
+        let output = runtime.take_guest_output();
+        Ok((SP1PublicValues::from(&runtime.state.public_values_stream), runtime.report, output))
     }
 
     /// Generate shard proofs which split up and prove the valid execution of a RISC-V program with
     /// the core prover. Uses the provided context.
-    #[instrument(name = "prove_core", level = "info", skip_all)]
     pub fn prove_core<'a>(
         &'a self,
         pk_d: &<<C as SP1ProverComponents>::CoreProver as MachineProver<
@@ -319,8 +379,30 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
         program: Program,
         stdin: &SP1Stdin,
         opts: SP1ProverOpts,
-        mut context: SP1Context<'a>,
+        context: SP1Context<'a>,
     ) -> Result<SP1CoreProof, SP1CoreProverError> {
+        self.prove_core_with_observer(pk_d, program, stdin, opts, context, &NoopObserver)
+            .map(|(proof, _)| proof)
+    }
+
+    /// Generate shard proofs which split up and prove the valid execution of a RISC-V program with
+    /// the core prover, notifying `observer` as each shard is proven.
+    ///
+    /// Up to `opts.core_opts.shard_concurrency` shards are proven concurrently. Returns the proof
+    /// along with a report of the time spent proving each shard.
+    #[instrument(name = "prove_core", level = "info", skip_all)]
+    pub fn prove_core_with_observer<'a>(
+        &'a self,
+        pk_d: &<<C as SP1ProverComponents>::CoreProver as MachineProver<
+            BabyBearPoseidon2,
+            RiscvAir<BabyBear>,
+        >>::DeviceProvingKey,
+        program: Program,
+        stdin: &SP1Stdin,
+        opts: SP1ProverOpts,
+        mut context: SP1Context<'a>,
+        observer: &dyn ProverObserver,
+    ) -> Result<(SP1CoreProof, ProvingReport), SP1CoreProverError> {
         context.subproof_verifier = Some(self);
 
         // Launch two threads to simultaneously prove the core and compile the first few
@@ -349,6 +431,7 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                     self.core_shape_config.as_ref(),
                     proof_tx,
                     shape_tx,
+                    observer,
                     None,
                 )
             });
@@ -369,26 +452,43 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
 
             // Collect the shard proofs and the public values stream.
             let shard_proofs: Vec<ShardProof<_>> = proof_rx.iter().collect();
-            let (public_values_stream, cycles) = handle.join().unwrap().unwrap();
+            let (public_values_stream, cycles, report) = handle.join().unwrap().unwrap();
             let public_values = SP1PublicValues::from(&public_values_stream);
             Self::check_for_high_cycles(cycles);
-            Ok(SP1CoreProof {
+            let proof = SP1CoreProof {
                 proof: SP1CoreProofData(shard_proofs),
                 stdin: stdin.clone(),
                 public_values,
                 cycles,
-            })
+            };
+            Ok((proof, report))
         })
     }
 
     /// Reduce shards proofs to a single shard proof using the recursion prover.
-    #[instrument(name = "compress", level = "info", skip_all)]
     pub fn compress(
         &self,
         vk: &SP1VerifyingKey,
         proof: SP1CoreProof,
         deferred_proofs: Vec<SP1ReduceProof<InnerSC>>,
         opts: SP1ProverOpts,
+    ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
+        self.compress_with_observer(vk, proof, deferred_proofs, opts, &NoopObserver)
+    }
+
+    /// Reduce shards proofs to a single shard proof using the recursion prover, reporting the
+    /// stages of each recursion proof to `observer`.
+    ///
+    /// The recursion proofs are indexed in the order in which their inputs are generated, the
+    /// first layer of the tree first.
+    #[instrument(name = "compress", level = "info", skip_all)]
+    pub fn compress_with_observer(
+        &self,
+        vk: &SP1VerifyingKey,
+        proof: SP1CoreProof,
+        deferred_proofs: Vec<SP1ReduceProof<InnerSC>>,
+        opts: SP1ProverOpts,
+        observer: &dyn ProverObserver,
     ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
         #[allow(clippy::type_complexity)]
         enum TracesOrInput {
@@ -507,6 +607,10 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                                         SP1RecursionProverError::RuntimeError(e.to_string())
                                     })
                                     .unwrap();
+                                runtime
+                                    .record
+                                    .check_shape::<COMPRESS_DEGREE>()
+                                    .unwrap_or_else(|e| panic!("{e}"));
                                 runtime.record
                             });
 
@@ -522,8 +626,11 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
 
                             // Generate the traces.
                             let record = records.into_iter().next().unwrap();
-                            let traces = tracing::debug_span!("generate traces")
-                                .in_scope(|| self.compress_prover.generate_traces(&record));
+                            let observer = ShardObserver::new(observer, index);
+                            let traces = tracing::debug_span!("generate traces").in_scope(|| {
+                                self.compress_prover
+                                    .generate_traces_with_observer(&record, observer)
+                            });
 
                             // Wait for our turn to update the state.
                             record_and_trace_sync.wait_for_turn(index);
@@ -588,6 +695,7 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                             received
                         {
                             let (program, record, traces) = *boxed_prt;
+                            let observer = ShardObserver::new(observer, index);
                             tracing::debug_span!("batch").in_scope(|| {
                                 // Get the keys.
                                 let (pk, vk) = tracing::debug_span!("Setup compress program")
@@ -607,12 +715,17 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                                 );
 
                                 // Commit to the record and traces.
-                                let data = tracing::debug_span!("commit")
-                                    .in_scope(|| self.compress_prover.commit(&record, traces));
+                                let data = tracing::debug_span!("commit").in_scope(|| {
+                                    observer.in_stage(ProverStage::Commit, || {
+                                        self.compress_prover.commit(&record, traces)
+                                    })
+                                });
 
                                 // Generate the proof.
                                 let proof = tracing::debug_span!("open").in_scope(|| {
-                                    self.compress_prover.open(&pk, data, &mut challenger).unwrap()
+                                    self.compress_prover
+                                        .open_with_observer(&pk, data, &mut challenger, observer)
+                                        .unwrap()
                                 });
 
                                 // Verify the proof.
@@ -768,11 +881,22 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
     }
 
     /// Wrap a reduce proof into a STARK proven over a SNARK-friendly field.
-    #[instrument(name = "shrink", level = "info", skip_all)]
     pub fn shrink(
         &self,
         reduced_proof: SP1ReduceProof<InnerSC>,
         opts: SP1ProverOpts,
+    ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
+        self.shrink_with_observer(reduced_proof, opts, &NoopObserver)
+    }
+
+    /// Wrap a reduce proof into a STARK proven over a SNARK-friendly field, reporting the stages
+    /// of the shrink proof to `observer`.
+    #[instrument(name = "shrink", level = "info", skip_all)]
+    pub fn shrink_with_observer(
+        &self,
+        reduced_proof: SP1ReduceProof<InnerSC>,
+        opts: SP1ProverOpts,
+        observer: &dyn ProverObserver,
     ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
         // Make the compress proof.
         let SP1ReduceProof { vk: compressed_vk, proof: compressed_proof } = reduced_proof;
@@ -798,6 +922,7 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
         runtime.witness_stream = witness_stream.into();
 
         runtime.run().map_err(|e| SP1RecursionProverError::RuntimeError(e.to_string()))?;
+        runtime.record.check_shape::<SHRINK_DEGREE>()?;
 
         runtime.print_stats();
         tracing::debug!("Shrink program executed successfully");
@@ -809,18 +934,35 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
         let mut compress_challenger = self.shrink_prover.config().challenger();
         let mut compress_proof = self
             .shrink_prover
-            .prove(&shrink_pk, vec![runtime.record], &mut compress_challenger, opts.recursion_opts)
+            .prove_with_observer(
+                &shrink_pk,
+                vec![runtime.record],
+                &mut compress_challenger,
+                opts.recursion_opts,
+                observer,
+            )
             .unwrap();
 
         Ok(SP1ReduceProof { vk: shrink_vk, proof: compress_proof.shard_proofs.pop().unwrap() })
     }
 
     /// Wrap a reduce proof into a STARK proven over a SNARK-friendly field.
-    #[instrument(name = "wrap_bn254", level = "info", skip_all)]
     pub fn wrap_bn254(
         &self,
         compressed_proof: SP1ReduceProof<InnerSC>,
         opts: SP1ProverOpts,
+    ) -> Result<SP1ReduceProof<OuterSC>, SP1RecursionProverError> {
+        self.wrap_bn254_with_observer(compressed_proof, opts, &NoopObserver)
+    }
+
+    /// Wrap a reduce proof into a STARK proven over a SNARK-friendly field, reporting the stages
+    /// of the wrap proof to `observer`.
+    #[instrument(name = "wrap_bn254", level = "info", skip_all)]
+    pub fn wrap_bn254_with_observer(
+        &self,
+        compressed_proof: SP1ReduceProof<InnerSC>,
+        opts: SP1ProverOpts,
+        observer: &dyn ProverObserver,
     ) -> Result<SP1ReduceProof<OuterSC>, SP1RecursionProverError> {
         let SP1ReduceProof { vk: compressed_vk, proof: compressed_proof } = compressed_proof;
         let input = SP1CompressWitnessValues {
@@ -843,6 +985,7 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
         runtime.witness_stream = witness_stream.into();
 
         runtime.run().map_err(|e| SP1RecursionProverError::RuntimeError(e.to_string()))?;
+        runtime.record.check_shape::<WRAP_DEGREE>()?;
 
         runtime.print_stats();
         tracing::debug!("wrap program executed successfully");
@@ -860,7 +1003,13 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
         let time = std::time::Instant::now();
         let mut wrap_proof = self
             .wrap_prover
-            .prove(&wrap_pk, vec![runtime.record], &mut wrap_challenger, opts.recursion_opts)
+            .prove_with_observer(
+                &wrap_pk,
+                vec![runtime.record],
+                &mut wrap_challenger,
+                opts.recursion_opts,
+                observer,
+            )
             .unwrap();
         let elapsed = time.elapsed();
         tracing::debug!("wrap proving time: {:?}", elapsed);
@@ -1283,6 +1432,23 @@ pub fn compress_program_from_input<C: SP1ProverComponents>(
     compress_prover: &C::CompressProver,
     vk_verification: bool,
     input: &SP1CompressWithVKeyWitnessValues<BabyBearPoseidon2>,
+) -> RecursionProgram<BabyBear> {
+    compress_program_from_input_with_compiler::<C>(
+        config,
+        compress_prover,
+        vk_verification,
+        input,
+        AsmCompiler::default(),
+    )
+}
+
+/// Like [`compress_program_from_input`], but compiles the program with the given compiler.
+pub fn compress_program_from_input_with_compiler<C: SP1ProverComponents>(
+    config: Option<&RecursionShapeConfig<BabyBear, CompressAir<BabyBear>>>,
+    compress_prover: &C::CompressProver,
+    vk_verification: bool,
+    input: &SP1CompressWithVKeyWitnessValues<BabyBearPoseidon2>,
+    mut compiler: AsmCompiler<InnerConfig>,
 ) -> RecursionProgram<BabyBear> {
     let builder_span = tracing::debug_span!("build compress program").entered();
     let mut builder = Builder::<InnerConfig>::default();
@@ -1304,7 +1470,6 @@ pub fn compress_program_from_input<C: SP1ProverComponents>(
 
     // Compile the program.
     let compiler_span = tracing::debug_span!("compile compress program").entered();
-    let mut compiler = AsmCompiler::<InnerConfig>::default();
     let mut program = compiler.compile(dsl_program);
     if let Some(config) = config {
         config.fix_shape(&mut program);
@@ -1570,12 +1735,16 @@ pub mod tests {
         stdin.write(&vkey_digest);
         stdin.write(&vec![pv_1.clone(), pv_2.clone(), pv_2.clone()]);
         stdin.write_proof(deferred_reduce_1.clone(), keccak_vk.vk.clone());
-        stdin.write_proof(deferred_reduce_2.clone(), keccak_vk.vk.clone());
-        stdin.write_proof(deferred_reduce_2.clone(), keccak_vk.vk.clone());
+        // The last two proofs are only supplied when the verify program verifies them.
+        let slots = [stdin.defer_proof_slot(), stdin.defer_proof_slot()];
+        let provider = |slot: usize| {
+            slots.contains(&slot).then(|| (deferred_reduce_2.clone(), keccak_vk.vk.clone()))
+        };
+        let context = SP1Context::builder().deferred_proof_provider(provider).build();
 
         tracing::info!("proving verify program (core)");
         let verify_proof =
-            prover.prove_core(&verify_pk_d, verify_program, &stdin, opts, Default::default())?;
+            prover.prove_core(&verify_pk_d, verify_program, &stdin, opts, context)?;
         // let public_values = verify_proof.public_values.clone();
 
         // Generate recursive proof of verify program
@@ -467,7 +467,45 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
 #[cfg(test)]
 mod tests {
 
+    use sp1_recursion_compiler::{
+        circuit::{AsmCompiler, BASE_ALU_IMM_BOUND},
+        config::InnerConfig,
+    };
+    use sp1_recursion_core::{
+        chips::alu_base::NUM_BASE_ALU_ENTRIES_PER_ROW, BaseAluInstr, BaseAluOpcode, Instruction,
+    };
+
     use super::*;
+    use crate::{
+        components::CpuProverComponents, compress_program_from_input_with_compiler,
+        REDUCE_BATCH_SIZE,
+    };
+
+    /// Compiles the program verifying a batch of compressed proofs of the first compress shape
+    /// with the given compiler, before it is padded to its shape.
+    fn compress_program(
+        prover: &SP1Prover<CpuProverComponents>,
+        compiler: AsmCompiler<InnerConfig>,
+    ) -> RecursionProgram<BabyBear> {
+        let recursion_shape_config = RecursionShapeConfig::default();
+        let shape =
+            SP1ProofShape::generate_compress_shapes(&recursion_shape_config, REDUCE_BATCH_SIZE)
+                .next()
+                .unwrap();
+        let shape = SP1CompressWithVkeyShape {
+            compress_shape: shape.into(),
+            merkle_tree_height: prover.recursion_vk_tree.height,
+        };
+        let input =
+            SP1CompressWithVKeyWitnessValues::dummy(prover.compress_prover.machine(), &shape);
+        compress_program_from_input_with_compiler::<CpuProverComponents>(
+            None,
+            &prover.compress_prover,
+            prover.vk_verification,
+            &input,
+            compiler,
+        )
+    }
 
     #[test]
     #[ignore]
@@ -481,4 +519,73 @@ mod tests {
 
         println!("Number of compress shapes: {}", all_shapes.len());
     }
+
+    /// Measures the memory rows of the compress program that immediates would save. The
+    /// compress machine does not enable immediates, since that would change the verifying keys,
+    /// so the program it proves is still compiled without them.
+    #[test]
+    #[ignore = "builds the compress program twice, run it to measure the saving"]
+    fn test_compress_program_base_alu_immediates() {
+        let prover = SP1Prover::<CpuProverComponents>::new();
+
+        // Count the rows of the memory chips, before the program is padded to its shape.
+        let mem_rows = |compiler| {
+            let program = compress_program(&prover, compiler);
+            let heights = CompressAir::<BabyBear>::heights(&program);
+            let height = |chip| heights.iter().find(|(name, _)| name == chip).unwrap().1;
+            (height("MemoryConst"), height("MemoryVar"))
+        };
+        let (const_with_imms, var_with_imms) = mem_rows(AsmCompiler {
+            base_alu_imm_bound: BASE_ALU_IMM_BOUND,
+            ..AsmCompiler::default()
+        });
+        let (const_without_imms, var_without_imms) = mem_rows(AsmCompiler::default());
+
+        println!(
+            "MemoryConst rows: {const_without_imms} without immediates, {const_with_imms} with \
+             immediates; MemoryVar rows: {var_without_imms} without immediates, {var_with_imms} \
+             with immediates"
+        );
+        assert!(const_with_imms < const_without_imms);
+        assert!(var_with_imms <= var_without_imms);
+    }
+
+    /// Measures how much of the base field ALU trace of the compress program the `Fma` chip
+    /// could save, if the verifier emitted it. The compress machine does not include the chip,
+    /// since adding it would change the verifying keys, and no verifier code emits `FmaF` yet.
+    #[test]
+    #[ignore = "builds the compress program, run it to measure the fusable operations"]
+    fn test_compress_program_fusable_fma() {
+        let prover = SP1Prover::<CpuProverComponents>::new();
+        let program = compress_program(&prover, AsmCompiler::default());
+
+        // A multiplication can be fused into the addition reading its output if nothing else
+        // reads it. The `Fma` chip does not take immediates.
+        let base_alu = || {
+            program.inner.iter().filter_map(|instruction| match instruction {
+                Instruction::BaseAlu(instr) => Some(instr),
+                _ => None,
+            })
+        };
+        let is_fusable = |instr: &BaseAluInstr<BabyBear>, opcode| {
+            instr.opcode == opcode && !instr.imms.in1 && !instr.imms.in2
+        };
+        let mut products = base_alu()
+            .filter(|instr| is_fusable(instr, BaseAluOpcode::MulF) && instr.mult == BabyBear::one())
+            .map(|instr| instr.addrs.out)
+            .collect::<HashSet<_>>();
+        let num_base_alu = base_alu().count();
+        let num_fusable = base_alu()
+            .filter(|instr| is_fusable(instr, BaseAluOpcode::AddF))
+            .filter(|instr| products.remove(&instr.addrs.in1) || products.remove(&instr.addrs.in2))
+            .count();
+
+        let base_alu_rows = num_base_alu.div_ceil(NUM_BASE_ALU_ENTRIES_PER_ROW);
+        let fused_rows = (num_base_alu - 2 * num_fusable).div_ceil(NUM_BASE_ALU_ENTRIES_PER_ROW);
+        println!(
+            "{num_fusable} of {num_base_alu} base ALU operations are a multiplication fusable \
+             into an addition: {base_alu_rows} BaseAlu rows, or {fused_rows} BaseAlu rows and \
+             {num_fusable} Fma rows when fused"
+        );
+    }
 }
@@ -14,6 +14,7 @@ use sp1_recursion_circuit::machine::{
     SP1CompressWitnessValues, SP1DeferredWitnessValues, SP1RecursionWitnessValues,
 };
 
+use sp1_recursion_core::shape::ShapeOverflowError;
 use sp1_recursion_gnark_ffi::proof::{Groth16Bn254Proof, PlonkBn254Proof};
 
 use sp1_stark::{ShardProof, StarkGenericConfig, StarkProvingKey, StarkVerifyingKey, DIGEST_SIZE};
@@ -21,7 +22,7 @@ use thiserror::Error;
 
 use crate::{
     utils::{babybears_to_bn254, words_to_bytes_be},
-    CoreSC, InnerSC,
+    CoreSC, InnerSC, SP1_CIRCUIT_VERSION,
 };
 
 /// The information necessary to generate a proof for a given RISC-V program.
@@ -39,6 +40,25 @@ pub struct SP1VerifyingKey {
     pub vk: StarkVerifyingKey<CoreSC>,
 }
 
+/// A verifying key together with the version of SP1 that generated it.
+///
+/// [`SP1VerifyingKey`] keeps its layout, since payloads exchanged with other versions of SP1 embed
+/// it, so the version is carried alongside the key instead.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SP1VersionedVerifyingKey {
+    /// The version of SP1 that generated the key.
+    pub sp1_version: String,
+    /// The verifying key.
+    pub vk: SP1VerifyingKey,
+}
+
+impl SP1VersionedVerifyingKey {
+    /// Records that a verifying key was generated by this version of SP1.
+    pub fn new(vk: SP1VerifyingKey) -> Self {
+        Self { sp1_version: SP1_CIRCUIT_VERSION.to_string(), vk }
+    }
+}
+
 /// A trait for keys that can be hashed into a digest.
 pub trait HashableKey {
     /// Hash the key into a digest of BabyBear elements.
@@ -237,6 +257,8 @@ pub enum SP1ReduceProofWrapper {
 pub enum SP1RecursionProverError {
     #[error("Runtime error: {0}")]
     RuntimeError(String),
+    #[error("Shape error: {0}")]
+    ShapeOverflow(#[from] ShapeOverflowError),
 }
 
 #[allow(clippy::large_enum_variant)]
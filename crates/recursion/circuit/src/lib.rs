@@ -14,6 +14,7 @@ use sp1_recursion_compiler::{
     config::{InnerConfig, OuterConfig},
     ir::{Builder, Config, DslIr, Ext, Felt, SymbolicFelt, Var, Variable},
 };
+use sp1_recursion_core::chips::range_check::RANGE_CHECK_MAX_BITS;
 use std::iter::{repeat, zip};
 
 mod types;
@@ -284,6 +285,19 @@ impl CircuitConfig for InnerConfig {
             power_bits.iter().rev().copied().collect(),
         )
     }
+
+    /// Checks the value with a single instruction of the RangeCheck chip if the builder emits
+    /// them, and decomposes it into hinted bits otherwise.
+    fn range_check_felt(builder: &mut Builder<Self>, value: Felt<Self::F>, num_bits: usize) {
+        if builder.range_check_chip && num_bits <= RANGE_CHECK_MAX_BITS {
+            builder.range_check_v2_f(value, num_bits);
+            return;
+        }
+        let bits = Self::num2bits(builder, value, 31);
+        for bit in bits.into_iter().skip(num_bits) {
+            Self::assert_bit_zero(builder, bit);
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -650,3 +664,41 @@ impl<C: CircuitConfig<F = BabyBear, N = Bn254Fr, Bit = Var<Bn254Fr>>> BabyBearFr
         builder.commit_vkey_hash_circuit(vkey_hash);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use sp1_core_machine::riscv::MAX_LOG_NUMBER_OF_SHARDS;
+    use sp1_recursion_compiler::circuit::AsmCompiler;
+    use sp1_recursion_core::machine::RecursionAir;
+
+    use super::*;
+
+    /// Compares the rows taken by the check of the shard number in the recursion program with and
+    /// without the RangeCheck chip.
+    #[test]
+    fn test_range_check_felt_rows() {
+        let rows = |range_check_chip: bool| {
+            let mut builder = Builder::<InnerConfig>::default();
+            builder.range_check_chip = range_check_chip;
+            let shard: Felt<_> = builder.eval(BabyBear::from_canonical_u32(7));
+            InnerConfig::range_check_felt(&mut builder, shard, MAX_LOG_NUMBER_OF_SHARDS);
+            let program = AsmCompiler::<InnerConfig>::default()
+                .compile_inner(builder.into_root_block())
+                .validate()
+                .unwrap();
+            // The public values chip has a fixed height, and the RangeCheck chip is not part of
+            // the compress machine whose heights are counted.
+            RecursionAir::<BabyBear, 3>::heights(&program)
+                .into_iter()
+                .filter(|(name, _)| name != "PublicValues")
+                .map(|(_, rows)| rows)
+                .sum::<usize>() +
+                program.range_check_instrs().len()
+        };
+
+        let (chip, emulated) = (rows(true), rows(false));
+        println!("range check of the shard number: {emulated} rows emulated, {chip} with the chip");
+        assert!(chip < emulated, "{chip} >= {emulated}");
+    }
+}
@@ -165,8 +165,10 @@ where
             StarkVerifier::verify_shard(builder, &vk, machine, &mut challenger, &shard_proof);
 
             // Get the current public values.
+            // The public values of the recursion machine start with the fixed layout, which may be
+            // followed by extra public values.
             let current_public_values: &RecursionPublicValues<Felt<C::F>> =
-                shard_proof.public_values.as_slice().borrow();
+                shard_proof.public_values[..RECURSIVE_PROOF_NUM_PV_ELTS].borrow();
             // Assert that the public values are valid.
             assert_recursion_public_values_valid::<C, SC>(builder, current_public_values);
             // Assert that the vk root is the same as the witnessed one.
@@ -170,7 +170,7 @@ where
 
             // Get the current public values.
             let current_public_values: &RecursionPublicValues<Felt<C::F>> =
-                shard_proof.public_values.as_slice().borrow();
+                shard_proof.public_values[..RECURSIVE_PROOF_NUM_PV_ELTS].borrow();
             // Assert that the `vk_root` is the same as the witnessed one.
             for (elem, expected) in current_public_values.vk_root.iter().zip(vk_root.iter()) {
                 builder.assert_felt_eq(*elem, *expected);
@@ -294,6 +294,16 @@ where
 
         let ShardCommitment { main_commit, permutation_commit, quotient_commit } = *commitment;
 
+        // The chips present are known when compiling, but they must be observed like the native
+        // verifier does.
+        if machine.prunes_empty_chips() {
+            let chip_presence = machine
+                .chip_presence::<C::F>(chip_ordering)
+                .into_iter()
+                .map(|bit| builder.constant(bit))
+                .collect::<Vec<Felt<_>>>();
+            challenger.observe_slice(builder, chip_presence);
+        }
         challenger.observe(builder, main_commit);
 
         let local_permutation_challenges =
@@ -506,7 +516,7 @@ impl<C: CircuitConfig<F = SC::Val>, SC: BabyBearFriConfigVariable<C>> ShardProof
 #[allow(unused_imports)]
 #[cfg(test)]
 pub mod tests {
-    use std::{collections::VecDeque, fmt::Debug};
+    use std::{collections::VecDeque, fmt::Debug, sync::Arc};
 
     use crate::{
         challenger::{CanCopyChallenger, CanObserveVariable, DuplexChallengerVariable},
@@ -521,15 +531,19 @@ pub mod tests {
         utils::{prove_core, prove_core_stream, setup_logger},
     };
     use sp1_recursion_compiler::{
+        circuit::AsmCompiler,
         config::{InnerConfig, OuterConfig},
         ir::{Builder, DslIr, DslIrBlock},
     };
 
     use sp1_core_executor::SP1Context;
-    use sp1_recursion_core::{air::Block, machine::RecursionAir, stark::BabyBearPoseidon2Outer};
+    use sp1_recursion_core::{
+        air::Block, chips::assert_deterministic_traces, machine::RecursionAir,
+        stark::BabyBearPoseidon2Outer, Runtime,
+    };
     use sp1_stark::{
-        baby_bear_poseidon2::BabyBearPoseidon2, CpuProver, InnerVal, MachineProver, SP1CoreOpts,
-        ShardProof,
+        baby_bear_poseidon2::BabyBearPoseidon2, CpuProver, InnerChallenge, InnerVal, MachineProver,
+        SP1CoreOpts, ShardProof,
     };
     use test_artifacts::FIBONACCI_ELF;
 
@@ -618,4 +632,31 @@ pub mod tests {
             );
         run_test_recursion_with_prover::<CpuProver<_, _>>(operations, stream);
     }
+
+    /// The shard verifier, which the compress program is built from, executes and generates the
+    /// same traces for every number of threads.
+    #[test]
+    fn test_verify_shard_deterministic_traces() {
+        type EF = InnerChallenge;
+
+        let (operations, stream) =
+            build_verify_shard_with_provers::<InnerConfig, CpuProver<_, _>, CpuProver<_, _>>(
+                BabyBearPoseidon2::new(),
+                FIBONACCI_ELF,
+                SP1CoreOpts::default(),
+                Some(2),
+            );
+        let program = Arc::new(
+            AsmCompiler::<InnerConfig>::default().compile_inner(operations).validate().unwrap(),
+        );
+
+        let machine = RecursionAir::<F, 3>::compress_machine(BabyBearPoseidon2::default());
+        assert_deterministic_traces(&machine, &program, || {
+            let mut runtime =
+                Runtime::<F, EF, _>::new(program.clone(), BabyBearPoseidon2::default().perm);
+            runtime.witness_stream.extend(stream.iter().copied());
+            runtime.run().unwrap();
+            runtime.record
+        });
+    }
 }
@@ -7,7 +7,10 @@ use itertools::Itertools;
 use p3_baby_bear::BabyBear;
 use p3_field::{AbstractExtensionField, AbstractField};
 use sp1_recursion_core::air::RecursionPublicValues;
-use sp1_recursion_core::{chips::poseidon2_skinny::WIDTH, D, DIGEST_SIZE, HASH_RATE};
+use sp1_recursion_core::{
+    chips::{poseidon2_skinny::WIDTH, range_check::RANGE_CHECK_MAX_BITS},
+    D, DIGEST_SIZE, HASH_RATE,
+};
 use sp1_stark::septic_curve::SepticCurve;
 use sp1_stark::septic_digest::SepticDigest;
 use sp1_stark::septic_extension::SepticExtension;
@@ -20,6 +23,8 @@ pub trait CircuitV2Builder<C: Config> {
     fn num2bits_v2_f(&mut self, num: Felt<C::F>, num_bits: usize) -> Vec<Felt<C::F>>;
     fn exp_reverse_bits_v2(&mut self, input: Felt<C::F>, power_bits: Vec<Felt<C::F>>)
         -> Felt<C::F>;
+    fn fma_v2_f(&mut self, in1: Felt<C::F>, in2: Felt<C::F>, in3: Felt<C::F>) -> Felt<C::F>;
+    fn range_check_v2_f(&mut self, num: Felt<C::F>, num_bits: usize);
     fn batch_fri_v2(
         &mut self,
         alphas: Vec<Ext<C::F, C::EF>>,
@@ -28,6 +33,7 @@ pub trait CircuitV2Builder<C: Config> {
     ) -> Ext<C::F, C::EF>;
     fn poseidon2_permute_v2(&mut self, state: [Felt<C::F>; WIDTH]) -> [Felt<C::F>; WIDTH];
     fn poseidon2_hash_v2(&mut self, array: &[Felt<C::F>]) -> [Felt<C::F>; DIGEST_SIZE];
+    fn poseidon2_hash_padded_v2(&mut self, input: &[Felt<C::F>]) -> [Felt<C::F>; DIGEST_SIZE];
     fn poseidon2_compress_v2(
         &mut self,
         input: impl IntoIterator<Item = Felt<C::F>>,
@@ -128,6 +134,28 @@ impl<C: Config<F = BabyBear>> CircuitV2Builder<C> for Builder<C> {
         output
     }
 
+    /// Computes `in1 * in2 + in3` in a single instruction of the Fma chip, instead of a
+    /// multiplication and an addition.
+    ///
+    /// The program must be proven with a machine that includes the Fma chip.
+    fn fma_v2_f(&mut self, in1: Felt<C::F>, in2: Felt<C::F>, in3: Felt<C::F>) -> Felt<C::F> {
+        let output: Felt<_> = self.uninit();
+        self.push_op(DslIr::FmaF(output, in1, in2, in3));
+        output
+    }
+
+    /// Asserts that `num` fits in `num_bits` bits in a single instruction of the RangeCheck chip,
+    /// instead of decomposing it into hinted bits.
+    ///
+    /// The program must be proven with a machine that includes the RangeCheck chip.
+    fn range_check_v2_f(&mut self, num: Felt<C::F>, num_bits: usize) {
+        assert!(
+            num_bits <= RANGE_CHECK_MAX_BITS,
+            "cannot range check more than {RANGE_CHECK_MAX_BITS} bits"
+        );
+        self.push_op(DslIr::RangeCheckF(num, num_bits as u32));
+    }
+
     /// A version of the `batch_fri` that uses the BatchFRI precompile.
     fn batch_fri_v2(
         &mut self,
@@ -161,6 +189,24 @@ impl<C: Config<F = BabyBear>> CircuitV2Builder<C> for Builder<C> {
         state
     }
 
+    /// Applies the Poseidon2 hash function to the given array, with the `10*` padding.
+    ///
+    /// Unlike [`poseidon2_hash_v2`](CircuitV2Builder::poseidon2_hash_v2), inputs of any length are
+    /// unambiguous, since the last block always ends with the padding. This is a different hash
+    /// function, so its digests never match the ones of [p3_symmetric::PaddingFreeSponge], and it
+    /// must not be used where the native verifier hashes without padding.
+    fn poseidon2_hash_padded_v2(&mut self, input: &[Felt<C::F>]) -> [Felt<C::F>; DIGEST_SIZE] {
+        let mut state = core::array::from_fn(|_| self.eval(C::F::zero()));
+        let chunks = input.chunks_exact(HASH_RATE);
+        let remainder = chunks.remainder();
+        for input_chunk in chunks {
+            state = self.poseidon2_absorb(state, input_chunk, false);
+        }
+        state = self.poseidon2_absorb(state, remainder, true);
+        let state: [Felt<C::F>; DIGEST_SIZE] = state[..DIGEST_SIZE].try_into().unwrap();
+        state
+    }
+
     /// Applies the Poseidon2 compression function to the given array.
     ///
     /// Reference: [p3_symmetric::TruncatedPermutation]
@@ -21,8 +21,17 @@ use sp1_recursion_core::*;
 
 use crate::prelude::*;
 
+/// The bound below which base field constants are passed to the base field ALU as immediates, for
+/// compilers that opt into immediates.
+///
+/// Immediates need a machine whose base field ALU chip has immediates enabled, so they are off by
+/// default. The compress, shrink and wrap machines do not enable them, since that changes their
+/// verifying keys and the vk map, so the programs they prove do not use immediates and their
+/// memory traffic is unchanged until both are regenerated.
+pub const BASE_ALU_IMM_BOUND: u64 = 1 << 16;
+
 /// The backend for the circuit compiler.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct AsmCompiler<C: Config> {
     pub next_addr: C::F,
     /// Map the frame pointers of the variables to the "physical" addresses.
@@ -31,6 +40,22 @@ pub struct AsmCompiler<C: Config> {
     pub consts: HashMap<Imm<C::F, C::EF>, (Address<C::F>, C::F)>,
     /// Map each "physical" address to its read count.
     pub addr_to_mult: VecMap<C::F>,
+    /// The base field constants whose canonical value is below this bound are passed to the base
+    /// field ALU as immediates instead of being written to memory. Zero, the default, disables
+    /// immediates.
+    pub base_alu_imm_bound: u64,
+}
+
+impl<C: Config> Default for AsmCompiler<C> {
+    fn default() -> Self {
+        Self {
+            next_addr: C::F::zero(),
+            virtual_to_physical: VecMap::default(),
+            consts: HashMap::default(),
+            addr_to_mult: VecMap::default(),
+            base_alu_imm_bound: 0,
+        }
+    }
 }
 
 impl<C: Config> AsmCompiler<C>
@@ -167,6 +192,16 @@ where
         })
     }
 
+    /// Get an input of the base field ALU, which is an immediate if it is a small enough constant.
+    fn base_alu_input(&mut self, src: impl Reg<C>) -> (Address<C::F>, bool) {
+        match src.base_imm() {
+            Some(value) if value.as_canonical_u64() < self.base_alu_imm_bound => {
+                (Address(value), true)
+            }
+            _ => (src.read(self), false),
+        }
+    }
+
     fn base_alu(
         &mut self,
         opcode: BaseAluOpcode,
@@ -174,10 +209,14 @@ where
         lhs: impl Reg<C>,
         rhs: impl Reg<C>,
     ) -> Instruction<C::F> {
+        let out = dst.write(self);
+        let (in1, imm1) = self.base_alu_input(lhs);
+        let (in2, imm2) = self.base_alu_input(rhs);
         Instruction::BaseAlu(BaseAluInstr {
             opcode,
             mult: C::F::zero(),
-            addrs: BaseAluIo { out: dst.write(self), in1: lhs.read(self), in2: rhs.read(self) },
+            addrs: BaseAluIo { out, in1, in2 },
+            imms: BaseAluImms { in1: imm1, in2: imm2 },
         })
     }
 
@@ -251,13 +290,17 @@ where
         &mut self,
         dst: [impl Reg<C>; WIDTH],
         src: [impl Reg<C>; WIDTH],
+        padding: u32,
     ) -> Instruction<C::F> {
+        // The padded lanes are filled in by the chip, so their registers are not read.
+        let input = core::array::from_fn(|i| match is_poseidon2_padding(padding, i) {
+            true => Address(C::F::zero()),
+            false => src[i].read(self),
+        });
         Instruction::Poseidon2(Box::new(Poseidon2Instr {
-            addrs: Poseidon2Io {
-                input: src.map(|r| r.read(self)),
-                output: dst.map(|r| r.write(self)),
-            },
+            addrs: Poseidon2Io { input, output: dst.map(|r| r.write(self)) },
             mults: [C::F::zero(); WIDTH],
+            padding,
         }))
     }
 
@@ -283,6 +326,31 @@ where
         })
     }
 
+    fn fma(
+        &mut self,
+        dst: impl Reg<C>,
+        in1: impl Reg<C>,
+        in2: impl Reg<C>,
+        in3: impl Reg<C>,
+    ) -> Instruction<C::F> {
+        Instruction::Fma(FmaInstr {
+            addrs: FmaIo {
+                out: dst.write(self),
+                in1: in1.read(self),
+                in2: in2.read(self),
+                in3: in3.read(self),
+            },
+            mult: C::F::zero(),
+        })
+    }
+
+    fn range_check(&mut self, value: impl Reg<C>, num_bits: u32) -> Instruction<C::F> {
+        Instruction::RangeCheck(RangeCheckInstr {
+            addrs: RangeCheckIo { value: value.read(self) },
+            num_bits,
+        })
+    }
+
     fn exp_reverse_bits(
         &mut self,
         dst: impl Reg<C>,
@@ -515,6 +583,8 @@ where
             DslIr::InvE(dst, src) => f(self.ext_alu(DivE, dst, Imm::F(C::F::one()), src)),
 
             DslIr::Select(bit, dst1, dst2, lhs, rhs) => f(self.select(bit, dst1, dst2, lhs, rhs)),
+            DslIr::FmaF(dst, in1, in2, in3) => f(self.fma(dst, in1, in2, in3)),
+            DslIr::RangeCheckF(value, num_bits) => f(self.range_check(value, num_bits)),
 
             DslIr::AssertEqV(lhs, rhs) => self.base_assert_eq(lhs, rhs, f),
             DslIr::AssertEqF(lhs, rhs) => self.base_assert_eq(lhs, rhs, f),
@@ -531,7 +601,10 @@ where
             DslIr::AssertNeEI(lhs, rhs) => self.ext_assert_ne(lhs, Imm::EF(rhs), f),
 
             DslIr::CircuitV2Poseidon2PermuteBabyBear(data) => {
-                f(self.poseidon2_permute(data.0, data.1))
+                f(self.poseidon2_permute(data.0, data.1, 0))
+            }
+            DslIr::CircuitV2Poseidon2AbsorbBabyBear(data) => {
+                f(self.poseidon2_permute(data.0, data.1, data.2))
             }
             DslIr::CircuitV2ExpReverseBits(dst, base, exp) => {
                 f(self.exp_reverse_bits(dst, base, exp))
@@ -640,6 +713,7 @@ where
                     let Poseidon2SkinnyInstr {
                         addrs: Poseidon2Io { output: ref addrs, .. },
                         mults,
+                        ..
                     } = instr.as_mut();
                     mults.iter_mut().zip(addrs).for_each(&mut backfill);
                 }
@@ -651,6 +725,9 @@ where
                     backfill((mult1, addr1));
                     backfill((mult2, addr2));
                 }
+                Instruction::Fma(FmaInstr { addrs: FmaIo { out: ref addr, .. }, mult }) => {
+                    backfill((mult, addr))
+                }
                 Instruction::ExpReverseBitsLen(ExpReverseBitsInstr {
                     addrs: ExpReverseBitsIo { result: ref addr, .. },
                     mult,
@@ -689,7 +766,9 @@ where
                 }
                 // Instructions that do not write to memory.
                 Instruction::Mem(MemInstr { kind: MemAccessKind::Read, .. })
+                | Instruction::RangeCheck(_)
                 | Instruction::CommitPublicValues(_)
+                | Instruction::CommitExtraPublicValues(_)
                 | Instruction::Print(_) => (),
                 #[cfg(feature = "debug")]
                 Instruction::DebugBacktrace(_) => (),
@@ -787,6 +866,11 @@ trait Reg<C: Config> {
     fn write(&self, compiler: &mut AsmCompiler<C>) -> Address<C::F>;
 
     fn write_many(&self, compiler: &mut AsmCompiler<C>, len: usize) -> Vec<Address<C::F>>;
+
+    /// The value of the register if it is a base field constant.
+    fn base_imm(&self) -> Option<C::F> {
+        None
+    }
 }
 
 macro_rules! impl_reg_borrowed {
@@ -811,6 +895,10 @@ macro_rules! impl_reg_borrowed {
             fn write_many(&self, compiler: &mut AsmCompiler<C>, len: usize) -> Vec<Address<C::F>> {
                 (**self).write_many(compiler, len)
             }
+
+            fn base_imm(&self) -> Option<C::F> {
+                (**self).base_imm()
+            }
         }
     };
 }
@@ -861,6 +949,13 @@ impl<C: Config<F: PrimeField64>> Reg<C> for Imm<C::F, C::EF> {
     fn write_many(&self, _compiler: &mut AsmCompiler<C>, _len: usize) -> Vec<Address<C::F>> {
         panic!("cannot write to immediate in register: {self:?}")
     }
+
+    fn base_imm(&self) -> Option<C::F> {
+        match *self {
+            Imm::F(value) => Some(value),
+            Imm::EF(_) => None,
+        }
+    }
 }
 
 impl<C: Config<F: PrimeField64>> Reg<C> for Address<C::F> {
@@ -894,7 +989,9 @@ mod tests {
     use rand::{rngs::StdRng, Rng, SeedableRng};
 
     use sp1_core_machine::utils::{run_test_machine, setup_logger};
-    use sp1_recursion_core::{machine::RecursionAir, Runtime};
+    use sp1_recursion_core::{
+        chips::range_check::RANGE_CHECK_MAX_BITS, machine::RecursionAir, Runtime,
+    };
     use sp1_stark::{
         baby_bear_poseidon2::BabyBearPoseidon2, inner_perm, BabyBearPoseidon2Inner, InnerHash,
         StarkGenericConfig,
@@ -908,7 +1005,14 @@ mod tests {
     type F = <SC as StarkGenericConfig>::Val;
     type EF = <SC as StarkGenericConfig>::Challenge;
     fn test_block(block: DslIrBlock<AsmConfig<F, EF>>) {
-        test_block_with_runner(block, |program| {
+        test_block_with_compiler(block, AsmCompiler::default());
+    }
+
+    fn test_block_with_compiler(
+        block: DslIrBlock<AsmConfig<F, EF>>,
+        compiler: AsmCompiler<AsmConfig<F, EF>>,
+    ) {
+        test_block_with_runner(block, compiler, |program| {
             let mut runtime = Runtime::<F, EF, DiffusionMatrixBabyBear>::new(
                 program,
                 BabyBearPoseidon2Inner::new().perm,
@@ -920,9 +1024,9 @@ mod tests {
 
     fn test_block_with_runner(
         block: DslIrBlock<AsmConfig<F, EF>>,
+        mut compiler: AsmCompiler<AsmConfig<F, EF>>,
         run: impl FnOnce(Arc<RecursionProgram<F>>) -> ExecutionRecord<F>,
     ) {
-        let mut compiler = super::AsmCompiler::<AsmConfig<F, EF>>::default();
         let program = Arc::new(compiler.compile_inner(block).validate().unwrap());
         let record = run(program.clone());
 
@@ -1013,6 +1117,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_poseidon2_hash_padded() {
+        setup_logger();
+
+        let perm = inner_perm();
+        let mut rng = StdRng::seed_from_u64(0xDEADBEEF);
+        let mut builder = AsmBuilder::<F, EF>::default();
+        for len in [0, HASH_RATE - 1, HASH_RATE, HASH_RATE + 1] {
+            let input = (0..len).map(|_| rng.gen::<F>()).collect::<Vec<_>>();
+
+            // Pad manually and hash without padding natively.
+            let mut padded = input.clone();
+            padded.push(F::one());
+            padded.resize(padded.len().next_multiple_of(HASH_RATE), F::zero());
+            let mut state = [F::zero(); WIDTH];
+            for chunk in padded.chunks(HASH_RATE) {
+                state[..HASH_RATE].copy_from_slice(chunk);
+                perm.permute_mut(&mut state);
+            }
+
+            let input_felts = input.iter().map(|&x| builder.eval(x)).collect::<Vec<Felt<_>>>();
+            let result = builder.poseidon2_hash_padded_v2(&input_felts);
+            for (actual_f, expected_f) in zip(result, &state[..DIGEST_SIZE]) {
+                builder.assert_felt_eq(actual_f, *expected_f);
+            }
+        }
+
+        test_block(builder.into_root_block());
+    }
+
     #[test]
     fn test_exp_reverse_bits() {
         setup_logger();
@@ -1126,6 +1260,79 @@ mod tests {
         test_block(builder.into_root_block());
     }
 
+    #[test]
+    fn test_fma() {
+        setup_logger();
+
+        let inputs = StdRng::seed_from_u64(0xFA57F00D)
+            .sample_iter::<[F; 3], _>(rand::distributions::Standard)
+            .take(100)
+            .collect::<Vec<_>>();
+        let build = |fused: bool| {
+            let mut builder = AsmBuilder::<F, EF>::default();
+            for &[a, b, c] in &inputs {
+                let [a_felt, b_felt, c_felt] = [a, b, c].map(|x| builder.eval(x));
+                let output: Felt<_> = if fused {
+                    builder.fma_v2_f(a_felt, b_felt, c_felt)
+                } else {
+                    builder.eval(a_felt * b_felt + c_felt)
+                };
+                builder.assert_felt_eq(output, a * b + c);
+            }
+            builder.into_root_block()
+        };
+        let num_instructions = |block| {
+            super::AsmCompiler::<AsmConfig<F, EF>>::default()
+                .compile_inner(block)
+                .inner
+                .iter()
+                .count()
+        };
+
+        // Each fused multiply-add replaces at least a multiplication and an addition.
+        assert!(num_instructions(build(true)) + inputs.len() <= num_instructions(build(false)));
+        test_block(build(true));
+    }
+
+    #[test]
+    fn test_range_check() {
+        setup_logger();
+
+        let mut rng = StdRng::seed_from_u64(0x7A9E_C4EC);
+        let inputs = (0..100)
+            .map(|_| {
+                let num_bits = rng.gen_range(1..=RANGE_CHECK_MAX_BITS);
+                (F::from_canonical_u32(rng.gen_range(0..1 << num_bits)), num_bits)
+            })
+            .collect::<Vec<_>>();
+        let build = |chip: bool| {
+            let mut builder = AsmBuilder::<F, EF>::default();
+            for &(value, num_bits) in &inputs {
+                let felt = builder.eval(value);
+                if chip {
+                    builder.range_check_v2_f(felt, num_bits);
+                } else {
+                    builder.num2bits_v2_f(felt, num_bits);
+                }
+            }
+            builder.into_root_block()
+        };
+        let num_instructions = |block| {
+            super::AsmCompiler::<AsmConfig<F, EF>>::default()
+                .compile_inner(block)
+                .inner
+                .iter()
+                .count()
+        };
+
+        // Each range check replaces a decomposition into hinted bits, which takes at least one
+        // instruction per bit.
+        let (chip, emulated) = (num_instructions(build(true)), num_instructions(build(false)));
+        let num_bits = inputs.iter().map(|&(_, num_bits)| num_bits).sum::<usize>();
+        assert!(chip + num_bits <= emulated, "{chip} + {num_bits} > {emulated}");
+        test_block(build(true));
+    }
+
     #[test]
     fn test_print_and_cycle_tracker() {
         const ITERS: usize = 5;
@@ -1164,7 +1371,7 @@ mod tests {
         }
         builder.cycle_tracker_v2_exit();
 
-        test_block_with_runner(builder.into_root_block(), |program| {
+        test_block_with_runner(builder.into_root_block(), AsmCompiler::default(), |program| {
             let mut runtime = Runtime::<F, EF, DiffusionMatrixBabyBear>::new(
                 program,
                 BabyBearPoseidon2Inner::new().perm,
@@ -1238,6 +1445,40 @@ mod tests {
         };
     }
 
+    #[test]
+    fn test_base_alu_immediates() {
+        setup_logger();
+
+        let compiler_with_imms =
+            || AsmCompiler { base_alu_imm_bound: BASE_ALU_IMM_BOUND, ..AsmCompiler::default() };
+
+        let build = || {
+            let mut rng = StdRng::seed_from_u64(0xFEEDF00D);
+            let mut builder = AsmBuilder::<F, EF>::default();
+            for _ in 0..100 {
+                let x = rng.gen::<F>();
+                let small = F::from_canonical_u32(rng.gen_range(0..1 << 16));
+                let large = F::from_canonical_u32(rng.gen_range(1 << 16..F::ORDER_U32));
+                let x_felt: Felt<_> = builder.eval(x);
+                let y_felt: Felt<_> = builder.eval(x_felt * small + large);
+                let z_felt: Felt<_> = builder.eval(small - x_felt / large);
+                builder.assert_felt_eq(y_felt, x * small + large);
+                builder.assert_felt_eq(z_felt, small - x / large);
+            }
+            builder.into_root_block()
+        };
+
+        let count_mem = |compiler: &mut AsmCompiler<AsmConfig<F, EF>>| {
+            let program = compiler.compile_inner(build()).validate().unwrap();
+            program.inner.iter().filter(|instr| matches!(instr, Instruction::Mem(_))).count()
+        };
+        let with_imms = count_mem(&mut compiler_with_imms());
+        let without_imms = count_mem(&mut AsmCompiler::default());
+        assert!(with_imms < without_imms, "{with_imms} >= {without_imms} memory instructions");
+
+        test_block_with_compiler(build(), compiler_with_imms());
+    }
+
     #[test]
     fn test_assert_eq_noop() {
         test_assert_fixture!(assert_felt_eq, assert_ext_eq, false);
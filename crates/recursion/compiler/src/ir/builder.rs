@@ -32,6 +32,10 @@ pub struct Builder<C: Config> {
     pub(crate) debug: bool,
     pub(crate) is_sub_builder: bool,
     pub program_type: RecursionProgramType,
+    /// Whether range checks are emitted as instructions of the RangeCheck chip, instead of being
+    /// decomposed into hinted bits. The program must then be proven with a machine that includes
+    /// the chip, which the compress, shrink and wrap machines do not.
+    pub range_check_chip: bool,
 }
 
 impl<C: Config> Default for Builder<C> {
@@ -70,6 +74,7 @@ impl<C: Config> Builder<C> {
             debug: false,
             is_sub_builder: false,
             program_type,
+            range_check_chip: false,
         };
 
         new_builder.p2_hash_num = new_builder.uninit();
@@ -95,13 +100,15 @@ impl<C: Config> Builder<C> {
 
     /// Convenience function for creating a new sub builder.
     pub fn sub_builder(&self) -> Self {
-        Builder::<C>::new_sub_builder(
+        let mut builder = Builder::<C>::new_sub_builder(
             self.variable_count(),
             self.nb_public_values,
             self.p2_hash_num,
             self.debug,
             self.program_type,
-        )
+        );
+        builder.range_check_chip = self.range_check_chip;
+        builder
     }
 
     /// Pushes an operation to the builder.
@@ -131,6 +131,11 @@ pub enum DslIr<C: Config> {
     /// Selects order of felts based on a bit (should_swap, first result, second result, first
     /// input, second input)
     Select(Felt<C::F>, Felt<C::F>, Felt<C::F>, Felt<C::F>, Felt<C::F>),
+    /// Multiplies two field elements and adds a third (output = first input * second input +
+    /// third input).
+    FmaF(Felt<C::F>, Felt<C::F>, Felt<C::F>, Felt<C::F>),
+    /// Asserts that a field element fits in a number of bits (value, number of bits).
+    RangeCheckF(Felt<C::F>, u32),
 
     // Control flow.
     /// Executes a for loop with the parameters (start step value, end step value, step size, step
@@ -227,6 +232,9 @@ pub enum DslIr<C: Config> {
     CircuitPoseidon2PermuteBabyBear(Box<[Felt<C::F>; 16]>),
     /// Permutates an array of BabyBear elements in the circuit using the skinny precompile.
     CircuitV2Poseidon2PermuteBabyBear(Box<([Felt<C::F>; 16], [Felt<C::F>; 16])>),
+    /// Absorbs into a sponge state using the skinny precompile. The last `padding` rate lanes of
+    /// the input are replaced by the `10*` padding instead of being read.
+    CircuitV2Poseidon2AbsorbBabyBear(Box<([Felt<C::F>; 16], [Felt<C::F>; 16], u32)>),
     /// Commits the public values.
     CircuitV2CommitPublicValues(Box<RecursionPublicValues<Felt<C::F>>>),
 
@@ -26,15 +26,29 @@ impl<C: Config> Builder<C> {
         self.push_op(DslIr::Poseidon2PermuteBabyBear(Box::new((array.clone(), array.clone()))));
     }
 
-    /// Applies the Poseidon2 absorb function to the given array.
+    /// Absorbs at most [`HASH_RATE`] elements into the sponge state, overwriting the start of the
+    /// rate, and applies the Poseidon2 permutation.
     ///
-    /// Reference: [p3_symmetric::PaddingFreeSponge]
+    /// When `finalize` is set, the input must be shorter than the rate, and the rest of the rate
+    /// is filled with the `10*` padding by the Poseidon2 chip itself, so the padding costs no
+    /// extra instructions. Padded absorbs need a machine whose Poseidon2 chip has padding enabled.
     pub fn poseidon2_absorb(
         &mut self,
-        p2_hash_and_absorb_num: Var<C::N>,
-        input: &Array<C, Felt<C::F>>,
-    ) {
-        self.push_op(DslIr::Poseidon2AbsorbBabyBear(p2_hash_and_absorb_num, input.clone()));
+        mut state: [Felt<C::F>; PERMUTATION_WIDTH],
+        input: &[Felt<C::F>],
+        finalize: bool,
+    ) -> [Felt<C::F>; PERMUTATION_WIDTH] {
+        assert!(input.len() <= HASH_RATE, "cannot absorb more than the rate at once");
+        state[..input.len()].copy_from_slice(input);
+        let output: [Felt<C::F>; PERMUTATION_WIDTH] = core::array::from_fn(|_| self.uninit());
+        if !finalize {
+            self.push_op(DslIr::CircuitV2Poseidon2PermuteBabyBear(Box::new((output, state))));
+            return output;
+        }
+        assert!(input.len() < HASH_RATE, "the padding needs at least one lane of the rate");
+        let padding = (HASH_RATE - input.len()) as u32;
+        self.push_op(DslIr::CircuitV2Poseidon2AbsorbBabyBear(Box::new((output, state, padding))));
+        output
     }
 
     /// Applies the Poseidon2 finalize to the given hash number.
@@ -126,7 +140,7 @@ impl<C: Config> Builder<C> {
             let subarray = builder.get(array, i);
             let p2_hash_and_absorb_num: Var<_> = builder.eval(p2_hash_num * two_power_12 + i);
 
-            builder.poseidon2_absorb(p2_hash_and_absorb_num, &subarray);
+            builder.push_op(DslIr::Poseidon2AbsorbBabyBear(p2_hash_and_absorb_num, subarray));
         });
 
         let output: Array<C, Felt<C::F>> = self.dyn_array(DIGEST_SIZE);
@@ -0,0 +1,97 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use p3_baby_bear::BabyBear;
+use p3_field::AbstractField;
+use sp1_recursion_core::{
+    chips::{
+        alu_base::{BaseAluChip, BaseAluValueCols, NUM_BASE_ALU_COLS},
+        select::{SelectChip, SelectValueCols, SELECT_COLS},
+    },
+    BaseAluEvent, ExecutionRecord, SelectEvent,
+};
+use sp1_stark::{air::MachineAir, fill_trace, ColMajorMatrix, RowPadding};
+
+const NUM_EVENTS: usize = 1 << 20;
+
+fn record() -> ExecutionRecord<BabyBear> {
+    let felt = BabyBear::from_canonical_usize;
+    ExecutionRecord {
+        base_alu_events: (0..NUM_EVENTS)
+            .map(|i| BaseAluEvent { out: felt(i), in1: felt(i + 1), in2: felt(i + 2) })
+            .collect(),
+        select_events: (0..NUM_EVENTS)
+            .map(|i| SelectEvent {
+                bit: BabyBear::zero(),
+                out1: felt(i),
+                out2: felt(i + 1),
+                in1: felt(i),
+                in2: felt(i + 1),
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+/// Compares the generation of the traces from the event columns of the record with their
+/// generation from a vector of events, one event at a time.
+fn trace_gen(c: &mut Criterion) {
+    let record = record();
+    let base_alu_events = record.base_alu_events.to_vec();
+    let select_events = record.select_events.to_vec();
+
+    let mut group = c.benchmark_group("BaseAlu trace");
+    let chip = BaseAluChip::default();
+    let height = chip.num_rows(&record).unwrap();
+    let from_events = || {
+        fill_trace(
+            &base_alu_events,
+            height,
+            NUM_BASE_ALU_COLS,
+            RowPadding::Zero,
+            |&vals, cols: &mut BaseAluValueCols<BabyBear>| *cols = BaseAluValueCols { vals },
+        )
+    };
+    group.bench_function("row-major from events", |b| b.iter(|| black_box(from_events())));
+    group.bench_function("row-major from columns", |b| {
+        b.iter(|| black_box(chip.generate_trace(&record, &mut ExecutionRecord::default())))
+    });
+    group.bench_function("col-major from events", |b| {
+        b.iter(|| black_box(ColMajorMatrix::from_row_major(&from_events())))
+    });
+    group.bench_function("col-major from columns", |b| {
+        b.iter(|| {
+            black_box(chip.generate_trace_col_major(&record, &mut ExecutionRecord::default()))
+        })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("Select trace");
+    let select: SelectChip = SelectChip;
+    let height = select.num_rows(&record).unwrap();
+    let from_events = || {
+        fill_trace(
+            &select_events,
+            height,
+            SELECT_COLS,
+            RowPadding::Zero,
+            |&vals, cols: &mut SelectValueCols<BabyBear>| *cols = SelectValueCols { vals },
+        )
+    };
+    group.bench_function("row-major from events", |b| b.iter(|| black_box(from_events())));
+    group.bench_function("row-major from columns", |b| {
+        b.iter(|| black_box(select.generate_trace(&record, &mut ExecutionRecord::default())))
+    });
+    group.bench_function("col-major from events", |b| {
+        b.iter(|| black_box(ColMajorMatrix::from_row_major(&from_events())))
+    });
+    group.bench_function("col-major from columns", |b| {
+        b.iter(|| {
+            black_box(select.generate_trace_col_major(&record, &mut ExecutionRecord::default()))
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, trace_gen);
+criterion_main!(benches);
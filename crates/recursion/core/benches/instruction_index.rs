@@ -0,0 +1,83 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use p3_baby_bear::BabyBear;
+use sp1_recursion_core::{
+    instruction as instr, BaseAluOpcode, BasicBlock, ExtAluOpcode, Instruction, RawProgram,
+    RecursionProgram, RootProgram, SeqBlock,
+};
+
+const NUM_INSTRUCTIONS: usize = 1 << 22;
+
+/// A compress-sized program, with its instructions spread across the blocks of a parallel block.
+fn root_program() -> RootProgram<BabyBear> {
+    let instrs = (0..NUM_INSTRUCTIONS as u32)
+        .map(|i| match i % 5 {
+            0 => instr::base_alu(BaseAluOpcode::AddF, 1, i, i + 1, i + 2),
+            1 => instr::ext_alu(ExtAluOpcode::MulE, 1, i, i + 1, i + 2),
+            2 => instr::select(1, 1, i, i + 1, i + 2, i + 3, i + 4),
+            3 => instr::fma(1, i, i + 1, i + 2, i + 3),
+            _ => instr::range_check(i, 16),
+        })
+        .collect::<Vec<Instruction<BabyBear>>>();
+    let blocks = instrs
+        .chunks(NUM_INSTRUCTIONS / 8)
+        .map(|chunk| RawProgram {
+            seq_blocks: vec![SeqBlock::Basic(BasicBlock { instrs: chunk.to_vec() })],
+        })
+        .collect();
+    RootProgram {
+        inner: RawProgram { seq_blocks: vec![SeqBlock::Parallel(blocks)] },
+        total_memory: 0,
+        shape: None,
+    }
+}
+
+/// Compares extracting the instructions of every chip with a pass over the program per chip, and
+/// with the instruction index, which is computed on the first extraction.
+fn instruction_index(c: &mut Criterion) {
+    let root = root_program();
+    // SAFETY: The program is only used to extract instructions.
+    let new_program = || unsafe { RecursionProgram::new_unchecked(root.clone()) };
+
+    let mut group = c.benchmark_group("Instruction extraction");
+    group.sample_size(10);
+    group.bench_function("pass per chip", |b| {
+        b.iter_batched(
+            new_program,
+            |program| {
+                black_box([
+                    program.inner.iter().filter(|i| matches!(i, Instruction::BaseAlu(_))).count(),
+                    program.inner.iter().filter(|i| matches!(i, Instruction::ExtAlu(_))).count(),
+                    program.inner.iter().filter(|i| matches!(i, Instruction::Select(_))).count(),
+                    program.inner.iter().filter(|i| matches!(i, Instruction::Fma(_))).count(),
+                    program
+                        .inner
+                        .iter()
+                        .filter(|i| matches!(i, Instruction::RangeCheck(_)))
+                        .count(),
+                ])
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("instruction index", |b| {
+        b.iter_batched(
+            new_program,
+            |program| {
+                black_box([
+                    program.base_alu_instrs().len(),
+                    program.ext_alu_instrs().len(),
+                    program.select_instrs().len(),
+                    program.fma_instrs().len(),
+                    program.range_check_instrs().len(),
+                ])
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, instruction_index);
+criterion_main!(benches);
@@ -0,0 +1,90 @@
+use std::{array, hint::black_box};
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use p3_baby_bear::BabyBear;
+use p3_field::AbstractField;
+use sp1_recursion_core::{
+    air::Block, BaseAluEvent, ExecutionRecord, ExtAluEvent, MemEvent, Poseidon2Event,
+    SegmentedRecord, SelectEvent,
+};
+use sp1_stark::MachineRecord;
+
+const NUM_SHARDS: usize = 64;
+const EVENTS_PER_SHARD: usize = 1 << 14;
+
+fn shard(index: usize) -> ExecutionRecord<BabyBear> {
+    let felt = |i: usize| BabyBear::from_canonical_usize(index * EVENTS_PER_SHARD + i);
+    let block = |i: usize| Block::from(felt(i));
+    ExecutionRecord {
+        base_alu_events: (0..EVENTS_PER_SHARD)
+            .map(|i| BaseAluEvent { out: felt(i), in1: felt(i), in2: felt(i) })
+            .collect(),
+        ext_alu_events: (0..EVENTS_PER_SHARD)
+            .map(|i| ExtAluEvent { out: block(i), in1: block(i), in2: block(i) })
+            .collect(),
+        mem_var_events: (0..EVENTS_PER_SHARD).map(|i| MemEvent { inner: block(i) }).collect(),
+        poseidon2_events: (0..EVENTS_PER_SHARD / 16)
+            .map(|i| Poseidon2Event {
+                input: array::from_fn(|j| felt(i + j)),
+                output: array::from_fn(|j| felt(i + j + 1)),
+            })
+            .collect(),
+        select_events: (0..EVENTS_PER_SHARD)
+            .map(|i| SelectEvent {
+                bit: BabyBear::zero(),
+                out1: felt(i),
+                out2: felt(i),
+                in1: felt(i),
+                in2: felt(i),
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn merge_records(c: &mut Criterion) {
+    let shards = (0..NUM_SHARDS).map(shard).collect::<Vec<_>>();
+    let mut group = c.benchmark_group("merge 64 records");
+
+    group.bench_function("append one at a time", |b| {
+        b.iter_batched(
+            || shards.clone(),
+            |mut shards| {
+                let mut record = ExecutionRecord::default();
+                for shard in &mut shards {
+                    record.append(shard);
+                }
+                black_box(record)
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("append_all", |b| {
+        b.iter_batched(
+            || shards.clone(),
+            |mut shards| {
+                let mut record = ExecutionRecord::default();
+                record.append_all(&mut shards);
+                black_box(record)
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("segmented", |b| {
+        b.iter_batched(
+            || shards.clone(),
+            |mut shards| {
+                let mut record = SegmentedRecord::default();
+                for shard in &mut shards {
+                    record.append(shard);
+                }
+                black_box(record.into_record())
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, merge_records);
+criterion_main!(benches);
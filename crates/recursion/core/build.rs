@@ -126,16 +126,29 @@ mod sys {
             .include_item("FriFoldCols")
             .include_item("FriFoldInstrFFI")
             .include_item("FriFoldPreprocessedCols")
+            .include_item("MemIo")
+            .include_item("MemInstr")
+            .include_item("MemAccessKind")
+            .include_item("MemoryConstAccessCols")
+            .include_item("MemoryAccessColsChips")
             .include_item("SelectEvent")
-            .include_item("SelectCols")
+            .include_item("SelectValueCols")
             .include_item("CommitPublicValuesEvent")
             .include_item("PublicValuesCols")
             .include_item("CommitPublicValuesInstr")
             .include_item("PublicValuesPreprocessedCols")
             .include_item("SelectEvent")
-            .include_item("SelectCols")
+            .include_item("SelectValueCols")
             .include_item("SelectInstr")
-            .include_item("SelectPreprocessedCols")
+            .include_item("SelectAccessCols")
+            .include_item("FmaEvent")
+            .include_item("FmaCols")
+            .include_item("FmaInstr")
+            .include_item("FmaPreprocessedCols")
+            .include_item("RangeCheckEvent")
+            .include_item("RangeCheckCols")
+            .include_item("RangeCheckInstr")
+            .include_item("RangeCheckPreprocessedCols")
             .include_item("Poseidon2Event")
             .include_item("Poseidon2")
             .include_item("Poseidon2Instr")
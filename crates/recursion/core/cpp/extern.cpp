@@ -75,6 +75,29 @@ extern void fri_fold_instr_to_row_babybear(
       *reinterpret_cast<FriFoldPreprocessedCols<bb31_t>*>(cols));
 }
 
+extern void mem_const_instr_to_row_babybear(
+    const MemInstr<BabyBearP3>* instr,
+    MemoryConstAccessCols<BabyBearP3>* cols) {
+  mem_const::instr_to_row<bb31_t>(
+      *reinterpret_cast<const MemInstr<bb31_t>*>(instr),
+      *reinterpret_cast<MemoryConstAccessCols<bb31_t>*>(cols));
+}
+
+extern void mem_var_event_to_row_babybear(const MemIo<Block<BabyBearP3>>* io,
+                                          Block<BabyBearP3>* value) {
+  mem_var::event_to_row<bb31_t>(
+      *reinterpret_cast<const MemIo<Block<bb31_t>>*>(io),
+      *reinterpret_cast<Block<bb31_t>*>(value));
+}
+extern void mem_var_instr_to_row_babybear(
+    const Address<BabyBearP3>* addr, const BabyBearP3* mult,
+    MemoryAccessColsChips<BabyBearP3>* access) {
+  mem_var::instr_to_row<bb31_t>(
+      *reinterpret_cast<const Address<bb31_t>*>(addr),
+      *reinterpret_cast<const bb31_t*>(mult),
+      *reinterpret_cast<MemoryAccessColsChips<bb31_t>*>(access));
+}
+
 extern void public_values_event_to_row_babybear(
     const CommitPublicValuesEvent<BabyBearP3>* io, size_t digest_idx,
     PublicValuesCols<BabyBearP3>* cols) {
@@ -92,17 +115,43 @@ extern void public_values_instr_to_row_babybear(
 }
 
 extern void select_event_to_row_babybear(const SelectEvent<BabyBearP3>* io,
-                                         SelectCols<BabyBearP3>* cols) {
+                                         SelectValueCols<BabyBearP3>* cols) {
   select::event_to_row<bb31_t>(
       *reinterpret_cast<const SelectEvent<bb31_t>*>(io),
-      *reinterpret_cast<SelectCols<bb31_t>*>(cols));
+      *reinterpret_cast<SelectValueCols<bb31_t>*>(cols));
 }
 extern void select_instr_to_row_babybear(
     const SelectInstr<BabyBearP3>* instr,
-    SelectPreprocessedCols<BabyBearP3>* cols) {
+    SelectAccessCols<BabyBearP3>* access) {
   select::instr_to_row<bb31_t>(
       *reinterpret_cast<const SelectInstr<bb31_t>*>(instr),
-      *reinterpret_cast<SelectPreprocessedCols<bb31_t>*>(cols));
+      *reinterpret_cast<SelectAccessCols<bb31_t>*>(access));
+}
+
+extern void fma_event_to_row_babybear(const FmaEvent<BabyBearP3>* io,
+                                      FmaCols<BabyBearP3>* cols) {
+  fma::event_to_row<bb31_t>(*reinterpret_cast<const FmaEvent<bb31_t>*>(io),
+                            *reinterpret_cast<FmaCols<bb31_t>*>(cols));
+}
+extern void fma_instr_to_row_babybear(const FmaInstr<BabyBearP3>* instr,
+                                      FmaPreprocessedCols<BabyBearP3>* cols) {
+  fma::instr_to_row<bb31_t>(
+      *reinterpret_cast<const FmaInstr<bb31_t>*>(instr),
+      *reinterpret_cast<FmaPreprocessedCols<bb31_t>*>(cols));
+}
+
+extern void range_check_event_to_row_babybear(
+    const RangeCheckEvent<BabyBearP3>* io, RangeCheckCols<BabyBearP3>* cols) {
+  range_check::event_to_row<bb31_t>(
+      *reinterpret_cast<const RangeCheckEvent<bb31_t>*>(io),
+      *reinterpret_cast<RangeCheckCols<bb31_t>*>(cols));
+}
+extern void range_check_instr_to_row_babybear(
+    const RangeCheckInstr<BabyBearP3>* instr,
+    RangeCheckPreprocessedCols<BabyBearP3>* cols) {
+  range_check::instr_to_row<bb31_t>(
+      *reinterpret_cast<const RangeCheckInstr<bb31_t>*>(instr),
+      *reinterpret_cast<RangeCheckPreprocessedCols<bb31_t>*>(cols));
 }
 
 extern void poseidon2_skinny_event_to_row_babybear(
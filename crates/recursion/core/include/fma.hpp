@@ -0,0 +1,18 @@
+#pragma once
+
+#include "prelude.hpp"
+
+namespace sp1_recursion_core_sys::fma {
+template <class F>
+__SP1_HOSTDEV__ void event_to_row(const FmaEvent<F>& event, FmaCols<F>& cols) {
+  cols.vals = event;
+}
+
+template <class F>
+__SP1_HOSTDEV__ void instr_to_row(const FmaInstr<F>& instr,
+                                  FmaPreprocessedCols<F>& cols) {
+  cols.is_real = F::one();
+  cols.addrs = instr.addrs;
+  cols.mult = instr.mult;
+}
+}  // namespace sp1_recursion_core_sys::fma
@@ -0,0 +1,44 @@
+#pragma once
+
+#include "sp1-recursion-core-sys-cbindgen.hpp"
+
+// The layouts of the column structs must match their Rust definitions, whose
+// widths are checked against the same constants in `src/sys.rs`.
+namespace sp1_recursion_core_sys {
+#define SP1_ASSERT_WIDTH(COLS, WIDTH)                            \
+  static_assert(                                                 \
+      sizeof(COLS<BabyBearP3>) == (WIDTH) * sizeof(BabyBearP3), \
+      #COLS " does not match its Rust layout")
+
+SP1_ASSERT_WIDTH(BaseAluValueCols, BASE_ALU_VALUE_COLS_WIDTH);
+SP1_ASSERT_WIDTH(BaseAluAccessCols, BASE_ALU_ACCESS_COLS_WIDTH);
+SP1_ASSERT_WIDTH(ExtAluValueCols, EXT_ALU_VALUE_COLS_WIDTH);
+SP1_ASSERT_WIDTH(ExtAluAccessCols, EXT_ALU_ACCESS_COLS_WIDTH);
+SP1_ASSERT_WIDTH(BatchFRICols, BATCH_FRI_COLS_WIDTH);
+SP1_ASSERT_WIDTH(BatchFRIPreprocessedCols, BATCH_FRI_PREPROCESSED_COLS_WIDTH);
+SP1_ASSERT_WIDTH(ExpReverseBitsLenCols, EXP_REVERSE_BITS_LEN_COLS_WIDTH);
+SP1_ASSERT_WIDTH(ExpReverseBitsLenPreprocessedCols,
+                 EXP_REVERSE_BITS_LEN_PREPROCESSED_COLS_WIDTH);
+SP1_ASSERT_WIDTH(FriFoldCols, FRI_FOLD_COLS_WIDTH);
+SP1_ASSERT_WIDTH(FriFoldPreprocessedCols, FRI_FOLD_PREPROCESSED_COLS_WIDTH);
+SP1_ASSERT_WIDTH(PublicValuesCols, PUBLIC_VALUES_COLS_WIDTH);
+SP1_ASSERT_WIDTH(PublicValuesPreprocessedCols,
+                 PUBLIC_VALUES_PREPROCESSED_COLS_WIDTH);
+SP1_ASSERT_WIDTH(SelectValueCols, SELECT_VALUE_COLS_WIDTH);
+SP1_ASSERT_WIDTH(SelectAccessCols, SELECT_ACCESS_COLS_WIDTH);
+SP1_ASSERT_WIDTH(FmaCols, FMA_COLS_WIDTH);
+SP1_ASSERT_WIDTH(FmaPreprocessedCols, FMA_PREPROCESSED_COLS_WIDTH);
+SP1_ASSERT_WIDTH(RangeCheckCols, RANGE_CHECK_COLS_WIDTH);
+SP1_ASSERT_WIDTH(RangeCheckPreprocessedCols,
+                 RANGE_CHECK_PREPROCESSED_COLS_WIDTH);
+SP1_ASSERT_WIDTH(Poseidon2, POSEIDON2_SKINNY_COLS_WIDTH);
+SP1_ASSERT_WIDTH(Poseidon2PreprocessedColsSkinny,
+                 POSEIDON2_PREPROCESSED_COLS_SKINNY_WIDTH);
+SP1_ASSERT_WIDTH(Poseidon2PreprocessedColsWide,
+                 POSEIDON2_PREPROCESSED_COLS_WIDE_WIDTH);
+SP1_ASSERT_WIDTH(MemoryConstAccessCols, MEMORY_CONST_ACCESS_COLS_WIDTH);
+SP1_ASSERT_WIDTH(MemoryAccessColsChips, MEMORY_ACCESS_COLS_WIDTH);
+SP1_ASSERT_WIDTH(Block, BLOCK_WIDTH);
+
+#undef SP1_ASSERT_WIDTH
+}  // namespace sp1_recursion_core_sys
@@ -0,0 +1,20 @@
+#pragma once
+
+#include "prelude.hpp"
+
+namespace sp1_recursion_core_sys::mem_const {
+template <class F>
+__SP1_HOSTDEV__ void instr_to_row(const MemInstr<F>& instr,
+                                  MemoryConstAccessCols<F>& cols) {
+  cols.value = instr.vals.inner;
+  cols.access.addr = instr.addrs.inner;
+  switch (instr.kind) {
+    case MemAccessKind::Read:
+      cols.access.mult = F::zero() - instr.mult;
+      break;
+    case MemAccessKind::Write:
+      cols.access.mult = instr.mult;
+      break;
+  }
+}
+}  // namespace sp1_recursion_core_sys::mem_const
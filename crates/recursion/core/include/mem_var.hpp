@@ -0,0 +1,18 @@
+#pragma once
+
+#include "prelude.hpp"
+
+namespace sp1_recursion_core_sys::mem_var {
+template <class F>
+__SP1_HOSTDEV__ void event_to_row(const MemIo<Block<F>>& event,
+                                  Block<F>& value) {
+  value = event.inner;
+}
+
+template <class F>
+__SP1_HOSTDEV__ void instr_to_row(const Address<F>& addr, const F& mult,
+                                  MemoryAccessColsChips<F>& access) {
+  access.addr = addr;
+  access.mult = mult;
+}
+}  // namespace sp1_recursion_core_sys::mem_var
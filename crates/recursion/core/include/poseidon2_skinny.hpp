@@ -103,8 +103,10 @@ __SP1_HOSTDEV__ void instr_to_row(const Poseidon2Instr<F>& instr, size_t i,
 
   if (i == INPUT_ROUND_IDX) {
     for (size_t j = 0; j < WIDTH; j++) {
+      bool is_padding = j < HASH_RATE && j + instr.padding >= HASH_RATE;
       cols.memory_preprocessed[j].addr = instr.addrs.input[j];
-      cols.memory_preprocessed[j].mult = F::zero() - F::one();
+      cols.memory_preprocessed[j].mult =
+          is_padding ? F::zero() : F::zero() - F::one();
     }
   } else if (i == OUTPUT_ROUND_IDX) {
     for (size_t j = 0; j < WIDTH; j++) {
@@ -0,0 +1,25 @@
+#pragma once
+
+#include "prelude.hpp"
+
+namespace sp1_recursion_core_sys::range_check {
+template <class F>
+__SP1_HOSTDEV__ void event_to_row(const RangeCheckEvent<F>& event,
+                                  RangeCheckCols<F>& cols) {
+  cols.vals = event;
+  uint32_t value = event.value.as_canonical_u32();
+  for (uintptr_t i = 0; i < RANGE_CHECK_MAX_BITS; i++) {
+    cols.bits[i] = ((value >> i) & 1) ? F::one() : F::zero();
+  }
+}
+
+template <class F>
+__SP1_HOSTDEV__ void instr_to_row(const RangeCheckInstr<F>& instr,
+                                  RangeCheckPreprocessedCols<F>& cols) {
+  cols.is_real = F::one();
+  cols.addrs = instr.addrs;
+  for (uintptr_t i = 0; i < RANGE_CHECK_MAX_BITS; i++) {
+    cols.bit_mask[i] = i < instr.num_bits ? F::one() : F::zero();
+  }
+}
+}  // namespace sp1_recursion_core_sys::range_check
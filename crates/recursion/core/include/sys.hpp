@@ -4,9 +4,14 @@
 #include "alu_ext.hpp"
 #include "batch_fri.hpp"
 #include "exp_reverse_bits.hpp"
+#include "fma.hpp"
 #include "fri_fold.hpp"
+#include "mem_const.hpp"
+#include "mem_var.hpp"
 #include "public_values.hpp"
+#include "range_check.hpp"
 #include "select.hpp"
 #include "poseidon2_skinny.hpp"
 #include "poseidon2_wide.hpp"
+#include "layout.hpp"
 #include "sp1-recursion-core-sys-cbindgen.hpp"
@@ -0,0 +1,16 @@
+//! Prints the constraint summary of the recursion chips, and writes it to the snapshot file that
+//! the tests compare against.
+
+use sp1_recursion_core::chips::constraint_summary::{
+    constraint_summary, constraint_summary_snapshot_path, render_constraint_summary,
+};
+
+fn main() {
+    let summary = render_constraint_summary(&constraint_summary());
+    print!("{summary}");
+
+    let path = constraint_summary_snapshot_path();
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, summary).unwrap();
+    eprintln!("wrote {}", path.display());
+}
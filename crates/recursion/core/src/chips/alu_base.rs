@@ -4,16 +4,38 @@ use p3_air::{Air, AirBuilder, BaseAir, PairBuilder};
 use p3_baby_bear::BabyBear;
 use p3_field::{AbstractField, Field, PrimeField32};
 use p3_matrix::{dense::RowMajorMatrix, Matrix};
-use p3_maybe_rayon::prelude::*;
 use sp1_core_machine::utils::next_power_of_two;
 use sp1_derive::AlignedBorrow;
-use sp1_stark::air::MachineAir;
-use std::{borrow::BorrowMut, iter::zip};
+use sp1_stark::{air::MachineAir, fill_trace, ColMajorMatrix, RowPadding};
+use std::iter::zip;
 
 pub const NUM_BASE_ALU_ENTRIES_PER_ROW: usize = 4;
 
 #[derive(Default)]
-pub struct BaseAluChip;
+pub struct BaseAluChip {
+    /// Whether the chip supports immediate inputs, at the cost of two extra preprocessed columns
+    /// per entry.
+    immediates: bool,
+}
+
+impl BaseAluChip {
+    /// A chip that supports immediate inputs, which are not read from memory.
+    ///
+    /// This changes the preprocessed trace of the chip, and so the verifying keys of the machines
+    /// that include it.
+    pub fn with_immediates() -> Self {
+        Self { immediates: true }
+    }
+
+    /// The number of preprocessed columns of each entry of a row.
+    fn access_width(&self) -> usize {
+        if self.immediates {
+            NUM_BASE_ALU_IMM_ACCESS_COLS
+        } else {
+            NUM_BASE_ALU_ACCESS_COLS
+        }
+    }
+}
 
 pub const NUM_BASE_ALU_COLS: usize = core::mem::size_of::<BaseAluCols<u8>>();
 
@@ -53,6 +75,17 @@ pub struct BaseAluAccessCols<F: Copy> {
     pub mult: F,
 }
 
+pub const NUM_BASE_ALU_IMM_ACCESS_COLS: usize = core::mem::size_of::<BaseAluImmAccessCols<u8>>();
+
+/// The preprocessed columns of an entry of a chip that supports immediate inputs.
+#[derive(AlignedBorrow, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct BaseAluImmAccessCols<F: Copy> {
+    pub access: BaseAluAccessCols<F>,
+    /// Whether each input is an immediate, whose value is then held by its column of `addrs`.
+    pub is_imm: BaseAluImms<F>,
+}
+
 impl<F: Field> BaseAir<F> for BaseAluChip {
     fn width(&self) -> usize {
         NUM_BASE_ALU_COLS
@@ -69,7 +102,7 @@ impl<F: PrimeField32> MachineAir<F> for BaseAluChip {
     }
 
     fn preprocessed_width(&self) -> usize {
-        NUM_BASE_ALU_PREPROCESSED_COLS
+        self.access_width() * NUM_BASE_ALU_ENTRIES_PER_ROW
     }
 
     fn preprocessed_num_rows(&self, program: &Self::Program, instrs_len: usize) -> Option<usize> {
@@ -90,34 +123,47 @@ impl<F: PrimeField32> MachineAir<F> for BaseAluChip {
 
         let instrs = unsafe {
             std::mem::transmute::<Vec<&BaseAluInstr<F>>, Vec<&BaseAluInstr<BabyBear>>>(
-                program
-                    .inner
-                    .iter()
-                    .filter_map(|instruction| match instruction {
-                        Instruction::BaseAlu(x) => Some(x),
-                        _ => None,
-                    })
-                    .collect::<Vec<_>>(),
+                program.base_alu_instrs(),
             )
         };
-        let padded_nb_rows = self.preprocessed_num_rows(program, instrs.len()).unwrap();
-        let mut values = vec![BabyBear::zero(); padded_nb_rows * NUM_BASE_ALU_PREPROCESSED_COLS];
-
-        // Generate the trace rows & corresponding records for each chunk of events in parallel.
-        let populate_len = instrs.len() * NUM_BASE_ALU_ACCESS_COLS;
-        values[..populate_len].par_chunks_mut(NUM_BASE_ALU_ACCESS_COLS).zip_eq(instrs).for_each(
-            |(row, instr)| {
-                let access: &mut BaseAluAccessCols<_> = row.borrow_mut();
-                unsafe {
+        let height = self.preprocessed_num_rows(program, instrs.len()).unwrap();
+        let width = <Self as MachineAir<F>>::preprocessed_width(self);
+        let trace = if self.immediates {
+            fill_trace(
+                &instrs,
+                height,
+                width,
+                RowPadding::Zero,
+                |instr, cols: &mut BaseAluImmAccessCols<BabyBear>| {
+                    unsafe {
+                        crate::sys::alu_base_instr_to_row_babybear(instr, &mut cols.access);
+                    }
+                    cols.is_imm = BaseAluImms {
+                        in1: BabyBear::from_bool(instr.imms.in1),
+                        in2: BabyBear::from_bool(instr.imms.in2),
+                    };
+                },
+            )
+        } else {
+            assert!(
+                instrs.iter().all(|instr| instr.imms == BaseAluImms::default()),
+                "immediate inputs need a base field ALU chip with immediates enabled"
+            );
+            fill_trace(
+                &instrs,
+                height,
+                width,
+                RowPadding::Zero,
+                |instr, access: &mut BaseAluAccessCols<BabyBear>| unsafe {
                     crate::sys::alu_base_instr_to_row_babybear(instr, access);
-                }
-            },
-        );
+                },
+            )
+        };
 
         // Convert the trace to a row major matrix.
         Some(RowMajorMatrix::new(
-            unsafe { std::mem::transmute::<Vec<BabyBear>, Vec<F>>(values) },
-            NUM_BASE_ALU_PREPROCESSED_COLS,
+            unsafe { std::mem::transmute::<Vec<BabyBear>, Vec<F>>(trace.values) },
+            width,
         ))
     }
 
@@ -135,42 +181,42 @@ impl<F: PrimeField32> MachineAir<F> for BaseAluChip {
     }
 
     fn generate_trace(&self, input: &Self::Record, _: &mut Self::Record) -> RowMajorMatrix<F> {
-        assert_eq!(
-            std::any::TypeId::of::<F>(),
-            std::any::TypeId::of::<BabyBear>(),
-            "generate_trace only supports BabyBear field"
-        );
-
-        let events = unsafe {
-            std::mem::transmute::<&Vec<BaseAluIo<F>>, &Vec<BaseAluIo<BabyBear>>>(
-                &input.base_alu_events,
-            )
-        };
-        let padded_nb_rows = self.num_rows(input).unwrap();
-        let mut values = vec![BabyBear::zero(); padded_nb_rows * NUM_BASE_ALU_COLS];
-
-        // Generate the trace rows & corresponding records for each chunk of events in parallel.
-        let populate_len = events.len() * NUM_BASE_ALU_VALUE_COLS;
-        values[..populate_len].par_chunks_mut(NUM_BASE_ALU_VALUE_COLS).zip_eq(events).for_each(
-            |(row, &vals)| {
-                let cols: &mut BaseAluValueCols<_> = row.borrow_mut();
-                unsafe {
-                    crate::sys::alu_base_event_to_row_babybear(&vals, cols);
-                }
-            },
+        #[cfg(all(debug_assertions, feature = "debug"))]
+        debug_check_multiplicities("BaseAlu", &input.program, |instruction| {
+            matches!(instruction, Instruction::BaseAlu(_))
+        });
+
+        let mut values = vec![F::zero(); self.num_rows(input).unwrap() * NUM_BASE_ALU_COLS];
+        input.base_alu_events.write_rows(
+            &mut values,
+            NUM_BASE_ALU_COLS,
+            NUM_BASE_ALU_ENTRIES_PER_ROW,
         );
+        RowMajorMatrix::new(values, NUM_BASE_ALU_COLS)
+    }
 
-        // Convert the trace to a row major matrix.
-        RowMajorMatrix::new(
-            unsafe { std::mem::transmute::<Vec<BabyBear>, Vec<F>>(values) },
-            NUM_BASE_ALU_COLS,
-        )
+    fn generate_trace_col_major(
+        &self,
+        input: &Self::Record,
+        _: &mut Self::Record,
+    ) -> ColMajorMatrix<F> {
+        let mut trace = ColMajorMatrix::zeros(self.num_rows(input).unwrap(), NUM_BASE_ALU_COLS);
+        input.base_alu_events.write_col_major(&mut trace, NUM_BASE_ALU_ENTRIES_PER_ROW);
+        trace
     }
 
     fn included(&self, _record: &Self::Record) -> bool {
         true
     }
 
+    fn is_empty_shard(&self, shard: &Self::Record) -> bool {
+        shard.base_alu_events.is_empty() && shard.program.is_prunable(|index| &index.base_alu)
+    }
+
+    fn is_empty_program(&self, program: &Self::Program) -> bool {
+        program.is_prunable(|index| &index.base_alu)
+    }
+
     fn local_only(&self) -> bool {
         true
     }
@@ -186,13 +232,14 @@ where
         let local: &BaseAluCols<AB::Var> = (*local).borrow();
         let prep = builder.preprocessed();
         let prep_local = prep.row_slice(0);
-        let prep_local: &BaseAluPreprocessedCols<AB::Var> = (*prep_local).borrow();
 
-        for (
-            BaseAluValueCols { vals: BaseAluIo { out, in1, in2 } },
-            BaseAluAccessCols { addrs, is_add, is_sub, is_mul, is_div, mult },
-        ) in zip(local.values, prep_local.accesses)
+        for (BaseAluValueCols { vals: BaseAluIo { out, in1, in2 } }, prep_entry) in
+            zip(local.values, prep_local.chunks_exact(self.access_width()))
         {
+            let access: &BaseAluAccessCols<AB::Var> =
+                prep_entry[..NUM_BASE_ALU_ACCESS_COLS].borrow();
+            let BaseAluAccessCols { addrs, is_add, is_sub, is_mul, is_div, mult } = *access;
+
             // Check exactly one flag is enabled.
             let is_real = is_add + is_sub + is_mul + is_div;
             builder.assert_bool(is_real.clone());
@@ -202,9 +249,21 @@ where
             builder.when(is_mul).assert_eq(out, in1 * in2);
             builder.when(is_div).assert_eq(in2 * out, in1);
 
-            builder.receive_single(addrs.in1, in1, is_real.clone());
+            let (mult1, mult2) = if self.immediates {
+                // An immediate input is equal to the value held in place of its address, and it is
+                // not received from memory.
+                let prep_entry: &BaseAluImmAccessCols<AB::Var> = prep_entry.borrow();
+                let BaseAluImms { in1: imm1, in2: imm2 } = prep_entry.is_imm;
+                builder.when(imm1).assert_eq(in1, addrs.in1.0);
+                builder.when(imm2).assert_eq(in2, addrs.in2.0);
+                (is_real.clone() - imm1, is_real - imm2)
+            } else {
+                (is_real.clone(), is_real)
+            };
+
+            builder.receive_single(addrs.in1, in1, mult1);
 
-            builder.receive_single(addrs.in2, in2, is_real);
+            builder.receive_single(addrs.in2, in2, mult2);
 
             builder.send_single(addrs.out, out, mult);
         }
@@ -213,13 +272,15 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::{chips::test_fixtures, runtime::instruction as instr};
+    use crate::{chips::test_fixtures::trace_parity_tests, runtime::instruction as instr};
     use machine::tests::test_recursion_linear_program;
     use p3_baby_bear::BabyBear;
     use p3_field::AbstractField;
     use p3_matrix::dense::RowMajorMatrix;
+    use p3_maybe_rayon::prelude::*;
     use rand::{rngs::StdRng, Rng, SeedableRng};
     use sp1_stark::{baby_bear_poseidon2::BabyBearPoseidon2, StarkGenericConfig};
+    use std::borrow::BorrowMut;
 
     use super::*;
 
@@ -227,12 +288,12 @@ mod tests {
         input: &ExecutionRecord<BabyBear>,
         _: &mut ExecutionRecord<BabyBear>,
     ) -> RowMajorMatrix<BabyBear> {
-        let events = &input.base_alu_events;
-        let padded_nb_rows = BaseAluChip.num_rows(input).unwrap();
+        let events = input.base_alu_events.to_vec();
+        let padded_nb_rows = BaseAluChip::default().num_rows(input).unwrap();
         let mut values = vec![BabyBear::zero(); padded_nb_rows * NUM_BASE_ALU_COLS];
 
         let populate_len = events.len() * NUM_BASE_ALU_VALUE_COLS;
-        values[..populate_len].par_chunks_mut(NUM_BASE_ALU_VALUE_COLS).zip_eq(events).for_each(
+        values[..populate_len].par_chunks_mut(NUM_BASE_ALU_VALUE_COLS).zip_eq(&events).for_each(
             |(row, &vals)| {
                 let cols: &mut BaseAluValueCols<_> = row.borrow_mut();
                 *cols = BaseAluValueCols { vals };
@@ -242,16 +303,6 @@ mod tests {
         RowMajorMatrix::new(values, NUM_BASE_ALU_COLS)
     }
 
-    #[test]
-    fn generate_trace() {
-        let shard = test_fixtures::shard();
-        let mut execution_record = test_fixtures::default_execution_record();
-        let trace = BaseAluChip.generate_trace(&shard, &mut execution_record);
-        assert!(trace.height() >= test_fixtures::MIN_TEST_CASES);
-
-        assert_eq!(trace, generate_trace_reference(&shard, &mut execution_record));
-    }
-
     fn generate_preprocessed_trace_reference(
         program: &RecursionProgram<BabyBear>,
     ) -> RowMajorMatrix<BabyBear> {
@@ -265,22 +316,33 @@ mod tests {
                 _ => None,
             })
             .collect::<Vec<_>>();
-        let padded_nb_rows = BaseAluChip.preprocessed_num_rows(program, instrs.len()).unwrap();
-        let mut values = vec![F::zero(); padded_nb_rows * NUM_BASE_ALU_PREPROCESSED_COLS];
-
-        let populate_len = instrs.len() * NUM_BASE_ALU_ACCESS_COLS;
-        values[..populate_len].par_chunks_mut(NUM_BASE_ALU_ACCESS_COLS).zip_eq(instrs).for_each(
-            |(row, instr)| {
-                let BaseAluInstr { opcode, mult, addrs } = instr;
-                let access: &mut BaseAluAccessCols<_> = row.borrow_mut();
-                *access = BaseAluAccessCols {
-                    addrs: addrs.to_owned(),
-                    is_add: F::from_bool(false),
-                    is_sub: F::from_bool(false),
-                    is_mul: F::from_bool(false),
-                    is_div: F::from_bool(false),
-                    mult: mult.to_owned(),
+        let padded_nb_rows =
+            BaseAluChip::with_immediates().preprocessed_num_rows(program, instrs.len()).unwrap();
+        let width = NUM_BASE_ALU_IMM_ACCESS_COLS * NUM_BASE_ALU_ENTRIES_PER_ROW;
+        let mut values = vec![F::zero(); padded_nb_rows * width];
+
+        let populate_len = instrs.len() * NUM_BASE_ALU_IMM_ACCESS_COLS;
+        values[..populate_len]
+            .par_chunks_mut(NUM_BASE_ALU_IMM_ACCESS_COLS)
+            .zip_eq(instrs)
+            .for_each(|(row, instr)| {
+                let BaseAluInstr { opcode, mult, addrs, imms } = instr;
+                let cols: &mut BaseAluImmAccessCols<_> = row.borrow_mut();
+                *cols = BaseAluImmAccessCols {
+                    access: BaseAluAccessCols {
+                        addrs: addrs.to_owned(),
+                        is_add: F::from_bool(false),
+                        is_sub: F::from_bool(false),
+                        is_mul: F::from_bool(false),
+                        is_div: F::from_bool(false),
+                        mult: mult.to_owned(),
+                    },
+                    is_imm: BaseAluImms {
+                        in1: F::from_bool(imms.in1),
+                        in2: F::from_bool(imms.in2),
+                    },
                 };
+                let access = &mut cols.access;
                 let target_flag = match opcode {
                     BaseAluOpcode::AddF => &mut access.is_add,
                     BaseAluOpcode::SubF => &mut access.is_sub,
@@ -288,20 +350,16 @@ mod tests {
                     BaseAluOpcode::DivF => &mut access.is_div,
                 };
                 *target_flag = F::from_bool(true);
-            },
-        );
+            });
 
-        RowMajorMatrix::new(values, NUM_BASE_ALU_PREPROCESSED_COLS)
+        RowMajorMatrix::new(values, width)
     }
 
-    #[test]
-    #[ignore = "Failing due to merge conflicts. Will be fixed shortly."]
-    fn generate_preprocessed_trace() {
-        let program = test_fixtures::program();
-        let trace = BaseAluChip.generate_preprocessed_trace(&program).unwrap();
-        assert!(trace.height() >= test_fixtures::MIN_TEST_CASES);
-
-        assert_eq!(trace, generate_preprocessed_trace_reference(&program));
+    trace_parity_tests! {
+        chip: BaseAluChip::with_immediates(),
+        trace: generate_trace_reference,
+        #[ignore = "Failing due to merge conflicts. Will be fixed shortly."]
+        preprocessed: generate_preprocessed_trace_reference,
     }
 
     #[test]
@@ -338,4 +396,56 @@ mod tests {
 
         test_recursion_linear_program(instructions);
     }
+
+    #[test]
+    pub fn four_ops_with_immediates() {
+        type SC = BabyBearPoseidon2;
+        type F = <SC as StarkGenericConfig>::Val;
+
+        let mut rng = StdRng::seed_from_u64(0xDEADBEEF);
+        let mut addr = 0;
+
+        let instructions = (0..1000)
+            .flat_map(|_| {
+                let quot: F = rng.gen();
+                let in2: F = rng.gen();
+                let in1 = in2 * quot;
+                let alloc_size = 6;
+                let a = (0..alloc_size).map(|x| x + addr).collect::<Vec<_>>();
+                addr += alloc_size;
+
+                let ops = [
+                    (BaseAluOpcode::AddF, in1 + in2),
+                    (BaseAluOpcode::SubF, in1 - in2),
+                    (BaseAluOpcode::MulF, in1 * in2),
+                    (BaseAluOpcode::DivF, quot),
+                ];
+                let imms: [BaseAluImms<bool>; 4] =
+                    core::array::from_fn(|_| BaseAluImms { in1: rng.gen(), in2: rng.gen() });
+                // The inputs are only read by the operations that do not take them as immediates.
+                let mult1 = imms.iter().filter(|imms| !imms.in1).count() as u32;
+                let mult2 = imms.iter().filter(|imms| !imms.in2).count() as u32;
+                let operand = |addr, value: F, imm| match imm {
+                    true => value.as_canonical_u32(),
+                    false => addr,
+                };
+
+                let writes = [
+                    instr::mem_single(MemAccessKind::Write, mult1, a[0], in1),
+                    instr::mem_single(MemAccessKind::Write, mult2, a[1], in2),
+                ];
+                let ops = zip(ops, imms).enumerate().flat_map(|(i, ((opcode, out), imms))| {
+                    let in1 = operand(a[0], in1, imms.in1);
+                    let in2 = operand(a[1], in2, imms.in2);
+                    [
+                        instr::base_alu_imm(opcode, 1, a[i + 2], in1, in2, imms),
+                        instr::mem_single(MemAccessKind::Read, 1, a[i + 2], out),
+                    ]
+                });
+                writes.into_iter().chain(ops).collect::<Vec<_>>()
+            })
+            .collect::<Vec<Instruction<F>>>();
+
+        test_recursion_linear_program(instructions);
+    }
 }
@@ -3,11 +3,13 @@ use p3_air::{Air, BaseAir, PairBuilder};
 use p3_baby_bear::BabyBear;
 use p3_field::{extension::BinomiallyExtendable, AbstractField, Field, PrimeField32};
 use p3_matrix::{dense::RowMajorMatrix, Matrix};
-use p3_maybe_rayon::prelude::*;
 use sp1_core_machine::utils::next_power_of_two;
 use sp1_derive::AlignedBorrow;
-use sp1_stark::air::{ExtensionAirBuilder, MachineAir};
-use std::{borrow::BorrowMut, iter::zip};
+use sp1_stark::{
+    air::{ExtensionAirBuilder, MachineAir},
+    fill_trace, ColMajorMatrix, RowPadding,
+};
+use std::iter::zip;
 
 use crate::{builder::SP1RecursionAirBuilder, *};
 
@@ -23,7 +25,7 @@ pub const NUM_EXT_ALU_COLS: usize = core::mem::size_of::<ExtAluCols<u8>>();
 pub struct ExtAluCols<F: Copy> {
     pub values: [ExtAluValueCols<F>; NUM_EXT_ALU_ENTRIES_PER_ROW],
 }
-const NUM_EXT_ALU_VALUE_COLS: usize = core::mem::size_of::<ExtAluValueCols<u8>>();
+pub const NUM_EXT_ALU_VALUE_COLS: usize = core::mem::size_of::<ExtAluValueCols<u8>>();
 
 #[derive(AlignedBorrow, Debug, Clone, Copy)]
 #[repr(C)]
@@ -89,33 +91,22 @@ impl<F: PrimeField32 + BinomiallyExtendable<D>> MachineAir<F> for ExtAluChip {
 
         let instrs = unsafe {
             std::mem::transmute::<Vec<&ExtAluInstr<F>>, Vec<&ExtAluInstr<BabyBear>>>(
-                program
-                    .inner
-                    .iter()
-                    .filter_map(|instruction| match instruction {
-                        Instruction::ExtAlu(x) => Some(x),
-                        _ => None,
-                    })
-                    .collect::<Vec<_>>(),
+                program.ext_alu_instrs(),
             )
         };
-        let padded_nb_rows = self.preprocessed_num_rows(program, instrs.len()).unwrap();
-        let mut values = vec![BabyBear::zero(); padded_nb_rows * NUM_EXT_ALU_PREPROCESSED_COLS];
-
-        // Generate the trace rows & corresponding records for each chunk of events in parallel.
-        let populate_len = instrs.len() * NUM_EXT_ALU_ACCESS_COLS;
-        values[..populate_len].par_chunks_mut(NUM_EXT_ALU_ACCESS_COLS).zip_eq(instrs).for_each(
-            |(row, instr)| {
-                let access: &mut ExtAluAccessCols<_> = row.borrow_mut();
-                unsafe {
-                    crate::sys::alu_ext_instr_to_row_babybear(instr, access);
-                }
+        let trace = fill_trace(
+            &instrs,
+            self.preprocessed_num_rows(program, instrs.len()).unwrap(),
+            NUM_EXT_ALU_PREPROCESSED_COLS,
+            RowPadding::Zero,
+            |instr, access: &mut ExtAluAccessCols<BabyBear>| unsafe {
+                crate::sys::alu_ext_instr_to_row_babybear(instr, access);
             },
         );
 
         // Convert the trace to a row major matrix.
         Some(RowMajorMatrix::new(
-            unsafe { std::mem::transmute::<Vec<BabyBear>, Vec<F>>(values) },
+            unsafe { std::mem::transmute::<Vec<BabyBear>, Vec<F>>(trace.values) },
             NUM_EXT_ALU_PREPROCESSED_COLS,
         ))
     }
@@ -135,42 +126,38 @@ impl<F: PrimeField32 + BinomiallyExtendable<D>> MachineAir<F> for ExtAluChip {
     }
 
     fn generate_trace(&self, input: &Self::Record, _: &mut Self::Record) -> RowMajorMatrix<F> {
-        assert_eq!(
-            std::any::TypeId::of::<F>(),
-            std::any::TypeId::of::<BabyBear>(),
-            "generate_trace only supports BabyBear field"
-        );
-
-        let events = unsafe {
-            std::mem::transmute::<&Vec<ExtAluIo<Block<F>>>, &Vec<ExtAluIo<Block<BabyBear>>>>(
-                &input.ext_alu_events,
-            )
-        };
-        let padded_nb_rows = self.num_rows(input).unwrap();
-        let mut values = vec![BabyBear::zero(); padded_nb_rows * NUM_EXT_ALU_COLS];
+        #[cfg(all(debug_assertions, feature = "debug"))]
+        debug_check_multiplicities("ExtAlu", &input.program, |instruction| {
+            matches!(instruction, Instruction::ExtAlu(_))
+        });
 
-        // Generate the trace rows & corresponding records for each chunk of events in parallel.
-        let populate_len = events.len() * NUM_EXT_ALU_VALUE_COLS;
-        values[..populate_len].par_chunks_mut(NUM_EXT_ALU_VALUE_COLS).zip_eq(events).for_each(
-            |(row, &vals)| {
-                let cols: &mut ExtAluValueCols<_> = row.borrow_mut();
-                unsafe {
-                    crate::sys::alu_ext_event_to_row_babybear(&vals, cols);
-                }
-            },
-        );
+        let mut values = vec![F::zero(); self.num_rows(input).unwrap() * NUM_EXT_ALU_COLS];
+        input.ext_alu_events.write_rows(&mut values, NUM_EXT_ALU_COLS, NUM_EXT_ALU_ENTRIES_PER_ROW);
+        RowMajorMatrix::new(values, NUM_EXT_ALU_COLS)
+    }
 
-        // Convert the trace to a row major matrix.
-        RowMajorMatrix::new(
-            unsafe { std::mem::transmute::<Vec<BabyBear>, Vec<F>>(values) },
-            NUM_EXT_ALU_COLS,
-        )
+    fn generate_trace_col_major(
+        &self,
+        input: &Self::Record,
+        _: &mut Self::Record,
+    ) -> ColMajorMatrix<F> {
+        let mut trace = ColMajorMatrix::zeros(self.num_rows(input).unwrap(), NUM_EXT_ALU_COLS);
+        input.ext_alu_events.write_col_major(&mut trace, NUM_EXT_ALU_ENTRIES_PER_ROW);
+        trace
     }
 
     fn included(&self, _record: &Self::Record) -> bool {
         true
     }
 
+    fn is_empty_shard(&self, shard: &Self::Record) -> bool {
+        shard.ext_alu_events.is_empty() && shard.program.is_prunable(|index| &index.ext_alu)
+    }
+
+    fn is_empty_program(&self, program: &Self::Program) -> bool {
+        program.is_prunable(|index| &index.ext_alu)
+    }
+
     fn local_only(&self) -> bool {
         true
     }
@@ -219,14 +206,16 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::{chips::test_fixtures, runtime::instruction as instr};
+    use crate::{chips::test_fixtures::trace_parity_tests, runtime::instruction as instr};
     use machine::tests::test_recursion_linear_program;
     use p3_baby_bear::BabyBear;
     use p3_field::{extension::BinomialExtensionField, AbstractExtensionField, AbstractField};
     use p3_matrix::dense::RowMajorMatrix;
+    use p3_maybe_rayon::prelude::*;
     use rand::{rngs::StdRng, Rng, SeedableRng};
     use sp1_stark::StarkGenericConfig;
     use stark::BabyBearPoseidon2Outer;
+    use std::borrow::BorrowMut;
 
     use super::*;
 
@@ -234,12 +223,12 @@ mod tests {
         input: &ExecutionRecord<BabyBear>,
         _: &mut ExecutionRecord<BabyBear>,
     ) -> RowMajorMatrix<BabyBear> {
-        let events = &input.ext_alu_events;
+        let events = input.ext_alu_events.to_vec();
         let padded_nb_rows = ExtAluChip.num_rows(input).unwrap();
         let mut values = vec![BabyBear::zero(); padded_nb_rows * NUM_EXT_ALU_COLS];
 
         let populate_len = events.len() * NUM_EXT_ALU_VALUE_COLS;
-        values[..populate_len].par_chunks_mut(NUM_EXT_ALU_VALUE_COLS).zip_eq(events).for_each(
+        values[..populate_len].par_chunks_mut(NUM_EXT_ALU_VALUE_COLS).zip_eq(&events).for_each(
             |(row, &vals)| {
                 let cols: &mut ExtAluValueCols<_> = row.borrow_mut();
                 *cols = ExtAluValueCols { vals };
@@ -249,16 +238,6 @@ mod tests {
         RowMajorMatrix::new(values, NUM_EXT_ALU_COLS)
     }
 
-    #[test]
-    fn generate_trace() {
-        let shard = test_fixtures::shard();
-        let mut execution_record = test_fixtures::default_execution_record();
-        let trace = ExtAluChip.generate_trace(&shard, &mut execution_record);
-        assert!(trace.height() >= test_fixtures::MIN_TEST_CASES);
-
-        assert_eq!(trace, generate_trace_reference(&shard, &mut execution_record));
-    }
-
     fn generate_preprocessed_trace_reference(
         program: &RecursionProgram<BabyBear>,
     ) -> RowMajorMatrix<BabyBear> {
@@ -301,14 +280,11 @@ mod tests {
         RowMajorMatrix::new(values, NUM_EXT_ALU_PREPROCESSED_COLS)
     }
 
-    #[test]
-    #[ignore = "Failing due to merge conflicts. Will be fixed shortly."]
-    fn generate_preprocessed_trace() {
-        let program = test_fixtures::program();
-        let trace = ExtAluChip.generate_preprocessed_trace(&program).unwrap();
-        assert!(trace.height() >= test_fixtures::MIN_TEST_CASES);
-
-        assert_eq!(trace, generate_preprocessed_trace_reference(&program));
+    trace_parity_tests! {
+        chip: ExtAluChip,
+        trace: generate_trace_reference,
+        #[ignore = "Failing due to merge conflicts. Will be fixed shortly."]
+        preprocessed: generate_preprocessed_trace_reference,
     }
 
     #[test]
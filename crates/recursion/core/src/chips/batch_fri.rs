@@ -2,7 +2,7 @@
 
 use crate::{
     air::Block, builder::SP1RecursionAirBuilder, Address, BatchFRIEvent, BatchFRIInstr,
-    ExecutionRecord, Instruction,
+    ExecutionRecord,
 };
 use core::borrow::Borrow;
 use itertools::Itertools;
@@ -78,20 +78,13 @@ impl<F: PrimeField32, const DEGREE: usize> MachineAir<F> for BatchFRIChip<DEGREE
 
         let mut rows = Vec::new();
         let instrs = unsafe {
-            std::mem::transmute::<Vec<&Box<BatchFRIInstr<F>>>, Vec<&Box<BatchFRIInstr<BabyBear>>>>(
-                program
-                    .inner
-                    .iter()
-                    .filter_map(|instruction| match instruction {
-                        Instruction::BatchFRI(x) => Some(x),
-                        _ => None,
-                    })
-                    .collect::<Vec<_>>(),
+            std::mem::transmute::<Vec<&BatchFRIInstr<F>>, Vec<&BatchFRIInstr<BabyBear>>>(
+                program.batch_fri_instrs(),
             )
         };
         instrs.iter().for_each(|instruction| {
             let BatchFRIInstr { base_vec_addrs: _, ext_single_addrs: _, ext_vec_addrs, acc_mult } =
-                instruction.as_ref();
+                instruction;
             let len: usize = ext_vec_addrs.p_at_z.len();
             let mut row_add = vec![[BabyBear::zero(); NUM_BATCH_FRI_PREPROCESSED_COLS]; len];
             debug_assert_eq!(*acc_mult, BabyBear::one());
@@ -183,6 +176,14 @@ impl<F: PrimeField32, const DEGREE: usize> MachineAir<F> for BatchFRIChip<DEGREE
     fn included(&self, _record: &Self::Record) -> bool {
         true
     }
+
+    fn is_empty_shard(&self, shard: &Self::Record) -> bool {
+        shard.batch_fri_events.is_empty() && shard.program.is_prunable(|index| &index.batch_fri)
+    }
+
+    fn is_empty_program(&self, program: &Self::Program) -> bool {
+        program.is_prunable(|index| &index.batch_fri)
+    }
 }
 
 impl<const DEGREE: usize> BatchFRIChip<DEGREE> {
@@ -259,7 +260,10 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::{chips::test_fixtures, Instruction, RecursionProgram};
+    use crate::{
+        chips::test_fixtures::trace_parity_tests, BatchFRIBaseVecIo, BatchFRIExtSingleIo,
+        BatchFRIExtVecIo, Instruction, RecursionProgram,
+    };
     use p3_baby_bear::BabyBear;
     use p3_field::AbstractField;
     use p3_matrix::dense::RowMajorMatrix;
@@ -268,6 +272,44 @@ mod tests {
 
     const DEGREE: usize = 2;
 
+    #[test]
+    fn generate_batch_fri_trace() {
+        type F = BabyBear;
+
+        let block = |x: u32| Block::from([F::from_canonical_u32(x); 4]);
+        let shard = ExecutionRecord {
+            batch_fri_events: (0..3)
+                .map(|i| BatchFRIEvent {
+                    base_vec: BatchFRIBaseVecIo { p_at_x: F::from_canonical_u32(4 * i) },
+                    ext_single: BatchFRIExtSingleIo { acc: block(4 * i + 1) },
+                    ext_vec: BatchFRIExtVecIo {
+                        p_at_z: block(4 * i + 2),
+                        alpha_pow: block(4 * i + 3),
+                    },
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let trace: RowMajorMatrix<F> =
+            BatchFRIChip::<DEGREE>.generate_trace(&shard, &mut ExecutionRecord::default());
+
+        // Every event gets a row, and the trace is padded to the minimum height with zeroes.
+        assert_eq!(trace.height(), 16);
+        for (i, row) in trace.rows().enumerate() {
+            let row = row.collect_vec();
+            let cols: &BatchFRICols<F> = row.as_slice().borrow();
+            match shard.batch_fri_events.get(i) {
+                Some(event) => {
+                    assert_eq!(cols.acc, event.ext_single.acc);
+                    assert_eq!(cols.alpha_pow, event.ext_vec.alpha_pow);
+                    assert_eq!(cols.p_at_z, event.ext_vec.p_at_z);
+                    assert_eq!(cols.p_at_x, event.base_vec.p_at_x);
+                }
+                None => assert!(row.iter().all(|x| *x == F::zero())),
+            }
+        }
+    }
+
     fn generate_trace_reference<const DEGREE: usize>(
         input: &ExecutionRecord<BabyBear>,
         _: &mut ExecutionRecord<BabyBear>,
@@ -296,16 +338,6 @@ mod tests {
         RowMajorMatrix::new(rows.into_iter().flatten().collect(), NUM_BATCH_FRI_COLS)
     }
 
-    #[test]
-    fn generate_trace() {
-        let shard = test_fixtures::shard();
-        let mut execution_record = test_fixtures::default_execution_record();
-        let trace = BatchFRIChip::<DEGREE>.generate_trace(&shard, &mut execution_record);
-        assert!(trace.height() >= test_fixtures::MIN_TEST_CASES);
-
-        assert_eq!(trace, generate_trace_reference::<DEGREE>(&shard, &mut execution_record));
-    }
-
     fn generate_preprocessed_trace_reference<const DEGREE: usize>(
         program: &RecursionProgram<BabyBear>,
     ) -> RowMajorMatrix<BabyBear> {
@@ -347,13 +379,10 @@ mod tests {
         RowMajorMatrix::new(rows.into_iter().flatten().collect(), NUM_BATCH_FRI_PREPROCESSED_COLS)
     }
 
-    #[test]
-    #[ignore = "Failing due to merge conflicts. Will be fixed shortly."]
-    fn generate_preprocessed_trace() {
-        let program = test_fixtures::program();
-        let trace = BatchFRIChip::<DEGREE>.generate_preprocessed_trace(&program).unwrap();
-        assert!(trace.height() >= test_fixtures::MIN_TEST_CASES);
-
-        assert_eq!(trace, generate_preprocessed_trace_reference::<DEGREE>(&program));
+    trace_parity_tests! {
+        chip: BatchFRIChip::<DEGREE>,
+        trace: generate_trace_reference::<DEGREE>,
+        #[ignore = "Failing due to merge conflicts. Will be fixed shortly."]
+        preprocessed: generate_preprocessed_trace_reference::<DEGREE>,
     }
 }
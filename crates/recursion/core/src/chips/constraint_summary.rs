@@ -0,0 +1,131 @@
+use std::{fmt, path::PathBuf};
+
+use hashbrown::HashSet;
+use p3_air::Air;
+use p3_baby_bear::BabyBear;
+use p3_uni_stark::{get_symbolic_constraints, SymbolicAirBuilder};
+use sp1_stark::{air::MachineAir, baby_bear_poseidon2::BabyBearPoseidon2, Chip};
+
+use crate::machine::RecursionAir;
+
+/// The constraints and interactions of a chip of the recursion machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChipConstraintSummary {
+    pub chip: String,
+    /// The number of constraints of the AIR, not counting the permutation constraints.
+    pub constraints: usize,
+    /// The largest degree of the constraints of the AIR.
+    pub max_degree: usize,
+    /// The relative log degree of the quotient polynomial, which accounts for the interactions.
+    pub log_quotient_degree: usize,
+    pub sends: usize,
+    pub receives: usize,
+}
+
+impl ChipConstraintSummary {
+    fn new<A>(chip: &Chip<BabyBear, A>) -> Self
+    where
+        A: MachineAir<BabyBear> + Air<SymbolicAirBuilder<BabyBear>>,
+    {
+        let constraints = get_symbolic_constraints(
+            &chip.air,
+            chip.air.preprocessed_width(),
+            chip.air.num_public_values(),
+        );
+        Self {
+            chip: chip.name(),
+            constraints: constraints.len(),
+            max_degree: constraints.iter().map(|c| c.degree_multiple()).max().unwrap_or(0),
+            log_quotient_degree: chip.log_quotient_degree(),
+            sends: chip.sends().len(),
+            receives: chip.receives().len(),
+        }
+    }
+}
+
+impl fmt::Display for ChipConstraintSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { chip, constraints, max_degree, log_quotient_degree, sends, receives } = self;
+        write!(
+            f,
+            "{chip:<24} constraints={constraints} max_degree={max_degree} \
+             log_quotient_degree={log_quotient_degree} sends={sends} receives={receives}"
+        )
+    }
+}
+
+/// Summarizes the constraints of every chip registered in the recursion machines, over BabyBear.
+///
+/// The chips of the machine with all the chips and the wide Poseidon2 chip are summarized with the
+/// degree of the compress machine, followed by the skinny Poseidon2 chip with the degree of the
+/// wrap machine.
+pub fn constraint_summary() -> Vec<ChipConstraintSummary> {
+    let wide =
+        RecursionAir::<BabyBear, 3>::machine_wide_with_all_chips(BabyBearPoseidon2::default());
+    let skinny = RecursionAir::<BabyBear, 9>::machine_skinny_with_all_chips(
+        BabyBearPoseidon2::ultra_compressed(),
+    );
+
+    let mut seen = HashSet::new();
+    wide.chips()
+        .iter()
+        .map(ChipConstraintSummary::new)
+        .chain(skinny.chips().iter().map(ChipConstraintSummary::new))
+        .filter(|summary| seen.insert(summary.chip.clone()))
+        .collect()
+}
+
+/// Renders the summaries one chip per line, in the format of the snapshot file.
+pub fn render_constraint_summary(summaries: &[ChipConstraintSummary]) -> String {
+    summaries.iter().map(|summary| format!("{summary}\n")).collect()
+}
+
+/// The path of the snapshot file that the constraint summary is checked against.
+pub fn constraint_summary_snapshot_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("snapshots/constraint_summary.txt")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Compares the constraint summary against the snapshot, which is written on the first run.
+    ///
+    /// To accept a change in the numbers, run the `constraint_summary` binary.
+    #[test]
+    fn constraint_summary_snapshot() {
+        let actual = render_constraint_summary(&constraint_summary());
+        let path = constraint_summary_snapshot_path();
+        let Ok(expected) = std::fs::read_to_string(&path) else {
+            assert!(std::env::var_os("CI").is_none(), "missing snapshot {}", path.display());
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, &actual).unwrap();
+            return;
+        };
+        if actual != expected {
+            let diff = expected
+                .lines()
+                .filter(|line| !actual.lines().any(|l| l == *line))
+                .map(|line| format!("- {line}\n"))
+                .chain(
+                    actual
+                        .lines()
+                        .filter(|line| !expected.lines().any(|l| l == *line))
+                        .map(|line| format!("+ {line}\n")),
+                )
+                .collect::<String>();
+            panic!("the constraint summary changed from {}:\n{diff}", path.display());
+        }
+    }
+
+    #[test]
+    fn constraint_summary_covers_all_chips() {
+        let names =
+            constraint_summary().into_iter().map(|summary| summary.chip).collect::<Vec<_>>();
+        for chip in ["Select", "BaseAlu", "ExtAlu", "MemoryConst", "MemoryVar", "PublicValues"] {
+            assert!(names.iter().any(|name| name == chip), "{chip} missing from {names:?}");
+        }
+        assert!(names.iter().any(|name| name.starts_with("Poseidon2Wide")));
+        assert!(names.iter().any(|name| name.starts_with("Poseidon2Skinny")));
+    }
+}
@@ -0,0 +1,165 @@
+use std::iter::zip;
+
+use p3_matrix::{dense::RowMajorMatrix, Matrix};
+use rayon::ThreadPoolBuilder;
+use sp1_stark::{air::MachineAir, StarkGenericConfig, StarkMachine, Val};
+
+use crate::{ExecutionRecord, RecursionProgram};
+
+/// The main and preprocessed traces of a chip.
+#[derive(Debug, Clone)]
+pub struct ChipTraces<F> {
+    pub chip: String,
+    pub preprocessed: Option<RowMajorMatrix<F>>,
+    pub main: RowMajorMatrix<F>,
+}
+
+/// The thread counts that the traces are compared across: a single thread, four threads, and one
+/// thread per core.
+pub fn determinism_thread_counts() -> Vec<usize> {
+    let mut counts = vec![1, 4, num_cpus::get()];
+    counts.sort_unstable();
+    counts.dedup();
+    counts
+}
+
+/// Executes the program with `execute` and generates the traces of every chip of the machine, in a
+/// thread pool with `num_threads` threads.
+pub fn generate_traces_with_threads<SC, A>(
+    machine: &StarkMachine<SC, A>,
+    program: &RecursionProgram<Val<SC>>,
+    execute: &(impl Fn() -> ExecutionRecord<Val<SC>> + Sync),
+    num_threads: usize,
+) -> Vec<ChipTraces<Val<SC>>>
+where
+    SC: StarkGenericConfig,
+    A: MachineAir<Val<SC>, Record = ExecutionRecord<Val<SC>>, Program = RecursionProgram<Val<SC>>>,
+    StarkMachine<SC, A>: Sync,
+{
+    let pool = ThreadPoolBuilder::new().num_threads(num_threads).build().unwrap();
+    pool.install(|| {
+        let record = execute();
+        machine
+            .chips()
+            .iter()
+            .map(|chip| ChipTraces {
+                chip: chip.name(),
+                preprocessed: chip.generate_preprocessed_trace(program),
+                main: chip.generate_trace(&record, &mut ExecutionRecord::default()),
+            })
+            .collect()
+    })
+}
+
+/// Asserts that executing the program and generating the traces of every chip gives the same
+/// values for all the [thread counts](determinism_thread_counts).
+///
+/// Rayon splits the work differently for each thread count, so a trace that depends on the order
+/// in which the work is scheduled is caught here.
+pub fn assert_deterministic_traces<SC, A>(
+    machine: &StarkMachine<SC, A>,
+    program: &RecursionProgram<Val<SC>>,
+    execute: impl Fn() -> ExecutionRecord<Val<SC>> + Sync,
+) where
+    SC: StarkGenericConfig,
+    A: MachineAir<Val<SC>, Record = ExecutionRecord<Val<SC>>, Program = RecursionProgram<Val<SC>>>,
+    StarkMachine<SC, A>: Sync,
+{
+    let thread_counts = determinism_thread_counts();
+    let (&first, rest) = thread_counts.split_first().unwrap();
+    let expected = generate_traces_with_threads(machine, program, &execute, first);
+    for &num_threads in rest {
+        let actual = generate_traces_with_threads(machine, program, &execute, num_threads);
+        for (expected, actual) in zip(&expected, &actual) {
+            let ChipTraces { chip, .. } = expected;
+            let threads = format!("{first} and {num_threads} threads");
+            match (&expected.preprocessed, &actual.preprocessed) {
+                (Some(expected), Some(actual)) => {
+                    assert_same_trace(expected, actual, &format!("{chip} preprocessed"), &threads)
+                }
+                (None, None) => (),
+                _ => panic!("{chip} has a preprocessed trace for only one of {threads}"),
+            }
+            assert_same_trace(&expected.main, &actual.main, &format!("{chip} main"), &threads);
+        }
+    }
+}
+
+fn assert_same_trace<F: PartialEq + Clone + Send + Sync>(
+    expected: &RowMajorMatrix<F>,
+    actual: &RowMajorMatrix<F>,
+    trace: &str,
+    threads: &str,
+) {
+    assert_eq!(
+        (expected.width(), expected.height()),
+        (actual.width(), actual.height()),
+        "the dimensions of the {trace} trace differ between {threads}"
+    );
+    let mismatch = zip(expected.rows(), actual.rows()).position(|(lhs, rhs)| !lhs.eq(rhs));
+    if let Some(row) = mismatch {
+        panic!("the {trace} trace differs at row {row} between {threads}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{array, iter::once, sync::Arc};
+
+    use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+    use sp1_stark::baby_bear_poseidon2::BabyBearPoseidon2;
+
+    use super::*;
+    use crate::{
+        chips::test_fixtures, linear_program, machine::RecursionAir, runtime::instruction as instr,
+        BaseAluOpcode, Instruction, MemAccessKind, Runtime,
+    };
+
+    type SC = BabyBearPoseidon2;
+    type F = BabyBear;
+    type EF = <SC as StarkGenericConfig>::Challenge;
+
+    #[test]
+    fn fixture_traces_are_deterministic() {
+        let program = test_fixtures::program();
+        let shard = test_fixtures::shard();
+
+        let wide = RecursionAir::<F, 3>::machine_wide_with_all_chips(SC::default());
+        assert_deterministic_traces(&wide, &program, || shard.clone());
+        let skinny = RecursionAir::<F, 9>::machine_skinny_with_all_chips(SC::ultra_compressed());
+        assert_deterministic_traces(&skinny, &program, || shard.clone());
+    }
+
+    #[test]
+    fn executed_traces_are_deterministic() {
+        const N: u32 = 1 << 12;
+        const WIDTH: u32 = crate::chips::poseidon2_skinny::WIDTH as u32;
+
+        // A Fibonacci sequence, hashed in a chain of permutations, and selected in pairs.
+        let instructions = once(instr::mem(MemAccessKind::Write, 2, 0, 0))
+            .chain(once(instr::mem(MemAccessKind::Write, 2, 1, 1)))
+            .chain((2..N).map(|i| instr::base_alu(BaseAluOpcode::AddF, 3, i, i - 2, i - 1)))
+            .chain((0..N / WIDTH).map(|j| {
+                let input = if j == 0 { 0 } else { N + (j - 1) * WIDTH };
+                instr::poseidon2(
+                    [1; WIDTH as usize],
+                    array::from_fn(|k| N + j * WIDTH + k as u32),
+                    array::from_fn(|k| input + k as u32),
+                )
+            }))
+            .chain(
+                (0..N - 1)
+                    .map(|i| instr::select(0, 0, 0, 2 * N + 2 * i, 2 * N + 2 * i + 1, i, i + 1)),
+            )
+            .collect::<Vec<Instruction<F>>>();
+        let program = Arc::new(linear_program(instructions).unwrap());
+
+        let machine = RecursionAir::<F, 3>::compress_machine(SC::default());
+        assert_deterministic_traces(&machine, &program, || {
+            let mut runtime =
+                Runtime::<F, EF, DiffusionMatrixBabyBear>::new(program.clone(), SC::new().perm);
+            runtime.run().unwrap();
+            runtime.record
+        });
+    }
+}
@@ -0,0 +1,308 @@
+use core::borrow::Borrow;
+use p3_air::{Air, BaseAir, PairBuilder};
+use p3_field::{AbstractField, Field, PrimeField32};
+use p3_matrix::{dense::RowMajorMatrix, Matrix};
+use sp1_core_machine::utils::next_power_of_two;
+use sp1_derive::AlignedBorrow;
+use sp1_stark::{air::MachineAir, fill_trace, RowPadding};
+
+use crate::{builder::SP1RecursionAirBuilder, *};
+
+/// A chip computing `out = in1 * in2 + in3` over the base field in a single row, instead of a
+/// multiplication and an addition in the base field ALU.
+///
+/// The chip is only part of the machines with all chips: adding it to the compress, shrink and
+/// wrap machines would change their verifying keys.
+#[derive(Default)]
+pub struct FmaChip;
+
+pub const FMA_COLS: usize = core::mem::size_of::<FmaCols<u8>>();
+
+#[derive(AlignedBorrow, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct FmaCols<F: Copy> {
+    pub vals: FmaIo<F>,
+}
+
+pub const FMA_PREPROCESSED_COLS: usize = core::mem::size_of::<FmaPreprocessedCols<u8>>();
+
+#[derive(AlignedBorrow, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct FmaPreprocessedCols<F: Copy> {
+    pub is_real: F,
+    pub addrs: FmaIo<Address<F>>,
+    pub mult: F,
+}
+
+impl<F: Field> BaseAir<F> for FmaChip {
+    fn width(&self) -> usize {
+        FMA_COLS
+    }
+}
+
+impl<F: PrimeField32> MachineAir<F> for FmaChip {
+    type Record = ExecutionRecord<F>;
+
+    type Program = crate::RecursionProgram<F>;
+
+    fn name(&self) -> String {
+        "Fma".to_string()
+    }
+
+    fn preprocessed_width(&self) -> usize {
+        FMA_PREPROCESSED_COLS
+    }
+
+    fn preprocessed_num_rows(&self, program: &Self::Program, instrs_len: usize) -> Option<usize> {
+        let fixed_log2_rows = program.fixed_log2_rows(self);
+        Some(match fixed_log2_rows {
+            Some(log2_rows) => 1 << log2_rows,
+            None => next_power_of_two(instrs_len, None),
+        })
+    }
+
+    fn generate_preprocessed_trace(&self, program: &Self::Program) -> Option<RowMajorMatrix<F>> {
+        let instrs = program.fma_instrs();
+        Some(fill_trace(
+            &instrs,
+            self.preprocessed_num_rows(program, instrs.len()).unwrap(),
+            FMA_PREPROCESSED_COLS,
+            RowPadding::Zero,
+            |instr, cols| instr_to_row(instr, cols),
+        ))
+    }
+
+    fn generate_dependencies(&self, _: &Self::Record, _: &mut Self::Record) {
+        // This is a no-op.
+    }
+
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        Some(next_power_of_two(input.fma_events.len(), input.fixed_log2_rows(self)))
+    }
+
+    fn generate_trace(&self, input: &Self::Record, _: &mut Self::Record) -> RowMajorMatrix<F> {
+        fill_trace(
+            &input.fma_events,
+            self.num_rows(input).unwrap(),
+            FMA_COLS,
+            RowPadding::Zero,
+            event_to_row,
+        )
+    }
+
+    fn included(&self, _record: &Self::Record) -> bool {
+        true
+    }
+
+    fn is_empty_shard(&self, shard: &Self::Record) -> bool {
+        shard.fma_events.is_empty() && shard.program.is_prunable(|index| &index.fma)
+    }
+
+    fn is_empty_program(&self, program: &Self::Program) -> bool {
+        program.is_prunable(|index| &index.fma)
+    }
+
+    fn local_only(&self) -> bool {
+        true
+    }
+}
+
+/// Populates the columns of a fused multiply-add event, with the FFI kernel when the field is
+/// BabyBear and the `sys` feature is enabled, and in Rust otherwise.
+fn event_to_row<F: PrimeField32>(event: &FmaEvent<F>, cols: &mut FmaCols<F>) {
+    #[cfg(feature = "sys")]
+    if std::any::TypeId::of::<F>() == std::any::TypeId::of::<p3_baby_bear::BabyBear>() {
+        use p3_baby_bear::BabyBear;
+        // SAFETY: `F` is `BabyBear`.
+        unsafe {
+            crate::sys::fma_event_to_row_babybear(
+                std::mem::transmute::<&FmaEvent<F>, &FmaEvent<BabyBear>>(event),
+                std::mem::transmute::<&mut FmaCols<F>, &mut FmaCols<BabyBear>>(cols),
+            );
+        }
+        return;
+    }
+    event_to_row_rust(event, cols);
+}
+
+fn event_to_row_rust<F: Copy>(event: &FmaEvent<F>, cols: &mut FmaCols<F>) {
+    *cols = FmaCols { vals: *event };
+}
+
+/// Populates the preprocessed columns of a fused multiply-add instruction, with the FFI kernel
+/// when the field is BabyBear and the `sys` feature is enabled, and in Rust otherwise.
+fn instr_to_row<F: PrimeField32>(instr: &FmaInstr<F>, cols: &mut FmaPreprocessedCols<F>) {
+    #[cfg(feature = "sys")]
+    if std::any::TypeId::of::<F>() == std::any::TypeId::of::<p3_baby_bear::BabyBear>() {
+        use p3_baby_bear::BabyBear;
+        // SAFETY: `F` is `BabyBear`.
+        unsafe {
+            crate::sys::fma_instr_to_row_babybear(
+                std::mem::transmute::<&FmaInstr<F>, &FmaInstr<BabyBear>>(instr),
+                std::mem::transmute::<
+                    &mut FmaPreprocessedCols<F>,
+                    &mut FmaPreprocessedCols<BabyBear>,
+                >(cols),
+            );
+        }
+        return;
+    }
+    instr_to_row_rust(instr, cols);
+}
+
+fn instr_to_row_rust<F: AbstractField + Copy>(
+    instr: &FmaInstr<F>,
+    cols: &mut FmaPreprocessedCols<F>,
+) {
+    let FmaInstr { addrs, mult } = *instr;
+    *cols = FmaPreprocessedCols { is_real: F::one(), addrs, mult };
+}
+
+impl<AB> Air<AB> for FmaChip
+where
+    AB: SP1RecursionAirBuilder + PairBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &FmaCols<AB::Var> = (*local).borrow();
+        let prep = builder.preprocessed();
+        let prep_local = prep.row_slice(0);
+        let prep_local: &FmaPreprocessedCols<AB::Var> = (*prep_local).borrow();
+
+        let FmaCols { vals: FmaIo { out, in1, in2, in3 } } = *local;
+        let FmaPreprocessedCols { is_real, addrs, mult } = *prep_local;
+        builder.receive_single(addrs.in1, in1, is_real);
+        builder.receive_single(addrs.in2, in2, is_real);
+        builder.receive_single(addrs.in3, in3, is_real);
+        builder.send_single(addrs.out, out, mult);
+        builder.assert_eq(out, in1 * in2 + in3);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        chips::test_fixtures::{self, trace_parity_tests},
+        runtime::instruction as instr,
+    };
+    use machine::tests::test_recursion_linear_program;
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+    use p3_matrix::dense::RowMajorMatrix;
+    use p3_maybe_rayon::prelude::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use sp1_stark::{baby_bear_poseidon2::BabyBearPoseidon2, StarkGenericConfig};
+    use std::borrow::BorrowMut;
+
+    use super::*;
+
+    #[test]
+    pub fn prove_fma() {
+        type SC = BabyBearPoseidon2;
+        type F = <SC as StarkGenericConfig>::Val;
+
+        let mut rng = StdRng::seed_from_u64(0xDEADBEEF);
+        let mut addr = 0;
+
+        let instructions = (0..1000)
+            .flat_map(|_| {
+                let in1: F = rng.sample(rand::distributions::Standard);
+                let in2: F = rng.sample(rand::distributions::Standard);
+                let in3: F = rng.sample(rand::distributions::Standard);
+                let alloc_size = 4;
+                let a = (0..alloc_size).map(|x| x + addr).collect::<Vec<_>>();
+                addr += alloc_size;
+                [
+                    instr::mem_single(MemAccessKind::Write, 1, a[1], in1),
+                    instr::mem_single(MemAccessKind::Write, 1, a[2], in2),
+                    instr::mem_single(MemAccessKind::Write, 1, a[3], in3),
+                    instr::fma(1, a[0], a[1], a[2], a[3]),
+                    instr::mem_single(MemAccessKind::Read, 1, a[0], in1 * in2 + in3),
+                ]
+            })
+            .collect::<Vec<Instruction<F>>>();
+
+        test_recursion_linear_program(instructions);
+    }
+
+    /// The FFI kernels and the Rust path, used for other fields, populate the same rows.
+    #[test]
+    #[cfg(feature = "sys")]
+    fn ffi_matches_rust() {
+        type F = BabyBear;
+
+        for event in &test_fixtures::shard().fma_events {
+            let (mut ffi, mut rust) = ([F::zero(); FMA_COLS], [F::zero(); FMA_COLS]);
+            event_to_row(event, ffi.as_mut_slice().borrow_mut());
+            event_to_row_rust(event, rust.as_mut_slice().borrow_mut());
+            assert_eq!(ffi, rust);
+        }
+
+        let program = test_fixtures::unvalidated_program();
+        for instr in program.fma_instrs() {
+            let (mut ffi, mut rust) =
+                ([F::zero(); FMA_PREPROCESSED_COLS], [F::zero(); FMA_PREPROCESSED_COLS]);
+            instr_to_row(instr, ffi.as_mut_slice().borrow_mut());
+            instr_to_row_rust(instr, rust.as_mut_slice().borrow_mut());
+            assert_eq!(ffi, rust);
+        }
+    }
+
+    fn generate_trace_reference(
+        input: &ExecutionRecord<BabyBear>,
+        _: &mut ExecutionRecord<BabyBear>,
+    ) -> RowMajorMatrix<BabyBear> {
+        type F = BabyBear;
+
+        let events = &input.fma_events;
+        let padded_nb_rows = FmaChip.num_rows(input).unwrap();
+        let mut values = vec![F::zero(); padded_nb_rows * FMA_COLS];
+
+        let populate_len = events.len() * FMA_COLS;
+        values[..populate_len].par_chunks_mut(FMA_COLS).zip_eq(events).for_each(|(row, &vals)| {
+            let cols: &mut FmaCols<_> = row.borrow_mut();
+            *cols = FmaCols { vals };
+        });
+
+        RowMajorMatrix::new(values, FMA_COLS)
+    }
+
+    fn generate_preprocessed_trace_reference(
+        program: &RecursionProgram<BabyBear>,
+    ) -> RowMajorMatrix<BabyBear> {
+        type F = BabyBear;
+
+        let instrs = program
+            .inner
+            .iter()
+            .filter_map(|instruction| match instruction {
+                Instruction::Fma(x) => Some(x),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let padded_nb_rows = FmaChip.preprocessed_num_rows(program, instrs.len()).unwrap();
+        let mut values = vec![F::zero(); padded_nb_rows * FMA_PREPROCESSED_COLS];
+
+        let populate_len = instrs.len() * FMA_PREPROCESSED_COLS;
+        values[..populate_len].par_chunks_mut(FMA_PREPROCESSED_COLS).zip_eq(instrs).for_each(
+            |(row, instr)| {
+                let FmaInstr { addrs, mult } = instr;
+                let cols: &mut FmaPreprocessedCols<_> = row.borrow_mut();
+                *cols = FmaPreprocessedCols {
+                    is_real: F::one(),
+                    addrs: addrs.to_owned(),
+                    mult: mult.to_owned(),
+                };
+            },
+        );
+
+        RowMajorMatrix::new(values, FMA_PREPROCESSED_COLS)
+    }
+
+    trace_parity_tests! {
+        chip: FmaChip,
+        trace: generate_trace_reference,
+        preprocessed: generate_preprocessed_trace_reference,
+    }
+}
@@ -16,8 +16,7 @@ use sp1_stark::air::{BaseAirBuilder, ExtensionAirBuilder};
 use sp1_derive::AlignedBorrow;
 
 use crate::{
-    air::Block, builder::SP1RecursionAirBuilder, runtime::Instruction, ExecutionRecord,
-    FriFoldEvent, FriFoldInstr,
+    air::Block, builder::SP1RecursionAirBuilder, ExecutionRecord, FriFoldEvent, FriFoldInstr,
 };
 
 use super::mem::MemoryAccessColsChips;
@@ -109,15 +108,10 @@ impl<F: PrimeField32, const DEGREE: usize> MachineAir<F> for FriFoldChip<DEGREE>
 
         let mut rows: Vec<[BabyBear; NUM_FRI_FOLD_PREPROCESSED_COLS]> = Vec::new();
         program
-            .inner
-            .iter()
-            .filter_map(|instruction| match instruction {
-                Instruction::FriFold(instr) => Some(unsafe {
-                    std::mem::transmute::<&Box<FriFoldInstr<F>>, &Box<FriFoldInstr<BabyBear>>>(
-                        instr,
-                    )
-                }),
-                _ => None,
+            .fri_fold_instrs()
+            .into_iter()
+            .map(|instruction| unsafe {
+                std::mem::transmute::<&FriFoldInstr<F>, &FriFoldInstr<BabyBear>>(instruction)
             })
             .for_each(|instruction| {
                 let mut row_add = vec![
@@ -219,6 +213,14 @@ impl<F: PrimeField32, const DEGREE: usize> MachineAir<F> for FriFoldChip<DEGREE>
     fn included(&self, _record: &Self::Record) -> bool {
         true
     }
+
+    fn is_empty_shard(&self, shard: &Self::Record) -> bool {
+        shard.fri_fold_events.is_empty() && shard.program.is_prunable(|index| &index.fri_fold)
+    }
+
+    fn is_empty_program(&self, program: &Self::Program) -> bool {
+        program.is_prunable(|index| &index.fri_fold)
+    }
 }
 
 impl<const DEGREE: usize> FriFoldChip<DEGREE> {
@@ -347,7 +349,7 @@ where
 mod tests {
     use crate::{
         air::Block,
-        chips::{fri_fold::FriFoldChip, mem::MemoryAccessCols, test_fixtures},
+        chips::{fri_fold::FriFoldChip, mem::MemoryAccessCols, test_fixtures::trace_parity_tests},
         machine::tests::test_recursion_linear_program,
         runtime::{instruction as instr, ExecutionRecord},
         stark::BabyBearPoseidon2Outer,
@@ -577,17 +579,6 @@ mod tests {
         RowMajorMatrix::new(rows.into_iter().flatten().collect(), NUM_FRI_FOLD_COLS)
     }
 
-    #[test]
-    fn test_generate_trace() {
-        let shard = test_fixtures::shard();
-        let mut execution_record = test_fixtures::default_execution_record();
-        let chip = FriFoldChip::<DEGREE>::default();
-        let trace = chip.generate_trace(&shard, &mut execution_record);
-        assert!(trace.height() >= test_fixtures::MIN_TEST_CASES);
-
-        assert_eq!(trace, generate_trace_reference::<DEGREE>(&shard, &mut execution_record));
-    }
-
     fn generate_preprocessed_trace_reference<const DEGREE: usize>(
         program: &RecursionProgram<BabyBear>,
     ) -> RowMajorMatrix<BabyBear> {
@@ -657,14 +648,10 @@ mod tests {
         RowMajorMatrix::new(rows.into_iter().flatten().collect(), NUM_FRI_FOLD_PREPROCESSED_COLS)
     }
 
-    #[test]
-    #[ignore = "Failing due to merge conflicts. Will be fixed shortly."]
-    fn generate_preprocessed_trace() {
-        let program = test_fixtures::program();
-        let chip = FriFoldChip::<DEGREE>::default();
-        let trace = chip.generate_preprocessed_trace(&program).unwrap();
-        assert!(trace.height() >= test_fixtures::MIN_TEST_CASES);
-
-        assert_eq!(trace, generate_preprocessed_trace_reference::<DEGREE>(&program));
+    trace_parity_tests! {
+        chip: FriFoldChip::<DEGREE>::default(),
+        trace: generate_trace_reference::<DEGREE>,
+        #[ignore = "Failing due to merge conflicts. Will be fixed shortly."]
+        preprocessed: generate_preprocessed_trace_reference::<DEGREE>,
     }
 }
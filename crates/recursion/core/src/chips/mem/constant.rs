@@ -1,16 +1,16 @@
 use core::borrow::Borrow;
-use itertools::Itertools;
 use p3_air::{Air, BaseAir, PairBuilder};
 use p3_field::PrimeField32;
 use p3_matrix::{dense::RowMajorMatrix, Matrix};
-use sp1_core_machine::utils::pad_rows_fixed;
+use p3_maybe_rayon::prelude::*;
+use sp1_core_machine::utils::{next_power_of_two, pad_rows_fixed};
 use sp1_derive::AlignedBorrow;
 use sp1_stark::air::MachineAir;
-use std::{borrow::BorrowMut, iter::zip, marker::PhantomData};
+use std::{borrow::BorrowMut, marker::PhantomData};
 
 use crate::{builder::SP1RecursionAirBuilder, *};
 
-use super::MemoryAccessCols;
+use super::{MemoryAccessCols, MemoryAccessColsChips};
 
 pub const NUM_CONST_MEM_ENTRIES_PER_ROW: usize = 2;
 
@@ -31,11 +31,22 @@ pub struct MemoryCols<F: Copy> {
 pub const NUM_MEM_PREPROCESSED_INIT_COLS: usize =
     core::mem::size_of::<MemoryPreprocessedCols<u8>>();
 
+pub const NUM_MEM_CONST_ACCESS_COLS: usize = core::mem::size_of::<MemoryConstAccessCols<u8>>();
+
 #[derive(AlignedBorrow, Debug, Clone, Copy)]
 #[repr(C)]
 pub struct MemoryPreprocessedCols<F: Copy> {
-    values_and_accesses: [(Block<F>, MemoryAccessCols<F>); NUM_CONST_MEM_ENTRIES_PER_ROW],
+    accesses: [MemoryConstAccessCols<F>; NUM_CONST_MEM_ENTRIES_PER_ROW],
+}
+
+/// The preprocessed columns of a memory instruction: the value it writes or reads, and the access.
+#[derive(AlignedBorrow, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct MemoryConstAccessCols<F: Copy> {
+    pub value: Block<F>,
+    pub access: MemoryAccessColsChips<F>,
 }
+
 impl<F: Send + Sync> BaseAir<F> for MemoryChip<F> {
     fn width(&self) -> usize {
         NUM_MEM_INIT_COLS
@@ -55,47 +66,27 @@ impl<F: PrimeField32> MachineAir<F> for MemoryChip<F> {
     }
 
     fn generate_preprocessed_trace(&self, program: &Self::Program) -> Option<RowMajorMatrix<F>> {
-        let mut rows = program
+        let instrs = program
             .inner
             .iter()
             .filter_map(|instruction| match instruction {
-                Instruction::Mem(MemInstr { addrs, vals, mult, kind }) => {
-                    let mult = mult.to_owned();
-                    let mult = match kind {
-                        MemAccessKind::Read => -mult,
-                        MemAccessKind::Write => mult,
-                    };
-
-                    Some((vals.inner, MemoryAccessCols { addr: addrs.inner, mult }))
-                }
+                Instruction::Mem(instr) => Some(instr),
                 _ => None,
             })
-            .chunks(NUM_CONST_MEM_ENTRIES_PER_ROW)
-            .into_iter()
-            .map(|row_vs_as| {
-                let mut row = [F::zero(); NUM_MEM_PREPROCESSED_INIT_COLS];
-                let cols: &mut MemoryPreprocessedCols<_> = row.as_mut_slice().borrow_mut();
-                for (cell, access) in zip(&mut cols.values_and_accesses, row_vs_as) {
-                    *cell = access;
-                }
-                row
-            })
             .collect::<Vec<_>>();
 
-        // Pad the rows to the next power of two.
-        pad_rows_fixed(
-            &mut rows,
-            || [F::zero(); NUM_MEM_PREPROCESSED_INIT_COLS],
-            program.fixed_log2_rows(self),
-        );
+        let nb_rows = instrs.len().div_ceil(NUM_CONST_MEM_ENTRIES_PER_ROW);
+        let padded_nb_rows = next_power_of_two(nb_rows, program.fixed_log2_rows(self));
+        let mut values = vec![F::zero(); padded_nb_rows * NUM_MEM_PREPROCESSED_INIT_COLS];
 
-        // Convert the trace to a row major matrix.
-        let trace = RowMajorMatrix::new(
-            rows.into_iter().flatten().collect::<Vec<_>>(),
-            NUM_MEM_PREPROCESSED_INIT_COLS,
-        );
+        // The accesses of a row are laid out one after the other.
+        let populate_len = instrs.len() * NUM_MEM_CONST_ACCESS_COLS;
+        values[..populate_len]
+            .par_chunks_mut(NUM_MEM_CONST_ACCESS_COLS)
+            .zip_eq(instrs)
+            .for_each(|(row, instr)| instr_to_row(instr, row.borrow_mut()));
 
-        Some(trace)
+        Some(RowMajorMatrix::new(values, NUM_MEM_PREPROCESSED_INIT_COLS))
     }
 
     fn generate_dependencies(&self, _: &Self::Record, _: &mut Self::Record) {
@@ -103,6 +94,11 @@ impl<F: PrimeField32> MachineAir<F> for MemoryChip<F> {
     }
 
     fn generate_trace(&self, input: &Self::Record, _: &mut Self::Record) -> RowMajorMatrix<F> {
+        #[cfg(all(debug_assertions, feature = "debug"))]
+        debug_check_multiplicities("MemoryConst", &input.program, |instruction| {
+            matches!(instruction, Instruction::Mem(_))
+        });
+
         // Match number of rows generated by the `.chunks` call in `generate_preprocessed_trace`.
         let num_rows = input
             .mem_const_count
@@ -128,6 +124,39 @@ impl<F: PrimeField32> MachineAir<F> for MemoryChip<F> {
     }
 }
 
+/// Populates the preprocessed columns of a memory instruction, with the FFI kernel when the field
+/// is BabyBear and the `sys` feature is enabled, and in Rust otherwise.
+fn instr_to_row<F: PrimeField32>(instr: &MemInstr<F>, cols: &mut MemoryConstAccessCols<F>) {
+    #[cfg(feature = "sys")]
+    if std::any::TypeId::of::<F>() == std::any::TypeId::of::<p3_baby_bear::BabyBear>() {
+        use p3_baby_bear::BabyBear;
+        // SAFETY: `F` is `BabyBear`.
+        unsafe {
+            crate::sys::mem_const_instr_to_row_babybear(
+                std::mem::transmute::<&MemInstr<F>, &MemInstr<BabyBear>>(instr),
+                std::mem::transmute::<
+                    &mut MemoryConstAccessCols<F>,
+                    &mut MemoryConstAccessCols<BabyBear>,
+                >(cols),
+            );
+        }
+        return;
+    }
+    instr_to_row_rust(instr, cols);
+}
+
+fn instr_to_row_rust<F: PrimeField32>(instr: &MemInstr<F>, cols: &mut MemoryConstAccessCols<F>) {
+    let MemInstr { addrs, vals, mult, kind } = *instr;
+    let mult = match kind {
+        MemAccessKind::Read => -mult,
+        MemAccessKind::Write => mult,
+    };
+    *cols = MemoryConstAccessCols {
+        value: vals.inner,
+        access: MemoryAccessCols { addr: addrs.inner, mult },
+    };
+}
+
 impl<AB> Air<AB> for MemoryChip<AB::F>
 where
     AB: SP1RecursionAirBuilder + PairBuilder,
@@ -137,7 +166,7 @@ where
         let prep_local = prep.row_slice(0);
         let prep_local: &MemoryPreprocessedCols<AB::Var> = (*prep_local).borrow();
 
-        for (value, access) in prep_local.values_and_accesses {
+        for MemoryConstAccessCols { value, access } in prep_local.accesses {
             builder.send_block(access.addr, value, access.mult);
         }
     }
@@ -145,11 +174,18 @@ where
 
 #[cfg(test)]
 mod tests {
+    use itertools::Itertools;
     use machine::tests::test_recursion_linear_program;
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+    use std::iter::zip;
 
     use super::*;
 
-    use crate::runtime::instruction as instr;
+    use crate::{
+        chips::test_fixtures::{self, trace_parity_tests},
+        runtime::instruction as instr,
+    };
 
     #[test]
     pub fn prove_basic_mem() {
@@ -185,4 +221,65 @@ mod tests {
             instr::mem(MemAccessKind::Read, 1, 1, 999),
         ]);
     }
+
+    /// The FFI kernel and the Rust path, used for other fields, populate the same rows.
+    #[test]
+    #[cfg(feature = "sys")]
+    fn ffi_matches_rust() {
+        type F = BabyBear;
+
+        let program = test_fixtures::unvalidated_program();
+        for instruction in program.inner.iter() {
+            let Instruction::Mem(instr) = instruction else { continue };
+            let (mut ffi, mut rust) =
+                ([F::zero(); NUM_MEM_CONST_ACCESS_COLS], [F::zero(); NUM_MEM_CONST_ACCESS_COLS]);
+            instr_to_row(instr, ffi.as_mut_slice().borrow_mut());
+            instr_to_row_rust(instr, rust.as_mut_slice().borrow_mut());
+            assert_eq!(ffi, rust);
+        }
+    }
+
+    fn generate_preprocessed_trace_reference(
+        program: &RecursionProgram<BabyBear>,
+    ) -> RowMajorMatrix<BabyBear> {
+        type F = BabyBear;
+
+        let mut rows = program
+            .inner
+            .iter()
+            .filter_map(|instruction| match instruction {
+                Instruction::Mem(MemInstr { addrs, vals, mult, kind }) => {
+                    let mult = match kind {
+                        MemAccessKind::Read => -*mult,
+                        MemAccessKind::Write => *mult,
+                    };
+                    Some((vals.inner, MemoryAccessCols { addr: addrs.inner, mult }))
+                }
+                _ => None,
+            })
+            .chunks(NUM_CONST_MEM_ENTRIES_PER_ROW)
+            .into_iter()
+            .map(|row_vs_as| {
+                let mut row = [F::zero(); NUM_MEM_PREPROCESSED_INIT_COLS];
+                let cols: &mut MemoryPreprocessedCols<_> = row.as_mut_slice().borrow_mut();
+                for (cell, (value, access)) in zip(&mut cols.accesses, row_vs_as) {
+                    *cell = MemoryConstAccessCols { value, access };
+                }
+                row
+            })
+            .collect::<Vec<_>>();
+
+        pad_rows_fixed(
+            &mut rows,
+            || [F::zero(); NUM_MEM_PREPROCESSED_INIT_COLS],
+            program.fixed_log2_rows(&MemoryChip::default()),
+        );
+
+        RowMajorMatrix::new(rows.into_iter().flatten().collect(), NUM_MEM_PREPROCESSED_INIT_COLS)
+    }
+
+    trace_parity_tests! {
+        chip: MemoryChip::<BabyBear>::default(),
+        preprocessed: generate_preprocessed_trace_reference,
+    }
 }
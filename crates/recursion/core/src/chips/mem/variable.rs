@@ -4,9 +4,9 @@ use p3_air::{Air, BaseAir, PairBuilder};
 use p3_field::PrimeField32;
 use p3_matrix::{dense::RowMajorMatrix, Matrix};
 use p3_maybe_rayon::prelude::*;
-use sp1_core_machine::utils::{next_power_of_two, pad_rows_fixed};
+use sp1_core_machine::utils::next_power_of_two;
 use sp1_derive::AlignedBorrow;
-use sp1_stark::air::MachineAir;
+use sp1_stark::{air::MachineAir, ColMajorMatrix};
 use std::{borrow::BorrowMut, iter::zip, marker::PhantomData};
 
 use crate::{builder::SP1RecursionAirBuilder, *};
@@ -60,7 +60,9 @@ impl<F: PrimeField32> MachineAir<F> for MemoryChip<F> {
         let accesses = program
             .inner
             .iter()
-            // .par_bridge() // Using `rayon` here provides a big speedup. TODO put rayon back
+            // Using `rayon` here provides a big speedup, but `par_bridge` does not preserve the
+            // order of the accesses, which must match the order of the events in the main trace.
+            // TODO put rayon back with an order-preserving iterator.
             .flat_map(|instruction| match instruction {
                 Instruction::Hint(HintInstr { output_addrs_mults })
                 | Instruction::HintBits(HintBitsInstr {
@@ -94,7 +96,7 @@ impl<F: PrimeField32> MachineAir<F> for MemoryChip<F> {
         values[..populate_len]
             .par_chunks_mut(NUM_MEM_ACCESS_COLS)
             .zip_eq(accesses)
-            .for_each(|(row, &(addr, mult))| *row.borrow_mut() = MemoryAccessCols { addr, mult });
+            .for_each(|(row, (addr, mult))| instr_to_row(addr, mult, row.borrow_mut()));
 
         Some(RowMajorMatrix::new(values, NUM_MEM_PREPROCESSED_INIT_COLS))
     }
@@ -104,25 +106,41 @@ impl<F: PrimeField32> MachineAir<F> for MemoryChip<F> {
     }
 
     fn generate_trace(&self, input: &Self::Record, _: &mut Self::Record) -> RowMajorMatrix<F> {
-        // Generate the trace rows & corresponding records for each chunk of events in parallel.
-        let mut rows = input
-            .mem_var_events
-            .chunks(NUM_VAR_MEM_ENTRIES_PER_ROW)
-            .map(|row_events| {
-                let mut row = [F::zero(); NUM_MEM_INIT_COLS];
-                let cols: &mut MemoryCols<_> = row.as_mut_slice().borrow_mut();
-                for (cell, vals) in zip(&mut cols.values, row_events) {
-                    *cell = vals.inner;
-                }
-                row
-            })
-            .collect::<Vec<_>>();
+        #[cfg(all(debug_assertions, feature = "debug"))]
+        debug_check_multiplicities("MemoryVar", &input.program, |instruction| {
+            matches!(
+                instruction,
+                Instruction::Hint(_)
+                    | Instruction::HintBits(_)
+                    | Instruction::HintExt2Felts(_)
+                    | Instruction::HintAddCurve(_)
+            )
+        });
 
-        // Pad the rows to the next power of two.
-        pad_rows_fixed(&mut rows, || [F::zero(); NUM_MEM_INIT_COLS], input.fixed_log2_rows(self));
+        let height = next_power_of_two(
+            input.mem_var_events.len().div_ceil(NUM_VAR_MEM_ENTRIES_PER_ROW),
+            input.fixed_log2_rows(self),
+        );
+        let mut values = vec![F::zero(); height * NUM_MEM_INIT_COLS];
+        input.mem_var_events.write_rows(
+            &mut values,
+            NUM_MEM_INIT_COLS,
+            NUM_VAR_MEM_ENTRIES_PER_ROW,
+        );
+        RowMajorMatrix::new(values, NUM_MEM_INIT_COLS)
+    }
 
-        // Convert the trace to a row major matrix.
-        RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_MEM_INIT_COLS)
+    fn generate_trace_col_major(
+        &self,
+        input: &Self::Record,
+        _: &mut Self::Record,
+    ) -> ColMajorMatrix<F> {
+        let events = &input.mem_var_events;
+        let nb_rows = events.len().div_ceil(NUM_VAR_MEM_ENTRIES_PER_ROW);
+        let padded_nb_rows = next_power_of_two(nb_rows, input.fixed_log2_rows(self));
+        let mut trace = ColMajorMatrix::zeros(padded_nb_rows, NUM_MEM_INIT_COLS);
+        events.write_col_major(&mut trace, NUM_VAR_MEM_ENTRIES_PER_ROW);
+        trace
     }
 
     fn included(&self, _record: &Self::Record) -> bool {
@@ -134,6 +152,31 @@ impl<F: PrimeField32> MachineAir<F> for MemoryChip<F> {
     }
 }
 
+/// Populates the preprocessed columns of a hinted write, with the FFI kernel when the field is
+/// BabyBear and the `sys` feature is enabled, and in Rust otherwise.
+fn instr_to_row<F: PrimeField32>(addr: &Address<F>, mult: &F, cols: &mut MemoryAccessCols<F>) {
+    #[cfg(feature = "sys")]
+    if std::any::TypeId::of::<F>() == std::any::TypeId::of::<p3_baby_bear::BabyBear>() {
+        use p3_baby_bear::BabyBear;
+        // SAFETY: `F` is `BabyBear`.
+        unsafe {
+            crate::sys::mem_var_instr_to_row_babybear(
+                std::mem::transmute::<&Address<F>, &Address<BabyBear>>(addr),
+                std::mem::transmute::<&F, &BabyBear>(mult),
+                std::mem::transmute::<&mut MemoryAccessCols<F>, &mut MemoryAccessCols<BabyBear>>(
+                    cols,
+                ),
+            );
+        }
+        return;
+    }
+    instr_to_row_rust(addr, mult, cols);
+}
+
+fn instr_to_row_rust<F: PrimeField32>(addr: &Address<F>, mult: &F, cols: &mut MemoryAccessCols<F>) {
+    *cols = MemoryAccessCols { addr: *addr, mult: *mult };
+}
+
 impl<AB> Air<AB> for MemoryChip<AB::F>
 where
     AB: SP1RecursionAirBuilder + PairBuilder,
@@ -157,16 +200,20 @@ mod tests {
     use p3_baby_bear::BabyBear;
     use p3_field::AbstractField;
     use p3_matrix::dense::RowMajorMatrix;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
 
     use super::*;
 
+    use crate::chips::test_fixtures::{self, trace_parity_tests};
+
     #[test]
-    pub fn generate_trace() {
+    pub fn generate_trace_two_events() {
         let shard = ExecutionRecord::<BabyBear> {
             mem_var_events: vec![
                 MemEvent { inner: BabyBear::one().into() },
                 MemEvent { inner: BabyBear::one().into() },
-            ],
+            ]
+            .into(),
             ..Default::default()
         };
         let chip = MemoryChip::default();
@@ -174,4 +221,123 @@ mod tests {
             chip.generate_trace(&shard, &mut ExecutionRecord::default());
         println!("{:?}", trace.values)
     }
+
+    #[test]
+    pub fn generate_trace_col_major() {
+        let mut rng = StdRng::seed_from_u64(0xC01_3A70);
+        let chip = MemoryChip::default();
+        // An odd number of events leaves the last populated row half empty.
+        for num_events in [0, 1, 1001, 4096] {
+            let shard = ExecutionRecord::<BabyBear> {
+                mem_var_events: (0..num_events)
+                    .map(|_| MemEvent { inner: Block(rng.gen()) })
+                    .collect(),
+                ..Default::default()
+            };
+            let row_major = chip.generate_trace(&shard, &mut ExecutionRecord::default());
+            assert_eq!(
+                chip.generate_trace_col_major(&shard, &mut ExecutionRecord::default()),
+                ColMajorMatrix::from_row_major(&row_major)
+            );
+        }
+    }
+
+    /// The FFI kernels and the Rust paths, used for other fields, populate the same rows.
+    #[test]
+    #[cfg(feature = "sys")]
+    fn ffi_matches_rust() {
+        type F = BabyBear;
+
+        let shard = test_fixtures::shard();
+        let trace = MemoryChip::default().generate_trace(&shard, &mut ExecutionRecord::default());
+        let values = trace.values.chunks_exact(D);
+        for (event, value) in zip(shard.mem_var_events.iter(), values) {
+            let mut ffi = Block::from([F::zero(); D]);
+            unsafe { crate::sys::mem_var_event_to_row_babybear(&event, &mut ffi) };
+            assert_eq!(ffi.0.as_slice(), value);
+        }
+
+        let program = test_fixtures::unvalidated_program();
+        for instruction in program.inner.iter() {
+            let Instruction::Hint(HintInstr { output_addrs_mults }) = instruction else {
+                continue;
+            };
+            for (addr, mult) in output_addrs_mults {
+                let (mut ffi, mut rust) =
+                    ([F::zero(); NUM_MEM_ACCESS_COLS], [F::zero(); NUM_MEM_ACCESS_COLS]);
+                instr_to_row(addr, mult, ffi.as_mut_slice().borrow_mut());
+                instr_to_row_rust(addr, mult, rust.as_mut_slice().borrow_mut());
+                assert_eq!(ffi, rust);
+            }
+        }
+    }
+
+    fn generate_trace_reference(
+        input: &ExecutionRecord<BabyBear>,
+        _: &mut ExecutionRecord<BabyBear>,
+    ) -> RowMajorMatrix<BabyBear> {
+        type F = BabyBear;
+
+        let events = input.mem_var_events.to_vec();
+        let chip = MemoryChip::default();
+        let nb_rows = events.len().div_ceil(NUM_VAR_MEM_ENTRIES_PER_ROW);
+        let padded_nb_rows = next_power_of_two(nb_rows, input.fixed_log2_rows(&chip));
+        let mut values = vec![F::zero(); padded_nb_rows * NUM_MEM_INIT_COLS];
+
+        for (row, chunk) in values
+            .chunks_exact_mut(NUM_MEM_INIT_COLS)
+            .zip(events.chunks(NUM_VAR_MEM_ENTRIES_PER_ROW))
+        {
+            let cols: &mut MemoryCols<F> = row.borrow_mut();
+            for (value, event) in zip(&mut cols.values, chunk) {
+                *value = event.inner;
+            }
+        }
+
+        RowMajorMatrix::new(values, NUM_MEM_INIT_COLS)
+    }
+
+    fn generate_preprocessed_trace_reference(
+        program: &RecursionProgram<BabyBear>,
+    ) -> RowMajorMatrix<BabyBear> {
+        type F = BabyBear;
+
+        let accesses = program
+            .inner
+            .iter()
+            .flat_map(|instruction| match instruction {
+                Instruction::Hint(HintInstr { output_addrs_mults })
+                | Instruction::HintBits(HintBitsInstr { output_addrs_mults, .. }) => {
+                    output_addrs_mults.clone()
+                }
+                Instruction::HintExt2Felts(HintExt2FeltsInstr { output_addrs_mults, .. }) => {
+                    output_addrs_mults.to_vec()
+                }
+                Instruction::HintAddCurve(instr) => instr
+                    .output_x_addrs_mults
+                    .iter()
+                    .chain(instr.output_y_addrs_mults.iter())
+                    .copied()
+                    .collect(),
+                _ => vec![],
+            })
+            .collect::<Vec<_>>();
+
+        let chip = MemoryChip::default();
+        let nb_rows = accesses.len().div_ceil(NUM_VAR_MEM_ENTRIES_PER_ROW);
+        let padded_nb_rows = next_power_of_two(nb_rows, program.fixed_log2_rows(&chip));
+        let mut values = vec![F::zero(); padded_nb_rows * NUM_MEM_PREPROCESSED_INIT_COLS];
+
+        for (row, (addr, mult)) in values.chunks_exact_mut(NUM_MEM_ACCESS_COLS).zip(accesses) {
+            *row.borrow_mut() = MemoryAccessCols { addr, mult };
+        }
+
+        RowMajorMatrix::new(values, NUM_MEM_PREPROCESSED_INIT_COLS)
+    }
+
+    trace_parity_tests! {
+        chip: MemoryChip::<BabyBear>::default(),
+        trace: generate_trace_reference,
+        preprocessed: generate_preprocessed_trace_reference,
+    }
 }
@@ -1,14 +1,23 @@
 pub mod alu_base;
 pub mod alu_ext;
 pub mod batch_fri;
+pub mod constraint_summary;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod determinism;
 pub mod exp_reverse_bits;
+pub mod fma;
 pub mod fri_fold;
 pub mod mem;
 pub mod poseidon2_skinny;
 pub mod poseidon2_wide;
 pub mod public_values;
+pub mod range_check;
 pub mod select;
 
+pub use constraint_summary::{constraint_summary, ChipConstraintSummary};
+#[cfg(any(test, feature = "test-utils"))]
+pub use determinism::assert_deterministic_traces;
+
 #[cfg(test)]
 pub mod test_fixtures {
     use crate::*;
@@ -23,21 +32,90 @@ pub mod test_fixtures {
     pub const MIN_TEST_CASES: usize = 1000;
     const MAX_TEST_CASES: usize = 10000;
 
+    /// Generates the `generate_trace` and `generate_preprocessed_trace` tests of a chip, which
+    /// check the traces generated from the [`shard`] and [`unvalidated_program`] fixtures against
+    /// the given reference functions.
+    ///
+    /// Either test may be omitted, and attributes such as `#[ignore]` may be given to the
+    /// preprocessed one.
+    macro_rules! trace_parity_tests {
+        (
+            chip: $chip:expr,
+            $(trace: $trace:expr,)?
+            $($(#[$attr:meta])* preprocessed: $preprocessed:expr,)?
+        ) => {
+            $(
+                #[test]
+                fn generate_trace() {
+                    use $crate::chips::test_fixtures;
+
+                    let shard = test_fixtures::shard();
+                    let mut execution_record = test_fixtures::default_execution_record();
+                    let trace = sp1_stark::air::MachineAir::generate_trace(
+                        &$chip,
+                        &shard,
+                        &mut execution_record,
+                    );
+                    assert!(p3_matrix::Matrix::height(&trace) >= test_fixtures::MIN_TEST_CASES);
+
+                    assert_eq!(trace, $trace(&shard, &mut execution_record));
+                }
+            )?
+            $(
+                #[test]
+                $(#[$attr])*
+                fn generate_preprocessed_trace() {
+                    use $crate::chips::test_fixtures;
+
+                    let program = test_fixtures::unvalidated_program();
+                    let trace =
+                        sp1_stark::air::MachineAir::generate_preprocessed_trace(&$chip, &program)
+                            .unwrap();
+                    assert!(p3_matrix::Matrix::height(&trace) >= test_fixtures::MIN_TEST_CASES);
+
+                    assert_eq!(trace, $preprocessed(&program));
+                }
+            )?
+        };
+    }
+    pub(crate) use trace_parity_tests;
+
     pub fn shard() -> ExecutionRecord<BabyBear> {
         ExecutionRecord {
-            base_alu_events: base_alu_events(),
-            ext_alu_events: ext_alu_events(),
+            base_alu_events: base_alu_events().into(),
+            ext_alu_events: ext_alu_events().into(),
             batch_fri_events: batch_fri_events(),
             exp_reverse_bits_len_events: exp_reverse_bits_events(),
             fri_fold_events: fri_fold_events(),
             commit_pv_hash_events: public_values_events(),
-            select_events: select_events(),
+            select_events: select_events().into(),
+            fma_events: fma_events(),
+            range_check_events: range_check_events(),
             poseidon2_events: poseidon2_events(),
+            mem_var_events: mem_var_events().into(),
             ..Default::default()
         }
     }
 
     pub fn program() -> RecursionProgram<BabyBear> {
+        linear_program(instructions()).unwrap()
+    }
+
+    /// The instructions of [`program`], without checking that reads happen after writes, which
+    /// the random addresses of the instructions do not satisfy.
+    pub fn unvalidated_program() -> RecursionProgram<BabyBear> {
+        let program = RootProgram {
+            inner: RawProgram {
+                seq_blocks: vec![SeqBlock::Basic(BasicBlock { instrs: instructions() })],
+            },
+            total_memory: 0,
+            shape: None,
+        };
+        // SAFETY: The program is only used to generate preprocessed traces.
+        unsafe { RecursionProgram::new_unchecked(program) }
+    }
+
+    pub fn instructions() -> Vec<Instruction<BabyBear>> {
         let mut instructions = [
             base_alu_instructions(),
             ext_alu_instructions(),
@@ -46,14 +124,17 @@ pub mod test_fixtures {
             fri_fold_instructions(),
             public_values_instructions(),
             select_instructions(),
+            fma_instructions(),
+            range_check_instructions(),
             poseidon2_instructions(),
+            mem_instructions(),
+            hint_instructions(),
         ]
         .concat();
 
         let mut rng = StdRng::seed_from_u64(SEED);
         instructions.shuffle(&mut rng);
-
-        linear_program(instructions).unwrap()
+        instructions
     }
 
     pub fn default_execution_record() -> ExecutionRecord<BabyBear> {
@@ -181,6 +262,29 @@ pub mod test_fixtures {
         events
     }
 
+    fn fma_events() -> Vec<FmaIo<BabyBear>> {
+        let (mut rng, num_test_cases) = initialize();
+        let mut events = Vec::with_capacity(num_test_cases);
+        for _ in 0..num_test_cases {
+            let in1 = BabyBear::from_wrapped_u32(rng.gen());
+            let in2 = BabyBear::from_wrapped_u32(rng.gen());
+            let in3 = BabyBear::from_wrapped_u32(rng.gen());
+            events.push(FmaIo { out: in1 * in2 + in3, in1, in2, in3 });
+        }
+        events
+    }
+
+    fn range_check_events() -> Vec<RangeCheckIo<BabyBear>> {
+        let (mut rng, num_test_cases) = initialize();
+        let mut events = Vec::with_capacity(num_test_cases);
+        for _ in 0..num_test_cases {
+            let num_bits = rng.gen_range(0..=super::range_check::RANGE_CHECK_MAX_BITS);
+            let value = BabyBear::from_canonical_u32(rng.gen_range(0..1 << num_bits));
+            events.push(RangeCheckIo { value });
+        }
+        events
+    }
+
     fn poseidon2_events() -> Vec<Poseidon2Event<BabyBear>> {
         let (mut rng, num_test_cases) = initialize();
         let mut events = Vec::with_capacity(num_test_cases);
@@ -194,6 +298,12 @@ pub mod test_fixtures {
         events
     }
 
+    fn mem_var_events() -> Vec<MemEvent<BabyBear>> {
+        let (mut rng, num_test_cases) = initialize();
+        // Two events per row, so that the trace has at least `MIN_TEST_CASES` rows.
+        (0..2 * num_test_cases).map(|_| MemEvent { inner: Block(rng.gen()) }).collect()
+    }
+
     fn base_alu_instructions() -> Vec<Instruction<BabyBear>> {
         let (mut rng, num_test_cases) = initialize();
         let mut instructions = Vec::with_capacity(num_test_cases);
@@ -212,6 +322,7 @@ pub mod test_fixtures {
                     in1: Address(BabyBear::from_wrapped_u32(rng.gen())),
                     in2: Address(BabyBear::from_wrapped_u32(rng.gen())),
                 },
+                imms: BaseAluImms { in1: rng.gen(), in2: rng.gen() },
             }));
         }
         instructions
@@ -338,6 +449,35 @@ pub mod test_fixtures {
         instructions
     }
 
+    fn fma_instructions() -> Vec<Instruction<BabyBear>> {
+        let (mut rng, num_test_cases) = initialize();
+        let mut instructions = Vec::with_capacity(num_test_cases);
+        for _ in 0..num_test_cases {
+            instructions.push(Instruction::Fma(FmaInstr {
+                addrs: FmaIo {
+                    out: Address(BabyBear::from_wrapped_u32(rng.gen())),
+                    in1: Address(BabyBear::from_wrapped_u32(rng.gen())),
+                    in2: Address(BabyBear::from_wrapped_u32(rng.gen())),
+                    in3: Address(BabyBear::from_wrapped_u32(rng.gen())),
+                },
+                mult: BabyBear::from_wrapped_u32(rng.gen()),
+            }));
+        }
+        instructions
+    }
+
+    fn range_check_instructions() -> Vec<Instruction<BabyBear>> {
+        let (mut rng, num_test_cases) = initialize();
+        let mut instructions = Vec::with_capacity(num_test_cases);
+        for _ in 0..num_test_cases {
+            instructions.push(Instruction::RangeCheck(RangeCheckInstr {
+                addrs: RangeCheckIo { value: Address(BabyBear::from_wrapped_u32(rng.gen())) },
+                num_bits: rng.gen_range(0..=super::range_check::RANGE_CHECK_MAX_BITS as u32),
+            }));
+        }
+        instructions
+    }
+
     fn poseidon2_instructions() -> Vec<Instruction<BabyBear>> {
         let (mut rng, num_test_cases) = initialize();
         let mut instructions = Vec::with_capacity(num_test_cases);
@@ -350,8 +490,42 @@ pub mod test_fixtures {
             instructions.push(Instruction::Poseidon2(Box::new(Poseidon2Instr {
                 addrs: Poseidon2Io { input, output },
                 mults,
+                padding: rng.gen_range(0..=crate::HASH_RATE as u32),
             })));
         }
         instructions
     }
+
+    fn mem_instructions() -> Vec<Instruction<BabyBear>> {
+        let (mut rng, num_test_cases) = initialize();
+        // Two accesses per row, so that the trace has at least `MIN_TEST_CASES` rows.
+        let num_accesses = super::mem::constant::NUM_CONST_MEM_ENTRIES_PER_ROW * num_test_cases;
+        let mut instructions = Vec::with_capacity(num_accesses);
+        for _ in 0..num_accesses {
+            let kind = if rng.gen_bool(0.5) { MemAccessKind::Read } else { MemAccessKind::Write };
+            instructions.push(Instruction::Mem(MemInstr {
+                addrs: MemIo { inner: Address(BabyBear::from_wrapped_u32(rng.gen())) },
+                vals: MemIo { inner: Block(rng.gen()) },
+                mult: BabyBear::from_wrapped_u32(rng.gen()),
+                kind,
+            }));
+        }
+        instructions
+    }
+
+    fn hint_instructions() -> Vec<Instruction<BabyBear>> {
+        let (mut rng, num_test_cases) = initialize();
+        let mut instructions = Vec::with_capacity(num_test_cases);
+        for _ in 0..num_test_cases {
+            let len = rng.gen_range(2..5); // At least one row of accesses per instruction
+            let output_addrs_mults = (0..len)
+                .map(|_| {
+                    let addr = Address(BabyBear::from_wrapped_u32(rng.gen()));
+                    (addr, BabyBear::from_wrapped_u32(rng.gen()))
+                })
+                .collect();
+            instructions.push(Instruction::Hint(instruction::HintInstr { output_addrs_mults }));
+        }
+        instructions
+    }
 }
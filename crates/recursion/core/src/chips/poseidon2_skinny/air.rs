@@ -10,8 +10,13 @@ use p3_matrix::Matrix;
 use crate::{builder::SP1RecursionAirBuilder, chips::poseidon2_skinny::columns::Poseidon2};
 
 use super::{
-    columns::{preprocessed::Poseidon2PreprocessedCols, NUM_POSEIDON2_COLS},
-    external_linear_layer, internal_linear_layer, Poseidon2SkinnyChip, NUM_INTERNAL_ROUNDS, WIDTH,
+    columns::{
+        preprocessed::{Poseidon2PaddingCols, Poseidon2PreprocessedCols},
+        NUM_POSEIDON2_COLS,
+    },
+    eval_padding, external_linear_layer, internal_linear_layer,
+    trace::PREPROCESSED_POSEIDON2_WIDTH,
+    Poseidon2SkinnyChip, NUM_INTERNAL_ROUNDS, WIDTH,
 };
 
 impl<F, const DEGREE: usize> BaseAir<F> for Poseidon2SkinnyChip<DEGREE> {
@@ -37,7 +42,8 @@ where
         let next_row: &Poseidon2<_> = (*next_row).borrow();
         let prepr = builder.preprocessed();
         let prep_local = prepr.row_slice(0);
-        let prep_local: &Poseidon2PreprocessedCols<_> = (*prep_local).borrow();
+        let (prep_local, prep_padding) = prep_local.split_at(PREPROCESSED_POSEIDON2_WIDTH);
+        let prep_local: &Poseidon2PreprocessedCols<_> = prep_local.borrow();
 
         // Dummy constraints to normalize to DEGREE.
         let lhs = (0..DEGREE).map(|_| local_row.state_var[0].into()).product::<AB::Expr>();
@@ -53,6 +59,13 @@ where
             )
         });
 
+        // The padded lanes of the input row are not read from memory, so they are constrained
+        // here instead.
+        if self.padding {
+            let prep_padding: &Poseidon2PaddingCols<_> = prep_padding.borrow();
+            eval_padding(builder, local_row.state_var, prep_padding.is_padding);
+        }
+
         self.eval_input_round(builder, local_row, prep_local, next_row);
 
         self.eval_external_round(builder, local_row, prep_local, next_row);
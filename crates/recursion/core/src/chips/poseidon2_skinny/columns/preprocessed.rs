@@ -1,6 +1,11 @@
+use std::mem::size_of;
+
 use sp1_derive::AlignedBorrow;
 
-use crate::chips::{mem::MemoryAccessColsChips, poseidon2_skinny::WIDTH};
+use crate::{
+    chips::{mem::MemoryAccessColsChips, poseidon2_skinny::WIDTH},
+    HASH_RATE,
+};
 
 #[derive(AlignedBorrow, Clone, Copy, Debug)]
 #[repr(C)]
@@ -19,3 +24,15 @@ pub struct Poseidon2PreprocessedColsSkinny<T: Copy> {
 }
 
 pub type Poseidon2PreprocessedCols<T> = Poseidon2PreprocessedColsSkinny<T>;
+
+pub const NUM_POSEIDON2_PADDING_COLS: usize = size_of::<Poseidon2PaddingCols<u8>>();
+
+/// The preprocessed columns appended to the rows of the Poseidon2 chips that support padded
+/// absorbs, after the columns of the chip.
+#[derive(AlignedBorrow, Clone, Copy, Debug)]
+#[repr(C)]
+pub struct Poseidon2PaddingCols<T: Copy> {
+    /// Whether each rate lane of the input holds the sponge padding instead of a value read from
+    /// memory. In the skinny chip, zero on every row but the input row.
+    pub is_padding: [T; HASH_RATE],
+}
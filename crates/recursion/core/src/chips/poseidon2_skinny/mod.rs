@@ -1,5 +1,4 @@
-use std::marker::PhantomData;
-
+use p3_air::AirBuilder;
 use p3_baby_bear::{MONTY_INVERSE, POSEIDON2_INTERNAL_MATRIX_DIAG_16_BABYBEAR_MONTY};
 use p3_field::{AbstractField, PrimeField32};
 
@@ -9,6 +8,8 @@ pub mod trace;
 
 use p3_poseidon2::matmul_internal;
 
+use crate::HASH_RATE;
+
 /// The width of the permutation.
 pub const WIDTH: usize = 16;
 pub const RATE: usize = WIDTH / 2;
@@ -19,13 +20,30 @@ pub const NUM_ROUNDS: usize = NUM_EXTERNAL_ROUNDS + NUM_INTERNAL_ROUNDS;
 
 /// A chip that implements the Poseidon2 permutation in the skinny variant (one external round per
 /// row and one row for all internal rounds).
-pub struct Poseidon2SkinnyChip<const DEGREE: usize>(PhantomData<()>);
+pub struct Poseidon2SkinnyChip<const DEGREE: usize> {
+    /// Whether the chip supports padded absorbs, at the cost of one extra preprocessed column per
+    /// rate lane.
+    padding: bool,
+}
 
 impl<const DEGREE: usize> Default for Poseidon2SkinnyChip<DEGREE> {
     fn default() -> Self {
         // We only support machines with degree 9.
         assert!(DEGREE >= 9);
-        Self(PhantomData)
+        Self { padding: false }
+    }
+}
+
+impl<const DEGREE: usize> Poseidon2SkinnyChip<DEGREE> {
+    /// A chip that supports padded absorbs, whose padded input lanes are constrained instead of
+    /// read from memory.
+    ///
+    /// This changes the preprocessed trace of the chip, and so the verifying keys of the machines
+    /// that include it.
+    pub fn with_padding() -> Self {
+        // We only support machines with degree 9.
+        assert!(DEGREE >= 9);
+        Self { padding: true }
     }
 }
 pub fn apply_m_4<AF>(x: &mut [AF])
@@ -56,6 +74,23 @@ pub(crate) fn external_linear_layer<AF: AbstractField>(state: &mut [AF; WIDTH])
     }
 }
 
+/// Constrains the padded rate lanes of the permutation input to hold the `10*` padding: a one in
+/// the first padded lane and zeroes after it.
+pub(crate) fn eval_padding<AB: AirBuilder>(
+    builder: &mut AB,
+    input: [AB::Var; WIDTH],
+    is_padding: [AB::Var; HASH_RATE],
+) {
+    for i in 0..HASH_RATE {
+        // The first padded lane is the one where `is_padding` turns on.
+        let is_first: AB::Expr = match i {
+            0 => is_padding[i].into(),
+            _ => is_padding[i] - is_padding[i - 1],
+        };
+        builder.when(is_padding[i]).assert_eq(input[i], is_first);
+    }
+}
+
 pub(crate) fn internal_linear_layer<F: AbstractField>(state: &mut [F; WIDTH]) {
     let matmul_constants: [<F as AbstractField>::F; WIDTH] =
         POSEIDON2_INTERNAL_MATRIX_DIAG_16_BABYBEAR_MONTY
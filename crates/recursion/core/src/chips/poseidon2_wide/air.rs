@@ -10,9 +10,13 @@ use sp1_core_machine::operations::poseidon2::permutation::NUM_POSEIDON2_DEGREE9_
 use sp1_core_machine::operations::poseidon2::NUM_EXTERNAL_ROUNDS;
 use sp1_core_machine::operations::poseidon2::WIDTH;
 
+use super::trace::PREPROCESSED_POSEIDON2_WIDTH;
 use super::Poseidon2WideChip;
 use crate::builder::SP1RecursionAirBuilder;
+use crate::chips::poseidon2_skinny::columns::preprocessed::Poseidon2PaddingCols;
+use crate::chips::poseidon2_skinny::eval_padding;
 use crate::chips::poseidon2_wide::columns::preprocessed::Poseidon2PreprocessedColsWide;
+use crate::HASH_RATE;
 
 impl<F, const DEGREE: usize> BaseAir<F> for Poseidon2WideChip<DEGREE> {
     fn width(&self) -> usize {
@@ -36,7 +40,10 @@ where
         let prepr = builder.preprocessed();
         let local_row = Self::convert::<AB::Var>(main.row_slice(0));
         let prep_local = prepr.row_slice(0);
-        let prep_local: &Poseidon2PreprocessedColsWide<_> = (*prep_local).borrow();
+        let (prep_local, prep_padding) = prep_local.split_at(PREPROCESSED_POSEIDON2_WIDTH);
+        let prep_local: &Poseidon2PreprocessedColsWide<_> = prep_local.borrow();
+        let prep_padding: Option<&Poseidon2PaddingCols<_>> =
+            self.padding.then(|| prep_padding.borrow());
 
         // Dummy constraints to normalize to DEGREE.
         let lhs = (0..DEGREE)
@@ -47,15 +54,21 @@ where
             .product::<AB::Expr>();
         builder.assert_eq(lhs, rhs);
 
-        // For now, include only memory constraints.
+        // For now, include only memory constraints. The padded lanes are not read from memory.
         (0..WIDTH).for_each(|i| {
-            builder.send_single(
-                prep_local.input[i],
-                local_row.external_rounds_state()[0][i],
-                prep_local.is_real_neg,
-            )
+            let mult: AB::Expr = match prep_padding {
+                Some(prep_padding) if i < HASH_RATE => {
+                    prep_local.is_real_neg + prep_padding.is_padding[i]
+                }
+                _ => prep_local.is_real_neg.into(),
+            };
+            builder.send_single(prep_local.input[i], local_row.external_rounds_state()[0][i], mult)
         });
 
+        if let Some(prep_padding) = prep_padding {
+            eval_padding(builder, local_row.external_rounds_state()[0], prep_padding.is_padding);
+        }
+
         (0..WIDTH).for_each(|i| {
             builder.send_single(
                 prep_local.output[i].addr,
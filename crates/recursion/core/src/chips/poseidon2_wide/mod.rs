@@ -12,7 +12,22 @@ pub mod trace;
 
 /// A chip that implements addition for the opcode Poseidon2Wide.
 #[derive(Default, Debug, Clone, Copy)]
-pub struct Poseidon2WideChip<const DEGREE: usize>;
+pub struct Poseidon2WideChip<const DEGREE: usize> {
+    /// Whether the chip supports padded absorbs, at the cost of one extra preprocessed column per
+    /// rate lane.
+    padding: bool,
+}
+
+impl<const DEGREE: usize> Poseidon2WideChip<DEGREE> {
+    /// A chip that supports padded absorbs, whose padded input lanes are constrained instead of
+    /// read from memory.
+    ///
+    /// This changes the preprocessed trace of the chip, and so the verifying keys of the machines
+    /// that include it.
+    pub fn with_padding() -> Self {
+        Self { padding: true }
+    }
+}
 
 impl<'a, const DEGREE: usize> Poseidon2WideChip<DEGREE> {
     /// Transmute a row it to an immutable [`Poseidon2Cols`] instance.
@@ -1,19 +1,21 @@
-use crate::{
-    instruction::Instruction::Poseidon2, ExecutionRecord, Poseidon2Io, Poseidon2SkinnyInstr,
-};
+use crate::{ExecutionRecord, Poseidon2Io, Poseidon2SkinnyInstr};
 use p3_air::BaseAir;
 use p3_baby_bear::BabyBear;
 use p3_field::{AbstractField, PrimeField32};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_maybe_rayon::prelude::*;
 use sp1_core_machine::{operations::poseidon2::WIDTH, utils::next_power_of_two};
-use sp1_stark::air::MachineAir;
+use sp1_stark::{air::MachineAir, ColMajorMatrix};
 use std::{borrow::BorrowMut, mem::size_of};
 use tracing::instrument;
 
 use super::{columns::preprocessed::Poseidon2PreprocessedColsWide, Poseidon2WideChip};
+use crate::chips::poseidon2_skinny::columns::preprocessed::{
+    Poseidon2PaddingCols, NUM_POSEIDON2_PADDING_COLS,
+};
 
-const PREPROCESSED_POSEIDON2_WIDTH: usize = size_of::<Poseidon2PreprocessedColsWide<u8>>();
+pub(crate) const PREPROCESSED_POSEIDON2_WIDTH: usize =
+    size_of::<Poseidon2PreprocessedColsWide<u8>>();
 
 impl<F: PrimeField32, const DEGREE: usize> MachineAir<F> for Poseidon2WideChip<DEGREE> {
     type Record = ExecutionRecord<F>;
@@ -90,16 +92,68 @@ impl<F: PrimeField32, const DEGREE: usize> MachineAir<F> for Poseidon2WideChip<D
         )
     }
 
+    #[instrument(name = "generate poseidon2 wide col-major trace", level = "debug", skip_all, fields(rows = input.poseidon2_events.len()))]
+    fn generate_trace_col_major(
+        &self,
+        input: &ExecutionRecord<F>,
+        _output: &mut ExecutionRecord<F>,
+    ) -> ColMajorMatrix<F> {
+        assert_eq!(
+            std::any::TypeId::of::<F>(),
+            std::any::TypeId::of::<BabyBear>(),
+            "generate_trace_col_major only supports BabyBear field"
+        );
+
+        let events = unsafe {
+            std::mem::transmute::<&Vec<Poseidon2Io<F>>, &Vec<Poseidon2Io<BabyBear>>>(
+                &input.poseidon2_events,
+            )
+        };
+        let padded_nb_rows = self.num_rows(input).unwrap();
+        let num_columns = <Self as BaseAir<F>>::width(self);
+
+        let populate_perm_ffi = |input: &[BabyBear; WIDTH], input_row: &mut [BabyBear]| unsafe {
+            crate::sys::poseidon2_wide_event_to_row_babybear(
+                input.as_ptr(),
+                input_row.as_mut_ptr(),
+                DEGREE == 3,
+            )
+        };
+        let mut dummy_row = vec![BabyBear::zero(); num_columns];
+        populate_perm_ffi(&[BabyBear::zero(); WIDTH], &mut dummy_row);
+
+        let mut trace = ColMajorMatrix::<BabyBear>::zeros(padded_nb_rows, num_columns);
+        trace.par_fill_rows(|r, row| match events.get(r) {
+            Some(event) => populate_perm_ffi(&event.input, row),
+            None => row.copy_from_slice(&dummy_row),
+        });
+
+        let ColMajorMatrix { values, height } = trace;
+        ColMajorMatrix::new(unsafe { std::mem::transmute::<Vec<BabyBear>, Vec<F>>(values) }, height)
+    }
+
     fn included(&self, _record: &Self::Record) -> bool {
         true
     }
 
+    fn is_empty_shard(&self, shard: &Self::Record) -> bool {
+        shard.poseidon2_events.is_empty() && shard.program.is_prunable(|index| &index.poseidon2)
+    }
+
+    fn is_empty_program(&self, program: &Self::Program) -> bool {
+        program.is_prunable(|index| &index.poseidon2)
+    }
+
     fn local_only(&self) -> bool {
         true
     }
 
     fn preprocessed_width(&self) -> usize {
-        PREPROCESSED_POSEIDON2_WIDTH
+        if self.padding {
+            PREPROCESSED_POSEIDON2_WIDTH + NUM_POSEIDON2_PADDING_COLS
+        } else {
+            PREPROCESSED_POSEIDON2_WIDTH
+        }
     }
 
     fn preprocessed_num_rows(&self, program: &Self::Program, instrs_len: usize) -> Option<usize> {
@@ -117,37 +171,37 @@ impl<F: PrimeField32, const DEGREE: usize> MachineAir<F> for Poseidon2WideChip<D
         );
 
         // Allocating an intermediate `Vec` is faster.
-        let instrs: Vec<&Poseidon2SkinnyInstr<BabyBear>> =
-            program
-                .inner
-                .iter() // Faster than using `rayon` for some reason. Maybe vectorization?
-                .filter_map(|instruction| match instruction {
-                    Poseidon2(instr) => Some(unsafe {
-                        std::mem::transmute::<
-                            &Poseidon2SkinnyInstr<F>,
-                            &Poseidon2SkinnyInstr<BabyBear>,
-                        >(instr.as_ref())
-                    }),
-                    _ => None,
-                })
-                .collect::<Vec<_>>();
+        let instrs = unsafe {
+            std::mem::transmute::<_, Vec<&Poseidon2SkinnyInstr<BabyBear>>>(
+                program.poseidon2_instrs(),
+            )
+        };
+        assert!(
+            self.padding || instrs.iter().all(|instr| instr.padding == 0),
+            "padded Poseidon2 absorbs need a chip with padding enabled"
+        );
+
+        let width = <Self as MachineAir<F>>::preprocessed_width(self);
         let padded_nb_rows = self.preprocessed_num_rows(program, instrs.len()).unwrap();
-        let mut values = vec![BabyBear::zero(); padded_nb_rows * PREPROCESSED_POSEIDON2_WIDTH];
-
-        let populate_len = instrs.len() * PREPROCESSED_POSEIDON2_WIDTH;
-        values[..populate_len]
-            .par_chunks_mut(PREPROCESSED_POSEIDON2_WIDTH)
-            .zip_eq(instrs)
-            .for_each(|(row, instr)| {
-                let cols: &mut Poseidon2PreprocessedColsWide<_> = row.borrow_mut();
-                unsafe {
-                    crate::sys::poseidon2_wide_instr_to_row_babybear(instr, cols);
-                }
-            });
+        let mut values = vec![BabyBear::zero(); padded_nb_rows * width];
+
+        let populate_len = instrs.len() * width;
+        values[..populate_len].par_chunks_mut(width).zip_eq(instrs).for_each(|(row, instr)| {
+            let (row, padding_row) = row.split_at_mut(PREPROCESSED_POSEIDON2_WIDTH);
+            let cols: &mut Poseidon2PreprocessedColsWide<_> = row.borrow_mut();
+            unsafe {
+                crate::sys::poseidon2_wide_instr_to_row_babybear(instr, cols);
+            }
+            if self.padding {
+                let padding_cols: &mut Poseidon2PaddingCols<_> = padding_row.borrow_mut();
+                padding_cols.is_padding =
+                    std::array::from_fn(|j| BabyBear::from_bool(instr.is_padding(j)));
+            }
+        });
 
         Some(RowMajorMatrix::new(
             unsafe { std::mem::transmute::<Vec<BabyBear>, Vec<F>>(values) },
-            PREPROCESSED_POSEIDON2_WIDTH,
+            width,
         ))
     }
 }
@@ -156,6 +210,7 @@ impl<F: PrimeField32, const DEGREE: usize> MachineAir<F> for Poseidon2WideChip<D
 mod tests {
     use crate::{
         chips::{mem::MemoryAccessCols, poseidon2_wide::Poseidon2WideChip, test_fixtures},
+        instruction::Instruction::Poseidon2,
         ExecutionRecord, RecursionProgram,
     };
     use p3_baby_bear::BabyBear;
@@ -176,7 +231,7 @@ mod tests {
         type F = BabyBear;
 
         let events = &input.poseidon2_events;
-        let chip = Poseidon2WideChip::<DEGREE>;
+        let chip = Poseidon2WideChip::<DEGREE>::default();
         let padded_nb_rows = chip.num_rows(input).unwrap();
         let num_columns = <Poseidon2WideChip<DEGREE> as BaseAir<F>>::width(&chip);
         let mut values = vec![F::zero(); padded_nb_rows * num_columns];
@@ -204,11 +259,28 @@ mod tests {
         RowMajorMatrix::new(values, num_columns)
     }
 
+    fn assert_col_major_matches<const DEGREE: usize>() {
+        let chip = Poseidon2WideChip::<DEGREE>::default();
+        let shard = test_fixtures::shard();
+        let mut execution_record = test_fixtures::default_execution_record();
+        let row_major = chip.generate_trace(&shard, &mut execution_record);
+        assert_eq!(
+            chip.generate_trace_col_major(&shard, &mut execution_record),
+            ColMajorMatrix::from_row_major(&row_major)
+        );
+    }
+
+    #[test]
+    fn test_generate_trace_col_major() {
+        assert_col_major_matches::<DEGREE_3>();
+        assert_col_major_matches::<DEGREE_9>();
+    }
+
     #[test]
     fn test_generate_trace_deg_3() {
         let shard = test_fixtures::shard();
         let mut execution_record = test_fixtures::default_execution_record();
-        let chip = Poseidon2WideChip::<DEGREE_3>;
+        let chip = Poseidon2WideChip::<DEGREE_3>::default();
         let trace = chip.generate_trace(&shard, &mut execution_record);
         assert!(trace.height() >= test_fixtures::MIN_TEST_CASES);
 
@@ -219,7 +291,7 @@ mod tests {
     fn test_generate_trace_deg_9() {
         let shard = test_fixtures::shard();
         let mut execution_record = test_fixtures::default_execution_record();
-        let chip = Poseidon2WideChip::<DEGREE_9>;
+        let chip = Poseidon2WideChip::<DEGREE_9>::default();
         let trace = chip.generate_trace(&shard, &mut execution_record);
         assert!(trace.height() >= test_fixtures::MIN_TEST_CASES);
 
@@ -240,38 +312,40 @@ mod tests {
             })
             .collect::<Vec<_>>();
         let padded_nb_rows = Poseidon2WideChip::<DEGREE>::preprocessed_num_rows(
-            &Poseidon2WideChip::<DEGREE>,
+            &Poseidon2WideChip::<DEGREE>::with_padding(),
             program,
             instrs.len(),
         )
         .unwrap();
-        let mut values = vec![F::zero(); padded_nb_rows * PREPROCESSED_POSEIDON2_WIDTH];
-
-        let populate_len = instrs.len() * PREPROCESSED_POSEIDON2_WIDTH;
-        values[..populate_len]
-            .par_chunks_mut(PREPROCESSED_POSEIDON2_WIDTH)
-            .zip_eq(instrs)
-            .for_each(|(row, instr)| {
-                // Set the memory columns. We read once, at the first iteration,
-                // and write once, at the last iteration.
-                *row.borrow_mut() = Poseidon2PreprocessedColsWide {
-                    input: instr.addrs.input,
-                    output: std::array::from_fn(|j| MemoryAccessCols {
-                        addr: instr.addrs.output[j],
-                        mult: instr.mults[j],
-                    }),
-                    is_real_neg: F::neg_one(),
-                }
-            });
-
-        RowMajorMatrix::new(values, PREPROCESSED_POSEIDON2_WIDTH)
+        let width = PREPROCESSED_POSEIDON2_WIDTH + NUM_POSEIDON2_PADDING_COLS;
+        let mut values = vec![F::zero(); padded_nb_rows * width];
+
+        let populate_len = instrs.len() * width;
+        values[..populate_len].par_chunks_mut(width).zip_eq(instrs).for_each(|(row, instr)| {
+            let (row, padding_row) = row.split_at_mut(PREPROCESSED_POSEIDON2_WIDTH);
+            // Set the memory columns. We read once, at the first iteration,
+            // and write once, at the last iteration.
+            *row.borrow_mut() = Poseidon2PreprocessedColsWide {
+                input: instr.addrs.input,
+                output: std::array::from_fn(|j| MemoryAccessCols {
+                    addr: instr.addrs.output[j],
+                    mult: instr.mults[j],
+                }),
+                is_real_neg: F::neg_one(),
+            };
+            *padding_row.borrow_mut() = Poseidon2PaddingCols {
+                is_padding: std::array::from_fn(|j| F::from_bool(instr.is_padding(j))),
+            };
+        });
+
+        RowMajorMatrix::new(values, width)
     }
 
     #[test]
     #[ignore = "Failing due to merge conflicts. Will be fixed shortly."]
     fn test_generate_preprocessed_trace_deg_3() {
         let program = test_fixtures::program();
-        let chip = Poseidon2WideChip::<DEGREE_3>;
+        let chip = Poseidon2WideChip::<DEGREE_3>::with_padding();
         let trace = chip.generate_preprocessed_trace(&program).unwrap();
         assert!(trace.height() >= test_fixtures::MIN_TEST_CASES);
 
@@ -282,7 +356,7 @@ mod tests {
     #[ignore = "Failing due to merge conflicts. Will be fixed shortly."]
     fn test_generate_preprocessed_trace_deg_9() {
         let program = test_fixtures::program();
-        let chip = Poseidon2WideChip::<DEGREE_9>;
+        let chip = Poseidon2WideChip::<DEGREE_9>::with_padding();
         let trace = chip.generate_preprocessed_trace(&program).unwrap();
         assert!(trace.height() >= test_fixtures::MIN_TEST_CASES);
 
@@ -1,19 +1,21 @@
 use crate::{
-    air::{RecursionPublicValues, RECURSIVE_PROOF_NUM_PV_ELTS},
-    builder::SP1RecursionAirBuilder,
-    runtime::Instruction,
-    CommitPublicValuesEvent, CommitPublicValuesInstr, ExecutionRecord, DIGEST_SIZE,
+    air::RECURSION_PUBLIC_VALUES_COL_MAP, builder::SP1RecursionAirBuilder, Address,
+    CommitPublicValuesEvent, CommitPublicValuesInstr, ExecutionRecord, RecursionProgram,
+    DIGEST_SIZE,
 };
 use p3_air::{Air, AirBuilder, BaseAir, PairBuilder};
 use p3_baby_bear::BabyBear;
 use p3_field::{AbstractField, PrimeField32};
 use p3_matrix::{dense::RowMajorMatrix, Matrix};
-use sp1_core_machine::utils::pad_rows_fixed;
+use sp1_core_machine::utils::{next_power_of_two, pad_rows_fixed};
 use sp1_derive::AlignedBorrow;
-use sp1_stark::air::MachineAir;
-use std::borrow::{Borrow, BorrowMut};
+use sp1_stark::{air::MachineAir, PROOF_MAX_NUM_PVS};
+use std::{
+    borrow::{Borrow, BorrowMut},
+    iter::zip,
+};
 
-use super::mem::MemoryAccessColsChips;
+use super::mem::{MemoryAccessColsChips, NUM_MEM_ACCESS_COLS};
 
 pub const NUM_PUBLIC_VALUES_COLS: usize = core::mem::size_of::<PublicValuesCols<u8>>();
 pub const NUM_PUBLIC_VALUES_PREPROCESSED_COLS: usize =
@@ -21,8 +23,57 @@ pub const NUM_PUBLIC_VALUES_PREPROCESSED_COLS: usize =
 
 pub const PUB_VALUES_LOG_HEIGHT: usize = 4;
 
-#[derive(Default)]
-pub struct PublicValuesChip;
+/// The chip committing the digest of the public values, and optionally extra public values that
+/// follow the digest.
+///
+/// Each committed element takes a row, so at most `1 << PUB_VALUES_LOG_HEIGHT` elements can be
+/// committed.
+#[derive(Debug, Clone, Copy)]
+pub struct PublicValuesChip {
+    num_committed: usize,
+}
+
+impl Default for PublicValuesChip {
+    fn default() -> Self {
+        Self { num_committed: DIGEST_SIZE }
+    }
+}
+
+impl PublicValuesChip {
+    /// A chip committing `num_extra` public values after the digest. With no extra public values,
+    /// the layout of the chip is the default one.
+    pub fn with_num_extra(num_extra: usize) -> Self {
+        let num_committed = DIGEST_SIZE + num_extra;
+        assert!(
+            num_committed <= 1 << PUB_VALUES_LOG_HEIGHT,
+            "at most {} extra public values can be committed, got {num_extra}",
+            (1 << PUB_VALUES_LOG_HEIGHT) - DIGEST_SIZE
+        );
+        Self { num_committed }
+    }
+
+    /// The number of public values committed after the digest.
+    pub const fn num_extra(&self) -> usize {
+        self.num_committed - DIGEST_SIZE
+    }
+
+    /// The extra public values committed by the program, checked against the declared count.
+    fn extra_instr_addrs<'a, F: PrimeField32>(
+        &self,
+        program: &'a RecursionProgram<F>,
+    ) -> &'a [Address<F>] {
+        let instrs = program.commit_extra_pv_instrs();
+        let addrs = instrs.first().copied().map_or(&[][..], |instr| instr.addrs.as_slice());
+        assert_eq!(
+            addrs.len(),
+            self.num_extra(),
+            "the program commits {} extra public values, but the public values chip declares {}",
+            addrs.len(),
+            self.num_extra()
+        );
+        addrs
+    }
+}
 
 /// The preprocessed columns for the CommitPVHash instruction.
 #[derive(AlignedBorrow, Debug, Clone, Copy)]
@@ -48,7 +99,7 @@ impl<F> BaseAir<F> for PublicValuesChip {
 impl<F: PrimeField32> MachineAir<F> for PublicValuesChip {
     type Record = ExecutionRecord<F>;
 
-    type Program = crate::RecursionProgram<F>;
+    type Program = RecursionProgram<F>;
 
     fn name(&self) -> String {
         "PublicValues".to_string()
@@ -59,10 +110,20 @@ impl<F: PrimeField32> MachineAir<F> for PublicValuesChip {
     }
 
     fn preprocessed_width(&self) -> usize {
-        NUM_PUBLIC_VALUES_PREPROCESSED_COLS
+        // The one-hot index of the committed element, followed by its memory access.
+        self.num_committed + NUM_MEM_ACCESS_COLS
+    }
+
+    fn num_public_values(&self) -> usize {
+        PROOF_MAX_NUM_PVS + self.num_extra()
     }
 
     fn generate_preprocessed_trace(&self, program: &Self::Program) -> Option<RowMajorMatrix<F>> {
+        let extra_addrs = self.extra_instr_addrs(program);
+        if self.num_extra() > 0 {
+            return Some(self.generate_extended_preprocessed_trace(program, extra_addrs));
+        }
+
         assert_eq!(
             std::any::TypeId::of::<F>(),
             std::any::TypeId::of::<BabyBear>(),
@@ -70,22 +131,12 @@ impl<F: PrimeField32> MachineAir<F> for PublicValuesChip {
         );
 
         let mut rows: Vec<[BabyBear; NUM_PUBLIC_VALUES_PREPROCESSED_COLS]> = Vec::new();
-        let commit_pv_hash_instrs: Vec<&Box<CommitPublicValuesInstr<BabyBear>>> = program
-            .inner
-            .iter()
-            .filter_map(|instruction| {
-                if let Instruction::CommitPublicValues(instr) = instruction {
-                    Some(unsafe {
-                        std::mem::transmute::<
-                            &Box<CommitPublicValuesInstr<F>>,
-                            &Box<CommitPublicValuesInstr<BabyBear>>,
-                        >(instr)
-                    })
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>();
+        let commit_pv_hash_instrs = unsafe {
+            std::mem::transmute::<
+                Vec<&CommitPublicValuesInstr<F>>,
+                Vec<&CommitPublicValuesInstr<BabyBear>>,
+            >(program.commit_pv_hash_instrs())
+        };
 
         if commit_pv_hash_instrs.len() != 1 {
             tracing::warn!("Expected exactly one CommitPVHash instruction.");
@@ -135,6 +186,17 @@ impl<F: PrimeField32> MachineAir<F> for PublicValuesChip {
             "generate_trace only supports BabyBear field"
         );
 
+        assert_eq!(
+            input.extra_public_values.len(),
+            self.num_extra(),
+            "the record has {} extra public values, but the public values chip declares {}",
+            input.extra_public_values.len(),
+            self.num_extra()
+        );
+        if self.num_extra() > 0 {
+            return self.generate_extended_trace(input);
+        }
+
         if input.commit_pv_hash_events.len() != 1 {
             tracing::warn!("Expected exactly one CommitPVHash event.");
         }
@@ -182,6 +244,50 @@ impl<F: PrimeField32> MachineAir<F> for PublicValuesChip {
     }
 }
 
+impl PublicValuesChip {
+    /// Generates the preprocessed trace of a chip committing extra public values, in Rust.
+    fn generate_extended_preprocessed_trace<F: PrimeField32>(
+        &self,
+        program: &RecursionProgram<F>,
+        extra_addrs: &[Address<F>],
+    ) -> RowMajorMatrix<F> {
+        let width = MachineAir::<F>::preprocessed_width(self);
+        let nb_rows = next_power_of_two(self.num_committed, Some(PUB_VALUES_LOG_HEIGHT));
+        let mut values = vec![F::zero(); nb_rows * width];
+
+        if let Some(instr) = program.commit_pv_hash_instrs().first() {
+            let addrs = instr.pv_addrs.digest.iter().chain(extra_addrs);
+            for (i, (row, addr)) in zip(values.chunks_exact_mut(width), addrs).enumerate() {
+                let (pv_idx, pv_mem) = row.split_at_mut(self.num_committed);
+                pv_idx[i] = F::one();
+                let pv_mem: &mut MemoryAccessColsChips<F> = pv_mem.borrow_mut();
+                *pv_mem = MemoryAccessColsChips { addr: *addr, mult: F::neg_one() };
+            }
+        }
+
+        RowMajorMatrix::new(values, width)
+    }
+
+    /// Generates the trace of a chip committing extra public values, in Rust.
+    fn generate_extended_trace<F: PrimeField32>(
+        &self,
+        input: &ExecutionRecord<F>,
+    ) -> RowMajorMatrix<F> {
+        let nb_rows = next_power_of_two(self.num_committed, Some(PUB_VALUES_LOG_HEIGHT));
+        let mut values = vec![F::zero(); nb_rows * NUM_PUBLIC_VALUES_COLS];
+
+        if let Some(event) = input.commit_pv_hash_events.first() {
+            let elements = event.public_values.digest.iter().chain(&input.extra_public_values);
+            for (row, element) in zip(values.chunks_exact_mut(NUM_PUBLIC_VALUES_COLS), elements) {
+                let cols: &mut PublicValuesCols<F> = row.borrow_mut();
+                cols.pv_element = *element;
+            }
+        }
+
+        RowMajorMatrix::new(values, NUM_PUBLIC_VALUES_COLS)
+    }
+}
+
 impl<AB> Air<AB> for PublicValuesChip
 where
     AB: SP1RecursionAirBuilder + PairBuilder,
@@ -192,19 +298,24 @@ where
         let local: &PublicValuesCols<AB::Var> = (*local).borrow();
         let prepr = builder.preprocessed();
         let local_prepr = prepr.row_slice(0);
-        let local_prepr: &PublicValuesPreprocessedCols<AB::Var> = (*local_prepr).borrow();
+        let (pv_idx, pv_mem) = local_prepr.split_at(self.num_committed);
+        let pv_mem: &MemoryAccessColsChips<AB::Var> = pv_mem.borrow();
         let pv = builder.public_values();
-        let pv_elms: [AB::Expr; RECURSIVE_PROOF_NUM_PV_ELTS] =
-            core::array::from_fn(|i| pv[i].into());
-        let public_values: &RecursionPublicValues<AB::Expr> = pv_elms.as_slice().borrow();
+        // The committed elements are the digest, which ends the fixed layout of the public values,
+        // followed by the extra public values.
+        let digest_start = RECURSION_PUBLIC_VALUES_COL_MAP.digest[0];
+        let committed = pv[digest_start..digest_start + self.num_committed]
+            .iter()
+            .map(|&pv_elm| pv_elm.into())
+            .collect::<Vec<AB::Expr>>();
 
         // Constrain mem read for the public value element.
-        builder.send_single(local_prepr.pv_mem.addr, local.pv_element, local_prepr.pv_mem.mult);
+        builder.send_single(pv_mem.addr, local.pv_element, pv_mem.mult);
 
-        for (i, pv_elm) in public_values.digest.iter().enumerate() {
+        for (&idx, pv_elm) in zip(pv_idx, committed) {
             // Ensure that the public value element is the same for all rows within a fri fold
             // invocation.
-            builder.when(local_prepr.pv_idx[i]).assert_eq(pv_elm.clone(), local.pv_element);
+            builder.when(idx).assert_eq(pv_elm, local.pv_element);
         }
     }
 }
@@ -221,20 +332,26 @@ mod tests {
             },
             test_fixtures,
         },
-        machine::tests::test_recursion_linear_program,
+        linear_program,
+        machine::{tests::test_recursion_linear_program, RecursionAir},
         runtime::{instruction as instr, ExecutionRecord},
         stark::BabyBearPoseidon2Outer,
-        Instruction, MemAccessKind, RecursionProgram, DIGEST_SIZE,
+        Instruction, MemAccessKind, RecursionProgram, Runtime, DIGEST_SIZE,
     };
-    use p3_baby_bear::BabyBear;
+    use p3_air::BaseAir;
+    use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
     use p3_field::AbstractField;
     use p3_matrix::{dense::RowMajorMatrix, Matrix};
     use rand::{rngs::StdRng, Rng, SeedableRng};
-    use sp1_core_machine::utils::{pad_rows_fixed, setup_logger};
-    use sp1_stark::{air::MachineAir, StarkGenericConfig};
+    use sp1_core_machine::utils::{pad_rows_fixed, run_test_machine, setup_logger};
+    use sp1_stark::{
+        air::MachineAir, baby_bear_poseidon2::BabyBearPoseidon2, MachineRecord, StarkGenericConfig,
+        PROOF_MAX_NUM_PVS,
+    };
     use std::{
         array,
         borrow::{Borrow, BorrowMut},
+        sync::Arc,
     };
 
     #[test]
@@ -266,12 +383,85 @@ mod tests {
         test_recursion_linear_program(instructions);
     }
 
+    #[test]
+    fn prove_babybear_circuit_extra_public_values() {
+        setup_logger();
+        type SC = BabyBearPoseidon2;
+        type F = <SC as StarkGenericConfig>::Val;
+        type EF = <SC as StarkGenericConfig>::Challenge;
+        const NUM_EXTRA: usize = 8;
+
+        let mut rng = StdRng::seed_from_u64(0xDEADBEEF);
+        let mut random_felt = move || -> F { F::from_canonical_u32(rng.gen_range(0..1 << 16)) };
+        let public_values_a: [u32; RECURSIVE_PROOF_NUM_PV_ELTS] = array::from_fn(|i| i as u32);
+        let extra_a: [u32; NUM_EXTRA] =
+            array::from_fn(|i| (RECURSIVE_PROOF_NUM_PV_ELTS + i) as u32);
+
+        let mut instructions = Vec::new();
+        for (i, &addr) in public_values_a.iter().enumerate() {
+            let mult = (NUM_PV_ELMS_TO_HASH..NUM_PV_ELMS_TO_HASH + DIGEST_SIZE).contains(&i);
+            instructions.push(instr::mem_block(
+                MemAccessKind::Write,
+                mult as u32,
+                addr,
+                random_felt().into(),
+            ));
+        }
+        let extra_values: [F; NUM_EXTRA] = array::from_fn(|_| random_felt());
+        for (&addr, &value) in extra_a.iter().zip(extra_values.iter()) {
+            instructions.push(instr::mem_block(MemAccessKind::Write, 1, addr, value.into()));
+        }
+        let public_values_a: &RecursionPublicValues<u32> = public_values_a.as_slice().borrow();
+        instructions.push(instr::commit_public_values(public_values_a));
+        instructions.push(instr::commit_extra_public_values(&extra_a));
+
+        let program = Arc::new(linear_program(instructions).unwrap());
+        let mut runtime =
+            Runtime::<F, EF, DiffusionMatrixBabyBear>::new(program.clone(), SC::default().perm);
+        runtime.run().unwrap();
+        let public_values = runtime.record.public_values::<F>();
+        assert_eq!(public_values.len(), PROOF_MAX_NUM_PVS + NUM_EXTRA);
+        assert_eq!(public_values[PROOF_MAX_NUM_PVS..], extra_values);
+
+        let machine = RecursionAir::<F, 3>::compress_machine_with_extra_public_values(
+            SC::default(),
+            NUM_EXTRA,
+        );
+        let (pk, vk) = machine.setup(&program);
+        run_test_machine(vec![runtime.record], machine, pk, vk).expect("Verification failed");
+    }
+
+    #[test]
+    fn default_layout_unchanged() {
+        let chip = PublicValuesChip::default();
+        assert_eq!(chip.num_extra(), 0);
+        assert_eq!(
+            MachineAir::<BabyBear>::preprocessed_width(&chip),
+            NUM_PUBLIC_VALUES_PREPROCESSED_COLS
+        );
+        assert_eq!(BaseAir::<BabyBear>::width(&chip), NUM_PUBLIC_VALUES_COLS);
+        assert_eq!(MachineAir::<BabyBear>::num_public_values(&chip), PROOF_MAX_NUM_PVS);
+        assert_eq!(
+            MachineAir::<BabyBear>::preprocessed_width(&PublicValuesChip::with_num_extra(0)),
+            NUM_PUBLIC_VALUES_PREPROCESSED_COLS
+        );
+
+        let shard = test_fixtures::shard();
+        assert_eq!(shard.public_values::<BabyBear>().len(), PROOF_MAX_NUM_PVS);
+    }
+
+    #[test]
+    #[should_panic(expected = "at most 12 extra public values")]
+    fn too_many_extra_public_values() {
+        PublicValuesChip::with_num_extra(13);
+    }
+
     #[test]
     #[ignore = "Failing due to merge conflicts. Will be fixed shortly."]
     fn generate_public_values_preprocessed_trace() {
         let program = test_fixtures::program();
 
-        let chip = PublicValuesChip;
+        let chip = PublicValuesChip::default();
         let trace = chip.generate_preprocessed_trace(&program).unwrap();
         println!("{:?}", trace.values);
     }
@@ -313,7 +503,8 @@ mod tests {
     #[test]
     fn test_generate_trace() {
         let shard = test_fixtures::shard();
-        let trace = PublicValuesChip.generate_trace(&shard, &mut ExecutionRecord::default());
+        let trace =
+            PublicValuesChip::default().generate_trace(&shard, &mut ExecutionRecord::default());
         assert_eq!(trace.height(), 16);
 
         assert_eq!(trace, generate_trace_reference(&shard, &mut ExecutionRecord::default()));
@@ -369,7 +560,7 @@ mod tests {
     #[ignore = "Failing due to merge conflicts. Will be fixed shortly."]
     fn test_generate_preprocessed_trace() {
         let program = test_fixtures::program();
-        let trace = PublicValuesChip.generate_preprocessed_trace(&program).unwrap();
+        let trace = PublicValuesChip::default().generate_preprocessed_trace(&program).unwrap();
         assert_eq!(trace.height(), 16);
 
         assert_eq!(trace, generate_preprocessed_trace_reference(&program));
@@ -0,0 +1,406 @@
+use core::borrow::Borrow;
+use p3_air::{Air, AirBuilder, BaseAir, PairBuilder};
+use p3_field::{AbstractField, Field, PrimeField32};
+use p3_matrix::{dense::RowMajorMatrix, Matrix};
+use sp1_core_machine::utils::next_power_of_two;
+use sp1_derive::AlignedBorrow;
+use sp1_stark::{air::MachineAir, fill_trace, RowPadding};
+use std::{array, iter::zip};
+
+use crate::{builder::SP1RecursionAirBuilder, *};
+
+/// The largest number of bits a value can be range checked against.
+///
+/// The bits of the value are summed without reduction, so their sum must stay below the BabyBear
+/// modulus.
+pub const RANGE_CHECK_MAX_BITS: usize = 30;
+
+/// A chip asserting that a base field element fits in a number of bits, by decomposing it into
+/// bits in a single row.
+///
+/// The chip is only part of the machines with all chips: adding it to the compress, shrink and
+/// wrap machines would change their verifying keys.
+#[derive(Default)]
+pub struct RangeCheckChip;
+
+pub const RANGE_CHECK_COLS: usize = core::mem::size_of::<RangeCheckCols<u8>>();
+
+#[derive(AlignedBorrow, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct RangeCheckCols<F: Copy> {
+    pub vals: RangeCheckIo<F>,
+    /// The bits of the value, in little-endian order.
+    pub bits: [F; RANGE_CHECK_MAX_BITS],
+}
+
+pub const RANGE_CHECK_PREPROCESSED_COLS: usize =
+    core::mem::size_of::<RangeCheckPreprocessedCols<u8>>();
+
+#[derive(AlignedBorrow, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct RangeCheckPreprocessedCols<F: Copy> {
+    pub is_real: F,
+    pub addrs: RangeCheckIo<Address<F>>,
+    /// The bit width of the check: the first `num_bits` entries are one, and the others zero.
+    pub bit_mask: [F; RANGE_CHECK_MAX_BITS],
+}
+
+impl<F: Field> BaseAir<F> for RangeCheckChip {
+    fn width(&self) -> usize {
+        RANGE_CHECK_COLS
+    }
+}
+
+impl<F: PrimeField32> MachineAir<F> for RangeCheckChip {
+    type Record = ExecutionRecord<F>;
+
+    type Program = crate::RecursionProgram<F>;
+
+    fn name(&self) -> String {
+        "RangeCheck".to_string()
+    }
+
+    fn preprocessed_width(&self) -> usize {
+        RANGE_CHECK_PREPROCESSED_COLS
+    }
+
+    fn preprocessed_num_rows(&self, program: &Self::Program, instrs_len: usize) -> Option<usize> {
+        let fixed_log2_rows = program.fixed_log2_rows(self);
+        Some(match fixed_log2_rows {
+            Some(log2_rows) => 1 << log2_rows,
+            None => next_power_of_two(instrs_len, None),
+        })
+    }
+
+    fn generate_preprocessed_trace(&self, program: &Self::Program) -> Option<RowMajorMatrix<F>> {
+        let instrs = program.range_check_instrs();
+        Some(fill_trace(
+            &instrs,
+            self.preprocessed_num_rows(program, instrs.len()).unwrap(),
+            RANGE_CHECK_PREPROCESSED_COLS,
+            RowPadding::Zero,
+            |instr, cols| instr_to_row(instr, cols),
+        ))
+    }
+
+    fn generate_dependencies(&self, _: &Self::Record, _: &mut Self::Record) {
+        // This is a no-op.
+    }
+
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        Some(next_power_of_two(input.range_check_events.len(), input.fixed_log2_rows(self)))
+    }
+
+    fn generate_trace(&self, input: &Self::Record, _: &mut Self::Record) -> RowMajorMatrix<F> {
+        fill_trace(
+            &input.range_check_events,
+            self.num_rows(input).unwrap(),
+            RANGE_CHECK_COLS,
+            RowPadding::Zero,
+            event_to_row,
+        )
+    }
+
+    fn included(&self, _record: &Self::Record) -> bool {
+        true
+    }
+
+    fn is_empty_shard(&self, shard: &Self::Record) -> bool {
+        shard.range_check_events.is_empty() && shard.program.is_prunable(|index| &index.range_check)
+    }
+
+    fn is_empty_program(&self, program: &Self::Program) -> bool {
+        program.is_prunable(|index| &index.range_check)
+    }
+
+    fn local_only(&self) -> bool {
+        true
+    }
+}
+
+/// Populates the columns of a range check event, with the FFI kernel when the field is BabyBear
+/// and the `sys` feature is enabled, and in Rust otherwise.
+fn event_to_row<F: PrimeField32>(event: &RangeCheckEvent<F>, cols: &mut RangeCheckCols<F>) {
+    #[cfg(feature = "sys")]
+    if std::any::TypeId::of::<F>() == std::any::TypeId::of::<p3_baby_bear::BabyBear>() {
+        use p3_baby_bear::BabyBear;
+        // SAFETY: `F` is `BabyBear`.
+        unsafe {
+            crate::sys::range_check_event_to_row_babybear(
+                std::mem::transmute::<&RangeCheckEvent<F>, &RangeCheckEvent<BabyBear>>(event),
+                std::mem::transmute::<&mut RangeCheckCols<F>, &mut RangeCheckCols<BabyBear>>(cols),
+            );
+        }
+        return;
+    }
+    event_to_row_rust(event, cols);
+}
+
+fn event_to_row_rust<F: PrimeField32>(event: &RangeCheckEvent<F>, cols: &mut RangeCheckCols<F>) {
+    let value = event.value.as_canonical_u32();
+    *cols = RangeCheckCols {
+        vals: *event,
+        bits: array::from_fn(|i| F::from_bool((value >> i) & 1 == 1)),
+    };
+}
+
+/// Populates the preprocessed columns of a range check instruction, with the FFI kernel when the
+/// field is BabyBear and the `sys` feature is enabled, and in Rust otherwise.
+fn instr_to_row<F: PrimeField32>(
+    instr: &RangeCheckInstr<F>,
+    cols: &mut RangeCheckPreprocessedCols<F>,
+) {
+    #[cfg(feature = "sys")]
+    if std::any::TypeId::of::<F>() == std::any::TypeId::of::<p3_baby_bear::BabyBear>() {
+        use p3_baby_bear::BabyBear;
+        // SAFETY: `F` is `BabyBear`.
+        unsafe {
+            crate::sys::range_check_instr_to_row_babybear(
+                std::mem::transmute::<&RangeCheckInstr<F>, &RangeCheckInstr<BabyBear>>(instr),
+                std::mem::transmute::<
+                    &mut RangeCheckPreprocessedCols<F>,
+                    &mut RangeCheckPreprocessedCols<BabyBear>,
+                >(cols),
+            );
+        }
+        return;
+    }
+    instr_to_row_rust(instr, cols);
+}
+
+fn instr_to_row_rust<F: AbstractField + Copy>(
+    instr: &RangeCheckInstr<F>,
+    cols: &mut RangeCheckPreprocessedCols<F>,
+) {
+    let RangeCheckInstr { addrs, num_bits } = *instr;
+    *cols = RangeCheckPreprocessedCols {
+        is_real: F::one(),
+        addrs,
+        bit_mask: array::from_fn(|i| F::from_bool(i < num_bits as usize)),
+    };
+}
+
+impl<AB> Air<AB> for RangeCheckChip
+where
+    AB: SP1RecursionAirBuilder + PairBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &RangeCheckCols<AB::Var> = (*local).borrow();
+        let prep = builder.preprocessed();
+        let prep_local = prep.row_slice(0);
+        let prep_local: &RangeCheckPreprocessedCols<AB::Var> = (*prep_local).borrow();
+
+        let RangeCheckCols { vals: RangeCheckIo { value }, bits } = *local;
+        let RangeCheckPreprocessedCols { is_real, addrs, bit_mask } = *prep_local;
+        builder.receive_single(addrs.value, value, is_real);
+
+        for (bit, enabled) in zip(bits, bit_mask) {
+            builder.assert_bool(bit);
+            // The bits beyond the width of the check are zero.
+            builder.assert_zero(bit * (AB::Expr::one() - enabled));
+        }
+        // Since there are at most 30 bits, their weighted sum does not wrap around the modulus.
+        let sum = bits
+            .into_iter()
+            .enumerate()
+            .map(|(i, bit)| bit * AB::F::from_canonical_u32(1 << i))
+            .sum::<AB::Expr>();
+        builder.assert_eq(value, sum);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::BorrowMut, sync::Arc};
+
+    use crate::{
+        chips::test_fixtures::{self, trace_parity_tests},
+        instruction::HintInstr,
+        machine::RecursionAir,
+        runtime::instruction as instr,
+    };
+    use machine::tests::test_recursion_linear_program;
+    use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+    use p3_matrix::dense::RowMajorMatrix;
+    use p3_maybe_rayon::prelude::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use sp1_stark::{
+        baby_bear_poseidon2::BabyBearPoseidon2, CpuProver, MachineProof, MachineProver,
+        MachineProvingKey, StarkGenericConfig,
+    };
+
+    use super::*;
+
+    #[test]
+    pub fn prove_range_check() {
+        type SC = BabyBearPoseidon2;
+        type F = <SC as StarkGenericConfig>::Val;
+
+        let mut rng = StdRng::seed_from_u64(0xDEADBEEF);
+
+        let instructions = (0..1000)
+            .flat_map(|addr| {
+                let num_bits = rng.gen_range(0..=RANGE_CHECK_MAX_BITS as u32);
+                let value = F::from_canonical_u32(rng.gen_range(0..1 << num_bits));
+                [
+                    instr::mem_single(MemAccessKind::Write, 1, addr, value),
+                    instr::range_check(addr, num_bits),
+                ]
+            })
+            .collect::<Vec<Instruction<F>>>();
+
+        test_recursion_linear_program(instructions);
+    }
+
+    #[test]
+    fn range_check_out_of_range() {
+        type SC = BabyBearPoseidon2;
+        type F = <SC as StarkGenericConfig>::Val;
+        type EF = <SC as StarkGenericConfig>::Challenge;
+
+        let program = linear_program(vec![
+            instr::mem(MemAccessKind::Write, 1, 0, 1 << 8),
+            instr::range_check(0, 8),
+        ])
+        .unwrap();
+        let mut runtime =
+            Runtime::<F, EF, DiffusionMatrixBabyBear>::new(Arc::new(program), SC::new().perm);
+        assert!(matches!(
+            runtime.run(),
+            Err(RuntimeError::RangeCheckFailed { instr: RangeCheckInstr { num_bits: 8, .. }, .. })
+        ));
+    }
+
+    /// A prover skipping the check of the runtime cannot prove a value beyond the width of its
+    /// check: the hinted value is replaced by one of 9 bits in the events of the memory and range
+    /// check chips, so that the memory interactions still balance, and the tampered RangeCheck
+    /// trace fails the constraints of the chip.
+    #[test]
+    fn range_check_tampered_trace() {
+        type SC = BabyBearPoseidon2;
+        type F = <SC as StarkGenericConfig>::Val;
+        type EF = <SC as StarkGenericConfig>::Challenge;
+
+        let program = linear_program(vec![
+            Instruction::Hint(HintInstr {
+                output_addrs_mults: vec![(Address(F::zero()), F::one())],
+            }),
+            instr::range_check(0, 8),
+        ])
+        .unwrap();
+        let program = Arc::new(program);
+        let mut runtime =
+            Runtime::<F, EF, DiffusionMatrixBabyBear>::new(program.clone(), SC::new().perm);
+        runtime.witness_stream.push_back(F::from_canonical_u32(0xFF).into());
+        runtime.run().unwrap();
+
+        let mut record = runtime.record;
+        let value = F::from_canonical_u32(1 << 8);
+        record.mem_var_events = vec![MemEvent { inner: value.into() }].into();
+        record.range_check_events[0].value = value;
+
+        let machine = RecursionAir::<F, 3>::machine_wide_with_all_chips(SC::default());
+        let (pk, vk) = machine.setup(&program);
+        let prover = CpuProver::new(machine);
+        let mut challenger = prover.config().challenger();
+        pk.observe_into(&mut challenger);
+        let traces = prover.generate_traces(&record);
+        let data = prover.commit(&record, traces);
+        let shard_proof = prover.open(&pk, data, &mut challenger).unwrap();
+        let proof = MachineProof { shard_proofs: vec![shard_proof] };
+
+        let mut challenger = prover.config().challenger();
+        let result = prover.machine().verify(&vk, &proof, &mut challenger);
+        assert!(result.is_err_and(|err| err.is_constraints_failing("RangeCheck")));
+    }
+
+    /// The FFI kernels and the Rust path, used for other fields, populate the same rows.
+    #[test]
+    #[cfg(feature = "sys")]
+    fn ffi_matches_rust() {
+        type F = BabyBear;
+
+        for event in &test_fixtures::shard().range_check_events {
+            let (mut ffi, mut rust) =
+                ([F::zero(); RANGE_CHECK_COLS], [F::zero(); RANGE_CHECK_COLS]);
+            event_to_row(event, ffi.as_mut_slice().borrow_mut());
+            event_to_row_rust(event, rust.as_mut_slice().borrow_mut());
+            assert_eq!(ffi, rust);
+        }
+
+        let program = test_fixtures::unvalidated_program();
+        for instr in program.range_check_instrs() {
+            let (mut ffi, mut rust) = (
+                [F::zero(); RANGE_CHECK_PREPROCESSED_COLS],
+                [F::zero(); RANGE_CHECK_PREPROCESSED_COLS],
+            );
+            instr_to_row(instr, ffi.as_mut_slice().borrow_mut());
+            instr_to_row_rust(instr, rust.as_mut_slice().borrow_mut());
+            assert_eq!(ffi, rust);
+        }
+    }
+
+    fn generate_trace_reference(
+        input: &ExecutionRecord<BabyBear>,
+        _: &mut ExecutionRecord<BabyBear>,
+    ) -> RowMajorMatrix<BabyBear> {
+        type F = BabyBear;
+
+        let events = &input.range_check_events;
+        let padded_nb_rows = RangeCheckChip.num_rows(input).unwrap();
+        let mut values = vec![F::zero(); padded_nb_rows * RANGE_CHECK_COLS];
+
+        let populate_len = events.len() * RANGE_CHECK_COLS;
+        values[..populate_len].par_chunks_mut(RANGE_CHECK_COLS).zip_eq(events).for_each(
+            |(row, &vals)| {
+                let cols: &mut RangeCheckCols<_> = row.borrow_mut();
+                let value = vals.value.as_canonical_u32();
+                cols.vals = vals;
+                for (i, bit) in cols.bits.iter_mut().enumerate() {
+                    *bit = F::from_canonical_u32((value >> i) & 1);
+                }
+            },
+        );
+
+        RowMajorMatrix::new(values, RANGE_CHECK_COLS)
+    }
+
+    fn generate_preprocessed_trace_reference(
+        program: &RecursionProgram<BabyBear>,
+    ) -> RowMajorMatrix<BabyBear> {
+        type F = BabyBear;
+
+        let instrs = program
+            .inner
+            .iter()
+            .filter_map(|instruction| match instruction {
+                Instruction::RangeCheck(x) => Some(x),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let padded_nb_rows = RangeCheckChip.preprocessed_num_rows(program, instrs.len()).unwrap();
+        let mut values = vec![F::zero(); padded_nb_rows * RANGE_CHECK_PREPROCESSED_COLS];
+
+        let populate_len = instrs.len() * RANGE_CHECK_PREPROCESSED_COLS;
+        values[..populate_len]
+            .par_chunks_mut(RANGE_CHECK_PREPROCESSED_COLS)
+            .zip_eq(instrs)
+            .for_each(|(row, instr)| {
+                let RangeCheckInstr { addrs, num_bits } = instr;
+                let cols: &mut RangeCheckPreprocessedCols<_> = row.borrow_mut();
+                cols.is_real = F::one();
+                cols.addrs = addrs.to_owned();
+                cols.bit_mask[..*num_bits as usize].fill(F::one());
+            });
+
+        RowMajorMatrix::new(values, RANGE_CHECK_PREPROCESSED_COLS)
+    }
+
+    trace_parity_tests! {
+        chip: RangeCheckChip,
+        trace: generate_trace_reference,
+        preprocessed: generate_preprocessed_trace_reference,
+    }
+}
@@ -1,24 +1,36 @@
 use core::borrow::Borrow;
 use p3_air::{Air, BaseAir, PairBuilder};
-use p3_baby_bear::BabyBear;
 use p3_field::{AbstractField, Field, PrimeField32};
 use p3_matrix::{dense::RowMajorMatrix, Matrix};
-use p3_maybe_rayon::prelude::*;
 use sp1_core_machine::utils::next_power_of_two;
 use sp1_derive::AlignedBorrow;
-use sp1_stark::air::MachineAir;
-use std::borrow::BorrowMut;
+use sp1_stark::{air::MachineAir, fill_trace, ColMajorMatrix, RowPadding, TraceBuffer};
+use std::iter::zip;
 
 use crate::{builder::SP1RecursionAirBuilder, *};
 
+/// The number of select events packed in a row of the trace of the recursion machines.
+///
+/// Changing the packing changes the verifying keys of the recursion programs.
+pub const NUM_SELECT_ENTRIES_PER_ROW: usize = 1;
+
+/// The select chip, packing `NUM_ENTRIES` select events in each row of its trace.
 #[derive(Default)]
-pub struct SelectChip;
+pub struct SelectChip<const NUM_ENTRIES: usize = NUM_SELECT_ENTRIES_PER_ROW>;
 
 pub const SELECT_COLS: usize = core::mem::size_of::<SelectCols<u8>>();
 
 #[derive(AlignedBorrow, Debug, Clone, Copy)]
 #[repr(C)]
-pub struct SelectCols<F: Copy> {
+pub struct SelectCols<F: Copy, const NUM_ENTRIES: usize = NUM_SELECT_ENTRIES_PER_ROW> {
+    pub values: [SelectValueCols<F>; NUM_ENTRIES],
+}
+
+pub const SELECT_VALUE_COLS: usize = core::mem::size_of::<SelectValueCols<u8>>();
+
+#[derive(AlignedBorrow, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct SelectValueCols<F: Copy> {
     pub vals: SelectIo<F>,
 }
 
@@ -26,20 +38,28 @@ pub const SELECT_PREPROCESSED_COLS: usize = core::mem::size_of::<SelectPreproces
 
 #[derive(AlignedBorrow, Debug, Clone, Copy)]
 #[repr(C)]
-pub struct SelectPreprocessedCols<F: Copy> {
+pub struct SelectPreprocessedCols<F: Copy, const NUM_ENTRIES: usize = NUM_SELECT_ENTRIES_PER_ROW> {
+    pub accesses: [SelectAccessCols<F>; NUM_ENTRIES],
+}
+
+pub const SELECT_ACCESS_COLS: usize = core::mem::size_of::<SelectAccessCols<u8>>();
+
+#[derive(AlignedBorrow, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct SelectAccessCols<F: Copy> {
     pub is_real: F,
     pub addrs: SelectIo<Address<F>>,
     pub mult1: F,
     pub mult2: F,
 }
 
-impl<F: Field> BaseAir<F> for SelectChip {
+impl<F: Field, const NUM_ENTRIES: usize> BaseAir<F> for SelectChip<NUM_ENTRIES> {
     fn width(&self) -> usize {
-        SELECT_COLS
+        SELECT_VALUE_COLS * NUM_ENTRIES
     }
 }
 
-impl<F: PrimeField32> MachineAir<F> for SelectChip {
+impl<F: PrimeField32, const NUM_ENTRIES: usize> MachineAir<F> for SelectChip<NUM_ENTRIES> {
     type Record = ExecutionRecord<F>;
 
     type Program = crate::RecursionProgram<F>;
@@ -49,54 +69,26 @@ impl<F: PrimeField32> MachineAir<F> for SelectChip {
     }
 
     fn preprocessed_width(&self) -> usize {
-        SELECT_PREPROCESSED_COLS
+        SELECT_ACCESS_COLS * NUM_ENTRIES
     }
 
     fn preprocessed_num_rows(&self, program: &Self::Program, instrs_len: usize) -> Option<usize> {
+        let nb_rows = instrs_len.div_ceil(NUM_ENTRIES);
         let fixed_log2_rows = program.fixed_log2_rows(self);
         Some(match fixed_log2_rows {
             Some(log2_rows) => 1 << log2_rows,
-            None => next_power_of_two(instrs_len, None),
+            None => next_power_of_two(nb_rows, None),
         })
     }
 
     fn generate_preprocessed_trace(&self, program: &Self::Program) -> Option<RowMajorMatrix<F>> {
-        assert_eq!(
-            std::any::TypeId::of::<F>(),
-            std::any::TypeId::of::<BabyBear>(),
-            "generate_preprocessed_trace only supports BabyBear field"
-        );
-
-        let instrs = unsafe {
-            std::mem::transmute::<Vec<&SelectInstr<F>>, Vec<&SelectInstr<BabyBear>>>(
-                program
-                    .inner
-                    .iter()
-                    .filter_map(|instruction| match instruction {
-                        Instruction::Select(x) => Some(x),
-                        _ => None,
-                    })
-                    .collect::<Vec<_>>(),
-            )
-        };
-        let padded_nb_rows = self.preprocessed_num_rows(program, instrs.len()).unwrap();
-        let mut values = vec![BabyBear::zero(); padded_nb_rows * SELECT_PREPROCESSED_COLS];
-
-        // Generate the trace rows & corresponding records for each chunk of events in parallel.
-        let populate_len = instrs.len() * SELECT_PREPROCESSED_COLS;
-        values[..populate_len].par_chunks_mut(SELECT_PREPROCESSED_COLS).zip_eq(instrs).for_each(
-            |(row, instr)| {
-                let cols: &mut SelectPreprocessedCols<_> = row.borrow_mut();
-                unsafe {
-                    crate::sys::select_instr_to_row_babybear(instr, cols);
-                }
-            },
-        );
-
-        // Convert the trace to a row major matrix.
-        Some(RowMajorMatrix::new(
-            unsafe { std::mem::transmute::<Vec<BabyBear>, Vec<F>>(values) },
-            SELECT_PREPROCESSED_COLS,
+        let instrs = program.select_instrs();
+        Some(fill_trace(
+            &instrs,
+            self.preprocessed_num_rows(program, instrs.len()).unwrap(),
+            MachineAir::<F>::preprocessed_width(self),
+            RowPadding::Zero,
+            |instr, access| instr_to_row(instr, access),
         ))
     }
 
@@ -105,99 +97,166 @@ impl<F: PrimeField32> MachineAir<F> for SelectChip {
     }
 
     fn num_rows(&self, input: &Self::Record) -> Option<usize> {
-        let events = &input.select_events;
-        Some(next_power_of_two(events.len(), input.fixed_log2_rows(self)))
+        let nb_rows = input.select_events.len().div_ceil(NUM_ENTRIES);
+        Some(next_power_of_two(nb_rows, input.fixed_log2_rows(self)))
     }
 
-    fn generate_trace(&self, input: &Self::Record, _: &mut Self::Record) -> RowMajorMatrix<F> {
-        assert_eq!(
-            std::any::TypeId::of::<F>(),
-            std::any::TypeId::of::<BabyBear>(),
-            "generate_trace only supports BabyBear field"
-        );
-
-        let events = unsafe {
-            std::mem::transmute::<&Vec<SelectIo<F>>, &Vec<SelectIo<BabyBear>>>(&input.select_events)
-        };
-        let padded_nb_rows = self.num_rows(input).unwrap();
-        let mut values = vec![BabyBear::zero(); padded_nb_rows * SELECT_COLS];
+    fn generate_trace(&self, input: &Self::Record, output: &mut Self::Record) -> RowMajorMatrix<F> {
+        let mut target = TraceBuffer::default();
+        self.generate_trace_into(input, output, &mut target);
+        target.into_matrix()
+    }
 
-        // Generate the trace rows & corresponding records for each chunk of events in parallel.
-        let populate_len = events.len() * SELECT_COLS;
-        values[..populate_len].par_chunks_mut(SELECT_COLS).zip_eq(events).for_each(
-            |(row, &vals)| {
-                let cols: &mut SelectCols<_> = row.borrow_mut();
-                unsafe {
-                    crate::sys::select_event_to_row_babybear(&vals, cols);
-                }
-            },
-        );
+    fn generate_trace_into(
+        &self,
+        input: &Self::Record,
+        _: &mut Self::Record,
+        target: &mut TraceBuffer<F>,
+    ) {
+        #[cfg(all(debug_assertions, feature = "debug"))]
+        debug_check_multiplicities("Select", &input.program, |instruction| {
+            matches!(instruction, Instruction::Select(_))
+        });
+
+        let width = BaseAir::<F>::width(self);
+        let values = target.reset(self.num_rows(input).unwrap(), width);
+        input.select_events.write_rows(values, width, NUM_ENTRIES);
+    }
 
-        // Convert the trace to a row major matrix.
-        RowMajorMatrix::new(
-            unsafe { std::mem::transmute::<Vec<BabyBear>, Vec<_>>(values) },
-            SELECT_COLS,
-        )
+    fn generate_trace_col_major(
+        &self,
+        input: &Self::Record,
+        _: &mut Self::Record,
+    ) -> ColMajorMatrix<F> {
+        let width = BaseAir::<F>::width(self);
+        let mut trace = ColMajorMatrix::zeros(self.num_rows(input).unwrap(), width);
+        input.select_events.write_col_major(&mut trace, NUM_ENTRIES);
+        trace
     }
 
     fn included(&self, _record: &Self::Record) -> bool {
         true
     }
 
+    fn is_empty_shard(&self, shard: &Self::Record) -> bool {
+        shard.select_events.is_empty() && shard.program.is_prunable(|index| &index.select)
+    }
+
+    fn is_empty_program(&self, program: &Self::Program) -> bool {
+        program.is_prunable(|index| &index.select)
+    }
+
     fn local_only(&self) -> bool {
         true
     }
 }
 
-impl<AB> Air<AB> for SelectChip
+/// Populates the access columns of a select instruction, with the FFI kernel when the field is
+/// BabyBear and the `sys` feature is enabled, and in Rust otherwise.
+fn instr_to_row<F: PrimeField32>(instr: &SelectInstr<F>, access: &mut SelectAccessCols<F>) {
+    #[cfg(feature = "sys")]
+    if std::any::TypeId::of::<F>() == std::any::TypeId::of::<p3_baby_bear::BabyBear>() {
+        use p3_baby_bear::BabyBear;
+        // SAFETY: `F` is `BabyBear`.
+        unsafe {
+            crate::sys::select_instr_to_row_babybear(
+                std::mem::transmute::<&SelectInstr<F>, &SelectInstr<BabyBear>>(instr),
+                std::mem::transmute::<&mut SelectAccessCols<F>, &mut SelectAccessCols<BabyBear>>(
+                    access,
+                ),
+            );
+        }
+        return;
+    }
+    instr_to_row_rust(instr, access);
+}
+
+fn instr_to_row_rust<F: AbstractField + Copy>(
+    instr: &SelectInstr<F>,
+    access: &mut SelectAccessCols<F>,
+) {
+    let SelectInstr { addrs, mult1, mult2 } = *instr;
+    *access = SelectAccessCols { is_real: F::one(), addrs, mult1, mult2 };
+}
+
+impl<AB, const NUM_ENTRIES: usize> Air<AB> for SelectChip<NUM_ENTRIES>
 where
     AB: SP1RecursionAirBuilder + PairBuilder,
 {
     fn eval(&self, builder: &mut AB) {
         let main = builder.main();
         let local = main.row_slice(0);
-        let local: &SelectCols<AB::Var> = (*local).borrow();
+        let local: &SelectCols<AB::Var, NUM_ENTRIES> = (*local).borrow();
         let prep = builder.preprocessed();
         let prep_local = prep.row_slice(0);
-        let prep_local: &SelectPreprocessedCols<AB::Var> = (*prep_local).borrow();
-
-        builder.receive_single(prep_local.addrs.bit, local.vals.bit, prep_local.is_real);
-        builder.receive_single(prep_local.addrs.in1, local.vals.in1, prep_local.is_real);
-        builder.receive_single(prep_local.addrs.in2, local.vals.in2, prep_local.is_real);
-        builder.send_single(prep_local.addrs.out1, local.vals.out1, prep_local.mult1);
-        builder.send_single(prep_local.addrs.out2, local.vals.out2, prep_local.mult2);
-        builder.assert_eq(
-            local.vals.out1,
-            local.vals.bit * local.vals.in2 + (AB::Expr::one() - local.vals.bit) * local.vals.in1,
-        );
-        builder.assert_eq(
-            local.vals.out2,
-            local.vals.bit * local.vals.in1 + (AB::Expr::one() - local.vals.bit) * local.vals.in2,
-        );
+        let prep_local: &SelectPreprocessedCols<AB::Var, NUM_ENTRIES> = (*prep_local).borrow();
+
+        for (
+            SelectValueCols { vals: SelectIo { bit, out1, out2, in1, in2 } },
+            SelectAccessCols { is_real, addrs, mult1, mult2 },
+        ) in zip(local.values, prep_local.accesses)
+        {
+            builder.receive_single(addrs.bit, bit, is_real);
+            // The selector is read from memory, so nothing else guarantees that it is a bit.
+            builder.when(is_real).assert_bool(bit);
+            builder.receive_single(addrs.in1, in1, is_real);
+            builder.receive_single(addrs.in2, in2, is_real);
+            builder.send_single(addrs.out1, out1, mult1);
+            builder.send_single(addrs.out2, out2, mult2);
+            builder.assert_eq(out1, bit * in2 + (AB::Expr::one() - bit) * in1);
+            builder.assert_eq(out2, bit * in1 + (AB::Expr::one() - bit) * in2);
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{chips::test_fixtures, runtime::instruction as instr};
+    use crate::{
+        chips::test_fixtures::{self, trace_parity_tests},
+        runtime::instruction as instr,
+    };
     use machine::tests::test_recursion_linear_program;
-    use p3_baby_bear::BabyBear;
-    use p3_field::AbstractField;
+    use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+    use p3_field::{extension::BinomiallyExtendable, AbstractField};
     use p3_matrix::dense::RowMajorMatrix;
+    use p3_maybe_rayon::prelude::*;
     use rand::{rngs::StdRng, Rng, SeedableRng};
-    use sp1_stark::{baby_bear_poseidon2::BabyBearPoseidon2, StarkGenericConfig};
+    use sp1_core_machine::utils::run_test_machine;
+    use sp1_stark::{
+        baby_bear_poseidon2::BabyBearPoseidon2, Chip, StarkGenericConfig, StarkMachine,
+        PROOF_MAX_NUM_PVS,
+    };
+    use std::{borrow::BorrowMut, sync::Arc};
 
     use super::*;
+    use crate::chips::mem::MemoryConstChip;
+
+    type SC = BabyBearPoseidon2;
+    type EF = <SC as StarkGenericConfig>::Challenge;
+
+    /// The chip of the recursion machines.
+    const CHIP: SelectChip = SelectChip;
+
+    /// The chips needed to prove select instructions, with `NUM_ENTRIES` select events per row.
+    #[derive(sp1_derive::MachineAir)]
+    #[sp1_core_path = "sp1_core_machine"]
+    #[execution_record_path = "crate::ExecutionRecord<F>"]
+    #[program_path = "crate::RecursionProgram<F>"]
+    #[builder_path = "crate::builder::SP1RecursionAirBuilder<F = F>"]
+    #[eval_trait_bound = "AB::Var: 'static"]
+    enum PackedSelectAir<F: PrimeField32 + BinomiallyExtendable<D>, const NUM_ENTRIES: usize> {
+        MemoryConst(MemoryConstChip<F>),
+        Select(SelectChip<NUM_ENTRIES>),
+    }
 
-    #[test]
-    pub fn prove_select() {
-        type SC = BabyBearPoseidon2;
-        type F = <SC as StarkGenericConfig>::Val;
+    /// Instructions that select between random values with random bits, and read the outputs.
+    fn select_instructions(num_selects: usize) -> Vec<Instruction<BabyBear>> {
+        type F = BabyBear;
 
         let mut rng = StdRng::seed_from_u64(0xDEADBEEF);
         let mut addr = 0;
 
-        let instructions = (0..1000)
+        (0..num_selects)
             .flat_map(|_| {
                 let in1: F = rng.sample(rand::distributions::Standard);
                 let in2: F = rng.sample(rand::distributions::Standard);
@@ -217,9 +276,78 @@ mod tests {
                     instr::mem_single(MemAccessKind::Read, 1, a[2], out2),
                 ]
             })
-            .collect::<Vec<Instruction<F>>>();
+            .collect()
+    }
 
-        test_recursion_linear_program(instructions);
+    /// Proves select instructions with `NUM_ENTRIES` select events per row, leaving the last row
+    /// partially filled.
+    fn prove_packed_select<const NUM_ENTRIES: usize>() {
+        let num_selects = 999;
+        let program = Arc::new(linear_program(select_instructions(num_selects)).unwrap());
+        let mut runtime =
+            Runtime::<BabyBear, EF, DiffusionMatrixBabyBear>::new(program.clone(), SC::new().perm);
+        runtime.run().unwrap();
+
+        let chip = SelectChip::<NUM_ENTRIES>;
+        let num_rows = num_selects.div_ceil(NUM_ENTRIES).next_power_of_two();
+        assert_eq!(chip.num_rows(&runtime.record), Some(num_rows));
+        assert_eq!(chip.preprocessed_num_rows(&program, num_selects), Some(num_rows));
+        let chips = [
+            PackedSelectAir::MemoryConst(MemoryConstChip::default()),
+            PackedSelectAir::Select(chip),
+        ]
+        .map(Chip::new)
+        .into_iter()
+        .collect();
+        let machine = StarkMachine::new(SC::new(), chips, PROOF_MAX_NUM_PVS, false);
+        let (pk, vk) = machine.setup(&program);
+        run_test_machine(vec![runtime.record], machine, pk, vk).expect("Verification failed");
+    }
+
+    #[test]
+    pub fn prove_select() {
+        test_recursion_linear_program(select_instructions(1000));
+    }
+
+    #[test]
+    fn prove_select_two_entries_per_row() {
+        prove_packed_select::<2>();
+    }
+
+    #[test]
+    fn prove_select_four_entries_per_row() {
+        prove_packed_select::<4>();
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn select_non_boolean_bit() {
+        // With `bit = 2`, the outputs `2 * in2 - in1` and `2 * in1 - in2` satisfy the selection
+        // constraints, so only the booleanity constraint rejects the trace.
+        test_recursion_linear_program(vec![
+            instr::mem(MemAccessKind::Write, 1, 0, 2),
+            instr::mem(MemAccessKind::Write, 1, 3, 3),
+            instr::mem(MemAccessKind::Write, 1, 4, 5),
+            instr::select(1, 1, 0, 1, 2, 3, 4),
+            instr::mem(MemAccessKind::Read, 1, 1, 7),
+            instr::mem(MemAccessKind::Read, 1, 2, 1),
+        ]);
+    }
+
+    /// The FFI kernel and the Rust path, used for other fields, populate the same rows.
+    #[test]
+    #[cfg(feature = "sys")]
+    fn ffi_matches_rust() {
+        type F = BabyBear;
+
+        let program = test_fixtures::unvalidated_program();
+        for instr in program.select_instrs() {
+            let (mut ffi, mut rust) =
+                ([F::zero(); SELECT_ACCESS_COLS], [F::zero(); SELECT_ACCESS_COLS]);
+            instr_to_row(instr, ffi.as_mut_slice().borrow_mut());
+            instr_to_row_rust(instr, rust.as_mut_slice().borrow_mut());
+            assert_eq!(ffi, rust);
+        }
     }
 
     fn generate_trace_reference(
@@ -228,15 +356,15 @@ mod tests {
     ) -> RowMajorMatrix<BabyBear> {
         type F = BabyBear;
 
-        let events = &input.select_events;
-        let padded_nb_rows = SelectChip.num_rows(input).unwrap();
+        let events = input.select_events.to_vec();
+        let padded_nb_rows = CHIP.num_rows(input).unwrap();
         let mut values = vec![F::zero(); padded_nb_rows * SELECT_COLS];
 
-        let populate_len = events.len() * SELECT_COLS;
-        values[..populate_len].par_chunks_mut(SELECT_COLS).zip_eq(events).for_each(
+        let populate_len = events.len() * SELECT_VALUE_COLS;
+        values[..populate_len].par_chunks_mut(SELECT_VALUE_COLS).zip_eq(&events).for_each(
             |(row, &vals)| {
-                let cols: &mut SelectCols<_> = row.borrow_mut();
-                *cols = SelectCols { vals };
+                let cols: &mut SelectValueCols<_> = row.borrow_mut();
+                *cols = SelectValueCols { vals };
             },
         );
 
@@ -244,13 +372,43 @@ mod tests {
     }
 
     #[test]
-    fn generate_trace() {
+    fn generate_trace_col_major() {
+        let mut rng = StdRng::seed_from_u64(0xC01_3A70);
+        for num_events in [0, 1, 1000, 3000] {
+            let shard = ExecutionRecord::<BabyBear> {
+                select_events: (0..num_events)
+                    .map(|_| SelectIo {
+                        bit: BabyBear::from_bool(rng.gen()),
+                        out1: rng.gen(),
+                        out2: rng.gen(),
+                        in1: rng.gen(),
+                        in2: rng.gen(),
+                    })
+                    .collect(),
+                ..Default::default()
+            };
+            let mut execution_record = test_fixtures::default_execution_record();
+            let row_major = CHIP.generate_trace(&shard, &mut execution_record);
+            assert_eq!(
+                CHIP.generate_trace_col_major(&shard, &mut execution_record),
+                ColMajorMatrix::from_row_major(&row_major)
+            );
+        }
+    }
+
+    #[test]
+    fn generate_trace_into_reused_buffer() {
         let shard = test_fixtures::shard();
         let mut execution_record = test_fixtures::default_execution_record();
-        let trace = SelectChip.generate_trace(&shard, &mut execution_record);
-        assert!(trace.height() >= test_fixtures::MIN_TEST_CASES);
-
-        assert_eq!(trace, generate_trace_reference(&shard, &mut execution_record));
+        let reference = generate_trace_reference(&shard, &mut execution_record);
+
+        // A dirty buffer with room for the trace is zeroed and reused without reallocating.
+        let mut target = TraceBuffer::with_capacity(reference.values.len());
+        target.reset(reference.height(), SELECT_COLS).fill(BabyBear::one());
+        let capacity = target.capacity();
+        CHIP.generate_trace_into(&shard, &mut execution_record, &mut target);
+        assert_eq!(target.capacity(), capacity);
+        assert_eq!(target.into_matrix(), reference);
     }
 
     fn generate_preprocessed_trace_reference(
@@ -266,15 +424,15 @@ mod tests {
                 _ => None,
             })
             .collect::<Vec<_>>();
-        let padded_nb_rows = SelectChip.preprocessed_num_rows(program, instrs.len()).unwrap();
+        let padded_nb_rows = CHIP.preprocessed_num_rows(program, instrs.len()).unwrap();
         let mut values = vec![F::zero(); padded_nb_rows * SELECT_PREPROCESSED_COLS];
 
-        let populate_len = instrs.len() * SELECT_PREPROCESSED_COLS;
-        values[..populate_len].par_chunks_mut(SELECT_PREPROCESSED_COLS).zip_eq(instrs).for_each(
+        let populate_len = instrs.len() * SELECT_ACCESS_COLS;
+        values[..populate_len].par_chunks_mut(SELECT_ACCESS_COLS).zip_eq(instrs).for_each(
             |(row, instr)| {
                 let SelectInstr { addrs, mult1, mult2 } = instr;
-                let access: &mut SelectPreprocessedCols<_> = row.borrow_mut();
-                *access = SelectPreprocessedCols {
+                let access: &mut SelectAccessCols<_> = row.borrow_mut();
+                *access = SelectAccessCols {
                     is_real: F::one(),
                     addrs: addrs.to_owned(),
                     mult1: mult1.to_owned(),
@@ -286,13 +444,10 @@ mod tests {
         RowMajorMatrix::new(values, SELECT_PREPROCESSED_COLS)
     }
 
-    #[test]
-    #[ignore = "Failing due to merge conflicts. Will be fixed shortly."]
-    fn generate_preprocessed_trace() {
-        let program = test_fixtures::program();
-        let trace = SelectChip.generate_preprocessed_trace(&program).unwrap();
-        assert!(trace.height() >= test_fixtures::MIN_TEST_CASES);
-
-        assert_eq!(trace, generate_preprocessed_trace_reference(&program));
+    trace_parity_tests! {
+        chip: CHIP,
+        trace: generate_trace_reference,
+        #[ignore = "Failing due to merge conflicts. Will be fixed shortly."]
+        preprocessed: generate_preprocessed_trace_reference,
     }
 }
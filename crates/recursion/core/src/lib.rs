@@ -1,4 +1,4 @@
-use p3_field::PrimeField64;
+use p3_field::{AbstractField, PrimeField64};
 use serde::{Deserialize, Serialize};
 use sp1_derive::AlignedBorrow;
 
@@ -47,13 +47,25 @@ pub struct BaseAluIo<V> {
 
 pub type BaseAluEvent<F> = BaseAluIo<F>;
 
+/// Whether each input to an operation of the base field ALU is an immediate.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(C)]
+pub struct BaseAluImms<V> {
+    pub in1: V,
+    pub in2: V,
+}
+
 /// An instruction invoking the extension field ALU.
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 #[repr(C)]
 pub struct BaseAluInstr<F> {
     pub opcode: BaseAluOpcode,
     pub mult: F,
+    /// The addresses of the inputs and output. The field of an input that is an immediate holds
+    /// its value instead of an address.
     pub addrs: BaseAluIo<Address<F>>,
+    /// Whether each input is an immediate, in which case it is not read from memory.
+    pub imms: BaseAluImms<bool>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -82,11 +94,13 @@ pub struct ExtAluInstr<F> {
 
 /// The inputs and outputs to the manual memory management/memory initialization table.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(C)]
 pub struct MemIo<V> {
     pub inner: V,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(C)]
 pub struct MemInstr<F> {
     pub addrs: MemIo<Address<F>>,
     pub vals: MemIo<Block<F>>,
@@ -99,6 +113,7 @@ pub type MemEvent<F> = MemIo<Block<F>>;
 // -------------------------------------------------------------------------------------------------
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(C)]
 pub enum MemAccessKind {
     Read,
     Write,
@@ -118,6 +133,28 @@ pub struct Poseidon2Io<V> {
 pub struct Poseidon2SkinnyInstr<F> {
     pub addrs: Poseidon2Io<Address<F>>,
     pub mults: [F; WIDTH],
+    /// The number of lanes at the end of the rate that hold the `10*` padding of the last chunk
+    /// absorbed by a sponge, instead of being read from memory: the first of them is one and the
+    /// others are zero. Zero when the permutation does not finalize a padded absorb.
+    pub padding: u32,
+}
+
+impl<F> Poseidon2SkinnyInstr<F> {
+    /// Whether input lane `i` holds padding, in which case it is not read from memory.
+    pub fn is_padding(&self, i: usize) -> bool {
+        is_poseidon2_padding(self.padding, i)
+    }
+}
+
+/// Whether input lane `i` of a Poseidon2 permutation with `padding` lanes of padding holds padding.
+pub fn is_poseidon2_padding(padding: u32, i: usize) -> bool {
+    i < HASH_RATE && i + padding as usize >= HASH_RATE
+}
+
+/// The value of input lane `i` of a Poseidon2 permutation with `padding` lanes of padding, if the
+/// lane holds padding: the `10*` padding starts with a one and continues with zeros.
+pub fn poseidon2_padding_value<F: AbstractField>(padding: u32, i: usize) -> Option<F> {
+    is_poseidon2_padding(padding, i).then(|| F::from_bool(i + padding as usize == HASH_RATE))
 }
 
 pub type Poseidon2Event<F> = Poseidon2Io<F>;
@@ -145,6 +182,45 @@ pub struct SelectInstr<F> {
 /// The event encoding the inputs and outputs of a select operation.
 pub type SelectEvent<F> = SelectIo<F>;
 
+/// The inputs and outputs to a fused multiply-add operation, computing `out = in1 * in2 + in3`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(C)]
+pub struct FmaIo<V> {
+    pub out: V,
+    pub in1: V,
+    pub in2: V,
+    pub in3: V,
+}
+
+/// An instruction invoking the fused multiply-add operation.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[repr(C)]
+pub struct FmaInstr<F> {
+    pub addrs: FmaIo<Address<F>>,
+    pub mult: F,
+}
+
+/// The event encoding the inputs and outputs of a fused multiply-add operation.
+pub type FmaEvent<F> = FmaIo<F>;
+
+/// The input of a range check, asserting that `value < 2^num_bits`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(C)]
+pub struct RangeCheckIo<V> {
+    pub value: V,
+}
+
+/// An instruction asserting that the value at an address fits in `num_bits` bits.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[repr(C)]
+pub struct RangeCheckInstr<F> {
+    pub addrs: RangeCheckIo<Address<F>>,
+    pub num_bits: u32,
+}
+
+/// The event encoding the value of a range check.
+pub type RangeCheckEvent<F> = RangeCheckIo<F>;
+
 /// The inputs and outputs to an exp-reverse-bits operation.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ExpReverseBitsIo<V> {
@@ -437,3 +513,10 @@ pub struct CommitPublicValuesInstr<F> {
 pub struct CommitPublicValuesEvent<F> {
     pub public_values: RecursionPublicValues<F>,
 }
+
+/// An instruction that commits extra public values after the digest of the public values, to be
+/// proven by a public values chip that declares them.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CommitExtraPublicValuesInstr<F> {
+    pub addrs: Vec<Address<F>>,
+}
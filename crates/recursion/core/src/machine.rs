@@ -14,6 +14,7 @@ use crate::{
         alu_ext::{ExtAluChip, NUM_EXT_ALU_ENTRIES_PER_ROW},
         batch_fri::BatchFRIChip,
         exp_reverse_bits::ExpReverseBitsLenChip,
+        fma::FmaChip,
         fri_fold::FriFoldChip,
         mem::{
             constant::NUM_CONST_MEM_ENTRIES_PER_ROW, variable::NUM_VAR_MEM_ENTRIES_PER_ROW,
@@ -22,7 +23,8 @@ use crate::{
         poseidon2_skinny::Poseidon2SkinnyChip,
         poseidon2_wide::Poseidon2WideChip,
         public_values::{PublicValuesChip, PUB_VALUES_LOG_HEIGHT},
-        select::SelectChip,
+        range_check::RangeCheckChip,
+        select::{SelectChip, NUM_SELECT_ENTRIES_PER_ROW},
     },
     instruction::{HintBitsInstr, HintExt2FeltsInstr, HintInstr},
     shape::RecursionShape,
@@ -43,6 +45,8 @@ pub enum RecursionAir<F: PrimeField32 + BinomiallyExtendable<D>, const DEGREE: u
     Poseidon2Skinny(Poseidon2SkinnyChip<DEGREE>),
     Poseidon2Wide(Poseidon2WideChip<DEGREE>),
     Select(SelectChip),
+    Fma(FmaChip),
+    RangeCheck(RangeCheckChip),
     FriFold(FriFoldChip<DEGREE>),
     BatchFRI(BatchFRIChip<DEGREE>),
     ExpReverseBitsLen(ExpReverseBitsLenChip<DEGREE>),
@@ -59,6 +63,8 @@ pub struct RecursionAirEventCount {
     pub fri_fold_events: usize,
     pub batch_fri_events: usize,
     pub select_events: usize,
+    pub fma_events: usize,
+    pub range_check_events: usize,
     pub exp_reverse_bits_len_events: usize,
 }
 
@@ -70,14 +76,16 @@ impl<F: PrimeField32 + BinomiallyExtendable<D>, const DEGREE: usize> RecursionAi
         let chips = [
             RecursionAir::MemoryConst(MemoryConstChip::default()),
             RecursionAir::MemoryVar(MemoryVarChip::default()),
-            RecursionAir::BaseAlu(BaseAluChip),
+            RecursionAir::BaseAlu(BaseAluChip::with_immediates()),
             RecursionAir::ExtAlu(ExtAluChip),
-            RecursionAir::Poseidon2Wide(Poseidon2WideChip::<DEGREE>),
+            RecursionAir::Poseidon2Wide(Poseidon2WideChip::<DEGREE>::with_padding()),
             RecursionAir::FriFold(FriFoldChip::<DEGREE>::default()),
             RecursionAir::BatchFRI(BatchFRIChip::<DEGREE>),
             RecursionAir::Select(SelectChip),
+            RecursionAir::Fma(FmaChip),
+            RecursionAir::RangeCheck(RangeCheckChip),
             RecursionAir::ExpReverseBitsLen(ExpReverseBitsLenChip::<DEGREE>),
-            RecursionAir::PublicValues(PublicValuesChip),
+            RecursionAir::PublicValues(PublicValuesChip::default()),
         ]
         .map(Chip::new)
         .into_iter()
@@ -92,14 +100,16 @@ impl<F: PrimeField32 + BinomiallyExtendable<D>, const DEGREE: usize> RecursionAi
         let chips = [
             RecursionAir::MemoryConst(MemoryConstChip::default()),
             RecursionAir::MemoryVar(MemoryVarChip::default()),
-            RecursionAir::BaseAlu(BaseAluChip),
+            RecursionAir::BaseAlu(BaseAluChip::with_immediates()),
             RecursionAir::ExtAlu(ExtAluChip),
-            RecursionAir::Poseidon2Skinny(Poseidon2SkinnyChip::<DEGREE>::default()),
+            RecursionAir::Poseidon2Skinny(Poseidon2SkinnyChip::<DEGREE>::with_padding()),
             RecursionAir::FriFold(FriFoldChip::<DEGREE>::default()),
             RecursionAir::BatchFRI(BatchFRIChip::<DEGREE>),
             RecursionAir::Select(SelectChip),
+            RecursionAir::Fma(FmaChip),
+            RecursionAir::RangeCheck(RangeCheckChip),
             RecursionAir::ExpReverseBitsLen(ExpReverseBitsLenChip::<DEGREE>),
-            RecursionAir::PublicValues(PublicValuesChip),
+            RecursionAir::PublicValues(PublicValuesChip::default()),
         ]
         .map(Chip::new)
         .into_iter()
@@ -109,21 +119,29 @@ impl<F: PrimeField32 + BinomiallyExtendable<D>, const DEGREE: usize> RecursionAi
 
     /// A machine with dyunamic chip sizes that includes the wide variant of the Poseidon2 chip.
     pub fn compress_machine<SC: StarkGenericConfig<Val = F>>(config: SC) -> StarkMachine<SC, Self> {
+        Self::compress_machine_with_extra_public_values(config, 0)
+    }
+
+    /// The compress machine, with `num_extra` public values committed after the digest.
+    pub fn compress_machine_with_extra_public_values<SC: StarkGenericConfig<Val = F>>(
+        config: SC,
+        num_extra: usize,
+    ) -> StarkMachine<SC, Self> {
         let chips = [
             RecursionAir::MemoryConst(MemoryConstChip::default()),
             RecursionAir::MemoryVar(MemoryVarChip::default()),
-            RecursionAir::BaseAlu(BaseAluChip),
+            RecursionAir::BaseAlu(BaseAluChip::default()),
             RecursionAir::ExtAlu(ExtAluChip),
-            RecursionAir::Poseidon2Wide(Poseidon2WideChip::<DEGREE>),
+            RecursionAir::Poseidon2Wide(Poseidon2WideChip::<DEGREE>::default()),
             RecursionAir::BatchFRI(BatchFRIChip::<DEGREE>),
             RecursionAir::Select(SelectChip),
             RecursionAir::ExpReverseBitsLen(ExpReverseBitsLenChip::<DEGREE>),
-            RecursionAir::PublicValues(PublicValuesChip),
+            RecursionAir::PublicValues(PublicValuesChip::with_num_extra(num_extra)),
         ]
         .map(Chip::new)
         .into_iter()
         .collect::<Vec<_>>();
-        StarkMachine::new(config, chips, PROOF_MAX_NUM_PVS, false)
+        StarkMachine::new(config, chips, PROOF_MAX_NUM_PVS + num_extra, false)
     }
 
     pub fn shrink_machine<SC: StarkGenericConfig<Val = F>>(config: SC) -> StarkMachine<SC, Self> {
@@ -138,12 +156,12 @@ impl<F: PrimeField32 + BinomiallyExtendable<D>, const DEGREE: usize> RecursionAi
         let chips = [
             RecursionAir::MemoryConst(MemoryConstChip::default()),
             RecursionAir::MemoryVar(MemoryVarChip::default()),
-            RecursionAir::BaseAlu(BaseAluChip),
+            RecursionAir::BaseAlu(BaseAluChip::default()),
             RecursionAir::ExtAlu(ExtAluChip),
             RecursionAir::Poseidon2Skinny(Poseidon2SkinnyChip::<DEGREE>::default()),
             // RecursionAir::BatchFRI(BatchFRIChip::<DEGREE>),
             RecursionAir::Select(SelectChip),
-            RecursionAir::PublicValues(PublicValuesChip),
+            RecursionAir::PublicValues(PublicValuesChip::default()),
         ]
         .map(Chip::new)
         .into_iter()
@@ -158,11 +176,11 @@ impl<F: PrimeField32 + BinomiallyExtendable<D>, const DEGREE: usize> RecursionAi
                 (Self::Select(SelectChip), 18),
                 (Self::MemoryConst(MemoryConstChip::default()), 17),
                 (Self::BatchFRI(BatchFRIChip::<DEGREE>), 17),
-                (Self::BaseAlu(BaseAluChip), 17),
+                (Self::BaseAlu(BaseAluChip::default()), 17),
                 (Self::ExtAlu(ExtAluChip), 18),
                 (Self::ExpReverseBitsLen(ExpReverseBitsLenChip::<DEGREE>), 17),
-                (Self::Poseidon2Wide(Poseidon2WideChip::<DEGREE>), 16),
-                (Self::PublicValues(PublicValuesChip), PUB_VALUES_LOG_HEIGHT),
+                (Self::Poseidon2Wide(Poseidon2WideChip::<DEGREE>::default()), 16),
+                (Self::PublicValues(PublicValuesChip::default()), PUB_VALUES_LOG_HEIGHT),
             ]
             .map(|(chip, log_height)| (chip.name(), log_height)),
         );
@@ -185,21 +203,24 @@ impl<F: PrimeField32 + BinomiallyExtendable<D>, const DEGREE: usize> RecursionAi
                 heights.mem_var_events.div_ceil(NUM_VAR_MEM_ENTRIES_PER_ROW),
             ),
             (
-                Self::BaseAlu(BaseAluChip),
+                Self::BaseAlu(BaseAluChip::default()),
                 heights.base_alu_events.div_ceil(NUM_BASE_ALU_ENTRIES_PER_ROW),
             ),
             (
                 Self::ExtAlu(ExtAluChip),
                 heights.ext_alu_events.div_ceil(NUM_EXT_ALU_ENTRIES_PER_ROW),
             ),
-            (Self::Poseidon2Wide(Poseidon2WideChip::<DEGREE>), heights.poseidon2_wide_events),
+            (
+                Self::Poseidon2Wide(Poseidon2WideChip::<DEGREE>::default()),
+                heights.poseidon2_wide_events,
+            ),
             (Self::BatchFRI(BatchFRIChip::<DEGREE>), heights.batch_fri_events),
-            (Self::Select(SelectChip), heights.select_events),
+            (Self::Select(SelectChip), heights.select_events.div_ceil(NUM_SELECT_ENTRIES_PER_ROW)),
             (
                 Self::ExpReverseBitsLen(ExpReverseBitsLenChip::<DEGREE>),
                 heights.exp_reverse_bits_len_events,
             ),
-            (Self::PublicValues(PublicValuesChip), PUB_VALUES_LOG_HEIGHT),
+            (Self::PublicValues(PublicValuesChip::default()), PUB_VALUES_LOG_HEIGHT),
         ]
         .map(|(chip, log_height)| (chip.name(), log_height))
         .to_vec()
@@ -215,6 +236,8 @@ impl<F> AddAssign<&Instruction<F>> for RecursionAirEventCount {
             Instruction::Mem(_) => self.mem_const_events += 1,
             Instruction::Poseidon2(_) => self.poseidon2_wide_events += 1,
             Instruction::Select(_) => self.select_events += 1,
+            Instruction::Fma(_) => self.fma_events += 1,
+            Instruction::RangeCheck(_) => self.range_check_events += 1,
             Instruction::ExpReverseBitsLen(ExpReverseBitsInstr { addrs, .. }) => {
                 self.exp_reverse_bits_len_events += addrs.exp.len()
             }
@@ -236,6 +259,7 @@ impl<F> AddAssign<&Instruction<F>> for RecursionAirEventCount {
                 self.mem_var_events += instr.output_y_addrs_mults.len();
             }
             Instruction::CommitPublicValues(_) => {}
+            Instruction::CommitExtraPublicValues(_) => {}
             Instruction::Print(_) => {}
             #[cfg(feature = "debug")]
             Instruction::DebugBacktrace(_) => {}
@@ -264,18 +288,27 @@ pub mod tests {
 
     use std::{iter::once, sync::Arc};
 
+    use hashbrown::HashMap;
     use machine::RecursionAir;
     use p3_baby_bear::DiffusionMatrixBabyBear;
     use p3_field::{
         extension::{BinomialExtensionField, HasFrobenius},
         AbstractExtensionField, AbstractField, Field,
     };
+    use p3_matrix::Matrix;
     use rand::prelude::*;
     use sp1_core_machine::utils::run_test_machine;
-    use sp1_stark::{baby_bear_poseidon2::BabyBearPoseidon2, StarkGenericConfig};
+    use sp1_stark::{
+        air::MachineAir, baby_bear_poseidon2::BabyBearPoseidon2, CpuProver, MachineProver,
+        StarkGenericConfig,
+    };
 
     // TODO expand glob import
-    use crate::{runtime::instruction as instr, *};
+    use crate::{
+        runtime::instruction as instr,
+        shape::{shape_of, ChipOverflow, RecursionShape, RecursionShapeConfig, ShapeError},
+        *,
+    };
 
     type SC = BabyBearPoseidon2;
     type F = <SC as StarkGenericConfig>::Val;
@@ -323,6 +356,220 @@ pub mod tests {
         test_recursion_linear_program(instructions);
     }
 
+    #[test]
+    pub fn preprocessed_cache() {
+        let program = linear_program(vec![
+            instr::mem(MemAccessKind::Write, 2, 0, 0),
+            instr::mem(MemAccessKind::Write, 2, 1, 1),
+            instr::base_alu(BaseAluOpcode::AddF, 1, 2, 1, 0),
+            instr::select(1, 1, 1, 3, 4, 0, 2),
+            instr::mem(MemAccessKind::Read, 1, 3, 1),
+            instr::mem(MemAccessKind::Read, 1, 4, 0),
+        ])
+        .unwrap();
+        let machine = A::compress_machine(SC::default()).with_preprocessed_cache(2);
+        let fresh_machine = A::compress_machine(SC::default());
+
+        // Cached and freshly generated traces are identical.
+        let (fresh_pk, fresh_vk) = fresh_machine.setup(&program);
+        for _ in 0..2 {
+            let (pk, vk) = machine.setup(&program);
+            assert_eq!(pk.traces, fresh_pk.traces);
+            assert_eq!(vk.commit, fresh_vk.commit);
+        }
+        let cache = machine.preprocessed_cache().unwrap();
+        assert_eq!(cache.len(), 1);
+
+        // Fixing the trace heights of the program invalidates the cached traces.
+        let mut shaped = program.clone();
+        let inner = machine.chips().iter().map(|chip| (chip.name(), 6)).collect();
+        *shaped.shape_mut() = Some(shape::RecursionShape { inner });
+        let (pk, _) = machine.setup(&shaped);
+        assert_eq!(pk.traces, fresh_machine.setup(&shaped).0.traces);
+        assert_ne!(pk.traces, fresh_pk.traces);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    pub fn bounded_trace_gen() {
+        let program = Arc::new(
+            linear_program(vec![
+                instr::mem(MemAccessKind::Write, 2, 0, 0),
+                instr::mem(MemAccessKind::Write, 2, 1, 1),
+                instr::base_alu(BaseAluOpcode::AddF, 1, 2, 1, 0),
+                instr::select(1, 1, 1, 3, 4, 0, 2),
+                instr::mem(MemAccessKind::Read, 1, 3, 1),
+                instr::mem(MemAccessKind::Read, 1, 4, 0),
+            ])
+            .unwrap(),
+        );
+        let mut runtime =
+            Runtime::<F, EF, DiffusionMatrixBabyBear>::new(program.clone(), SC::new().perm);
+        runtime.run().unwrap();
+
+        let prover = CpuProver::new(A::compress_machine(SC::default()));
+        let (traces, report) = prover.generate_traces_with_report(&runtime.record);
+        assert_eq!(report.batches, 1);
+        assert_eq!(report.chips.len(), traces.len());
+
+        // Bounding the trace bytes generated at once splits the chips into batches, but leaves
+        // the traces unchanged.
+        let bounded_prover =
+            CpuProver::new(A::compress_machine(SC::default()).with_max_trace_gen_bytes(1));
+        let (bounded_traces, bounded_report) =
+            bounded_prover.generate_traces_with_report(&runtime.record);
+        assert_eq!(bounded_traces, traces);
+        assert!(bounded_report.batches > 1);
+        for (timing, (name, trace)) in bounded_report.chips.iter().zip(&bounded_traces) {
+            assert_eq!(&timing.chip, name);
+            assert_eq!((timing.height, timing.width), (trace.height(), trace.width()));
+        }
+    }
+
+    #[test]
+    pub fn pruned_empty_chips() {
+        let program = Arc::new(
+            linear_program(vec![
+                instr::mem(MemAccessKind::Write, 1, 0, 0),
+                instr::mem(MemAccessKind::Write, 1, 1, 1),
+                instr::base_alu(BaseAluOpcode::AddF, 1, 2, 1, 0),
+                instr::mem(MemAccessKind::Read, 1, 2, 1),
+            ])
+            .unwrap(),
+        );
+        let mut runtime =
+            Runtime::<F, EF, DiffusionMatrixBabyBear>::new(program.clone(), SC::new().perm);
+        runtime.run().unwrap();
+
+        // Only the chips proving the memory and the base field ALU operations are kept.
+        let machine = A::machine_wide_with_all_chips(SC::default()).with_empty_chips_pruned();
+        let (pk, vk) = machine.setup(&program);
+        let proof = run_test_machine(vec![runtime.record], machine, pk, vk.clone())
+            .expect("Verification failed");
+        let chip_ordering = &proof.shard_proofs[0].chip_ordering;
+        for chip in ["BaseAlu", "MemoryConst", "MemoryVar", "PublicValues"] {
+            assert!(chip_ordering.contains_key(chip), "{chip} was pruned");
+        }
+        for chip in ["ExtAlu", "Select", "Poseidon2WideDeg3", "FriFold", "ExpReverseBitsLen"] {
+            assert!(!chip_ordering.contains_key(chip), "{chip} was not pruned");
+            assert!(vk.chip_information.iter().all(|(name, _, _)| name != chip));
+        }
+
+        // A fixed shape keeps every chip.
+        let machine = A::compress_machine(SC::default()).with_empty_chips_pruned();
+        let mut shaped = (*program).clone();
+        let inner = machine.chips().iter().map(|chip| (chip.name(), 4)).collect();
+        *shaped.shape_mut() = Some(shape::RecursionShape { inner });
+        let mut runtime =
+            Runtime::<F, EF, DiffusionMatrixBabyBear>::new(Arc::new(shaped), SC::new().perm);
+        runtime.run().unwrap();
+        assert_eq!(machine.shard_chips(&runtime.record).count(), machine.chips().len());
+    }
+
+    /// A program with more base field ALU operations than fit in a single row, and a shape that
+    /// allows a single row for the base field ALU chip.
+    fn undersized_shape_program() -> (RecursionProgram<F>, RecursionShapeConfig<F, A>) {
+        let n = 10;
+        let instructions = once(instr::mem(MemAccessKind::Write, 2 * n, 0, 1))
+            .chain((1..=n).map(|i| instr::base_alu(BaseAluOpcode::AddF, 0, i, 0, 0)))
+            .collect::<Vec<_>>();
+        let program = linear_program(instructions).unwrap();
+        let shape = A::heights(&program)
+            .into_iter()
+            .map(|(chip, _)| {
+                let log2_rows = if chip == "BaseAlu" { 0 } else { 4 };
+                (chip, log2_rows)
+            })
+            .collect::<HashMap<_, _>>();
+        (program, RecursionShapeConfig::from_hash_map(&shape))
+    }
+
+    #[test]
+    pub fn undersized_shape_chip_heights() {
+        let (mut program, config) = undersized_shape_program();
+        *program.shape_mut() = config.first().cloned().map(Into::into);
+        let mut runtime =
+            Runtime::<F, EF, DiffusionMatrixBabyBear>::new(Arc::new(program), SC::new().perm);
+        runtime.run().unwrap();
+
+        let heights = runtime.record.chip_heights::<3>();
+        assert!(heights.contains(&("BaseAlu".to_string(), 3)));
+        let err = runtime.record.check_shape::<3>().unwrap_err();
+        assert_eq!(
+            err.overflows,
+            vec![ChipOverflow { chip: "BaseAlu".to_string(), rows: 3, allowed_rows: 1 }]
+        );
+        assert_eq!(err.heights.len(), heights.len());
+        assert!(err.to_string().contains("BaseAlu has 3 rows but 1 are allowed"));
+    }
+
+    #[test]
+    #[should_panic(expected = "BaseAlu has 3 rows but 1 are allowed")]
+    pub fn undersized_shape_fix_shape() {
+        let (mut program, config) = undersized_shape_program();
+        config.fix_shape(&mut program);
+    }
+
+    #[test]
+    pub fn explicit_shape_trace_heights() {
+        let program = linear_program(vec![
+            instr::mem(MemAccessKind::Write, 2, 0, 0),
+            instr::mem(MemAccessKind::Write, 2, 1, 1),
+            instr::base_alu(BaseAluOpcode::AddF, 1, 2, 1, 0),
+            instr::select(1, 1, 1, 3, 4, 0, 2),
+            instr::mem(MemAccessKind::Read, 1, 3, 1),
+            instr::mem(MemAccessKind::Read, 1, 4, 0),
+        ])
+        .unwrap();
+        let shape =
+            RecursionShape::new().with_chip_height("Select", 5).with_chip_height("BaseAlu", 6);
+        let program = Arc::new(program.with_shape::<3>(shape).unwrap());
+
+        let mut runtime =
+            Runtime::<F, EF, DiffusionMatrixBabyBear>::new(program.clone(), SC::new().perm);
+        runtime.run().unwrap();
+        let prover = CpuProver::new(A::compress_machine(SC::default()));
+        let traces = prover.generate_traces(&runtime.record);
+
+        // The chips the shape fixes have exactly the requested heights, and the others the
+        // heights of the minimal shape.
+        let minimal = shape_of::<F, 3>(&program);
+        for (chip, trace) in &traces {
+            let log2_rows = match chip.as_str() {
+                "Select" => 5,
+                "BaseAlu" => 6,
+                _ => minimal.chip_height(chip).unwrap(),
+            };
+            assert_eq!(trace.height(), 1 << log2_rows, "{chip}");
+        }
+    }
+
+    #[test]
+    pub fn explicit_shape_rejected() {
+        let program = linear_program(vec![
+            instr::mem(MemAccessKind::Write, 6, 0, 0),
+            instr::select(1, 1, 0, 1, 2, 0, 0),
+            instr::select(1, 1, 0, 3, 4, 0, 0),
+            instr::mem(MemAccessKind::Read, 1, 1, 0),
+            instr::mem(MemAccessKind::Read, 1, 2, 0),
+            instr::mem(MemAccessKind::Read, 1, 3, 0),
+            instr::mem(MemAccessKind::Read, 1, 4, 0),
+        ])
+        .unwrap();
+
+        let shape = RecursionShape::new().with_chip_height("Select", 0);
+        let ShapeError::Overflow(err) = program.clone().with_shape::<3>(shape).unwrap_err() else {
+            panic!("expected the select chip to overflow");
+        };
+        assert_eq!(
+            err.overflows,
+            vec![ChipOverflow { chip: "Select".to_string(), rows: 2, allowed_rows: 1 }]
+        );
+
+        let err = program.with_shape::<3>(RecursionShape::new().with_chip_height("Selct", 4));
+        assert_eq!(err.unwrap_err(), ShapeError::UnknownChip("Selct".to_string()));
+    }
+
     #[test]
     #[should_panic]
     pub fn div_nonzero_by_zero() {
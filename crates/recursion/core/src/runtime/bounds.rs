@@ -0,0 +1,175 @@
+use std::fmt::Debug;
+
+use p3_field::PrimeField32;
+use thiserror::Error;
+
+use crate::{
+    runtime::instruction::{HintAddCurveInstr, HintBitsInstr, HintExt2FeltsInstr, PrintInstr},
+    *,
+};
+
+/// An instruction accessing an address past the end of the memory.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("instruction {pc} ({instr}) accesses address {addr:?}, but the memory has size {bound}")]
+pub struct OutOfBoundsError<F: Debug> {
+    /// The index of the instruction, in program order.
+    pub pc: usize,
+    /// The instruction, in the format of [`Instruction`]'s `Display` implementation.
+    pub instr: String,
+    pub addr: Address<F>,
+    /// The size of the memory the instruction was checked against.
+    pub bound: usize,
+}
+
+/// A violation of the invariants of a program, as reported by [`RecursionProgram::validate`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ProgramViolation<F: Debug> {
+    #[error(transparent)]
+    OutOfBounds(#[from] OutOfBoundsError<F>),
+    #[error(transparent)]
+    Multiplicity(#[from] MultiplicityError<F>),
+}
+
+impl<F: PrimeField32> RecursionProgram<F> {
+    /// The accesses of the program to addresses not below `bound`, in program order.
+    ///
+    /// Unlike [`Instruction::memory_interactions`], this includes the inputs of hints and prints,
+    /// since the runtime reads them from the same memory.
+    pub fn out_of_bounds_accesses(
+        &self,
+        bound: usize,
+    ) -> impl Iterator<Item = OutOfBoundsError<F>> + '_ {
+        self.inner.iter().enumerate().flat_map(move |(pc, instruction)| {
+            accessed_addrs(instruction)
+                .into_iter()
+                .filter(move |addr| addr.as_usize() >= bound)
+                .map(move |addr| OutOfBoundsError {
+                    pc,
+                    instr: instruction.to_string(),
+                    addr,
+                    bound,
+                })
+        })
+    }
+
+    /// Statically checks that every address accessed by the program is below `total_memory`, and
+    /// that every written address is read as many times as its writer declares.
+    ///
+    /// All the violations are returned: the out-of-bounds accesses first, then the mismatched
+    /// multiplicities, each in program order.
+    pub fn validate(&self) -> Result<(), Vec<ProgramViolation<F>>> {
+        let multiplicity_errors = MultiplicityLedger::new(self).errors(self, |_| true);
+        let violations = self
+            .out_of_bounds_accesses(self.total_memory)
+            .map(ProgramViolation::from)
+            .chain(multiplicity_errors.into_iter().map(ProgramViolation::from))
+            .collect::<Vec<_>>();
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+}
+
+/// Every address the runtime reads or writes when executing the instruction.
+fn accessed_addrs<F: PrimeField32>(instruction: &Instruction<F>) -> Vec<Address<F>> {
+    let (writes, reads) = instruction.memory_interactions();
+    let inputs = match instruction {
+        Instruction::HintBits(HintBitsInstr { input_addr, .. }) |
+        Instruction::HintExt2Felts(HintExt2FeltsInstr { input_addr, .. }) => vec![*input_addr],
+        Instruction::Print(PrintInstr { addr, .. }) => vec![*addr],
+        Instruction::HintAddCurve(instr) => {
+            let HintAddCurveInstr {
+                input1_x_addrs,
+                input1_y_addrs,
+                input2_x_addrs,
+                input2_y_addrs,
+                ..
+            } = instr.as_ref();
+            [input1_x_addrs, input1_y_addrs, input2_x_addrs, input2_y_addrs].concat()
+        }
+        _ => vec![],
+    };
+    writes.into_iter().chain(reads).map(|(addr, _)| addr).chain(inputs).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use p3_baby_bear::DiffusionMatrixBabyBear;
+    use p3_field::AbstractField;
+    use sp1_stark::{baby_bear_poseidon2::BabyBearPoseidon2, StarkGenericConfig};
+
+    use super::*;
+    use crate::runtime::instruction as instr;
+
+    type SC = BabyBearPoseidon2;
+    type F = <SC as StarkGenericConfig>::Val;
+    type EF = <SC as StarkGenericConfig>::Challenge;
+
+    fn mul_program(mult: u32) -> RecursionProgram<F> {
+        linear_program(vec![
+            instr::mem(MemAccessKind::Write, mult, 0, 3),
+            instr::mem(MemAccessKind::Write, 1, 1, 4),
+            instr::base_alu(BaseAluOpcode::MulF, 1, 2, 0, 1),
+            instr::mem(MemAccessKind::Read, 1, 2, 12),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn valid_program() {
+        assert_eq!(mul_program(1).validate(), Ok(()));
+    }
+
+    #[test]
+    fn all_violations_are_reported() {
+        let mut program = mul_program(2).into_inner();
+        program.total_memory = 2;
+        // SAFETY: Only the size of the memory is changed, and the program is not executed.
+        let program = unsafe { RecursionProgram::new_unchecked(program) };
+
+        let violations = program.validate().unwrap_err();
+        assert!(matches!(
+            violations[..],
+            [
+                ProgramViolation::OutOfBounds(OutOfBoundsError { pc: 2, bound: 2, .. }),
+                ProgramViolation::OutOfBounds(OutOfBoundsError { pc: 3, bound: 2, .. }),
+                ProgramViolation::Multiplicity(MultiplicityError {
+                    instr_idx: 0,
+                    expected: 2,
+                    actual: 1,
+                    ..
+                }),
+            ]
+        ));
+        assert!(violations[0]
+            .to_string()
+            .starts_with("instruction 2 (base_alu.mulf mult=1 out=@2 in1=@0 in2=@1) accesses"));
+    }
+
+    #[test]
+    fn runtime_reports_out_of_bounds_access() {
+        let mut runtime = Runtime::<F, EF, DiffusionMatrixBabyBear>::new(
+            Arc::new(mul_program(1)),
+            SC::new().perm,
+        )
+        .with_memory_size(2);
+        let Err(RuntimeError::MemoryOutOfBounds(err)) = runtime.run() else {
+            panic!("expected an out-of-bounds access");
+        };
+        assert_eq!(
+            err,
+            OutOfBoundsError {
+                pc: 2,
+                instr: "base_alu.mulf mult=1 out=@2 in1=@0 in2=@1".to_string(),
+                addr: Address(F::two()),
+                bound: 2,
+            }
+        );
+        // The accesses are checked before any instruction is executed.
+        assert!(runtime.record.mem_const_count == 0 && runtime.record.base_alu_events.is_empty());
+    }
+}
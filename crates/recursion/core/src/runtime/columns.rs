@@ -0,0 +1,383 @@
+use std::{array, iter::zip, marker::PhantomData, mem};
+
+use p3_field::Field;
+use p3_maybe_rayon::prelude::*;
+use sp1_stark::ColMajorMatrix;
+
+use crate::{air::Block, BaseAluIo, ExtAluIo, MemIo, SegmentedVec, SelectIo, D};
+
+/// An event made of a fixed number of field elements, which [`EventColumns`] stores in one column
+/// per element.
+///
+/// The columns are numbered in the order of the fields of the event, which is also the order of
+/// the trace columns the event fills in the main trace of its chip.
+pub trait ColumnarEvent<F>: Copy {
+    /// The number of field elements of the event.
+    const NUM_COLUMNS: usize;
+
+    /// The element of the event in the given column.
+    fn value(&self, column: usize) -> F;
+
+    /// The event whose element in each column is given by `value`.
+    fn from_values(value: impl FnMut(usize) -> F) -> Self;
+}
+
+impl<F: Copy> ColumnarEvent<F> for BaseAluIo<F> {
+    const NUM_COLUMNS: usize = 3;
+
+    fn value(&self, column: usize) -> F {
+        [self.out, self.in1, self.in2][column]
+    }
+
+    fn from_values(mut value: impl FnMut(usize) -> F) -> Self {
+        Self { out: value(0), in1: value(1), in2: value(2) }
+    }
+}
+
+impl<F: Copy> ColumnarEvent<F> for ExtAluIo<Block<F>> {
+    const NUM_COLUMNS: usize = 3 * D;
+
+    fn value(&self, column: usize) -> F {
+        [self.out, self.in1, self.in2][column / D].0[column % D]
+    }
+
+    fn from_values(mut value: impl FnMut(usize) -> F) -> Self {
+        let mut block = |i: usize| Block(array::from_fn(|j| value(i * D + j)));
+        Self { out: block(0), in1: block(1), in2: block(2) }
+    }
+}
+
+impl<F: Copy> ColumnarEvent<F> for SelectIo<F> {
+    const NUM_COLUMNS: usize = 5;
+
+    fn value(&self, column: usize) -> F {
+        [self.bit, self.out1, self.out2, self.in1, self.in2][column]
+    }
+
+    fn from_values(mut value: impl FnMut(usize) -> F) -> Self {
+        Self { bit: value(0), out1: value(1), out2: value(2), in1: value(3), in2: value(4) }
+    }
+}
+
+impl<F: Copy> ColumnarEvent<F> for MemIo<Block<F>> {
+    const NUM_COLUMNS: usize = D;
+
+    fn value(&self, column: usize) -> F {
+        self.inner.0[column]
+    }
+
+    fn from_values(value: impl FnMut(usize) -> F) -> Self {
+        Self { inner: Block(array::from_fn(value)) }
+    }
+}
+
+/// Events stored as a structure of arrays: the `i`-th element of each column belongs to the `i`-th
+/// event.
+///
+/// Trace generation copies every column into the trace columns it fills, instead of reading all
+/// the fields of each event in turn. Conversions from and into a `Vec` of events are provided for
+/// code that builds or inspects the events one at a time.
+#[derive(Clone, Debug)]
+pub struct EventColumns<F, E> {
+    /// The columns, which are allocated when the first event is pushed or reserved.
+    columns: Vec<Vec<F>>,
+    len: usize,
+    _marker: PhantomData<E>,
+}
+
+impl<F, E> Default for EventColumns<F, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F, E> EventColumns<F, E> {
+    pub const fn new() -> Self {
+        Self { columns: Vec::new(), len: 0, _marker: PhantomData }
+    }
+
+    /// The number of events.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The elements of all the events in the given column.
+    pub fn column(&self, column: usize) -> &[F] {
+        self.columns.get(column).map_or(&[], Vec::as_slice)
+    }
+
+    /// Moves the events of `other` after the events of `self`, leaving `other` empty.
+    pub fn append(&mut self, other: &mut Self) {
+        if other.is_empty() {
+            return;
+        }
+        if self.columns.is_empty() {
+            self.columns = mem::take(&mut other.columns);
+        } else {
+            for (column, other_column) in zip(&mut self.columns, &mut other.columns) {
+                column.append(other_column);
+            }
+        }
+        self.len += mem::take(&mut other.len);
+    }
+
+    /// The columns of the events, which are empty if no event was ever pushed or reserved.
+    pub fn into_columns(self) -> Vec<Vec<F>> {
+        self.columns
+    }
+}
+
+impl<F: Copy, E: ColumnarEvent<F>> EventColumns<F, E> {
+    /// Creates the events from their columns, which must all have the same length.
+    pub fn from_columns(columns: Vec<Vec<F>>) -> Self {
+        if columns.is_empty() {
+            return Self::new();
+        }
+        assert_eq!(columns.len(), E::NUM_COLUMNS, "wrong number of columns");
+        let len = columns[0].len();
+        assert!(columns.iter().all(|column| column.len() == len), "columns of different lengths");
+        Self { columns, len, _marker: PhantomData }
+    }
+
+    fn allocate_columns(&mut self) {
+        if self.columns.is_empty() {
+            self.columns.resize_with(E::NUM_COLUMNS, Vec::new);
+        }
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.allocate_columns();
+        self.columns.iter_mut().for_each(|column| column.reserve(additional));
+    }
+
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.allocate_columns();
+        self.columns.iter_mut().for_each(|column| column.reserve_exact(additional));
+    }
+
+    /// Appends an event, writing each of its elements to its column.
+    #[inline]
+    pub fn push(&mut self, event: E) {
+        self.allocate_columns();
+        for (i, column) in self.columns.iter_mut().enumerate() {
+            column.push(event.value(i));
+        }
+        self.len += 1;
+    }
+
+    /// The event at the given index, gathered from the columns.
+    pub fn get(&self, index: usize) -> Option<E> {
+        (index < self.len).then(|| E::from_values(|i| self.columns[i][index]))
+    }
+
+    /// Iterates over the events, in order, gathering each one from the columns.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = E> + '_ {
+        (0..self.len).map(|index| E::from_values(|i| self.columns[i][index]))
+    }
+
+    pub fn to_vec(&self) -> Vec<E> {
+        self.iter().collect()
+    }
+}
+
+impl<F: Field, E: ColumnarEvent<F>> EventColumns<F, E> {
+    /// Writes the events into the first rows of a row-major trace of the given width. Each row
+    /// holds `events_per_row` consecutive events, and each event fills [`E::NUM_COLUMNS`]
+    /// consecutive columns, in the order of the columns of the event.
+    ///
+    /// [`E::NUM_COLUMNS`]: ColumnarEvent::NUM_COLUMNS
+    pub fn write_rows(&self, values: &mut [F], width: usize, events_per_row: usize) {
+        debug_assert!(events_per_row * E::NUM_COLUMNS <= width);
+        let (columns, len) = (&self.columns, self.len);
+        values[..len.div_ceil(events_per_row) * width].par_chunks_mut(width).enumerate().for_each(
+            |(r, row)| {
+                let events = r * events_per_row..((r + 1) * events_per_row).min(len);
+                for (entry, index) in row.chunks_mut(E::NUM_COLUMNS).zip(events) {
+                    for (value, column) in zip(entry, columns) {
+                        *value = column[index];
+                    }
+                }
+            },
+        );
+    }
+
+    /// Writes the events into a column-major trace, in the layout of [`EventColumns::write_rows`].
+    ///
+    /// Every trace column is copied from a single column of the events, with a stride of
+    /// `events_per_row`, so a trace with one event per row is filled by plain copies.
+    pub fn write_col_major(&self, trace: &mut ColMajorMatrix<F>, events_per_row: usize) {
+        debug_assert!(events_per_row * E::NUM_COLUMNS <= trace.width());
+        if self.is_empty() {
+            return;
+        }
+        let (columns, height) = (&self.columns, trace.height());
+        trace.values[..events_per_row * E::NUM_COLUMNS * height]
+            .par_chunks_mut(height)
+            .enumerate()
+            .for_each(|(c, trace_column)| {
+                let (entry, i) = (c / E::NUM_COLUMNS, c % E::NUM_COLUMNS);
+                let column = &columns[i];
+                if events_per_row == 1 {
+                    trace_column[..column.len()].copy_from_slice(column);
+                } else {
+                    let values = column.iter().skip(entry).step_by(events_per_row);
+                    for (value, &event_value) in zip(trace_column, values) {
+                        *value = event_value;
+                    }
+                }
+            });
+    }
+}
+
+impl<F: Copy, E: ColumnarEvent<F>> Extend<E> for EventColumns<F, E> {
+    fn extend<I: IntoIterator<Item = E>>(&mut self, events: I) {
+        let events = events.into_iter();
+        self.reserve(events.size_hint().0);
+        events.for_each(|event| self.push(event));
+    }
+}
+
+impl<F: Copy, E: ColumnarEvent<F>> FromIterator<E> for EventColumns<F, E> {
+    fn from_iter<I: IntoIterator<Item = E>>(events: I) -> Self {
+        let mut columns = Self::new();
+        columns.extend(events);
+        columns
+    }
+}
+
+impl<F: Copy, E: ColumnarEvent<F>> From<Vec<E>> for EventColumns<F, E> {
+    fn from(events: Vec<E>) -> Self {
+        events.into_iter().collect()
+    }
+}
+
+impl<F: Copy, E: ColumnarEvent<F>> From<EventColumns<F, E>> for Vec<E> {
+    fn from(columns: EventColumns<F, E>) -> Self {
+        columns.to_vec()
+    }
+}
+
+/// Event columns stored as a list of segments per column, like a [`SegmentedVec`].
+#[derive(Clone, Debug)]
+pub struct SegmentedColumns<F, E> {
+    columns: Vec<SegmentedVec<F>>,
+    _marker: PhantomData<E>,
+}
+
+impl<F, E> Default for SegmentedColumns<F, E> {
+    fn default() -> Self {
+        Self { columns: Vec::new(), _marker: PhantomData }
+    }
+}
+
+impl<F: Copy, E: ColumnarEvent<F>> SegmentedColumns<F, E> {
+    /// The total number of events in all the segments.
+    pub fn len(&self) -> usize {
+        self.columns.first().map_or(0, SegmentedVec::len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds the columns of `segment` after the existing segments, without copying them.
+    pub fn push_segment(&mut self, segment: EventColumns<F, E>) {
+        if segment.is_empty() {
+            return;
+        }
+        if self.columns.is_empty() {
+            self.columns.resize_with(E::NUM_COLUMNS, SegmentedVec::new);
+        }
+        for (column, segment_column) in zip(&mut self.columns, segment.into_columns()) {
+            column.push_segment(segment_column);
+        }
+    }
+
+    /// Concatenates the segments of each column. A single segment is returned without copying.
+    pub fn into_columns(self) -> EventColumns<F, E> {
+        EventColumns::from_columns(self.columns.into_iter().map(SegmentedVec::into_vec).collect())
+    }
+}
+
+impl<F: Copy, E: ColumnarEvent<F>> From<EventColumns<F, E>> for SegmentedColumns<F, E> {
+    fn from(columns: EventColumns<F, E>) -> Self {
+        let mut segmented = Self::default();
+        segmented.push_segment(columns);
+        segmented
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+
+    use super::*;
+    use crate::ExtAluEvent;
+
+    type F = BabyBear;
+
+    fn events(n: usize) -> Vec<ExtAluEvent<F>> {
+        let block = |i: usize| Block::from([F::from_canonical_usize(i); D]);
+        (0..n)
+            .map(|i| ExtAluIo { out: block(3 * i), in1: block(3 * i + 1), in2: block(3 * i + 2) })
+            .collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        let events = events(10);
+        let columns = EventColumns::<F, _>::from(events.clone());
+        assert_eq!(columns.len(), 10);
+        assert_eq!(
+            columns.column(D),
+            events.iter().map(|event| event.in1.0[0]).collect::<Vec<_>>()
+        );
+        assert_eq!(columns.get(3), Some(events[3]));
+        assert_eq!(columns.get(10), None);
+        assert_eq!(Vec::from(columns), events);
+    }
+
+    #[test]
+    fn append_and_segments() {
+        let events = events(10);
+        let mut columns = EventColumns::<F, ExtAluEvent<F>>::default();
+        let mut segmented = SegmentedColumns::<F, ExtAluEvent<F>>::default();
+        for chunk in events.chunks(3) {
+            columns.append(&mut chunk.to_vec().into());
+            segmented.push_segment(chunk.to_vec().into());
+        }
+        segmented.push_segment(EventColumns::new());
+        assert_eq!(columns.to_vec(), events);
+        assert_eq!(segmented.len(), events.len());
+        assert_eq!(segmented.into_columns().to_vec(), events);
+    }
+
+    #[test]
+    fn row_and_col_major_layouts_match() {
+        for (num_events, events_per_row) in [(0, 4), (1, 4), (13, 4), (13, 1)] {
+            let events = events(num_events);
+            let columns = EventColumns::<F, _>::from(events.clone());
+            let width = events_per_row * <ExtAluEvent<F> as ColumnarEvent<F>>::NUM_COLUMNS;
+            let height = 16;
+
+            let mut rows = vec![F::zero(); height * width];
+            columns.write_rows(&mut rows, width, events_per_row);
+            let flattened = events
+                .iter()
+                .flat_map(|e| [e.out.0, e.in1.0, e.in2.0])
+                .flatten()
+                .collect::<Vec<_>>();
+            assert_eq!(rows[..flattened.len()], flattened);
+            assert!(rows[flattened.len()..].iter().all(F::is_zero));
+
+            let mut col_major = ColMajorMatrix::zeros(height, width);
+            columns.write_col_major(&mut col_major, events_per_row);
+            assert_eq!(col_major.to_row_major().values, rows);
+        }
+    }
+}
@@ -0,0 +1,1021 @@
+//! A textual format for recursion programs.
+//!
+//! Each instruction is written on one line as a mnemonic followed by `key=value` fields, for
+//! example `select mult1=1 mult2=0 bit=@0 out1=@3 out2=@4 in1=@1 in2=@2`. Values are written as
+//! follows:
+//! - addresses as `@<address>`, except for the inputs of the base field ALU that are immediates,
+//!   which are written as their value;
+//! - field elements, multiplicities and bit counts as canonical integers;
+//! - lists as `[<item>,<item>,...]`, without spaces; and
+//! - the outputs of an instruction, together with the number of times each is read, as
+//!   `@<address>:<mult>`.
+//!
+//! The fields of an instruction may be given in any order when parsing.
+
+use std::{
+    borrow::Borrow,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use itertools::Itertools;
+use p3_field::PrimeField32;
+use thiserror::Error;
+
+use super::{
+    instruction::{
+        FieldEltType, HintAddCurveInstr, HintBitsInstr, HintExt2FeltsInstr, HintInstr, PrintInstr,
+    },
+    BasicBlock, RawProgram, RecursionProgram, RootProgram, SeqBlock,
+};
+use crate::{air::RECURSIVE_PROOF_NUM_PV_ELTS, *};
+
+const BASE_ALU_MNEMONICS: [(BaseAluOpcode, &str); 4] = [
+    (BaseAluOpcode::AddF, "base_alu.addf"),
+    (BaseAluOpcode::SubF, "base_alu.subf"),
+    (BaseAluOpcode::MulF, "base_alu.mulf"),
+    (BaseAluOpcode::DivF, "base_alu.divf"),
+];
+
+const EXT_ALU_MNEMONICS: [(ExtAluOpcode, &str); 4] = [
+    (ExtAluOpcode::AddE, "ext_alu.adde"),
+    (ExtAluOpcode::SubE, "ext_alu.sube"),
+    (ExtAluOpcode::MulE, "ext_alu.mule"),
+    (ExtAluOpcode::DivE, "ext_alu.dive"),
+];
+
+/// The indentation of each level of nesting in a disassembled program.
+const INDENT: &str = "    ";
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseInstructionError {
+    #[error("empty instruction")]
+    Empty,
+    #[error("unknown mnemonic `{0}`")]
+    UnknownMnemonic(String),
+    #[error("expected `key=value`, found `{0}`")]
+    MalformedField(String),
+    #[error("missing field `{0}`")]
+    MissingField(&'static str),
+    #[error("unexpected field `{0}`")]
+    UnexpectedField(String),
+    #[error("invalid value `{value}` for field `{field}`")]
+    InvalidValue { field: &'static str, value: String },
+    #[error("expected {expected} elements in field `{field}`, found {found}")]
+    WrongLength { field: &'static str, expected: usize, found: usize },
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseProgramError {
+    #[error("line {line}: {source}")]
+    Instruction { line: usize, source: ParseInstructionError },
+    #[error("line {line}: unexpected `{text}`")]
+    UnexpectedLine { line: usize, text: String },
+    #[error("missing `total_memory` header")]
+    MissingTotalMemory,
+    #[error("{0} blocks are not closed")]
+    Unclosed(usize),
+}
+
+fn fmt_felt<F: PrimeField32>(value: F) -> String {
+    value.as_canonical_u32().to_string()
+}
+
+pub(crate) fn fmt_addr<F: PrimeField32>(addr: Address<F>) -> String {
+    format!("@{}", addr.0.as_canonical_u32())
+}
+
+pub(crate) fn fmt_block<F: PrimeField32>(block: &Block<F>) -> String {
+    fmt_list(block.0.iter().copied().map(fmt_felt))
+}
+
+fn fmt_addr_mult<F: PrimeField32>(&(addr, mult): &(Address<F>, F)) -> String {
+    format!("{}:{}", fmt_addr(addr), fmt_felt(mult))
+}
+
+fn fmt_list(items: impl IntoIterator<Item = String>) -> String {
+    format!("[{}]", items.into_iter().join(","))
+}
+
+fn fmt_addrs<'a, F: PrimeField32>(addrs: impl IntoIterator<Item = &'a Address<F>>) -> String {
+    fmt_list(addrs.into_iter().copied().map(fmt_addr))
+}
+
+fn fmt_addr_mults<'a, F: PrimeField32>(
+    addrs_mults: impl IntoIterator<Item = &'a (Address<F>, F)>,
+) -> String {
+    fmt_list(addrs_mults.into_iter().map(fmt_addr_mult))
+}
+
+fn fmt_operand<F: PrimeField32>(addr: Address<F>, imm: bool) -> String {
+    match imm {
+        true => fmt_felt(addr.0),
+        false => fmt_addr(addr),
+    }
+}
+
+fn fmt_felts<'a, F: PrimeField32>(values: impl IntoIterator<Item = &'a F>) -> String {
+    fmt_list(values.into_iter().copied().map(fmt_felt))
+}
+
+impl<F: PrimeField32> Display for Instruction<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::BaseAlu(BaseAluInstr {
+                opcode,
+                mult,
+                addrs: BaseAluIo { out, in1, in2 },
+                imms,
+            }) => {
+                let (_, mnemonic) = BASE_ALU_MNEMONICS.iter().find(|(op, _)| op == opcode).unwrap();
+                write!(
+                    f,
+                    "{mnemonic} mult={} out={} in1={} in2={}",
+                    fmt_felt(*mult),
+                    fmt_addr(*out),
+                    fmt_operand(*in1, imms.in1),
+                    fmt_operand(*in2, imms.in2)
+                )
+            }
+            Instruction::ExtAlu(ExtAluInstr {
+                opcode,
+                mult,
+                addrs: ExtAluIo { out, in1, in2 },
+            }) => {
+                let (_, mnemonic) = EXT_ALU_MNEMONICS.iter().find(|(op, _)| op == opcode).unwrap();
+                write!(
+                    f,
+                    "{mnemonic} mult={} out={} in1={} in2={}",
+                    fmt_felt(*mult),
+                    fmt_addr(*out),
+                    fmt_addr(*in1),
+                    fmt_addr(*in2)
+                )
+            }
+            Instruction::Mem(MemInstr { addrs, vals, mult, kind }) => {
+                let mnemonic = match kind {
+                    MemAccessKind::Read => "mem.read",
+                    MemAccessKind::Write => "mem.write",
+                };
+                write!(
+                    f,
+                    "{mnemonic} mult={} addr={} val={}",
+                    fmt_felt(*mult),
+                    fmt_addr(addrs.inner),
+                    fmt_block(&vals.inner)
+                )
+            }
+            Instruction::Poseidon2(instr) => {
+                let Poseidon2SkinnyInstr { addrs: Poseidon2Io { input, output }, mults, padding } =
+                    instr.as_ref();
+                write!(
+                    f,
+                    "poseidon2 out={} in={} padding={padding}",
+                    fmt_list(
+                        output.iter().zip(mults).map(|(&addr, &mult)| fmt_addr_mult(&(addr, mult)))
+                    ),
+                    fmt_addrs(input)
+                )
+            }
+            Instruction::Select(SelectInstr {
+                addrs: SelectIo { bit, out1, out2, in1, in2 },
+                mult1,
+                mult2,
+            }) => write!(
+                f,
+                "select mult1={} mult2={} bit={} out1={} out2={} in1={} in2={}",
+                fmt_felt(*mult1),
+                fmt_felt(*mult2),
+                fmt_addr(*bit),
+                fmt_addr(*out1),
+                fmt_addr(*out2),
+                fmt_addr(*in1),
+                fmt_addr(*in2)
+            ),
+            Instruction::Fma(FmaInstr { addrs: FmaIo { out, in1, in2, in3 }, mult }) => write!(
+                f,
+                "fma mult={} out={} in1={} in2={} in3={}",
+                fmt_felt(*mult),
+                fmt_addr(*out),
+                fmt_addr(*in1),
+                fmt_addr(*in2),
+                fmt_addr(*in3)
+            ),
+            Instruction::RangeCheck(RangeCheckInstr {
+                addrs: RangeCheckIo { value },
+                num_bits,
+            }) => {
+                write!(f, "range_check value={} bits={num_bits}", fmt_addr(*value))
+            }
+            Instruction::ExpReverseBitsLen(ExpReverseBitsInstr {
+                addrs: ExpReverseBitsIo { base, exp, result },
+                mult,
+            }) => write!(
+                f,
+                "exp_reverse_bits_len mult={} base={} exp={} result={}",
+                fmt_felt(*mult),
+                fmt_addr(*base),
+                fmt_addrs(exp),
+                fmt_addr(*result)
+            ),
+            Instruction::HintBits(HintBitsInstr { output_addrs_mults, input_addr }) => write!(
+                f,
+                "hint_bits out={} in={}",
+                fmt_addr_mults(output_addrs_mults),
+                fmt_addr(*input_addr)
+            ),
+            Instruction::HintAddCurve(instr) => {
+                let HintAddCurveInstr {
+                    output_x_addrs_mults,
+                    output_y_addrs_mults,
+                    input1_x_addrs,
+                    input1_y_addrs,
+                    input2_x_addrs,
+                    input2_y_addrs,
+                } = instr.as_ref();
+                write!(
+                    f,
+                    "hint_add_curve out_x={} out_y={} in1_x={} in1_y={} in2_x={} in2_y={}",
+                    fmt_addr_mults(output_x_addrs_mults),
+                    fmt_addr_mults(output_y_addrs_mults),
+                    fmt_addrs(input1_x_addrs),
+                    fmt_addrs(input1_y_addrs),
+                    fmt_addrs(input2_x_addrs),
+                    fmt_addrs(input2_y_addrs)
+                )
+            }
+            Instruction::FriFold(instr) => {
+                let FriFoldInstr {
+                    base_single_addrs: FriFoldBaseIo { x },
+                    ext_single_addrs: FriFoldExtSingleIo { z, alpha },
+                    ext_vec_addrs:
+                        FriFoldExtVecIo {
+                            mat_opening,
+                            ps_at_z,
+                            alpha_pow_input,
+                            ro_input,
+                            alpha_pow_output,
+                            ro_output,
+                        },
+                    alpha_pow_mults,
+                    ro_mults,
+                } = instr.as_ref();
+                write!(
+                    f,
+                    "fri_fold x={} z={} alpha={} mat_opening={} ps_at_z={} alpha_pow_input={} \
+                     ro_input={} alpha_pow_output={} ro_output={} alpha_pow_mults={} ro_mults={}",
+                    fmt_addr(*x),
+                    fmt_addr(*z),
+                    fmt_addr(*alpha),
+                    fmt_addrs(mat_opening),
+                    fmt_addrs(ps_at_z),
+                    fmt_addrs(alpha_pow_input),
+                    fmt_addrs(ro_input),
+                    fmt_addrs(alpha_pow_output),
+                    fmt_addrs(ro_output),
+                    fmt_felts(alpha_pow_mults),
+                    fmt_felts(ro_mults)
+                )
+            }
+            Instruction::BatchFRI(instr) => {
+                let BatchFRIInstr { base_vec_addrs, ext_single_addrs, ext_vec_addrs, acc_mult } =
+                    instr.as_ref();
+                write!(
+                    f,
+                    "batch_fri acc_mult={} acc={} p_at_x={} p_at_z={} alpha_pow={}",
+                    fmt_felt(*acc_mult),
+                    fmt_addr(ext_single_addrs.acc),
+                    fmt_addrs(&base_vec_addrs.p_at_x),
+                    fmt_addrs(&ext_vec_addrs.p_at_z),
+                    fmt_addrs(&ext_vec_addrs.alpha_pow)
+                )
+            }
+            Instruction::Print(PrintInstr { field_elt_type, addr }) => {
+                let mnemonic = match field_elt_type {
+                    FieldEltType::Base => "print.f",
+                    FieldEltType::Extension => "print.ef",
+                };
+                write!(f, "{mnemonic} addr={}", fmt_addr(*addr))
+            }
+            Instruction::HintExt2Felts(HintExt2FeltsInstr { output_addrs_mults, input_addr }) => {
+                write!(
+                    f,
+                    "hint_ext2felts out={} in={}",
+                    fmt_addr_mults(output_addrs_mults),
+                    fmt_addr(*input_addr)
+                )
+            }
+            Instruction::CommitPublicValues(instr) => {
+                write!(f, "commit_public_values pv={}", fmt_addrs(&instr.pv_addrs.as_array()))
+            }
+            Instruction::CommitExtraPublicValues(instr) => {
+                write!(f, "commit_extra_public_values addrs={}", fmt_addrs(&instr.addrs))
+            }
+            Instruction::Hint(HintInstr { output_addrs_mults }) => {
+                write!(f, "hint out={}", fmt_addr_mults(output_addrs_mults))
+            }
+            #[cfg(feature = "debug")]
+            Instruction::DebugBacktrace(_) => write!(f, "debug_backtrace"),
+        }
+    }
+}
+
+/// The fields of an instruction being parsed.
+struct Fields<'a> {
+    fields: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Fields<'a> {
+    fn take(&mut self, key: &'static str) -> Result<&'a str, ParseInstructionError> {
+        let index = self
+            .fields
+            .iter()
+            .position(|&(k, _)| k == key)
+            .ok_or(ParseInstructionError::MissingField(key))?;
+        Ok(self.fields.remove(index).1)
+    }
+
+    fn parse<T>(
+        &mut self,
+        key: &'static str,
+        parse: impl FnOnce(&str) -> Option<T>,
+    ) -> Result<T, ParseInstructionError> {
+        let value = self.take(key)?;
+        parse(value)
+            .ok_or_else(|| ParseInstructionError::InvalidValue { field: key, value: value.into() })
+    }
+
+    fn felt<F: PrimeField32>(&mut self, key: &'static str) -> Result<F, ParseInstructionError> {
+        self.parse(key, parse_felt)
+    }
+
+    fn addr<F: PrimeField32>(
+        &mut self,
+        key: &'static str,
+    ) -> Result<Address<F>, ParseInstructionError> {
+        self.parse(key, parse_addr)
+    }
+
+    /// An input that is either an address or an immediate, with whether it is an immediate.
+    fn operand<F: PrimeField32>(
+        &mut self,
+        key: &'static str,
+    ) -> Result<(Address<F>, bool), ParseInstructionError> {
+        self.parse(key, |value| match parse_addr(value) {
+            Some(addr) => Some((addr, false)),
+            None => parse_felt(value).map(|value| (Address(value), true)),
+        })
+    }
+
+    fn felts<F: PrimeField32>(
+        &mut self,
+        key: &'static str,
+    ) -> Result<Vec<F>, ParseInstructionError> {
+        self.parse(key, |value| parse_list(value, parse_felt))
+    }
+
+    fn addrs<F: PrimeField32>(
+        &mut self,
+        key: &'static str,
+    ) -> Result<Vec<Address<F>>, ParseInstructionError> {
+        self.parse(key, |value| parse_list(value, parse_addr))
+    }
+
+    fn addr_mults<F: PrimeField32>(
+        &mut self,
+        key: &'static str,
+    ) -> Result<Vec<(Address<F>, F)>, ParseInstructionError> {
+        self.parse(key, |value| parse_list(value, parse_addr_mult))
+    }
+
+    /// Fails if a field was not used by the instruction.
+    fn finish(self) -> Result<(), ParseInstructionError> {
+        match self.fields.first() {
+            Some((key, _)) => Err(ParseInstructionError::UnexpectedField(key.to_string())),
+            None => Ok(()),
+        }
+    }
+}
+
+fn parse_felt<F: PrimeField32>(value: &str) -> Option<F> {
+    value.parse().ok().filter(|&value| value < F::ORDER_U32).map(F::from_canonical_u32)
+}
+
+fn parse_addr<F: PrimeField32>(value: &str) -> Option<Address<F>> {
+    value.strip_prefix('@').and_then(parse_felt).map(Address)
+}
+
+fn parse_addr_mult<F: PrimeField32>(value: &str) -> Option<(Address<F>, F)> {
+    let (addr, mult) = value.split_once(':')?;
+    Some((parse_addr(addr)?, parse_felt(mult)?))
+}
+
+fn parse_list<T>(value: &str, parse: impl Fn(&str) -> Option<T>) -> Option<Vec<T>> {
+    let items = value.strip_prefix('[')?.strip_suffix(']')?;
+    if items.is_empty() {
+        return Some(vec![]);
+    }
+    items.split(',').map(parse).collect()
+}
+
+fn to_array<T, const N: usize>(
+    field: &'static str,
+    items: Vec<T>,
+) -> Result<[T; N], ParseInstructionError> {
+    items.try_into().map_err(|items: Vec<T>| ParseInstructionError::WrongLength {
+        field,
+        expected: N,
+        found: items.len(),
+    })
+}
+
+impl<F: PrimeField32> FromStr for Instruction<F> {
+    type Err = ParseInstructionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = s.split_whitespace();
+        let mnemonic = tokens.next().ok_or(ParseInstructionError::Empty)?;
+        let mut fields = Fields {
+            fields: tokens
+                .map(|token| {
+                    token
+                        .split_once('=')
+                        .ok_or_else(|| ParseInstructionError::MalformedField(token.into()))
+                })
+                .collect::<Result<_, _>>()?,
+        };
+
+        if let Some(&(opcode, _)) = BASE_ALU_MNEMONICS.iter().find(|(_, m)| *m == mnemonic) {
+            let mult = fields.felt("mult")?;
+            let out = fields.addr("out")?;
+            let (in1, imm1) = fields.operand("in1")?;
+            let (in2, imm2) = fields.operand("in2")?;
+            let instr = Instruction::BaseAlu(BaseAluInstr {
+                opcode,
+                mult,
+                addrs: BaseAluIo { out, in1, in2 },
+                imms: BaseAluImms { in1: imm1, in2: imm2 },
+            });
+            fields.finish()?;
+            return Ok(instr);
+        }
+        if let Some(&(opcode, _)) = EXT_ALU_MNEMONICS.iter().find(|(_, m)| *m == mnemonic) {
+            let instr = Instruction::ExtAlu(ExtAluInstr {
+                opcode,
+                mult: fields.felt("mult")?,
+                addrs: ExtAluIo {
+                    out: fields.addr("out")?,
+                    in1: fields.addr("in1")?,
+                    in2: fields.addr("in2")?,
+                },
+            });
+            fields.finish()?;
+            return Ok(instr);
+        }
+
+        let instr = match mnemonic {
+            "mem.read" | "mem.write" => Instruction::Mem(MemInstr {
+                mult: fields.felt("mult")?,
+                addrs: MemIo { inner: fields.addr("addr")? },
+                vals: MemIo { inner: Block(to_array("val", fields.felts("val")?)?) },
+                kind: if mnemonic == "mem.read" {
+                    MemAccessKind::Read
+                } else {
+                    MemAccessKind::Write
+                },
+            }),
+            "poseidon2" => {
+                let (output, mults): (Vec<_>, Vec<_>) =
+                    fields.addr_mults("out")?.into_iter().unzip();
+                Instruction::Poseidon2(Box::new(Poseidon2Instr {
+                    addrs: Poseidon2Io {
+                        input: to_array("in", fields.addrs("in")?)?,
+                        output: to_array("out", output)?,
+                    },
+                    mults: to_array("out", mults)?,
+                    padding: fields.parse("padding", |value| value.parse().ok())?,
+                }))
+            }
+            "select" => Instruction::Select(SelectInstr {
+                mult1: fields.felt("mult1")?,
+                mult2: fields.felt("mult2")?,
+                addrs: SelectIo {
+                    bit: fields.addr("bit")?,
+                    out1: fields.addr("out1")?,
+                    out2: fields.addr("out2")?,
+                    in1: fields.addr("in1")?,
+                    in2: fields.addr("in2")?,
+                },
+            }),
+            "fma" => Instruction::Fma(FmaInstr {
+                mult: fields.felt("mult")?,
+                addrs: FmaIo {
+                    out: fields.addr("out")?,
+                    in1: fields.addr("in1")?,
+                    in2: fields.addr("in2")?,
+                    in3: fields.addr("in3")?,
+                },
+            }),
+            "range_check" => Instruction::RangeCheck(RangeCheckInstr {
+                addrs: RangeCheckIo { value: fields.addr("value")? },
+                num_bits: fields.parse("bits", |value| value.parse().ok())?,
+            }),
+            "exp_reverse_bits_len" => Instruction::ExpReverseBitsLen(ExpReverseBitsInstr {
+                mult: fields.felt("mult")?,
+                addrs: ExpReverseBitsIo {
+                    base: fields.addr("base")?,
+                    exp: fields.addrs("exp")?,
+                    result: fields.addr("result")?,
+                },
+            }),
+            "hint_bits" => Instruction::HintBits(HintBitsInstr {
+                output_addrs_mults: fields.addr_mults("out")?,
+                input_addr: fields.addr("in")?,
+            }),
+            "hint_add_curve" => Instruction::HintAddCurve(Box::new(HintAddCurveInstr {
+                output_x_addrs_mults: fields.addr_mults("out_x")?,
+                output_y_addrs_mults: fields.addr_mults("out_y")?,
+                input1_x_addrs: fields.addrs("in1_x")?,
+                input1_y_addrs: fields.addrs("in1_y")?,
+                input2_x_addrs: fields.addrs("in2_x")?,
+                input2_y_addrs: fields.addrs("in2_y")?,
+            })),
+            "fri_fold" => Instruction::FriFold(Box::new(FriFoldInstr {
+                base_single_addrs: FriFoldBaseIo { x: fields.addr("x")? },
+                ext_single_addrs: FriFoldExtSingleIo {
+                    z: fields.addr("z")?,
+                    alpha: fields.addr("alpha")?,
+                },
+                ext_vec_addrs: FriFoldExtVecIo {
+                    mat_opening: fields.addrs("mat_opening")?,
+                    ps_at_z: fields.addrs("ps_at_z")?,
+                    alpha_pow_input: fields.addrs("alpha_pow_input")?,
+                    ro_input: fields.addrs("ro_input")?,
+                    alpha_pow_output: fields.addrs("alpha_pow_output")?,
+                    ro_output: fields.addrs("ro_output")?,
+                },
+                alpha_pow_mults: fields.felts("alpha_pow_mults")?,
+                ro_mults: fields.felts("ro_mults")?,
+            })),
+            "batch_fri" => Instruction::BatchFRI(Box::new(BatchFRIInstr {
+                acc_mult: fields.felt("acc_mult")?,
+                ext_single_addrs: BatchFRIExtSingleIo { acc: fields.addr("acc")? },
+                base_vec_addrs: BatchFRIBaseVecIo { p_at_x: fields.addrs("p_at_x")? },
+                ext_vec_addrs: BatchFRIExtVecIo {
+                    p_at_z: fields.addrs("p_at_z")?,
+                    alpha_pow: fields.addrs("alpha_pow")?,
+                },
+            })),
+            "print.f" | "print.ef" => Instruction::Print(PrintInstr {
+                field_elt_type: if mnemonic == "print.f" {
+                    FieldEltType::Base
+                } else {
+                    FieldEltType::Extension
+                },
+                addr: fields.addr("addr")?,
+            }),
+            "hint_ext2felts" => Instruction::HintExt2Felts(HintExt2FeltsInstr {
+                output_addrs_mults: to_array("out", fields.addr_mults("out")?)?,
+                input_addr: fields.addr("in")?,
+            }),
+            "commit_public_values" => {
+                let pv_addrs: [Address<F>; RECURSIVE_PROOF_NUM_PV_ELTS] =
+                    to_array("pv", fields.addrs("pv")?)?;
+                let pv_addrs: &RecursionPublicValues<Address<F>> = pv_addrs.as_slice().borrow();
+                Instruction::CommitPublicValues(Box::new(CommitPublicValuesInstr {
+                    pv_addrs: *pv_addrs,
+                }))
+            }
+            "commit_extra_public_values" => {
+                Instruction::CommitExtraPublicValues(Box::new(CommitExtraPublicValuesInstr {
+                    addrs: fields.addrs("addrs")?,
+                }))
+            }
+            "hint" => {
+                Instruction::Hint(HintInstr { output_addrs_mults: fields.addr_mults("out")? })
+            }
+            #[cfg(feature = "debug")]
+            "debug_backtrace" => {
+                Instruction::DebugBacktrace(backtrace::Backtrace::new_unresolved())
+            }
+            _ => return Err(ParseInstructionError::UnknownMnemonic(mnemonic.into())),
+        };
+        fields.finish()?;
+        Ok(instr)
+    }
+}
+
+impl<F: PrimeField32> RecursionProgram<F> {
+    /// Renders the program with one instruction per line, in the format described in the
+    /// [module documentation](self).
+    ///
+    /// The sequential blocks of the program are written as `basic { ... }` and
+    /// `parallel { { ... } { ... } }`, one bracket per line, and nested blocks are indented. The
+    /// output is parsed back into a [`RootProgram`] by [`str::parse`]. The shape of the program is
+    /// not written.
+    pub fn disassemble(&self) -> String {
+        let mut out = format!("total_memory {}\n", self.total_memory);
+        disassemble_raw(&self.inner, 0, &mut out);
+        out
+    }
+}
+
+fn disassemble_raw<F: PrimeField32>(
+    program: &RawProgram<Instruction<F>>,
+    depth: usize,
+    out: &mut String,
+) {
+    let indent = INDENT.repeat(depth);
+    for seq_block in &program.seq_blocks {
+        match seq_block {
+            SeqBlock::Basic(BasicBlock { instrs }) => {
+                out.push_str(&format!("{indent}basic {{\n"));
+                for instr in instrs {
+                    out.push_str(&format!("{indent}{INDENT}{instr}\n"));
+                }
+                out.push_str(&format!("{indent}}}\n"));
+            }
+            SeqBlock::Parallel(programs) => {
+                out.push_str(&format!("{indent}parallel {{\n"));
+                for program in programs {
+                    out.push_str(&format!("{indent}{INDENT}{{\n"));
+                    disassemble_raw(program, depth + 2, out);
+                    out.push_str(&format!("{indent}{INDENT}}}\n"));
+                }
+                out.push_str(&format!("{indent}}}\n"));
+            }
+        }
+    }
+}
+
+/// A block that is being parsed.
+enum OpenBlock<F> {
+    Program(RawProgram<Instruction<F>>),
+    Basic(BasicBlock<Instruction<F>>),
+    Parallel(Vec<RawProgram<Instruction<F>>>),
+}
+
+impl<F: PrimeField32> FromStr for RootProgram<F> {
+    type Err = ParseProgramError;
+
+    /// Parses the output of [`RecursionProgram::disassemble`]. The program still has to be
+    /// validated before it is run.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty());
+
+        let (line, header) = lines.next().ok_or(ParseProgramError::MissingTotalMemory)?;
+        let total_memory = header
+            .strip_prefix("total_memory ")
+            .and_then(|value| value.parse().ok())
+            .ok_or_else(|| ParseProgramError::UnexpectedLine { line, text: header.into() })?;
+
+        let mut stack = vec![OpenBlock::Program(RawProgram::default())];
+        for (line, text) in lines {
+            if text == "}" && stack.len() > 1 {
+                let closed = stack.pop().unwrap();
+                match (closed, stack.last_mut().unwrap()) {
+                    (OpenBlock::Basic(block), OpenBlock::Program(program)) => {
+                        program.seq_blocks.push(SeqBlock::Basic(block))
+                    }
+                    (OpenBlock::Parallel(programs), OpenBlock::Program(program)) => {
+                        program.seq_blocks.push(SeqBlock::Parallel(programs))
+                    }
+                    (OpenBlock::Program(program), OpenBlock::Parallel(programs)) => {
+                        programs.push(program)
+                    }
+                    _ => unreachable!("blocks are only opened inside their parent kind"),
+                }
+                continue;
+            }
+            let opened = match (text, stack.last_mut().unwrap()) {
+                ("basic {", OpenBlock::Program(_)) => {
+                    OpenBlock::Basic(BasicBlock { instrs: vec![] })
+                }
+                ("parallel {", OpenBlock::Program(_)) => OpenBlock::Parallel(vec![]),
+                ("{", OpenBlock::Parallel(_)) => OpenBlock::Program(RawProgram::default()),
+                (_, OpenBlock::Basic(block)) => {
+                    let instr = text
+                        .parse()
+                        .map_err(|source| ParseProgramError::Instruction { line, source })?;
+                    block.instrs.push(instr);
+                    continue;
+                }
+                _ => return Err(ParseProgramError::UnexpectedLine { line, text: text.into() }),
+            };
+            stack.push(opened);
+        }
+
+        match <[_; 1]>::try_from(stack) {
+            Ok([OpenBlock::Program(inner)]) => Ok(RootProgram { inner, total_memory, shape: None }),
+            Ok(_) => unreachable!("the root of the stack is always a program"),
+            Err(stack) => Err(ParseProgramError::Unclosed(stack.len() - 1)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{array, sync::Arc};
+
+    use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+    use p3_field::AbstractField;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use sp1_stark::{baby_bear_poseidon2::BabyBearPoseidon2, StarkGenericConfig};
+
+    use super::*;
+    use crate::{
+        chips::poseidon2_skinny::WIDTH, linear_program, runtime::instruction as instr, Runtime,
+    };
+
+    type SC = BabyBearPoseidon2;
+    type F = BabyBear;
+    type EF = <SC as StarkGenericConfig>::Challenge;
+
+    fn random_felt(rng: &mut StdRng) -> F {
+        F::from_canonical_u32(rng.gen_range(0..F::ORDER_U32))
+    }
+
+    fn random_addr(rng: &mut StdRng) -> Address<F> {
+        Address(random_felt(rng))
+    }
+
+    fn random_vec<T>(rng: &mut StdRng, mut item: impl FnMut(&mut StdRng) -> T) -> Vec<T> {
+        let len = rng.gen_range(0..6);
+        (0..len).map(|_| item(rng)).collect()
+    }
+
+    fn random_addr_mult(rng: &mut StdRng) -> (Address<F>, F) {
+        (random_addr(rng), random_felt(rng))
+    }
+
+    fn random_instruction(rng: &mut StdRng) -> Instruction<F> {
+        match rng.gen_range(0..17) {
+            0 => Instruction::BaseAlu(BaseAluInstr {
+                opcode: BASE_ALU_MNEMONICS[rng.gen_range(0..4)].0,
+                mult: random_felt(rng),
+                addrs: BaseAluIo {
+                    out: random_addr(rng),
+                    in1: random_addr(rng),
+                    in2: random_addr(rng),
+                },
+                imms: BaseAluImms { in1: rng.gen(), in2: rng.gen() },
+            }),
+            1 => Instruction::ExtAlu(ExtAluInstr {
+                opcode: EXT_ALU_MNEMONICS[rng.gen_range(0..4)].0,
+                mult: random_felt(rng),
+                addrs: ExtAluIo {
+                    out: random_addr(rng),
+                    in1: random_addr(rng),
+                    in2: random_addr(rng),
+                },
+            }),
+            2 => Instruction::Mem(MemInstr {
+                addrs: MemIo { inner: random_addr(rng) },
+                vals: MemIo { inner: Block(array::from_fn(|_| random_felt(rng))) },
+                mult: random_felt(rng),
+                kind: if rng.gen() { MemAccessKind::Read } else { MemAccessKind::Write },
+            }),
+            3 => Instruction::Poseidon2(Box::new(Poseidon2Instr {
+                addrs: Poseidon2Io {
+                    input: array::from_fn(|_| random_addr(rng)),
+                    output: array::from_fn(|_| random_addr(rng)),
+                },
+                mults: array::from_fn(|_| random_felt(rng)),
+                padding: rng.gen_range(0..=HASH_RATE as u32),
+            })),
+            4 => Instruction::Select(SelectInstr {
+                addrs: SelectIo {
+                    bit: random_addr(rng),
+                    out1: random_addr(rng),
+                    out2: random_addr(rng),
+                    in1: random_addr(rng),
+                    in2: random_addr(rng),
+                },
+                mult1: random_felt(rng),
+                mult2: random_felt(rng),
+            }),
+            5 => Instruction::Fma(FmaInstr {
+                addrs: FmaIo {
+                    out: random_addr(rng),
+                    in1: random_addr(rng),
+                    in2: random_addr(rng),
+                    in3: random_addr(rng),
+                },
+                mult: random_felt(rng),
+            }),
+            6 => Instruction::RangeCheck(RangeCheckInstr {
+                addrs: RangeCheckIo { value: random_addr(rng) },
+                num_bits: rng.gen_range(0..=30),
+            }),
+            7 => Instruction::ExpReverseBitsLen(ExpReverseBitsInstr {
+                addrs: ExpReverseBitsIo {
+                    base: random_addr(rng),
+                    exp: random_vec(rng, random_addr),
+                    result: random_addr(rng),
+                },
+                mult: random_felt(rng),
+            }),
+            8 => Instruction::HintBits(HintBitsInstr {
+                output_addrs_mults: random_vec(rng, random_addr_mult),
+                input_addr: random_addr(rng),
+            }),
+            9 => Instruction::HintAddCurve(Box::new(HintAddCurveInstr {
+                output_x_addrs_mults: random_vec(rng, random_addr_mult),
+                output_y_addrs_mults: random_vec(rng, random_addr_mult),
+                input1_x_addrs: random_vec(rng, random_addr),
+                input1_y_addrs: random_vec(rng, random_addr),
+                input2_x_addrs: random_vec(rng, random_addr),
+                input2_y_addrs: random_vec(rng, random_addr),
+            })),
+            10 => Instruction::FriFold(Box::new(FriFoldInstr {
+                base_single_addrs: FriFoldBaseIo { x: random_addr(rng) },
+                ext_single_addrs: FriFoldExtSingleIo {
+                    z: random_addr(rng),
+                    alpha: random_addr(rng),
+                },
+                ext_vec_addrs: FriFoldExtVecIo {
+                    mat_opening: random_vec(rng, random_addr),
+                    ps_at_z: random_vec(rng, random_addr),
+                    alpha_pow_input: random_vec(rng, random_addr),
+                    ro_input: random_vec(rng, random_addr),
+                    alpha_pow_output: random_vec(rng, random_addr),
+                    ro_output: random_vec(rng, random_addr),
+                },
+                alpha_pow_mults: random_vec(rng, random_felt),
+                ro_mults: random_vec(rng, random_felt),
+            })),
+            11 => Instruction::BatchFRI(Box::new(BatchFRIInstr {
+                base_vec_addrs: BatchFRIBaseVecIo { p_at_x: random_vec(rng, random_addr) },
+                ext_single_addrs: BatchFRIExtSingleIo { acc: random_addr(rng) },
+                ext_vec_addrs: BatchFRIExtVecIo {
+                    p_at_z: random_vec(rng, random_addr),
+                    alpha_pow: random_vec(rng, random_addr),
+                },
+                acc_mult: random_felt(rng),
+            })),
+            12 => Instruction::Print(PrintInstr {
+                field_elt_type: if rng.gen() {
+                    FieldEltType::Base
+                } else {
+                    FieldEltType::Extension
+                },
+                addr: random_addr(rng),
+            }),
+            13 => Instruction::HintExt2Felts(HintExt2FeltsInstr {
+                output_addrs_mults: array::from_fn(|_| random_addr_mult(rng)),
+                input_addr: random_addr(rng),
+            }),
+            14 => {
+                let pv_addrs: [Address<F>; RECURSIVE_PROOF_NUM_PV_ELTS] =
+                    array::from_fn(|_| random_addr(rng));
+                let pv_addrs: &RecursionPublicValues<Address<F>> = pv_addrs.as_slice().borrow();
+                Instruction::CommitPublicValues(Box::new(CommitPublicValuesInstr {
+                    pv_addrs: *pv_addrs,
+                }))
+            }
+            15 => Instruction::CommitExtraPublicValues(Box::new(CommitExtraPublicValuesInstr {
+                addrs: random_vec(rng, random_addr),
+            })),
+            _ => Instruction::Hint(HintInstr {
+                output_addrs_mults: random_vec(rng, random_addr_mult),
+            }),
+        }
+    }
+
+    #[test]
+    fn instruction_round_trip() {
+        let mut rng = StdRng::seed_from_u64(0x6969);
+        for _ in 0..500 {
+            let instr = random_instruction(&mut rng);
+            let text = instr.to_string();
+            let parsed = text.parse::<Instruction<F>>().unwrap();
+            // `Instruction` does not implement `PartialEq`, and `Debug` shows every field.
+            assert_eq!(format!("{parsed:?}"), format!("{instr:?}"), "{text}");
+            assert_eq!(parsed.to_string(), text);
+        }
+    }
+
+    #[test]
+    fn program_round_trip() {
+        let mut rng = StdRng::seed_from_u64(0x6970);
+        let basic = |rng: &mut StdRng| {
+            SeqBlock::Basic(BasicBlock { instrs: random_vec(rng, random_instruction) })
+        };
+        let inner = RawProgram {
+            seq_blocks: vec![
+                basic(&mut rng),
+                SeqBlock::Parallel(vec![
+                    RawProgram { seq_blocks: vec![basic(&mut rng), basic(&mut rng)] },
+                    RawProgram::default(),
+                    RawProgram {
+                        seq_blocks: vec![SeqBlock::Parallel(vec![RawProgram {
+                            seq_blocks: vec![basic(&mut rng)],
+                        }])],
+                    },
+                ]),
+                basic(&mut rng),
+            ],
+        };
+        // SAFETY: The program is only disassembled, never run.
+        let program = unsafe {
+            RecursionProgram::new_unchecked(RootProgram { inner, total_memory: 42, shape: None })
+        };
+
+        let text = program.disassemble();
+        let parsed = text.parse::<RootProgram<F>>().unwrap();
+        assert_eq!(format!("{parsed:?}"), format!("{:?}", *program));
+        // SAFETY: As above.
+        assert_eq!(unsafe { RecursionProgram::new_unchecked(parsed) }.disassemble(), text);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let parse = |s: &str| s.parse::<Instruction<F>>().unwrap_err();
+        assert_eq!(parse(""), ParseInstructionError::Empty);
+        assert_eq!(parse("jump to=@3"), ParseInstructionError::UnknownMnemonic("jump".into()));
+        assert_eq!(parse("range_check value=@3"), ParseInstructionError::MissingField("bits"));
+        assert_eq!(
+            parse("range_check value=@3 bits=4 mult=1"),
+            ParseInstructionError::UnexpectedField("mult".into())
+        );
+        assert_eq!(
+            parse("fma mult=1 out=3 in1=@0 in2=@1 in3=@2"),
+            ParseInstructionError::InvalidValue { field: "out", value: "3".into() }
+        );
+        assert_eq!(
+            parse(&format!("base_alu.addf mult={} out=@2 in1=@0 in2=@1", F::ORDER_U32)),
+            ParseInstructionError::InvalidValue { field: "mult", value: F::ORDER_U32.to_string() }
+        );
+        assert_eq!(
+            parse("mem.write mult=1 addr=@0 val=[1,2,3]"),
+            ParseInstructionError::WrongLength { field: "val", expected: 4, found: 3 }
+        );
+
+        assert_eq!(
+            "total_memory 4\nbasic {\n    fma\n}\n".parse::<RootProgram<F>>().unwrap_err(),
+            ParseProgramError::Instruction {
+                line: 3,
+                source: ParseInstructionError::MissingField("mult")
+            }
+        );
+        assert_eq!(
+            "total_memory 4\nparallel {\n    {\n".parse::<RootProgram<F>>().unwrap_err(),
+            ParseProgramError::Unclosed(2)
+        );
+    }
+
+    #[test]
+    fn display_format() {
+        let instr = instr::select::<F>(1, 0, 0, 3, 4, 1, 2);
+        assert_eq!(
+            instr.to_string(),
+            "select mult1=1 mult2=0 bit=@0 out1=@3 out2=@4 in1=@1 in2=@2"
+        );
+        let instr = instr::mem::<F>(MemAccessKind::Write, 2, 5, 7);
+        assert_eq!(instr.to_string(), "mem.write mult=2 addr=@5 val=[7,0,0,0]");
+        let imms = BaseAluImms { in1: false, in2: true };
+        let instr = instr::base_alu_imm::<F>(BaseAluOpcode::AddF, 1, 2, 0, 5, imms);
+        assert_eq!(instr.to_string(), "base_alu.addf mult=1 out=@2 in1=@0 in2=5");
+        assert_eq!(
+            instr::poseidon2::<F>([1; WIDTH], array::from_fn(|i| i as u32), [0; WIDTH])
+                .to_string()
+                .split_whitespace()
+                .nth(1)
+                .unwrap(),
+            format!("out=[{}]", (0..WIDTH).map(|i| format!("@{i}:1")).join(","))
+        );
+    }
+
+    #[test]
+    fn trace_execution() {
+        let program = linear_program(vec![
+            instr::mem(MemAccessKind::Write, 1, 0, 3),
+            instr::mem(MemAccessKind::Write, 1, 1, 4),
+            instr::base_alu(BaseAluOpcode::MulF, 1, 2, 0, 1),
+            instr::mem(MemAccessKind::Read, 1, 2, 12),
+        ])
+        .unwrap();
+        let mut buf = Vec::new();
+        let mut runtime =
+            Runtime::<F, EF, DiffusionMatrixBabyBear>::new(Arc::new(program), SC::new().perm);
+        runtime.debug_stdout = Box::new(&mut buf);
+        runtime.trace_execution = true;
+        runtime.run().unwrap();
+        drop(runtime);
+
+        assert_eq!(
+            String::from_utf8(buf).unwrap().lines().collect::<Vec<_>>(),
+            [
+                "TRACE mem.write mult=1 addr=@0 val=[3,0,0,0] | reads: | writes: @0=[3,0,0,0]",
+                "TRACE mem.write mult=1 addr=@1 val=[4,0,0,0] | reads: | writes: @1=[4,0,0,0]",
+                "TRACE base_alu.mulf mult=1 out=@2 in1=@0 in2=@1 \
+                 | reads: @0=[3,0,0,0] @1=[4,0,0,0] | writes: @2=[12,0,0,0]",
+                "TRACE mem.read mult=1 addr=@2 val=[12,0,0,0] | reads: @2=[12,0,0,0] | writes:",
+            ]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::*;
+use crate::{chips::range_check::RANGE_CHECK_MAX_BITS, *};
 #[cfg(feature = "debug")]
 use backtrace::Backtrace;
 use p3_field::{AbstractExtensionField, AbstractField};
@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 #[cfg(any(test, feature = "program_validation"))]
 use smallvec::SmallVec;
 
-use std::borrow::Borrow;
+use std::{borrow::Borrow, iter::zip};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Instruction<F> {
@@ -16,6 +16,8 @@ pub enum Instruction<F> {
     Mem(MemInstr<F>),
     Poseidon2(Box<Poseidon2Instr<F>>),
     Select(SelectInstr<F>),
+    Fma(FmaInstr<F>),
+    RangeCheck(RangeCheckInstr<F>),
     ExpReverseBitsLen(ExpReverseBitsInstr<F>),
     HintBits(HintBitsInstr<F>),
     HintAddCurve(Box<HintAddCurveInstr<F>>),
@@ -24,6 +26,7 @@ pub enum Instruction<F> {
     Print(PrintInstr<F>),
     HintExt2Felts(HintExt2FeltsInstr<F>),
     CommitPublicValues(Box<CommitPublicValuesInstr<F>>),
+    CommitExtraPublicValues(Box<CommitExtraPublicValuesInstr<F>>),
     Hint(HintInstr<F>),
     #[cfg(feature = "debug")]
     DebugBacktrace(Backtrace),
@@ -38,8 +41,13 @@ impl<F: Copy> Instruction<F> {
         use std::iter;
 
         match *self {
-            Instruction::BaseAlu(BaseAluInstr { addrs: BaseAluIo { out, in1, in2 }, .. }) => {
-                (svec![in1, in2], svec![out])
+            Instruction::BaseAlu(BaseAluInstr {
+                addrs: BaseAluIo { out, in1, in2 },
+                imms: BaseAluImms { in1: imm1, in2: imm2 },
+                ..
+            }) => {
+                let reads = [(in1, imm1), (in2, imm2)].into_iter();
+                (reads.filter(|&(_, imm)| !imm).map(|(addr, _)| addr).collect(), svec![out])
             }
             Instruction::ExtAlu(ExtAluInstr { addrs: ExtAluIo { out, in1, in2 }, .. }) => {
                 (svec![in1, in2], svec![out])
@@ -48,12 +56,26 @@ impl<F: Copy> Instruction<F> {
             Instruction::Poseidon2(ref instr) => {
                 let Poseidon2SkinnyInstr { addrs: Poseidon2Io { input, output }, .. } =
                     instr.as_ref();
-                (SmallVec::from_slice(input), SmallVec::from_slice(output))
+                (
+                    input
+                        .iter()
+                        .enumerate()
+                        .filter(|&(i, _)| !instr.is_padding(i))
+                        .map(|(_, &addr)| addr)
+                        .collect(),
+                    SmallVec::from_slice(output),
+                )
             }
             Instruction::Select(SelectInstr {
                 addrs: SelectIo { bit, out1, out2, in1, in2 },
                 ..
             }) => (svec![bit, in1, in2], svec![out1, out2]),
+            Instruction::Fma(FmaInstr { addrs: FmaIo { out, in1, in2, in3 }, .. }) => {
+                (svec![in1, in2, in3], svec![out])
+            }
+            Instruction::RangeCheck(RangeCheckInstr { addrs: RangeCheckIo { value }, .. }) => {
+                (svec![value], svec![])
+            }
             Instruction::ExpReverseBitsLen(ExpReverseBitsInstr {
                 addrs: ExpReverseBitsIo { base, ref exp, result },
                 ..
@@ -133,6 +155,9 @@ impl<F: Copy> Instruction<F> {
                 let CommitPublicValuesInstr { pv_addrs } = instr.as_ref();
                 (pv_addrs.as_array().to_vec().into(), svec![])
             }
+            Instruction::CommitExtraPublicValues(ref instr) => {
+                (instr.addrs.iter().copied().collect(), svec![])
+            }
             Instruction::Hint(HintInstr { ref output_addrs_mults }) => {
                 (svec![], output_addrs_mults.iter().map(|(a, _)| *a).collect())
             }
@@ -140,6 +165,140 @@ impl<F: Copy> Instruction<F> {
     }
 }
 
+impl<F: AbstractField + Copy> Instruction<F> {
+    /// The memory interactions of the instruction in the recursion machine: the addresses it
+    /// writes, with the number of reads declared for each, and the addresses it reads, with the
+    /// number of times each is read.
+    ///
+    /// Hints and prints read their inputs outside of the machine, so only the outputs of hints are
+    /// listed.
+    #[must_use]
+    #[allow(clippy::type_complexity)]
+    pub fn memory_interactions(&self) -> (Vec<(Address<F>, F)>, Vec<(Address<F>, F)>) {
+        let once = |addr: Address<F>| (addr, F::one());
+        match *self {
+            Instruction::BaseAlu(BaseAluInstr {
+                addrs: BaseAluIo { out, in1, in2 },
+                mult,
+                imms: BaseAluImms { in1: imm1, in2: imm2 },
+                ..
+            }) => {
+                let reads = [(in1, imm1), (in2, imm2)].into_iter();
+                let reads = reads.filter(|&(_, imm)| !imm).map(|(addr, _)| once(addr));
+                (vec![(out, mult)], reads.collect())
+            }
+            Instruction::ExtAlu(ExtAluInstr {
+                addrs: ExtAluIo { out, in1, in2 }, mult, ..
+            }) => (vec![(out, mult)], vec![once(in1), once(in2)]),
+            Instruction::Mem(MemInstr { addrs: MemIo { inner }, mult, kind, .. }) => match kind {
+                MemAccessKind::Write => (vec![(inner, mult)], vec![]),
+                MemAccessKind::Read => (vec![], vec![(inner, mult)]),
+            },
+            Instruction::Poseidon2(ref instr) => {
+                let Poseidon2SkinnyInstr { addrs: Poseidon2Io { input, output }, mults, .. } =
+                    instr.as_ref();
+                (
+                    zip(*output, *mults).collect(),
+                    input
+                        .iter()
+                        .enumerate()
+                        .filter(|&(i, _)| !instr.is_padding(i))
+                        .map(|(_, &addr)| once(addr))
+                        .collect(),
+                )
+            }
+            Instruction::Select(SelectInstr {
+                addrs: SelectIo { bit, out1, out2, in1, in2 },
+                mult1,
+                mult2,
+            }) => (vec![(out1, mult1), (out2, mult2)], vec![once(bit), once(in1), once(in2)]),
+            Instruction::Fma(FmaInstr { addrs: FmaIo { out, in1, in2, in3 }, mult }) => {
+                (vec![(out, mult)], vec![once(in1), once(in2), once(in3)])
+            }
+            Instruction::RangeCheck(RangeCheckInstr { addrs: RangeCheckIo { value }, .. }) => {
+                (vec![], vec![once(value)])
+            }
+            Instruction::ExpReverseBitsLen(ExpReverseBitsInstr {
+                addrs: ExpReverseBitsIo { base, ref exp, result },
+                mult,
+            }) => (vec![(result, mult)], exp.iter().copied().chain([base]).map(once).collect()),
+            Instruction::HintBits(HintBitsInstr { ref output_addrs_mults, .. }) => {
+                (output_addrs_mults.clone(), vec![])
+            }
+            Instruction::HintAddCurve(ref instr) => {
+                let HintAddCurveInstr { output_x_addrs_mults, output_y_addrs_mults, .. } =
+                    instr.as_ref();
+                (
+                    [output_x_addrs_mults.as_slice(), output_y_addrs_mults.as_slice()].concat(),
+                    vec![],
+                )
+            }
+            Instruction::FriFold(ref instr) => {
+                let FriFoldInstr {
+                    base_single_addrs: FriFoldBaseIo { x },
+                    ext_single_addrs: FriFoldExtSingleIo { z, alpha },
+                    ext_vec_addrs:
+                        FriFoldExtVecIo {
+                            ref mat_opening,
+                            ref ps_at_z,
+                            ref alpha_pow_input,
+                            ref ro_input,
+                            ref alpha_pow_output,
+                            ref ro_output,
+                        },
+                    ref alpha_pow_mults,
+                    ref ro_mults,
+                } = *instr.as_ref();
+                (
+                    zip(alpha_pow_output, alpha_pow_mults)
+                        .chain(zip(ro_output, ro_mults))
+                        .map(|(&addr, &mult)| (addr, mult))
+                        .collect(),
+                    [mat_opening, ps_at_z, alpha_pow_input, ro_input]
+                        .into_iter()
+                        .flatten()
+                        .copied()
+                        .chain([x, z, alpha])
+                        .map(once)
+                        .collect(),
+                )
+            }
+            Instruction::BatchFRI(ref instr) => {
+                let BatchFRIInstr { base_vec_addrs, ext_single_addrs, ext_vec_addrs, acc_mult } =
+                    instr.as_ref();
+                (
+                    vec![(ext_single_addrs.acc, *acc_mult)],
+                    [
+                        base_vec_addrs.p_at_x.as_slice(),
+                        ext_vec_addrs.p_at_z.as_slice(),
+                        ext_vec_addrs.alpha_pow.as_slice(),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .copied()
+                    .map(once)
+                    .collect(),
+                )
+            }
+            Instruction::Print(_) => Default::default(),
+            #[cfg(feature = "debug")]
+            Instruction::DebugBacktrace(_) => Default::default(),
+            Instruction::HintExt2Felts(HintExt2FeltsInstr { output_addrs_mults, .. }) => {
+                (output_addrs_mults.to_vec(), vec![])
+            }
+            Instruction::CommitPublicValues(ref instr) => {
+                (vec![], instr.pv_addrs.digest.map(once).to_vec())
+            }
+            Instruction::CommitExtraPublicValues(ref instr) => {
+                (vec![], instr.addrs.iter().copied().map(once).collect())
+            }
+            Instruction::Hint(HintInstr { ref output_addrs_mults }) => {
+                (output_addrs_mults.clone(), vec![])
+            }
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct HintBitsInstr<F> {
     /// Addresses and mults of the output bits.
@@ -189,6 +348,19 @@ pub fn base_alu<F: AbstractField>(
     out: u32,
     in1: u32,
     in2: u32,
+) -> Instruction<F> {
+    base_alu_imm(opcode, mult, out, in1, in2, BaseAluImms::default())
+}
+
+/// A base field ALU operation whose inputs flagged in `imms` are immediates, in which case `in1`
+/// or `in2` is the value of the input instead of its address.
+pub fn base_alu_imm<F: AbstractField>(
+    opcode: BaseAluOpcode,
+    mult: u32,
+    out: u32,
+    in1: u32,
+    in2: u32,
+    imms: BaseAluImms<bool>,
 ) -> Instruction<F> {
     Instruction::BaseAlu(BaseAluInstr {
         opcode,
@@ -198,6 +370,7 @@ pub fn base_alu<F: AbstractField>(
             in1: Address(F::from_canonical_u32(in1)),
             in2: Address(F::from_canonical_u32(in2)),
         },
+        imms,
     })
 }
 
@@ -265,12 +438,25 @@ pub fn poseidon2<F: AbstractField>(
     output: [u32; WIDTH],
     input: [u32; WIDTH],
 ) -> Instruction<F> {
+    poseidon2_padded(mults, output, input, 0)
+}
+
+/// A Poseidon2 permutation whose last `padding` rate lanes hold the `10*` padding, in which case
+/// the corresponding addresses of `input` are not read.
+pub fn poseidon2_padded<F: AbstractField>(
+    mults: [u32; WIDTH],
+    output: [u32; WIDTH],
+    input: [u32; WIDTH],
+    padding: u32,
+) -> Instruction<F> {
+    assert!(padding as usize <= HASH_RATE, "cannot pad {padding} lanes, the rate is {HASH_RATE}");
     Instruction::Poseidon2(Box::new(Poseidon2Instr {
         mults: mults.map(F::from_canonical_u32),
         addrs: Poseidon2Io {
             output: output.map(F::from_canonical_u32).map(Address),
             input: input.map(F::from_canonical_u32).map(Address),
         },
+        padding,
     }))
 }
 
@@ -297,6 +483,29 @@ pub fn select<F: AbstractField>(
     })
 }
 
+pub fn fma<F: AbstractField>(mult: u32, out: u32, in1: u32, in2: u32, in3: u32) -> Instruction<F> {
+    Instruction::Fma(FmaInstr {
+        mult: F::from_canonical_u32(mult),
+        addrs: FmaIo {
+            out: Address(F::from_canonical_u32(out)),
+            in1: Address(F::from_canonical_u32(in1)),
+            in2: Address(F::from_canonical_u32(in2)),
+            in3: Address(F::from_canonical_u32(in3)),
+        },
+    })
+}
+
+pub fn range_check<F: AbstractField>(value: u32, num_bits: u32) -> Instruction<F> {
+    assert!(
+        num_bits as usize <= RANGE_CHECK_MAX_BITS,
+        "cannot range check {num_bits} bits, the maximum is {RANGE_CHECK_MAX_BITS}"
+    );
+    Instruction::RangeCheck(RangeCheckInstr {
+        addrs: RangeCheckIo { value: Address(F::from_canonical_u32(value)) },
+        num_bits,
+    })
+}
+
 pub fn exp_reverse_bits_len<F: AbstractField>(
     mult: u32,
     base: F,
@@ -386,3 +595,9 @@ pub fn commit_public_values<F: AbstractField>(
         pv_addrs: pv_address.clone(),
     }))
 }
+
+pub fn commit_extra_public_values<F: AbstractField>(addrs: &[u32]) -> Instruction<F> {
+    Instruction::CommitExtraPublicValues(Box::new(CommitExtraPublicValuesInstr {
+        addrs: addrs.iter().map(|&addr| Address(F::from_canonical_u32(addr))).collect(),
+    }))
+}
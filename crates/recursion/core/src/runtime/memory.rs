@@ -36,6 +36,15 @@ impl<F: PrimeField64> MemVec<F> {
         })
     }
 
+    /// The number of entries of the memory.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     pub fn mr(&mut self, addr: Address<F>) -> &MemoryEntry<F> {
         // SAFETY: We have exclusive access to the memory, so no data races can occur.
         unsafe { self.mr_unchecked(addr) }
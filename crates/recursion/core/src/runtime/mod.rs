@@ -1,16 +1,25 @@
+mod bounds;
+mod columns;
+mod disassemble;
 pub mod instruction;
 mod memory;
+mod multiplicity;
 mod opcode;
 mod program;
 mod record;
+mod segmented;
 
 // Avoid triggering annoying branch of thiserror derive macro.
 use backtrace::Backtrace as Trace;
+pub use bounds::*;
+pub use columns::*;
+pub use disassemble::{ParseInstructionError, ParseProgramError};
 use instruction::HintAddCurveInstr;
 pub use instruction::Instruction;
 use instruction::{FieldEltType, HintBitsInstr, HintExt2FeltsInstr, HintInstr, PrintInstr};
 use itertools::Itertools;
 use memory::*;
+pub use multiplicity::*;
 pub use opcode::*;
 use p3_field::AbstractExtensionField;
 use p3_field::{AbstractField, ExtensionField, PrimeField32};
@@ -20,6 +29,7 @@ use p3_symmetric::{CryptographicPermutation, Permutation};
 use p3_util::reverse_bits_len;
 pub use program::*;
 pub use record::*;
+pub use segmented::*;
 use sp1_stark::septic_curve::SepticCurve;
 use sp1_stark::septic_extension::SepticExtension;
 use sp1_stark::MachineRecord;
@@ -59,6 +69,17 @@ pub const NUM_BITS: usize = 31;
 
 pub const D: usize = 4;
 
+/// The environment variable that turns on the execution trace of [`Runtime`].
+pub const TRACE_EXECUTION_ENV_VAR: &str = "SP1_RECURSION_TRACE";
+
+/// Returns whether the `SP1_RECURSION_TRACE` environment variable is enabled.
+///
+/// By default, the variable is disabled.
+pub fn trace_execution_enabled() -> bool {
+    std::env::var(TRACE_EXECUTION_ENV_VAR)
+        .is_ok_and(|value| value == "1" || value.eq_ignore_ascii_case("true"))
+}
+
 type Perm<F, Diffusion> = Poseidon2<
     F,
     Poseidon2ExternalMatrixGeneral,
@@ -113,6 +134,11 @@ pub struct Runtime<'a, F: PrimeField32, EF: ExtensionField<F>, Diffusion> {
     /// The stream that print statements write to.
     pub debug_stdout: Box<dyn Write + Send + 'a>,
 
+    /// Whether to write each executed instruction to `debug_stdout`, with the values at the
+    /// addresses it reads before it runs and at the addresses it writes after it runs. Defaults to
+    /// [`trace_execution_enabled`].
+    pub trace_execution: bool,
+
     /// Entries for dealing with the Poseidon2 hash state.
     perm: Option<Perm<F, Diffusion>>,
 
@@ -135,10 +161,19 @@ pub enum RuntimeError<F: Debug, EF: Debug> {
         \tnearest backtrace:\n{trace:#?}"
     )]
     DivEOutOfDomain { in1: EF, in2: EF, instr: ExtAluInstr<F>, trace: Option<Trace> },
+    #[error(
+        "range check failed: {value:?} does not fit in {} bits\n\
+        \tin instruction {instr:#?}\n\
+        \tnearest backtrace:\n{trace:#?}",
+        .instr.num_bits
+    )]
+    RangeCheckFailed { value: F, instr: RangeCheckInstr<F>, trace: Option<Trace> },
     #[error("failed to print to `debug_stdout`: {0}")]
     DebugPrint(#[from] std::io::Error),
     #[error("attempted to read from empty witness stream")]
     EmptyWitnessStream,
+    #[error("memory access out of bounds: {0}")]
+    MemoryOutOfBounds(#[from] OutOfBoundsError<F>),
 }
 
 impl<F: PrimeField32, EF: ExtensionField<F>, Diffusion> Runtime<'_, F, EF, Diffusion>
@@ -183,12 +218,22 @@ where
             record,
             witness_stream: VecDeque::new(),
             debug_stdout: Box::new(stdout()),
+            trace_execution: trace_execution_enabled(),
             perm: Some(perm),
             _marker_ef: PhantomData,
             _marker_diffusion: PhantomData,
         }
     }
 
+    /// Replaces the memory, sized by the `total_memory` declared by the program, with one of
+    /// `memory_size` entries.
+    ///
+    /// [`Self::run`] reports the accesses past the end of the memory before executing anything.
+    pub fn with_memory_size(mut self, memory_size: usize) -> Self {
+        self.memory = MemVec::with_capacity(memory_size);
+        self
+    }
+
     pub fn print_stats(&self) {
         if tracing::event_enabled!(tracing::Level::DEBUG) {
             let mut stats = self.record.stats().into_iter().collect::<Vec<_>>();
@@ -215,12 +260,14 @@ where
         witness_stream: Option<&mut VecDeque<Block<F>>>,
         instruction: Instruction<F>,
     ) -> Result<(), RuntimeError<F, EF>> {
-        let ExecEnv { memory, perm, debug_stdout } = state.env;
+        let ExecEnv { memory, perm, debug_stdout, .. } = state.env;
         let record = &mut state.record;
         match instruction {
-            Instruction::BaseAlu(instr @ BaseAluInstr { opcode, mult: _, addrs }) => {
-                let in1 = memory.mr_unchecked(addrs.in1).val[0];
-                let in2 = memory.mr_unchecked(addrs.in2).val[0];
+            Instruction::BaseAlu(instr @ BaseAluInstr { opcode, mult: _, addrs, imms }) => {
+                let in1 =
+                    if imms.in1 { addrs.in1.0 } else { memory.mr_unchecked(addrs.in1).val[0] };
+                let in2 =
+                    if imms.in2 { addrs.in2.0 } else { memory.mr_unchecked(addrs.in2).val[0] };
                 // Do the computation.
                 let out = match opcode {
                     BaseAluOpcode::AddF => in1 + in2,
@@ -298,8 +345,12 @@ where
                 record.mem_const_count += 1;
             }
             Instruction::Poseidon2(instr) => {
-                let Poseidon2Instr { addrs: Poseidon2Io { input, output }, mults: _ } = *instr;
-                let in_vals = std::array::from_fn(|i| memory.mr_unchecked(input[i]).val[0]);
+                let Poseidon2Instr { addrs: Poseidon2Io { input, output }, mults: _, padding } =
+                    *instr;
+                let in_vals = std::array::from_fn(|i| {
+                    poseidon2_padding_value(padding, i)
+                        .unwrap_or_else(|| memory.mr_unchecked(input[i]).val[0])
+                });
                 let perm_output = perm.permute(in_vals);
 
                 perm_output.iter().zip(output).for_each(|(&val, addr)| {
@@ -329,6 +380,25 @@ where
                     in2,
                 })
             }
+            Instruction::Fma(FmaInstr { addrs: FmaIo { out, in1, in2, in3 }, mult: _ }) => {
+                let in1 = memory.mr_unchecked(in1).val[0];
+                let in2 = memory.mr_unchecked(in2).val[0];
+                let in3 = memory.mr_unchecked(in3).val[0];
+                let out_val = in1 * in2 + in3;
+                memory.mw_unchecked(out, Block::from(out_val));
+                record.fma_events.push(FmaEvent { out: out_val, in1, in2, in3 });
+            }
+            Instruction::RangeCheck(instr @ RangeCheckInstr { addrs, num_bits }) => {
+                let value = memory.mr_unchecked(addrs.value).val[0];
+                if u64::from(value.as_canonical_u32()) >= 1u64 << num_bits {
+                    return Err(RuntimeError::RangeCheckFailed {
+                        value,
+                        instr,
+                        trace: state.resolve_trace().cloned(),
+                    });
+                }
+                record.range_check_events.push(RangeCheckEvent { value });
+            }
             Instruction::ExpReverseBitsLen(ExpReverseBitsInstr {
                 addrs: ExpReverseBitsIo { base, exp, result },
                 mult: _,
@@ -514,6 +584,10 @@ where
                     .commit_pv_hash_events
                     .push(CommitPublicValuesEvent { public_values: record.public_values });
             }
+            Instruction::CommitExtraPublicValues(instr) => {
+                record.extra_public_values =
+                    instr.addrs.iter().map(|&addr| memory.mr_unchecked(addr).val[0]).collect();
+            }
 
             Instruction::Print(PrintInstr { field_elt_type, addr }) => match field_elt_type {
                 FieldEltType::Base => {
@@ -558,6 +632,39 @@ where
         Ok(())
     }
 
+    /// Executes the instruction with [`Self::execute_one`], then writes it to `debug_stdout` in
+    /// the format of [`Instruction`]'s `Display` implementation, followed by the values at the
+    /// addresses it reads and writes.
+    ///
+    /// # Safety
+    ///
+    /// The same as for [`Self::execute_one`].
+    unsafe fn execute_one_traced(
+        state: &mut ExecState<F, Diffusion>,
+        witness_stream: Option<&mut VecDeque<Block<F>>>,
+        instruction: Instruction<F>,
+    ) -> Result<(), RuntimeError<F, EF>> {
+        let memory = state.env.memory;
+        let access = |(addr, _): &(Address<F>, F)| {
+            format!(
+                " {}={}",
+                disassemble::fmt_addr(*addr),
+                disassemble::fmt_block(&memory.mr_unchecked(*addr).val)
+            )
+        };
+        let text = instruction.to_string();
+        let (writes, reads) = instruction.memory_interactions();
+        let reads = reads.iter().map(access).collect::<String>();
+        Self::execute_one(state, witness_stream, instruction)?;
+        let writes = writes.iter().map(access).collect::<String>();
+        // Write the line at once, so that lines from parallel blocks are not interleaved.
+        writeln!(
+            state.env.debug_stdout.lock().unwrap(),
+            "TRACE {text} | reads:{reads} | writes:{writes}"
+        )
+        .map_err(RuntimeError::DebugPrint)
+    }
+
     /// # Safety
     ///
     /// This function makes the same safety assumptions as [`RecursionProgram::new_unchecked`].
@@ -567,12 +674,9 @@ where
         root_program: &Arc<RecursionProgram<F>>,
         mut witness_stream: Option<&mut VecDeque<Block<F>>>,
     ) -> Result<ExecutionRecord<F>, RuntimeError<F, EF>> {
-        let fresh_record =
-            || ExecutionRecord { program: Arc::clone(root_program), ..Default::default() };
-
         let mut state = ExecState {
             env: env.clone(),
-            record: fresh_record(),
+            record: ExecutionRecord { program: Arc::clone(root_program), ..Default::default() },
             #[cfg(feature = "debug")]
             last_trace: None,
         };
@@ -581,29 +685,28 @@ where
             match block {
                 SeqBlock::Basic(basic_block) => {
                     for instruction in &basic_block.instrs {
+                        let witness_stream = witness_stream.as_deref_mut();
+                        let instruction = instruction.clone();
                         unsafe {
-                            Self::execute_one(
-                                &mut state,
-                                witness_stream.as_deref_mut(),
-                                instruction.clone(),
-                            )
+                            if env.trace {
+                                Self::execute_one_traced(&mut state, witness_stream, instruction)
+                            } else {
+                                Self::execute_one(&mut state, witness_stream, instruction)
+                            }
                         }?;
                     }
                 }
                 SeqBlock::Parallel(vec) => {
-                    state.record.append(
-                        &mut vec
-                            .par_iter()
-                            .map(|subprogram| {
-                                // Witness stream may not be called inside parallel contexts to
-                                // avoid nondeterminism.
-                                Self::execute_raw(env, subprogram, root_program, None)
-                            })
-                            .try_reduce(fresh_record, |mut record, mut res| {
-                                record.append(&mut res);
-                                Ok(record)
-                            })?,
-                    );
+                    let mut records = vec
+                        .par_iter()
+                        .map(|subprogram| {
+                            // Witness stream may not be called inside parallel contexts to
+                            // avoid nondeterminism.
+                            Self::execute_raw(env, subprogram, root_program, None)
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    // Merge the records at once, so that each event vector is grown only once.
+                    state.record.append_all(&mut records);
                 }
             }
         }
@@ -612,12 +715,18 @@ where
 
     /// Run the program.
     pub fn run(&mut self) -> Result<(), RuntimeError<F, EF>> {
+        // Report accesses past the end of the memory with their instruction, instead of panicking
+        // in the middle of the execution.
+        if let Some(err) = self.program.out_of_bounds_accesses(self.memory.len()).next() {
+            return Err(err.into());
+        }
         let record = unsafe {
             Self::execute_raw(
                 &ExecEnv {
                     memory: &self.memory,
                     perm: self.perm.as_ref().unwrap(),
                     debug_stdout: &Mutex::new(&mut self.debug_stdout),
+                    trace: self.trace_execution,
                 },
                 &self.program.inner,
                 &self.program,
@@ -693,18 +802,20 @@ struct ExecEnv<'a, 'b, F, Diffusion> {
     pub memory: &'a MemVec<F>,
     pub perm: &'a Perm<F, Diffusion>,
     pub debug_stdout: &'a Mutex<dyn Write + Send + 'b>,
+    pub trace: bool,
 }
 
 impl<F, Diffusion> Clone for ExecEnv<'_, '_, F, Diffusion> {
     fn clone(&self) -> Self {
-        let Self { memory, perm, debug_stdout } = self;
-        Self { memory, perm, debug_stdout }
+        let Self { memory, perm, debug_stdout, trace } = self;
+        Self { memory, perm, debug_stdout, trace: *trace }
     }
 
     fn clone_from(&mut self, source: &Self) {
-        let Self { memory, perm, debug_stdout } = self;
+        let Self { memory, perm, debug_stdout, trace } = self;
         memory.clone_from(&source.memory);
         perm.clone_from(&source.perm);
         debug_stdout.clone_from(&source.debug_stdout);
+        trace.clone_from(&source.trace);
     }
 }
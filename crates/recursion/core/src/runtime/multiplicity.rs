@@ -0,0 +1,172 @@
+use std::fmt::Debug;
+
+use hashbrown::HashMap;
+use p3_field::PrimeField32;
+use thiserror::Error;
+
+use crate::*;
+
+/// An address whose reads do not match the multiplicity declared by its writer.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error(
+    "instruction {instr_idx} ({instr}) writes address {addr:?} with multiplicity {expected}, but it \
+     is read {actual} times"
+)]
+pub struct MultiplicityError<F: Debug> {
+    /// The index of the writing instruction, in program order.
+    pub instr_idx: usize,
+    /// The writing instruction, as its debug representation.
+    pub instr: String,
+    pub addr: Address<F>,
+    /// The multiplicity declared by the writing instruction.
+    pub expected: u32,
+    /// The number of times the address is read by the other instructions.
+    pub actual: u32,
+}
+
+/// The memory interactions of a program, accounted per address.
+#[derive(Debug, Clone, Default)]
+pub struct MultiplicityLedger<F> {
+    /// For each address, the index of its writer and the declared multiplicity, if it is written.
+    writes: HashMap<Address<F>, (usize, F)>,
+    /// For each address, the number of reads.
+    reads: HashMap<Address<F>, F>,
+}
+
+impl<F: PrimeField32> MultiplicityLedger<F> {
+    /// Accounts the memory interactions of every instruction of the program in a single pass.
+    pub fn new(program: &RecursionProgram<F>) -> Self {
+        let mut ledger = Self { writes: HashMap::new(), reads: HashMap::new() };
+        for (instr_idx, instruction) in program.inner.iter().enumerate() {
+            let (writes, reads) = instruction.memory_interactions();
+            for (addr, mult) in writes {
+                ledger.writes.insert(addr, (instr_idx, mult));
+            }
+            for (addr, count) in reads {
+                *ledger.reads.entry(addr).or_insert_with(F::zero) += count;
+            }
+        }
+        ledger
+    }
+
+    /// Checks that each address written by an instruction selected by `writers` is read as many
+    /// times as the instruction declares.
+    pub fn check(
+        &self,
+        program: &RecursionProgram<F>,
+        writers: impl Fn(&Instruction<F>) -> bool,
+    ) -> Result<(), MultiplicityError<F>> {
+        // Report the first offending instruction in program order.
+        match self.errors(program, writers).into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Every address written by an instruction selected by `writers` that is not read as many
+    /// times as the instruction declares, in program order.
+    pub fn errors(
+        &self,
+        program: &RecursionProgram<F>,
+        writers: impl Fn(&Instruction<F>) -> bool,
+    ) -> Vec<MultiplicityError<F>> {
+        let instructions = program.inner.iter().collect::<Vec<_>>();
+        let mut writes = self.writes.iter().collect::<Vec<_>>();
+        // Instructions writing several addresses report them in address order.
+        writes.sort_by_key(|(addr, &(instr_idx, _))| (instr_idx, addr.0.as_canonical_u32()));
+        writes
+            .into_iter()
+            .filter_map(|(&addr, &(instr_idx, expected))| {
+                let instruction = instructions[instr_idx];
+                if !writers(instruction) {
+                    return None;
+                }
+                let actual = self.reads.get(&addr).copied().unwrap_or_else(F::zero);
+                (actual != expected).then(|| MultiplicityError {
+                    instr_idx,
+                    instr: format!("{instruction:?}"),
+                    addr,
+                    expected: expected.as_canonical_u32(),
+                    actual: actual.as_canonical_u32(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Panics with a diagnostic if an address written by an instruction selected by `writers` is not
+/// read exactly as many times as the instruction declares.
+///
+/// This only exists in debug builds with the `debug` feature, so that mismatched multiplicities in
+/// hand-written programs are reported by the chip generating the trace, instead of as a lookup
+/// imbalance when proving.
+#[cfg(all(debug_assertions, feature = "debug"))]
+pub(crate) fn debug_check_multiplicities<F: PrimeField32>(
+    chip: &str,
+    program: &RecursionProgram<F>,
+    writers: impl Fn(&Instruction<F>) -> bool,
+) {
+    if let Err(err) = MultiplicityLedger::new(program).check(program, writers) {
+        panic!("{chip}: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+
+    use super::*;
+    use crate::runtime::instruction as instr;
+
+    type F = BabyBear;
+
+    fn select_program(mult1: u32) -> RecursionProgram<F> {
+        linear_program(vec![
+            instr::mem(MemAccessKind::Write, 1, 0, 1),
+            instr::mem(MemAccessKind::Write, 1, 1, 3),
+            instr::mem(MemAccessKind::Write, 1, 2, 5),
+            instr::select(mult1, 1, 0, 3, 4, 1, 2),
+            instr::mem(MemAccessKind::Read, 1, 3, 5),
+            instr::mem(MemAccessKind::Read, 1, 4, 3),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn balanced_multiplicities() {
+        let program = select_program(1);
+        let ledger = MultiplicityLedger::new(&program);
+        assert_eq!(ledger.check(&program, |_| true), Ok(()));
+    }
+
+    #[test]
+    fn wrong_select_multiplicity() {
+        let program = select_program(2);
+        let ledger = MultiplicityLedger::new(&program);
+        let err = ledger
+            .check(&program, |instruction| matches!(instruction, Instruction::Select(_)))
+            .unwrap_err();
+        assert_eq!(
+            (err.instr_idx, err.addr, err.expected, err.actual),
+            (3, Address(F::from_canonical_u32(3)), 2, 1)
+        );
+        assert!(err.to_string().starts_with("instruction 3 (Select("));
+
+        // Instructions not selected as writers are not checked.
+        assert_eq!(
+            ledger.check(&program, |instruction| matches!(instruction, Instruction::Mem(_))),
+            Ok(())
+        );
+    }
+
+    #[test]
+    #[cfg(all(debug_assertions, feature = "debug"))]
+    #[should_panic(expected = "Select: instruction 3")]
+    fn debug_check_wrong_select_multiplicity() {
+        let program = select_program(2);
+        debug_check_multiplicities("Select", &program, |instruction| {
+            matches!(instruction, Instruction::Select(_))
+        });
+    }
+}
@@ -1,10 +1,11 @@
 use crate::*;
-use p3_field::Field;
+use machine::RecursionAir;
+use p3_field::{extension::BinomiallyExtendable, Field, PrimeField32};
 use serde::{Deserialize, Serialize};
-use shape::RecursionShape;
+use shape::{RecursionShape, ShapeError};
 use sp1_stark::air::{MachineAir, MachineProgram};
 use sp1_stark::septic_digest::SepticDigest;
-use std::ops::Deref;
+use std::{ops::Deref, sync::OnceLock};
 
 pub use basic_block::BasicBlock;
 pub use raw::RawProgram;
@@ -12,8 +13,14 @@ pub use seq_block::SeqBlock;
 
 /// A well-formed recursion program. See [`Self::new_unchecked`] for guaranteed (safety) invariants.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[repr(transparent)]
-pub struct RecursionProgram<F>(RootProgram<F>);
+#[serde(transparent)]
+pub struct RecursionProgram<F>(
+    RootProgram<F>,
+    /// The instruction index, computed on first use. The instructions of the program cannot be
+    /// modified, so it never goes out of date.
+    #[serde(skip)]
+    OnceLock<InstructionIndex>,
+);
 
 impl<F> RecursionProgram<F> {
     /// # Safety
@@ -34,7 +41,7 @@ impl<F> RecursionProgram<F> {
     ///         - `O` is the set of final instructions in the last `SeqBlock` of the enclosed `RawProgram`.
     ///     - For consecutive `SeqBlock`s, each element of the first one's `O` happens before the second one's `I`.
     pub unsafe fn new_unchecked(program: RootProgram<F>) -> Self {
-        Self(program)
+        Self(program, OnceLock::new())
     }
 
     pub fn into_inner(self) -> RootProgram<F> {
@@ -44,6 +51,203 @@ impl<F> RecursionProgram<F> {
     pub fn shape_mut(&mut self) -> &mut Option<RecursionShape> {
         &mut self.0.shape
     }
+
+    /// The positions of the instructions proven by each chip, bucketed in a single pass over the
+    /// program the first time it is called.
+    pub fn instruction_index(&self) -> &InstructionIndex {
+        self.1.get_or_init(|| InstructionIndex::new(&self.0.inner))
+    }
+
+    /// Whether the program has no instructions at the given positions of the instruction index,
+    /// so that their chip can be pruned from its proofs.
+    ///
+    /// Programs with a fixed shape never have prunable chips, since their proofs must all have the
+    /// same chips.
+    pub fn is_prunable(
+        &self,
+        bucket: impl FnOnce(&InstructionIndex) -> &Vec<InstructionPosition>,
+    ) -> bool {
+        self.0.shape.is_none() && bucket(self.instruction_index()).is_empty()
+    }
+
+    /// The instructions at the given positions of the instruction index, in program order.
+    fn indexed_instrs<'a, T: ?Sized>(
+        &'a self,
+        bucket: impl FnOnce(&'a InstructionIndex) -> &'a Vec<InstructionPosition>,
+        extract: impl Fn(&'a Instruction<F>) -> Option<&'a T>,
+    ) -> Vec<&'a T> {
+        let blocks = self.0.inner.basic_blocks();
+        bucket(self.instruction_index())
+            .iter()
+            .map(|&InstructionPosition { block, offset }| {
+                extract(&blocks[block].instrs[offset])
+                    .expect("the instruction index does not match the program")
+            })
+            .collect()
+    }
+
+    pub fn base_alu_instrs(&self) -> Vec<&BaseAluInstr<F>> {
+        self.indexed_instrs(
+            |index| &index.base_alu,
+            |instruction| match instruction {
+                Instruction::BaseAlu(instr) => Some(instr),
+                _ => None,
+            },
+        )
+    }
+
+    pub fn ext_alu_instrs(&self) -> Vec<&ExtAluInstr<F>> {
+        self.indexed_instrs(
+            |index| &index.ext_alu,
+            |instruction| match instruction {
+                Instruction::ExtAlu(instr) => Some(instr),
+                _ => None,
+            },
+        )
+    }
+
+    pub fn poseidon2_instrs(&self) -> Vec<&Poseidon2Instr<F>> {
+        self.indexed_instrs(
+            |index| &index.poseidon2,
+            |instruction| match instruction {
+                Instruction::Poseidon2(instr) => Some(instr.as_ref()),
+                _ => None,
+            },
+        )
+    }
+
+    pub fn select_instrs(&self) -> Vec<&SelectInstr<F>> {
+        self.indexed_instrs(
+            |index| &index.select,
+            |instruction| match instruction {
+                Instruction::Select(instr) => Some(instr),
+                _ => None,
+            },
+        )
+    }
+
+    pub fn fma_instrs(&self) -> Vec<&FmaInstr<F>> {
+        self.indexed_instrs(
+            |index| &index.fma,
+            |instruction| match instruction {
+                Instruction::Fma(instr) => Some(instr),
+                _ => None,
+            },
+        )
+    }
+
+    pub fn range_check_instrs(&self) -> Vec<&RangeCheckInstr<F>> {
+        self.indexed_instrs(
+            |index| &index.range_check,
+            |instruction| match instruction {
+                Instruction::RangeCheck(instr) => Some(instr),
+                _ => None,
+            },
+        )
+    }
+
+    pub fn exp_reverse_bits_len_instrs(&self) -> Vec<&ExpReverseBitsInstr<F>> {
+        self.indexed_instrs(
+            |index| &index.exp_reverse_bits_len,
+            |instruction| match instruction {
+                Instruction::ExpReverseBitsLen(instr) => Some(instr),
+                _ => None,
+            },
+        )
+    }
+
+    pub fn fri_fold_instrs(&self) -> Vec<&FriFoldInstr<F>> {
+        self.indexed_instrs(
+            |index| &index.fri_fold,
+            |instruction| match instruction {
+                Instruction::FriFold(instr) => Some(instr.as_ref()),
+                _ => None,
+            },
+        )
+    }
+
+    pub fn batch_fri_instrs(&self) -> Vec<&BatchFRIInstr<F>> {
+        self.indexed_instrs(
+            |index| &index.batch_fri,
+            |instruction| match instruction {
+                Instruction::BatchFRI(instr) => Some(instr.as_ref()),
+                _ => None,
+            },
+        )
+    }
+
+    pub fn commit_pv_hash_instrs(&self) -> Vec<&CommitPublicValuesInstr<F>> {
+        self.indexed_instrs(
+            |index| &index.commit_pv_hash,
+            |instruction| match instruction {
+                Instruction::CommitPublicValues(instr) => Some(instr.as_ref()),
+                _ => None,
+            },
+        )
+    }
+
+    pub fn commit_extra_pv_instrs(&self) -> Vec<&CommitExtraPublicValuesInstr<F>> {
+        self.indexed_instrs(
+            |index| &index.commit_extra_pv,
+            |instruction| match instruction {
+                Instruction::CommitExtraPublicValues(instr) => Some(instr.as_ref()),
+                _ => None,
+            },
+        )
+    }
+}
+
+/// The position of an instruction: the index of its basic block, in the order given by
+/// [`RawProgram::basic_blocks`], and its offset in the block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstructionPosition {
+    pub block: usize,
+    pub offset: usize,
+}
+
+/// The positions of the instructions of a program, bucketed by the chip whose preprocessed trace
+/// they make up, in program order.
+///
+/// Positions are stored instead of references so that the index can be cached on the program.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstructionIndex {
+    pub base_alu: Vec<InstructionPosition>,
+    pub ext_alu: Vec<InstructionPosition>,
+    pub poseidon2: Vec<InstructionPosition>,
+    pub select: Vec<InstructionPosition>,
+    pub fma: Vec<InstructionPosition>,
+    pub range_check: Vec<InstructionPosition>,
+    pub exp_reverse_bits_len: Vec<InstructionPosition>,
+    pub fri_fold: Vec<InstructionPosition>,
+    pub batch_fri: Vec<InstructionPosition>,
+    pub commit_pv_hash: Vec<InstructionPosition>,
+    pub commit_extra_pv: Vec<InstructionPosition>,
+}
+
+impl InstructionIndex {
+    fn new<F>(program: &RawProgram<Instruction<F>>) -> Self {
+        let mut index = Self::default();
+        for (block, basic_block) in program.basic_blocks().into_iter().enumerate() {
+            for (offset, instruction) in basic_block.instrs.iter().enumerate() {
+                let bucket = match instruction {
+                    Instruction::BaseAlu(_) => &mut index.base_alu,
+                    Instruction::ExtAlu(_) => &mut index.ext_alu,
+                    Instruction::Poseidon2(_) => &mut index.poseidon2,
+                    Instruction::Select(_) => &mut index.select,
+                    Instruction::Fma(_) => &mut index.fma,
+                    Instruction::RangeCheck(_) => &mut index.range_check,
+                    Instruction::ExpReverseBitsLen(_) => &mut index.exp_reverse_bits_len,
+                    Instruction::FriFold(_) => &mut index.fri_fold,
+                    Instruction::BatchFRI(_) => &mut index.batch_fri,
+                    Instruction::CommitPublicValues(_) => &mut index.commit_pv_hash,
+                    Instruction::CommitExtraPublicValues(_) => &mut index.commit_extra_pv,
+                    _ => continue,
+                };
+                bucket.push(InstructionPosition { block, offset });
+            }
+        }
+        index
+    }
 }
 
 impl<F> Default for RecursionProgram<F> {
@@ -69,6 +273,11 @@ impl<F: Field> MachineProgram<F> for RecursionProgram<F> {
     fn initial_global_cumulative_sum(&self) -> SepticDigest<F> {
         SepticDigest::<F>::zero()
     }
+
+    fn preprocessed_digest(&self) -> Option<[u8; 32]> {
+        // The shape is part of the program, so fixing different trace heights changes the digest.
+        Some(sp1_primitives::io::hash_serialized(&self.0))
+    }
 }
 
 impl<F: Field> RecursionProgram<F> {
@@ -87,6 +296,30 @@ impl<F: Field> RecursionProgram<F> {
     }
 }
 
+impl<F: PrimeField32 + BinomiallyExtendable<D>> RecursionProgram<F> {
+    /// Fixes the trace heights of the chips of the recursion machine with the given degree to
+    /// those of `shape`.
+    ///
+    /// Chips that the shape leaves out get their height in [`shape::shape_of`]. Fails if the shape
+    /// names a chip that is not part of the machine, or if a chip has more rows than the shape
+    /// allows.
+    pub fn with_shape<const DEGREE: usize>(
+        mut self,
+        shape: RecursionShape,
+    ) -> Result<Self, ShapeError> {
+        let mut fixed = shape::shape_of::<F, DEGREE>(&self);
+        for (chip, log2_rows) in shape.inner {
+            match fixed.inner.get_mut(&chip) {
+                Some(fixed_log2_rows) => *fixed_log2_rows = log2_rows,
+                None => return Err(ShapeError::UnknownChip(chip)),
+            }
+        }
+        fixed.check_heights(&RecursionAir::<F, DEGREE>::heights(&self))?;
+        self.0.shape = Some(fixed);
+        Ok(self)
+    }
+}
+
 #[cfg(any(test, feature = "program_validation"))]
 pub use validation::*;
 
@@ -248,6 +481,24 @@ pub mod raw {
         pub fn iter(&self) -> impl Iterator<Item = &'_ T> {
             self.seq_blocks.iter().flatten()
         }
+
+        /// The basic blocks of the program, in the order in which [`Self::iter`] visits them.
+        pub fn basic_blocks(&self) -> Vec<&BasicBlock<T>> {
+            let mut blocks = Vec::new();
+            self.push_basic_blocks(&mut blocks);
+            blocks
+        }
+
+        fn push_basic_blocks<'a>(&'a self, blocks: &mut Vec<&'a BasicBlock<T>>) {
+            for seq_block in &self.seq_blocks {
+                match seq_block {
+                    SeqBlock::Basic(basic_block) => blocks.push(basic_block),
+                    SeqBlock::Parallel(programs) => {
+                        programs.iter().for_each(|program| program.push_basic_blocks(blocks))
+                    }
+                }
+            }
+        }
         pub fn iter_mut(&mut self) -> impl Iterator<Item = &'_ mut T> {
             self.seq_blocks.iter_mut().flatten()
         }
@@ -415,3 +666,87 @@ pub mod basic_block {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::iter::zip;
+
+    use p3_baby_bear::BabyBear;
+
+    use super::*;
+    use crate::chips::test_fixtures;
+
+    /// A program with the fixture instructions spread across nested parallel blocks.
+    fn nested_program(instrs: Vec<Instruction<BabyBear>>) -> RecursionProgram<BabyBear> {
+        let mut chunks = instrs
+            .chunks(instrs.len().div_ceil(5))
+            .map(|chunk| SeqBlock::Basic(BasicBlock { instrs: chunk.to_vec() }));
+        let mut next = || chunks.next().unwrap_or_else(|| SeqBlock::Basic(BasicBlock::default()));
+        let first = next();
+        let (second, third, fourth, fifth) = (next(), next(), next(), next());
+        let inner = RawProgram {
+            seq_blocks: vec![
+                first,
+                SeqBlock::Parallel(vec![
+                    RawProgram { seq_blocks: vec![second] },
+                    RawProgram {
+                        seq_blocks: vec![
+                            third,
+                            SeqBlock::Parallel(vec![RawProgram { seq_blocks: vec![fourth] }]),
+                        ],
+                    },
+                ]),
+                fifth,
+            ],
+        };
+        // SAFETY: The program is only used to extract instructions.
+        unsafe {
+            RecursionProgram::new_unchecked(RootProgram { inner, total_memory: 0, shape: None })
+        }
+    }
+
+    /// Extracts the instructions of a chip with a full pass over the program.
+    fn extract<'a, T: ?Sized>(
+        program: &'a RecursionProgram<BabyBear>,
+        extract: impl Fn(&'a Instruction<BabyBear>) -> Option<&'a T>,
+    ) -> Vec<&'a T> {
+        program.inner.iter().filter_map(extract).collect()
+    }
+
+    fn assert_index_matches_extraction(program: &RecursionProgram<BabyBear>) {
+        macro_rules! assert_matches_extraction {
+            ($instrs:ident, $variant:ident $(, $as_ref:ident)?) => {
+                let expected = extract(program, |instruction| match instruction {
+                    Instruction::$variant(instr) => Some(instr$(.$as_ref())?),
+                    _ => None,
+                });
+                let actual = program.$instrs();
+                assert_eq!(actual.len(), expected.len());
+                assert!(zip(actual, expected).all(|(a, b)| std::ptr::eq(a, b)));
+            };
+        }
+        assert_matches_extraction!(base_alu_instrs, BaseAlu);
+        assert_matches_extraction!(ext_alu_instrs, ExtAlu);
+        assert_matches_extraction!(poseidon2_instrs, Poseidon2, as_ref);
+        assert_matches_extraction!(select_instrs, Select);
+        assert_matches_extraction!(fma_instrs, Fma);
+        assert_matches_extraction!(range_check_instrs, RangeCheck);
+        assert_matches_extraction!(exp_reverse_bits_len_instrs, ExpReverseBitsLen);
+        assert_matches_extraction!(fri_fold_instrs, FriFold, as_ref);
+        assert_matches_extraction!(batch_fri_instrs, BatchFRI, as_ref);
+        assert_matches_extraction!(commit_pv_hash_instrs, CommitPublicValues, as_ref);
+        assert_matches_extraction!(commit_extra_pv_instrs, CommitExtraPublicValues, as_ref);
+    }
+
+    #[test]
+    fn instruction_index() {
+        assert_index_matches_extraction(&test_fixtures::unvalidated_program());
+        assert_index_matches_extraction(&nested_program(test_fixtures::instructions()));
+
+        // The index is computed once, and carried over by clones.
+        let program = nested_program(test_fixtures::instructions());
+        let index = program.instruction_index() as *const InstructionIndex;
+        assert!(std::ptr::eq(program.instruction_index(), index));
+        assert_eq!(program.clone().instruction_index(), program.instruction_index());
+    }
+}
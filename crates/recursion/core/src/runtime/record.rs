@@ -1,12 +1,29 @@
-use std::{array, ops::Add, sync::Arc};
+use std::{array, mem, ops::Add, sync::Arc};
 
-use p3_field::{AbstractField, Field, PrimeField32};
+use p3_field::{extension::BinomiallyExtendable, AbstractField, Field, PrimeField32};
 use sp1_stark::{air::MachineAir, MachineRecord, SP1CoreOpts, PROOF_MAX_NUM_PVS};
 
 use super::{
-    machine::RecursionAirEventCount, BaseAluEvent, BatchFRIEvent, CommitPublicValuesEvent,
-    ExpReverseBitsEvent, ExtAluEvent, FriFoldEvent, MemEvent, Poseidon2Event, RecursionProgram,
-    RecursionPublicValues, SelectEvent,
+    machine::{RecursionAir, RecursionAirEventCount},
+    shape::ShapeOverflowError,
+    BaseAluEvent, BatchFRIEvent, CommitPublicValuesEvent, EventColumns, ExpReverseBitsEvent,
+    ExtAluEvent, FmaEvent, FriFoldEvent, MemEvent, Poseidon2Event, RangeCheckEvent,
+    RecursionProgram, RecursionPublicValues, SegmentedColumns, SegmentedVec, SelectEvent, D,
+};
+use crate::chips::{
+    alu_base::{BaseAluChip, NUM_BASE_ALU_ENTRIES_PER_ROW},
+    alu_ext::{ExtAluChip, NUM_EXT_ALU_ENTRIES_PER_ROW},
+    batch_fri::BatchFRIChip,
+    exp_reverse_bits::ExpReverseBitsLenChip,
+    fma::FmaChip,
+    fri_fold::FriFoldChip,
+    mem::{
+        constant::NUM_CONST_MEM_ENTRIES_PER_ROW, variable::NUM_VAR_MEM_ENTRIES_PER_ROW,
+        MemoryConstChip, MemoryVarChip,
+    },
+    poseidon2_wide::Poseidon2WideChip,
+    range_check::RangeCheckChip,
+    select::{SelectChip, NUM_SELECT_ENTRIES_PER_ROW},
 };
 
 #[derive(Clone, Default, Debug)]
@@ -15,15 +32,22 @@ pub struct ExecutionRecord<F> {
     /// The index of the shard.
     pub index: u32,
 
-    pub base_alu_events: Vec<BaseAluEvent<F>>,
-    pub ext_alu_events: Vec<ExtAluEvent<F>>,
+    /// The events of the base field ALU, extension field ALU, variable memory and select chips are
+    /// stored column-wise, so that their traces are generated by copying columns.
+    pub base_alu_events: EventColumns<F, BaseAluEvent<F>>,
+    pub ext_alu_events: EventColumns<F, ExtAluEvent<F>>,
     pub mem_const_count: usize,
-    pub mem_var_events: Vec<MemEvent<F>>,
+    pub mem_var_events: EventColumns<F, MemEvent<F>>,
     /// The public values.
     pub public_values: RecursionPublicValues<F>,
+    /// The public values committed after the digest of the public values, if the program commits
+    /// any.
+    pub extra_public_values: Vec<F>,
 
     pub poseidon2_events: Vec<Poseidon2Event<F>>,
-    pub select_events: Vec<SelectEvent<F>>,
+    pub select_events: EventColumns<F, SelectEvent<F>>,
+    pub fma_events: Vec<FmaEvent<F>>,
+    pub range_check_events: Vec<RangeCheckEvent<F>>,
     pub exp_reverse_bits_len_events: Vec<ExpReverseBitsEvent<F>>,
     pub fri_fold_events: Vec<FriFoldEvent<F>>,
     pub batch_fri_events: Vec<BatchFRIEvent<F>>,
@@ -41,6 +65,8 @@ impl<F: PrimeField32> MachineRecord for ExecutionRecord<F> {
             ("mem_var_events", self.mem_var_events.len()),
             ("poseidon2_events", self.poseidon2_events.len()),
             ("select_events", self.select_events.len()),
+            ("fma_events", self.fma_events.len()),
+            ("range_check_events", self.range_check_events.len()),
             ("exp_reverse_bits_len_events", self.exp_reverse_bits_len_events.len()),
             ("fri_fold_events", self.fri_fold_events.len()),
             ("batch_fri_events", self.batch_fri_events.len()),
@@ -61,8 +87,11 @@ impl<F: PrimeField32> MachineRecord for ExecutionRecord<F> {
             mem_const_count,
             mem_var_events,
             public_values: _,
+            extra_public_values,
             poseidon2_events,
             select_events,
+            fma_events,
+            range_check_events,
             exp_reverse_bits_len_events,
             fri_fold_events,
             batch_fri_events,
@@ -74,10 +103,15 @@ impl<F: PrimeField32> MachineRecord for ExecutionRecord<F> {
         mem_var_events.append(&mut other.mem_var_events);
         poseidon2_events.append(&mut other.poseidon2_events);
         select_events.append(&mut other.select_events);
+        fma_events.append(&mut other.fma_events);
+        range_check_events.append(&mut other.range_check_events);
         exp_reverse_bits_len_events.append(&mut other.exp_reverse_bits_len_events);
         fri_fold_events.append(&mut other.fri_fold_events);
         batch_fri_events.append(&mut other.batch_fri_events);
         commit_pv_hash_events.append(&mut other.commit_pv_hash_events);
+        if extra_public_values.is_empty() {
+            *extra_public_values = std::mem::take(&mut other.extra_public_values);
+        }
     }
 
     fn public_values<T: AbstractField>(&self) -> Vec<T> {
@@ -91,7 +125,63 @@ impl<F: PrimeField32> MachineRecord for ExecutionRecord<F> {
             }
         });
 
-        ret.to_vec()
+        // The extra public values follow the digest, which ends the fixed layout.
+        let extra =
+            self.extra_public_values.iter().map(|pv| T::from_canonical_u32(pv.as_canonical_u32()));
+        ret.into_iter().chain(extra).collect()
+    }
+}
+
+impl<F: Copy> ExecutionRecord<F> {
+    /// Reserves the exact capacity needed to append the events of all of `others`.
+    fn reserve_for_append(&mut self, others: &[Self]) {
+        fn total<F>(others: &[ExecutionRecord<F>], len: fn(&ExecutionRecord<F>) -> usize) -> usize {
+            others.iter().map(len).sum()
+        }
+
+        // Exhaustive destructuring for refactoring purposes.
+        let Self {
+            program: _,
+            index: _,
+            base_alu_events,
+            ext_alu_events,
+            mem_const_count: _,
+            mem_var_events,
+            public_values: _,
+            extra_public_values: _,
+            poseidon2_events,
+            select_events,
+            fma_events,
+            range_check_events,
+            exp_reverse_bits_len_events,
+            fri_fold_events,
+            batch_fri_events,
+            commit_pv_hash_events,
+        } = self;
+        base_alu_events.reserve_exact(total(others, |r| r.base_alu_events.len()));
+        ext_alu_events.reserve_exact(total(others, |r| r.ext_alu_events.len()));
+        mem_var_events.reserve_exact(total(others, |r| r.mem_var_events.len()));
+        poseidon2_events.reserve_exact(total(others, |r| r.poseidon2_events.len()));
+        select_events.reserve_exact(total(others, |r| r.select_events.len()));
+        fma_events.reserve_exact(total(others, |r| r.fma_events.len()));
+        range_check_events.reserve_exact(total(others, |r| r.range_check_events.len()));
+        exp_reverse_bits_len_events
+            .reserve_exact(total(others, |r| r.exp_reverse_bits_len_events.len()));
+        fri_fold_events.reserve_exact(total(others, |r| r.fri_fold_events.len()));
+        batch_fri_events.reserve_exact(total(others, |r| r.batch_fri_events.len()));
+        commit_pv_hash_events.reserve_exact(total(others, |r| r.commit_pv_hash_events.len()));
+    }
+}
+
+impl<F: PrimeField32> ExecutionRecord<F> {
+    /// Appends the events of all of `others`, in order.
+    ///
+    /// Unlike appending the records one at a time, every event vector is grown at most once.
+    pub fn append_all(&mut self, others: &mut [Self]) {
+        self.reserve_for_append(others);
+        for other in others {
+            self.append(other);
+        }
     }
 }
 
@@ -110,5 +200,120 @@ impl<F: Field> ExecutionRecord<F> {
         self.ext_alu_events.reserve(event_counts.ext_alu_events);
         self.exp_reverse_bits_len_events.reserve(event_counts.exp_reverse_bits_len_events);
         self.select_events.reserve(event_counts.select_events);
+        self.fma_events.reserve(event_counts.fma_events);
+        self.range_check_events.reserve(event_counts.range_check_events);
+    }
+}
+
+impl<F: PrimeField32 + BinomiallyExtendable<D>> ExecutionRecord<F> {
+    /// The number of rows the events of the record take in the trace of each chip, before padding,
+    /// keyed by the chip names of the recursion machine with the given degree.
+    ///
+    /// This mirrors [`RecursionAir::heights`], which computes the same table from a program.
+    pub fn chip_heights<const DEGREE: usize>(&self) -> Vec<(String, usize)> {
+        [
+            (
+                RecursionAir::<F, DEGREE>::MemoryConst(MemoryConstChip::default()),
+                self.mem_const_count.div_ceil(NUM_CONST_MEM_ENTRIES_PER_ROW),
+            ),
+            (
+                RecursionAir::MemoryVar(MemoryVarChip::default()),
+                self.mem_var_events.len().div_ceil(NUM_VAR_MEM_ENTRIES_PER_ROW),
+            ),
+            (
+                RecursionAir::BaseAlu(BaseAluChip::default()),
+                self.base_alu_events.len().div_ceil(NUM_BASE_ALU_ENTRIES_PER_ROW),
+            ),
+            (
+                RecursionAir::ExtAlu(ExtAluChip),
+                self.ext_alu_events.len().div_ceil(NUM_EXT_ALU_ENTRIES_PER_ROW),
+            ),
+            (
+                RecursionAir::Poseidon2Wide(Poseidon2WideChip::<DEGREE>::default()),
+                self.poseidon2_events.len(),
+            ),
+            (RecursionAir::FriFold(FriFoldChip::<DEGREE>::default()), self.fri_fold_events.len()),
+            (RecursionAir::BatchFRI(BatchFRIChip::<DEGREE>), self.batch_fri_events.len()),
+            (
+                RecursionAir::Select(SelectChip),
+                self.select_events.len().div_ceil(NUM_SELECT_ENTRIES_PER_ROW),
+            ),
+            (RecursionAir::Fma(FmaChip), self.fma_events.len()),
+            (RecursionAir::RangeCheck(RangeCheckChip), self.range_check_events.len()),
+            (
+                RecursionAir::ExpReverseBitsLen(ExpReverseBitsLenChip::<DEGREE>),
+                self.exp_reverse_bits_len_events.len(),
+            ),
+        ]
+        .map(|(chip, rows)| (chip.name(), rows))
+        .to_vec()
+    }
+
+    /// Checks that the events of the record fit in the trace heights fixed by the shape of its
+    /// program, if it has one.
+    pub fn check_shape<const DEGREE: usize>(&self) -> Result<(), ShapeOverflowError> {
+        match &self.program.shape {
+            Some(shape) => shape.check_heights(&self.chip_heights::<DEGREE>()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Execution records merged by moving their largest event vectors as segments.
+///
+/// Appending a record moves the base ALU, extension ALU, variable memory, Poseidon2 and select
+/// events, or their columns, without copying them. The other events are appended to
+/// [`SegmentedRecord::record`]. Once all the records are merged, [`SegmentedRecord::into_record`]
+/// concatenates the segments into an [`ExecutionRecord`] whose traces are identical to those of the
+/// records appended in order.
+#[derive(Clone, Debug, Default)]
+pub struct SegmentedRecord<F> {
+    pub record: ExecutionRecord<F>,
+    pub base_alu_events: SegmentedColumns<F, BaseAluEvent<F>>,
+    pub ext_alu_events: SegmentedColumns<F, ExtAluEvent<F>>,
+    pub mem_var_events: SegmentedColumns<F, MemEvent<F>>,
+    pub poseidon2_events: SegmentedVec<Poseidon2Event<F>>,
+    pub select_events: SegmentedColumns<F, SelectEvent<F>>,
+}
+
+impl<F: PrimeField32> SegmentedRecord<F> {
+    /// Moves the events of `other` after the events already in the record, leaving `other` empty.
+    pub fn append(&mut self, other: &mut ExecutionRecord<F>) {
+        self.base_alu_events.push_segment(mem::take(&mut other.base_alu_events));
+        self.ext_alu_events.push_segment(mem::take(&mut other.ext_alu_events));
+        self.mem_var_events.push_segment(mem::take(&mut other.mem_var_events));
+        self.poseidon2_events.push_segment(mem::take(&mut other.poseidon2_events));
+        self.select_events.push_segment(mem::take(&mut other.select_events));
+        self.record.append(other);
+    }
+
+    pub fn into_record(self) -> ExecutionRecord<F> {
+        let Self {
+            mut record,
+            base_alu_events,
+            ext_alu_events,
+            mem_var_events,
+            poseidon2_events,
+            select_events,
+        } = self;
+        record.base_alu_events = base_alu_events.into_columns();
+        record.ext_alu_events = ext_alu_events.into_columns();
+        record.mem_var_events = mem_var_events.into_columns();
+        record.poseidon2_events = poseidon2_events.into_vec();
+        record.select_events = select_events.into_columns();
+        record
+    }
+}
+
+impl<F: PrimeField32> From<ExecutionRecord<F>> for SegmentedRecord<F> {
+    fn from(mut record: ExecutionRecord<F>) -> Self {
+        Self {
+            base_alu_events: mem::take(&mut record.base_alu_events).into(),
+            ext_alu_events: mem::take(&mut record.ext_alu_events).into(),
+            mem_var_events: mem::take(&mut record.mem_var_events).into(),
+            poseidon2_events: mem::take(&mut record.poseidon2_events).into(),
+            select_events: mem::take(&mut record.select_events).into(),
+            record,
+        }
     }
 }
@@ -0,0 +1,176 @@
+use std::{iter::Flatten, mem, slice};
+
+/// A vector stored as a list of segments.
+///
+/// Appending another segmented vector moves its segments instead of its elements, so merging many
+/// vectors takes time proportional to the number of segments. The elements are copied once, by
+/// [`SegmentedVec::into_vec`], into an allocation of the exact total length.
+#[derive(Clone, Debug)]
+pub struct SegmentedVec<T> {
+    segments: Vec<Vec<T>>,
+    len: usize,
+}
+
+impl<T> Default for SegmentedVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SegmentedVec<T> {
+    pub const fn new() -> Self {
+        Self { segments: Vec::new(), len: 0 }
+    }
+
+    /// The total number of elements in all the segments.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn segments(&self) -> &[Vec<T>] {
+        &self.segments
+    }
+
+    /// Adds a segment after the existing ones. Empty segments are dropped.
+    pub fn push_segment(&mut self, segment: Vec<T>) {
+        if !segment.is_empty() {
+            self.len += segment.len();
+            self.segments.push(segment);
+        }
+    }
+
+    /// Moves the segments of `other` after the segments of `self`, leaving `other` empty.
+    pub fn append(&mut self, other: &mut Self) {
+        self.len += mem::take(&mut other.len);
+        self.segments.append(&mut other.segments);
+    }
+
+    /// Iterates over the elements of all the segments, in order.
+    pub fn iter(&self) -> Flatten<slice::Iter<'_, Vec<T>>> {
+        self.segments.iter().flatten()
+    }
+
+    /// Concatenates the segments. A single segment is returned without copying.
+    pub fn into_vec(mut self) -> Vec<T> {
+        if self.segments.len() == 1 {
+            return self.segments.pop().unwrap();
+        }
+        let mut vec = Vec::with_capacity(self.len);
+        for segment in self.segments {
+            vec.extend(segment);
+        }
+        vec
+    }
+}
+
+impl<T> From<Vec<T>> for SegmentedVec<T> {
+    fn from(vec: Vec<T>) -> Self {
+        let mut segmented = Self::new();
+        segmented.push_segment(vec);
+        segmented
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SegmentedVec<T> {
+    type Item = &'a T;
+    type IntoIter = Flatten<slice::Iter<'a, Vec<T>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use sp1_stark::{air::MachineAir, baby_bear_poseidon2::BabyBearPoseidon2, MachineRecord};
+
+    use super::*;
+    use crate::{chips::test_fixtures, machine::RecursionAir, ExecutionRecord, SegmentedRecord};
+
+    /// Splits the events of the record in `parts` records, keeping their order.
+    fn split(record: &ExecutionRecord<BabyBear>, parts: usize) -> Vec<ExecutionRecord<BabyBear>> {
+        fn chunk<T: Clone>(events: &[T], part: usize, parts: usize) -> Vec<T> {
+            events
+                .chunks(events.len().div_ceil(parts).max(1))
+                .nth(part)
+                .unwrap_or_default()
+                .to_vec()
+        }
+        (0..parts)
+            .map(|part| ExecutionRecord {
+                program: record.program.clone(),
+                base_alu_events: chunk(&record.base_alu_events.to_vec(), part, parts).into(),
+                ext_alu_events: chunk(&record.ext_alu_events.to_vec(), part, parts).into(),
+                mem_var_events: chunk(&record.mem_var_events.to_vec(), part, parts).into(),
+                poseidon2_events: chunk(&record.poseidon2_events, part, parts),
+                select_events: chunk(&record.select_events.to_vec(), part, parts).into(),
+                fma_events: chunk(&record.fma_events, part, parts),
+                range_check_events: chunk(&record.range_check_events, part, parts),
+                exp_reverse_bits_len_events: chunk(
+                    &record.exp_reverse_bits_len_events,
+                    part,
+                    parts,
+                ),
+                fri_fold_events: chunk(&record.fri_fold_events, part, parts),
+                batch_fri_events: chunk(&record.batch_fri_events, part, parts),
+                commit_pv_hash_events: chunk(&record.commit_pv_hash_events, part, parts),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn merged_and_segmented_traces_match() {
+        let shards = split(&test_fixtures::shard(), 64);
+
+        let mut appended = ExecutionRecord::default();
+        for shard in &mut shards.clone() {
+            appended.append(shard);
+        }
+        let mut merged = ExecutionRecord::default();
+        merged.append_all(&mut shards.clone());
+        let mut segmented = SegmentedRecord::default();
+        for shard in &mut shards.clone() {
+            segmented.append(shard);
+        }
+        let segmented = segmented.into_record();
+
+        let machine =
+            RecursionAir::<BabyBear, 3>::machine_wide_with_all_chips(BabyBearPoseidon2::default());
+        for chip in machine.chips() {
+            let trace = chip.generate_trace(&appended, &mut ExecutionRecord::default());
+            assert_eq!(
+                chip.generate_trace(&merged, &mut ExecutionRecord::default()),
+                trace,
+                "{}",
+                chip.name()
+            );
+            assert_eq!(
+                chip.generate_trace(&segmented, &mut ExecutionRecord::default()),
+                trace,
+                "{}",
+                chip.name()
+            );
+        }
+    }
+
+    #[test]
+    fn append_moves_segments() {
+        let mut a = SegmentedVec::from(vec![1, 2, 3]);
+        let mut b = SegmentedVec::from(vec![4]);
+        b.push_segment(vec![]);
+        b.push_segment(vec![5, 6]);
+        a.append(&mut b);
+
+        assert!(b.is_empty());
+        assert_eq!(a.len(), 6);
+        assert_eq!(a.segments().len(), 3);
+        assert_eq!(a.iter().copied().collect::<Vec<_>>(), [1, 2, 3, 4, 5, 6]);
+        assert_eq!(a.into_vec(), [1, 2, 3, 4, 5, 6]);
+    }
+}
@@ -1,13 +1,15 @@
 #![allow(clippy::never_loop)]
 
-use std::marker::PhantomData;
+use std::{fmt, marker::PhantomData};
 
 use hashbrown::HashMap;
 
 use itertools::Itertools;
 use p3_field::{extension::BinomiallyExtendable, PrimeField32};
+use p3_util::log2_ceil_usize;
 use serde::{Deserialize, Serialize};
 use sp1_stark::{air::MachineAir, shape::OrderedShape};
+use thiserror::Error;
 
 use crate::{
     chips::{
@@ -30,9 +32,134 @@ pub struct RecursionShape {
 }
 
 impl RecursionShape {
+    /// A shape that fixes no chip heights, to be built up with [`Self::with_chip_height`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fixes the trace of the chip with the given name to `1 << log2_rows` rows.
+    #[must_use]
+    pub fn with_chip_height(mut self, chip: impl Into<String>, log2_rows: usize) -> Self {
+        self.inner.insert(chip.into(), log2_rows);
+        self
+    }
+
+    /// The log2 of the number of rows fixed for the chip with the given name, if any.
+    pub fn chip_height(&self, chip: &str) -> Option<usize> {
+        self.inner.get(chip).copied()
+    }
+
     pub fn clone_into_hash_map(&self) -> HashMap<String, usize> {
         self.inner.clone()
     }
+
+    /// Checks that chips with the given numbers of rows fit in the heights fixed by the shape.
+    ///
+    /// Chips that are not part of the shape are not checked.
+    pub fn check_heights(&self, heights: &[(String, usize)]) -> Result<(), ShapeOverflowError> {
+        let overflows = heights
+            .iter()
+            .filter_map(|(chip, rows)| {
+                let allowed_rows = 1 << *self.inner.get(chip)?;
+                (*rows > allowed_rows).then(|| ChipOverflow {
+                    chip: chip.clone(),
+                    rows: *rows,
+                    allowed_rows,
+                })
+            })
+            .collect::<Vec<_>>();
+        if overflows.is_empty() {
+            return Ok(());
+        }
+        let heights = heights
+            .iter()
+            .map(|(chip, rows)| ChipHeight {
+                chip: chip.clone(),
+                rows: *rows,
+                allowed_rows: self.inner.get(chip).map(|log2_rows| 1 << log2_rows),
+            })
+            .collect();
+        Err(ShapeOverflowError { overflows, heights })
+    }
+}
+
+/// A chip whose trace has more rows than its shape allows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChipOverflow {
+    pub chip: String,
+    pub rows: usize,
+    pub allowed_rows: usize,
+}
+
+/// The number of rows of a chip, and the number of rows the shape allows for it, if any.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChipHeight {
+    pub chip: String,
+    pub rows: usize,
+    pub allowed_rows: Option<usize>,
+}
+
+/// An error returned when the traces of some chips have more rows than fixed by a shape.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub struct ShapeOverflowError {
+    /// The chips whose traces do not fit.
+    pub overflows: Vec<ChipOverflow>,
+    /// The heights of all the chips, to make the failure diagnosable.
+    pub heights: Vec<ChipHeight>,
+}
+
+impl fmt::Display for ShapeOverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "trace heights exceed the shape:")?;
+        for ChipOverflow { chip, rows, allowed_rows } in &self.overflows {
+            write!(f, " {chip} has {rows} rows but {allowed_rows} are allowed;")?;
+        }
+        writeln!(f, " chip heights:")?;
+        for ChipHeight { chip, rows, allowed_rows } in &self.heights {
+            match allowed_rows {
+                Some(allowed_rows) => writeln!(f, "  {chip:<24} {rows:>10} / {allowed_rows}")?,
+                None => writeln!(f, "  {chip:<24} {rows:>10}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An error returned when a shape cannot be applied to a program.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ShapeError {
+    #[error("chip {0} is not part of the recursion machine")]
+    UnknownChip(String),
+    #[error(transparent)]
+    Overflow(#[from] ShapeOverflowError),
+}
+
+/// The log2 of the smallest number of rows a chip is padded to when its height is not fixed.
+const MIN_LOG2_ROWS: usize = 4;
+
+/// The smallest shape that fits the program, computed from the same instruction counts that the
+/// chips use to size their traces.
+///
+/// Each chip is given the next power of two of its height, and at least `1 << 4` rows, as when its
+/// height is not fixed.
+pub fn shape_of<F: PrimeField32 + BinomiallyExtendable<D>, const DEGREE: usize>(
+    program: &RecursionProgram<F>,
+) -> RecursionShape {
+    let public_values =
+        RecursionAir::<F, DEGREE>::PublicValues(PublicValuesChip::default()).name();
+    let inner = RecursionAir::<F, DEGREE>::heights(program)
+        .into_iter()
+        .map(|(chip, height)| {
+            // The public values chip has a fixed height, independent of the program.
+            let log2_rows = if chip == public_values {
+                PUB_VALUES_LOG_HEIGHT
+            } else {
+                log2_ceil_usize(height).max(MIN_LOG2_ROWS)
+            };
+            (chip, log2_rows)
+        })
+        .collect();
+    RecursionShape { inner }
 }
 
 impl From<HashMap<String, usize>> for RecursionShape {
@@ -75,8 +202,22 @@ impl<F: PrimeField32 + BinomiallyExtendable<D>, const DEGREE: usize>
             let shape = RecursionShape { inner: shape };
             *program.shape_mut() = Some(shape);
         } else {
-            panic!("no shape found for heights: {:?}", heights);
+            // Report the heights against the largest allowed shape, which every shape failed.
+            match self.largest_shape().check_heights(&heights) {
+                Err(err) => panic!("no shape found: {err}"),
+                Ok(()) => panic!("no shape found for heights: {:?}", heights),
+            }
+        }
+    }
+
+    /// The shape allowing, for each chip, the largest height of all the allowed shapes.
+    fn largest_shape(&self) -> RecursionShape {
+        let mut inner = HashMap::<String, usize>::new();
+        for (chip, &log2_rows) in self.allowed_shapes.iter().flatten() {
+            let entry = inner.entry(chip.clone()).or_default();
+            *entry = (*entry).max(log2_rows);
         }
+        RecursionShape { inner }
     }
 
     pub fn get_all_shape_combinations(
@@ -122,15 +263,16 @@ impl<F: PrimeField32 + BinomiallyExtendable<D>, const DEGREE: usize> Default
         // Get the names of all the recursion airs to make the shape specification more readable.
         let mem_const = RecursionAir::<F, DEGREE>::MemoryConst(MemoryConstChip::default()).name();
         let mem_var = RecursionAir::<F, DEGREE>::MemoryVar(MemoryVarChip::default()).name();
-        let base_alu = RecursionAir::<F, DEGREE>::BaseAlu(BaseAluChip).name();
+        let base_alu = RecursionAir::<F, DEGREE>::BaseAlu(BaseAluChip::default()).name();
         let ext_alu = RecursionAir::<F, DEGREE>::ExtAlu(ExtAluChip).name();
         let poseidon2_wide =
-            RecursionAir::<F, DEGREE>::Poseidon2Wide(Poseidon2WideChip::<DEGREE>).name();
+            RecursionAir::<F, DEGREE>::Poseidon2Wide(Poseidon2WideChip::<DEGREE>::default()).name();
         let batch_fri = RecursionAir::<F, DEGREE>::BatchFRI(BatchFRIChip::<DEGREE>).name();
         let select = RecursionAir::<F, DEGREE>::Select(SelectChip).name();
         let exp_reverse_bits_len =
             RecursionAir::<F, DEGREE>::ExpReverseBitsLen(ExpReverseBitsLenChip::<DEGREE>).name();
-        let public_values = RecursionAir::<F, DEGREE>::PublicValues(PublicValuesChip).name();
+        let public_values =
+            RecursionAir::<F, DEGREE>::PublicValues(PublicValuesChip::default()).name();
 
         // Specify allowed shapes.
 
@@ -5,18 +5,84 @@ use crate::{
         alu_ext::{ExtAluAccessCols, ExtAluValueCols},
         batch_fri::{BatchFRICols, BatchFRIPreprocessedCols},
         exp_reverse_bits::{ExpReverseBitsLenCols, ExpReverseBitsLenPreprocessedCols},
+        fma::{FmaCols, FmaPreprocessedCols},
         fri_fold::{FriFoldCols, FriFoldPreprocessedCols},
+        mem::{constant::MemoryConstAccessCols, MemoryAccessColsChips},
         poseidon2_skinny::columns::{preprocessed::Poseidon2PreprocessedColsSkinny, Poseidon2},
         poseidon2_wide::columns::preprocessed::Poseidon2PreprocessedColsWide,
         public_values::{PublicValuesCols, PublicValuesPreprocessedCols},
-        select::{SelectCols, SelectPreprocessedCols},
+        range_check::{RangeCheckCols, RangeCheckPreprocessedCols},
+        select::{SelectAccessCols, SelectValueCols},
     },
-    BaseAluInstr, BaseAluIo, BatchFRIEvent, BatchFRIInstrFFI, CommitPublicValuesEvent,
+    Address, BaseAluInstr, BaseAluIo, BatchFRIEvent, BatchFRIInstrFFI, CommitPublicValuesEvent,
     CommitPublicValuesInstr, ExpReverseBitsEventFFI, ExpReverseBitsInstrFFI, ExtAluInstr, ExtAluIo,
-    FriFoldEvent, FriFoldInstrFFI, Poseidon2Event, Poseidon2Instr, SelectEvent, SelectInstr,
+    FmaEvent, FmaInstr, FriFoldEvent, FriFoldInstrFFI, MemInstr, MemIo, Poseidon2Event,
+    Poseidon2Instr, RangeCheckEvent, RangeCheckInstr, SelectEvent, SelectInstr,
 };
 use p3_baby_bear::BabyBear;
 
+// The widths, in field elements, of the column structs shared with the C++ kernels. They are
+// literals so that cbindgen exports them: `include/layout.hpp` checks them against the sizes of the
+// C++ structs, and the assertions below against the sizes of the Rust structs, so that a column
+// added on one side only fails to compile instead of shifting the rows written by the kernels.
+pub const BASE_ALU_VALUE_COLS_WIDTH: usize = 3;
+pub const BASE_ALU_ACCESS_COLS_WIDTH: usize = 8;
+pub const EXT_ALU_VALUE_COLS_WIDTH: usize = 12;
+pub const EXT_ALU_ACCESS_COLS_WIDTH: usize = 8;
+pub const BATCH_FRI_COLS_WIDTH: usize = 13;
+pub const BATCH_FRI_PREPROCESSED_COLS_WIDTH: usize = 6;
+pub const EXP_REVERSE_BITS_LEN_COLS_WIDTH: usize = 7;
+pub const EXP_REVERSE_BITS_LEN_PREPROCESSED_COLS_WIDTH: usize = 10;
+pub const FRI_FOLD_COLS_WIDTH: usize = 33;
+pub const FRI_FOLD_PREPROCESSED_COLS_WIDTH: usize = 20;
+pub const PUBLIC_VALUES_COLS_WIDTH: usize = 1;
+pub const PUBLIC_VALUES_PREPROCESSED_COLS_WIDTH: usize = 10;
+pub const SELECT_VALUE_COLS_WIDTH: usize = 5;
+pub const SELECT_ACCESS_COLS_WIDTH: usize = 8;
+pub const FMA_COLS_WIDTH: usize = 4;
+pub const FMA_PREPROCESSED_COLS_WIDTH: usize = 6;
+pub const RANGE_CHECK_COLS_WIDTH: usize = 31;
+pub const RANGE_CHECK_PREPROCESSED_COLS_WIDTH: usize = 32;
+pub const POSEIDON2_SKINNY_COLS_WIDTH: usize = 28;
+pub const POSEIDON2_PREPROCESSED_COLS_SKINNY_WIDTH: usize = 51;
+pub const POSEIDON2_PREPROCESSED_COLS_WIDE_WIDTH: usize = 49;
+pub const MEMORY_CONST_ACCESS_COLS_WIDTH: usize = 6;
+pub const MEMORY_ACCESS_COLS_WIDTH: usize = 2;
+pub const BLOCK_WIDTH: usize = 4;
+
+macro_rules! assert_width {
+    ($($cols:ident => $width:ident),* $(,)?) => {
+        $(const _: () = assert!(core::mem::size_of::<$cols<u8>>() == $width);)*
+    };
+}
+
+assert_width! {
+    BaseAluValueCols => BASE_ALU_VALUE_COLS_WIDTH,
+    BaseAluAccessCols => BASE_ALU_ACCESS_COLS_WIDTH,
+    ExtAluValueCols => EXT_ALU_VALUE_COLS_WIDTH,
+    ExtAluAccessCols => EXT_ALU_ACCESS_COLS_WIDTH,
+    BatchFRICols => BATCH_FRI_COLS_WIDTH,
+    BatchFRIPreprocessedCols => BATCH_FRI_PREPROCESSED_COLS_WIDTH,
+    ExpReverseBitsLenCols => EXP_REVERSE_BITS_LEN_COLS_WIDTH,
+    ExpReverseBitsLenPreprocessedCols => EXP_REVERSE_BITS_LEN_PREPROCESSED_COLS_WIDTH,
+    FriFoldCols => FRI_FOLD_COLS_WIDTH,
+    FriFoldPreprocessedCols => FRI_FOLD_PREPROCESSED_COLS_WIDTH,
+    PublicValuesCols => PUBLIC_VALUES_COLS_WIDTH,
+    PublicValuesPreprocessedCols => PUBLIC_VALUES_PREPROCESSED_COLS_WIDTH,
+    SelectValueCols => SELECT_VALUE_COLS_WIDTH,
+    SelectAccessCols => SELECT_ACCESS_COLS_WIDTH,
+    FmaCols => FMA_COLS_WIDTH,
+    FmaPreprocessedCols => FMA_PREPROCESSED_COLS_WIDTH,
+    RangeCheckCols => RANGE_CHECK_COLS_WIDTH,
+    RangeCheckPreprocessedCols => RANGE_CHECK_PREPROCESSED_COLS_WIDTH,
+    Poseidon2 => POSEIDON2_SKINNY_COLS_WIDTH,
+    Poseidon2PreprocessedColsSkinny => POSEIDON2_PREPROCESSED_COLS_SKINNY_WIDTH,
+    Poseidon2PreprocessedColsWide => POSEIDON2_PREPROCESSED_COLS_WIDE_WIDTH,
+    MemoryConstAccessCols => MEMORY_CONST_ACCESS_COLS_WIDTH,
+    MemoryAccessColsChips => MEMORY_ACCESS_COLS_WIDTH,
+    Block => BLOCK_WIDTH,
+}
+
 #[link(name = "sp1-recursion-core-sys", kind = "static")]
 extern "C-unwind" {
     pub fn alu_base_event_to_row_babybear(
@@ -69,6 +135,18 @@ extern "C-unwind" {
         cols: &mut FriFoldPreprocessedCols<BabyBear>,
     );
 
+    pub fn mem_const_instr_to_row_babybear(
+        instr: &MemInstr<BabyBear>,
+        cols: &mut MemoryConstAccessCols<BabyBear>,
+    );
+
+    pub fn mem_var_event_to_row_babybear(io: &MemIo<Block<BabyBear>>, value: &mut Block<BabyBear>);
+    pub fn mem_var_instr_to_row_babybear(
+        addr: &Address<BabyBear>,
+        mult: &BabyBear,
+        access: &mut MemoryAccessColsChips<BabyBear>,
+    );
+
     pub fn public_values_event_to_row_babybear(
         io: &CommitPublicValuesEvent<BabyBear>,
         digest_idx: usize,
@@ -82,11 +160,26 @@ extern "C-unwind" {
 
     pub fn select_event_to_row_babybear(
         io: &SelectEvent<BabyBear>,
-        cols: &mut SelectCols<BabyBear>,
+        cols: &mut SelectValueCols<BabyBear>,
     );
     pub fn select_instr_to_row_babybear(
         instr: &SelectInstr<BabyBear>,
-        cols: &mut SelectPreprocessedCols<BabyBear>,
+        cols: &mut SelectAccessCols<BabyBear>,
+    );
+
+    pub fn fma_event_to_row_babybear(io: &FmaEvent<BabyBear>, cols: &mut FmaCols<BabyBear>);
+    pub fn fma_instr_to_row_babybear(
+        instr: &FmaInstr<BabyBear>,
+        cols: &mut FmaPreprocessedCols<BabyBear>,
+    );
+
+    pub fn range_check_event_to_row_babybear(
+        io: &RangeCheckEvent<BabyBear>,
+        cols: &mut RangeCheckCols<BabyBear>,
+    );
+    pub fn range_check_instr_to_row_babybear(
+        instr: &RangeCheckInstr<BabyBear>,
+        cols: &mut RangeCheckPreprocessedCols<BabyBear>,
     );
 
     pub fn poseidon2_skinny_event_to_row_babybear(
@@ -6,7 +6,7 @@ use std::path::PathBuf;
 
 use anyhow::{Context, Result};
 
-use crate::install::try_install_circuit_artifacts;
+use crate::install::ensure_circuit_artifacts;
 pub use sp1_prover::build::build_plonk_bn254_artifacts_with_dummy;
 
 /// Exports the solidity verifier for PLONK proofs to the specified output directory.
@@ -18,7 +18,7 @@ pub fn export_solidity_plonk_bn254_verifier(output_dir: impl Into<PathBuf>) -> R
     let artifacts_dir = if sp1_prover::build::sp1_dev_mode() {
         sp1_prover::build::plonk_bn254_artifacts_dev_dir()
     } else {
-        try_install_circuit_artifacts("plonk")
+        ensure_circuit_artifacts("plonk")?
     };
     let verifier_path = artifacts_dir.join("SP1VerifierPlonk.sol");
 
@@ -47,7 +47,7 @@ pub fn export_solidity_groth16_bn254_verifier(output_dir: impl Into<PathBuf>) ->
     let artifacts_dir = if sp1_prover::build::sp1_dev_mode() {
         sp1_prover::build::groth16_bn254_artifacts_dev_dir()
     } else {
-        try_install_circuit_artifacts("groth16")
+        ensure_circuit_artifacts("groth16")?
     };
     let verifier_path = artifacts_dir.join("SP1VerifierGroth16.sol");
 
@@ -30,11 +30,30 @@ impl ProverClient {
     /// let (pk, vk) = prover.setup(elf);
     /// let proof = prover.prove(&pk, &stdin).compressed().run().unwrap();
     /// ```
+    ///
+    /// # Panics
+    /// Panics if the environment is misconfigured, for example if `NETWORK_PRIVATE_KEY` is
+    /// malformed. Use [`ProverClient::try_from_env`] to handle the error instead.
     #[must_use]
     pub fn from_env() -> EnvProver {
         EnvProver::new()
     }
 
+    /// Builds an [`EnvProver`] from the environment, returning an error if the environment is
+    /// misconfigured.
+    ///
+    /// # Usage
+    /// ```no_run
+    /// use sp1_sdk::ProverClient;
+    ///
+    /// std::env::set_var("SP1_PROVER", "network");
+    /// std::env::set_var("NETWORK_PRIVATE_KEY", "...");
+    /// let prover = ProverClient::try_from_env().unwrap();
+    /// ```
+    pub fn try_from_env() -> anyhow::Result<EnvProver> {
+        EnvProver::try_new()
+    }
+
     /// Creates a new [`ProverClientBuilder`] so that you can configure the prover client.
     #[must_use]
     pub fn builder() -> ProverClientBuilder {
@@ -134,6 +153,6 @@ impl ProverClientBuilder {
     #[cfg(feature = "network")]
     #[must_use]
     pub fn network(&self) -> NetworkProverBuilder {
-        NetworkProverBuilder { private_key: None, rpc_url: None }
+        NetworkProverBuilder::default()
     }
 }
@@ -3,10 +3,53 @@
 //! This module provides a builder for simulating the execution of a program on the CPU.
 
 use anyhow::Result;
-use sp1_core_executor::{ExecutionReport, HookEnv, SP1ContextBuilder};
+use sp1_core_executor::{
+    ExecutionError, ExecutionReport, GuestOutput, HookEnv, SP1Context, SP1ContextBuilder,
+};
 use sp1_core_machine::io::SP1Stdin;
 use sp1_primitives::io::SP1PublicValues;
 use sp1_prover::{components::CpuProverComponents, SP1Prover};
+use thiserror::Error;
+
+/// The error returned when an execution is stopped at its cycle limit, with the partial results
+/// of the execution.
+///
+/// The error is returned as an [`anyhow::Error`] that can be downcast to it.
+#[derive(Error, Debug)]
+#[error("execution exceeded the limit of {max_cycles} cycles at pc {last_pc:#x}")]
+pub struct ExecutionLimitExceeded {
+    /// The cycle limit of the execution.
+    pub max_cycles: u64,
+    /// The report of the execution up to the limit, with its cycle and syscall counts.
+    pub report: ExecutionReport,
+    /// The program counter of the next instruction the execution would have run.
+    pub last_pc: u32,
+    /// The public values the program committed before the limit was reached.
+    pub public_values: SP1PublicValues,
+}
+
+/// Executes the program, failing with [`ExecutionLimitExceeded`] if it exceeds the cycle limit
+/// of `context`.
+pub(crate) fn execute_with_cycle_limit<'a>(
+    prover: &'a SP1Prover<CpuProverComponents>,
+    elf: &[u8],
+    stdin: &SP1Stdin,
+    context: SP1Context<'a>,
+) -> Result<(SP1PublicValues, ExecutionReport)> {
+    let (runtime, result) = prover.execute_partial(elf, stdin, context);
+    let public_values = SP1PublicValues::from(&runtime.state.public_values_stream);
+    match result {
+        Ok(()) => Ok((public_values, runtime.report)),
+        Err(ExecutionError::ExceededCycleLimit(max_cycles)) => Err(ExecutionLimitExceeded {
+            max_cycles,
+            report: runtime.report,
+            last_pc: runtime.state.pc,
+            public_values,
+        }
+        .into()),
+        Err(e) => Err(e.into()),
+    }
+}
 
 /// A builder for simulating the execution of a program on the CPU.
 ///
@@ -17,6 +60,7 @@ pub struct CpuExecuteBuilder<'a> {
     pub(crate) stdin: SP1Stdin,
     pub(crate) prover: &'a SP1Prover<CpuProverComponents>,
     pub(crate) context_builder: SP1ContextBuilder<'a>,
+    pub(crate) max_cycles: Option<u64>,
 }
 
 impl<'a> CpuExecuteBuilder<'a> {
@@ -29,7 +73,19 @@ impl<'a> CpuExecuteBuilder<'a> {
     /// # Details
     /// Hooks may be invoked from within SP1 by writing to the specified file descriptor `fd`
     /// with [`sp1_zkvm::io::write`], returning a list of arbitrary data that may be read
-    /// with successive calls to [`sp1_zkvm::io::read`].
+    /// with successive calls to [`sp1_zkvm::io::read`]. The file descriptor must be greater than
+    /// [`sp1_core_executor::LOWEST_ALLOWED_FD`]. Writing to a file descriptor with no hook
+    /// registered for it fails the execution with
+    /// [`sp1_core_executor::ExecutionError::UnregisteredHook`]. The invocations of each hook are
+    /// counted in [`ExecutionReport::hook_invocations`].
+    ///
+    /// The responses of a hook are read by the program like any other input, so they become part
+    /// of the witness of its proof: nothing checks them, and the program must validate them
+    /// itself, e.g. by checking a Merkle branch against a committed root. A program using a hook
+    /// must be proven with the same hooks, registered with
+    /// [`crate::cpu::prove::CpuProveBuilder::with_hook`], and the program is executed more than
+    /// once while it is proven: a hook must return the same response every time it is invoked
+    /// with the same request.
     ///
     /// # Example
     /// ```rust,no_run
@@ -40,7 +96,7 @@ impl<'a> CpuExecuteBuilder<'a> {
     ///
     /// let client = ProverClient::builder().cpu().build();
     /// let builder = client.execute(elf, &stdin)
-    ///     .with_hook(1, |env, data| {
+    ///     .with_hook(100, |env, data| {
     ///         println!("Hook triggered with data: {:?}", data);
     ///         vec![vec![1, 2, 3]]
     ///     })
@@ -84,6 +140,42 @@ impl<'a> CpuExecuteBuilder<'a> {
         self
     }
 
+    /// Set the maximum number of cpu cycles to execute, stopping the execution with its partial
+    /// results if the program runs for longer.
+    ///
+    /// # Arguments
+    /// * `max_cycles` - The maximum number of cycles to execute.
+    ///
+    /// # Details
+    /// Unlike with [`Self::cycle_limit`], which it overrides, an execution stopped at the limit
+    /// fails with [`ExecutionLimitExceeded`], carrying the report of the execution so far, the
+    /// program counter it stopped at and the public values committed so far. This is useful to
+    /// bound the execution of programs that may loop forever on untrusted inputs. With
+    /// [`Self::run_with_io_callbacks`], the limit is enforced, but the execution fails with
+    /// [`sp1_core_executor::ExecutionError::ExceededCycleLimit`] instead.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ExecutionLimitExceeded, ProverClient, SP1Stdin, include_elf, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().cpu().build();
+    /// let err = client.execute(elf, &stdin)
+    ///     .max_cycles(1000000)
+    ///     .run()
+    ///     .unwrap_err();
+    /// if let Some(err) = err.downcast_ref::<ExecutionLimitExceeded>() {
+    ///     println!("stopped after {} cycles", err.report.total_instruction_count());
+    /// }
+    /// ```
+    #[must_use]
+    pub fn max_cycles(mut self, max_cycles: u64) -> Self {
+        self.max_cycles = Some(max_cycles);
+        self
+    }
+
     /// Whether to enable deferred proof verification in the executor.
     ///
     /// # Arguments
@@ -132,8 +224,76 @@ impl<'a> CpuExecuteBuilder<'a> {
     ///     .unwrap();
     /// ```
     pub fn run(self) -> Result<(SP1PublicValues, ExecutionReport)> {
-        let Self { prover, elf, stdin, mut context_builder } = self;
+        let Self { prover, elf, stdin, mut context_builder, max_cycles } = self;
+        let Some(max_cycles) = max_cycles else {
+            let context = context_builder.build();
+            return Ok(prover.execute(elf, &stdin, context)?);
+        };
+        let context = context_builder.max_cycles(max_cycles).build();
+        execute_with_cycle_limit(prover, elf, &stdin, context)
+    }
+
+    /// Executes the program on the input with the built arguments and returns its prover gas.
+    ///
+    /// # Details
+    /// The prover gas estimates the cost of proving the execution better than its cycle count.
+    /// See [`ExecutionReport::gas`] for a breakdown of the gas.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, include_elf, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().cpu().build();
+    /// let gas = client.execute(elf, &stdin)
+    ///     .gas_used()
+    ///     .unwrap();
+    /// ```
+    pub fn gas_used(self) -> Result<u64> {
+        let (_, report) = self.run()?;
+        Ok(report.gas().total())
+    }
+
+    /// Executes the program on the input with the built arguments, streaming the output of the
+    /// program to the given callbacks.
+    ///
+    /// # Arguments
+    /// * `on_stdout` - The function to invoke with the bytes of each write to stdout.
+    /// * `on_stderr` - The function to invoke with the bytes of each write to stderr.
+    ///
+    /// # Details
+    /// The callbacks are invoked as the program writes to stdout and stderr, so the output of long
+    /// running programs can be observed before the execution finishes. The full output is also
+    /// returned in the [`GuestOutput`]. If a callback panics, the execution is aborted and the
+    /// method returns [`sp1_core_executor::ExecutionError::IoCallbackPanicked`].
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, include_elf, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().cpu().build();
+    /// let (public_values, execution_report, output) = client.execute(elf, &stdin)
+    ///     .run_with_io_callbacks(
+    ///         |bytes| print!("{}", String::from_utf8_lossy(bytes)),
+    ///         |bytes| eprint!("{}", String::from_utf8_lossy(bytes)),
+    ///     )
+    ///     .unwrap();
+    /// ```
+    pub fn run_with_io_callbacks(
+        self,
+        on_stdout: impl FnMut(&[u8]) + Send + 'a,
+        on_stderr: impl FnMut(&[u8]) + Send + 'a,
+    ) -> Result<(SP1PublicValues, ExecutionReport, GuestOutput)> {
+        let Self { prover, elf, stdin, mut context_builder, max_cycles } = self;
+        if let Some(max_cycles) = max_cycles {
+            context_builder.max_cycles(max_cycles);
+        }
         let context = context_builder.build();
-        Ok(prover.execute(elf, &stdin, context)?)
+        Ok(prover.execute_with_io_callbacks(elf, &stdin, context, on_stdout, on_stderr)?)
     }
 }
@@ -6,23 +6,28 @@ pub mod builder;
 pub mod execute;
 pub mod prove;
 
+use std::collections::HashMap;
+
 use anyhow::Result;
-use execute::CpuExecuteBuilder;
+use execute::{execute_with_cycle_limit, CpuExecuteBuilder};
 use prove::CpuProveBuilder;
-use sp1_core_executor::{SP1Context, SP1ContextBuilder};
+use sp1_core_executor::{SP1Context, SP1ContextBuilder, SP1ReduceProof};
 use sp1_core_machine::io::SP1Stdin;
 use sp1_prover::{
     components::CpuProverComponents,
     verify::{verify_groth16_bn254_public_inputs, verify_plonk_bn254_public_inputs},
-    Groth16Bn254Proof, PlonkBn254Proof, SP1CoreProofData, SP1ProofWithMetadata, SP1Prover,
+    Groth16Bn254Proof, HashableKey, InnerSC, PlonkBn254Proof, SP1CoreProofData,
+    SP1ProofWithMetadata, SP1Prover,
 };
-use sp1_stark::{SP1CoreOpts, SP1ProverOpts};
+use sp1_stark::{NoopObserver, ProverObserver, ProvingReport, SP1CoreOpts, SP1ProverOpts};
+use sp1_verifier::MockVerifier;
 
-use crate::install::try_install_circuit_artifacts;
-use crate::prover::verify_proof;
+use crate::install::{ensure_circuit_artifacts, ensure_circuit_artifacts_for};
+use crate::prover::{compressed_proof_to_wrap, core_proof_to_compress, verify_proof};
 use crate::SP1VerificationError;
 use crate::{
-    Prover, SP1Proof, SP1ProofMode, SP1ProofWithPublicValues, SP1ProvingKey, SP1VerifyingKey,
+    Prover, SP1Proof, SP1ProofMode, SP1ProofWithPublicValues, SP1ProvingKey, SP1PublicValues,
+    SP1VerifyingKey,
 };
 
 /// A prover that uses the CPU to execute and prove programs.
@@ -68,6 +73,7 @@ impl CpuProver {
             elf,
             stdin: stdin.clone(),
             context_builder: SP1ContextBuilder::default(),
+            max_cycles: None,
         }
     }
 
@@ -99,6 +105,8 @@ impl CpuProver {
             core_opts: SP1CoreOpts::default(),
             recursion_opts: SP1CoreOpts::recursion(),
             mock: self.mock,
+            observer: None,
+            deferred_proofs: HashMap::new(),
         }
     }
 
@@ -109,43 +117,80 @@ impl CpuProver {
         opts: SP1ProverOpts,
         context: SP1Context<'a>,
         mode: SP1ProofMode,
-    ) -> Result<SP1ProofWithPublicValues> {
+        observer: &dyn ProverObserver,
+    ) -> Result<(SP1ProofWithPublicValues, ProvingReport)> {
         let program = self.prover.get_program(&pk.elf).unwrap();
 
         // If we're in mock mode, return a mock proof.
         if self.mock {
-            return self.mock_prove_impl(pk, stdin, context, mode);
+            let proof = self.mock_prove_impl(pk, stdin, context, mode)?;
+            return Ok((proof, ProvingReport::default()));
+        }
+
+        // Keep the provider of the deferred proofs, which are needed again to compress the proof.
+        let deferred_proof_provider = context.deferred_proof_provider.clone();
+
+        // Refuse to start proving if the program does not halt within the cycle limit, as the
+        // proving would only fail once the execution reaches it.
+        if let Some(max_cycles) = context.max_cycles {
+            let mut limit_context = SP1Context::builder()
+                .max_cycles(max_cycles)
+                .set_deferred_proof_verification(false)
+                .build();
+            limit_context.deferred_proof_provider = deferred_proof_provider.clone();
+            execute_with_cycle_limit(&self.prover, &pk.elf, stdin, limit_context)?;
         }
+        ensure_circuit_artifacts_for(mode)?;
 
         // Generate the core proof.
-        let proof: SP1ProofWithMetadata<SP1CoreProofData> =
-            self.prover.prove_core(&pk.pk, program, stdin, opts, context)?;
+        let (proof, report): (SP1ProofWithMetadata<SP1CoreProofData>, _) = self
+            .prover
+            .prove_core_with_observer(&pk.pk, program, stdin, opts, context, observer)?;
         if mode == SP1ProofMode::Core {
-            return Ok(SP1ProofWithPublicValues {
+            let proof = SP1ProofWithPublicValues {
                 proof: SP1Proof::Core(proof.proof.0),
                 public_values: proof.public_values,
                 sp1_version: self.version().to_string(),
-            });
+            };
+            return Ok((proof, report));
         }
 
         // Generate the compressed proof.
-        let deferred_proofs =
-            stdin.proofs.iter().map(|(reduce_proof, _)| reduce_proof.clone()).collect();
+        let deferred_proofs = stdin
+            .resolve_proofs(deferred_proof_provider.as_deref())?
+            .into_iter()
+            .map(|(reduce_proof, _)| reduce_proof)
+            .collect();
         let public_values = proof.public_values.clone();
-        let reduce_proof = self.prover.compress(&pk.vk, proof, deferred_proofs, opts)?;
+        let reduce_proof =
+            self.prover.compress_with_observer(&pk.vk, proof, deferred_proofs, opts, observer)?;
         if mode == SP1ProofMode::Compressed {
-            return Ok(SP1ProofWithPublicValues {
+            let proof = SP1ProofWithPublicValues {
                 proof: SP1Proof::Compressed(Box::new(reduce_proof)),
                 public_values,
                 sp1_version: self.version().to_string(),
-            });
+            };
+            return Ok((proof, report));
         }
 
         // Generate the shrink proof.
-        let compress_proof = self.prover.shrink(reduce_proof, opts)?;
+        let shrink_proof = self.prover.shrink_with_observer(reduce_proof, opts, observer)?;
+
+        self.wrap_impl(shrink_proof, public_values, mode, opts, observer)
+            .map(|proof| (proof, report))
+    }
 
+    /// Wraps a shrink proof into a [`SP1ProofMode::Groth16`] or [`SP1ProofMode::Plonk`] proof.
+    fn wrap_impl(
+        &self,
+        shrink_proof: SP1ReduceProof<InnerSC>,
+        public_values: SP1PublicValues,
+        mode: SP1ProofMode,
+        opts: SP1ProverOpts,
+        observer: &dyn ProverObserver,
+    ) -> Result<SP1ProofWithPublicValues> {
         // Generate the wrap proof.
-        let outer_proof = self.prover.wrap_bn254(compress_proof, opts)?;
+        let outer_proof = self.prover.wrap_bn254_with_observer(shrink_proof, opts, observer)?;
 
         // Generate the gnark proof.
         match mode {
@@ -156,7 +201,7 @@ impl CpuProver {
                         &outer_proof.proof,
                     )
                 } else {
-                    try_install_circuit_artifacts("groth16")
+                    ensure_circuit_artifacts("groth16")?
                 };
 
                 let proof = self.prover.wrap_groth16_bn254(outer_proof, &groth16_bn254_artifacts);
@@ -173,7 +218,7 @@ impl CpuProver {
                         &outer_proof.proof,
                     )
                 } else {
-                    try_install_circuit_artifacts("plonk")
+                    ensure_circuit_artifacts("plonk")?
                 };
                 let proof = self.prover.wrap_plonk_bn254(outer_proof, &plonk_bn254_artifacts);
                 Ok(SP1ProofWithPublicValues {
@@ -193,7 +238,7 @@ impl CpuProver {
         context: SP1Context<'a>,
         mode: SP1ProofMode,
     ) -> Result<SP1ProofWithPublicValues> {
-        let (public_values, _) = self.prover.execute(&pk.elf, stdin, context)?;
+        let (public_values, _) = execute_with_cycle_limit(&self.prover, &pk.elf, stdin, context)?;
         Ok(SP1ProofWithPublicValues::create_mock_proof(pk, public_values, mode, self.version()))
     }
 
@@ -204,15 +249,26 @@ impl CpuProver {
         match &bundle.proof {
             SP1Proof::Plonk(PlonkBn254Proof { public_inputs, .. }) => {
                 verify_plonk_bn254_public_inputs(vkey, &bundle.public_values, public_inputs)
+                    .and_then(|()| Self::mock_verify_bytes(bundle, vkey))
                     .map_err(SP1VerificationError::Plonk)
             }
             SP1Proof::Groth16(Groth16Bn254Proof { public_inputs, .. }) => {
                 verify_groth16_bn254_public_inputs(vkey, &bundle.public_values, public_inputs)
+                    .and_then(|()| Self::mock_verify_bytes(bundle, vkey))
                     .map_err(SP1VerificationError::Groth16)
             }
             _ => Ok(()),
         }
     }
+
+    /// Checks the bytes of a mock Plonk or Groth16 proof with the [`MockVerifier`].
+    fn mock_verify_bytes(bundle: &SP1ProofWithPublicValues, vkey: &SP1VerifyingKey) -> Result<()> {
+        if bundle.is_mock() {
+            let public_values = bundle.public_values.as_slice();
+            MockVerifier::verify(&bundle.bytes(), public_values, &vkey.bytes32())?;
+        }
+        Ok(())
+    }
 }
 
 impl Prover<CpuProverComponents> for CpuProver {
@@ -231,7 +287,105 @@ impl Prover<CpuProverComponents> for CpuProver {
         stdin: &SP1Stdin,
         mode: SP1ProofMode,
     ) -> Result<SP1ProofWithPublicValues> {
-        self.prove_impl(pk, stdin, SP1ProverOpts::default(), SP1Context::default(), mode)
+        self.prove_impl(
+            pk,
+            stdin,
+            SP1ProverOpts::default(),
+            SP1Context::default(),
+            mode,
+            &NoopObserver,
+        )
+        .map(|(proof, _)| proof)
+    }
+
+    /// Compresses a [`SP1ProofMode::Core`] proof generated before, e.g. one loaded with
+    /// [`SP1ProofWithPublicValues::load`], into a [`SP1ProofMode::Compressed`] proof.
+    ///
+    /// # Details
+    /// The proof is verified against `vk` and the version of this prover before it is compressed.
+    /// `deferred_proofs` are the compressed proofs the program verified, in the order it verified
+    /// them.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{Prover, ProverClient, SP1ProofWithPublicValues};
+    ///
+    /// let elf = &[1, 2, 3];
+    ///
+    /// let client = ProverClient::builder().cpu().build();
+    /// let (_, vk) = client.setup(elf);
+    /// let proof = SP1ProofWithPublicValues::load("core_proof.bin").unwrap();
+    /// let proof = client.compress(&vk, &proof, vec![]).unwrap();
+    /// let proof = client.wrap_groth16(&vk, &proof).unwrap();
+    /// ```
+    fn compress(
+        &self,
+        vk: &SP1VerifyingKey,
+        proof: &SP1ProofWithPublicValues,
+        deferred_proofs: Vec<SP1ReduceProof<InnerSC>>,
+    ) -> Result<SP1ProofWithPublicValues> {
+        let core_proof = core_proof_to_compress(&self.prover, self.version(), proof, vk)?;
+        let reduce_proof =
+            self.prover.compress(vk, core_proof, deferred_proofs, SP1ProverOpts::default())?;
+        Ok(SP1ProofWithPublicValues {
+            proof: SP1Proof::Compressed(Box::new(reduce_proof)),
+            public_values: proof.public_values.clone(),
+            sp1_version: self.version().to_string(),
+        })
+    }
+
+    /// Shrinks a [`SP1ProofMode::Compressed`] proof generated before, e.g. with
+    /// [`Prover::compress`], into the proof of the shrink program.
+    ///
+    /// # Details
+    /// The proof is verified against `vk` and the version of this prover before it is shrunk.
+    fn shrink(
+        &self,
+        vk: &SP1VerifyingKey,
+        proof: &SP1ProofWithPublicValues,
+    ) -> Result<SP1ReduceProof<InnerSC>> {
+        let reduce_proof = compressed_proof_to_wrap(&self.prover, self.version(), proof, vk)?;
+        Ok(self.prover.shrink(reduce_proof, SP1ProverOpts::default())?)
+    }
+
+    /// Shrinks and wraps a [`SP1ProofMode::Compressed`] proof generated before, e.g. with
+    /// [`Prover::compress`], into a [`SP1ProofMode::Groth16`] proof.
+    ///
+    /// # Details
+    /// The proof is verified against `vk` and the version of this prover before it is wrapped.
+    fn wrap_groth16(
+        &self,
+        vk: &SP1VerifyingKey,
+        proof: &SP1ProofWithPublicValues,
+    ) -> Result<SP1ProofWithPublicValues> {
+        let shrink_proof = self.shrink(vk, proof)?;
+        self.wrap_impl(
+            shrink_proof,
+            proof.public_values.clone(),
+            SP1ProofMode::Groth16,
+            SP1ProverOpts::default(),
+            &NoopObserver,
+        )
+    }
+
+    /// Shrinks and wraps a [`SP1ProofMode::Compressed`] proof generated before, e.g. with
+    /// [`Prover::compress`], into a [`SP1ProofMode::Plonk`] proof.
+    ///
+    /// # Details
+    /// The proof is verified against `vk` and the version of this prover before it is wrapped.
+    fn wrap_plonk(
+        &self,
+        vk: &SP1VerifyingKey,
+        proof: &SP1ProofWithPublicValues,
+    ) -> Result<SP1ProofWithPublicValues> {
+        let shrink_proof = self.shrink(vk, proof)?;
+        self.wrap_impl(
+            shrink_proof,
+            proof.public_values.clone(),
+            SP1ProofMode::Plonk,
+            SP1ProverOpts::default(),
+            &NoopObserver,
+        )
     }
 
     fn verify(
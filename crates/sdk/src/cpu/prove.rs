@@ -2,11 +2,15 @@
 //!
 //! This module provides a builder for proving a program on the CPU.
 
-use anyhow::Result;
-use sp1_core_executor::SP1ContextBuilder;
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::{bail, Result};
+use sp1_core_executor::{HookEnv, SP1ContextBuilder, SP1ReduceProof};
 use sp1_core_machine::io::SP1Stdin;
-use sp1_prover::SP1ProvingKey;
-use sp1_stark::{SP1CoreOpts, SP1ProverOpts};
+use sp1_prover::{InnerSC, SP1ProvingKey};
+use sp1_stark::{
+    NoopObserver, ProverObserver, ProvingReport, SP1CoreOpts, SP1ProverOpts, StarkVerifyingKey,
+};
 
 use super::CpuProver;
 use crate::{SP1ProofMode, SP1ProofWithPublicValues};
@@ -24,9 +28,12 @@ pub struct CpuProveBuilder<'a> {
     pub(crate) core_opts: SP1CoreOpts,
     pub(crate) recursion_opts: SP1CoreOpts,
     pub(crate) mock: bool,
+    pub(crate) observer: Option<Arc<dyn ProverObserver>>,
+    pub(crate) deferred_proofs:
+        HashMap<usize, (SP1ReduceProof<InnerSC>, StarkVerifyingKey<InnerSC>)>,
 }
 
-impl CpuProveBuilder<'_> {
+impl<'a> CpuProveBuilder<'a> {
     /// Set the proof kind to [`SP1ProofKind::Core`] mode.
     ///
     /// # Details
@@ -207,11 +214,40 @@ impl CpuProveBuilder<'_> {
         self
     }
 
+    /// Set the number of shards proven concurrently.
+    ///
+    /// # Details
+    /// Each shard is proven by the first idle worker once its traces are generated, so that a slow
+    /// shard does not hold up the others, and the proofs are still output in shard order. Defaults
+    /// to the `SP1_PROVER_SHARD_CONCURRENCY` environment variable if set, and to the shard batch
+    /// size otherwise. Every shard being proven holds its traces in memory.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, include_elf, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().cpu().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let builder = client.prove(&pk, &stdin)
+    ///     .shard_concurrency(4)
+    ///     .run();
+    /// ```
+    #[must_use]
+    pub fn shard_concurrency(mut self, value: usize) -> Self {
+        assert!(value > 0, "shard concurrency must be positive");
+        self.core_opts.shard_concurrency = value;
+        self
+    }
+
     /// Set the maximum number of cpu cycles to use for execution.
     ///
     /// # Details
-    /// If the cycle limit is exceeded, execution will return
-    /// [`sp1_core_executor::ExecutionError::ExceededCycleLimit`].
+    /// The program is executed before proving starts, and if it exceeds the cycle limit, the
+    /// prover refuses to start and returns [`crate::ExecutionLimitExceeded`] with the partial
+    /// results of the execution.
     ///
     /// # Example
     /// ```rust,no_run
@@ -232,6 +268,41 @@ impl CpuProveBuilder<'_> {
         self
     }
 
+    /// Add a executor [`sp1_core_executor::Hook`] into the context.
+    ///
+    /// # Arguments
+    /// * `fd` - The file descriptor that triggers this execution hook.
+    /// * `f` - The function to invoke when the hook is triggered.
+    ///
+    /// # Details
+    /// A program using a hook must be proven with the same hooks it was executed with, see
+    /// [`crate::cpu::execute::CpuExecuteBuilder::with_hook`]. The program is executed more than
+    /// once while it is proven, so the hook must return the same response every time it is
+    /// invoked with the same request.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, include_elf, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().cpu().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let builder = client.prove(&pk, &stdin)
+    ///     .with_hook(100, |env, data| vec![data.to_vec()])
+    ///     .run();
+    /// ```
+    #[must_use]
+    pub fn with_hook(
+        mut self,
+        fd: u32,
+        f: impl FnMut(HookEnv, &[u8]) -> Vec<Vec<u8>> + Send + Sync + 'a,
+    ) -> Self {
+        self.context_builder.hook(fd, f);
+        self
+    }
+
     /// Whether to enable deferred proof verification in the executor.
     ///
     /// # Arguments
@@ -262,6 +333,69 @@ impl CpuProveBuilder<'_> {
         self
     }
 
+    /// Set an observer of the progress of the prover.
+    ///
+    /// # Details
+    /// The observer is notified when each shard of the core proof is proven, which can be used to
+    /// render a progress bar, and when each stage of each compress, shrink and wrap proof starts
+    /// and finishes: the generation of the trace of each chip, the commitment to the traces, the
+    /// permutation, the quotient and the opening. By default, no observer is set.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, include_elf, Prover, TracingObserver};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().cpu().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let builder = client.prove(&pk, &stdin)
+    ///     .compressed()
+    ///     .with_observer(TracingObserver)
+    ///     .run();
+    /// ```
+    #[must_use]
+    pub fn with_observer(mut self, observer: impl ProverObserver + 'static) -> Self {
+        self.observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Supply the proof of a deferred proof slot of the stdin.
+    ///
+    /// # Details
+    /// The slot must have been reserved with [`SP1Stdin::defer_proof_slot`]. The proof is only
+    /// read by the executor when the program verifies it, so it does not have to be known when
+    /// the stdin is built. Proving fails if the program verifies a slot without a proof.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, include_elf, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let client = ProverClient::builder().cpu().build();
+    /// let (pk, vk) = client.setup(elf);
+    ///
+    /// let inner_proof = client.prove(&pk, &SP1Stdin::new()).compressed().run().unwrap();
+    ///
+    /// let mut stdin = SP1Stdin::new();
+    /// let slot = stdin.defer_proof_slot();
+    /// let builder = client.prove(&pk, &stdin)
+    ///     .compressed()
+    ///     .with_deferred_proof(slot, *inner_proof.proof.try_as_compressed().unwrap(), vk.vk)
+    ///     .run();
+    /// ```
+    #[must_use]
+    pub fn with_deferred_proof(
+        mut self,
+        slot: usize,
+        proof: SP1ReduceProof<InnerSC>,
+        vk: StarkVerifyingKey<InnerSC>,
+    ) -> Self {
+        self.deferred_proofs.insert(slot, (proof, vk));
+        self
+    }
+
     /// Run the prover with the built arguments.
     ///
     /// # Details
@@ -282,10 +416,55 @@ impl CpuProveBuilder<'_> {
     ///     .unwrap();
     /// ```
     pub fn run(self) -> Result<SP1ProofWithPublicValues> {
+        self.run_with_report().map(|(proof, _)| proof)
+    }
+
+    /// Run the prover with the built arguments, returning the proof along with a report of the
+    /// proving of its core shards.
+    ///
+    /// # Details
+    /// The report holds the time spent proving each shard, in shard order, the peak memory of the
+    /// process and the total time of the core proving. It is empty for a mock proof.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, include_elf, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().cpu().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let (proof, report) = client.prove(&pk, &stdin)
+    ///     .run_with_report()
+    ///     .unwrap();
+    /// println!("proved {} shards in {:?}", report.shard_timings.len(), report.total_time);
+    /// ```
+    pub fn run_with_report(self) -> Result<(SP1ProofWithPublicValues, ProvingReport)> {
         // Get the arguments.
-        let Self { prover, mode, pk, stdin, mut context_builder, core_opts, recursion_opts, mock } =
-            self;
+        let Self {
+            prover,
+            mode,
+            pk,
+            stdin,
+            mut context_builder,
+            core_opts,
+            recursion_opts,
+            mock,
+            observer,
+            deferred_proofs,
+        } = self;
         let opts = SP1ProverOpts { core_opts, recursion_opts };
+
+        // Check that the deferred proofs fill slots of the stdin.
+        if let Some(slot) = deferred_proofs.keys().find(|s| !stdin.deferred_proof_slots.contains(s))
+        {
+            bail!("deferred proof slot {slot} was not reserved in the stdin");
+        }
+        if !deferred_proofs.is_empty() {
+            context_builder
+                .deferred_proof_provider(move |slot: usize| deferred_proofs.get(&slot).cloned());
+        }
         let context = context_builder.build();
 
         // Dump the program and stdin to files for debugging if `SP1_DUMP` is set.
@@ -293,9 +472,11 @@ impl CpuProveBuilder<'_> {
 
         // Run the prover.
         if mock {
-            prover.mock_prove_impl(pk, &stdin, context, mode)
+            let proof = prover.mock_prove_impl(pk, &stdin, context, mode)?;
+            Ok((proof, ProvingReport::default()))
         } else {
-            prover.prove_impl(pk, &stdin, opts, context, mode)
+            let observer = observer.as_deref().unwrap_or(&NoopObserver);
+            prover.prove_impl(pk, &stdin, opts, context, mode, observer)
         }
     }
 }
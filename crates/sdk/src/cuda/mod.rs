@@ -7,15 +7,17 @@ pub mod prove;
 
 use anyhow::Result;
 use prove::CudaProveBuilder;
-use sp1_core_executor::SP1ContextBuilder;
+use sp1_core_executor::{SP1ContextBuilder, SP1ReduceProof};
 use sp1_core_machine::io::SP1Stdin;
 use sp1_cuda::SP1CudaProver;
-use sp1_prover::{components::CpuProverComponents, SP1Prover};
+use sp1_prover::{components::CpuProverComponents, InnerSC, SP1Prover};
 
 use crate::cpu::execute::CpuExecuteBuilder;
-use crate::install::try_install_circuit_artifacts;
+use crate::install::{ensure_circuit_artifacts, ensure_circuit_artifacts_for};
+use crate::prover::{compressed_proof_to_wrap, core_proof_to_compress};
 use crate::{
-    Prover, SP1Proof, SP1ProofMode, SP1ProofWithPublicValues, SP1ProvingKey, SP1VerifyingKey,
+    Prover, SP1Proof, SP1ProofMode, SP1ProofWithPublicValues, SP1ProvingKey, SP1PublicValues,
+    SP1VerifyingKey,
 };
 
 /// A prover that uses the CPU for execution and the CUDA for proving.
@@ -58,6 +60,7 @@ impl CudaProver {
             elf,
             stdin: stdin.clone(),
             context_builder: SP1ContextBuilder::default(),
+            max_cycles: None,
         }
     }
 
@@ -82,6 +85,52 @@ impl CudaProver {
     pub fn prove<'a>(&'a self, pk: &'a SP1ProvingKey, stdin: &'a SP1Stdin) -> CudaProveBuilder<'a> {
         CudaProveBuilder { prover: self, mode: SP1ProofMode::Core, pk, stdin: stdin.clone() }
     }
+
+    /// Wraps a shrink proof into a [`SP1ProofMode::Groth16`] or [`SP1ProofMode::Plonk`] proof.
+    fn wrap_impl(
+        &self,
+        shrink_proof: SP1ReduceProof<InnerSC>,
+        public_values: SP1PublicValues,
+        kind: SP1ProofMode,
+    ) -> Result<SP1ProofWithPublicValues> {
+        // Generate the wrap proof.
+        let outer_proof = self.cuda_prover.wrap_bn254(shrink_proof)?;
+
+        if kind == SP1ProofMode::Plonk {
+            let plonk_bn254_artifacts = if sp1_prover::build::sp1_dev_mode() {
+                sp1_prover::build::try_build_plonk_bn254_artifacts_dev(
+                    &outer_proof.vk,
+                    &outer_proof.proof,
+                )
+            } else {
+                ensure_circuit_artifacts("plonk")?
+            };
+            let proof = self.cpu_prover.wrap_plonk_bn254(outer_proof, &plonk_bn254_artifacts);
+            return Ok(SP1ProofWithPublicValues {
+                proof: SP1Proof::Plonk(proof),
+                public_values,
+                sp1_version: self.version().to_string(),
+            });
+        } else if kind == SP1ProofMode::Groth16 {
+            let groth16_bn254_artifacts = if sp1_prover::build::sp1_dev_mode() {
+                sp1_prover::build::try_build_groth16_bn254_artifacts_dev(
+                    &outer_proof.vk,
+                    &outer_proof.proof,
+                )
+            } else {
+                ensure_circuit_artifacts("groth16")?
+            };
+
+            let proof = self.cpu_prover.wrap_groth16_bn254(outer_proof, &groth16_bn254_artifacts);
+            return Ok(SP1ProofWithPublicValues {
+                proof: SP1Proof::Groth16(proof),
+                public_values,
+                sp1_version: self.version().to_string(),
+            });
+        }
+
+        unreachable!()
+    }
 }
 
 impl Prover<CpuProverComponents> for CudaProver {
@@ -90,6 +139,12 @@ impl Prover<CpuProverComponents> for CudaProver {
         (pk, vk)
     }
 
+    fn setup_cached(&self, elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey) {
+        // The CUDA prover proves the program it was last setup with, so the setup can't be
+        // skipped.
+        self.setup(elf)
+    }
+
     fn inner(&self) -> &SP1Prover<CpuProverComponents> {
         &self.cpu_prover
     }
@@ -100,6 +155,11 @@ impl Prover<CpuProverComponents> for CudaProver {
         stdin: &SP1Stdin,
         kind: SP1ProofMode,
     ) -> Result<SP1ProofWithPublicValues> {
+        // The program is executed by the CUDA prover, which cannot be given proofs of deferred
+        // proof slots, so only the proofs written to the stdin can be verified.
+        let deferred_proofs = stdin.resolve_proofs(None)?;
+        ensure_circuit_artifacts_for(kind)?;
+
         // Generate the core proof.
         let proof = self.cuda_prover.prove_core(stdin)?;
         if kind == SP1ProofMode::Core {
@@ -112,7 +172,7 @@ impl Prover<CpuProverComponents> for CudaProver {
 
         // Generate the compressed proof.
         let deferred_proofs =
-            stdin.proofs.iter().map(|(reduce_proof, _)| reduce_proof.clone()).collect();
+            deferred_proofs.into_iter().map(|(reduce_proof, _)| reduce_proof).collect();
         let public_values = proof.public_values.clone();
         let reduce_proof = self.cuda_prover.compress(&pk.vk, proof, deferred_proofs)?;
         if kind == SP1ProofMode::Compressed {
@@ -124,45 +184,86 @@ impl Prover<CpuProverComponents> for CudaProver {
         }
 
         // Generate the shrink proof.
-        let compress_proof = self.cuda_prover.shrink(reduce_proof)?;
+        let shrink_proof = self.cuda_prover.shrink(reduce_proof)?;
 
-        // Genenerate the wrap proof.
-        let outer_proof = self.cuda_prover.wrap_bn254(compress_proof)?;
+        self.wrap_impl(shrink_proof, public_values, kind)
+    }
 
-        if kind == SP1ProofMode::Plonk {
-            let plonk_bn254_artifacts = if sp1_prover::build::sp1_dev_mode() {
-                sp1_prover::build::try_build_plonk_bn254_artifacts_dev(
-                    &outer_proof.vk,
-                    &outer_proof.proof,
-                )
-            } else {
-                try_install_circuit_artifacts("plonk")
-            };
-            let proof = self.cpu_prover.wrap_plonk_bn254(outer_proof, &plonk_bn254_artifacts);
-            return Ok(SP1ProofWithPublicValues {
-                proof: SP1Proof::Plonk(proof),
-                public_values,
-                sp1_version: self.version().to_string(),
-            });
-        } else if kind == SP1ProofMode::Groth16 {
-            let groth16_bn254_artifacts = if sp1_prover::build::sp1_dev_mode() {
-                sp1_prover::build::try_build_groth16_bn254_artifacts_dev(
-                    &outer_proof.vk,
-                    &outer_proof.proof,
-                )
-            } else {
-                try_install_circuit_artifacts("groth16")
-            };
+    /// Compresses a [`SP1ProofMode::Core`] proof generated before, e.g. one loaded with
+    /// [`SP1ProofWithPublicValues::load`], into a [`SP1ProofMode::Compressed`] proof.
+    ///
+    /// # Details
+    /// The proof is verified against `vk` and the version of this prover before it is compressed.
+    /// `deferred_proofs` are the compressed proofs the program verified, in the order it verified
+    /// them.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{Prover, ProverClient, SP1ProofWithPublicValues};
+    ///
+    /// let elf = &[1, 2, 3];
+    ///
+    /// let client = ProverClient::builder().cuda().build();
+    /// let (_, vk) = client.setup(elf);
+    /// let proof = SP1ProofWithPublicValues::load("core_proof.bin").unwrap();
+    /// let proof = client.compress(&vk, &proof, vec![]).unwrap();
+    /// let proof = client.wrap_groth16(&vk, &proof).unwrap();
+    /// ```
+    fn compress(
+        &self,
+        vk: &SP1VerifyingKey,
+        proof: &SP1ProofWithPublicValues,
+        deferred_proofs: Vec<SP1ReduceProof<InnerSC>>,
+    ) -> Result<SP1ProofWithPublicValues> {
+        let core_proof = core_proof_to_compress(&self.cpu_prover, self.version(), proof, vk)?;
+        let reduce_proof = self.cuda_prover.compress(vk, core_proof, deferred_proofs)?;
+        Ok(SP1ProofWithPublicValues {
+            proof: SP1Proof::Compressed(Box::new(reduce_proof)),
+            public_values: proof.public_values.clone(),
+            sp1_version: self.version().to_string(),
+        })
+    }
 
-            let proof = self.cpu_prover.wrap_groth16_bn254(outer_proof, &groth16_bn254_artifacts);
-            return Ok(SP1ProofWithPublicValues {
-                proof: SP1Proof::Groth16(proof),
-                public_values,
-                sp1_version: self.version().to_string(),
-            });
-        }
+    /// Shrinks a [`SP1ProofMode::Compressed`] proof generated before, e.g. with
+    /// [`Prover::compress`], into the proof of the shrink program.
+    ///
+    /// # Details
+    /// The proof is verified against `vk` and the version of this prover before it is shrunk.
+    fn shrink(
+        &self,
+        vk: &SP1VerifyingKey,
+        proof: &SP1ProofWithPublicValues,
+    ) -> Result<SP1ReduceProof<InnerSC>> {
+        let reduce_proof = compressed_proof_to_wrap(&self.cpu_prover, self.version(), proof, vk)?;
+        Ok(self.cuda_prover.shrink(reduce_proof)?)
+    }
 
-        unreachable!()
+    /// Shrinks and wraps a [`SP1ProofMode::Compressed`] proof generated before, e.g. with
+    /// [`Prover::compress`], into a [`SP1ProofMode::Groth16`] proof.
+    ///
+    /// # Details
+    /// The proof is verified against `vk` and the version of this prover before it is wrapped.
+    fn wrap_groth16(
+        &self,
+        vk: &SP1VerifyingKey,
+        proof: &SP1ProofWithPublicValues,
+    ) -> Result<SP1ProofWithPublicValues> {
+        let shrink_proof = self.shrink(vk, proof)?;
+        self.wrap_impl(shrink_proof, proof.public_values.clone(), SP1ProofMode::Groth16)
+    }
+
+    /// Shrinks and wraps a [`SP1ProofMode::Compressed`] proof generated before, e.g. with
+    /// [`Prover::compress`], into a [`SP1ProofMode::Plonk`] proof.
+    ///
+    /// # Details
+    /// The proof is verified against `vk` and the version of this prover before it is wrapped.
+    fn wrap_plonk(
+        &self,
+        vk: &SP1VerifyingKey,
+        proof: &SP1ProofWithPublicValues,
+    ) -> Result<SP1ProofWithPublicValues> {
+        let shrink_proof = self.shrink(vk, proof)?;
+        self.wrap_impl(shrink_proof, proof.public_values.clone(), SP1ProofMode::Plonk)
     }
 }
 
@@ -7,11 +7,14 @@ mod prove;
 
 use std::env;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use prove::EnvProveBuilder;
-use sp1_core_executor::SP1ContextBuilder;
+use sp1_core_executor::{SP1ContextBuilder, SP1ReduceProof};
 use sp1_core_machine::io::SP1Stdin;
-use sp1_prover::{components::CpuProverComponents, SP1Prover, SP1ProvingKey, SP1VerifyingKey};
+use sp1_prover::{
+    components::CpuProverComponents, InnerSC, SP1Prover, SP1ProvingKey, SP1VerifyingKey,
+    SP1VersionedVerifyingKey,
+};
 
 use super::{Prover, SP1VerificationError};
 use crate::cpu::execute::CpuExecuteBuilder;
@@ -36,8 +39,20 @@ impl EnvProver {
     /// - `SP1_PROVER`: The type of prover to use. Must be one of `mock`, `local`, `cuda`, or `network`.
     /// - `NETWORK_PRIVATE_KEY`: The private key to use for the network prover.
     /// - `NETWORK_RPC_URL`: The RPC URL to use for the network prover.
+    ///
+    /// # Panics
+    /// Panics if the environment is misconfigured. Use [`EnvProver::try_new`] to handle the error
+    /// instead.
     #[must_use]
     pub fn new() -> Self {
+        Self::try_new().unwrap_or_else(|e| panic!("{e:#}"))
+    }
+
+    /// Creates a new [`EnvProver`] with the given configuration, returning an error if the
+    /// environment is misconfigured.
+    ///
+    /// See [`EnvProver::new`] for the environment variables that are used.
+    pub fn try_new() -> Result<Self> {
         let mode = if let Ok(mode) = env::var("SP1_PROVER") {
             mode
         } else {
@@ -53,7 +68,7 @@ impl EnvProver {
             }
             "network" => {
                 #[cfg(not(feature = "network"))]
-                panic!(
+                bail!(
                     r#"The network prover requires the 'network' feature to be enabled.
                     Please enable it in your Cargo.toml with:
                     sp1-sdk = {{ version = "...", features = ["network"] }}"#
@@ -61,15 +76,15 @@ impl EnvProver {
 
                 #[cfg(feature = "network")]
                 {
-                    Box::new(NetworkProverBuilder::default().build())
+                    Box::new(NetworkProverBuilder::default().try_build()?)
                 }
             }
-            _ => panic!(
+            _ => bail!(
                 "Invalid SP1_PROVER value. Expected one of: mock, cpu, cuda, or network. Got: '{mode}'.\n\
                 Please set the SP1_PROVER environment variable to one of the supported values."
             ),
         };
-        EnvProver { prover }
+        Ok(EnvProver { prover })
     }
 
     /// Creates a new [`CpuExecuteBuilder`] for simulating the execution of a program on the CPU.
@@ -97,6 +112,7 @@ impl EnvProver {
             elf,
             stdin: stdin.clone(),
             context_builder: SP1ContextBuilder::default(),
+            max_cycles: None,
         }
     }
 
@@ -151,12 +167,39 @@ impl EnvProver {
         self.prover.verify(proof, vk)
     }
 
+    /// Verifies that the given proof is valid like [`Self::verify`], after checking that the
+    /// verifying key was generated by this version of SP1.
+    pub fn verify_versioned(
+        &self,
+        proof: &SP1ProofWithPublicValues,
+        vk: &SP1VersionedVerifyingKey,
+    ) -> Result<(), SP1VerificationError> {
+        self.prover.verify_versioned(proof, vk)
+    }
+
     /// Setup a program to be proven and verified by the SP1 RISC-V zkVM by computing the proving
     /// and verifying keys.
     #[must_use]
     pub fn setup(&self, elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey) {
         self.prover.setup(elf)
     }
+
+    /// Setup a program like [`Self::setup`], loading the keys from the
+    /// [`crate::SetupCache`] if they were computed before.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::ProverClient;
+    ///
+    /// let elf = &[1, 2, 3];
+    ///
+    /// let client = ProverClient::from_env();
+    /// let (pk, vk) = client.setup_cached(elf);
+    /// ```
+    #[must_use]
+    pub fn setup_cached(&self, elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey) {
+        self.prover.setup_cached(elf)
+    }
 }
 
 impl Default for EnvProver {
@@ -174,6 +217,10 @@ impl Prover<CpuProverComponents> for EnvProver {
         self.prover.setup(elf)
     }
 
+    fn setup_cached(&self, elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey) {
+        self.prover.setup_cached(elf)
+    }
+
     fn prove(
         &self,
         pk: &SP1ProvingKey,
@@ -182,4 +229,37 @@ impl Prover<CpuProverComponents> for EnvProver {
     ) -> Result<SP1ProofWithPublicValues> {
         self.prover.prove(pk, stdin, mode)
     }
+
+    fn compress(
+        &self,
+        vk: &SP1VerifyingKey,
+        proof: &SP1ProofWithPublicValues,
+        deferred_proofs: Vec<SP1ReduceProof<InnerSC>>,
+    ) -> Result<SP1ProofWithPublicValues> {
+        self.prover.compress(vk, proof, deferred_proofs)
+    }
+
+    fn shrink(
+        &self,
+        vk: &SP1VerifyingKey,
+        proof: &SP1ProofWithPublicValues,
+    ) -> Result<SP1ReduceProof<InnerSC>> {
+        self.prover.shrink(vk, proof)
+    }
+
+    fn wrap_groth16(
+        &self,
+        vk: &SP1VerifyingKey,
+        proof: &SP1ProofWithPublicValues,
+    ) -> Result<SP1ProofWithPublicValues> {
+        self.prover.wrap_groth16(vk, proof)
+    }
+
+    fn wrap_plonk(
+        &self,
+        vk: &SP1VerifyingKey,
+        proof: &SP1ProofWithPublicValues,
+    ) -> Result<SP1ProofWithPublicValues> {
+        self.prover.wrap_plonk(vk, proof)
+    }
 }
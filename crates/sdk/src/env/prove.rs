@@ -163,4 +163,31 @@ impl EnvProveBuilder<'_> {
 
         prover.prove(pk, &stdin, kind)
     }
+
+    /// Run the prover with the built arguments, for callers that are not async.
+    ///
+    /// # Details
+    /// This is the same as [`Self::run`], but returns
+    /// [`crate::network::Error::BlockingInAsyncContext`] if called from within an async runtime,
+    /// instead of blocking one of its threads. Network requests run on a runtime shared by the
+    /// blocking methods of the SDK, which is created on first use.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::from_env();
+    /// let (pk, vk) = client.setup(elf);
+    /// let proof = client.prove(&pk, &stdin)
+    ///     .run_blocking()
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "network")]
+    pub fn run_blocking(self) -> Result<SP1ProofWithPublicValues> {
+        crate::utils::ensure_not_in_async_context("run_blocking")?;
+        self.run()
+    }
 }
@@ -2,23 +2,34 @@
 //!
 //! A library for installing the SP1 circuit artifacts.
 
+use anyhow::{Context, Result};
 use cfg_if::cfg_if;
-use std::path::PathBuf;
+use std::{collections::BTreeSet, path::PathBuf, sync::Mutex};
 
 #[cfg(any(feature = "network", feature = "network"))]
 use {
     crate::utils::block_on,
+    anyhow::{bail, ensure},
     futures::StreamExt,
     indicatif::{ProgressBar, ProgressStyle},
-    reqwest::Client,
-    std::{cmp::min, process::Command},
+    reqwest::{header::RANGE, Client, StatusCode},
+    std::{cmp::min, fs::OpenOptions, io::Write, path::Path, process::Command},
 };
 
-use crate::SP1_CIRCUIT_VERSION;
+pub use sp1_prover::artifacts::{
+    circuit_artifacts_dir_override, verify_artifacts, verify_circuit_artifacts, ArtifactFile,
+    ArtifactManifest, ArtifactsError, CircuitArtifactsKind, CIRCUIT_ARTIFACTS_DIR_ENV,
+};
+
+use crate::{SP1ProofMode, SP1_CIRCUIT_VERSION};
 
 /// The base URL for the S3 bucket containing the circuit artifacts.
 pub const CIRCUIT_ARTIFACTS_URL_BASE: &str = "https://sp1-circuits.s3-us-east-2.amazonaws.com";
 
+/// The artifact directories checked by [`ensure_circuit_artifacts`] in this process, so that the
+/// proving keys are only hashed once.
+static VERIFIED_ARTIFACTS_DIRS: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+
 /// The directory where the groth16 circuit artifacts will be stored.
 #[must_use]
 pub fn groth16_circuit_artifacts_dir() -> PathBuf {
@@ -31,37 +42,90 @@ pub fn plonk_circuit_artifacts_dir() -> PathBuf {
     dirs::home_dir().unwrap().join(".sp1").join("circuits/plonk").join(SP1_CIRCUIT_VERSION)
 }
 
-/// Tries to install the groth16 circuit artifacts if they are not already installed.
+/// Tries to install the circuit artifacts if they are not already installed.
+///
+/// # Panics
+/// Panics if the artifacts are missing or corrupted, see [`ensure_circuit_artifacts`].
 #[must_use]
 pub fn try_install_circuit_artifacts(artifacts_type: &str) -> PathBuf {
-    let build_dir = if artifacts_type == "groth16" {
-        groth16_circuit_artifacts_dir()
-    } else if artifacts_type == "plonk" {
-        plonk_circuit_artifacts_dir()
-    } else {
-        unimplemented!("unsupported artifacts type: {}", artifacts_type);
-    };
+    ensure_circuit_artifacts(artifacts_type).unwrap_or_else(|err| panic!("[sp1] {err:#}"))
+}
 
-    if build_dir.exists() {
-        println!(
-            "[sp1] {} circuit artifacts already seem to exist at {}. if you want to re-download them, delete the directory",
-            artifacts_type,
-            build_dir.display()
-        );
+/// Returns the directory of the circuit artifacts of the given type, once they are checked
+/// against the manifest of this version.
+///
+/// # Details
+/// If [`CIRCUIT_ARTIFACTS_DIR_ENV`] is set, the artifacts are read from its subdirectory named
+/// after the type and are never downloaded. Otherwise they are read from
+/// [`groth16_circuit_artifacts_dir`] or [`plonk_circuit_artifacts_dir`], and downloaded there
+/// first if the directory does not exist and the `network` feature is enabled.
+///
+/// # Errors
+/// Returns an error naming the file and its expected hash if the artifacts are missing or
+/// corrupted. Corrupted artifacts are not downloaded again.
+pub fn ensure_circuit_artifacts(artifacts_type: &str) -> Result<PathBuf> {
+    let kind = circuit_artifacts_kind(artifacts_type);
+    let (build_dir, hint) = if let Some(dir) = circuit_artifacts_dir_override() {
+        (dir.join(kind.name()), format!("provision them in {CIRCUIT_ARTIFACTS_DIR_ENV}"))
     } else {
-        cfg_if! {
-            if #[cfg(any(feature = "network", feature = "network"))] {
-                println!(
-                    "[sp1] {} circuit artifacts for version {} do not exist at {}. downloading...",
-                    artifacts_type,
-                    SP1_CIRCUIT_VERSION,
-                    build_dir.display()
-                );
-                install_circuit_artifacts(build_dir.clone(), artifacts_type);
+        let build_dir = match kind {
+            CircuitArtifactsKind::Groth16 => groth16_circuit_artifacts_dir(),
+            CircuitArtifactsKind::Plonk => plonk_circuit_artifacts_dir(),
+        };
+        if !build_dir.exists() {
+            cfg_if! {
+                if #[cfg(any(feature = "network", feature = "network"))] {
+                    println!(
+                        "[sp1] {} circuit artifacts for version {} do not exist at {}. downloading...",
+                        artifacts_type,
+                        SP1_CIRCUIT_VERSION,
+                        build_dir.display()
+                    );
+                    install_circuit_artifacts(build_dir.clone(), artifacts_type);
+                }
             }
         }
+        let hint = format!(
+            "delete the directory to download them again or set {CIRCUIT_ARTIFACTS_DIR_ENV}"
+        );
+        (build_dir, hint)
+    };
+
+    // The lock is not held while hashing, which takes seconds for the proving keys, so that
+    // checking one directory does not block the callers of another. Concurrent first calls for the
+    // same directory may both hash it.
+    if VERIFIED_ARTIFACTS_DIRS.lock().unwrap().contains(&build_dir) {
+        return Ok(build_dir);
+    }
+    verify_circuit_artifacts(&build_dir, kind).with_context(|| {
+        format!(
+            "the {kind} circuit artifacts of {SP1_CIRCUIT_VERSION} at {} are unusable, {hint}",
+            build_dir.display()
+        )
+    })?;
+    VERIFIED_ARTIFACTS_DIRS.lock().unwrap().insert(build_dir.clone());
+    Ok(build_dir)
+}
+
+/// Checks the circuit artifacts needed to prove in the given mode, if any, so that missing or
+/// corrupted artifacts fail the proving before the core proof is generated.
+pub(crate) fn ensure_circuit_artifacts_for(mode: SP1ProofMode) -> Result<()> {
+    if sp1_prover::build::sp1_dev_mode() {
+        return Ok(());
+    }
+    match mode {
+        SP1ProofMode::Groth16 => ensure_circuit_artifacts("groth16").map(drop),
+        SP1ProofMode::Plonk => ensure_circuit_artifacts("plonk").map(drop),
+        SP1ProofMode::Core | SP1ProofMode::Compressed => Ok(()),
+    }
+}
+
+fn circuit_artifacts_kind(artifacts_type: &str) -> CircuitArtifactsKind {
+    match artifacts_type {
+        "groth16" => CircuitArtifactsKind::Groth16,
+        "plonk" => CircuitArtifactsKind::Plonk,
+        _ => unimplemented!("unsupported artifacts type: {}", artifacts_type),
     }
-    build_dir
 }
 
 /// Install the latest circuit artifacts.
@@ -71,31 +135,147 @@ pub fn try_install_circuit_artifacts(artifacts_type: &str) -> PathBuf {
 #[cfg(any(feature = "network", feature = "network"))]
 #[allow(clippy::needless_pass_by_value)]
 pub fn install_circuit_artifacts(build_dir: PathBuf, artifacts_type: &str) {
-    // Create the build directory.
-    std::fs::create_dir_all(&build_dir).expect("failed to create build directory");
-
-    // Download the artifacts.
-    let download_url =
-        format!("{CIRCUIT_ARTIFACTS_URL_BASE}/{SP1_CIRCUIT_VERSION}-{artifacts_type}.tar.gz");
-    let mut artifacts_tar_gz_file =
-        tempfile::NamedTempFile::new().expect("failed to create tempfile");
-    let client = Client::builder().build().expect("failed to create reqwest client");
-    block_on(download_file(&client, &download_url, &mut artifacts_tar_gz_file))
-        .expect("failed to download file");
+    let kind = circuit_artifacts_kind(artifacts_type);
+    let pb = download_progress_bar(0);
+    download_circuit_artifacts(&build_dir, kind, &mut |progress: DownloadProgress| {
+        pb.set_length(progress.total);
+        pb.set_position(progress.downloaded);
+    })
+    .expect("failed to download the circuit artifacts");
+    pb.finish();
+
+    println!(
+        "[sp1] downloaded {} to {:?}",
+        circuit_artifacts_url(kind),
+        build_dir.to_str().unwrap(),
+    );
+}
+
+/// The progress of a download of circuit artifacts, reported to the callback of
+/// [`download_artifacts`].
+#[cfg(any(feature = "network", feature = "network"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DownloadProgress {
+    /// The kind of the artifacts being downloaded.
+    pub kind: CircuitArtifactsKind,
+    /// The number of bytes of the tarball downloaded so far, including the ones downloaded before
+    /// the download was resumed.
+    pub downloaded: u64,
+    /// The size of the tarball in bytes.
+    pub total: u64,
+}
+
+/// Downloads the circuit artifacts of every kind for this version into `dir`, laid out as
+/// expected by [`CIRCUIT_ARTIFACTS_DIR_ENV`], and checks them against the manifest of this
+/// version.
+///
+/// # Details
+/// The artifacts of a kind that are already in `dir` and match the manifest are not downloaded
+/// again, and an interrupted download resumes from the partially downloaded tarball next to the
+/// directory of its kind.
+///
+/// # Example
+/// ```rust,no_run
+/// use sp1_sdk::install::download_artifacts;
+///
+/// let manifest = download_artifacts("/opt/sp1/circuits", |progress| {
+///     println!("{}: {}/{} bytes", progress.kind, progress.downloaded, progress.total);
+/// })
+/// .unwrap();
+/// ```
+#[cfg(any(feature = "network", feature = "network"))]
+pub fn download_artifacts(
+    dir: impl AsRef<Path>,
+    mut progress_callback: impl FnMut(DownloadProgress),
+) -> Result<ArtifactManifest> {
+    let dir = dir.as_ref();
+    for kind in CircuitArtifactsKind::ALL {
+        let build_dir = dir.join(kind.name());
+        if verify_circuit_artifacts(&build_dir, kind).is_ok() {
+            continue;
+        }
+        download_circuit_artifacts(&build_dir, kind, &mut progress_callback)?;
+    }
+    Ok(verify_artifacts(dir)?)
+}
+
+/// The URL of the tarball of the circuit artifacts of the given kind for this version.
+#[cfg(any(feature = "network", feature = "network"))]
+fn circuit_artifacts_url(kind: CircuitArtifactsKind) -> String {
+    format!("{CIRCUIT_ARTIFACTS_URL_BASE}/{SP1_CIRCUIT_VERSION}-{kind}.tar.gz")
+}
+
+/// Downloads the tarball of the circuit artifacts of the given kind next to `build_dir` and
+/// extracts it to `build_dir`.
+#[cfg(any(feature = "network", feature = "network"))]
+fn download_circuit_artifacts(
+    build_dir: &Path,
+    kind: CircuitArtifactsKind,
+    progress_callback: &mut impl FnMut(DownloadProgress),
+) -> Result<()> {
+    let url = circuit_artifacts_url(kind);
+    let parent = build_dir.parent().unwrap();
+    std::fs::create_dir_all(parent).context("failed to create build directory")?;
+    let tarball = parent.join(format!("{SP1_CIRCUIT_VERSION}-{kind}.tar.gz.part"));
+
+    let client = Client::builder().build().context("failed to create reqwest client")?;
+    block_on(download_file_resumable(&client, &url, &tarball, |downloaded, total| {
+        progress_callback(DownloadProgress { kind, downloaded, total });
+    }))?;
 
     // Extract the tarball to the build directory.
-    let mut res = Command::new("tar")
-        .args([
-            "-Pxzf",
-            artifacts_tar_gz_file.path().to_str().unwrap(),
-            "-C",
-            build_dir.to_str().unwrap(),
-        ])
-        .spawn()
-        .expect("failed to extract tarball");
-    res.wait().unwrap();
-
-    println!("[sp1] downloaded {} to {:?}", download_url, build_dir.to_str().unwrap(),);
+    std::fs::create_dir_all(build_dir).context("failed to create build directory")?;
+    let status = Command::new("tar")
+        .args(["-Pxzf", tarball.to_str().unwrap(), "-C", build_dir.to_str().unwrap()])
+        .status()
+        .context("failed to extract tarball")?;
+    ensure!(status.success(), "failed to extract {} to {}", url, build_dir.display());
+    std::fs::remove_file(&tarball).context("failed to remove tarball")?;
+    Ok(())
+}
+
+/// Download the file to `path`, resuming from the bytes already written to it.
+#[cfg(any(feature = "network", feature = "network"))]
+async fn download_file_resumable(
+    client: &Client,
+    url: &str,
+    path: &Path,
+    mut progress: impl FnMut(u64, u64),
+) -> Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let mut downloaded = file.metadata()?.len();
+
+    let mut request = client.get(url);
+    if downloaded > 0 {
+        request = request.header(RANGE, format!("bytes={downloaded}-"));
+    }
+    let res = request.send().await.with_context(|| format!("Failed to GET from '{url}'"))?;
+    let content_length = || {
+        res.content_length().with_context(|| format!("Failed to get content length from '{url}'"))
+    };
+    let total = match res.status() {
+        // The file was downloaded completely before.
+        StatusCode::RANGE_NOT_SATISFIABLE if downloaded > 0 => return Ok(()),
+        StatusCode::PARTIAL_CONTENT => downloaded + content_length()?,
+        status if status.is_success() => {
+            // The server sent the whole file, so the download starts over.
+            file.set_len(0)?;
+            downloaded = 0;
+            content_length()?
+        }
+        status => bail!("Failed to GET from '{url}': {status}"),
+    };
+
+    progress(downloaded, total);
+    let mut stream = res.bytes_stream();
+    while let Some(item) = stream.next().await {
+        let chunk = item.context("Error while downloading file")?;
+        file.write_all(&chunk).context("Error while writing to file")?;
+        downloaded = min(downloaded + (chunk.len() as u64), total);
+        progress(downloaded, total);
+    }
+
+    Ok(())
 }
 
 /// Download the file with a progress bar that indicates the progress.
@@ -110,10 +290,7 @@ pub async fn download_file(
     let total_size =
         res.content_length().ok_or(format!("Failed to get content length from '{}'", &url))?;
 
-    let pb = ProgressBar::new(total_size);
-    pb.set_style(ProgressStyle::default_bar()
-        .template("{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta})").unwrap()
-        .progress_chars("#>-"));
+    let pb = download_progress_bar(total_size);
 
     let mut downloaded: u64 = 0;
     let mut stream = res.bytes_stream();
@@ -128,3 +305,12 @@ pub async fn download_file(
 
     Ok(())
 }
+
+#[cfg(any(feature = "network", feature = "network"))]
+fn download_progress_bar(total_size: u64) -> ProgressBar {
+    let pb = ProgressBar::new(total_size);
+    pb.set_style(ProgressStyle::default_bar()
+        .template("{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta})").unwrap()
+        .progress_chars("#>-"));
+    pb
+}
@@ -32,6 +32,7 @@ pub mod env;
 pub mod install;
 #[cfg(feature = "network")]
 pub mod network;
+pub mod setup_cache;
 pub mod utils;
 
 // Re-export the client.
@@ -51,25 +52,45 @@ pub mod prover;
 pub use prover::Prover;
 pub use prover::SP1VerificationError;
 
+// Re-export the execution errors.
+pub use cpu::execute::ExecutionLimitExceeded;
+
 // Re-export the build utilities and executor primitives.
 pub use sp1_build::include_elf;
-pub use sp1_core_executor::{ExecutionReport, Executor, HookEnv, SP1Context, SP1ContextBuilder};
+pub use sp1_core_executor::{
+    ExecutionReport, Executor, GasTable, GuestOutput, HookEnv, HookStats, MemoryStats, ProverGas,
+    SP1Context, SP1ContextBuilder,
+};
 
 // Re-export the machine/prover primitives.
-pub use sp1_core_machine::io::SP1Stdin;
+pub use sp1_core_machine::io::{SP1Stdin, StdinEntry};
 pub use sp1_primitives::io::SP1PublicValues;
 pub use sp1_prover::{
-    HashableKey, ProverMode, SP1Prover, SP1ProvingKey, SP1VerifyingKey, SP1_CIRCUIT_VERSION,
+    HashableKey, ProverMode, SP1Prover, SP1ProvingKey, SP1VerifyingKey, SP1VersionedVerifyingKey,
+    SP1_CIRCUIT_VERSION,
 };
+pub use sp1_stark::{
+    NoopObserver, ProverEvent, ProverObserver, ProverStage, ProvingReport, ShardTiming,
+    StageStatus, TracingObserver,
+};
+
+// Re-export the setup cache.
+pub use setup_cache::{clear_cache, SetupCache, SetupCacheError};
 
 // Re-export the utilities.
 pub use utils::setup_logger;
 
 #[cfg(test)]
 mod tests {
+    use sha2::{Digest, Sha256};
+    use sp1_core_executor::{syscalls::SyscallCode, ExecutionError};
     use sp1_primitives::io::SP1PublicValues;
 
-    use crate::{utils, Prover, ProverClient, SP1Stdin};
+    use crate::{
+        utils, ExecutionLimitExceeded, HashableKey, HookStats, Prover, ProverClient,
+        SP1ProofWithPublicValues, SP1Stdin, SP1VerificationError, SP1VerifyingKey,
+        SP1_CIRCUIT_VERSION,
+    };
 
     #[test]
     fn test_execute() {
@@ -92,6 +113,150 @@ mod tests {
         client.execute(elf, &stdin).run().unwrap();
     }
 
+    #[test]
+    fn test_execute_guest_panic() {
+        utils::setup_logger();
+        let client = ProverClient::builder().cpu().build();
+        let elf = test_artifacts::PANIC_MESSAGE_ELF;
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&5u32);
+        stdin.write(&false);
+
+        let err = client.execute(elf, &stdin).run().unwrap_err();
+        let Some(ExecutionError::GuestPanic { message, location }) = err.downcast_ref() else {
+            panic!("expected the program to report a panic, got {err}");
+        };
+        assert_eq!(message, "message of 5 bytes: aaaaa");
+        assert_eq!(location.line, 12);
+        assert!(err.to_string().contains("main.rs:12"));
+    }
+
+    #[test]
+    fn test_execute_typed_hints() {
+        utils::setup_logger();
+        let client = ProverClient::builder().cpu().build();
+        let elf = test_artifacts::TYPED_HINTS_ELF;
+        let panic_message = |stdin: &SP1Stdin| {
+            let err = client.execute(elf, stdin).run().unwrap_err();
+            match err.downcast_ref() {
+                Some(ExecutionError::GuestPanic { message, .. }) => message.clone(),
+                _ => panic!("expected the program to report a panic, got {err}"),
+            }
+        };
+
+        // Typed hints are read back with their types, interleaved with untagged inputs.
+        let mut stdin = SP1Stdin::new();
+        stdin.write_hint(&5u32);
+        stdin.write(&vec![1u8, 2, 3]);
+        stdin.write_hint(&(6u64, "six".to_string()));
+        let (mut public_values, _) = client.execute(elf, &stdin).run().unwrap();
+        let values = public_values.read::<(u32, Vec<u8>, u64, String)>();
+        assert_eq!(values, (5, vec![1, 2, 3], 6, "six".to_string()));
+
+        // A hint written with another type aborts the program.
+        let mut stdin = SP1Stdin::new();
+        stdin.write_hint(&5u32);
+        stdin.write(&vec![1u8, 2, 3]);
+        stdin.write_hint(&(6u64, 7u64));
+        assert_eq!(
+            panic_message(&stdin),
+            "expected hint type (u64, alloc::string::String), found (u64, u64) at hint index 1"
+        );
+
+        // So does an untagged input read as a typed hint.
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&5u32);
+        assert_eq!(
+            panic_message(&stdin),
+            "expected hint type u32, found an untagged input at hint index 0"
+        );
+    }
+
+    #[test]
+    fn test_execute_hook_request() {
+        utils::setup_logger();
+        let client = ProverClient::builder().cpu().build();
+        let elf = test_artifacts::HOOK_REQUEST_ELF;
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&vec![1u32, 2, 3]);
+
+        // The hook answers each request with two values, read back by the program in order.
+        let (mut public_values, report) = client
+            .execute(elf, &stdin)
+            .with_hook(100, |_, request| {
+                let key = u32::from_le_bytes(request.try_into().unwrap());
+                vec![(u64::from(key) * 2).to_le_bytes().to_vec(), format!("key {key}").into_bytes()]
+            })
+            .run()
+            .unwrap();
+        let values = public_values.read::<Vec<(u32, Vec<u8>, String)>>();
+        let expected = [38u32, 69, 100]
+            .map(|key| (key, (u64::from(key) * 2).to_le_bytes().to_vec(), format!("key {key}")));
+        assert_eq!(values, expected);
+        assert_eq!(
+            report.hook_invocations[&100],
+            HookStats { invocations: 3, request_bytes: 12, response_bytes: 3 * 8 + 6 + 6 + 7 }
+        );
+
+        // Without a hook registered for the requests, the execution fails.
+        let err = client.execute(elf, &stdin).run().unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ExecutionError>(),
+            Some(&ExecutionError::UnregisteredHook(100))
+        );
+    }
+
+    #[test]
+    fn test_execute_named_inputs() {
+        utils::setup_logger();
+        let client = ProverClient::builder().cpu().build();
+        let elf = test_artifacts::NAMED_INPUTS_ELF;
+        let panic_message = |stdin: &SP1Stdin| {
+            let err = client.execute(elf, stdin).run().unwrap_err();
+            match err.downcast_ref() {
+                Some(ExecutionError::GuestPanic { message, .. }) => message.clone(),
+                _ => panic!("expected the program to report a panic, got {err}"),
+            }
+        };
+
+        // Named inputs are read back under their names, interleaved with unnamed inputs.
+        let mut stdin = SP1Stdin::new();
+        stdin.write_named("header", &5u32);
+        stdin.write(&vec![1u8, 2, 3]);
+        stdin.write_named("body", &(6u64, "six".to_string()));
+        let (mut public_values, _) = client.execute(elf, &stdin).run().unwrap();
+        let values = public_values.read::<(u32, Vec<u8>, u64, String)>();
+        assert_eq!(values, (5, vec![1, 2, 3], 6, "six".to_string()));
+
+        // Inputs written out of order abort the program.
+        let mut stdin = SP1Stdin::new();
+        stdin.write_named("body", &(6u64, "six".to_string()));
+        stdin.write(&vec![1u8, 2, 3]);
+        stdin.write_named("header", &5u32);
+        assert_eq!(
+            panic_message(&stdin),
+            "expected input `header` of type u32, found input `body` of type \
+             (u64, alloc::string::String) at named input index 0"
+        );
+
+        // So does an input written with another type.
+        let mut stdin = SP1Stdin::new();
+        stdin.write_named("header", &5u64);
+        assert_eq!(
+            panic_message(&stdin),
+            "expected input `header` of type u32, found input `header` of type u64 at named \
+             input index 0"
+        );
+
+        // And an unnamed input read as a named input.
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&5u32);
+        assert_eq!(
+            panic_message(&stdin),
+            "expected input `header` of type u32, found an unnamed input at named input index 0"
+        );
+    }
+
     #[should_panic]
     #[test]
     fn test_cycle_limit_fail() {
@@ -103,6 +268,58 @@ mod tests {
         client.execute(elf, &stdin).cycle_limit(1).run().unwrap();
     }
 
+    #[test]
+    fn test_execute_max_cycles() {
+        utils::setup_logger();
+        let client = ProverClient::builder().cpu().build();
+        let elf = test_artifacts::INFINITE_LOOP_ELF;
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&42u32);
+
+        let err = client.execute(elf, &stdin).max_cycles(100_000).run().unwrap_err();
+        let mut err = err.downcast::<ExecutionLimitExceeded>().unwrap();
+        assert_eq!(err.max_cycles, 100_000);
+        assert_eq!(err.report.total_instruction_count(), 100_000);
+        assert!(err.report.syscall_counts[SyscallCode::WRITE] > 0);
+        assert_eq!(err.report.syscall_counts[SyscallCode::HALT], 0);
+        assert_ne!(err.last_pc, 0);
+        assert_eq!(err.public_values.read::<u32>(), 42);
+    }
+
+    #[test]
+    fn test_prove_refuses_exceeded_cycle_limit() {
+        utils::setup_logger();
+        let client = ProverClient::builder().cpu().build();
+        let elf = test_artifacts::INFINITE_LOOP_ELF;
+        let (pk, _) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&42u32);
+
+        // The program is executed up to the limit before any proving work starts.
+        let err = client.prove(&pk, &stdin).cycle_limit(100_000).run().unwrap_err();
+        let err = err.downcast::<ExecutionLimitExceeded>().unwrap();
+        assert_eq!(err.report.total_instruction_count(), 100_000);
+    }
+
+    #[test]
+    fn test_execute_read_chunks() {
+        utils::setup_logger();
+        let client = ProverClient::builder().cpu().build();
+        let elf = test_artifacts::READ_CHUNKS_ELF;
+        let input = (0..(1 << 20) + 1234).map(|i: u32| (i % 251) as u8).collect::<Vec<_>>();
+        let mut stdin = SP1Stdin::new();
+        stdin.write_reader(&input[..]).unwrap();
+        stdin.write(&7u32);
+
+        let (mut public_values, _) = client.execute(elf, &stdin).run().unwrap();
+        // The input is read in 64KB chunks, the last one partial.
+        assert_eq!(public_values.read::<u32>(), 17);
+        assert_eq!(public_values.read::<u32>(), 7);
+        let mut hash = [0u8; 32];
+        public_values.read_slice(&mut hash);
+        assert_eq!(hash[..], Sha256::digest(&input)[..]);
+    }
+
     #[test]
     fn test_e2e_core() {
         utils::setup_logger();
@@ -123,6 +340,75 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_verify_version_mismatch() {
+        utils::setup_logger();
+        let client = ProverClient::builder().cpu().build();
+        let elf = test_artifacts::FIBONACCI_ELF;
+        let (pk, vk) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let proof = client.prove(&pk, &stdin).run().unwrap();
+        let verifier_version = format!("sp1-{SP1_CIRCUIT_VERSION}");
+
+        // A proof generated by another version is rejected before it is verified.
+        let mut stale_proof = proof.clone();
+        stale_proof.sp1_version = "v3.0.0".to_string();
+        match client.verify(&stale_proof, &vk) {
+            Err(SP1VerificationError::VersionMismatch { proof_version, verifier_version: v }) => {
+                assert_eq!(proof_version, "sp1-v3.0.0");
+                assert_eq!(v, verifier_version);
+            }
+            other => panic!("expected a version mismatch, got {other:?}"),
+        }
+
+        // So is a verifying key generated by another version, in the network's format.
+        let versioned_vk = SP1VersionedVerifyingKey::new(vk.clone());
+        assert_eq!(versioned_vk.sp1_version, SP1_CIRCUIT_VERSION);
+        client.verify_versioned(&proof, &versioned_vk).unwrap();
+        let mut stale_vk = versioned_vk.clone();
+        stale_vk.sp1_version = "sp1-v3.0.0".to_string();
+        match client.verify_versioned(&proof, &stale_vk) {
+            Err(SP1VerificationError::VerifyingKeyVersionMismatch { vk_version, .. }) => {
+                assert_eq!(vk_version, "sp1-v3.0.0");
+            }
+            other => panic!("expected a version mismatch, got {other:?}"),
+        }
+
+        // Proofs serialized before the version was embedded in them verify as before.
+        let mut legacy_proof = proof.clone();
+        legacy_proof.sp1_version = String::new();
+        client.verify(&legacy_proof, &vk).unwrap();
+    }
+
+    #[test]
+    fn test_verifying_key_layout() {
+        let client = ProverClient::builder().cpu().build();
+        let (_, vk) = client.setup(test_artifacts::FIBONACCI_ELF);
+
+        // Keys serialized before the versioned key was introduced, which other payloads embed,
+        // keep their layout: a key is its `vk` field alone.
+        let legacy_bytes = bincode::serialize(&vk.vk).unwrap();
+        assert_eq!(bincode::serialize(&vk).unwrap(), legacy_bytes);
+        let legacy_vk: SP1VerifyingKey = bincode::deserialize(&legacy_bytes).unwrap();
+        assert_eq!(legacy_vk.bytes32(), vk.bytes32());
+
+        let legacy_json = serde_json::json!({ "vk": vk.vk });
+        let legacy_vk: SP1VerifyingKey = serde_json::from_value(legacy_json).unwrap();
+        assert_eq!(legacy_vk.bytes32(), vk.bytes32());
+
+        // A truncated key is an error rather than a key without a version.
+        assert!(bincode::deserialize::<SP1VerifyingKey>(&legacy_bytes[..legacy_bytes.len() / 2])
+            .is_err());
+
+        // The versioned key round trips with its version.
+        let versioned_vk = SP1VersionedVerifyingKey::new(vk.clone());
+        let decoded: SP1VersionedVerifyingKey =
+            bincode::deserialize(&bincode::serialize(&versioned_vk).unwrap()).unwrap();
+        assert_eq!(decoded.sp1_version, SP1_CIRCUIT_VERSION);
+        assert_eq!(decoded.vk.bytes32(), vk.bytes32());
+    }
+
     #[test]
     fn test_e2e_compressed() {
         utils::setup_logger();
@@ -143,6 +429,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_compress_saved_core_proof() {
+        utils::setup_logger();
+        let client = ProverClient::builder().cpu().build();
+        let elf = test_artifacts::FIBONACCI_ELF;
+        let (pk, vk) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("proof.bin");
+
+        // Compress a core proof loaded from disk.
+        client.prove(&pk, &stdin).run().unwrap().save(&path).unwrap();
+        let core_proof = SP1ProofWithPublicValues::load(&path).unwrap();
+        let compressed_proof = client.compress(&vk, &core_proof, vec![]).unwrap();
+        assert_eq!(compressed_proof.public_values, core_proof.public_values);
+        client.verify(&compressed_proof, &vk).unwrap();
+
+        // Shrink and wrap the compressed proof loaded from disk.
+        compressed_proof.save(&path).unwrap();
+        let compressed_proof = SP1ProofWithPublicValues::load(&path).unwrap();
+        let shrink_proof = client.shrink(&vk, &compressed_proof).unwrap();
+        client.inner().verify_shrink(&shrink_proof, &vk).unwrap();
+        let plonk_proof = client.wrap_plonk(&vk, &compressed_proof).unwrap();
+        client.verify(&plonk_proof, &vk).unwrap();
+
+        // Proofs of the wrong kind or version are rejected before being compressed or wrapped.
+        assert!(client.compress(&vk, &compressed_proof, vec![]).is_err());
+        assert!(client.shrink(&vk, &core_proof).is_err());
+        assert!(client.wrap_groth16(&vk, &core_proof).is_err());
+        let mut stale_proof = core_proof.clone();
+        stale_proof.sp1_version = "v3.0.0".to_string();
+        assert!(client.compress(&vk, &stale_proof, vec![]).is_err());
+
+        // So are proofs that don't verify.
+        let mut invalid_proof = core_proof;
+        invalid_proof.public_values = SP1PublicValues::from(&[255, 4, 84]);
+        assert!(client.compress(&vk, &invalid_proof, vec![]).is_err());
+    }
+
     #[test]
     fn test_e2e_prove_plonk() {
         utils::setup_logger();
@@ -173,6 +499,45 @@ mod tests {
         stdin.write(&10usize);
         let proof = client.prove(&pk, &stdin).plonk().run().unwrap();
         client.verify(&proof, &vk).unwrap();
+        assert!(proof.is_mock());
+        assert_eq!(proof.bytes()[..4], sp1_verifier::MOCK_PROOF_SELECTOR);
+
+        // Test that the real verifier rejects the mock proof.
+        let real_client = ProverClient::builder().cpu().build();
+        assert!(matches!(
+            real_client.verify(&proof, &vk),
+            Err(crate::SP1VerificationError::MockProof)
+        ));
+    }
+
+    #[test]
+    fn test_e2e_prove_groth16_mock() {
+        utils::setup_logger();
+        let client = ProverClient::builder().mock().build();
+        let elf = test_artifacts::FIBONACCI_ELF;
+        let (pk, vk) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let mut proof = client.prove(&pk, &stdin).groth16().run().unwrap();
+        client.verify(&proof, &vk).unwrap();
+        assert!(proof.is_mock());
+
+        // The mock proof is deterministic.
+        let other_proof = client.prove(&pk, &stdin).groth16().run().unwrap();
+        assert_eq!(proof.bytes(), other_proof.bytes());
+
+        // Test that the real verifier rejects the mock proof.
+        let real_client = ProverClient::builder().cpu().build();
+        assert!(matches!(
+            real_client.verify(&proof, &vk),
+            Err(crate::SP1VerificationError::MockProof)
+        ));
+
+        // Test invalid public values.
+        proof.public_values = SP1PublicValues::from(&[255, 4, 84]);
+        if client.verify(&proof, &vk).is_ok() {
+            panic!("verified proof with invalid public values")
+        }
     }
 }
 
@@ -0,0 +1,265 @@
+//! # Artifact Transport
+//!
+//! This module abstracts how the content of artifacts is uploaded to and downloaded from the
+//! artifact store, so that deployments which do not issue presigned URLs can be supported.
+
+use std::collections::BTreeMap;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use futures::{Stream, TryStreamExt};
+use reqwest::header::{CONTENT_ENCODING, CONTENT_LENGTH, ETAG};
+use reqwest::{Body, Response};
+use reqwest_middleware::ClientWithMiddleware as HttpClientWithMiddleware;
+
+use super::retry::HttpStatusError;
+use super::Error;
+
+/// The content of a downloaded artifact.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DownloadedArtifact {
+    /// The content of the artifact, as stored.
+    pub bytes: Vec<u8>,
+    /// The encoding the content is compressed with, if any.
+    pub content_encoding: Option<String>,
+}
+
+/// The content of an artifact streamed in chunks, e.g. as it is being encoded.
+pub type ArtifactStream = Pin<Box<dyn Stream<Item = std::io::Result<Vec<u8>>> + Send>>;
+
+/// Transfers the content of artifacts to and from the artifact store.
+///
+/// # Details
+/// The transport is selected by the scheme of the URI returned by the artifact store, e.g.
+/// `https://` for presigned URLs or `s3://` for objects accessed with the credentials of the
+/// client. See [`crate::network::NetworkClient::with_artifact_transport`].
+///
+/// The HTTP client of the network client is passed to each call, so that transfers go through
+/// the configured proxy and TLS roots. Failed transfers are retried by the network client
+/// according to its [`crate::network::RetryPolicy`].
+#[async_trait]
+pub trait ArtifactTransport: Send + Sync {
+    /// Uploads (part of) an artifact to the given URI, returning its entity tag, if any.
+    async fn upload(
+        &self,
+        http: &HttpClientWithMiddleware,
+        uri: &str,
+        body: &[u8],
+        content_encoding: Option<&str>,
+    ) -> Result<Option<String>>;
+
+    /// Uploads an artifact streamed in chunks to the given URI, returning its entity tag, if any.
+    ///
+    /// # Details
+    /// The stream yields exactly `content_length` bytes. The default implementation collects the
+    /// stream and calls [`ArtifactTransport::upload`], so transports should override it to keep
+    /// memory usage bounded by the size of the chunks.
+    async fn upload_stream(
+        &self,
+        http: &HttpClientWithMiddleware,
+        uri: &str,
+        body: ArtifactStream,
+        content_length: u64,
+        content_encoding: Option<&str>,
+    ) -> Result<Option<String>> {
+        let body = body.try_concat().await?;
+        if body.len() as u64 != content_length {
+            return Err(anyhow!("Expected {content_length} bytes, got {}", body.len()));
+        }
+        self.upload(http, uri, &body, content_encoding).await
+    }
+
+    /// Downloads an artifact from the given URI.
+    async fn download(
+        &self,
+        http: &HttpClientWithMiddleware,
+        uri: &str,
+    ) -> Result<DownloadedArtifact>;
+}
+
+/// Transfers artifacts through presigned `http://` or `https://` URLs.
+///
+/// # Details
+/// This is the default transport, used with the artifact store of the network.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PresignedUrlTransport;
+
+#[async_trait]
+impl ArtifactTransport for PresignedUrlTransport {
+    async fn upload(
+        &self,
+        http: &HttpClientWithMiddleware,
+        uri: &str,
+        body: &[u8],
+        content_encoding: Option<&str>,
+    ) -> Result<Option<String>> {
+        let mut request = http.put(uri).body(body.to_vec());
+        if let Some(encoding) = content_encoding {
+            request = request.header(CONTENT_ENCODING, encoding);
+        }
+        let response = request.send().await?;
+        upload_response(response)
+    }
+
+    async fn upload_stream(
+        &self,
+        http: &HttpClientWithMiddleware,
+        uri: &str,
+        body: ArtifactStream,
+        content_length: u64,
+        content_encoding: Option<&str>,
+    ) -> Result<Option<String>> {
+        // Presigned URLs do not accept chunked transfer encoding, so the length is sent upfront.
+        let mut request =
+            http.put(uri).header(CONTENT_LENGTH, content_length).body(Body::wrap_stream(body));
+        if let Some(encoding) = content_encoding {
+            request = request.header(CONTENT_ENCODING, encoding);
+        }
+        let response = request.send().await?;
+        upload_response(response)
+    }
+
+    async fn download(
+        &self,
+        http: &HttpClientWithMiddleware,
+        uri: &str,
+    ) -> Result<DownloadedArtifact> {
+        let response = http.get(uri).send().await.context("Failed to download from URI")?;
+        download_response(response).await
+    }
+}
+
+/// The artifact transports of a network client, by URI scheme.
+#[derive(Clone, Default)]
+pub(crate) struct ArtifactTransports {
+    schemes: BTreeMap<String, Arc<dyn ArtifactTransport>>,
+}
+
+impl ArtifactTransports {
+    /// Uses the given transport for URIs with the given scheme, e.g. `s3`.
+    pub(crate) fn insert(&mut self, scheme: &str, transport: Arc<dyn ArtifactTransport>) {
+        self.schemes.insert(scheme.to_ascii_lowercase(), transport);
+    }
+
+    /// Returns the transport for the given URI.
+    ///
+    /// # Details
+    /// `http` and `https` URIs are transferred as presigned URLs unless another transport is
+    /// registered for their scheme.
+    pub(crate) fn for_uri(&self, uri: &str) -> Result<Arc<dyn ArtifactTransport>> {
+        let scheme = uri_scheme(uri)
+            .ok_or_else(|| anyhow!("Artifact URI {uri} has no scheme"))?
+            .to_ascii_lowercase();
+        if let Some(transport) = self.schemes.get(&scheme) {
+            return Ok(transport.clone());
+        }
+        match scheme.as_str() {
+            "http" | "https" => Ok(Arc::new(PresignedUrlTransport)),
+            _ => Err(anyhow!(
+                "No artifact transport for {scheme}:// URIs. Register one with \
+                 NetworkClient::with_artifact_transport"
+            )),
+        }
+    }
+}
+
+/// Returns the scheme of a URI, e.g. `s3` for `s3://bucket/key`.
+fn uri_scheme(uri: &str) -> Option<&str> {
+    let (scheme, _) = uri.split_once("://")?;
+    let valid = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    valid.then_some(scheme)
+}
+
+/// Checks the response to an upload, returning the entity tag of the uploaded content.
+pub(crate) fn upload_response(response: Response) -> Result<Option<String>> {
+    if !response.status().is_success() {
+        let error = HttpStatusError::from_response(&response);
+        let reason = format!("HTTP {}", error.status);
+        return Err(anyhow::Error::new(error).context(Error::ArtifactUpload { reason }));
+    }
+    Ok(response.headers().get(ETAG).and_then(|value| value.to_str().ok()).map(String::from))
+}
+
+/// Checks the response to a download, returning the downloaded content.
+pub(crate) async fn download_response(response: Response) -> Result<DownloadedArtifact> {
+    if !response.status().is_success() {
+        let error = HttpStatusError::from_response(&response);
+        let context = format!("Failed to download artifact: HTTP {}", error.status);
+        return Err(anyhow::Error::new(error).context(context));
+    }
+
+    let content_encoding = response
+        .headers()
+        .get(CONTENT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from);
+    let bytes = response.bytes().await.context("Failed to read response body")?;
+    Ok(DownloadedArtifact { bytes: bytes.to_vec(), content_encoding })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A transport that returns a fixed entity tag, to tell which transport was selected.
+    struct NamedTransport;
+
+    #[async_trait]
+    impl ArtifactTransport for NamedTransport {
+        async fn upload(
+            &self,
+            _: &HttpClientWithMiddleware,
+            _: &str,
+            _: &[u8],
+            _: Option<&str>,
+        ) -> Result<Option<String>> {
+            Ok(Some("named".to_string()))
+        }
+
+        async fn download(
+            &self,
+            _: &HttpClientWithMiddleware,
+            _: &str,
+        ) -> Result<DownloadedArtifact> {
+            Ok(DownloadedArtifact::default())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_default_upload_stream() {
+        let http = HttpClientWithMiddleware::from(reqwest::Client::new());
+        let stream = || -> ArtifactStream {
+            Box::pin(futures::stream::iter([Ok(vec![1, 2]), Ok(vec![]), Ok(vec![3])]))
+        };
+        let etag = NamedTransport.upload_stream(&http, "s3://a/b", stream(), 3, None).await;
+        assert_eq!(etag.unwrap().as_deref(), Some("named"));
+        assert!(NamedTransport.upload_stream(&http, "s3://a/b", stream(), 4, None).await.is_err());
+    }
+
+    #[test]
+    fn test_uri_scheme() {
+        assert_eq!(
+            uri_scheme("https://bucket.s3.amazonaws.com/key?X-Amz-Signature=1"),
+            Some("https")
+        );
+        assert_eq!(uri_scheme("s3://bucket/artifacts/stdin"), Some("s3"));
+        assert_eq!(uri_scheme("bucket/key"), None);
+        assert_eq!(uri_scheme("://bucket/key"), None);
+    }
+
+    #[tokio::test]
+    async fn test_select_transport_by_scheme() {
+        let http = HttpClientWithMiddleware::from(reqwest::Client::new());
+        let mut transports = ArtifactTransports::default();
+        assert!(transports.for_uri("https://example.com/artifact").is_ok());
+        assert!(transports.for_uri("s3://bucket/key").is_err());
+
+        transports.insert("S3", Arc::new(NamedTransport));
+        let transport = transports.for_uri("s3://bucket/key").unwrap();
+        let etag = transport.upload(&http, "s3://bucket/key", b"", None).await.unwrap();
+        assert_eq!(etag.as_deref(), Some("named"));
+    }
+}
@@ -0,0 +1,91 @@
+//! # Auxiliary Artifacts
+//!
+//! This module provides the types of the artifacts that can be attached to an existing proof
+//! request, e.g. an execution report or a cycle tracker profile, for the fulfiller of the request
+//! or for later debugging.
+
+use alloy_primitives::B256;
+
+use super::proto::artifact::ArtifactType;
+
+/// The capability of servers that accept auxiliary artifacts.
+pub(crate) const AUXILIARY_ARTIFACTS_CAPABILITY: &str = "auxiliary_artifacts";
+
+/// The type of an auxiliary artifact attached to a proof request.
+///
+/// # Details
+/// A request has at most one artifact of each type, so attaching another artifact of the same
+/// type replaces it. [`AuxiliaryArtifactType::Custom`] types are user-defined, and are distinct
+/// from each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuxiliaryArtifactType {
+    /// An execution report, e.g. the cycle counts of a local execution.
+    ExecutionReport,
+    /// A trace, e.g. a cycle tracker profile.
+    Trace,
+    /// A user-defined artifact, identified by its type.
+    Custom(u32),
+}
+
+impl AuxiliaryArtifactType {
+    /// The user-defined type of the artifact, if it is a custom artifact.
+    #[must_use]
+    pub fn custom_type(self) -> Option<u32> {
+        match self {
+            Self::Custom(custom_type) => Some(custom_type),
+            Self::ExecutionReport | Self::Trace => None,
+        }
+    }
+}
+
+impl From<AuxiliaryArtifactType> for ArtifactType {
+    fn from(artifact_type: AuxiliaryArtifactType) -> Self {
+        match artifact_type {
+            AuxiliaryArtifactType::ExecutionReport => Self::ExecutionReport,
+            AuxiliaryArtifactType::Trace => Self::Trace,
+            AuxiliaryArtifactType::Custom(_) => Self::Custom,
+        }
+    }
+}
+
+/// The proof request an artifact is attached to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Attachment {
+    pub(crate) request_id: B256,
+    pub(crate) artifact_type: AuxiliaryArtifactType,
+}
+
+/// The message signed to attach an artifact to a request with the legacy signatures.
+///
+/// # Details
+/// The message includes the request ID, so that a signature cannot be replayed to attach an
+/// artifact to another request.
+pub(crate) fn create_message(request_id: B256) -> Vec<u8> {
+    [b"create_artifact".as_slice(), request_id.as_slice()].concat()
+}
+
+/// The message signed to get an artifact attached to a request with the legacy signatures.
+pub(crate) fn get_message(request_id: B256) -> Vec<u8> {
+    [b"get_auxiliary_artifact".as_slice(), request_id.as_slice()].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_artifact_type() {
+        assert_eq!(ArtifactType::from(AuxiliaryArtifactType::Trace), ArtifactType::Trace);
+        assert_eq!(ArtifactType::from(AuxiliaryArtifactType::Custom(7)), ArtifactType::Custom);
+        assert_eq!(AuxiliaryArtifactType::Custom(7).custom_type(), Some(7));
+        assert_eq!(AuxiliaryArtifactType::ExecutionReport.custom_type(), None);
+    }
+
+    #[test]
+    fn test_messages_bind_request_id() {
+        let message = create_message(B256::repeat_byte(1));
+        assert!(message.starts_with(b"create_artifact"));
+        assert_ne!(message, create_message(B256::repeat_byte(2)));
+        assert_ne!(get_message(B256::repeat_byte(1)), message);
+    }
+}
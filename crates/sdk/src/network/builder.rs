@@ -2,7 +2,15 @@
 //!
 //! This module provides a builder for the [`NetworkProver`].
 
-use crate::network::DEFAULT_NETWORK_RPC_URL;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+
+use crate::network::polling::PollingConfig;
+use crate::network::request_store::RequestStore;
+use crate::network::transport::TransportConfig;
+use crate::network::utils::NetworkSigner;
+use crate::network::{NetworkClientBuilder, DEFAULT_NETWORK_RPC_URL};
 use crate::NetworkProver;
 
 /// A builder for the [`NetworkProver`].
@@ -12,6 +20,10 @@ use crate::NetworkProver;
 pub struct NetworkProverBuilder {
     pub(crate) private_key: Option<String>,
     pub(crate) rpc_url: Option<String>,
+    pub(crate) signer: Option<Arc<dyn NetworkSigner>>,
+    pub(crate) polling: Option<PollingConfig>,
+    pub(crate) clock_sync: bool,
+    pub(crate) request_store: Option<Arc<dyn RequestStore>>,
 }
 
 impl NetworkProverBuilder {
@@ -35,6 +47,28 @@ impl NetworkProverBuilder {
         self
     }
 
+    /// Sets the signer used to sign requests.
+    ///
+    /// # Details
+    /// Sets a custom signer (e.g. a KMS or hardware wallet signer) that will be used to sign
+    /// requests sent to the network, instead of a raw private key. If a signer is set, the private
+    /// key is ignored.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient};
+    /// use alloy_signer_local::PrivateKeySigner;
+    ///
+    /// let prover = ProverClient::builder().network()
+    ///     .signer(PrivateKeySigner::random())
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn signer(mut self, signer: impl NetworkSigner + 'static) -> Self {
+        self.signer = Some(Arc::new(signer));
+        self
+    }
+
     /// Sets the remote procedure call URL.
     ///
     /// # Details
@@ -56,11 +90,79 @@ impl NetworkProverBuilder {
         self
     }
 
+    /// Sets how the status of proof requests is polled while waiting for their proofs.
+    ///
+    /// # Details
+    /// Polling is only used if the network does not support streaming status updates. See
+    /// [`PollingConfig`] for the defaults.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use sp1_sdk::{network::PollingConfig, ProverClient};
+    ///
+    /// let prover = ProverClient::builder()
+    ///     .network()
+    ///     .polling(PollingConfig { jitter_fraction: 0.2, ..Default::default() })
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn polling(mut self, polling: PollingConfig) -> Self {
+        self.polling = Some(polling);
+        self
+    }
+
+    /// Computes the deadlines of proof requests with the clock of the network.
+    ///
+    /// # Details
+    /// By default, requests fail with [`crate::network::Error::ClockSkew`] if the local clock is
+    /// too far off. With clock sync, the skew is compensated for instead. See
+    /// [`crate::network::NetworkClient::with_clock_sync`].
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::ProverClient;
+    ///
+    /// let prover = ProverClient::builder().network().clock_sync(true).build();
+    /// ```
+    #[must_use]
+    pub fn clock_sync(mut self, clock_sync: bool) -> Self {
+        self.clock_sync = clock_sync;
+        self
+    }
+
+    /// Persists submitted proof requests in the given store until they are resolved.
+    ///
+    /// # Details
+    /// See [`NetworkProver::with_request_store`]. Resume waiting for the requests left in the
+    /// store after a restart with [`NetworkProver::recover_pending`].
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{network::JsonFileRequestStore, ProverClient};
+    ///
+    /// let prover = ProverClient::builder()
+    ///     .network()
+    ///     .request_store(JsonFileRequestStore::new("pending-requests.json"))
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn request_store(mut self, store: impl RequestStore + 'static) -> Self {
+        self.request_store = Some(Arc::new(store));
+        self
+    }
+
     /// Builds a [`NetworkProver`].
     ///
     /// # Details
     /// This method will build a [`NetworkProver`] with the given parameters. If the private key is
-    /// not provided, the method will look for the `NETWORK_PRIVATE_KEY` environment variable.
+    /// not provided, the method will look for the `NETWORK_PRIVATE_KEY` environment variable. The
+    /// `SP1_HTTPS_PROXY` and `SP1_EXTRA_CA_CERT` environment variables can be used to reach the
+    /// network through a proxy and to trust an additional certificate authority (PEM file).
+    ///
+    /// # Panics
+    /// Panics if the private key is missing or malformed. Use [`NetworkProverBuilder::try_build`]
+    /// to handle the error instead.
     ///
     /// # Example
     /// ```rust,no_run
@@ -74,19 +176,55 @@ impl NetworkProverBuilder {
     /// ```
     #[must_use]
     pub fn build(self) -> NetworkProver {
-        let private_key = match self.private_key {
-            Some(private_key) => private_key,
-            None => std::env::var("NETWORK_PRIVATE_KEY").expect(
-                "NETWORK_PRIVATE_KEY environment variable is not set. \
-                Please set it to your private key or use the .private_key() method.",
-            ),
-        };
+        self.try_build().unwrap_or_else(|e| panic!("{e:#}"))
+    }
 
+    /// Builds a [`NetworkProver`], returning an error if the private key is missing or malformed.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient};
+    ///
+    /// let prover = ProverClient::builder()
+    ///     .network()
+    ///     .private_key("...")
+    ///     .try_build()
+    ///     .unwrap();
+    /// ```
+    pub fn try_build(self) -> Result<NetworkProver> {
         let rpc_url = match self.rpc_url {
             Some(rpc_url) => rpc_url,
             None => std::env::var("NETWORK_RPC_URL").unwrap_or(DEFAULT_NETWORK_RPC_URL.to_string()),
         };
 
-        NetworkProver::new(&private_key, &rpc_url)
+        // Pick up the proxy and TLS settings from the environment.
+        let mut client = NetworkClientBuilder {
+            rpc_url: Some(rpc_url),
+            transport: TransportConfig::from_env()?,
+            ..Default::default()
+        };
+
+        if let Some(signer) = self.signer {
+            client.signer = Some(signer);
+        } else {
+            client.private_key = Some(match self.private_key {
+                Some(private_key) => private_key,
+                None => std::env::var("NETWORK_PRIVATE_KEY").map_err(|_| {
+                    anyhow!(
+                        "NETWORK_PRIVATE_KEY environment variable is not set. \
+                        Please set it to your private key or use the .private_key() method."
+                    )
+                })?,
+            });
+        }
+
+        let mut client = client.build()?;
+        if let Some(polling) = self.polling {
+            client = client.with_polling_config(polling);
+        }
+        client = client.with_clock_sync(self.clock_sync);
+        let mut prover = NetworkProver::with_client(client);
+        prover.request_store = self.request_store;
+        Ok(prover)
     }
 }
@@ -0,0 +1,169 @@
+//! # On-chain Calldata
+//!
+//! This module extracts the calldata to verify a Plonk or Groth16 proof on-chain from the raw
+//! proof artifact, without deserializing the full [`crate::SP1ProofWithPublicValues`].
+
+use serde::de::{self, Deserializer};
+use serde::Deserialize;
+
+use super::Error;
+use crate::{SP1ProofMode, SP1PublicValues};
+
+/// The error message for proofs that cannot be verified on-chain.
+const UNSUPPORTED_MODE: &str = "only Plonk and Groth16 proofs can be verified on-chain";
+
+/// The calldata to verify a Plonk or Groth16 proof with the SP1 verifier contracts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OnchainCalldata {
+    /// The mode of the proof, either [`SP1ProofMode::Plonk`] or [`SP1ProofMode::Groth16`].
+    pub mode: SP1ProofMode,
+    /// The proof, in the encoding accepted by the verifier contracts. Empty for mock proofs.
+    pub proof: Vec<u8>,
+    /// The public values committed to by the program.
+    pub public_values: Vec<u8>,
+    /// The version of SP1 the proof was generated with.
+    pub sp1_version: String,
+}
+
+impl OnchainCalldata {
+    /// Extracts the calldata from a raw proof artifact, e.g. as returned by
+    /// [`crate::network::NetworkClient::get_proof_request_bytes`].
+    ///
+    /// # Details
+    /// The proof is encoded the same way as by [`crate::SP1ProofWithPublicValues::bytes`]. Only
+    /// the parts of the artifact needed for the calldata are decoded, so the proof types of the
+    /// prover are not needed. Returns an error if the artifact is not a Plonk or Groth16 proof.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::network::{NetworkClient, OnchainCalldata, B256};
+    ///
+    /// tokio_test::block_on(async {
+    ///     let client = NetworkClient::new("0x...", "https://rpc.production.succinct.xyz").unwrap();
+    ///     let request_id = B256::repeat_byte(1);
+    ///     let (_, artifact) = client.get_proof_request_bytes(request_id, None).await.unwrap();
+    ///     if let Some(artifact) = artifact {
+    ///         let calldata = OnchainCalldata::from_proof_artifact(&artifact).unwrap();
+    ///     }
+    /// })
+    /// ```
+    pub fn from_proof_artifact(artifact: &[u8]) -> Result<Self, Error> {
+        let artifact: OnchainProofArtifact = bincode::deserialize(artifact).map_err(|e| {
+            Error::from(anyhow::anyhow!("Failed to extract calldata from proof: {e}"))
+        })?;
+        let (mode, proof) = match artifact.proof {
+            OnchainProof::Core(_) | OnchainProof::Compressed(_) => {
+                return Err(Error::from(anyhow::anyhow!(UNSUPPORTED_MODE)));
+            }
+            OnchainProof::Plonk(proof) => (SP1ProofMode::Plonk, proof),
+            OnchainProof::Groth16(proof) => (SP1ProofMode::Groth16, proof),
+        };
+
+        // Mock proofs are empty, which the mock verifier expects as is.
+        let proof = if proof.encoded_proof.is_empty() {
+            Vec::new()
+        } else {
+            let encoded = hex::decode(&proof.encoded_proof)
+                .map_err(|e| Error::from(anyhow::anyhow!("Invalid encoded proof: {e}")))?;
+            [&proof.vkey_hash[..4], &encoded].concat()
+        };
+
+        Ok(Self {
+            mode,
+            proof,
+            public_values: artifact.public_values.to_vec(),
+            sp1_version: artifact.sp1_version,
+        })
+    }
+}
+
+/// The layout of a serialized [`crate::SP1ProofWithPublicValues`] with a Plonk or Groth16 proof.
+#[derive(Deserialize)]
+struct OnchainProofArtifact {
+    proof: OnchainProof,
+    public_values: SP1PublicValues,
+    sp1_version: String,
+}
+
+/// The variants of [`crate::SP1Proof`], in the same order.
+#[derive(Deserialize)]
+#[allow(dead_code)]
+enum OnchainProof {
+    Core(Unsupported),
+    Compressed(Unsupported),
+    Plonk(GnarkProof),
+    Groth16(GnarkProof),
+}
+
+/// The layout of a Plonk or Groth16 proof.
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct GnarkProof {
+    public_inputs: [String; 2],
+    encoded_proof: String,
+    raw_proof: String,
+    vkey_hash: [u8; 32],
+}
+
+/// A proof that cannot be verified on-chain, which fails to deserialize before it is read.
+struct Unsupported;
+
+impl<'de> Deserialize<'de> for Unsupported {
+    fn deserialize<D: Deserializer<'de>>(_: D) -> Result<Self, D::Error> {
+        Err(de::Error::custom(UNSUPPORTED_MODE))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sp1_prover::{Groth16Bn254Proof, PlonkBn254Proof};
+
+    use super::*;
+    use crate::{SP1Proof, SP1ProofWithPublicValues};
+
+    fn artifact(proof: SP1Proof) -> (SP1ProofWithPublicValues, Vec<u8>) {
+        let proof = SP1ProofWithPublicValues {
+            proof,
+            public_values: SP1PublicValues::from(&[1, 2, 3]),
+            sp1_version: "v4.0.0".to_string(),
+        };
+        let bytes = bincode::serialize(&proof).unwrap();
+        (proof, bytes)
+    }
+
+    #[test]
+    fn test_groth16_calldata() {
+        let (proof, bytes) = artifact(SP1Proof::Groth16(Groth16Bn254Proof {
+            public_inputs: ["1".to_string(), "2".to_string()],
+            encoded_proof: "deadbeef".to_string(),
+            raw_proof: "raw".to_string(),
+            groth16_vkey_hash: [7; 32],
+        }));
+        let calldata = OnchainCalldata::from_proof_artifact(&bytes).unwrap();
+        assert_eq!(calldata.mode, SP1ProofMode::Groth16);
+        assert_eq!(calldata.proof, proof.bytes());
+        assert_eq!(calldata.proof, [7, 7, 7, 7, 0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(calldata.public_values, [1, 2, 3]);
+        assert_eq!(calldata.sp1_version, "v4.0.0");
+    }
+
+    #[test]
+    fn test_mock_plonk_calldata() {
+        let (_, bytes) = artifact(SP1Proof::Plonk(PlonkBn254Proof {
+            public_inputs: [String::new(), String::new()],
+            encoded_proof: String::new(),
+            raw_proof: String::new(),
+            plonk_vkey_hash: [0; 32],
+        }));
+        let calldata = OnchainCalldata::from_proof_artifact(&bytes).unwrap();
+        assert_eq!(calldata.mode, SP1ProofMode::Plonk);
+        assert!(calldata.proof.is_empty());
+    }
+
+    #[test]
+    fn test_unsupported_mode() {
+        let (_, bytes) = artifact(SP1Proof::Core(Vec::new()));
+        let err = OnchainCalldata::from_proof_artifact(&bytes).unwrap_err();
+        assert!(err.to_string().contains("only Plonk and Groth16"), "{err}");
+    }
+}
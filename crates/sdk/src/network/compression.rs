@@ -0,0 +1,131 @@
+//! # Network Compression
+//!
+//! This module provides compression for artifacts uploaded to and downloaded from the network.
+
+use std::io::{Read, Write};
+
+use anyhow::{anyhow, Context, Result};
+use flate2::{read::GzDecoder, write::GzEncoder};
+
+/// The content encoding used for gzip compressed artifacts.
+pub(crate) const GZIP_ENCODING: &str = "gzip";
+
+/// The content encoding used for zstd compressed artifacts.
+pub(crate) const ZSTD_ENCODING: &str = "zstd";
+
+/// The compression to apply to artifacts before uploading them to the network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    /// Upload artifacts uncompressed.
+    #[default]
+    None,
+    /// Compress artifacts with gzip at the given level (0-9).
+    Gzip(u32),
+    /// Compress artifacts with zstd at the given level (1-22).
+    Zstd(i32),
+}
+
+impl Compression {
+    /// The content encoding that identifies this compression, if any.
+    #[must_use]
+    pub fn content_encoding(&self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::Gzip(_) => Some(GZIP_ENCODING),
+            Self::Zstd(_) => Some(ZSTD_ENCODING),
+        }
+    }
+
+    /// Compresses the given bytes.
+    pub fn compress(&self, bytes: &[u8]) -> Result<Vec<u8>> {
+        self.compress_segments(&[bytes])
+    }
+
+    /// Compresses the concatenation of the given segments, without concatenating them first.
+    pub(crate) fn compress_segments(&self, segments: &[&[u8]]) -> Result<Vec<u8>> {
+        match *self {
+            Self::None => Ok(segments.concat()),
+            Self::Gzip(level) => {
+                let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::new(level));
+                for segment in segments {
+                    encoder.write_all(segment)?;
+                }
+                encoder.finish().context("Failed to gzip artifact")
+            }
+            Self::Zstd(level) => {
+                let mut encoder = zstd::Encoder::new(Vec::new(), level)?;
+                for segment in segments {
+                    encoder.write_all(segment)?;
+                }
+                encoder.finish().context("Failed to zstd compress artifact")
+            }
+        }
+    }
+}
+
+/// Decompresses bytes according to the given content encoding.
+///
+/// # Details
+/// Bytes without a content encoding (or with the `identity` encoding) are returned unchanged, so
+/// that artifacts from servers without compression support can still be read.
+pub(crate) fn decompress(bytes: Vec<u8>, content_encoding: Option<&str>) -> Result<Vec<u8>> {
+    match content_encoding.map(str::trim) {
+        None | Some("" | "identity") => Ok(bytes),
+        Some(GZIP_ENCODING) => {
+            let mut decompressed = Vec::new();
+            GzDecoder::new(bytes.as_slice())
+                .read_to_end(&mut decompressed)
+                .context("Failed to gunzip artifact")?;
+            Ok(decompressed)
+        }
+        Some(ZSTD_ENCODING) => {
+            zstd::decode_all(bytes.as_slice()).context("Failed to zstd decompress artifact")
+        }
+        Some(encoding) => Err(anyhow!("Unsupported artifact content encoding: {encoding}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload() -> Vec<u8> {
+        (0..100_000u32).flat_map(|i| (i % 251).to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let payload = payload();
+        for compression in [Compression::None, Compression::Gzip(6), Compression::Zstd(3)] {
+            let compressed = compression.compress(&payload).unwrap();
+            if compression != Compression::None {
+                assert!(compressed.len() < payload.len());
+            }
+            let decompressed = decompress(compressed, compression.content_encoding()).unwrap();
+            assert_eq!(decompressed, payload);
+        }
+    }
+
+    #[test]
+    fn test_compress_segments() {
+        let payload = payload();
+        let segments = [&payload[..10], &payload[10..50_000], &[], &payload[50_000..]];
+        for compression in [Compression::None, Compression::Gzip(6), Compression::Zstd(3)] {
+            let compressed = compression.compress_segments(&segments).unwrap();
+            let decompressed = decompress(compressed, compression.content_encoding()).unwrap();
+            assert_eq!(decompressed, payload);
+        }
+    }
+
+    #[test]
+    fn test_uncompressed_artifacts_are_unchanged() {
+        let payload = payload();
+        assert_eq!(decompress(payload.clone(), None).unwrap(), payload);
+        assert_eq!(decompress(payload.clone(), Some("identity")).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_unsupported_encoding() {
+        assert!(decompress(payload(), Some("br")).is_err());
+    }
+}
@@ -0,0 +1,361 @@
+//! # EIP-712 Signing
+//!
+//! This module provides the EIP-712 typed structures that requests to the network are signed as,
+//! when the network supports them. Unlike the legacy signatures over the encoded message, typed
+//! signatures are bound to the domain of the network, so they cannot be replayed against another
+//! network.
+
+use std::borrow::Cow;
+
+use alloy_primitives::{Bytes, B256};
+use alloy_sol_types::{sol, Eip712Domain, SolStruct};
+
+use super::proto::artifact::{
+    CreateArtifactRequest, CreateMultipartArtifactRequest, GetAuxiliaryArtifactRequest,
+};
+use super::proto::network::{CreateProgramRequestBody, RequestProofRequestBody};
+
+/// The capability of servers that accept EIP-712 signatures.
+pub(crate) const EIP712_CAPABILITY: &str = "eip712_signatures";
+
+/// The default name of the domain of the network.
+pub const DEFAULT_DOMAIN_NAME: &str = "Succinct Prover Network";
+
+/// The version of the typed structures, part of the domain.
+pub const DOMAIN_VERSION: &str = "1";
+
+sol! {
+    /// The typed structure of a request to create an artifact.
+    struct CreateArtifact {
+        uint32 artifactType;
+        string contentEncoding;
+        bytes sha256;
+    }
+
+    /// The typed structure of a request to create an artifact attached to a proof request.
+    struct CreateAuxiliaryArtifact {
+        bytes requestId;
+        uint32 artifactType;
+        uint32 customType;
+        string contentEncoding;
+        bytes sha256;
+    }
+
+    /// The typed structure of a request to get an artifact attached to a proof request.
+    struct GetAuxiliaryArtifact {
+        bytes requestId;
+        uint32 artifactType;
+        uint32 customType;
+    }
+
+    /// The typed structure of a request to create a program.
+    struct CreateProgram {
+        uint64 nonce;
+        bytes vkHash;
+        bytes vk;
+        string programUri;
+        string name;
+    }
+
+    /// A label of a proof request.
+    struct Label {
+        string key;
+        string value;
+    }
+
+    /// The typed structure of a proof request.
+    struct RequestProof {
+        uint64 nonce;
+        bytes vkHash;
+        string version;
+        uint32 mode;
+        uint32 strategy;
+        string stdinUri;
+        uint64 deadline;
+        uint64 cycleLimit;
+        string maxPricePerBgas;
+        bytes[] whitelist;
+        uint64 minAuctionPeriod;
+        Label[] labels;
+        bytes replaces;
+        string callbackUrl;
+    }
+}
+
+/// Returns the EIP-712 domain of the network with the given name, e.g. to sign a proof request
+/// offline with [`crate::network::UnsignedProofRequest::sign_typed`].
+///
+/// # Details
+/// The domain consists of the name of the network and [`DOMAIN_VERSION`], so that signatures for
+/// one network, e.g. a testnet, are rejected by another. The name of the production network is
+/// [`DEFAULT_DOMAIN_NAME`].
+#[must_use]
+pub fn signing_domain(name: &str) -> Eip712Domain {
+    Eip712Domain::new(
+        Some(Cow::Owned(name.to_string())),
+        Some(Cow::Borrowed(DOMAIN_VERSION)),
+        None,
+        None,
+        None,
+    )
+}
+
+/// A message that can be signed as EIP-712 typed data.
+///
+/// # Details
+/// Unset optional fields are signed as zero or empty values.
+pub(crate) trait TypedMessage {
+    /// The hash to sign for the message in the given domain.
+    fn eip712_signing_hash(&self, domain: &Eip712Domain) -> B256;
+}
+
+/// The hash to sign for a request to create an artifact.
+///
+/// # Details
+/// Artifacts attached to a proof request are signed as a distinct structure that includes the
+/// request ID, so that a signature cannot be replayed to attach an artifact to another request.
+fn create_artifact_hash(
+    domain: &Eip712Domain,
+    artifact_type: i32,
+    content_encoding: Option<&str>,
+    sha256: Option<&[u8]>,
+    request_id: Option<&[u8]>,
+    custom_type: Option<u32>,
+) -> B256 {
+    let artifact_type = u32::try_from(artifact_type).unwrap_or_default();
+    let content_encoding = content_encoding.unwrap_or_default().to_string();
+    let sha256 = Bytes::copy_from_slice(sha256.unwrap_or_default());
+    match request_id {
+        None => CreateArtifact {
+            artifactType: artifact_type,
+            contentEncoding: content_encoding,
+            sha256,
+        }
+        .eip712_signing_hash(domain),
+        Some(request_id) => CreateAuxiliaryArtifact {
+            requestId: Bytes::copy_from_slice(request_id),
+            artifactType: artifact_type,
+            customType: custom_type.unwrap_or_default(),
+            contentEncoding: content_encoding,
+            sha256,
+        }
+        .eip712_signing_hash(domain),
+    }
+}
+
+impl TypedMessage for CreateArtifactRequest {
+    fn eip712_signing_hash(&self, domain: &Eip712Domain) -> B256 {
+        create_artifact_hash(
+            domain,
+            self.artifact_type,
+            self.content_encoding.as_deref(),
+            self.sha256.as_deref(),
+            self.request_id.as_deref(),
+            self.custom_type,
+        )
+    }
+}
+
+impl TypedMessage for CreateMultipartArtifactRequest {
+    fn eip712_signing_hash(&self, domain: &Eip712Domain) -> B256 {
+        create_artifact_hash(
+            domain,
+            self.artifact_type,
+            self.content_encoding.as_deref(),
+            self.sha256.as_deref(),
+            self.request_id.as_deref(),
+            self.custom_type,
+        )
+    }
+}
+
+impl TypedMessage for GetAuxiliaryArtifactRequest {
+    fn eip712_signing_hash(&self, domain: &Eip712Domain) -> B256 {
+        GetAuxiliaryArtifact {
+            requestId: Bytes::copy_from_slice(&self.request_id),
+            artifactType: u32::try_from(self.artifact_type).unwrap_or_default(),
+            customType: self.custom_type.unwrap_or_default(),
+        }
+        .eip712_signing_hash(domain)
+    }
+}
+
+impl TypedMessage for CreateProgramRequestBody {
+    fn eip712_signing_hash(&self, domain: &Eip712Domain) -> B256 {
+        CreateProgram {
+            nonce: self.nonce,
+            vkHash: Bytes::copy_from_slice(&self.vk_hash),
+            vk: Bytes::copy_from_slice(&self.vk),
+            programUri: self.program_uri.clone(),
+            name: self.name.clone().unwrap_or_default(),
+        }
+        .eip712_signing_hash(domain)
+    }
+}
+
+impl TypedMessage for RequestProofRequestBody {
+    fn eip712_signing_hash(&self, domain: &Eip712Domain) -> B256 {
+        RequestProof {
+            nonce: self.nonce,
+            vkHash: Bytes::copy_from_slice(&self.vk_hash),
+            version: self.version.clone(),
+            mode: u32::try_from(self.mode).unwrap_or_default(),
+            strategy: u32::try_from(self.strategy).unwrap_or_default(),
+            stdinUri: self.stdin_uri.clone(),
+            deadline: self.deadline,
+            cycleLimit: self.cycle_limit,
+            maxPricePerBgas: self.max_price_per_bgas.clone().unwrap_or_default(),
+            whitelist: self
+                .whitelist
+                .iter()
+                .map(|address| Bytes::copy_from_slice(address))
+                .collect(),
+            minAuctionPeriod: self.min_auction_period.unwrap_or_default(),
+            labels: self
+                .metadata
+                .iter()
+                .map(|(key, value)| Label { key: key.clone(), value: value.clone() })
+                .collect(),
+            replaces: Bytes::copy_from_slice(self.replaces.as_deref().unwrap_or_default()),
+            callbackUrl: self.callback_url.clone().unwrap_or_default(),
+        }
+        .eip712_signing_hash(domain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use alloy_primitives::b256;
+
+    use super::*;
+    use crate::network::proto::artifact::ArtifactType;
+
+    fn proof_request_body() -> RequestProofRequestBody {
+        RequestProofRequestBody {
+            nonce: 7,
+            vk_hash: B256::repeat_byte(1).to_vec(),
+            version: "sp1-v4.0.0".to_string(),
+            mode: 4,
+            strategy: 1,
+            stdin_uri: "s3://stdin".to_string(),
+            deadline: 1_700_000_000,
+            cycle_limit: 1_000_000,
+            max_price_per_bgas: Some("100".to_string()),
+            whitelist: vec![vec![0x22; 20]],
+            min_auction_period: Some(30),
+            metadata: BTreeMap::from([("service".to_string(), "bridge".to_string())]),
+            replaces: None,
+            callback_url: Some("https://example.com/proofs".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_domain_separator() {
+        let domain = signing_domain(DEFAULT_DOMAIN_NAME);
+        assert_eq!(
+            domain.separator(),
+            b256!("8130252fdc497b80eb12827663530737c87036416de7c75f98e52dcb1d9da58b")
+        );
+    }
+
+    #[test]
+    fn test_create_artifact_digest() {
+        let request = CreateArtifactRequest {
+            signature: Vec::new(),
+            artifact_type: ArtifactType::Stdin.into(),
+            content_encoding: Some("zstd".to_string()),
+            sha256: Some(B256::repeat_byte(0xab).to_vec()),
+            format: 0,
+            request_id: None,
+            custom_type: None,
+        };
+        let domain = signing_domain(DEFAULT_DOMAIN_NAME);
+        assert_eq!(
+            request.eip712_signing_hash(&domain),
+            b256!("de2e17c0a319abbec1a46129ccf1fe429fc8d02a20e72fdb4478df676c67732b")
+        );
+    }
+
+    #[test]
+    fn test_auxiliary_artifact_digests() {
+        let domain = signing_domain(DEFAULT_DOMAIN_NAME);
+        let request = CreateArtifactRequest {
+            signature: Vec::new(),
+            artifact_type: ArtifactType::Custom.into(),
+            content_encoding: Some("zstd".to_string()),
+            sha256: Some(B256::repeat_byte(0xab).to_vec()),
+            format: 0,
+            request_id: Some(B256::repeat_byte(0x11).to_vec()),
+            custom_type: Some(42),
+        };
+        assert_eq!(
+            request.eip712_signing_hash(&domain),
+            b256!("b61d3464f1b4082228579346df083f13944b40f5d06ece9bc476b6de3a0b8e06")
+        );
+
+        // The request ID is signed, so the signature cannot attach the artifact elsewhere.
+        let other = CreateArtifactRequest {
+            request_id: Some(B256::repeat_byte(0x12).to_vec()),
+            ..request.clone()
+        };
+        assert_ne!(other.eip712_signing_hash(&domain), request.eip712_signing_hash(&domain));
+
+        let request = GetAuxiliaryArtifactRequest {
+            signature: Vec::new(),
+            request_id: B256::repeat_byte(0x11).to_vec(),
+            artifact_type: ArtifactType::ExecutionReport.into(),
+            custom_type: None,
+            format: 0,
+        };
+        assert_eq!(
+            request.eip712_signing_hash(&domain),
+            b256!("bc8129a6b1ad83b617f501994b6512d08daf021bcce0db1632f916e1acc788a0")
+        );
+    }
+
+    #[test]
+    fn test_create_program_digest() {
+        let body = CreateProgramRequestBody {
+            nonce: 3,
+            vk_hash: B256::repeat_byte(1).to_vec(),
+            vk: vec![1, 2, 3],
+            program_uri: "s3://program".to_string(),
+            name: Some("fibonacci".to_string()),
+        };
+        let domain = signing_domain(DEFAULT_DOMAIN_NAME);
+        assert_eq!(
+            body.eip712_signing_hash(&domain),
+            b256!("f9aff75f9a169a572b1336f1f1dc1747a03fdfe0e63572841939360aa8467e80")
+        );
+    }
+
+    #[test]
+    fn test_request_proof_digest() {
+        let domain = signing_domain(DEFAULT_DOMAIN_NAME);
+        assert_eq!(
+            proof_request_body().eip712_signing_hash(&domain),
+            b256!("498e51fae612eddf5b46f864e10bbac5b4d605d02f507f044ba68080d557aef6")
+        );
+    }
+
+    #[test]
+    fn test_digest_depends_on_domain() {
+        let body = proof_request_body();
+        let production = body.eip712_signing_hash(&signing_domain(DEFAULT_DOMAIN_NAME));
+        let testnet = body.eip712_signing_hash(&signing_domain("Succinct Prover Network Testnet"));
+        assert_ne!(production, testnet);
+    }
+
+    #[test]
+    fn test_encode_type() {
+        assert_eq!(
+            RequestProof::eip712_encode_type(),
+            "RequestProof(uint64 nonce,bytes vkHash,string version,uint32 mode,uint32 strategy,\
+             string stdinUri,uint64 deadline,uint64 cycleLimit,string maxPricePerBgas,\
+             bytes[] whitelist,uint64 minAuctionPeriod,Label[] labels,bytes replaces,\
+             string callbackUrl)Label(string key,string value)"
+        );
+    }
+}
@@ -0,0 +1,157 @@
+//! # RPC Endpoints
+//!
+//! This module keeps track of the RPC endpoints of a client, and which of them is in use.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The default interval between health checks of the primary endpoint while failed over.
+pub const DEFAULT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// An ordered list of RPC endpoints, the first of which is the primary.
+///
+/// # Details
+/// Requests are sent to the active endpoint. When it cannot be reached, the client fails over to
+/// the next endpoint in the list, and while failed over, the primary is health-checked at most
+/// once per interval so that the client can fail back to it.
+#[derive(Debug)]
+pub(crate) struct Endpoints {
+    urls: Vec<String>,
+    health_check_interval: Duration,
+    state: Mutex<EndpointState>,
+}
+
+#[derive(Debug, Default)]
+struct EndpointState {
+    /// The index of the endpoint in use.
+    active: usize,
+    /// When the primary was last health-checked, or when it was failed over from.
+    last_health_check: Option<Instant>,
+}
+
+impl Endpoints {
+    /// Creates the endpoints from the given URLs, in order of preference.
+    pub(crate) fn new(urls: Vec<String>, health_check_interval: Duration) -> Self {
+        assert!(!urls.is_empty(), "at least one rpc url is required");
+        Self { urls, health_check_interval, state: Mutex::new(EndpointState::default()) }
+    }
+
+    /// The URL of the primary endpoint.
+    pub(crate) fn primary(&self) -> &str {
+        &self.urls[0]
+    }
+
+    /// The number of endpoints.
+    pub(crate) fn len(&self) -> usize {
+        self.urls.len()
+    }
+
+    /// The URL of the endpoint at the given index.
+    pub(crate) fn url(&self, index: usize) -> &str {
+        &self.urls[index]
+    }
+
+    /// The index of the endpoint in use.
+    pub(crate) fn active(&self) -> usize {
+        self.state.lock().unwrap().active
+    }
+
+    /// Moves on from the given endpoint to the next one, returning the index of the endpoint in
+    /// use.
+    ///
+    /// # Details
+    /// If the given endpoint is no longer in use, e.g. because a concurrent call already failed
+    /// over, the endpoint in use is kept.
+    pub(crate) fn fail_over(&self, from: usize) -> usize {
+        let mut state = self.state.lock().unwrap();
+        if state.active == from && self.urls.len() > 1 {
+            state.active = (from + 1) % self.urls.len();
+            if from == 0 {
+                state.last_health_check = Some(Instant::now());
+            }
+            log::warn!(
+                "RPC endpoint {} is unavailable, failing over to {}",
+                self.urls[from],
+                self.urls[state.active]
+            );
+        }
+        state.active
+    }
+
+    /// Whether the primary should be health-checked now, in which case the check is recorded.
+    ///
+    /// # Details
+    /// The primary is only checked while failed over, and at most once per interval.
+    pub(crate) fn should_check_primary(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.active == 0 {
+            return false;
+        }
+        let due = !matches!(
+            state.last_health_check,
+            Some(last) if last.elapsed() < self.health_check_interval
+        );
+        if due {
+            state.last_health_check = Some(Instant::now());
+        }
+        due
+    }
+
+    /// Moves back to the primary endpoint after it passed a health check.
+    pub(crate) fn fail_back(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.active != 0 {
+            log::info!("RPC endpoint {} is available again, failing back", self.urls[0]);
+            *state = EndpointState::default();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoints(interval: Duration) -> Endpoints {
+        Endpoints::new(vec!["primary".to_string(), "backup".to_string()], interval)
+    }
+
+    #[test]
+    fn test_fail_over_and_back() {
+        let endpoints = endpoints(Duration::ZERO);
+        assert_eq!(endpoints.active(), 0);
+        assert!(!endpoints.should_check_primary());
+
+        assert_eq!(endpoints.fail_over(0), 1);
+        assert_eq!(endpoints.url(endpoints.active()), "backup");
+        assert!(endpoints.should_check_primary());
+
+        endpoints.fail_back();
+        assert_eq!(endpoints.active(), 0);
+        assert!(!endpoints.should_check_primary());
+    }
+
+    #[test]
+    fn test_concurrent_fail_over() {
+        let endpoints = endpoints(Duration::ZERO);
+        // Two calls fail on the primary, but only the first one fails over.
+        assert_eq!(endpoints.fail_over(0), 1);
+        assert_eq!(endpoints.fail_over(0), 1);
+        // Failing over from the last endpoint wraps around to the primary.
+        assert_eq!(endpoints.fail_over(1), 0);
+    }
+
+    #[test]
+    fn test_health_check_interval() {
+        let endpoints = endpoints(Duration::from_secs(3600));
+        endpoints.fail_over(0);
+        // The primary was just failed over from, so it is not checked until the interval passes.
+        assert!(!endpoints.should_check_primary());
+    }
+
+    #[test]
+    fn test_single_endpoint() {
+        let endpoints = Endpoints::new(vec!["primary".to_string()], Duration::ZERO);
+        assert_eq!(endpoints.fail_over(0), 0);
+        assert!(!endpoints.should_check_primary());
+    }
+}
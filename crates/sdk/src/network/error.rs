@@ -1,25 +1,46 @@
+use std::time::Duration;
+
+use alloy_primitives::Address;
 use thiserror::Error;
-use tonic::Status;
+use tonic::{Code, Status};
+
+use super::proto::network::ProofMode;
 
 /// An error that can occur when interacting with the prover network.
+///
+/// # Details
+/// Errors returned by the network are mapped from their gRPC status code to a specific variant
+/// where possible, so that callers can decide programmatically whether to retry or abort (see
+/// [`SP1NetworkError::is_retryable`]).
 #[derive(Error, Debug)]
-pub enum Error {
+pub enum SP1NetworkError {
     /// The program execution failed.
     #[error("Program simulation failed")]
     SimulationFailed,
 
+    /// The local execution of the program exceeded the configured cycle cap.
+    #[error("Local execution exceeded the cap of {max_cycles} cycles")]
+    LocalExecutionCapExceeded {
+        /// The maximum number of cycles the program was allowed to run for locally.
+        max_cycles: u64,
+    },
+
     /// The proof request is unexecutable.
-    #[error("Proof request 0x{} is unexecutable", hex::encode(.request_id))]
+    #[error("Proof request 0x{} is unexecutable{}", hex::encode(.request_id), format_reason(.reason))]
     RequestUnexecutable {
         /// The ID of the request that cannot be executed.
         request_id: Vec<u8>,
+        /// Why the request cannot be executed, if reported by the network.
+        reason: Option<String>,
     },
 
     /// The proof request is unfulfillable.
-    #[error("Proof request 0x{} is unfulfillable", hex::encode(.request_id))]
+    #[error("Proof request 0x{} is unfulfillable{}", hex::encode(.request_id), format_reason(.reason))]
     RequestUnfulfillable {
         /// The ID of the request that cannot be fulfilled.
         request_id: Vec<u8>,
+        /// Why the request cannot be fulfilled, if reported by the network.
+        reason: Option<String>,
     },
 
     /// The proof request timed out.
@@ -29,11 +50,313 @@ pub enum Error {
         request_id: Vec<u8>,
     },
 
+    /// The proof returned for the request does not verify against the verifying key of the
+    /// program.
+    #[error(
+        "Proof for request 0x{}{} failed verification: {reason}",
+        hex::encode(.request_id),
+        format_fulfiller(.fulfiller)
+    )]
+    ProofVerificationFailed {
+        /// The ID of the request whose proof failed verification.
+        request_id: Vec<u8>,
+        /// The fulfiller that returned the proof, if reported by the network.
+        fulfiller: Option<Address>,
+        /// Why the proof failed verification.
+        reason: String,
+    },
+
+    /// The proof request cannot be cancelled or replaced because it was already assigned to a
+    /// prover.
+    #[error("Proof request 0x{} is already assigned and cannot be cancelled or replaced", hex::encode(.request_id))]
+    RequestAlreadyAssigned {
+        /// The ID of the request that is already assigned.
+        request_id: Vec<u8>,
+    },
+
+    /// The proof request cannot be cancelled or replaced because it was already fulfilled.
+    #[error("Proof request 0x{} is already fulfilled and cannot be cancelled or replaced", hex::encode(.request_id))]
+    RequestAlreadyFulfilled {
+        /// The ID of the request that is already fulfilled.
+        request_id: Vec<u8>,
+    },
+
+    /// The private key is malformed.
+    #[error("Invalid private key: {reason}")]
+    InvalidPrivateKey {
+        /// What is wrong with the private key. Never includes the key itself.
+        reason: String,
+    },
+
+    /// The ELF of a program does not match its verifying key.
+    #[error("Program ELF does not match the verifying key: {reason}")]
+    ProgramVkMismatch {
+        /// Which part of the verifying key does not match the ELF.
+        reason: String,
+    },
+
+    /// The request parameters are invalid.
+    #[error("Invalid request: {reason}")]
+    InvalidRequest {
+        /// What is wrong with the request.
+        reason: String,
+    },
+
+    /// The local clock differs too much from the clock of the network, so the deadline of a
+    /// request cannot be computed reliably.
+    #[error(
+        "Local clock is skewed: local time is {local}, but network time is {server}. Synchronize \
+         the system clock (e.g. with NTP) and try again"
+    )]
+    ClockSkew {
+        /// The local time, in seconds since the Unix epoch.
+        local: u64,
+        /// The time reported by the network, in seconds since the Unix epoch.
+        server: u64,
+    },
+
+    /// A downloaded artifact does not match the checksum reported by the server.
+    #[error(
+        "Artifact checksum mismatch: expected 0x{}, got 0x{}",
+        hex::encode(.expected),
+        hex::encode(.actual)
+    )]
+    ChecksumMismatch {
+        /// The SHA-256 hash reported by the server.
+        expected: Vec<u8>,
+        /// The SHA-256 hash of the downloaded artifact.
+        actual: Vec<u8>,
+    },
+
+    /// The account does not have enough balance to pay for the request.
+    #[error("Insufficient balance: {message}")]
+    InsufficientBalance {
+        /// The message returned by the network.
+        message: String,
+    },
+
+    /// The program is not registered on the network.
+    #[error("Program not found: {message}")]
+    ProgramNotFound {
+        /// The message returned by the network.
+        message: String,
+    },
+
+    /// The network rejected the signature or the signer is not allowed to make the request.
+    #[error("Signature rejected: {message}")]
+    SignatureRejected {
+        /// The message returned by the network.
+        message: String,
+    },
+
+    /// A webhook notification was not signed by the network.
+    #[error("Invalid webhook notification: {reason}")]
+    InvalidNotification {
+        /// Why the notification is invalid.
+        reason: String,
+    },
+
+    /// An artifact could not be uploaded.
+    #[error("Failed to upload artifact: {reason}")]
+    ArtifactUpload {
+        /// Why the upload failed.
+        reason: String,
+    },
+
+    /// The network does not support cost estimation, e.g. because it runs an older version.
+    #[error("Pricing unavailable: {message}")]
+    PricingUnavailable {
+        /// The message returned by the network.
+        message: String,
+    },
+
+    /// The network does not support a feature used by the request, e.g. because it runs an older
+    /// version.
+    #[error("The network does not support {capability}")]
+    UnsupportedCapability {
+        /// The name of the capability, e.g. `request_metadata`.
+        capability: String,
+    },
+
+    /// The proof mode is not supported by the version of the SP1 circuits of the request.
+    #[error("Proof mode {} is not supported by SP1 {version}", .mode.as_str_name())]
+    UnsupportedMode {
+        /// The requested proof mode.
+        mode: ProofMode,
+        /// The version of the SP1 circuits, e.g. `v4.0.0`.
+        version: String,
+    },
+
+    /// The RPC did not complete before its deadline.
+    #[error("RPC timed out: {message}")]
+    Timeout {
+        /// The message returned by the network.
+        message: String,
+    },
+
+    /// Calls to the RPC endpoint are short-circuited after repeated transport failures, see
+    /// [`CircuitBreakerPolicy`](crate::network::CircuitBreakerPolicy).
+    #[error(
+        "Circuit open for RPC endpoint {endpoint} after repeated failures, retry in {retry_in:?}"
+    )]
+    CircuitOpen {
+        /// The URL of the endpoint.
+        endpoint: String,
+        /// How long until a probe call is let through to the endpoint.
+        retry_in: Duration,
+    },
+
+    /// A blocking method was called from within an async runtime, whose thread it would block.
+    #[error(
+        "`{method}` cannot be called from within an async runtime, as it would block the \
+         runtime; use its async variant instead"
+    )]
+    BlockingInAsyncContext {
+        /// The name of the blocking method.
+        method: &'static str,
+    },
+
     /// An error occurred while interacting with the RPC server.
-    #[error("RPC error")]
-    RpcError(#[from] Status),
+    #[error("RPC error: {}", .0.message())]
+    RpcError(Status),
 
     /// An unknown error occurred.
     #[error("Other error: {0}")]
-    Other(#[from] anyhow::Error),
+    Other(anyhow::Error),
+}
+
+/// Formats the optional failure reason of a request as a suffix of the error message.
+fn format_reason(reason: &Option<String>) -> String {
+    reason.as_ref().map(|reason| format!(": {reason}")).unwrap_or_default()
+}
+
+/// Formats the optional fulfiller of a request as a suffix of the request ID.
+fn format_fulfiller(fulfiller: &Option<Address>) -> String {
+    fulfiller.map(|fulfiller| format!(" fulfilled by {fulfiller}")).unwrap_or_default()
+}
+
+/// The network error type, kept under its original name for compatibility.
+pub type Error = SP1NetworkError;
+
+impl SP1NetworkError {
+    /// Whether the operation that failed with this error may succeed if retried.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Timeout { .. }
+            | Self::ArtifactUpload { .. }
+            | Self::ChecksumMismatch { .. }
+            | Self::CircuitOpen { .. } => true,
+            Self::RpcError(status) => matches!(
+                status.code(),
+                Code::Unavailable | Code::DeadlineExceeded | Code::Internal | Code::Aborted
+            ),
+            _ => false,
+        }
+    }
+}
+
+impl From<Status> for SP1NetworkError {
+    fn from(status: Status) -> Self {
+        let message = status.message().to_string();
+        let lowercase = message.to_lowercase();
+        match status.code() {
+            Code::NotFound if lowercase.contains("program") => Self::ProgramNotFound { message },
+            Code::FailedPrecondition | Code::ResourceExhausted if lowercase.contains("balance") => {
+                Self::InsufficientBalance { message }
+            }
+            Code::Unauthenticated | Code::PermissionDenied => Self::SignatureRejected { message },
+            Code::DeadlineExceeded => Self::Timeout { message },
+            _ => Self::RpcError(status),
+        }
+    }
+}
+
+impl From<anyhow::Error> for SP1NetworkError {
+    fn from(e: anyhow::Error) -> Self {
+        let e = match e.downcast::<Self>() {
+            Ok(e) => return e,
+            Err(e) => e,
+        };
+        match e.downcast::<Status>() {
+            Ok(status) => status.into(),
+            Err(e) => Self::Other(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_mapping() {
+        let cases = [
+            (Status::not_found("program 0x01 not found"), "ProgramNotFound"),
+            (Status::not_found("request not found"), "RpcError"),
+            (Status::failed_precondition("insufficient balance"), "InsufficientBalance"),
+            (Status::resource_exhausted("balance too low"), "InsufficientBalance"),
+            (Status::resource_exhausted("rate limited"), "RpcError"),
+            (Status::unauthenticated("invalid signature"), "SignatureRejected"),
+            (Status::permission_denied("not whitelisted"), "SignatureRejected"),
+            (Status::deadline_exceeded("deadline exceeded"), "Timeout"),
+            (Status::unavailable("connection reset"), "RpcError"),
+            (Status::internal("internal error"), "RpcError"),
+            (Status::invalid_argument("invalid nonce"), "RpcError"),
+        ];
+        for (status, expected) in cases {
+            let code = status.code();
+            let variant = match SP1NetworkError::from(status) {
+                SP1NetworkError::ProgramNotFound { .. } => "ProgramNotFound",
+                SP1NetworkError::InsufficientBalance { .. } => "InsufficientBalance",
+                SP1NetworkError::SignatureRejected { .. } => "SignatureRejected",
+                SP1NetworkError::Timeout { .. } => "Timeout",
+                SP1NetworkError::RpcError(_) => "RpcError",
+                e => panic!("unexpected error for {code:?}: {e}"),
+            };
+            assert_eq!(variant, expected, "{code:?}");
+        }
+    }
+
+    #[test]
+    fn test_retryable() {
+        assert!(SP1NetworkError::from(Status::unavailable("")).is_retryable());
+        assert!(SP1NetworkError::from(Status::deadline_exceeded("")).is_retryable());
+        assert!(SP1NetworkError::from(Status::internal("")).is_retryable());
+        assert!(SP1NetworkError::from(Status::aborted("")).is_retryable());
+        assert!(!SP1NetworkError::from(Status::invalid_argument("")).is_retryable());
+        assert!(!SP1NetworkError::from(Status::failed_precondition("balance")).is_retryable());
+        let circuit_open = SP1NetworkError::CircuitOpen {
+            endpoint: "https://rpc.example.com".to_string(),
+            retry_in: Duration::from_secs(30),
+        };
+        assert!(circuit_open.is_retryable());
+    }
+
+    #[test]
+    fn test_proof_verification_failed() {
+        let e = SP1NetworkError::ProofVerificationFailed {
+            request_id: vec![0xab],
+            fulfiller: Some(Address::repeat_byte(0x11)),
+            reason: "Invalid public values".to_string(),
+        };
+        assert_eq!(
+            e.to_string(),
+            "Proof for request 0xab fulfilled by 0x1111111111111111111111111111111111111111 \
+             failed verification: Invalid public values"
+        );
+        assert!(!e.is_retryable());
+    }
+
+    #[test]
+    fn test_from_anyhow() {
+        let e: anyhow::Error = Status::not_found("program not found").into();
+        assert!(matches!(SP1NetworkError::from(e), SP1NetworkError::ProgramNotFound { .. }));
+
+        let e: anyhow::Error = SP1NetworkError::RequestTimedOut { request_id: vec![1] }.into();
+        assert!(matches!(SP1NetworkError::from(e), SP1NetworkError::RequestTimedOut { .. }));
+
+        let e = anyhow::anyhow!("something else");
+        assert!(matches!(SP1NetworkError::from(e), SP1NetworkError::Other(_)));
+    }
 }
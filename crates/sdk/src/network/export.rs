@@ -0,0 +1,322 @@
+//! # Request Export
+//!
+//! This module flattens proof requests into rows and writes them as CSV or JSON lines, e.g. to
+//! account for the spending on the network per program.
+
+use std::io::Write;
+
+use alloy_primitives::{Address, B256, U256};
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use super::proto::network::{FulfillmentStatus, ProofMode, ProofRequest};
+
+/// The format that proof requests are exported in by
+/// [`crate::network::NetworkClient::export_requests`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Comma-separated values, with a header row.
+    Csv,
+    /// One JSON object per line.
+    JsonLines,
+}
+
+/// A proof request flattened into a row of an export.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExportedRequest {
+    /// The ID of the request.
+    pub request_id: B256,
+    /// The verifying key hash of the program.
+    pub vk_hash: B256,
+    /// The proof mode, e.g. `GROTH16`.
+    pub mode: String,
+    /// The fulfillment status, e.g. `FULFILLED`.
+    pub fulfillment_status: String,
+    /// The cycles used to execute the request, once executed.
+    pub cycles: Option<u64>,
+    /// The amount paid for the request, in wei, once assigned. Serialized as a decimal string.
+    #[serde(serialize_with = "serialize_decimal")]
+    pub price_paid: Option<U256>,
+    /// The fulfiller of the request, once assigned.
+    pub fulfiller: Option<Address>,
+    /// When the request was created, in seconds since the Unix epoch.
+    pub created_at: u64,
+    /// When the request was last updated, in seconds since the Unix epoch.
+    pub updated_at: u64,
+    /// When the request was fulfilled, in seconds since the Unix epoch.
+    pub fulfilled_at: Option<u64>,
+}
+
+/// The columns of a CSV export, in the order of the fields of [`ExportedRequest`].
+const CSV_HEADER: [&str; 10] = [
+    "request_id",
+    "vk_hash",
+    "mode",
+    "fulfillment_status",
+    "cycles",
+    "price_paid",
+    "fulfiller",
+    "created_at",
+    "updated_at",
+    "fulfilled_at",
+];
+
+impl TryFrom<&ProofRequest> for ExportedRequest {
+    type Error = anyhow::Error;
+
+    fn try_from(request: &ProofRequest) -> Result<Self> {
+        let request_id =
+            B256::try_from(request.request_id.as_slice()).context("Invalid request ID")?;
+        let mode = ProofMode::try_from(request.mode).map_or("", |mode| mode.as_str_name());
+        let fulfillment_status = FulfillmentStatus::try_from(request.fulfillment_status)
+            .map_or("", |status| status.as_str_name());
+        let fulfiller = request
+            .fulfiller
+            .as_deref()
+            .map(Address::try_from)
+            .transpose()
+            .with_context(|| format!("Invalid fulfiller of request {request_id}"))?;
+        Ok(Self {
+            request_id,
+            vk_hash: B256::try_from(request.vk_hash.as_slice())
+                .with_context(|| format!("Invalid vk hash of request {request_id}"))?,
+            mode: mode.to_string(),
+            fulfillment_status: fulfillment_status.to_string(),
+            cycles: request.cycles,
+            price_paid: price_paid(request)
+                .with_context(|| format!("Invalid amounts of request {request_id}"))?,
+            fulfiller,
+            created_at: request.created_at,
+            updated_at: request.updated_at,
+            fulfilled_at: request.fulfilled_at,
+        })
+    }
+}
+
+/// The amount deducted for a request, minus the amount refunded.
+fn price_paid(request: &ProofRequest) -> Result<Option<U256>> {
+    let parse = |amount: &Option<String>| {
+        amount.as_deref().map(|amount| U256::from_str_radix(amount.trim(), 10)).transpose()
+    };
+    let Some(deducted) = parse(&request.deduction_amount)? else {
+        return Ok(None);
+    };
+    let refunded = parse(&request.refund_amount)?.unwrap_or_default();
+    Ok(Some(deducted.saturating_sub(refunded)))
+}
+
+/// Serializes an optional amount as a decimal string.
+fn serialize_decimal<S: serde::Serializer>(
+    value: &Option<U256>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(value) => serializer.serialize_some(&value.to_string()),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Writes exported requests to a writer, one row at a time.
+pub(crate) struct RequestExporter<W: Write> {
+    writer: W,
+    format: ExportFormat,
+    rows: u64,
+}
+
+impl<W: Write> RequestExporter<W> {
+    /// Creates an exporter, writing the header of the format if it has one.
+    pub(crate) fn new(mut writer: W, format: ExportFormat) -> Result<Self> {
+        if format == ExportFormat::Csv {
+            writeln!(writer, "{}", CSV_HEADER.join(","))?;
+        }
+        Ok(Self { writer, format, rows: 0 })
+    }
+
+    /// Writes a request as a row.
+    pub(crate) fn write(&mut self, request: &ProofRequest) -> Result<()> {
+        let row = ExportedRequest::try_from(request)?;
+        match self.format {
+            ExportFormat::Csv => {
+                let fields = [
+                    row.request_id.to_string(),
+                    row.vk_hash.to_string(),
+                    row.mode,
+                    row.fulfillment_status,
+                    optional(row.cycles),
+                    optional(row.price_paid),
+                    optional(row.fulfiller),
+                    row.created_at.to_string(),
+                    row.updated_at.to_string(),
+                    optional(row.fulfilled_at),
+                ];
+                let fields: Vec<_> = fields.iter().map(|field| csv_field(field)).collect();
+                writeln!(self.writer, "{}", fields.join(","))?;
+            }
+            ExportFormat::JsonLines => {
+                serde_json::to_writer(&mut self.writer, &row)?;
+                self.writer.write_all(b"\n")?;
+            }
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// Flushes the writer and returns the number of rows written.
+    pub(crate) fn finish(mut self) -> Result<u64> {
+        self.writer.flush()?;
+        Ok(self.rows)
+    }
+}
+
+/// Formats an optional value, as an empty string if it is not set.
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+/// Quotes a CSV field if it contains a separator, a quote or a line break.
+fn csv_field(field: &str) -> std::borrow::Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\"")).into()
+    } else {
+        field.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::test_utils::{MockNetworkServer, MockOutcome};
+    use crate::network::{NetworkClient, ProofRequestFilter};
+    use crate::{NetworkProver, SP1Stdin};
+
+    const PRIVATE_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+    fn request() -> ProofRequest {
+        ProofRequest {
+            request_id: B256::repeat_byte(1).to_vec(),
+            vk_hash: B256::repeat_byte(2).to_vec(),
+            mode: ProofMode::Groth16.into(),
+            fulfillment_status: FulfillmentStatus::Fulfilled.into(),
+            fulfiller: Some(Address::repeat_byte(3).to_vec()),
+            cycles: Some(1_000_000),
+            deduction_amount: Some("5000".to_string()),
+            refund_amount: Some("1500".to_string()),
+            created_at: 1_700_000_000,
+            updated_at: 1_700_000_100,
+            fulfilled_at: Some(1_700_000_100),
+            ..Default::default()
+        }
+    }
+
+    fn export(format: ExportFormat, requests: &[ProofRequest]) -> String {
+        let mut out = Vec::new();
+        let mut exporter = RequestExporter::new(&mut out, format).unwrap();
+        for request in requests {
+            exporter.write(request).unwrap();
+        }
+        assert_eq!(exporter.finish().unwrap(), requests.len() as u64);
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_flatten_request() {
+        let row = ExportedRequest::try_from(&request()).unwrap();
+        assert_eq!(row.mode, "GROTH16");
+        assert_eq!(row.fulfillment_status, "FULFILLED");
+        assert_eq!(row.price_paid, Some(U256::from(3500)));
+        assert_eq!(row.fulfiller, Some(Address::repeat_byte(3)));
+
+        let unassigned = ProofRequest {
+            fulfillment_status: FulfillmentStatus::Requested.into(),
+            fulfiller: None,
+            cycles: None,
+            deduction_amount: None,
+            refund_amount: None,
+            fulfilled_at: None,
+            ..request()
+        };
+        let row = ExportedRequest::try_from(&unassigned).unwrap();
+        assert_eq!((row.price_paid, row.fulfiller, row.cycles), (None, None, None));
+
+        let invalid = ProofRequest { deduction_amount: Some("lots".to_string()), ..request() };
+        assert!(ExportedRequest::try_from(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_export_csv() {
+        let unassigned = ProofRequest {
+            fulfiller: None,
+            deduction_amount: None,
+            fulfilled_at: None,
+            ..request()
+        };
+        let csv = export(ExportFormat::Csv, &[request(), unassigned]);
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], CSV_HEADER.join(","));
+        assert_eq!(
+            lines[1],
+            format!(
+                "{},{},GROTH16,FULFILLED,1000000,3500,{},1700000000,1700000100,1700000100",
+                B256::repeat_byte(1),
+                B256::repeat_byte(2),
+                Address::repeat_byte(3)
+            )
+        );
+        assert!(lines[2].ends_with(",1000000,,,1700000000,1700000100,"), "{}", lines[2]);
+
+        // An empty export still has the header.
+        assert_eq!(export(ExportFormat::Csv, &[]), format!("{}\n", CSV_HEADER.join(",")));
+    }
+
+    #[test]
+    fn test_export_json_lines() {
+        let json = export(ExportFormat::JsonLines, &[request(), request()]);
+        let rows: Vec<serde_json::Value> =
+            json.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["request_id"], serde_json::json!(B256::repeat_byte(1)));
+        assert_eq!(rows[0]["price_paid"], "3500");
+        assert_eq!(rows[0]["cycles"], 1_000_000);
+        assert_eq!(export(ExportFormat::JsonLines, &[]), "");
+    }
+
+    #[tokio::test]
+    async fn test_export_requests() {
+        let server = MockNetworkServer::start().await.unwrap();
+        let vk_hash = B256::repeat_byte(1);
+        server.insert_program(vk_hash);
+        let prover = NetworkProver::new(PRIVATE_KEY, server.rpc_url());
+        for outcome in [MockOutcome::default(), MockOutcome::default(), MockOutcome::Pending] {
+            server.push_outcome(outcome);
+            prover.prove_with_vk_hash(vk_hash, SP1Stdin::new()).request_async().await.unwrap();
+        }
+        let client = NetworkClient::new(PRIVATE_KEY, server.rpc_url()).unwrap();
+
+        // Requests are paginated, two per page.
+        let filter = ProofRequestFilter::new().requester(client.address()).limit(2);
+        let mut csv = Vec::new();
+        let rows = client.export_requests(filter.clone(), ExportFormat::Csv, &mut csv).await;
+        assert_eq!(rows.unwrap(), 3);
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().count(), 4);
+        assert_eq!(csv.lines().filter(|line| line.contains(",FULFILLED,")).count(), 2);
+
+        let filter = filter.fulfillment_status(FulfillmentStatus::Fulfilled);
+        let mut json = Vec::new();
+        let rows = client.export_requests(filter, ExportFormat::JsonLines, &mut json).await;
+        assert_eq!(rows.unwrap(), 2);
+        for line in String::from_utf8(json).unwrap().lines() {
+            let row: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(row["vk_hash"], serde_json::json!(vk_hash));
+            assert!(row["price_paid"].is_string());
+        }
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("GROTH16"), "GROTH16");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -0,0 +1,188 @@
+//! # Network Proof Request Filter
+//!
+//! This module provides a builder for filtering the proof requests on the network.
+
+use std::collections::BTreeMap;
+
+use alloy_primitives::{Address, B256};
+
+use crate::network::proto::network::{
+    ExecutionStatus, FulfillmentStatus, GetFilteredProofRequestsRequest, ProofMode,
+};
+
+/// The number of requests fetched per page when paginating, which is the maximum allowed by the
+/// server.
+pub(crate) const DEFAULT_PAGE_LIMIT: u32 = 100;
+
+/// A filter for the proof requests on the network.
+///
+/// # Example
+/// ```rust,no_run
+/// use sp1_sdk::network::{proto::network::FulfillmentStatus, ProofRequestFilter};
+///
+/// let filter = ProofRequestFilter::new()
+///     .fulfillment_status(FulfillmentStatus::Fulfilled)
+///     .from_unix(1_700_000_000);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProofRequestFilter {
+    version: Option<String>,
+    fulfillment_status: Option<FulfillmentStatus>,
+    execution_status: Option<ExecutionStatus>,
+    minimum_deadline: Option<u64>,
+    vk_hash: Option<B256>,
+    requester: Option<Address>,
+    fulfiller: Option<Address>,
+    from: Option<u64>,
+    to: Option<u64>,
+    limit: Option<u32>,
+    mode: Option<ProofMode>,
+    labels: BTreeMap<String, String>,
+}
+
+impl ProofRequestFilter {
+    /// Creates a new [`ProofRequestFilter`] that matches every proof request.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match requests for the given SP1 circuit version.
+    #[must_use]
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Only match requests with the given fulfillment status.
+    #[must_use]
+    pub fn fulfillment_status(mut self, status: FulfillmentStatus) -> Self {
+        self.fulfillment_status = Some(status);
+        self
+    }
+
+    /// Only match requests with the given execution status.
+    #[must_use]
+    pub fn execution_status(mut self, status: ExecutionStatus) -> Self {
+        self.execution_status = Some(status);
+        self
+    }
+
+    /// Only match requests with a deadline after the given unix timestamp.
+    #[must_use]
+    pub fn minimum_deadline(mut self, deadline: u64) -> Self {
+        self.minimum_deadline = Some(deadline);
+        self
+    }
+
+    /// Only match requests for the program with the given verification key hash.
+    #[must_use]
+    pub fn vk_hash(mut self, vk_hash: B256) -> Self {
+        self.vk_hash = Some(vk_hash);
+        self
+    }
+
+    /// Only match requests made by the given address.
+    #[must_use]
+    pub fn requester(mut self, requester: Address) -> Self {
+        self.requester = Some(requester);
+        self
+    }
+
+    /// Only match requests fulfilled by the given address.
+    #[must_use]
+    pub fn fulfiller(mut self, fulfiller: Address) -> Self {
+        self.fulfiller = Some(fulfiller);
+        self
+    }
+
+    /// Only match requests created at or after the given unix timestamp.
+    #[must_use]
+    pub fn from_unix(mut self, timestamp: u64) -> Self {
+        self.from = Some(timestamp);
+        self
+    }
+
+    /// Only match requests created at or before the given unix timestamp.
+    #[must_use]
+    pub fn to_unix(mut self, timestamp: u64) -> Self {
+        self.to = Some(timestamp);
+        self
+    }
+
+    /// Set the number of requests fetched per page (at most 100).
+    #[must_use]
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Only match requests with the given proof mode.
+    #[must_use]
+    pub fn mode(mut self, mode: ProofMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Only match requests with the given label. Can be called multiple times to match requests
+    /// with all of the given labels.
+    ///
+    /// # Details
+    /// If the network does not support labels, fetching the requests fails with
+    /// [`crate::network::Error::UnsupportedCapability`].
+    #[must_use]
+    pub fn label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.labels.insert(key.into(), value.into());
+        self
+    }
+
+    /// The number of requests fetched per page.
+    pub(crate) fn page_limit(&self) -> u32 {
+        self.limit.unwrap_or(DEFAULT_PAGE_LIMIT)
+    }
+
+    /// Builds the request for the given page of results.
+    pub(crate) fn to_request(&self, page: u32) -> GetFilteredProofRequestsRequest {
+        GetFilteredProofRequestsRequest {
+            version: self.version.clone(),
+            fulfillment_status: self.fulfillment_status.map(Into::into),
+            execution_status: self.execution_status.map(Into::into),
+            minimum_deadline: self.minimum_deadline,
+            vk_hash: self.vk_hash.map(|vk_hash| vk_hash.to_vec()),
+            requester: self.requester.map(|requester| requester.to_vec()),
+            fulfiller: self.fulfiller.map(|fulfiller| fulfiller.to_vec()),
+            from: self.from,
+            to: self.to,
+            limit: Some(self.page_limit()),
+            page: Some(page),
+            mode: self.mode.map(Into::into),
+            metadata: self.labels.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_request() {
+        let requester = Address::repeat_byte(0xab);
+        let filter = ProofRequestFilter::new()
+            .requester(requester)
+            .fulfillment_status(FulfillmentStatus::Fulfilled)
+            .mode(ProofMode::Groth16)
+            .from_unix(1_700_000_000)
+            .label("block", "12345");
+
+        let request = filter.to_request(3);
+        assert_eq!(request.requester, Some(requester.to_vec()));
+        assert_eq!(request.fulfillment_status, Some(FulfillmentStatus::Fulfilled as i32));
+        assert_eq!(request.mode, Some(ProofMode::Groth16 as i32));
+        assert_eq!(request.from, Some(1_700_000_000));
+        assert_eq!(request.to, None);
+        assert_eq!(request.limit, Some(DEFAULT_PAGE_LIMIT));
+        assert_eq!(request.page, Some(3));
+        assert_eq!(request.metadata.get("block").map(String::as_str), Some("12345"));
+    }
+}
@@ -1,12 +1,25 @@
 use std::time::Duration;
 use tonic::transport::{ClientTlsConfig, Endpoint, Error};
+use tonic::Code;
+
+use super::transport::TransportConfig;
+
+/// The default timeout for a single gRPC call.
+pub const DEFAULT_RPC_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The default maximum size of a gRPC message, in bytes.
+///
+/// This is larger than the 4MB default of tonic, since programs with large verifying keys are
+/// sent and received in a single message.
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024;
 
 /// Configures the endpoint for the gRPC client.
 ///
-/// Sets reasonable settings to handle timeouts and keep-alive.
-pub fn configure_endpoint(addr: &str) -> Result<Endpoint, Error> {
+/// Sets reasonable settings to handle timeouts and keep-alive, and applies the TLS settings of the
+/// transport.
+pub fn configure_endpoint(addr: &str, transport: &TransportConfig) -> Result<Endpoint, Error> {
     let mut endpoint = Endpoint::new(addr.to_string())?
-        .timeout(Duration::from_secs(60))
+        .timeout(DEFAULT_RPC_TIMEOUT)
         .connect_timeout(Duration::from_secs(15))
         .keep_alive_while_idle(true)
         .http2_keep_alive_interval(Duration::from_secs(15))
@@ -16,9 +29,18 @@ pub fn configure_endpoint(addr: &str) -> Result<Endpoint, Error> {
 
     // Configure TLS if using HTTPS.
     if addr.starts_with("https://") {
-        let tls_config = ClientTlsConfig::new().with_enabled_roots();
+        let tls_config = transport.apply_tls(ClientTlsConfig::new().with_enabled_roots());
         endpoint = endpoint.tls_config(tls_config)?;
     }
 
     Ok(endpoint)
 }
+
+/// Whether the error indicates that the connection to the server is broken, in which case the
+/// channel should be re-established rather than reused.
+pub fn is_connection_error(e: &anyhow::Error) -> bool {
+    if e.downcast_ref::<Error>().is_some() {
+        return true;
+    }
+    e.downcast_ref::<tonic::Status>().is_some_and(|status| status.code() == Code::Unavailable)
+}
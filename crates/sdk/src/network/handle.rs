@@ -0,0 +1,578 @@
+//! # Proof Request Handle
+//!
+//! This module provides a handle to a proof request on the network, which can be used to check the
+//! status of the request and to wait for its proof.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use alloy_primitives::{B256, U256};
+use futures::{pin_mut, StreamExt};
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::oneshot;
+
+use super::client::{ensure_request_not_failed, FulfillmentParams, NetworkClient};
+use super::proto::network::{FulfillmentStatus, GetProofRequestStatusResponse, ProofMode};
+use super::prover::verify_fulfilled_proof;
+use super::request_store::RequestStore;
+use super::status::ProofRequestStatus;
+use super::webhook::ProofFulfilledNotification;
+use super::Error;
+use crate::{utils::block_on_blocking, CpuProver, SP1ProofWithPublicValues, SP1VerifyingKey};
+
+/// A handle to a proof request on the network.
+///
+/// # Details
+/// Created by [`crate::network::prove::NetworkProveBuilder::request_handle`] when a proof is
+/// requested, or by [`crate::NetworkProver::attach`] for an existing request.
+///
+/// The handle serializes to the request ID and the verifying key hash of the program, so that it
+/// can be persisted and attached to again with [`crate::NetworkProver::attach`] after a restart.
+///
+/// If the request becomes unexecutable or unfulfillable, waiting for it fails with
+/// [`Error::RequestUnexecutable`] or [`Error::RequestUnfulfillable`], along with the reason
+/// reported by the network.
+///
+/// Handles returned by [`crate::network::prove::NetworkProveBuilder::request_handle`] verify the
+/// proof against the verifying key of the program before returning it, if enabled with
+/// [`crate::network::prove::NetworkProveBuilder::verify_proof`] or by default for the mode, and
+/// fail with [`Error::ProofVerificationFailed`] if it does not verify. The proof is verified as a
+/// proof of the requested mode, see [`SP1ProofRequestHandle::mode`], so a proof of another mode
+/// fails verification. The verifying key is not serialized, so handles attached to with
+/// [`crate::NetworkProver::attach`] do not verify.
+///
+/// If the prover has a [`RequestStore`], the request is removed from it once its proof is
+/// downloaded or it can no longer be fulfilled. A request whose deadline passed without being
+/// fulfilled fails with [`Error::RequestUnfulfillable`], even if the network did not mark it
+/// unfulfillable yet.
+///
+/// # Example
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use sp1_sdk::{ProverClient, SP1Stdin, Prover};
+///
+/// tokio_test::block_on(async {
+///     let elf = &[1, 2, 3];
+///     let stdin = SP1Stdin::new();
+///
+///     let client = ProverClient::builder().network().build();
+///     let (pk, vk) = client.setup(elf);
+///     let handle = client.prove(&pk, &stdin).request_handle_async().await.unwrap();
+///
+///     // Persist the request, e.g. to resume waiting after a restart.
+///     let persisted = serde_json::to_string(&handle).unwrap();
+///
+///     let proof = handle.wait_with_timeout(Duration::from_secs(3600)).await.unwrap();
+/// })
+/// ```
+#[derive(Clone, Serialize)]
+pub struct SP1ProofRequestHandle {
+    #[serde(skip)]
+    client: Arc<NetworkClient>,
+    #[serde(skip)]
+    verifier: Option<(Arc<CpuProver>, Arc<SP1VerifyingKey>)>,
+    #[serde(skip)]
+    store: Option<Arc<dyn RequestStore>>,
+    #[serde(skip)]
+    mode: Option<ProofMode>,
+    request_id: B256,
+    vk_hash: B256,
+}
+
+impl SP1ProofRequestHandle {
+    pub(crate) fn new(client: Arc<NetworkClient>, request_id: B256, vk_hash: B256) -> Self {
+        Self { client, verifier: None, store: None, mode: None, request_id, vk_hash }
+    }
+
+    /// Removes the request from the given store once it is resolved.
+    pub(crate) fn with_store(mut self, store: Option<Arc<dyn RequestStore>>) -> Self {
+        self.store = store;
+        self
+    }
+
+    /// Sets the proof mode of the request.
+    pub(crate) fn with_mode(mut self, mode: ProofMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Verifies proofs against the given verifying key before returning them.
+    pub(crate) fn with_verifier(mut self, prover: Arc<CpuProver>, vk: SP1VerifyingKey) -> Self {
+        self.verifier = Some((prover, Arc::new(vk)));
+        self
+    }
+
+    /// The ID of the request.
+    #[must_use]
+    pub fn request_id(&self) -> B256 {
+        self.request_id
+    }
+
+    /// The verifying key hash of the program the request proves.
+    #[must_use]
+    pub fn vk_hash(&self) -> B256 {
+        self.vk_hash
+    }
+
+    /// The proof mode of the request, or `None` if it is not known.
+    #[must_use]
+    pub fn mode(&self) -> Option<ProofMode> {
+        self.mode
+    }
+
+    /// Gets the current status of the request.
+    pub async fn status(&self) -> Result<ProofRequestStatus, Error> {
+        let status = self.client.fetch_proof_request_status(self.request_id, None).await?;
+        ProofRequestStatus::try_from(status)
+    }
+
+    /// Waits for the request to be fulfilled and returns its proof.
+    pub async fn wait(&self) -> Result<SP1ProofWithPublicValues, Error> {
+        self.wait_proof(None).await
+    }
+
+    /// Waits for the request to be fulfilled and returns its proof, blocking the current thread.
+    ///
+    /// # Details
+    /// This is for callers that are not async. It returns [`Error::BlockingInAsyncContext`]
+    /// instead of blocking if called from within an async runtime, where [`Self::wait`] should be
+    /// awaited instead.
+    pub fn wait_blocking(&self) -> Result<SP1ProofWithPublicValues, Error> {
+        block_on_blocking("wait_blocking", self.wait())?
+    }
+
+    /// Waits for the request to be fulfilled and returns its proof, or returns
+    /// [`Error::RequestTimedOut`] if it is not fulfilled within the timeout.
+    pub async fn wait_with_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<SP1ProofWithPublicValues, Error> {
+        self.wait_proof(Some(timeout)).await
+    }
+
+    /// Waits for the webhook notification of the request, then returns its proof, falling back to
+    /// polling if no notification arrives within the grace period.
+    ///
+    /// # Details
+    /// The request must have been made with a callback URL, e.g. with
+    /// [`crate::network::prove::NetworkProveBuilder::callback_url`]. The receiver of the callback
+    /// sends the notification of this request to the channel, once it is verified with
+    /// [`crate::network::webhook::verify_notification`].
+    ///
+    /// No RPC is made while waiting for the notification. Once it arrives, the status is fetched
+    /// once and the proof downloaded. If the grace period elapses, the sender is dropped, or the
+    /// request is not fulfilled when the notification arrives, this waits as
+    /// [`SP1ProofRequestHandle::wait`] does.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover};
+    ///
+    /// tokio_test::block_on(async {
+    ///     let elf = &[1, 2, 3];
+    ///     let stdin = SP1Stdin::new();
+    ///
+    ///     let client = ProverClient::builder().network().build();
+    ///     let (pk, vk) = client.setup(elf);
+    ///     let handle = client
+    ///         .prove(&pk, &stdin)
+    ///         .callback_url("https://example.com/proofs")
+    ///         .request_handle_async()
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     // Hand the sender to the webhook receiver, keyed by `handle.request_id()`.
+    ///     let (sender, receiver) = tokio::sync::oneshot::channel();
+    ///     let proof = handle
+    ///         .wait_with_notification(receiver, Duration::from_secs(3600))
+    ///         .await
+    ///         .unwrap();
+    /// })
+    /// ```
+    pub async fn wait_with_notification(
+        &self,
+        notification: oneshot::Receiver<ProofFulfilledNotification>,
+        grace_period: Duration,
+    ) -> Result<SP1ProofWithPublicValues, Error> {
+        match tokio::time::timeout(grace_period, notification).await {
+            Ok(Ok(notification)) if notification.request_id == self.request_id => {
+                let status = self.client.fetch_proof_request_status(self.request_id, None).await?;
+                if status.fulfillment_status == FulfillmentStatus::Fulfilled as i32 {
+                    return self.finish(&status).await;
+                }
+                log::debug!("Request {} is not fulfilled yet, polling", self.request_id);
+            }
+            Ok(Ok(notification)) => log::warn!(
+                "Received a notification for request {} while waiting for {}, polling",
+                notification.request_id,
+                self.request_id
+            ),
+            Ok(Err(_)) => log::debug!("No notifier for {}, polling", self.request_id),
+            Err(_) => log::debug!("No notification for {} yet, polling", self.request_id),
+        }
+        self.wait_proof(None).await
+    }
+
+    /// Waits until the request reaches the given status, or a later one, and returns it.
+    ///
+    /// # Details
+    /// The status must be `Requested`, `Assigned` or `Fulfilled`, since the request never reaches
+    /// a status after it becomes unfulfillable. For example, waiting until the request is
+    /// `Assigned` also returns if it is already fulfilled.
+    pub async fn wait_until(&self, status: FulfillmentStatus) -> Result<ProofRequestStatus, Error> {
+        let result = self.wait_for(status, None).await;
+        self.settle(result.as_ref().err().is_some_and(|e| self.is_failure(e))).await;
+        ProofRequestStatus::try_from(result?)
+    }
+
+    /// Returns the proof if the request is fulfilled, without waiting.
+    ///
+    /// # Details
+    /// Returns `None` if the request is not fulfilled yet, and an error if it never will be.
+    pub async fn try_proof<P: DeserializeOwned>(&self) -> Result<Option<P>, Error> {
+        let status = self.client.fetch_proof_request_status(self.request_id, None).await?;
+        if let Err(e) = ensure_request_not_failed(self.request_id, &status) {
+            self.settle(true).await;
+            return Err(e);
+        }
+        if status.fulfillment_status != FulfillmentStatus::Fulfilled as i32 {
+            return Ok(None);
+        }
+        let proof = self.client.download_proof(&status).await?;
+        self.settle(true).await;
+        Ok(Some(proof))
+    }
+
+    /// Replaces the request with one that pays at most the given price per billion cycles, e.g.
+    /// when an auction request is not being picked up, and returns the handle of the replacement.
+    ///
+    /// # Details
+    /// No artifacts are uploaded again. The replacement only sets the maximum price, so other
+    /// fulfillment parameters and labels of the original request are not carried over. See
+    /// [`NetworkClient::replace_proof_request`] for details.
+    ///
+    /// If the prover has a [`RequestStore`], the persisted request is replaced as well.
+    ///
+    /// Returns [`Error::RequestAlreadyAssigned`] if the request was already assigned to a prover.
+    pub async fn bump_price(&self, new_max_price_per_bgas: U256) -> Result<Self, Error> {
+        let params = FulfillmentParams {
+            max_price_per_bgas: Some(new_max_price_per_bgas),
+            ..Default::default()
+        };
+        let response = self.client.replace_proof_request(self.request_id, &params).await?;
+        let body =
+            response.body.ok_or_else(|| Error::from(anyhow::anyhow!("Missing response body")))?;
+        let request_id = B256::from_slice(&body.request_id);
+        if let Some(store) = &self.store {
+            self.replace_in_store(store.as_ref(), request_id).await;
+        }
+        Ok(Self { request_id, ..self.clone() })
+    }
+
+    /// Replaces the persisted request with its replacement, keeping its other details.
+    async fn replace_in_store(&self, store: &dyn RequestStore, request_id: B256) {
+        let result = async {
+            let requests = store.list().await?;
+            let Some(mut pending) = requests.into_iter().find(|r| r.request_id == self.request_id)
+            else {
+                return anyhow::Ok(());
+            };
+            pending.request_id = request_id;
+            store.insert(&pending).await?;
+            store.remove(self.request_id).await
+        };
+        if let Err(e) = result.await {
+            log::warn!("Failed to replace request {} in the request store: {e:#}", self.request_id);
+        }
+    }
+
+    /// Waits for the request to be fulfilled, up to the timeout, and returns its proof.
+    async fn wait_proof(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<SP1ProofWithPublicValues, Error> {
+        let status = match self.wait_for(FulfillmentStatus::Fulfilled, timeout).await {
+            Ok(status) => status,
+            Err(e) => {
+                self.settle(self.is_failure(&e)).await;
+                return Err(e);
+            }
+        };
+        self.finish(&status).await
+    }
+
+    /// Downloads and verifies the proof of the fulfilled request.
+    async fn finish(
+        &self,
+        status: &GetProofRequestStatusResponse,
+    ) -> Result<SP1ProofWithPublicValues, Error> {
+        let proof = self.client.download_proof(status).await?;
+        self.settle(true).await;
+        if let Some((prover, vk)) = &self.verifier {
+            verify_fulfilled_proof(prover, vk, &proof, self.request_id, status, self.mode)?;
+        }
+        Ok(proof)
+    }
+
+    /// Whether the error means that the request can no longer be fulfilled.
+    fn is_failure(&self, e: &Error) -> bool {
+        match e {
+            Error::RequestUnexecutable { request_id, .. }
+            | Error::RequestUnfulfillable { request_id, .. } => {
+                request_id.as_slice() == self.request_id.as_slice()
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes the request from the request store, if it is resolved.
+    async fn settle(&self, resolved: bool) {
+        let Some(store) = self.store.as_ref().filter(|_| resolved) else {
+            return;
+        };
+        if let Err(e) = store.remove(self.request_id).await {
+            log::warn!(
+                "Failed to remove request {} from the request store: {e:#}",
+                self.request_id
+            );
+        }
+    }
+
+    /// Waits until the request reaches the given status, or a later one, up to the timeout.
+    async fn wait_for(
+        &self,
+        target: FulfillmentStatus,
+        timeout: Option<Duration>,
+    ) -> Result<GetProofRequestStatusResponse, Error> {
+        let Some(target_rank) = progress(target) else {
+            return Err(Error::InvalidRequest {
+                reason: format!("cannot wait until a request is {}", target.as_str_name()),
+            });
+        };
+
+        let wait = async {
+            let statuses = self.client.subscribe_proof_request_status(self.request_id);
+            pin_mut!(statuses);
+            while let Some(status) = statuses.next().await {
+                let status = status.map_err(Error::from)?;
+                let reached = FulfillmentStatus::try_from(status.fulfillment_status)
+                    .ok()
+                    .and_then(progress)
+                    .is_some_and(|rank| rank >= target_rank);
+                if reached {
+                    return Ok(status);
+                }
+
+                // The request cannot be fulfilled after its deadline, e.g. if it was persisted
+                // and resumed after the deadline passed.
+                let expired =
+                    status.deadline > 0 && self.client.now().is_ok_and(|now| now > status.deadline);
+                if expired {
+                    return Err(Error::RequestUnfulfillable {
+                        request_id: self.request_id.to_vec(),
+                        reason: Some("deadline exceeded".to_string()),
+                    });
+                }
+            }
+            Err(Error::from(anyhow::anyhow!(
+                "Status subscription for request {} ended unexpectedly",
+                self.request_id
+            )))
+        };
+
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, wait)
+                .await
+                .map_err(|_| Error::RequestTimedOut { request_id: self.request_id.to_vec() })?,
+            None => wait.await,
+        }
+    }
+}
+
+/// The progress of a request with the given status, or `None` if the status is not part of the
+/// regular progression of a request.
+fn progress(status: FulfillmentStatus) -> Option<u8> {
+    match status {
+        FulfillmentStatus::Requested => Some(1),
+        FulfillmentStatus::Assigned => Some(2),
+        FulfillmentStatus::Fulfilled => Some(3),
+        FulfillmentStatus::UnspecifiedFulfillmentStatus | FulfillmentStatus::Unfulfillable => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::Bytes;
+
+    use super::*;
+    use crate::network::test_utils::{MockNetworkServer, MockOutcome};
+    use crate::{NetworkProver, SP1ProofMode, SP1Stdin};
+
+    const PRIVATE_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+    async fn request(server: &MockNetworkServer, outcome: MockOutcome) -> SP1ProofRequestHandle {
+        let vk_hash = B256::repeat_byte(1);
+        server.insert_program(vk_hash);
+        server.push_outcome(outcome);
+        let prover = NetworkProver::new(PRIVATE_KEY, server.rpc_url());
+        prover.prove_with_vk_hash(vk_hash, SP1Stdin::new()).request_handle_async().await.unwrap()
+    }
+
+    fn notification(request_id: B256) -> ProofFulfilledNotification {
+        ProofFulfilledNotification {
+            request_id,
+            proof_uri: String::new(),
+            public_values_hash: B256::ZERO,
+            signature: Bytes::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_with_notification() {
+        let server = MockNetworkServer::start().await.unwrap();
+        let handle = request(&server, MockOutcome::Fulfilled { delay: Duration::ZERO }).await;
+        let (sender, receiver) = oneshot::channel();
+        sender.send(notification(handle.request_id())).unwrap();
+
+        // The notification ends the wait long before the grace period.
+        let wait = handle.wait_with_notification(receiver, Duration::from_secs(3600));
+        let proof = tokio::time::timeout(Duration::from_secs(10), wait).await.unwrap();
+        assert!(proof.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_wait_with_notification_falls_back_to_polling() {
+        let server = MockNetworkServer::start().await.unwrap();
+        let delay = Duration::from_millis(100);
+
+        // No notification within the grace period.
+        let handle = request(&server, MockOutcome::Fulfilled { delay }).await;
+        let (_sender, receiver) = oneshot::channel();
+        let proof = handle.wait_with_notification(receiver, Duration::from_millis(200)).await;
+        assert!(proof.is_ok());
+
+        // The notification channel is closed.
+        let handle = request(&server, MockOutcome::Fulfilled { delay }).await;
+        let (sender, receiver) = oneshot::channel();
+        drop(sender);
+        let wait = handle.wait_with_notification(receiver, Duration::from_secs(3600));
+        assert!(tokio::time::timeout(Duration::from_secs(10), wait).await.unwrap().is_ok());
+
+        // The notification is for another request.
+        let handle = request(&server, MockOutcome::Fulfilled { delay }).await;
+        let (sender, receiver) = oneshot::channel();
+        sender.send(notification(B256::repeat_byte(9))).unwrap();
+        let wait = handle.wait_with_notification(receiver, Duration::from_secs(3600));
+        assert!(tokio::time::timeout(Duration::from_secs(10), wait).await.unwrap().is_ok());
+    }
+
+    #[test]
+    fn test_blocking_from_plain_threads() {
+        // The server runs on its own runtime, which the blocking calls are not made from.
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let server = runtime.block_on(MockNetworkServer::start()).unwrap();
+        let vk_hash = B256::repeat_byte(1);
+        server.insert_program(vk_hash);
+        let prover = Arc::new(NetworkProver::new(PRIVATE_KEY, server.rpc_url()));
+
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let prover = prover.clone();
+                std::thread::spawn(move || {
+                    let handle = prover
+                        .prove_with_vk_hash(vk_hash, SP1Stdin::new())
+                        .request_handle_blocking()
+                        .unwrap();
+                    handle.wait_blocking().unwrap();
+                    prover.prove_with_vk_hash(vk_hash, SP1Stdin::new()).run_blocking().unwrap();
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_blocking_in_async_context() {
+        let server = MockNetworkServer::start().await.unwrap();
+        let handle = request(&server, MockOutcome::Fulfilled { delay: Duration::ZERO }).await;
+        let err = handle.wait_blocking().unwrap_err();
+        assert!(matches!(err, Error::BlockingInAsyncContext { method: "wait_blocking" }));
+
+        // The request is not submitted.
+        let prover = NetworkProver::new(PRIVATE_KEY, server.rpc_url());
+        let err = prover
+            .prove_with_vk_hash(B256::repeat_byte(1), SP1Stdin::new())
+            .run_blocking()
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::BlockingInAsyncContext { method: "run_blocking" })
+        ));
+    }
+
+    #[test]
+    fn test_progress() {
+        assert!(progress(FulfillmentStatus::Requested) < progress(FulfillmentStatus::Assigned));
+        assert!(progress(FulfillmentStatus::Assigned) < progress(FulfillmentStatus::Fulfilled));
+        assert_eq!(progress(FulfillmentStatus::Unfulfillable), None);
+    }
+
+    #[test]
+    fn test_unfulfillable_status_error() {
+        let request_id = B256::repeat_byte(1);
+        let status = GetProofRequestStatusResponse {
+            fulfillment_status: FulfillmentStatus::Unfulfillable.into(),
+            failure_reason: Some("deadline exceeded".to_string()),
+            ..Default::default()
+        };
+        let err = ensure_request_not_failed(request_id, &status).unwrap_err();
+        assert!(matches!(
+            &err,
+            Error::RequestUnfulfillable { reason: Some(reason), .. } if reason == "deadline exceeded"
+        ));
+        assert!(err.to_string().ends_with(": deadline exceeded"));
+    }
+
+    #[tokio::test]
+    async fn test_handle_mode() {
+        let server = MockNetworkServer::start().await.unwrap();
+        let vk_hash = B256::repeat_byte(1);
+        server.insert_program(vk_hash);
+        let prover = NetworkProver::new(PRIVATE_KEY, server.rpc_url());
+        let handle = prover
+            .prove_with_vk_hash(vk_hash, SP1Stdin::new())
+            .mode(SP1ProofMode::Plonk)
+            .request_handle_async()
+            .await
+            .unwrap();
+        assert_eq!(handle.mode(), Some(ProofMode::Plonk));
+
+        let attached = prover.attach(handle.request_id()).await.unwrap();
+        assert_eq!(attached.mode(), Some(ProofMode::Plonk));
+    }
+
+    #[test]
+    fn test_serialize_handle() {
+        let client = NetworkClient::new(
+            "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318",
+            "https://rpc.example.com",
+        )
+        .unwrap();
+        let handle = SP1ProofRequestHandle::new(
+            Arc::new(client),
+            B256::repeat_byte(1),
+            B256::repeat_byte(2),
+        );
+        let json = serde_json::to_value(&handle).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "request_id": B256::repeat_byte(1),
+                "vk_hash": B256::repeat_byte(2),
+            })
+        );
+    }
+}
@@ -9,18 +9,65 @@ pub mod prover;
 #[allow(clippy::default_trait_access)]
 #[allow(clippy::too_many_lines)]
 pub mod proto;
+mod artifact_transport;
+mod auxiliary;
 pub mod builder;
+mod calldata;
+mod compression;
+mod eip712;
+mod endpoints;
 mod error;
+mod export;
+mod filter;
 mod grpc;
+mod handle;
+mod nonce;
+mod polling;
 pub mod prove;
+mod rate_limit;
+mod relay;
+mod request_store;
 mod retry;
+#[cfg(feature = "s3")]
+mod s3;
+mod status;
+mod telemetry;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+mod transport;
 pub mod utils;
+pub mod webhook;
 
-pub use crate::network::client::NetworkClient;
+pub use crate::network::artifact_transport::{
+    ArtifactStream, ArtifactTransport, DownloadedArtifact, PresignedUrlTransport,
+};
+pub use crate::network::auxiliary::AuxiliaryArtifactType;
+pub use crate::network::calldata::OnchainCalldata;
+pub use crate::network::client::{
+    supported_modes, AccountBalance, CostEstimate, FulfillmentParams, NetworkClient,
+    NetworkClientBuilder, ProgramMetadata, ProofRequestSpec,
+};
+pub use crate::network::compression::Compression;
+pub use crate::network::eip712::{signing_domain, DEFAULT_DOMAIN_NAME, DOMAIN_VERSION};
+pub use crate::network::export::{ExportFormat, ExportedRequest};
+pub use crate::network::filter::ProofRequestFilter;
+pub use crate::network::handle::SP1ProofRequestHandle;
+pub use crate::network::polling::PollingConfig;
 pub use crate::network::proto::network::FulfillmentStrategy;
+pub use crate::network::relay::{SignedProofRequest, SimulatedProofRequest, UnsignedProofRequest};
+pub use crate::network::request_store::{
+    JsonFileRequestStore, MemoryRequestStore, PendingRequest, RequestStore,
+};
+pub use crate::network::retry::{CircuitBreakerPolicy, RetryClassifier, RetryPolicy};
+#[cfg(feature = "s3")]
+pub use crate::network::s3::{S3Credentials, S3Transport};
+pub use crate::network::status::ProofRequestStatus;
+pub use crate::network::utils::NetworkSigner;
 pub use alloy_primitives::B256;
 pub use error::*;
 
 pub(crate) const DEFAULT_NETWORK_RPC_URL: &str = "https://rpc.production.succinct.xyz/";
 pub(crate) const DEFAULT_TIMEOUT_SECS: u64 = 14400;
+pub(crate) const MIN_TIMEOUT_SECS: u64 = 60;
+pub(crate) const DEFAULT_MAX_TIMEOUT_SECS: u64 = 86400;
 pub(crate) const DEFAULT_CYCLE_LIMIT: u64 = 100_000_000;
@@ -0,0 +1,320 @@
+//! # Network Nonce Manager
+//!
+//! This module provides a cache of the account nonce, so that signed requests sent concurrently
+//! from the same process each use a distinct nonce.
+
+use std::future::Future;
+
+use anyhow::{anyhow, Result};
+use futures::{stream, StreamExt};
+use tokio::sync::Mutex;
+use tonic::Code;
+
+/// Hands out the nonces used to sign requests.
+///
+/// # Details
+/// The nonce is fetched from the network once and incremented locally for every request that is
+/// accepted. Signed requests are sent one at a time, so that the network receives them in nonce
+/// order, except for the requests of a batch, see [`NonceManager::with_nonces`]. If a request
+/// fails, the cached nonce is discarded and fetched again for the next request, since it is unknown
+/// whether the failed request consumed it.
+#[derive(Debug, Default)]
+pub(crate) struct NonceManager {
+    next_nonce: Mutex<Option<u64>>,
+}
+
+impl NonceManager {
+    /// Sends a signed request with the next nonce of the account.
+    ///
+    /// # Details
+    /// * `fetch`: Fetches the latest nonce of the account from the network.
+    /// * `send`: Signs and sends the request with the given nonce.
+    ///
+    /// If the network rejects the nonce, it is fetched again and the request is re-sent once.
+    pub(crate) async fn with_nonce<T, F, FFut, S, SFut>(&self, fetch: F, send: S) -> Result<T>
+    where
+        F: Fn() -> FFut,
+        FFut: Future<Output = Result<u64>>,
+        S: Fn(u64) -> SFut,
+        SFut: Future<Output = Result<T>>,
+    {
+        let mut next_nonce = self.next_nonce.lock().await;
+        let nonce = match *next_nonce {
+            Some(nonce) => nonce,
+            None => fetch().await?,
+        };
+
+        let result = match send(nonce).await {
+            Err(e) if is_nonce_mismatch(&e) => {
+                log::debug!("Nonce {nonce} was rejected by the network, resyncing");
+                *next_nonce = None;
+                let nonce = fetch().await?;
+                send(nonce).await.map(|response| (response, nonce))
+            }
+            result => result.map(|response| (response, nonce)),
+        };
+
+        *next_nonce = result.as_ref().ok().map(|(_, nonce)| nonce + 1);
+        result.map(|(response, _)| response)
+    }
+
+    /// Sends a batch of `len` signed requests with consecutive nonces of the account, returning
+    /// the result of each request in order.
+    ///
+    /// # Details
+    /// * `fetch`: Fetches the latest nonce of the account from the network.
+    /// * `sign`: Signs the request at the given index with the given nonce.
+    /// * `send`: Sends a signed request.
+    ///
+    /// The nonces are reserved up front, every request is signed, and the signed requests are
+    /// sent with at most `concurrency` in flight. Requests that fail to sign are not assigned a
+    /// nonce. If the network rejects the nonce of some requests, e.g. because another request of
+    /// the batch failed and left a gap, the nonce is fetched again and only those requests are
+    /// re-signed and re-sent, for as long as each round gets some of them accepted.
+    pub(crate) async fn with_nonces<R, T, F, FFut, G, S, SFut>(
+        &self,
+        len: usize,
+        concurrency: usize,
+        fetch: F,
+        sign: G,
+        send: S,
+    ) -> Vec<Result<T>>
+    where
+        F: Fn() -> FFut,
+        FFut: Future<Output = Result<u64>>,
+        G: Fn(usize, u64) -> Result<R>,
+        S: Fn(R) -> SFut,
+        SFut: Future<Output = Result<T>>,
+    {
+        let mut next_nonce = self.next_nonce.lock().await;
+        let mut results = (0..len).map(|_| None).collect::<Vec<Option<Result<T>>>>();
+        let mut pending = (0..len).collect::<Vec<_>>();
+        let mut stalled = false;
+        while !pending.is_empty() {
+            // Reserve consecutive nonces for the pending requests and sign them.
+            let mut nonce = match *next_nonce {
+                Some(nonce) => nonce,
+                None => match fetch().await {
+                    Ok(nonce) => nonce,
+                    Err(e) => {
+                        for i in pending {
+                            results[i] = Some(Err(anyhow!("failed to fetch the nonce: {e:#}")));
+                        }
+                        break;
+                    }
+                },
+            };
+            let mut signed = Vec::with_capacity(pending.len());
+            for i in pending.drain(..) {
+                match sign(i, nonce) {
+                    Ok(request) => {
+                        signed.push((i, request));
+                        nonce += 1;
+                    }
+                    Err(e) => results[i] = Some(Err(e)),
+                }
+            }
+
+            // Send the signed requests.
+            let sent = stream::iter(signed)
+                .map(|(i, request)| {
+                    let response = send(request);
+                    async move { (i, response.await) }
+                })
+                .buffer_unordered(concurrency.max(1))
+                .collect::<Vec<_>>()
+                .await;
+            let mut accepted = false;
+            let mut failed = false;
+            for (i, result) in sent {
+                match &result {
+                    Ok(_) => accepted = true,
+                    Err(e) if is_nonce_mismatch(e) => pending.push(i),
+                    Err(_) => failed = true,
+                }
+                results[i] = Some(result);
+            }
+
+            // A failed request may or may not have consumed its nonce.
+            *next_nonce = (!failed && pending.is_empty()).then_some(nonce);
+            if pending.is_empty() {
+                break;
+            }
+            if !accepted {
+                if stalled {
+                    break;
+                }
+                stalled = true;
+            } else {
+                stalled = false;
+            }
+            log::debug!("{} nonces were rejected by the network, resyncing", pending.len());
+            pending.sort_unstable();
+        }
+
+        results.into_iter().map(|result| result.expect("every request has a result")).collect()
+    }
+}
+
+/// Whether the error was caused by the network rejecting the nonce of a signed request.
+fn is_nonce_mismatch(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<tonic::Status>().is_some_and(|status| {
+        matches!(status.code(), Code::InvalidArgument | Code::FailedPrecondition)
+            && status.message().to_lowercase().contains("nonce")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex as StdMutex};
+
+    use super::*;
+
+    /// A network that only accepts requests signed with the account's current nonce.
+    #[derive(Default)]
+    struct MockNetwork {
+        nonce: StdMutex<u64>,
+        accepted: StdMutex<Vec<u64>>,
+        fetches: AtomicUsize,
+    }
+
+    impl MockNetwork {
+        async fn get_nonce(&self) -> Result<u64> {
+            self.fetches.fetch_add(1, Ordering::SeqCst);
+            Ok(*self.nonce.lock().unwrap())
+        }
+
+        async fn request_proof(&self, nonce: u64) -> Result<u64> {
+            tokio::task::yield_now().await;
+            let mut expected = self.nonce.lock().unwrap();
+            if nonce != *expected {
+                return Err(tonic::Status::invalid_argument(format!(
+                    "invalid nonce: expected {expected}, got {nonce}"
+                ))
+                .into());
+            }
+            *expected += 1;
+            self.accepted.lock().unwrap().push(nonce);
+            Ok(nonce)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_use_increasing_nonces() {
+        let network = Arc::new(MockNetwork::default());
+        let nonces = Arc::new(NonceManager::default());
+
+        let handles = (0..10)
+            .map(|_| {
+                let network = network.clone();
+                let nonces = nonces.clone();
+                tokio::spawn(async move {
+                    nonces
+                        .with_nonce(|| network.get_nonce(), |nonce| network.request_proof(nonce))
+                        .await
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.await.unwrap().unwrap();
+        }
+
+        let accepted = network.accepted.lock().unwrap().clone();
+        assert_eq!(accepted, (0..10).collect::<Vec<_>>());
+        assert_eq!(network.fetches.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_resync_on_nonce_mismatch() {
+        let network = MockNetwork::default();
+        let nonces = NonceManager::default();
+        nonces
+            .with_nonce(|| network.get_nonce(), |nonce| network.request_proof(nonce))
+            .await
+            .unwrap();
+
+        // Another process uses the account in the meantime.
+        *network.nonce.lock().unwrap() = 5;
+
+        let nonce = nonces
+            .with_nonce(|| network.get_nonce(), |nonce| network.request_proof(nonce))
+            .await
+            .unwrap();
+        assert_eq!(nonce, 5);
+        assert_eq!(network.fetches.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_batch_uses_consecutive_nonces() {
+        let network = &MockNetwork::default();
+        let nonces = NonceManager::default();
+
+        let results = nonces
+            .with_nonces(
+                10,
+                4,
+                || network.get_nonce(),
+                |i, nonce| Ok((i, nonce)),
+                |(i, nonce)| async move { network.request_proof(nonce).await.map(|_| i) },
+            )
+            .await;
+
+        // Every request is accepted, and the results are in the order of the requests.
+        let results = results.into_iter().map(Result::unwrap).collect::<Vec<_>>();
+        assert_eq!(results, (0..10).collect::<Vec<_>>());
+        let mut accepted = network.accepted.lock().unwrap().clone();
+        accepted.sort_unstable();
+        assert_eq!(accepted, (0..10).collect::<Vec<_>>());
+
+        // The next request continues from the nonces of the batch.
+        let nonce = nonces
+            .with_nonce(|| network.get_nonce(), |nonce| network.request_proof(nonce))
+            .await
+            .unwrap();
+        assert_eq!(nonce, 10);
+    }
+
+    #[tokio::test]
+    async fn test_batch_resyncs_after_failures() {
+        let network = &MockNetwork::default();
+        let nonces = NonceManager::default();
+
+        // Request 1 fails to sign and request 3 is rejected for another reason than its nonce,
+        // leaving a gap that the requests signed after it are re-signed across.
+        let results = nonces
+            .with_nonces(
+                6,
+                6,
+                || network.get_nonce(),
+                |i, nonce| if i == 1 { Err(anyhow!("failed to sign")) } else { Ok((i, nonce)) },
+                |(i, nonce)| async move {
+                    if i == 3 {
+                        return Err(anyhow!("invalid request"));
+                    }
+                    network.request_proof(nonce).await.map(|_| i)
+                },
+            )
+            .await;
+
+        assert_eq!(results.len(), 6);
+        for (i, result) in results.iter().enumerate() {
+            match i {
+                1 | 3 => assert!(result.is_err()),
+                _ => assert_eq!(*result.as_ref().unwrap(), i),
+            }
+        }
+        let mut accepted = network.accepted.lock().unwrap().clone();
+        accepted.sort_unstable();
+        assert_eq!(accepted, (0..4).collect::<Vec<_>>());
+
+        // The nonce was fetched once more to resync, and the next request continues from it.
+        assert_eq!(network.fetches.load(Ordering::SeqCst), 2);
+        let nonce = nonces
+            .with_nonce(|| network.get_nonce(), |nonce| network.request_proof(nonce))
+            .await
+            .unwrap();
+        assert_eq!(nonce, 4);
+        assert_eq!(network.fetches.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,145 @@
+//! # Status Polling
+//!
+//! This module configures how often the status of a proof request is polled when the network
+//! does not support streaming status updates.
+
+use std::time::{Duration, Instant};
+
+use backoff::{exponential::ExponentialBackoff, Clock};
+
+/// How the status of a proof request is polled while waiting for its proof.
+///
+/// # Details
+/// Polls are spaced out with exponential backoff, from `initial_interval` up to `max_interval`.
+/// The interval starts over whenever the status of the request changes, and once the request is
+/// assigned to a prover it is capped at `assigned_max_interval` instead, so that a proof is
+/// picked up soon after it is fulfilled.
+///
+/// Each interval is randomized by up to `jitter_fraction` in either direction, so that many
+/// requests waited on at the same time are not polled in lockstep. If the network asks for a
+/// longer interval with a `retry-after` hint, the hint is respected.
+///
+/// Polling is only used if the network does not support streaming status updates.
+///
+/// # Example
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use sp1_sdk::{network::PollingConfig, ProverClient};
+///
+/// let prover = ProverClient::builder()
+///     .network()
+///     .polling(PollingConfig {
+///         max_interval: Duration::from_secs(60),
+///         assigned_max_interval: Duration::from_secs(10),
+///         jitter_fraction: 0.2,
+///         ..Default::default()
+///     })
+///     .build();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PollingConfig {
+    /// The interval before the first poll after the status of the request changes.
+    pub initial_interval: Duration,
+    /// The maximum interval between polls.
+    pub max_interval: Duration,
+    /// The maximum interval between polls once the request is assigned to a prover.
+    pub assigned_max_interval: Duration,
+    /// The factor the interval is multiplied by after each poll.
+    pub multiplier: f64,
+    /// The fraction by which each interval is randomized, between 0 and 1.
+    pub jitter_fraction: f64,
+}
+
+impl Default for PollingConfig {
+    fn default() -> Self {
+        Self {
+            initial_interval: Duration::from_secs(1),
+            max_interval: Duration::from_secs(30),
+            assigned_max_interval: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter_fraction: 0.0,
+        }
+    }
+}
+
+impl PollingConfig {
+    /// Builds the polling schedule for a request that is, or is not yet, assigned to a prover.
+    pub(crate) fn backoff<C: Clock>(&self, assigned: bool, clock: C) -> ExponentialBackoff<C> {
+        ExponentialBackoff {
+            current_interval: self.initial_interval,
+            initial_interval: self.initial_interval,
+            randomization_factor: self.jitter_fraction.clamp(0.0, 1.0),
+            multiplier: self.multiplier,
+            max_interval: if assigned { self.assigned_max_interval } else { self.max_interval },
+            start_time: Instant::now(),
+            max_elapsed_time: None,
+            clock,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use backoff::backoff::Backoff;
+
+    use super::*;
+
+    /// A clock that only advances when told to.
+    #[derive(Clone)]
+    struct MockClock(Arc<Mutex<Instant>>);
+
+    impl Clock for MockClock {
+        fn now(&self) -> Instant {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    /// Returns the times of the given number of polls, in seconds since the first poll.
+    fn poll_times(config: &PollingConfig, assigned: bool, polls: usize) -> Vec<u64> {
+        let start = Instant::now();
+        let clock = MockClock(Arc::new(Mutex::new(start)));
+        let mut backoff = config.backoff(assigned, clock.clone());
+        (0..polls)
+            .map(|_| {
+                let elapsed = clock.now() - start;
+                *clock.0.lock().unwrap() += backoff.next_backoff().unwrap();
+                elapsed.as_secs()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_default_schedule() {
+        // The interval doubles from one second up to 30 seconds.
+        let config = PollingConfig::default();
+        assert_eq!(poll_times(&config, false, 8), [0, 1, 3, 7, 15, 31, 61, 91]);
+        assert_eq!(poll_times(&config, true, 8), [0, 1, 3, 7, 15, 31, 61, 91]);
+    }
+
+    #[test]
+    fn test_assigned_schedule() {
+        let config = PollingConfig {
+            initial_interval: Duration::from_secs(2),
+            max_interval: Duration::from_secs(60),
+            assigned_max_interval: Duration::from_secs(5),
+            multiplier: 3.0,
+            ..Default::default()
+        };
+        assert_eq!(poll_times(&config, false, 6), [0, 2, 8, 26, 80, 140]);
+        assert_eq!(poll_times(&config, true, 6), [0, 2, 7, 12, 17, 22]);
+    }
+
+    #[test]
+    fn test_jitter() {
+        let config = PollingConfig { jitter_fraction: 0.5, ..Default::default() };
+        let mut backoff = config.backoff(false, backoff::SystemClock {});
+        let mut expected = 1.0;
+        for _ in 0..10 {
+            let interval = backoff.next_backoff().unwrap().as_secs_f64();
+            assert!(interval >= expected * 0.5 - 1e-6 && interval <= expected * 1.5 + 1e-6);
+            expected = (expected * 2.0_f64).min(30.0);
+        }
+    }
+}
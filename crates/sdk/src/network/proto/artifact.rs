@@ -7,6 +7,23 @@ pub struct CreateArtifactRequest {
     /// The type of artifact to create.
     #[prost(enumeration = "ArtifactType", tag = "2")]
     pub artifact_type: i32,
+    /// The optional content encoding (e.g. gzip, zstd) that the artifact will be uploaded with.
+    #[prost(string, optional, tag = "3")]
+    pub content_encoding: ::core::option::Option<::prost::alloc::string::String>,
+    /// The optional SHA-256 hash of the uncompressed artifact content. Used to verify the
+    /// integrity of the artifact when it is downloaded.
+    #[prost(bytes = "vec", optional, tag = "4")]
+    pub sha256: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// The format of the signature. Unspecified for the legacy signature over the message.
+    #[prost(enumeration = "super::network::MessageFormat", tag = "5")]
+    pub format: i32,
+    /// The identifier of the proof request to attach the artifact to. Only set for auxiliary
+    /// artifacts, e.g. execution reports.
+    #[prost(bytes = "vec", optional, tag = "6")]
+    pub request_id: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// The user-defined type of the artifact, if its type is `CUSTOM`.
+    #[prost(uint32, optional, tag = "7")]
+    pub custom_type: ::core::option::Option<u32>,
 }
 #[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct CreateArtifactResponse {
@@ -16,6 +33,134 @@ pub struct CreateArtifactResponse {
     /// The presigned url to upload the artifact.
     #[prost(string, tag = "2")]
     pub artifact_presigned_url: ::prost::alloc::string::String,
+    /// The optional content encoding accepted by the server. If not set, the artifact must be
+    /// uploaded uncompressed.
+    #[prost(string, optional, tag = "3")]
+    pub content_encoding: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct CreateMultipartArtifactRequest {
+    /// The signature of the user on a pre-defined message. Used for authentication.
+    #[prost(bytes = "vec", tag = "1")]
+    pub signature: ::prost::alloc::vec::Vec<u8>,
+    /// The type of artifact to create.
+    #[prost(enumeration = "ArtifactType", tag = "2")]
+    pub artifact_type: i32,
+    /// The optional content encoding (e.g. gzip, zstd) that the artifact will be uploaded with.
+    #[prost(string, optional, tag = "3")]
+    pub content_encoding: ::core::option::Option<::prost::alloc::string::String>,
+    /// The optional SHA-256 hash of the uncompressed artifact content. Used to verify the
+    /// integrity of the artifact when it is downloaded.
+    #[prost(bytes = "vec", optional, tag = "4")]
+    pub sha256: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// The format of the signature. Unspecified for the legacy signature over the message.
+    #[prost(enumeration = "super::network::MessageFormat", tag = "5")]
+    pub format: i32,
+    /// The identifier of the proof request to attach the artifact to. Only set for auxiliary
+    /// artifacts, e.g. execution reports.
+    #[prost(bytes = "vec", optional, tag = "6")]
+    pub request_id: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// The user-defined type of the artifact, if its type is `CUSTOM`.
+    #[prost(uint32, optional, tag = "7")]
+    pub custom_type: ::core::option::Option<u32>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct CreateMultipartArtifactResponse {
+    /// The unique resource identifier of the artifact.
+    #[prost(string, tag = "1")]
+    pub artifact_uri: ::prost::alloc::string::String,
+    /// The identifier of the multipart upload.
+    #[prost(string, tag = "2")]
+    pub upload_id: ::prost::alloc::string::String,
+    /// The optional content encoding accepted by the server. If not set, the artifact must be
+    /// uploaded uncompressed.
+    #[prost(string, optional, tag = "3")]
+    pub content_encoding: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct CreateArtifactPartRequest {
+    /// The signature of the user on a pre-defined message. Used for authentication.
+    #[prost(bytes = "vec", tag = "1")]
+    pub signature: ::prost::alloc::vec::Vec<u8>,
+    /// The unique resource identifier of the artifact.
+    #[prost(string, tag = "2")]
+    pub artifact_uri: ::prost::alloc::string::String,
+    /// The identifier of the multipart upload.
+    #[prost(string, tag = "3")]
+    pub upload_id: ::prost::alloc::string::String,
+    /// The number of the part, starting from 1.
+    #[prost(uint32, tag = "4")]
+    pub part_number: u32,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct CreateArtifactPartResponse {
+    /// The presigned url to upload the part.
+    #[prost(string, tag = "1")]
+    pub part_presigned_url: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct CompleteMultipartArtifactRequest {
+    /// The signature of the user on a pre-defined message. Used for authentication.
+    #[prost(bytes = "vec", tag = "1")]
+    pub signature: ::prost::alloc::vec::Vec<u8>,
+    /// The unique resource identifier of the artifact.
+    #[prost(string, tag = "2")]
+    pub artifact_uri: ::prost::alloc::string::String,
+    /// The identifier of the multipart upload.
+    #[prost(string, tag = "3")]
+    pub upload_id: ::prost::alloc::string::String,
+    /// The entity tags of the uploaded parts, ordered by part number.
+    #[prost(string, repeated, tag = "4")]
+    pub part_etags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, ::prost::Message)]
+pub struct CompleteMultipartArtifactResponse {}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct GetArtifactByHashRequest {
+    /// The signature of the user on a pre-defined message. Used for authentication.
+    #[prost(bytes = "vec", tag = "1")]
+    pub signature: ::prost::alloc::vec::Vec<u8>,
+    /// The type of the artifact.
+    #[prost(enumeration = "ArtifactType", tag = "2")]
+    pub artifact_type: i32,
+    /// The SHA-256 hash of the uncompressed artifact content.
+    #[prost(bytes = "vec", tag = "3")]
+    pub sha256: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct GetArtifactByHashResponse {
+    /// The unique resource identifier of an artifact of the user with the given hash. Only
+    /// included if such an artifact exists.
+    #[prost(string, optional, tag = "1")]
+    pub artifact_uri: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct GetAuxiliaryArtifactRequest {
+    /// The signature of the user on a pre-defined message. Used for authentication.
+    #[prost(bytes = "vec", tag = "1")]
+    pub signature: ::prost::alloc::vec::Vec<u8>,
+    /// The identifier of the proof request the artifact is attached to.
+    #[prost(bytes = "vec", tag = "2")]
+    pub request_id: ::prost::alloc::vec::Vec<u8>,
+    /// The type of the artifact.
+    #[prost(enumeration = "ArtifactType", tag = "3")]
+    pub artifact_type: i32,
+    /// The user-defined type of the artifact, if its type is `CUSTOM`.
+    #[prost(uint32, optional, tag = "4")]
+    pub custom_type: ::core::option::Option<u32>,
+    /// The format of the signature. Unspecified for the legacy signature over the message.
+    #[prost(enumeration = "super::network::MessageFormat", tag = "5")]
+    pub format: i32,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct GetAuxiliaryArtifactResponse {
+    /// The unique resource identifier of the artifact. Only included if an artifact of the type
+    /// is attached to the request.
+    #[prost(string, optional, tag = "1")]
+    pub artifact_uri: ::core::option::Option<::prost::alloc::string::String>,
+    /// The SHA-256 hash of the uncompressed artifact content, if it was provided on creation.
+    #[prost(bytes = "vec", optional, tag = "2")]
+    pub sha256: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
 }
 #[derive(
     serde::Serialize,
@@ -39,6 +184,12 @@ pub enum ArtifactType {
     Stdin = 2,
     /// A proof artifact.
     Proof = 3,
+    /// An execution report attached to a proof request.
+    ExecutionReport = 4,
+    /// A trace, e.g. a cycle tracker profile, attached to a proof request.
+    Trace = 5,
+    /// A user-defined artifact attached to a proof request.
+    Custom = 6,
 }
 impl ArtifactType {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -51,6 +202,9 @@ impl ArtifactType {
             Self::Program => "PROGRAM",
             Self::Stdin => "STDIN",
             Self::Proof => "PROOF",
+            Self::ExecutionReport => "EXECUTION_REPORT",
+            Self::Trace => "TRACE",
+            Self::Custom => "CUSTOM",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -60,6 +214,9 @@ impl ArtifactType {
             "PROGRAM" => Some(Self::Program),
             "STDIN" => Some(Self::Stdin),
             "PROOF" => Some(Self::Proof),
+            "EXECUTION_REPORT" => Some(Self::ExecutionReport),
+            "TRACE" => Some(Self::Trace),
+            "CUSTOM" => Some(Self::Custom),
             _ => None,
         }
     }
@@ -170,6 +327,104 @@ pub mod artifact_store_client {
                 .insert(GrpcMethod::new("artifact.ArtifactStore", "CreateArtifact"));
             self.inner.unary(req, path, codec).await
         }
+        /// Creates an artifact that is uploaded in multiple parts.
+        pub async fn create_multipart_artifact(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateMultipartArtifactRequest>,
+        ) -> std::result::Result<tonic::Response<super::CreateMultipartArtifactResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/artifact.ArtifactStore/CreateMultipartArtifact");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("artifact.ArtifactStore", "CreateMultipartArtifact"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Creates a presigned url to upload a part of a multipart artifact.
+        pub async fn create_artifact_part(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateArtifactPartRequest>,
+        ) -> std::result::Result<tonic::Response<super::CreateArtifactPartResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/artifact.ArtifactStore/CreateArtifactPart");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("artifact.ArtifactStore", "CreateArtifactPart"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Completes a multipart artifact once all of its parts have been uploaded.
+        pub async fn complete_multipart_artifact(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CompleteMultipartArtifactRequest>,
+        ) -> std::result::Result<tonic::Response<super::CompleteMultipartArtifactResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/artifact.ArtifactStore/CompleteMultipartArtifact");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("artifact.ArtifactStore", "CompleteMultipartArtifact"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Get an existing artifact of the user by the hash of its content.
+        pub async fn get_artifact_by_hash(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetArtifactByHashRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetArtifactByHashResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/artifact.ArtifactStore/GetArtifactByHash");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("artifact.ArtifactStore", "GetArtifactByHash"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Get the auxiliary artifact of the given type attached to a proof request.
+        pub async fn get_auxiliary_artifact(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetAuxiliaryArtifactRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetAuxiliaryArtifactResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/artifact.ArtifactStore/GetAuxiliaryArtifact");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("artifact.ArtifactStore", "GetAuxiliaryArtifact"));
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -187,6 +442,31 @@ pub mod artifact_store_server {
             &self,
             request: tonic::Request<super::CreateArtifactRequest>,
         ) -> std::result::Result<tonic::Response<super::CreateArtifactResponse>, tonic::Status>;
+        /// Creates an artifact that is uploaded in multiple parts.
+        async fn create_multipart_artifact(
+            &self,
+            request: tonic::Request<super::CreateMultipartArtifactRequest>,
+        ) -> std::result::Result<tonic::Response<super::CreateMultipartArtifactResponse>, tonic::Status>;
+        /// Creates a presigned url to upload a part of a multipart artifact.
+        async fn create_artifact_part(
+            &self,
+            request: tonic::Request<super::CreateArtifactPartRequest>,
+        ) -> std::result::Result<tonic::Response<super::CreateArtifactPartResponse>, tonic::Status>;
+        /// Completes a multipart artifact once all of its parts have been uploaded.
+        async fn complete_multipart_artifact(
+            &self,
+            request: tonic::Request<super::CompleteMultipartArtifactRequest>,
+        ) -> std::result::Result<tonic::Response<super::CompleteMultipartArtifactResponse>, tonic::Status>;
+        /// Get an existing artifact of the user by the hash of its content.
+        async fn get_artifact_by_hash(
+            &self,
+            request: tonic::Request<super::GetArtifactByHashRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetArtifactByHashResponse>, tonic::Status>;
+        /// Get the auxiliary artifact of the given type attached to a proof request.
+        async fn get_auxiliary_artifact(
+            &self,
+            request: tonic::Request<super::GetAuxiliaryArtifactRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetAuxiliaryArtifactResponse>, tonic::Status>;
     }
     #[derive(Debug)]
     pub struct ArtifactStoreServer<T> {
@@ -302,6 +582,213 @@ pub mod artifact_store_server {
                     };
                     Box::pin(fut)
                 }
+                "/artifact.ArtifactStore/CreateMultipartArtifact" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateMultipartArtifactSvc<T: ArtifactStore>(pub Arc<T>);
+                    impl<T: ArtifactStore> tonic::server::UnaryService<super::CreateMultipartArtifactRequest>
+                        for CreateMultipartArtifactSvc<T>
+                    {
+                        type Response = super::CreateMultipartArtifactResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateMultipartArtifactRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ArtifactStore>::create_multipart_artifact(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CreateMultipartArtifactSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/artifact.ArtifactStore/CreateArtifactPart" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateArtifactPartSvc<T: ArtifactStore>(pub Arc<T>);
+                    impl<T: ArtifactStore> tonic::server::UnaryService<super::CreateArtifactPartRequest>
+                        for CreateArtifactPartSvc<T>
+                    {
+                        type Response = super::CreateArtifactPartResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateArtifactPartRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ArtifactStore>::create_artifact_part(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CreateArtifactPartSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/artifact.ArtifactStore/CompleteMultipartArtifact" => {
+                    #[allow(non_camel_case_types)]
+                    struct CompleteMultipartArtifactSvc<T: ArtifactStore>(pub Arc<T>);
+                    impl<T: ArtifactStore> tonic::server::UnaryService<super::CompleteMultipartArtifactRequest>
+                        for CompleteMultipartArtifactSvc<T>
+                    {
+                        type Response = super::CompleteMultipartArtifactResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CompleteMultipartArtifactRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ArtifactStore>::complete_multipart_artifact(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CompleteMultipartArtifactSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/artifact.ArtifactStore/GetArtifactByHash" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetArtifactByHashSvc<T: ArtifactStore>(pub Arc<T>);
+                    impl<T: ArtifactStore> tonic::server::UnaryService<super::GetArtifactByHashRequest>
+                        for GetArtifactByHashSvc<T>
+                    {
+                        type Response = super::GetArtifactByHashResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetArtifactByHashRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ArtifactStore>::get_artifact_by_hash(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetArtifactByHashSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/artifact.ArtifactStore/GetAuxiliaryArtifact" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAuxiliaryArtifactSvc<T: ArtifactStore>(pub Arc<T>);
+                    impl<T: ArtifactStore> tonic::server::UnaryService<super::GetAuxiliaryArtifactRequest>
+                        for GetAuxiliaryArtifactSvc<T>
+                    {
+                        type Response = super::GetAuxiliaryArtifactResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetAuxiliaryArtifactRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ArtifactStore>::get_auxiliary_artifact(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAuxiliaryArtifactSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => Box::pin(async move {
                     Ok(http::Response::builder()
                         .status(200)
@@ -37,6 +37,34 @@ pub struct RequestProofRequestBody {
     /// The cycle limit for the request.
     #[prost(uint64, tag = "8")]
     pub cycle_limit: u64,
+    /// The optional maximum price per billion cycles (in credits) that the requester is willing
+    /// to pay. Used by the auction fulfillment strategy.
+    #[prost(string, optional, tag = "9")]
+    pub max_price_per_bgas: ::core::option::Option<::prost::alloc::string::String>,
+    /// The addresses of the fulfillers allowed to fulfill the request. Only valid for the
+    /// reserved fulfillment strategy.
+    #[prost(bytes = "vec", repeated, tag = "10")]
+    pub whitelist: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    /// The optional minimum duration of the auction in seconds. Used by the auction fulfillment
+    /// strategy.
+    #[prost(uint64, optional, tag = "11")]
+    pub min_auction_period: ::core::option::Option<u64>,
+    /// The labels of the request, e.g. to identify the service that made it. At most 8 labels
+    /// are allowed, with keys and values of at most 256 bytes.
+    #[prost(btree_map = "string, string", tag = "12")]
+    pub metadata: ::prost::alloc::collections::BTreeMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+    /// The optional ID of a request of the same requester that this request replaces, e.g. to
+    /// raise its price. The replaced request must not be assigned yet, and can no longer be
+    /// fulfilled once replaced.
+    #[prost(bytes = "vec", optional, tag = "13")]
+    pub replaces: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// The optional URL that the network notifies with a signed request once the request is
+    /// fulfilled.
+    #[prost(string, optional, tag = "14")]
+    pub callback_url: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct RequestProofResponse {
@@ -54,6 +82,48 @@ pub struct RequestProofResponseBody {
     pub request_id: ::prost::alloc::vec::Vec<u8>,
 }
 #[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct ValidateProofRequestResponse {
+    /// Whether the request would be accepted if it were submitted.
+    #[prost(bool, tag = "1")]
+    pub valid: bool,
+    /// The optional reason the request would be rejected. Only included if the request is not
+    /// valid.
+    #[prost(string, optional, tag = "2")]
+    pub reason: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct CancelRequestRequest {
+    /// The message format of the body.
+    #[prost(enumeration = "MessageFormat", tag = "1")]
+    pub format: i32,
+    /// The signature of the sender.
+    #[prost(bytes = "vec", tag = "2")]
+    pub signature: ::prost::alloc::vec::Vec<u8>,
+    /// The body of the request.
+    #[prost(message, optional, tag = "3")]
+    pub body: ::core::option::Option<CancelRequestRequestBody>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct CancelRequestRequestBody {
+    /// The account nonce of the sender.
+    #[prost(uint64, tag = "1")]
+    pub nonce: u64,
+    /// The identifier for the request.
+    #[prost(bytes = "vec", tag = "2")]
+    pub request_id: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct CancelRequestResponse {
+    /// The transaction hash.
+    #[prost(bytes = "vec", tag = "1")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// The body of the response.
+    #[prost(message, optional, tag = "2")]
+    pub body: ::core::option::Option<CancelRequestResponseBody>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, ::prost::Message)]
+pub struct CancelRequestResponseBody {}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct FulfillProofRequest {
     /// The message format of the body.
     #[prost(enumeration = "MessageFormat", tag = "1")]
@@ -277,6 +347,12 @@ pub struct ProofRequest {
     /// request has a fulfillment status of EXECUTED.
     #[prost(string, optional, tag = "25")]
     pub refund_amount: ::core::option::Option<::prost::alloc::string::String>,
+    /// The labels of the request.
+    #[prost(btree_map = "string, string", tag = "26")]
+    pub metadata: ::prost::alloc::collections::BTreeMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
 }
 #[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetProofRequestStatusRequest {
@@ -311,6 +387,32 @@ pub struct GetProofRequestStatusResponse {
     /// included if the request has an execution status of EXECUTED.
     #[prost(bytes = "vec", optional, tag = "7")]
     pub public_values_hash: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// The optional SHA-256 hash of the uncompressed proof artifact. Only included if the
+    /// request has a fulfillment status of FULFILLED.
+    #[prost(bytes = "vec", optional, tag = "8")]
+    pub proof_sha256: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// The optional reason the request failed. Only included if the request has a fulfillment
+    /// status of UNFULFILLABLE or an execution status of UNEXECUTABLE.
+    #[prost(string, optional, tag = "9")]
+    pub failure_reason: ::core::option::Option<::prost::alloc::string::String>,
+    /// The optional address of the fulfiller the request is assigned to. Only included if the
+    /// request has a fulfillment status of ASSIGNED or FULFILLED.
+    #[prost(bytes = "vec", optional, tag = "10")]
+    pub fulfiller: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// The optional size of the proof artifact in bytes. Only included if the request has a
+    /// fulfillment status of FULFILLED.
+    #[prost(uint64, optional, tag = "11")]
+    pub proof_size: ::core::option::Option<u64>,
+    /// The optional unix timestamp of when the request was created.
+    #[prost(uint64, optional, tag = "12")]
+    pub created_at: ::core::option::Option<u64>,
+    /// The optional unix timestamp of when the request was last updated.
+    #[prost(uint64, optional, tag = "13")]
+    pub updated_at: ::core::option::Option<u64>,
+    /// The optional unix timestamp of when the request was fulfilled. Only included if the
+    /// request has a fulfillment status of FULFILLED.
+    #[prost(uint64, optional, tag = "14")]
+    pub fulfilled_at: ::core::option::Option<u64>,
 }
 #[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetProofRequestDetailsRequest {
@@ -364,6 +466,13 @@ pub struct GetFilteredProofRequestsRequest {
     /// The optional mode of the requests to filter for.
     #[prost(enumeration = "ProofMode", optional, tag = "12")]
     pub mode: ::core::option::Option<i32>,
+    /// The labels of the requests to filter for. Only returns requests that have all of these
+    /// labels.
+    #[prost(btree_map = "string, string", tag = "13")]
+    pub metadata: ::prost::alloc::collections::BTreeMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
 }
 #[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetFilteredProofRequestsResponse {
@@ -762,6 +871,24 @@ pub struct GetProgramResponse {
     pub program: ::core::option::Option<Program>,
 }
 #[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct GetProgramsByOwnerRequest {
+    /// The address of the owner of the programs.
+    #[prost(bytes = "vec", tag = "1")]
+    pub owner: ::prost::alloc::vec::Vec<u8>,
+    /// The optional maximum number of programs to return (default is 10, maximum is 100).
+    #[prost(uint32, optional, tag = "2")]
+    pub limit: ::core::option::Option<u32>,
+    /// The optional page number to return (default is 1).
+    #[prost(uint32, optional, tag = "3")]
+    pub page: ::core::option::Option<u32>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct GetProgramsByOwnerResponse {
+    /// The programs owned by the address.
+    #[prost(message, repeated, tag = "1")]
+    pub programs: ::prost::alloc::vec::Vec<Program>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct CreateProgramRequest {
     /// The message format of the body.
     #[prost(enumeration = "MessageFormat", tag = "1")]
@@ -787,6 +914,9 @@ pub struct CreateProgramRequestBody {
     /// The program resource identifier.
     #[prost(string, tag = "4")]
     pub program_uri: ::prost::alloc::string::String,
+    /// The optional name of the program.
+    #[prost(string, optional, tag = "5")]
+    pub name: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct CreateProgramResponse {
@@ -846,6 +976,53 @@ pub struct GetBalanceResponse {
     /// The amount of credits owned by the account.
     #[prost(string, tag = "1")]
     pub amount: ::prost::alloc::string::String,
+    /// The optional amount of credits reserved for the account's pending requests.
+    #[prost(string, optional, tag = "2")]
+    pub reserved_amount: ::core::option::Option<::prost::alloc::string::String>,
+    /// The optional amount of credits that will be deducted once the account's in-flight
+    /// requests are settled.
+    #[prost(string, optional, tag = "3")]
+    pub pending_amount: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct GetProofCostEstimateRequest {
+    /// The proof mode.
+    #[prost(enumeration = "ProofMode", tag = "1")]
+    pub mode: i32,
+    /// The cycle limit of the request.
+    #[prost(uint64, tag = "2")]
+    pub cycle_limit: u64,
+    /// The fulfillment strategy.
+    #[prost(enumeration = "FulfillmentStrategy", tag = "3")]
+    pub strategy: i32,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct GetProofCostEstimateResponse {
+    /// The fixed fee of a request, in credits.
+    #[prost(string, tag = "1")]
+    pub base_fee: ::prost::alloc::string::String,
+    /// The fee per billion cycles, in credits.
+    #[prost(string, tag = "2")]
+    pub price_per_bgas: ::prost::alloc::string::String,
+    /// The estimated total cost of the request, in credits.
+    #[prost(string, tag = "3")]
+    pub total: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetServerTimeRequest {}
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetServerTimeResponse {
+    /// The current time of the server, in seconds since the Unix epoch.
+    #[prost(uint64, tag = "1")]
+    pub timestamp: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetCapabilitiesRequest {}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct GetCapabilitiesResponse {
+    /// The optional features supported by the server, e.g. `request_metadata`.
+    #[prost(string, repeated, tag = "1")]
+    pub capabilities: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct BalanceLog {
@@ -1169,6 +1346,8 @@ pub enum MessageFormat {
     Binary = 1,
     /// The message is in JSON format.
     Json = 2,
+    /// The message is signed as EIP-712 typed data.
+    Eip712 = 3,
 }
 impl MessageFormat {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -1180,6 +1359,7 @@ impl MessageFormat {
             Self::UnspecifiedMessageFormat => "UNSPECIFIED_MESSAGE_FORMAT",
             Self::Binary => "BINARY",
             Self::Json => "JSON",
+            Self::Eip712 => "EIP712",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -1188,6 +1368,7 @@ impl MessageFormat {
             "UNSPECIFIED_MESSAGE_FORMAT" => Some(Self::UnspecifiedMessageFormat),
             "BINARY" => Some(Self::Binary),
             "JSON" => Some(Self::Json),
+            "EIP712" => Some(Self::Eip712),
             _ => None,
         }
     }
@@ -1555,6 +1736,44 @@ pub mod prover_network_client {
             req.extensions_mut().insert(GrpcMethod::new("network.ProverNetwork", "RequestProof"));
             self.inner.unary(req, path, codec).await
         }
+        /// Validate a proof request without submitting it.
+        pub async fn validate_proof_request(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RequestProofRequest>,
+        ) -> std::result::Result<tonic::Response<super::ValidateProofRequestResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/network.ProverNetwork/ValidateProofRequest");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("network.ProverNetwork", "ValidateProofRequest"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Cancels a proof request. Only callable by the requester, and only while the request
+        /// has not yet been assigned to a fulfiller.
+        pub async fn cancel_request(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CancelRequestRequest>,
+        ) -> std::result::Result<tonic::Response<super::CancelRequestResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/network.ProverNetwork/CancelRequest");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("network.ProverNetwork", "CancelRequest"));
+            self.inner.unary(req, path, codec).await
+        }
         /// Fulfills a proof request. Only callable by the assigned fulfiller.
         pub async fn fulfill_proof(
             &mut self,
@@ -1650,6 +1869,30 @@ pub mod prover_network_client {
                 .insert(GrpcMethod::new("network.ProverNetwork", "GetProofRequestStatus"));
             self.inner.unary(req, path, codec).await
         }
+        /// Subscribe to the status of a proof request. A new status is sent every time the
+        /// request transitions, and the stream is closed once the request reaches a terminal status.
+        pub async fn subscribe_proof_request_status(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetProofRequestStatusRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::GetProofRequestStatusResponse>>,
+            tonic::Status,
+        > {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/network.ProverNetwork/SubscribeProofRequestStatus",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("network.ProverNetwork", "SubscribeProofRequestStatus"));
+            self.inner.server_streaming(req, path, codec).await
+        }
         /// Get the details of a proof request.
         pub async fn get_proof_request_details(
             &mut self,
@@ -1993,6 +2236,26 @@ pub mod prover_network_client {
             req.extensions_mut().insert(GrpcMethod::new("network.ProverNetwork", "GetProgram"));
             self.inner.unary(req, path, codec).await
         }
+        /// Get the programs registered by an owner.
+        pub async fn get_programs_by_owner(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetProgramsByOwnerRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetProgramsByOwnerResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/network.ProverNetwork/GetProgramsByOwner");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("network.ProverNetwork", "GetProgramsByOwner"));
+            self.inner.unary(req, path, codec).await
+        }
         /// Create a new program. Must be called before requesting proofs.
         pub async fn create_program(
             &mut self,
@@ -2048,6 +2311,62 @@ pub mod prover_network_client {
             req.extensions_mut().insert(GrpcMethod::new("network.ProverNetwork", "GetBalance"));
             self.inner.unary(req, path, codec).await
         }
+        /// Estimate the cost of a proof request from the current network pricing.
+        pub async fn get_proof_cost_estimate(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetProofCostEstimateRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetProofCostEstimateResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/network.ProverNetwork/GetProofCostEstimate");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("network.ProverNetwork", "GetProofCostEstimate"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Get the current time of the server.
+        pub async fn get_server_time(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetServerTimeRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetServerTimeResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/network.ProverNetwork/GetServerTime");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("network.ProverNetwork", "GetServerTime"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Get the optional features supported by the server.
+        pub async fn get_capabilities(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetCapabilitiesRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetCapabilitiesResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/network.ProverNetwork/GetCapabilities");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("network.ProverNetwork", "GetCapabilities"));
+            self.inner.unary(req, path, codec).await
+        }
         /// Get the balance logs that meet the filter criteria.
         pub async fn get_filtered_balance_logs(
             &mut self,
@@ -2261,6 +2580,17 @@ pub mod prover_network_server {
             &self,
             request: tonic::Request<super::RequestProofRequest>,
         ) -> std::result::Result<tonic::Response<super::RequestProofResponse>, tonic::Status>;
+        /// Validate a proof request without submitting it.
+        async fn validate_proof_request(
+            &self,
+            request: tonic::Request<super::RequestProofRequest>,
+        ) -> std::result::Result<tonic::Response<super::ValidateProofRequestResponse>, tonic::Status>;
+        /// Cancels a proof request. Only callable by the requester, and only while the request
+        /// has not yet been assigned to a fulfiller.
+        async fn cancel_request(
+            &self,
+            request: tonic::Request<super::CancelRequestRequest>,
+        ) -> std::result::Result<tonic::Response<super::CancelRequestResponse>, tonic::Status>;
         /// Fulfills a proof request. Only callable by the assigned fulfiller.
         async fn fulfill_proof(
             &self,
@@ -2286,6 +2616,17 @@ pub mod prover_network_server {
             &self,
             request: tonic::Request<super::GetProofRequestStatusRequest>,
         ) -> std::result::Result<tonic::Response<super::GetProofRequestStatusResponse>, tonic::Status>;
+        /// Server streaming response type for the SubscribeProofRequestStatus method.
+        type SubscribeProofRequestStatusStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::GetProofRequestStatusResponse, tonic::Status>,
+            > + std::marker::Send
+            + 'static;
+        /// Subscribe to the status of a proof request. A new status is sent every time the
+        /// request transitions, and the stream is closed once the request reaches a terminal status.
+        async fn subscribe_proof_request_status(
+            &self,
+            request: tonic::Request<super::GetProofRequestStatusRequest>,
+        ) -> std::result::Result<tonic::Response<Self::SubscribeProofRequestStatusStream>, tonic::Status>;
         /// Get the details of a proof request.
         async fn get_proof_request_details(
             &self,
@@ -2383,6 +2724,11 @@ pub mod prover_network_server {
             &self,
             request: tonic::Request<super::GetProgramRequest>,
         ) -> std::result::Result<tonic::Response<super::GetProgramResponse>, tonic::Status>;
+        /// Get the programs registered by an owner.
+        async fn get_programs_by_owner(
+            &self,
+            request: tonic::Request<super::GetProgramsByOwnerRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetProgramsByOwnerResponse>, tonic::Status>;
         /// Create a new program. Must be called before requesting proofs.
         async fn create_program(
             &self,
@@ -2398,6 +2744,21 @@ pub mod prover_network_server {
             &self,
             request: tonic::Request<super::GetBalanceRequest>,
         ) -> std::result::Result<tonic::Response<super::GetBalanceResponse>, tonic::Status>;
+        /// Estimate the cost of a proof request from the current network pricing.
+        async fn get_proof_cost_estimate(
+            &self,
+            request: tonic::Request<super::GetProofCostEstimateRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetProofCostEstimateResponse>, tonic::Status>;
+        /// Get the current time of the server.
+        async fn get_server_time(
+            &self,
+            request: tonic::Request<super::GetServerTimeRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetServerTimeResponse>, tonic::Status>;
+        /// Get the optional features supported by the server.
+        async fn get_capabilities(
+            &self,
+            request: tonic::Request<super::GetCapabilitiesRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetCapabilitiesResponse>, tonic::Status>;
         /// Get the balance logs that meet the filter criteria.
         async fn get_filtered_balance_logs(
             &self,
@@ -2571,6 +2932,88 @@ pub mod prover_network_server {
                     };
                     Box::pin(fut)
                 }
+                "/network.ProverNetwork/ValidateProofRequest" => {
+                    #[allow(non_camel_case_types)]
+                    struct ValidateProofRequestSvc<T: ProverNetwork>(pub Arc<T>);
+                    impl<T: ProverNetwork> tonic::server::UnaryService<super::RequestProofRequest>
+                        for ValidateProofRequestSvc<T>
+                    {
+                        type Response = super::ValidateProofRequestResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RequestProofRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProverNetwork>::validate_proof_request(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ValidateProofRequestSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/network.ProverNetwork/CancelRequest" => {
+                    #[allow(non_camel_case_types)]
+                    struct CancelRequestSvc<T: ProverNetwork>(pub Arc<T>);
+                    impl<T: ProverNetwork> tonic::server::UnaryService<super::CancelRequestRequest>
+                        for CancelRequestSvc<T>
+                    {
+                        type Response = super::CancelRequestResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CancelRequestRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProverNetwork>::cancel_request(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CancelRequestSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/network.ProverNetwork/FulfillProof" => {
                     #[allow(non_camel_case_types)]
                     struct FulfillProofSvc<T: ProverNetwork>(pub Arc<T>);
@@ -2779,6 +3222,50 @@ pub mod prover_network_server {
                     };
                     Box::pin(fut)
                 }
+                "/network.ProverNetwork/SubscribeProofRequestStatus" => {
+                    #[allow(non_camel_case_types)]
+                    struct SubscribeProofRequestStatusSvc<T: ProverNetwork>(pub Arc<T>);
+                    impl<T: ProverNetwork>
+                        tonic::server::ServerStreamingService<super::GetProofRequestStatusRequest>
+                        for SubscribeProofRequestStatusSvc<T>
+                    {
+                        type Response = super::GetProofRequestStatusResponse;
+                        type ResponseStream = T::SubscribeProofRequestStatusStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetProofRequestStatusRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProverNetwork>::subscribe_proof_request_status(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SubscribeProofRequestStatusSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/network.ProverNetwork/GetProofRequestDetails" => {
                     #[allow(non_camel_case_types)]
                     struct GetProofRequestDetailsSvc<T: ProverNetwork>(pub Arc<T>);
@@ -3488,6 +3975,47 @@ pub mod prover_network_server {
                     };
                     Box::pin(fut)
                 }
+                "/network.ProverNetwork/GetProgramsByOwner" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetProgramsByOwnerSvc<T: ProverNetwork>(pub Arc<T>);
+                    impl<T: ProverNetwork> tonic::server::UnaryService<super::GetProgramsByOwnerRequest>
+                        for GetProgramsByOwnerSvc<T>
+                    {
+                        type Response = super::GetProgramsByOwnerResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetProgramsByOwnerRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProverNetwork>::get_programs_by_owner(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetProgramsByOwnerSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/network.ProverNetwork/CreateProgram" => {
                     #[allow(non_camel_case_types)]
                     struct CreateProgramSvc<T: ProverNetwork>(pub Arc<T>);
@@ -3609,6 +4137,129 @@ pub mod prover_network_server {
                     };
                     Box::pin(fut)
                 }
+                "/network.ProverNetwork/GetProofCostEstimate" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetProofCostEstimateSvc<T: ProverNetwork>(pub Arc<T>);
+                    impl<T: ProverNetwork> tonic::server::UnaryService<super::GetProofCostEstimateRequest>
+                        for GetProofCostEstimateSvc<T>
+                    {
+                        type Response = super::GetProofCostEstimateResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetProofCostEstimateRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProverNetwork>::get_proof_cost_estimate(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetProofCostEstimateSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/network.ProverNetwork/GetServerTime" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetServerTimeSvc<T: ProverNetwork>(pub Arc<T>);
+                    impl<T: ProverNetwork> tonic::server::UnaryService<super::GetServerTimeRequest>
+                        for GetServerTimeSvc<T>
+                    {
+                        type Response = super::GetServerTimeResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetServerTimeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProverNetwork>::get_server_time(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetServerTimeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/network.ProverNetwork/GetCapabilities" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetCapabilitiesSvc<T: ProverNetwork>(pub Arc<T>);
+                    impl<T: ProverNetwork> tonic::server::UnaryService<super::GetCapabilitiesRequest>
+                        for GetCapabilitiesSvc<T>
+                    {
+                        type Response = super::GetCapabilitiesResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetCapabilitiesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProverNetwork>::get_capabilities(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetCapabilitiesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/network.ProverNetwork/GetFilteredBalanceLogs" => {
                     #[allow(non_camel_case_types)]
                     struct GetFilteredBalanceLogsSvc<T: ProverNetwork>(pub Arc<T>);
@@ -4,16 +4,37 @@
 
 use std::time::Duration;
 
-use alloy_primitives::B256;
+use alloy_primitives::{Address, B256, U256};
 use anyhow::Result;
+use sp1_core_executor::ExecutionReport;
 use sp1_core_machine::io::SP1Stdin;
-use sp1_prover::SP1ProvingKey;
+use sp1_prover::{SP1ProvingKey, SP1VerifyingKey};
 
 use crate::{
-    utils::block_on, utils::sp1_dump, NetworkProver, SP1ProofMode, SP1ProofWithPublicValues,
+    utils::block_on, utils::block_on_blocking, utils::sp1_dump, NetworkProver, SP1ProofMode,
+    SP1ProofWithPublicValues,
 };
 
 use super::proto::network::FulfillmentStrategy;
+use super::prover::{verify_by_default, CycleLimitConfig};
+use super::{
+    Error, FulfillmentParams, NetworkClient, SP1ProofRequestHandle, SimulatedProofRequest,
+    DEFAULT_CYCLE_LIMIT,
+};
+
+/// A proof request that was submitted to the network.
+#[derive(Debug, Clone)]
+pub struct ProofRequestHandle {
+    /// The ID of the request.
+    pub request_id: B256,
+    /// The cycle limit of the request.
+    pub cycle_limit: u64,
+    /// The deadline of the request, in seconds since the Unix epoch.
+    pub deadline: u64,
+    /// The report of the local execution used to determine the cycle limit, if the program was
+    /// executed locally.
+    pub execution_report: Option<ExecutionReport>,
+}
 
 /// A builder for creating a proof request to the network.
 pub struct NetworkProveBuilder<'a> {
@@ -25,6 +46,11 @@ pub struct NetworkProveBuilder<'a> {
     pub(crate) strategy: FulfillmentStrategy,
     pub(crate) skip_simulation: bool,
     pub(crate) cycle_limit: Option<u64>,
+    pub(crate) cycle_limit_margin: Option<u32>,
+    pub(crate) cycle_limit_from_gas: bool,
+    pub(crate) max_local_cycles: Option<u64>,
+    pub(crate) fulfillment_params: FulfillmentParams,
+    pub(crate) verify: Option<bool>,
 }
 
 impl<'a> NetworkProveBuilder<'a> {
@@ -241,6 +267,10 @@ impl<'a> NetworkProveBuilder<'a> {
     /// However, you can manually set it if you know the exact cycle count needed and want to skip
     /// the simulation step locally.
     ///
+    /// Unless [`Self::skip_simulation`] is set, the program is still executed locally up to the
+    /// cycle limit, and the request is refused with [`crate::ExecutionLimitExceeded`] if the
+    /// program does not halt within it.
+    ///
     /// The cycle limit ensures that a prover on the network will stop generating a proof once the
     /// cycle limit is reached, which prevents denial of service attacks.
     ///
@@ -265,6 +295,264 @@ impl<'a> NetworkProveBuilder<'a> {
         self
     }
 
+    /// Sets the cycle limit from the cycle count of a local execution, plus a safety margin.
+    ///
+    /// # Details
+    /// The program is executed locally to count its cycles, and the cycle limit is set to that
+    /// count increased by `margin_percent` percent. The execution happens even if
+    /// [`Self::skip_simulation`] is set, but not if an explicit limit is set with
+    /// [`Self::cycle_limit`]. The execution report is available on the [`ProofRequestHandle`]
+    /// returned by [`Self::request_with_report`].
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().network().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let handle = client.prove(&pk, &stdin)
+    ///     .cycle_limit_auto(10) // Allow 10% more cycles than the local execution.
+    ///     .request_with_report()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn cycle_limit_auto(mut self, margin_percent: u32) -> Self {
+        self.cycle_limit_margin = Some(margin_percent);
+        self.cycle_limit_from_gas = false;
+        self
+    }
+
+    /// Sets the cycle limit from the prover gas of a local execution, plus a safety margin.
+    ///
+    /// # Details
+    /// This is the same as [`Self::cycle_limit_auto`], but the limit is computed from the
+    /// [`crate::ProverGas`] of the execution instead of its cycle count. The gas accounts for the
+    /// cost of the precompiles and of the shards, which raw cycles don't reflect.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().network().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let proof = client.prove(&pk, &stdin)
+    ///     .cycle_limit_auto_gas(10) // Allow 10% more gas than the local execution.
+    ///     .run()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn cycle_limit_auto_gas(mut self, margin_percent: u32) -> Self {
+        self.cycle_limit_margin = Some(margin_percent);
+        self.cycle_limit_from_gas = true;
+        self
+    }
+
+    /// Sets the maximum number of cycles the program may run for when executed locally.
+    ///
+    /// # Details
+    /// If the local execution used to determine the cycle limit exceeds this cap, the request
+    /// fails with [`crate::network::Error::LocalExecutionCapExceeded`] before anything is sent to
+    /// the network. By default, the local execution is not capped.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().network().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let proof = client.prove(&pk, &stdin)
+    ///     .cycle_limit_auto(10)
+    ///     .max_local_cycles(100_000_000)
+    ///     .run()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn max_local_cycles(mut self, max_local_cycles: u64) -> Self {
+        self.max_local_cycles = Some(max_local_cycles);
+        self
+    }
+
+    /// Sets the maximum price per billion cycles (in credits) to pay for the proof request.
+    ///
+    /// # Details
+    /// This is used by the [`FulfillmentStrategy::Auction`] strategy. If not set, the
+    /// `SP1_MAX_PRICE_PER_BGAS` environment variable is used, and if that is not set either, the
+    /// network default applies.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover, network::FulfillmentStrategy};
+    /// use alloy_primitives::U256;
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().network().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let proof = client.prove(&pk, &stdin)
+    ///     .strategy(FulfillmentStrategy::Auction)
+    ///     .max_price_per_bgas(U256::from(100_000_000u64))
+    ///     .run()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn max_price_per_bgas(mut self, max_price_per_bgas: U256) -> Self {
+        self.fulfillment_params.max_price_per_bgas = Some(max_price_per_bgas);
+        self
+    }
+
+    /// Sets the fulfillers that are allowed to fulfill the proof request.
+    ///
+    /// # Details
+    /// This is only valid for the [`FulfillmentStrategy::Reserved`] strategy, and requesting the
+    /// proof with any other strategy will return an error.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover, network::FulfillmentStrategy};
+    /// use alloy_primitives::Address;
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().network().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let proof = client.prove(&pk, &stdin)
+    ///     .strategy(FulfillmentStrategy::Reserved)
+    ///     .whitelisted_fulfillers(vec![Address::ZERO])
+    ///     .run()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn whitelisted_fulfillers(mut self, fulfillers: Vec<Address>) -> Self {
+        self.fulfillment_params.whitelisted_fulfillers = fulfillers;
+        self
+    }
+
+    /// Sets the minimum duration of the auction for the proof request.
+    ///
+    /// # Details
+    /// This is used by the [`FulfillmentStrategy::Auction`] strategy. If not set, the network
+    /// default applies.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover, network::FulfillmentStrategy};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().network().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let proof = client.prove(&pk, &stdin)
+    ///     .strategy(FulfillmentStrategy::Auction)
+    ///     .min_auction_period(Duration::from_secs(30))
+    ///     .run()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn min_auction_period(mut self, min_auction_period: Duration) -> Self {
+        self.fulfillment_params.min_auction_period = Some(min_auction_period);
+        self
+    }
+
+    /// Adds a label to the proof request, e.g. to identify the service or block it belongs to.
+    ///
+    /// # Details
+    /// Labels are signed along with the request, and can be used to filter requests with
+    /// [`crate::network::ProofRequestFilter::label`]. At most 8 labels are allowed, with keys and
+    /// values of at most 256 bytes. If the network does not support labels, the request fails
+    /// with [`crate::network::Error::UnsupportedCapability`].
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().network().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let proof = client.prove(&pk, &stdin)
+    ///     .with_label("service", "indexer")
+    ///     .with_label("block", "12345")
+    ///     .run()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn with_label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.fulfillment_params.labels.insert(key.into(), value.into());
+        self
+    }
+
+    /// Sets the URL that the network notifies once the proof request is fulfilled.
+    ///
+    /// # Details
+    /// The network sends a signed [`crate::network::webhook::ProofFulfilledNotification`] to the
+    /// URL, which the receiver authenticates with
+    /// [`crate::network::webhook::verify_notification`]. The URL must be an HTTP or HTTPS URL.
+    /// If the network does not support callbacks, the request fails with
+    /// [`crate::network::Error::UnsupportedCapability`].
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().network().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let request_id = client.prove(&pk, &stdin)
+    ///     .callback_url("https://example.com/proofs")
+    ///     .request()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn callback_url(mut self, callback_url: impl Into<String>) -> Self {
+        self.fulfillment_params.callback_url = Some(callback_url.into());
+        self
+    }
+
+    /// Sets whether to verify the proof locally before returning it.
+    ///
+    /// # Details
+    /// The proof returned by the fulfiller is verified against the verifying key of the program,
+    /// so that an invalid proof fails with [`crate::network::Error::ProofVerificationFailed`]
+    /// instead of later, e.g. on-chain. By default, Plonk and Groth16 proofs are verified, since
+    /// they are cheap to verify, and other proofs are not.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().network().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let proof = client.prove(&pk, &stdin)
+    ///     .compressed()
+    ///     .verify_proof(true)
+    ///     .run()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn verify_proof(mut self, verify: bool) -> Self {
+        self.verify = Some(verify);
+        self
+    }
+
     /// Request a proof from the prover network.
     ///
     /// # Details
@@ -312,10 +600,186 @@ impl<'a> NetworkProveBuilder<'a> {
     /// })
     /// ```
     pub async fn request_async(self) -> Result<B256> {
-        let Self { prover, mode, pk, stdin, timeout, strategy, skip_simulation, cycle_limit } =
-            self;
+        Ok(self.request_with_report_async().await?.request_id)
+    }
+
+    /// Request a proof from the prover network, for callers that are not async.
+    ///
+    /// # Details
+    /// This is the same as [`Self::request`], but returns
+    /// [`Error::BlockingInAsyncContext`] instead of blocking if called from within an async
+    /// runtime. The request runs on a runtime shared by the blocking methods of the SDK, which is
+    /// created on first use.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().network().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let request_id = client.prove(&pk, &stdin)
+    ///     .request_blocking()
+    ///     .unwrap();
+    /// ```
+    pub fn request_blocking(self) -> Result<B256> {
+        block_on_blocking("request_blocking", self.request_async())?
+    }
+
+    /// Request a proof from the prover network, returning a handle to the request.
+    ///
+    /// # Details
+    /// This is the same as [`Self::request`], but the returned [`ProofRequestHandle`] also
+    /// includes the cycle limit of the request and the report of the local execution, if any.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().network().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let handle = client.prove(&pk, &stdin)
+    ///     .request_with_report()
+    ///     .unwrap();
+    /// ```
+    pub fn request_with_report(self) -> Result<ProofRequestHandle> {
+        block_on(self.request_with_report_async())
+    }
+
+    /// Request a proof from the prover network asynchronously, returning a handle to the request.
+    ///
+    /// # Details
+    /// This is the same as [`Self::request_async`], but the returned [`ProofRequestHandle`] also
+    /// includes the cycle limit of the request and the report of the local execution, if any.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover};
+    ///
+    /// tokio_test::block_on(async {
+    ///     let elf = &[1, 2, 3];
+    ///     let stdin = SP1Stdin::new();
+    ///
+    ///     let client = ProverClient::builder().network().build();
+    ///     let (pk, vk) = client.setup(elf);
+    ///     let handle = client.prove(&pk, &stdin)
+    ///         .request_with_report_async()
+    ///         .await
+    ///         .unwrap();
+    /// })
+    /// ```
+    pub async fn request_with_report_async(self) -> Result<ProofRequestHandle> {
+        let cycle_limit = self.cycle_limit_config();
+        let Self { prover, mode, pk, stdin, timeout, strategy, fulfillment_params, .. } = self;
         prover
-            .request_proof_impl(pk, &stdin, mode, strategy, timeout, skip_simulation, cycle_limit)
+            .request_proof_impl(
+                pk,
+                &stdin,
+                mode,
+                strategy,
+                timeout,
+                cycle_limit,
+                fulfillment_params,
+            )
+            .await
+    }
+
+    /// Request a proof from the prover network, returning an [`SP1ProofRequestHandle`] to check
+    /// its status and wait for the proof.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().network().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let handle = client.prove(&pk, &stdin)
+    ///     .request_handle()
+    ///     .unwrap();
+    /// ```
+    pub fn request_handle(self) -> Result<SP1ProofRequestHandle> {
+        block_on(self.request_handle_async())
+    }
+
+    /// Request a proof from the prover network asynchronously, returning an
+    /// [`SP1ProofRequestHandle`] to check its status and wait for the proof.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover};
+    ///
+    /// tokio_test::block_on(async {
+    ///     let elf = &[1, 2, 3];
+    ///     let stdin = SP1Stdin::new();
+    ///
+    ///     let client = ProverClient::builder().network().build();
+    ///     let (pk, vk) = client.setup(elf);
+    ///     let handle = client.prove(&pk, &stdin)
+    ///         .request_handle_async()
+    ///         .await
+    ///         .unwrap();
+    ///     let proof = handle.wait().await.unwrap();
+    /// })
+    /// ```
+    pub async fn request_handle_async(self) -> Result<SP1ProofRequestHandle> {
+        let (prover, pk, mode, verify) = (self.prover, self.pk, self.mode, self.should_verify());
+        let request = self.request_with_report_async().await?;
+        let vk_hash = NetworkClient::get_vk_hash(&pk.vk)?;
+        let handle = prover.handle(request.request_id, vk_hash, mode.into());
+        Ok(if verify { handle.with_verifier(prover.prover.clone(), pk.vk.clone()) } else { handle })
+    }
+
+    /// Build and sign the proof request without submitting it to the prover network.
+    ///
+    /// # Details
+    /// This is useful to test that requests are built correctly. The program is not registered
+    /// and the stdin is not uploaded, so nothing is created on the network. See
+    /// [`crate::network::NetworkClient::simulate_proof_request`] for details.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().network().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let simulated = client.prove(&pk, &stdin).dry_run().unwrap();
+    ///
+    /// // Execute the program locally with the stdin of the request.
+    /// let (_, report) = client.execute(elf, &simulated.stdin().unwrap()).run().unwrap();
+    /// ```
+    pub fn dry_run(self) -> Result<SimulatedProofRequest> {
+        block_on(self.dry_run_async())
+    }
+
+    /// Build and sign the proof request without submitting it to the prover network,
+    /// asynchronously.
+    ///
+    /// # Details
+    /// See [`Self::dry_run`].
+    pub async fn dry_run_async(self) -> Result<SimulatedProofRequest> {
+        let cycle_limit = self.cycle_limit_config();
+        let Self { prover, mode, pk, stdin, timeout, strategy, fulfillment_params, .. } = self;
+        prover
+            .simulate_proof_impl(
+                pk,
+                &stdin,
+                mode,
+                strategy,
+                timeout,
+                cycle_limit,
+                fulfillment_params,
+            )
             .await
     }
 
@@ -359,20 +823,268 @@ impl<'a> NetworkProveBuilder<'a> {
     /// let proof = client.prove(&pk, &stdin)
     ///     .run_async();
     /// ```
-    pub async fn run_async(self) -> Result<SP1ProofWithPublicValues> {
-        let Self { prover, mode, pk, stdin, timeout, strategy, mut skip_simulation, cycle_limit } =
-            self;
-
+    pub async fn run_async(mut self) -> Result<SP1ProofWithPublicValues> {
         // Check for deprecated environment variable
         if let Ok(val) = std::env::var("SKIP_SIMULATION") {
             eprintln!(
                 "Warning: SKIP_SIMULATION environment variable is deprecated. Please use .skip_simulation() instead."
             );
-            skip_simulation = matches!(val.to_lowercase().as_str(), "true" | "1");
+            self.skip_simulation = matches!(val.to_lowercase().as_str(), "true" | "1");
         }
 
+        let cycle_limit = self.cycle_limit_config();
+        let verify = self.should_verify();
+        let Self { prover, mode, pk, stdin, timeout, strategy, fulfillment_params, .. } = self;
+
         sp1_dump(&pk.elf, &stdin);
 
-        prover.prove_impl(pk, &stdin, mode, strategy, timeout, skip_simulation, cycle_limit).await
+        prover
+            .prove_impl(
+                pk,
+                &stdin,
+                mode,
+                strategy,
+                timeout,
+                cycle_limit,
+                fulfillment_params,
+                verify,
+            )
+            .await
+    }
+
+    /// Run the prover with the built arguments, for callers that are not async.
+    ///
+    /// # Details
+    /// This is the same as [`Self::run`], but returns [`Error::BlockingInAsyncContext`] instead
+    /// of blocking if called from within an async runtime. See [`Self::request_blocking`].
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().network().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let proof = client.prove(&pk, &stdin)
+    ///     .run_blocking()
+    ///     .unwrap();
+    /// ```
+    pub fn run_blocking(self) -> Result<SP1ProofWithPublicValues> {
+        block_on_blocking("run_blocking", self.run_async())?
+    }
+
+    /// Whether the proof is verified before it is returned.
+    fn should_verify(&self) -> bool {
+        self.verify.unwrap_or_else(|| verify_by_default(self.mode))
+    }
+
+    /// The configuration used to determine the cycle limit of the request.
+    fn cycle_limit_config(&self) -> CycleLimitConfig {
+        CycleLimitConfig {
+            cycle_limit: self.cycle_limit,
+            skip_simulation: self.skip_simulation,
+            auto_margin_percent: self.cycle_limit_margin,
+            auto_from_gas: self.cycle_limit_from_gas,
+            max_local_cycles: self.max_local_cycles,
+        }
+    }
+}
+
+/// A builder for requesting a proof of a program registered on the network, by the hash of its
+/// verifying key.
+///
+/// # Details
+/// Created by [`NetworkProver::prove_with_vk_hash`]. Since the ELF is not available, the program
+/// is neither registered nor executed locally, so the cycle limit must be set with
+/// [`Self::cycle_limit`] if the default of 100 million cycles is not enough.
+pub struct NetworkProveWithVkHashBuilder<'a> {
+    pub(crate) prover: &'a NetworkProver,
+    pub(crate) vk_hash: B256,
+    pub(crate) stdin: SP1Stdin,
+    pub(crate) mode: SP1ProofMode,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) strategy: FulfillmentStrategy,
+    pub(crate) cycle_limit: Option<u64>,
+    pub(crate) fulfillment_params: FulfillmentParams,
+    pub(crate) vk: Option<SP1VerifyingKey>,
+    pub(crate) verify: Option<bool>,
+    pub(crate) check_registered: bool,
+}
+
+impl NetworkProveWithVkHashBuilder<'_> {
+    /// Set the proof mode to the given [`SP1ProofMode`].
+    #[must_use]
+    pub fn mode(mut self, mode: SP1ProofMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the timeout for the proof's generation.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the fulfillment strategy for the proof's generation.
+    #[must_use]
+    pub fn strategy(mut self, strategy: FulfillmentStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Sets the cycle limit for the proof request.
+    #[must_use]
+    pub fn cycle_limit(mut self, cycle_limit: u64) -> Self {
+        self.cycle_limit = Some(cycle_limit);
+        self
+    }
+
+    /// Sets the parameters of the fulfillment strategy, e.g. the maximum price or labels.
+    #[must_use]
+    pub fn fulfillment_params(mut self, params: FulfillmentParams) -> Self {
+        self.fulfillment_params = params;
+        self
+    }
+
+    /// Sets the verifying key of the program, to verify the proof before returning it.
+    ///
+    /// # Details
+    /// The verifying key must hash to the requested vk hash. The proof is verified as described
+    /// in [`NetworkProveBuilder::verify_proof`].
+    #[must_use]
+    pub fn verifying_key(mut self, vk: SP1VerifyingKey) -> Self {
+        self.vk = Some(vk);
+        self
+    }
+
+    /// Sets whether to verify the proof locally before returning it, if the verifying key is
+    /// set with [`Self::verifying_key`].
+    #[must_use]
+    pub fn verify_proof(mut self, verify: bool) -> Self {
+        self.verify = Some(verify);
+        self
+    }
+
+    /// Sets whether to check that the program is registered on the network before requesting
+    /// the proof. Enabled by default.
+    ///
+    /// # Details
+    /// Requests for programs that are not registered are unfulfillable. Skip the check to save a
+    /// round trip if the program is known to be registered.
+    #[must_use]
+    pub fn check_registered(mut self, check_registered: bool) -> Self {
+        self.check_registered = check_registered;
+        self
+    }
+
+    /// Request a proof from the prover network, returning an [`SP1ProofRequestHandle`] to check
+    /// its status and wait for the proof.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{network::B256, NetworkProver, SP1Stdin};
+    ///
+    /// let prover = NetworkProver::new("...", "...");
+    /// let vk_hash = B256::repeat_byte(1);
+    /// let handle = prover
+    ///     .prove_with_vk_hash(vk_hash, SP1Stdin::new())
+    ///     .cycle_limit(1_000_000)
+    ///     .request_handle()
+    ///     .unwrap();
+    /// ```
+    pub fn request_handle(self) -> Result<SP1ProofRequestHandle> {
+        block_on(self.request_handle_async())
+    }
+
+    /// Request a proof from the prover network asynchronously, returning an
+    /// [`SP1ProofRequestHandle`] to check its status and wait for the proof.
+    ///
+    /// # Details
+    /// Returns [`Error::ProgramNotFound`] if the program is not registered on the network.
+    pub async fn request_handle_async(self) -> Result<SP1ProofRequestHandle> {
+        let verify = self.verify.unwrap_or_else(|| verify_by_default(self.mode));
+        let Self {
+            prover,
+            vk_hash,
+            stdin,
+            mode,
+            timeout,
+            strategy,
+            cycle_limit,
+            fulfillment_params,
+            vk,
+            check_registered,
+            ..
+        } = self;
+
+        let params = fulfillment_params.with_env_defaults()?;
+        params.validate(strategy)?;
+        if let Some(vk) = &vk {
+            let actual = NetworkClient::get_vk_hash(vk)?;
+            if actual != vk_hash {
+                return Err(Error::InvalidRequest {
+                    reason: format!("the verifying key hashes to {actual}, not {vk_hash}"),
+                }
+                .into());
+            }
+        }
+        if check_registered && prover.client.get_program(vk_hash).await?.is_none() {
+            return Err(Error::ProgramNotFound {
+                message: format!("program {vk_hash} is not registered on the network"),
+            }
+            .into());
+        }
+
+        let cycle_limit = cycle_limit.unwrap_or(DEFAULT_CYCLE_LIMIT);
+        let (request_id, _) = prover
+            .request_proof(vk_hash, &stdin, mode.into(), strategy, cycle_limit, timeout, &params)
+            .await?;
+        let handle = prover.handle(request_id, vk_hash, mode.into());
+        Ok(match vk {
+            Some(vk) if verify => handle.with_verifier(prover.prover.clone(), vk),
+            _ => handle,
+        })
+    }
+
+    /// Request a proof from the prover network, returning an [`SP1ProofRequestHandle`], for
+    /// callers that are not async.
+    ///
+    /// # Details
+    /// This is the same as [`Self::request_handle`], but returns
+    /// [`Error::BlockingInAsyncContext`] instead of blocking if called from within an async
+    /// runtime.
+    pub fn request_handle_blocking(self) -> Result<SP1ProofRequestHandle> {
+        block_on_blocking("request_handle_blocking", self.request_handle_async())?
+    }
+
+    /// Request a proof from the prover network and wait for it.
+    pub fn run(self) -> Result<SP1ProofWithPublicValues> {
+        block_on(self.run_async())
+    }
+
+    /// Request a proof from the prover network and wait for it asynchronously.
+    ///
+    /// # Details
+    /// Returns [`Error::RequestTimedOut`] if the proof is not generated within the timeout.
+    pub async fn run_async(self) -> Result<SP1ProofWithPublicValues> {
+        let timeout = self.timeout;
+        let handle = self.request_handle_async().await?;
+        let proof = match timeout {
+            Some(timeout) => handle.wait_with_timeout(timeout).await?,
+            None => handle.wait().await?,
+        };
+        Ok(proof)
+    }
+
+    /// Request a proof from the prover network and wait for it, for callers that are not async.
+    ///
+    /// # Details
+    /// This is the same as [`Self::run`], but returns [`Error::BlockingInAsyncContext`] instead
+    /// of blocking if called from within an async runtime.
+    pub fn run_blocking(self) -> Result<SP1ProofWithPublicValues> {
+        block_on_blocking("run_blocking", self.run_async())?
     }
 }
@@ -3,31 +3,61 @@
 //! This module provides an implementation of the [`crate::Prover`] trait that can generate proofs
 //! on a remote RPC server.
 
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use super::prove::NetworkProveBuilder;
+use super::prove::{NetworkProveBuilder, NetworkProveWithVkHashBuilder, ProofRequestHandle};
+use super::request_store::{PendingRequest, RequestStore};
 use super::DEFAULT_CYCLE_LIMIT;
-use crate::cpu::execute::CpuExecuteBuilder;
+use super::{SP1ProofRequestHandle, SimulatedProofRequest};
+use crate::cpu::execute::{execute_with_cycle_limit, CpuExecuteBuilder};
 use crate::cpu::CpuProver;
 use crate::network::proto::network::GetProofRequestStatusResponse;
-use crate::network::{Error, DEFAULT_NETWORK_RPC_URL, DEFAULT_TIMEOUT_SECS};
+use crate::network::utils::{self, NetworkSigner};
+use crate::network::{
+    AccountBalance, CostEstimate, Error, FulfillmentParams, ProgramMetadata,
+    DEFAULT_NETWORK_RPC_URL, DEFAULT_TIMEOUT_SECS,
+};
 use crate::{
-    network::client::NetworkClient,
-    network::proto::network::{ExecutionStatus, FulfillmentStatus, FulfillmentStrategy, ProofMode},
-    Prover, SP1ProofMode, SP1ProofWithPublicValues, SP1ProvingKey, SP1VerifyingKey,
+    network::client::{check_mode, ensure_request_not_failed, NetworkClient, ProofRequestSpec},
+    network::proto::network::{FulfillmentStatus, FulfillmentStrategy, ProofMode},
+    ExecutionLimitExceeded, Prover, SP1ProofMode, SP1ProofWithPublicValues, SP1ProvingKey,
+    SP1VerifyingKey,
 };
-use alloy_primitives::B256;
-use anyhow::Result;
-use sp1_core_executor::{SP1Context, SP1ContextBuilder};
+use alloy_primitives::{Address, B256};
+use anyhow::{anyhow, Result};
+use futures::{pin_mut, StreamExt, TryStreamExt};
+use sp1_core_executor::{ExecutionError, ExecutionReport, SP1ContextBuilder};
 use sp1_core_machine::io::SP1Stdin;
 use sp1_prover::{components::CpuProverComponents, SP1Prover, SP1_CIRCUIT_VERSION};
 
-use {crate::utils::block_on, tokio::time::sleep};
+use crate::utils::block_on;
 
 /// An implementation of [`crate::ProverClient`] that can generate proofs on a remote RPC server.
+///
+/// Errors returned by the network are [`Error`]s, either directly or wrapped in an
+/// [`anyhow::Error`] that can be downcast to one, so that callers can use
+/// [`Error::is_retryable`] to decide whether to retry a failed operation.
 pub struct NetworkProver {
-    pub(crate) client: NetworkClient,
-    pub(crate) prover: CpuProver,
+    pub(crate) client: Arc<NetworkClient>,
+    pub(crate) prover: Arc<CpuProver>,
+    pub(crate) request_store: Option<Arc<dyn RequestStore>>,
+}
+
+/// How the cycle limit of a proof request is determined.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct CycleLimitConfig {
+    /// The cycle limit set by the requester.
+    pub(crate) cycle_limit: Option<u64>,
+    /// Whether to skip the local execution of the program.
+    pub(crate) skip_simulation: bool,
+    /// The margin (in percent) added to the cycle count of the local execution.
+    pub(crate) auto_margin_percent: Option<u32>,
+    /// Whether the automatic limit is computed from the prover gas of the local execution
+    /// instead of its cycle count.
+    pub(crate) auto_from_gas: bool,
+    /// The maximum number of cycles the program may run for locally.
+    pub(crate) max_local_cycles: Option<u64>,
 }
 
 impl NetworkProver {
@@ -43,11 +73,93 @@ impl NetworkProver {
     ///
     /// let prover = NetworkProver::new("...", "...");
     /// ```
+    ///
+    /// # Panics
+    /// Panics if the private key is malformed. Use [`NetworkProver::try_new`] to handle the error
+    /// instead.
     #[must_use]
     pub fn new(private_key: &str, rpc_url: &str) -> Self {
-        let prover = CpuProver::new();
-        let client = NetworkClient::new(private_key, rpc_url);
-        Self { client, prover }
+        Self::try_new(private_key, rpc_url).unwrap_or_else(|e| panic!("{e:#}"))
+    }
+
+    /// Creates a new [`NetworkProver`] with the given private key, returning an error if the
+    /// private key is malformed.
+    ///
+    /// # Details
+    /// * `private_key`: The Secp256k1 private key to use for signing requests.
+    /// * `rpc_url`: The rpc url to use for the prover network.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::NetworkProver;
+    ///
+    /// let prover = NetworkProver::try_new("...", "...").unwrap();
+    /// ```
+    pub fn try_new(private_key: &str, rpc_url: &str) -> Result<Self> {
+        let client = NetworkClient::new(private_key, rpc_url)?;
+        Ok(Self::with_client(client))
+    }
+
+    /// Creates a new [`NetworkProver`] with the given signer.
+    ///
+    /// # Details
+    /// * `signer`: The signer to use for signing requests (e.g. a KMS or hardware wallet signer).
+    /// * `rpc_url`: The rpc url to use for the prover network.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::NetworkProver;
+    /// use alloy_signer_local::PrivateKeySigner;
+    ///
+    /// let signer = PrivateKeySigner::random();
+    /// let prover = NetworkProver::with_signer(signer, "...");
+    /// ```
+    #[must_use]
+    pub fn with_signer(signer: impl NetworkSigner + 'static, rpc_url: &str) -> Self {
+        Self::from_signer(Arc::new(signer), rpc_url).unwrap_or_else(|e| panic!("{e:#}"))
+    }
+
+    /// Creates a new [`NetworkProver`] from a configured [`NetworkClient`].
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{network::NetworkClient, NetworkProver};
+    ///
+    /// let client = NetworkClient::builder()
+    ///     .private_key("...")
+    ///     .proxy("http://proxy.internal:3128")
+    ///     .build()
+    ///     .unwrap();
+    /// let prover = NetworkProver::with_client(client);
+    /// ```
+    #[must_use]
+    pub fn with_client(client: NetworkClient) -> Self {
+        Self { client: Arc::new(client), prover: Arc::new(CpuProver::new()), request_store: None }
+    }
+
+    /// Persists submitted proof requests in the given store until they are resolved.
+    ///
+    /// # Details
+    /// Requests are inserted into the store when they are submitted, and removed once their
+    /// proof is downloaded or they can no longer be fulfilled. After a restart, resume waiting
+    /// for the requests left in the store with [`NetworkProver::recover_pending`].
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{network::JsonFileRequestStore, NetworkProver};
+    ///
+    /// let prover = NetworkProver::new("...", "...")
+    ///     .with_request_store(JsonFileRequestStore::new("pending-requests.json"));
+    /// ```
+    #[must_use]
+    pub fn with_request_store(mut self, store: impl RequestStore + 'static) -> Self {
+        self.request_store = Some(Arc::new(store));
+        self
+    }
+
+    pub(crate) fn from_signer(signer: Arc<dyn NetworkSigner>, rpc_url: &str) -> Result<Self> {
+        let client = NetworkClient::from_signer(signer, rpc_url)?;
+        Ok(Self::with_client(client))
     }
 
     /// Creates a new [`CpuExecuteBuilder`] for simulating the execution of a program on the CPU.
@@ -74,6 +186,7 @@ impl NetworkProver {
             elf,
             stdin: stdin.clone(),
             context_builder: SP1ContextBuilder::default(),
+            max_cycles: None,
         }
     }
 
@@ -108,6 +221,53 @@ impl NetworkProver {
             strategy: FulfillmentStrategy::Hosted,
             skip_simulation: false,
             cycle_limit: None,
+            cycle_limit_margin: None,
+            cycle_limit_from_gas: false,
+            max_local_cycles: None,
+            fulfillment_params: FulfillmentParams::default(),
+            verify: None,
+        }
+    }
+
+    /// A request to generate a proof for a program registered on the network, without its ELF.
+    ///
+    /// # Details
+    /// * `vk_hash`: The hash of the verifying key of the program.
+    /// * `stdin`: The input to use for the proof.
+    ///
+    /// This is useful for services that request proofs of programs registered elsewhere, e.g. by
+    /// a deploy pipeline. The program is not registered or executed locally. By default, the
+    /// request fails with [`Error::ProgramNotFound`] if the program is not registered.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{network::B256, NetworkProver, SP1Stdin};
+    ///
+    /// let prover = NetworkProver::new("...", "...");
+    /// let vk_hash = B256::repeat_byte(1);
+    /// let proof = prover
+    ///     .prove_with_vk_hash(vk_hash, SP1Stdin::new())
+    ///     .cycle_limit(1_000_000)
+    ///     .run()
+    ///     .unwrap();
+    /// ```
+    pub fn prove_with_vk_hash(
+        &self,
+        vk_hash: B256,
+        stdin: SP1Stdin,
+    ) -> NetworkProveWithVkHashBuilder<'_> {
+        NetworkProveWithVkHashBuilder {
+            prover: self,
+            vk_hash,
+            stdin,
+            mode: SP1ProofMode::Core,
+            timeout: None,
+            strategy: FulfillmentStrategy::Hosted,
+            cycle_limit: None,
+            fulfillment_params: FulfillmentParams::default(),
+            vk: None,
+            verify: None,
+            check_registered: true,
         }
     }
 
@@ -117,8 +277,8 @@ impl NetworkProver {
     /// * `vk`: The verifying key to use for the program.
     /// * `elf`: The elf to use for the program.
     ///
-    /// Note that this method requires that the user honestly registers the program (i.e., the elf
-    /// matches the vk).
+    /// Returns [`Error::ProgramVkMismatch`] if the elf does not match the vk. Use
+    /// [`NetworkProver::register_program_unchecked`] to skip this check.
     ///
     /// # Example
     /// ```rust,no_run
@@ -130,10 +290,114 @@ impl NetworkProver {
     ///
     /// let vk_hash = client.register_program(&vk, elf);
     /// ```
-    pub async fn register_program(&self, vk: &SP1VerifyingKey, elf: &[u8]) -> Result<B256> {
+    pub async fn register_program(&self, vk: &SP1VerifyingKey, elf: &[u8]) -> Result<B256, Error> {
         self.client.register_program(vk, elf).await
     }
 
+    /// Registers a program if it is not already registered, without checking that the elf matches
+    /// the vk.
+    ///
+    /// # Details
+    /// * `vk`: The verifying key to use for the program.
+    /// * `elf`: The elf to use for the program.
+    ///
+    /// Note that this method requires that the user honestly registers the program (i.e., the elf
+    /// matches the vk), otherwise requests for the program will be unfulfillable.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover};
+    ///
+    /// tokio_test::block_on(async {
+    ///     let elf = &[1, 2, 3];
+    ///     let client = ProverClient::builder().network().build();
+    ///     let (pk, vk) = client.setup(elf);
+    ///
+    ///     let vk_hash = client.register_program_unchecked(&vk, elf).await.unwrap();
+    /// })
+    /// ```
+    pub async fn register_program_unchecked(
+        &self,
+        vk: &SP1VerifyingKey,
+        elf: &[u8],
+    ) -> Result<B256, Error> {
+        self.client.register_program_unchecked(vk, elf).await
+    }
+
+    /// Lists the programs registered by the account used to sign requests.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::ProverClient;
+    ///
+    /// tokio_test::block_on(async {
+    ///     let client = ProverClient::builder().network().build();
+    ///     for program in client.list_programs().await.unwrap() {
+    ///         println!("{} {:?}", program.vk_hash, program.name);
+    ///     }
+    /// })
+    /// ```
+    pub async fn list_programs(&self) -> Result<Vec<ProgramMetadata>, Error> {
+        self.client.stream_programs_by_owner(self.client.address()).try_collect().await
+    }
+
+    /// Gets the balance of the account used to sign requests.
+    ///
+    /// # Details
+    /// The returned [`AccountBalance`] includes the amounts reserved for and pending from
+    /// in-flight requests, so [`AccountBalance::available`] can be checked before submitting a
+    /// batch of requests.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::ProverClient;
+    ///
+    /// tokio_test::block_on(async {
+    ///     let client = ProverClient::builder().network().build();
+    ///     let balance = client.balance().await.unwrap();
+    ///     println!("Available credits: {}", balance.available());
+    /// })
+    /// ```
+    pub async fn balance(&self) -> Result<AccountBalance, Error> {
+        self.client.get_account_balance().await
+    }
+
+    /// Estimates the cost of proving a program on the given input.
+    ///
+    /// # Details
+    /// * `pk`: The proving key to use for the proof.
+    /// * `stdin`: The input to use for the proof.
+    ///
+    /// The program is executed locally to get its cycle count, and the cost is estimated for the
+    /// defaults of [`NetworkProver::prove`], i.e. a core proof with the hosted strategy. Returns
+    /// [`Error::PricingUnavailable`] if the network does not support cost estimation.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover};
+    ///
+    /// tokio_test::block_on(async {
+    ///     let elf = &[1, 2, 3];
+    ///     let stdin = SP1Stdin::new();
+    ///
+    ///     let client = ProverClient::builder().network().build();
+    ///     let (pk, vk) = client.setup(elf);
+    ///     let estimate = client.estimate_cost(&pk, &stdin).await.unwrap();
+    ///     println!("Estimated cost: {} credits", estimate.total);
+    /// })
+    /// ```
+    pub async fn estimate_cost(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: &SP1Stdin,
+    ) -> Result<CostEstimate, Error> {
+        let (cycle_limit, _) =
+            self.get_cycle_limit(&pk.elf, stdin, &CycleLimitConfig::default())?;
+        self.client
+            .estimate_proof_cost(ProofMode::Core, cycle_limit, FulfillmentStrategy::Hosted)
+            .await
+    }
+
     /// Gets the status of a proof request. Re-exposes the status response from the client.
     ///
     /// # Details
@@ -152,7 +416,7 @@ impl NetworkProver {
     pub async fn get_proof_status(
         &self,
         request_id: B256,
-    ) -> Result<(GetProofRequestStatusResponse, Option<SP1ProofWithPublicValues>)> {
+    ) -> Result<(GetProofRequestStatusResponse, Option<SP1ProofWithPublicValues>), Error> {
         self.client.get_proof_request_status(request_id, None).await
     }
 
@@ -191,27 +455,246 @@ impl NetworkProver {
             return Err(Error::RequestTimedOut { request_id: request_id.to_vec() }.into());
         }
 
-        // Get the execution and fulfillment statuses.
-        let execution_status = ExecutionStatus::try_from(status.execution_status).unwrap();
+        // Check the execution and fulfillment statuses.
+        ensure_request_not_failed(request_id, &status)?;
         let fulfillment_status = FulfillmentStatus::try_from(status.fulfillment_status).unwrap();
-
-        // Check the execution status.
-        if execution_status == ExecutionStatus::Unexecutable {
-            return Err(Error::RequestUnexecutable { request_id: request_id.to_vec() }.into());
-        }
-
-        // Check the fulfillment status.
         if fulfillment_status == FulfillmentStatus::Fulfilled {
             return Ok((maybe_proof, fulfillment_status));
         }
-        if fulfillment_status == FulfillmentStatus::Unfulfillable {
-            return Err(Error::RequestUnfulfillable { request_id: request_id.to_vec() }.into());
-        }
 
         Ok((None, fulfillment_status))
     }
 
-    /// Requests a proof from the prover network, returning the request ID.
+    /// Attaches to an existing proof request, returning a handle to wait for its proof.
+    ///
+    /// # Details
+    /// * `request_id`: The ID of the request, e.g. from a persisted [`SP1ProofRequestHandle`].
+    ///
+    /// The details of the request are fetched from the network, so this fails if the request
+    /// does not exist.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, network::B256};
+    ///
+    /// tokio_test::block_on(async {
+    ///     let request_id = B256::from_slice(&vec![1u8; 32]);
+    ///     let client = ProverClient::builder().network().build();
+    ///     let handle = client.attach(request_id).await.unwrap();
+    ///     let proof = handle.wait().await.unwrap();
+    /// })
+    /// ```
+    pub async fn attach(&self, request_id: B256) -> Result<SP1ProofRequestHandle, Error> {
+        let request = self.client.get_proof_request_details(request_id).await?;
+        let vk_hash = B256::try_from(request.vk_hash.as_slice()).map_err(anyhow::Error::from)?;
+        let mode = ProofMode::try_from(request.mode).map_err(anyhow::Error::from)?;
+        Ok(self.handle(request_id, vk_hash, mode))
+    }
+
+    /// Returns handles for the requests persisted in the request store, to resume waiting for
+    /// them, e.g. after a restart.
+    ///
+    /// # Details
+    /// Returns no handles if the prover has no request store, see
+    /// [`NetworkProver::with_request_store`]. Requests that were fulfilled in the meantime resolve
+    /// to their proof, and requests that expired fail with [`Error::RequestUnfulfillable`], as
+    /// soon as their status is received. The verifying keys of the requests are not persisted,
+    /// so the proofs are not verified.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{network::JsonFileRequestStore, ProverClient};
+    ///
+    /// tokio_test::block_on(async {
+    ///     let client = ProverClient::builder()
+    ///         .network()
+    ///         .request_store(JsonFileRequestStore::new("pending-requests.json"))
+    ///         .build();
+    ///     for handle in client.recover_pending().await.unwrap() {
+    ///         match handle.wait().await {
+    ///             Ok(proof) => println!("recovered proof for {}", handle.request_id()),
+    ///             Err(e) => println!("request {} failed: {e}", handle.request_id()),
+    ///         }
+    ///     }
+    /// })
+    /// ```
+    pub async fn recover_pending(&self) -> Result<Vec<SP1ProofRequestHandle>, Error> {
+        let Some(store) = &self.request_store else {
+            return Ok(Vec::new());
+        };
+        let requests = store.list().await?;
+        Ok(requests
+            .into_iter()
+            .map(|request| self.handle(request.request_id, request.vk_hash, request.mode))
+            .collect())
+    }
+
+    /// Returns a handle to the given request, which removes it from the request store once it is
+    /// resolved.
+    pub(crate) fn handle(
+        &self,
+        request_id: B256,
+        vk_hash: B256,
+        mode: ProofMode,
+    ) -> SP1ProofRequestHandle {
+        SP1ProofRequestHandle::new(self.client.clone(), request_id, vk_hash)
+            .with_mode(mode)
+            .with_store(self.request_store.clone())
+    }
+
+    /// Replays a proof request locally, returning the report of its execution.
+    ///
+    /// # Details
+    /// * `request_id`: The request ID to replay.
+    ///
+    /// The program and stdin of the request are downloaded from the network and executed with
+    /// the local executor, which helps debugging requests that are unexecutable or
+    /// unfulfillable.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, network::B256};
+    ///
+    /// tokio_test::block_on(async {
+    ///     let request_id = B256::from_slice(&vec![1u8; 32]);
+    ///     let client = ProverClient::builder().network().build();
+    ///     let report = client.replay(request_id).await.unwrap();
+    ///     println!("{}", report);
+    /// })
+    /// ```
+    pub async fn replay(&self, request_id: B256) -> Result<ExecutionReport> {
+        let artifacts = self.client.get_request_artifacts(request_id).await?;
+        let elf = self.client.download_program(&artifacts.program_uri).await?;
+        let (_, report) = self.prover.execute(&elf, &artifacts.stdin).run()?;
+        Ok(report)
+    }
+
+    /// Cancels a proof request that has not yet been assigned to a prover.
+    ///
+    /// # Details
+    /// * `request_id`: The request ID to cancel.
+    ///
+    /// Returns an error if the request has already been assigned or fulfilled, including when
+    /// that happens concurrently with the cancellation.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover};
+    /// use std::time::Duration;
+    ///
+    /// tokio_test::block_on(async {
+    ///     let elf = &[1, 2, 3];
+    ///     let stdin = SP1Stdin::new();
+    ///
+    ///     let client = ProverClient::builder().network().build();
+    ///     let (pk, vk) = client.setup(elf);
+    ///     let request_id = client.prove(&pk, &stdin).request_async().await.unwrap();
+    ///
+    ///     tokio::select! {
+    ///         proof = client.wait_proof(request_id, None) => {
+    ///             let proof = proof.unwrap();
+    ///         }
+    ///         _ = tokio::time::sleep(Duration::from_secs(60)) => {
+    ///             client.cancel_request(request_id).await.unwrap();
+    ///         }
+    ///     }
+    /// })
+    /// ```
+    pub async fn cancel_request(&self, request_id: B256) -> Result<()> {
+        self.client.cancel_proof_request(request_id).await?;
+        log::info!("Cancelled request {}", request_id);
+        Ok(())
+    }
+
+    /// Generates proofs for a batch of inputs to the same program.
+    ///
+    /// # Details
+    /// * `pk`: The proving key to use for the proofs.
+    /// * `stdins`: The inputs to use for the proofs.
+    /// * `mode`: The proof mode to use for the proofs.
+    /// * `strategy`: The fulfillment strategy to use for the proofs.
+    ///
+    /// The requests are submitted together with [`NetworkClient::request_proofs_batch`], and the
+    /// proofs are returned in the order of the inputs. A request that fails, e.g. because its
+    /// simulation fails, does not fail the rest of the batch.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{network::FulfillmentStrategy, Prover, ProverClient, SP1ProofMode, SP1Stdin};
+    ///
+    /// tokio_test::block_on(async {
+    ///     let elf = &[1, 2, 3];
+    ///     let stdins = vec![SP1Stdin::new(), SP1Stdin::new()];
+    ///
+    ///     let client = ProverClient::builder().network().build();
+    ///     let (pk, vk) = client.setup(elf);
+    ///     let proofs = client
+    ///         .prove_batch(&pk, &stdins, SP1ProofMode::Compressed, FulfillmentStrategy::Hosted)
+    ///         .await
+    ///         .unwrap();
+    /// })
+    /// ```
+    pub async fn prove_batch(
+        &self,
+        pk: &SP1ProvingKey,
+        stdins: &[SP1Stdin],
+        mode: SP1ProofMode,
+        strategy: FulfillmentStrategy,
+    ) -> Result<Vec<Result<SP1ProofWithPublicValues>>> {
+        let params = FulfillmentParams::default().with_env_defaults()?;
+        check_mode(mode.into(), SP1_CIRCUIT_VERSION)?;
+        let vk_hash = self.register_program(&pk.vk, &pk.elf).await?;
+
+        // Simulate the inputs to get their cycle limits.
+        let cycle_limits = stdins
+            .iter()
+            .map(|stdin| {
+                self.get_cycle_limit(&pk.elf, stdin, &CycleLimitConfig::default())
+                    .map(|(cycle_limit, _)| cycle_limit)
+            })
+            .collect::<Vec<_>>();
+        let specs = stdins
+            .iter()
+            .zip(&cycle_limits)
+            .filter_map(|(stdin, cycle_limit)| {
+                let cycle_limit = *cycle_limit.as_ref().ok()?;
+                Some(ProofRequestSpec {
+                    vk_hash,
+                    stdin: stdin.clone(),
+                    mode: mode.into(),
+                    version: SP1_CIRCUIT_VERSION.to_string(),
+                    strategy,
+                    timeout_secs: DEFAULT_TIMEOUT_SECS,
+                    cycle_limit,
+                    params: params.clone(),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // Request the proofs.
+        log::info!("Requesting {} proofs in a batch", specs.len());
+        let mut responses = self.client.request_proofs_batch(specs).await.into_iter();
+        let request_ids = cycle_limits
+            .into_iter()
+            .map(|cycle_limit| {
+                cycle_limit?;
+                let response = responses.next().expect("one response per submitted request")?;
+                let body = response.body.ok_or_else(|| anyhow!("Missing response body"))?;
+                Ok(B256::from_slice(&body.request_id))
+            })
+            .collect::<Vec<Result<B256>>>();
+
+        // Wait for the proofs.
+        let vk = verify_by_default(mode).then_some((&pk.vk, mode.into()));
+        let proofs = request_ids.into_iter().map(|request_id| async move {
+            let request_id = request_id?;
+            log::info!("Created request {}", request_id);
+            self.wait_proof_impl(request_id, None, vk).await
+        });
+        Ok(futures::future::join_all(proofs).await)
+    }
+
+    /// Requests a proof from the prover network, returning the request ID and its deadline.
     ///
     /// # Details
     /// * `vk_hash`: The hash of the verifying key to use for the proof.
@@ -219,6 +702,8 @@ impl NetworkProver {
     /// * `mode`: The proof mode to use for the proof.
     /// * `strategy`: The fulfillment strategy to use for the proof.
     /// * `cycle_limit`: The cycle limit to use for the proof.
+    /// * `params`: The parameters of the fulfillment strategy.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn request_proof(
         &self,
         vk_hash: B256,
@@ -227,29 +712,36 @@ impl NetworkProver {
         strategy: FulfillmentStrategy,
         cycle_limit: u64,
         timeout: Option<Duration>,
-    ) -> Result<B256> {
-        // Get the timeout.
+        params: &FulfillmentParams,
+    ) -> Result<(B256, u64)> {
+        // Get the timeout and the deadline.
         let timeout_secs = timeout.map_or(DEFAULT_TIMEOUT_SECS, |dur| dur.as_secs());
+        let deadline = self.client.deadline(timeout_secs).await?;
 
         // Log the request.
         log::info!("Requesting proof:");
         log::info!("├─ Cycle limit: {}", cycle_limit);
         log::info!("├─ Proof mode: {:?}", mode);
         log::info!("├─ Strategy: {:?}", strategy);
+        if let Some(max_price) = params.max_price_per_bgas {
+            log::info!("├─ Max price per bgas: {}", max_price);
+        }
         log::info!("├─ Timeout: {} seconds", timeout_secs);
+        log::info!("├─ Deadline: {}", deadline);
         log::info!("└─ Circuit version: {}", SP1_CIRCUIT_VERSION);
 
         // Request the proof.
         let response = self
             .client
-            .request_proof(
+            .request_proof_with_deadline(
                 vk_hash,
                 stdin,
                 mode,
                 SP1_CIRCUIT_VERSION,
                 strategy,
-                timeout_secs,
+                deadline,
                 cycle_limit,
+                params,
             )
             .await?;
 
@@ -258,56 +750,158 @@ impl NetworkProver {
         let request_id = B256::from_slice(&response.body.unwrap().request_id);
         log::info!("Created request {} in transaction {:?}", request_id, tx_hash);
 
-        if self.client.rpc_url == DEFAULT_NETWORK_RPC_URL {
+        if self.client.endpoints.primary() == DEFAULT_NETWORK_RPC_URL {
             log::info!(
                 "View request status at: https://network.succinct.xyz/request/{}",
                 request_id
             );
         }
 
-        Ok(request_id)
+        if let Some(store) = &self.request_store {
+            let pending = PendingRequest {
+                request_id,
+                vk_hash,
+                mode,
+                deadline,
+                labels: params.labels.clone(),
+            };
+            if let Err(e) = store.insert(&pending).await {
+                log::error!("Failed to persist request {request_id} in the request store: {e:#}");
+            }
+        }
+
+        Ok((request_id, deadline))
     }
 
     /// Waits for a proof to be generated and returns the proof. If a timeout is supplied, the
     /// function will return an error if the proof is not generated within the timeout.
+    ///
+    /// # Details
+    /// Status updates are received through
+    /// [`NetworkClient::subscribe_proof_request_status`], so the proof is downloaded as soon as
+    /// the request is fulfilled.
+    ///
+    /// The proof is not verified, since the verifying key is not known. Verify it with
+    /// [`Prover::verify`], or wait through the [`SP1ProofRequestHandle`] returned by
+    /// [`NetworkProveBuilder::request_handle`], which verifies it.
     pub async fn wait_proof(
         &self,
         request_id: B256,
         timeout: Option<Duration>,
     ) -> Result<SP1ProofWithPublicValues> {
+        self.wait_proof_impl(request_id, timeout, None).await
+    }
+
+    /// Waits for a proof like [`Self::wait_proof`], verifying it against the verifying key, if
+    /// given, as a proof of the requested mode before returning it.
+    pub(crate) async fn wait_proof_impl(
+        &self,
+        request_id: B256,
+        timeout: Option<Duration>,
+        vk: Option<(&SP1VerifyingKey, ProofMode)>,
+    ) -> Result<SP1ProofWithPublicValues> {
+        let status = match self.wait_fulfilled(request_id, timeout).await {
+            Ok(status) => status,
+            Err(e) => {
+                let failed = matches!(
+                    e.downcast_ref::<Error>(),
+                    Some(Error::RequestUnexecutable { .. } | Error::RequestUnfulfillable { .. })
+                );
+                if failed {
+                    self.forget_request(request_id).await;
+                }
+                return Err(e);
+            }
+        };
+        let proof = self.client.download_proof(&status).await?;
+        self.forget_request(request_id).await;
+        if let Some((vk, mode)) = vk {
+            verify_fulfilled_proof(&self.prover, vk, &proof, request_id, &status, Some(mode))?;
+        }
+        Ok(proof)
+    }
+
+    /// Removes a resolved request from the request store, if any.
+    async fn forget_request(&self, request_id: B256) {
+        if let Some(store) = &self.request_store {
+            if let Err(e) = store.remove(request_id).await {
+                log::warn!("Failed to remove request {request_id} from the request store: {e:#}");
+            }
+        }
+    }
+
+    /// Waits for a request to be fulfilled, returning its status.
+    async fn wait_fulfilled(
+        &self,
+        request_id: B256,
+        timeout: Option<Duration>,
+    ) -> Result<GetProofRequestStatusResponse> {
         let mut is_assigned = false;
         let start_time = Instant::now();
+        let statuses = self.client.subscribe_proof_request_status(request_id);
+        pin_mut!(statuses);
 
         loop {
-            // Calculate the remaining timeout.
-            if let Some(timeout) = timeout {
-                if start_time.elapsed() > timeout {
-                    return Err(Error::RequestTimedOut { request_id: request_id.to_vec() }.into());
+            // Wait for the next status, up to the remaining timeout.
+            let next = match timeout {
+                Some(timeout) => {
+                    let remaining_timeout = timeout.saturating_sub(start_time.elapsed());
+                    tokio::time::timeout(remaining_timeout, statuses.next())
+                        .await
+                        .map_err(|_| Error::RequestTimedOut { request_id: request_id.to_vec() })?
                 }
-            }
-            let remaining_timeout = timeout.map(|t| {
-                let elapsed = start_time.elapsed();
-                if elapsed < t {
-                    t - elapsed
-                } else {
-                    Duration::from_secs(0)
-                }
-            });
-
-            let (maybe_proof, fulfillment_status) =
-                self.process_proof_status(request_id, remaining_timeout).await?;
+                None => statuses.next().await,
+            };
+            let status = next.ok_or_else(|| {
+                anyhow!("Status subscription for request {} ended unexpectedly", request_id)
+            })??;
 
+            let fulfillment_status = FulfillmentStatus::try_from(status.fulfillment_status)?;
             if fulfillment_status == FulfillmentStatus::Fulfilled {
-                return Ok(maybe_proof.unwrap());
+                return Ok(status);
             } else if fulfillment_status == FulfillmentStatus::Assigned && !is_assigned {
                 log::info!("Proof request assigned, proving...");
                 is_assigned = true;
             }
-
-            sleep(Duration::from_secs(2)).await;
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn simulate_proof_impl(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: &SP1Stdin,
+        mode: SP1ProofMode,
+        strategy: FulfillmentStrategy,
+        timeout: Option<Duration>,
+        cycle_limit: CycleLimitConfig,
+        params: FulfillmentParams,
+    ) -> Result<SimulatedProofRequest> {
+        let params = params.with_env_defaults()?;
+        params.validate(strategy)?;
+
+        // Check the program instead of registering it, so that nothing is created on the network.
+        utils::verify_program_vk(&pk.vk, &pk.elf)?;
+        let vk_hash = NetworkClient::get_vk_hash(&pk.vk)?;
+
+        let (cycle_limit, _) = self.get_cycle_limit(&pk.elf, stdin, &cycle_limit)?;
+        let timeout_secs = timeout.map_or(DEFAULT_TIMEOUT_SECS, |dur| dur.as_secs());
+        let simulated = self
+            .client
+            .simulate_proof_request(
+                vk_hash,
+                stdin,
+                mode.into(),
+                SP1_CIRCUIT_VERSION,
+                strategy,
+                timeout_secs,
+                cycle_limit,
+                &params,
+            )
+            .await?;
+        Ok(simulated)
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub(crate) async fn request_proof_impl(
         &self,
@@ -316,12 +910,38 @@ impl NetworkProver {
         mode: SP1ProofMode,
         strategy: FulfillmentStrategy,
         timeout: Option<Duration>,
-        skip_simulation: bool,
-        cycle_limit: Option<u64>,
-    ) -> Result<B256> {
+        cycle_limit: CycleLimitConfig,
+        params: FulfillmentParams,
+    ) -> Result<ProofRequestHandle> {
+        let params = params.with_env_defaults()?;
+        check_mode(mode.into(), SP1_CIRCUIT_VERSION)?;
+        params.validate(strategy)?;
+
+        // The network only receives the stdin, so the proofs of deferred proof slots cannot be
+        // supplied to it.
+        if !stdin.deferred_proof_slots.is_empty() {
+            return Err(Error::InvalidRequest {
+                reason: "the network prover does not support deferred proof slots".to_string(),
+            }
+            .into());
+        }
+
+        // Determine the cycle limit before any network traffic, so that programs exceeding the
+        // local execution cap fail early.
+        let (cycle_limit, execution_report) = self.get_cycle_limit(&pk.elf, stdin, &cycle_limit)?;
+        if let Some(report) = &execution_report {
+            log::info!(
+                "Executed program locally: {} cycles, {} syscalls",
+                report.total_instruction_count(),
+                report.total_syscall_count()
+            );
+        }
+
         let vk_hash = self.register_program(&pk.vk, &pk.elf).await?;
-        let cycle_limit = self.get_cycle_limit(cycle_limit, &pk.elf, stdin, skip_simulation)?;
-        self.request_proof(vk_hash, stdin, mode.into(), strategy, cycle_limit, timeout).await
+        let (request_id, deadline) = self
+            .request_proof(vk_hash, stdin, mode.into(), strategy, cycle_limit, timeout, &params)
+            .await?;
+        Ok(ProofRequestHandle { request_id, cycle_limit, deadline, execution_report })
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -332,41 +952,82 @@ impl NetworkProver {
         mode: SP1ProofMode,
         strategy: FulfillmentStrategy,
         timeout: Option<Duration>,
-        skip_simulation: bool,
-        cycle_limit: Option<u64>,
+        cycle_limit: CycleLimitConfig,
+        params: FulfillmentParams,
+        verify: bool,
     ) -> Result<SP1ProofWithPublicValues> {
-        let request_id = self
-            .request_proof_impl(pk, stdin, mode, strategy, timeout, skip_simulation, cycle_limit)
+        let handle = self
+            .request_proof_impl(pk, stdin, mode, strategy, timeout, cycle_limit, params)
             .await?;
-        self.wait_proof(request_id, timeout).await
+        let vk = verify.then_some((&pk.vk, mode.into()));
+        self.wait_proof_impl(handle.request_id, timeout, vk).await
     }
 
     /// The cycle limit is determined according to the following priority:
     ///
-    /// 1. If a cycle limit was explicitly set by the requester, use the specified value.
-    /// 2. If simulation is enabled, calculate the limit by simulating the
+    /// 1. If a cycle limit was explicitly set by the requester, use the specified value. Unless
+    ///    simulation is skipped, the program is first executed up to the limit, failing with
+    ///    [`ExecutionLimitExceeded`] if it does not halt within it.
+    /// 2. If an automatic cycle limit was requested, simulate the execution of the program and
+    ///    add the margin to its cycle count, or to its prover gas if requested.
+    /// 3. If simulation is enabled, calculate the limit by simulating the
     ///    execution of the program. This is the default behavior.
-    /// 3. Otherwise, use the default cycle limit ([`DEFAULT_CYCLE_LIMIT`]).
+    /// 4. Otherwise, use the default cycle limit ([`DEFAULT_CYCLE_LIMIT`]).
+    ///
+    /// The execution report is returned if the program was simulated.
     fn get_cycle_limit(
         &self,
-        cycle_limit: Option<u64>,
         elf: &[u8],
         stdin: &SP1Stdin,
-        skip_simulation: bool,
-    ) -> Result<u64> {
-        if let Some(cycle_limit) = cycle_limit {
-            return Ok(cycle_limit);
+        config: &CycleLimitConfig,
+    ) -> Result<(u64, Option<ExecutionReport>)> {
+        if let Some(cycle_limit) = config.cycle_limit {
+            if config.skip_simulation {
+                return Ok((cycle_limit, None));
+            }
+
+            // Refuse to request a proof that the prover would fail to generate within the limit.
+            let local_cap = config.max_local_cycles.filter(|&cap| cap < cycle_limit);
+            let mut context = SP1ContextBuilder::default();
+            context.max_cycles(local_cap.unwrap_or(cycle_limit));
+            let (_, report) =
+                execute_with_cycle_limit(self.prover.inner(), elf, stdin, context.build())
+                    .map_err(|e| match e.downcast::<ExecutionLimitExceeded>() {
+                        Ok(e) if local_cap.is_some() => {
+                            Error::LocalExecutionCapExceeded { max_cycles: e.max_cycles }.into()
+                        }
+                        Ok(e) => e.into(),
+                        Err(_) => anyhow::Error::from(Error::SimulationFailed),
+                    })?;
+            return Ok((cycle_limit, Some(report)));
         }
 
-        if skip_simulation {
-            Ok(DEFAULT_CYCLE_LIMIT)
-        } else {
-            self.prover
-                .inner()
-                .execute(elf, stdin, SP1Context::default())
-                .map(|(_, report)| report.total_instruction_count())
-                .map_err(|_| Error::SimulationFailed.into())
+        if config.skip_simulation && config.auto_margin_percent.is_none() {
+            return Ok((DEFAULT_CYCLE_LIMIT, None));
         }
+
+        let mut context = SP1ContextBuilder::default();
+        if let Some(max_cycles) = config.max_local_cycles {
+            context.max_cycles(max_cycles);
+        }
+        let (_, report) =
+            self.prover.inner().execute(elf, stdin, context.build()).map_err(|e| match e {
+                ExecutionError::ExceededCycleLimit(max_cycles) => {
+                    Error::LocalExecutionCapExceeded { max_cycles }
+                }
+                _ => Error::SimulationFailed,
+            })?;
+
+        let cycles = if config.auto_from_gas {
+            report.gas().total()
+        } else {
+            report.total_instruction_count()
+        };
+        let cycle_limit = match config.auto_margin_percent {
+            Some(margin) => cycles.saturating_add(cycles.saturating_mul(margin.into()) / 100),
+            None => cycles,
+        };
+        Ok((cycle_limit, Some(report)))
     }
 }
 
@@ -385,8 +1046,58 @@ impl Prover<CpuProverComponents> for NetworkProver {
         stdin: &SP1Stdin,
         mode: SP1ProofMode,
     ) -> Result<SP1ProofWithPublicValues> {
-        block_on(self.prove_impl(pk, stdin, mode, FulfillmentStrategy::Hosted, None, false, None))
+        block_on(self.prove_impl(
+            pk,
+            stdin,
+            mode,
+            FulfillmentStrategy::Hosted,
+            None,
+            CycleLimitConfig::default(),
+            FulfillmentParams::default(),
+            verify_by_default(mode),
+        ))
+    }
+}
+
+/// Whether proofs of the given mode are verified before they are returned by default, i.e. for
+/// Plonk and Groth16 proofs, which are cheap to verify.
+pub(crate) fn verify_by_default(mode: SP1ProofMode) -> bool {
+    matches!(mode, SP1ProofMode::Plonk | SP1ProofMode::Groth16)
+}
+
+/// Verifies a proof downloaded from the network against the verifying key of its program.
+///
+/// # Details
+/// Returns [`Error::ProofVerificationFailed`], along with the fulfiller reported in the status of
+/// the request, if the proof does not verify.
+pub(crate) fn verify_fulfilled_proof(
+    prover: &CpuProver,
+    vk: &SP1VerifyingKey,
+    proof: &SP1ProofWithPublicValues,
+    request_id: B256,
+    status: &GetProofRequestStatusResponse,
+    mode: Option<ProofMode>,
+) -> Result<(), Error> {
+    let failed = |reason: String| {
+        log::warn!("Proof for request {request_id} failed verification: {reason}");
+        Error::ProofVerificationFailed {
+            request_id: request_id.to_vec(),
+            fulfiller: status.fulfiller.as_deref().map(Address::from_slice),
+            reason,
+        }
+    };
+
+    // The verifier is picked by the kind of the proof, so a proof of another mode than the one
+    // requested would be verified as such.
+    let actual = ProofMode::from(SP1ProofMode::from(&proof.proof));
+    if let Some(mode) = mode.filter(|mode| *mode != actual) {
+        return Err(failed(format!(
+            "expected a {} proof, but got a {} proof",
+            mode.as_str_name(),
+            actual.as_str_name()
+        )));
     }
+    prover.verify(proof, vk).map_err(|e| failed(e.to_string()))
 }
 
 impl From<SP1ProofMode> for ProofMode {
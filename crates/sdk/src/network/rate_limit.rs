@@ -0,0 +1,101 @@
+//! # Rate Limiting
+//!
+//! This module provides a client-side rate limiter for the RPCs of the network, so that a client
+//! stays below the rate limit of the network instead of being throttled by it.
+
+use std::time::{Duration, Instant};
+
+use tokio::sync::Mutex;
+
+/// A token bucket that lets calls through at a steady rate, with bursts of up to one second of
+/// calls.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    requests_per_second: f64,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// The available tokens, negative if calls are waiting for tokens.
+    tokens: f64,
+    /// When the tokens were last refilled.
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    /// Creates a rate limiter that lets `requests_per_second` calls through per second.
+    pub(crate) fn new(requests_per_second: f64) -> Self {
+        let bucket =
+            Bucket { tokens: Self::burst(requests_per_second), refilled_at: Instant::now() };
+        Self { requests_per_second, bucket: Mutex::new(bucket) }
+    }
+
+    /// The maximum number of calls let through at once.
+    fn burst(requests_per_second: f64) -> f64 {
+        requests_per_second.max(1.0)
+    }
+
+    /// Waits until a call may be made.
+    ///
+    /// # Details
+    /// Calls are let through in the order they acquire, since each call reserves its token
+    /// before waiting for it.
+    pub(crate) async fn acquire(&self) {
+        let wait = {
+            let mut bucket = self.bucket.lock().await;
+            let now = Instant::now();
+            let refill = now.duration_since(bucket.refilled_at).as_secs_f64();
+            bucket.tokens = (bucket.tokens + refill * self.requests_per_second)
+                .min(Self::burst(self.requests_per_second));
+            bucket.refilled_at = now;
+            bucket.tokens -= 1.0;
+            if bucket.tokens >= 0.0 {
+                return;
+            }
+            Duration::from_secs_f64(-bucket.tokens / self.requests_per_second)
+        };
+        tokio::time::sleep(wait).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_burst() {
+        let limiter = RateLimiter::new(50.0);
+        let start = Instant::now();
+        for _ in 0..50 {
+            limiter.acquire().await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_steady_rate() {
+        let limiter = RateLimiter::new(50.0);
+        let start = Instant::now();
+        for _ in 0..75 {
+            limiter.acquire().await;
+        }
+        // The first 50 calls are a burst, and the next 25 are spread over half a second.
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(450), "{elapsed:?}");
+        assert!(elapsed < Duration::from_secs(2), "{elapsed:?}");
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_calls_share_the_rate() {
+        let limiter = std::sync::Arc::new(RateLimiter::new(20.0));
+        let start = Instant::now();
+        let calls = (0..30).map(|_| {
+            let limiter = limiter.clone();
+            tokio::spawn(async move { limiter.acquire().await })
+        });
+        futures::future::try_join_all(calls).await.unwrap();
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(450), "{elapsed:?}");
+    }
+}
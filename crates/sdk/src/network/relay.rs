@@ -0,0 +1,268 @@
+//! # Network Request Relay
+//!
+//! This module provides proof requests that are prepared, signed and submitted in separate steps,
+//! so that the requester key can sign them on a machine without network access, as well as
+//! simulated proof requests that are built and signed but never submitted.
+
+use alloy_primitives::Address;
+use alloy_signer::{Signature, SignerSync};
+use alloy_sol_types::Eip712Domain;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use sp1_core_machine::io::SP1Stdin;
+
+use super::client::decode_artifact_content;
+use super::eip712::TypedMessage;
+use super::proto::network::{MessageFormat, RequestProofRequest, RequestProofRequestBody};
+use super::utils::Signable;
+use super::Error;
+
+/// A proof request that is ready to be signed by the requester.
+///
+/// # Details
+/// Created by [`crate::network::NetworkClient::prepare_proof_request`], which uploads the stdin
+/// and fetches the nonce of the requester. It can be serialized and moved to the machine that
+/// holds the requester key, where it is signed with [`UnsignedProofRequest::sign`], or with
+/// [`UnsignedProofRequest::sign_typed`] if the network accepts EIP-712 signatures.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnsignedProofRequest {
+    /// The address of the account that must sign the request.
+    pub requester: Address,
+    /// The body of the request.
+    pub body: RequestProofRequestBody,
+}
+
+impl UnsignedProofRequest {
+    /// Signs the request with the requester key.
+    ///
+    /// # Details
+    /// This does not access the network. Returns [`Error::InvalidRequest`] if the signer is not
+    /// the requester the request was prepared for, since the nonce would not match.
+    pub fn sign<S: SignerSync + ?Sized>(&self, signer: &S) -> Result<SignedProofRequest, Error> {
+        let signature = self.body.sign(signer).map_err(anyhow::Error::from)?;
+        let signer = signature
+            .recover_address_from_msg(self.body.encode_to_vec())
+            .map_err(anyhow::Error::from)?;
+        self.signed(&signature, signer, MessageFormat::Binary)
+    }
+
+    /// Signs the request with the requester key, as EIP-712 typed data in the given domain.
+    ///
+    /// # Details
+    /// This does not access the network. The domain must be the one of the network the request
+    /// is submitted to, see [`crate::network::signing_domain`]. Returns [`Error::InvalidRequest`]
+    /// if the signer is not the requester the request was prepared for.
+    pub fn sign_typed<S: SignerSync + ?Sized>(
+        &self,
+        signer: &S,
+        domain: &Eip712Domain,
+    ) -> Result<SignedProofRequest, Error> {
+        let signature = self.body.sign_typed(signer, domain).map_err(anyhow::Error::from)?;
+        let signer = signature
+            .recover_address_from_prehash(&self.body.eip712_signing_hash(domain))
+            .map_err(anyhow::Error::from)?;
+        self.signed(&signature, signer, MessageFormat::Eip712)
+    }
+
+    /// Checks that the request was signed by the requester.
+    fn signed(
+        &self,
+        signature: &Signature,
+        signer: Address,
+        format: MessageFormat,
+    ) -> Result<SignedProofRequest, Error> {
+        if signer != self.requester {
+            return Err(Error::InvalidRequest {
+                reason: format!(
+                    "request was prepared for {}, but was signed by {signer}",
+                    self.requester
+                ),
+            });
+        }
+        Ok(SignedProofRequest {
+            body: self.body.clone(),
+            signature: signature.as_bytes().to_vec(),
+            format,
+        })
+    }
+}
+
+/// A proof request signed by the requester, ready to be submitted to the network.
+///
+/// # Details
+/// Submitted with [`crate::network::NetworkClient::submit_signed_proof_request`], from any
+/// machine with network access.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignedProofRequest {
+    /// The body of the request.
+    pub body: RequestProofRequestBody,
+    /// The signature of the body.
+    pub signature: Vec<u8>,
+    /// The format of the signature: [`MessageFormat::Binary`] for a signature of the encoded
+    /// body, or [`MessageFormat::Eip712`] for a signature of the body as typed data.
+    #[serde(default = "legacy_format")]
+    pub format: MessageFormat,
+}
+
+/// The format of requests signed before the format was recorded.
+fn legacy_format() -> MessageFormat {
+    MessageFormat::Binary
+}
+
+impl From<SignedProofRequest> for RequestProofRequest {
+    fn from(signed: SignedProofRequest) -> Self {
+        Self { format: signed.format.into(), signature: signed.signature, body: Some(signed.body) }
+    }
+}
+
+/// A proof request that was built and signed, but not submitted to the network.
+///
+/// # Details
+/// Created by [`crate::network::NetworkClient::simulate_proof_request`]. The stdin is not
+/// uploaded: the request references it by a `simulated://` URI, and the encoded artifact is kept
+/// in memory instead, so that it can be checked or executed locally with [`Self::stdin`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulatedProofRequest {
+    /// The signed request, exactly as it would be submitted.
+    pub request: RequestProofRequest,
+    /// The encoded stdin artifact, exactly as it would be uploaded.
+    pub stdin_artifact: Vec<u8>,
+    /// Whether the network validated the request. This is `false` if the network does not
+    /// support validating requests.
+    pub validated: bool,
+    /// The EIP-712 domain the request was signed in, if it was signed as typed data.
+    pub domain: Option<Eip712Domain>,
+}
+
+impl SimulatedProofRequest {
+    /// The body of the request.
+    #[must_use]
+    pub fn body(&self) -> Option<&RequestProofRequestBody> {
+        self.request.body.as_ref()
+    }
+
+    /// Decodes the stdin of the request, e.g. to execute the program locally.
+    pub fn stdin(&self) -> Result<SP1Stdin, Error> {
+        Ok(decode_artifact_content(&self.stdin_artifact)?)
+    }
+
+    /// Recovers the address that signed the request.
+    pub fn signer(&self) -> Result<Address, Error> {
+        let body = self.body().ok_or_else(|| anyhow::anyhow!("Missing request body"))?;
+        let signature =
+            Signature::try_from(self.request.signature.as_slice()).map_err(anyhow::Error::from)?;
+        let signer = match &self.domain {
+            Some(domain) => {
+                signature.recover_address_from_prehash(&body.eip712_signing_hash(domain))
+            }
+            None => signature.recover_address_from_msg(body.encode_to_vec()),
+        };
+        Ok(signer.map_err(anyhow::Error::from)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use alloy_signer_local::PrivateKeySigner;
+
+    use super::*;
+    use crate::network::{signing_domain, DEFAULT_DOMAIN_NAME};
+
+    fn unsigned_request(requester: Address) -> UnsignedProofRequest {
+        UnsignedProofRequest {
+            requester,
+            body: RequestProofRequestBody {
+                nonce: 7,
+                version: "sp1-v4.0.0-rc.3".to_string(),
+                vk_hash: B256::repeat_byte(1).to_vec(),
+                stdin_uri: "s3://stdin".to_string(),
+                deadline: 1_700_000_000,
+                cycle_limit: 1_000_000,
+                max_price_per_bgas: Some("100".to_string()),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_round_trip_matches_single_call_signature() {
+        let signer = PrivateKeySigner::random();
+        let unsigned = unsigned_request(signer.address());
+
+        // The signature produced by the single-call path.
+        let expected = unsigned.body.sign(&signer).unwrap().as_bytes().to_vec();
+
+        // Move the unsigned request to the offline machine, sign it and move it back.
+        let json = serde_json::to_string(&unsigned).unwrap();
+        let offline: UnsignedProofRequest = serde_json::from_str(&json).unwrap();
+        let signed = offline.sign(&signer).unwrap();
+        let json = serde_json::to_string(&signed).unwrap();
+        let signed: SignedProofRequest = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(signed.signature, expected);
+        let request = RequestProofRequest::from(signed);
+        assert_eq!(request.body, Some(unsigned.body));
+        assert_eq!(request.signature, expected);
+    }
+
+    #[test]
+    fn test_simulated_request() {
+        let signer = PrivateKeySigner::random();
+        let signed = unsigned_request(signer.address()).sign(&signer).unwrap();
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+
+        let simulated = SimulatedProofRequest {
+            request: RequestProofRequest::from(signed),
+            stdin_artifact: bincode::serialize(&stdin).unwrap(),
+            validated: false,
+            domain: None,
+        };
+        assert_eq!(simulated.signer().unwrap(), signer.address());
+        assert_eq!(simulated.stdin().unwrap().buffer, stdin.buffer);
+    }
+
+    #[test]
+    fn test_sign_typed() {
+        let signer = PrivateKeySigner::random();
+        let unsigned = unsigned_request(signer.address());
+        let domain = signing_domain(DEFAULT_DOMAIN_NAME);
+        let signed = unsigned.sign_typed(&signer, &domain).unwrap();
+        assert_eq!(signed.format, MessageFormat::Eip712);
+        assert_ne!(signed.signature, unsigned.sign(&signer).unwrap().signature);
+
+        let request = RequestProofRequest::from(signed);
+        assert_eq!(request.format, MessageFormat::Eip712 as i32);
+        let simulated = SimulatedProofRequest {
+            request,
+            stdin_artifact: Vec::new(),
+            validated: false,
+            domain: Some(domain),
+        };
+        assert_eq!(simulated.signer().unwrap(), signer.address());
+
+        let err = unsigned_request(Address::repeat_byte(2))
+            .sign_typed(&signer, &signing_domain(DEFAULT_DOMAIN_NAME))
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidRequest { .. }));
+    }
+
+    #[test]
+    fn test_signed_request_defaults_to_binary_format() {
+        // Requests signed before the format was recorded have no format.
+        let signer = PrivateKeySigner::random();
+        let signed = unsigned_request(signer.address()).sign(&signer).unwrap();
+        let mut json = serde_json::to_value(&signed).unwrap();
+        json.as_object_mut().unwrap().remove("format");
+        let decoded: SignedProofRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, signed);
+    }
+
+    #[test]
+    fn test_sign_with_wrong_signer() {
+        let unsigned = unsigned_request(Address::repeat_byte(2));
+        let err = unsigned.sign(&PrivateKeySigner::random()).unwrap_err();
+        assert!(matches!(err, Error::InvalidRequest { .. }));
+    }
+}
@@ -0,0 +1,272 @@
+//! # Request Store
+//!
+//! This module persists the proof requests a [`crate::NetworkProver`] is waiting for, so that a
+//! service can resume waiting for them after a restart instead of orphaning paid requests.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use alloy_primitives::B256;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::proto::network::ProofMode;
+
+/// A proof request that was submitted to the network and is not resolved yet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingRequest {
+    /// The ID of the request.
+    pub request_id: B256,
+    /// The verifying key hash of the program the request proves.
+    pub vk_hash: B256,
+    /// The proof mode of the request.
+    pub mode: ProofMode,
+    /// The deadline of the request, in seconds since the Unix epoch.
+    pub deadline: u64,
+    /// The labels of the request.
+    pub labels: BTreeMap<String, String>,
+}
+
+/// Persists the proof requests a [`crate::NetworkProver`] is waiting for.
+///
+/// # Details
+/// The prover inserts a request when it is submitted, and removes it once its proof is
+/// downloaded or the request can no longer be fulfilled. Requests left in the store, e.g. by a
+/// crash, are resumed with [`crate::NetworkProver::recover_pending`].
+///
+/// Implementations must be safe to call concurrently, since requests are submitted and resolved
+/// from many tasks. See [`JsonFileRequestStore`] and [`MemoryRequestStore`].
+#[async_trait]
+pub trait RequestStore: Send + Sync {
+    /// Persists a submitted request, replacing any request with the same ID.
+    async fn insert(&self, request: &PendingRequest) -> Result<()>;
+
+    /// Removes a request. Removing a request that is not in the store is not an error.
+    async fn remove(&self, request_id: B256) -> Result<()>;
+
+    /// Lists the persisted requests.
+    async fn list(&self) -> Result<Vec<PendingRequest>>;
+}
+
+/// A [`RequestStore`] that keeps requests in memory, e.g. for tests.
+#[derive(Debug, Default)]
+pub struct MemoryRequestStore {
+    requests: Mutex<BTreeMap<B256, PendingRequest>>,
+}
+
+impl MemoryRequestStore {
+    /// Creates an empty store.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RequestStore for MemoryRequestStore {
+    async fn insert(&self, request: &PendingRequest) -> Result<()> {
+        self.requests.lock().unwrap().insert(request.request_id, request.clone());
+        Ok(())
+    }
+
+    async fn remove(&self, request_id: B256) -> Result<()> {
+        self.requests.lock().unwrap().remove(&request_id);
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<PendingRequest>> {
+        Ok(self.requests.lock().unwrap().values().cloned().collect())
+    }
+}
+
+/// A [`RequestStore`] that keeps requests in a JSON file.
+///
+/// # Details
+/// The whole file is rewritten on every change, through a temporary file that replaces it, so
+/// that a crash never leaves it half written. The file should not be shared by several
+/// processes.
+///
+/// # Example
+/// ```rust,no_run
+/// use sp1_sdk::network::JsonFileRequestStore;
+/// use sp1_sdk::ProverClient;
+///
+/// tokio_test::block_on(async {
+///     let prover = ProverClient::builder()
+///         .network()
+///         .request_store(JsonFileRequestStore::new("pending-requests.json"))
+///         .build();
+///     for handle in prover.recover_pending().await.unwrap() {
+///         let proof = handle.wait().await.unwrap();
+///     }
+/// })
+/// ```
+#[derive(Debug)]
+pub struct JsonFileRequestStore {
+    path: PathBuf,
+    lock: tokio::sync::Mutex<()>,
+}
+
+impl JsonFileRequestStore {
+    /// Creates a store backed by the file at the given path, which is created on the first
+    /// insert if it does not exist.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), lock: tokio::sync::Mutex::new(()) }
+    }
+
+    /// The path of the file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads the requests in the file, by ID.
+    async fn read(&self) -> Result<BTreeMap<B256, PendingRequest>> {
+        let json = match tokio::fs::read(&self.path).await {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(e) => return Err(e).context(format!("Failed to read {}", self.path.display())),
+        };
+        let requests: Vec<PendingRequest> = serde_json::from_slice(&json)
+            .with_context(|| format!("Failed to parse {}", self.path.display()))?;
+        Ok(requests.into_iter().map(|request| (request.request_id, request)).collect())
+    }
+
+    /// Replaces the requests in the file.
+    fn write(&self, requests: &BTreeMap<B256, PendingRequest>) -> Result<()> {
+        let dir = match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let mut file = tempfile::NamedTempFile::new_in(dir)?;
+        serde_json::to_writer_pretty(&mut file, &requests.values().collect::<Vec<_>>())?;
+        file.flush()?;
+        file.as_file().sync_all()?;
+        file.persist(&self.path)
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RequestStore for JsonFileRequestStore {
+    async fn insert(&self, request: &PendingRequest) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        let mut requests = self.read().await?;
+        requests.insert(request.request_id, request.clone());
+        self.write(&requests)
+    }
+
+    async fn remove(&self, request_id: B256) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        let mut requests = self.read().await?;
+        if requests.remove(&request_id).is_some() {
+            self.write(&requests)?;
+        }
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<PendingRequest>> {
+        let _guard = self.lock.lock().await;
+        Ok(self.read().await?.into_values().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::network::test_utils::{MockNetworkServer, MockOutcome};
+    use crate::network::Error;
+    use crate::{NetworkProver, SP1Stdin};
+
+    const PRIVATE_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+    fn request(byte: u8) -> PendingRequest {
+        PendingRequest {
+            request_id: B256::repeat_byte(byte),
+            vk_hash: B256::repeat_byte(0xff),
+            mode: ProofMode::Groth16,
+            deadline: 1_700_000_000,
+            labels: BTreeMap::from([("service".to_string(), "bridge".to_string())]),
+        }
+    }
+
+    async fn check_store(store: &dyn RequestStore) {
+        assert!(store.list().await.unwrap().is_empty());
+        store.insert(&request(1)).await.unwrap();
+        store.insert(&request(2)).await.unwrap();
+        store.insert(&request(1)).await.unwrap();
+        assert_eq!(store.list().await.unwrap(), vec![request(1), request(2)]);
+
+        store.remove(B256::repeat_byte(1)).await.unwrap();
+        store.remove(B256::repeat_byte(3)).await.unwrap();
+        assert_eq!(store.list().await.unwrap(), vec![request(2)]);
+    }
+
+    #[tokio::test]
+    async fn test_memory_store() {
+        check_store(&MemoryRequestStore::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_json_file_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("requests.json");
+        check_store(&JsonFileRequestStore::new(&path)).await;
+
+        // The requests survive a restart.
+        let reopened = JsonFileRequestStore::new(&path);
+        assert_eq!(reopened.list().await.unwrap(), vec![request(2)]);
+    }
+
+    #[tokio::test]
+    async fn test_recover_pending() {
+        let server = MockNetworkServer::start().await.unwrap();
+        let vk_hash = B256::repeat_byte(1);
+        server.insert_program(vk_hash);
+        server.push_outcome(MockOutcome::Fulfilled { delay: Duration::from_millis(200) });
+        server.push_outcome(MockOutcome::Unfulfillable {
+            delay: Duration::ZERO,
+            reason: "deadline exceeded".to_string(),
+        });
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("requests.json");
+
+        // Submit two requests, then lose track of them as if the process crashed.
+        let prover = NetworkProver::new(PRIVATE_KEY, server.rpc_url())
+            .with_request_store(JsonFileRequestStore::new(&path));
+        let mut request_ids = Vec::new();
+        for _ in 0..2 {
+            let handle =
+                prover.prove_with_vk_hash(vk_hash, SP1Stdin::new()).request_handle_async().await;
+            request_ids.push(handle.unwrap().request_id());
+        }
+        drop(prover);
+
+        let store = JsonFileRequestStore::new(&path);
+        let pending = store.list().await.unwrap();
+        assert_eq!(pending.len(), 2);
+        assert!(pending.iter().all(|request| request.vk_hash == vk_hash));
+        assert!(pending.iter().all(|request| request.mode == ProofMode::Core));
+
+        // After the restart, the fulfilled request resolves to its proof and the failed one to an
+        // error, and both are removed from the store.
+        let prover = NetworkProver::new(PRIVATE_KEY, server.rpc_url()).with_request_store(store);
+        let handles = prover.recover_pending().await.unwrap();
+        assert_eq!(handles.len(), 2);
+        for handle in handles {
+            let result = handle.wait().await;
+            if handle.request_id() == request_ids[0] {
+                assert!(result.is_ok());
+            } else {
+                assert!(matches!(result, Err(Error::RequestUnfulfillable { .. })));
+            }
+        }
+        assert!(prover.recover_pending().await.unwrap().is_empty());
+    }
+}
@@ -1,11 +1,28 @@
 use anyhow::Result;
-use backoff::{future::retry, Error as BackoffError, ExponentialBackoff};
-use std::time::Duration;
+use backoff::{
+    exponential::ExponentialBackoff, future::retry, Clock, Error as BackoffError, SystemClock,
+};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use thiserror::Error as ThisError;
 use tonic::Code;
+use tracing::Instrument;
+
+use super::{grpc, telemetry, Error};
 
 /// Default timeout for retry operations.
 pub const DEFAULT_RETRY_TIMEOUT: Duration = Duration::from_secs(120);
 
+/// The metadata key, or HTTP header, of the hint for how long to wait before calling again, in
+/// seconds.
+pub(crate) const RETRY_AFTER_METADATA_KEY: &str = "retry-after";
+
+/// How much longer than the initial backoff of the policy to wait after being rate limited
+/// without a hint, before doubling the wait for each consecutive rate limit.
+const RATE_LIMIT_BACKOFF_FACTOR: u32 = 4;
+
 /// Trait for implementing retryable RPC operations.
 #[async_trait::async_trait]
 pub trait RetryableRpc {
@@ -29,82 +46,884 @@ pub trait RetryableRpc {
         T: Send;
 }
 
-/// Execute an async operation with exponential backoff retries.
+/// An HTTP request that failed with a non-success status.
+#[derive(ThisError, Debug)]
+#[error("HTTP {status}")]
+pub(crate) struct HttpStatusError {
+    pub(crate) status: reqwest::StatusCode,
+    /// How long the server asked to wait before trying again, if it did.
+    pub(crate) retry_after: Option<Duration>,
+}
+
+impl HttpStatusError {
+    /// The error of a response with a non-success status.
+    pub(crate) fn from_response(response: &reqwest::Response) -> Self {
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_retry_after);
+        Self { status: response.status(), retry_after }
+    }
+}
+
+/// Parses the hint for how long to wait before calling again from the metadata of a response.
+pub(crate) fn retry_after_hint(metadata: &tonic::metadata::MetadataMap) -> Option<Duration> {
+    parse_retry_after(metadata.get(RETRY_AFTER_METADATA_KEY)?.to_str().ok()?)
+}
+
+/// Parses a retry-after hint in seconds.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    value.trim().parse().ok().map(Duration::from_secs)
+}
+
+/// A rate limit reported by the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RateLimit {
+    /// How long the server asked to wait before calling again, if it did.
+    retry_after: Option<Duration>,
+}
+
+/// Returns the rate limit the error reports, if any.
+///
+/// # Details
+/// Rate limits are reported with the gRPC code `ResourceExhausted`, unless the balance of the
+/// account is exhausted, or with HTTP 429 for artifact transfers.
+fn rate_limit(e: &anyhow::Error) -> Option<RateLimit> {
+    if let Some(status) = e.downcast_ref::<tonic::Status>() {
+        let is_rate_limit = status.code() == Code::ResourceExhausted
+            && !status.message().to_lowercase().contains("balance");
+        return is_rate_limit
+            .then(|| RateLimit { retry_after: retry_after_hint(status.metadata()) });
+    }
+    let http = e.downcast_ref::<HttpStatusError>()?;
+    (http.status == reqwest::StatusCode::TOO_MANY_REQUESTS)
+        .then_some(RateLimit { retry_after: http.retry_after })
+}
+
+/// Decides which errors are transient and should be retried.
+///
+/// # Details
+/// By default, the gRPC codes `Unavailable`, `DeadlineExceeded`, `Internal` and `Aborted` are
+/// retried, along with transport errors (e.g. dropped connections or timeouts), corrupted
+/// downloads and rate limits. No other HTTP statuses are retried by default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryClassifier {
+    codes: Vec<Code>,
+    http_statuses: Vec<u16>,
+    transport_errors: bool,
+    rate_limits: bool,
+}
+
+impl Default for RetryClassifier {
+    fn default() -> Self {
+        Self {
+            codes: vec![Code::Unavailable, Code::DeadlineExceeded, Code::Internal, Code::Aborted],
+            http_statuses: Vec::new(),
+            transport_errors: true,
+            rate_limits: true,
+        }
+    }
+}
+
+impl RetryClassifier {
+    /// Creates a [`RetryClassifier`] that does not retry any error.
+    #[must_use]
+    pub fn none() -> Self {
+        Self {
+            codes: Vec::new(),
+            http_statuses: Vec::new(),
+            transport_errors: false,
+            rate_limits: false,
+        }
+    }
+
+    /// Sets the gRPC codes that are retried.
+    #[must_use]
+    pub fn codes(mut self, codes: impl IntoIterator<Item = Code>) -> Self {
+        self.codes = codes.into_iter().collect();
+        self
+    }
+
+    /// Sets the HTTP statuses of artifact transfers that are retried.
+    #[must_use]
+    pub fn http_statuses(mut self, statuses: impl IntoIterator<Item = u16>) -> Self {
+        self.http_statuses = statuses.into_iter().collect();
+        self
+    }
+
+    /// Sets whether transport errors, client-side timeouts and corrupted downloads are retried.
+    #[must_use]
+    pub fn transport_errors(mut self, transport_errors: bool) -> Self {
+        self.transport_errors = transport_errors;
+        self
+    }
+
+    /// Sets whether rate limits, i.e. the gRPC code `ResourceExhausted` and HTTP 429, are
+    /// retried.
+    ///
+    /// # Details
+    /// Rate limited calls are retried after the delay the server asks for, or on a slower
+    /// schedule than other errors if it does not, see [`RetryPolicy`].
+    #[must_use]
+    pub fn rate_limits(mut self, rate_limits: bool) -> Self {
+        self.rate_limits = rate_limits;
+        self
+    }
+
+    /// Whether the given gRPC code is retried.
+    #[must_use]
+    pub fn retries_code(&self, code: Code) -> bool {
+        self.codes.contains(&code)
+    }
+
+    /// Whether the given HTTP status is retried.
+    #[must_use]
+    pub fn retries_http_status(&self, status: u16) -> bool {
+        self.http_statuses.contains(&status)
+    }
+
+    /// Returns whether the error is transient, logging the decision.
+    fn is_transient(&self, e: &anyhow::Error, operation_name: &str) -> bool {
+        if self.rate_limits && rate_limit(e).is_some() {
+            log::warn!("Rate limited when {}, backing off...", operation_name);
+            return true;
+        }
+
+        // Check for tonic status errors.
+        if let Some(status) = e.downcast_ref::<tonic::Status>() {
+            return match status.code() {
+                code if self.retries_code(code) => {
+                    log::warn!(
+                        "Network temporarily unavailable when {} due to {}, retrying...",
+                        operation_name,
+                        status.message(),
+                    );
+                    true
+                }
+                // Calls cancelled by the client-side per-call timeout.
+                Code::Cancelled
+                    if self.transport_errors
+                        && status.message().to_lowercase().contains("timeout") =>
+                {
+                    log::warn!(
+                        "Timed out when {} due to {}, retrying...",
+                        operation_name,
+                        status.message(),
+                    );
+                    true
+                }
+                Code::NotFound => {
+                    log::error!("{} not found due to {}", operation_name, status.message());
+                    false
+                }
+                _ => {
+                    log::error!(
+                        "Permanent error encountered when {}: {} ({})",
+                        operation_name,
+                        status.message(),
+                        status.code()
+                    );
+                    false
+                }
+            };
+        }
+
+        if let Some(http) = e.downcast_ref::<HttpStatusError>() {
+            if self.retries_http_status(http.status.as_u16()) {
+                log::warn!("HTTP {} when {}, retrying...", http.status, operation_name);
+                return true;
+            }
+        }
+
+        if let Some(Error::CircuitOpen { .. }) = e.downcast_ref::<Error>() {
+            // The circuit breaker already decided when the endpoint may be called again.
+            log::error!("Not retrying {}: {}", operation_name, e);
+            return false;
+        }
+
+        if let Some(Error::ChecksumMismatch { .. }) = e.downcast_ref::<Error>() {
+            if self.transport_errors {
+                // A corrupted or truncated download is likely to succeed when retried.
+                log::warn!("Checksum mismatch when {}: {}, retrying...", operation_name, e);
+                return true;
+            }
+        }
+
+        // Check for common transport errors. HTTP clients report the cause of a failed request,
+        // e.g. a reset connection, as the source of the error, so the whole chain is checked.
+        let error_msg = format!("{e:#}").to_lowercase();
+        let is_transient = self.transport_errors
+            && (error_msg.contains("tls handshake")
+                || error_msg.contains("dns error")
+                || error_msg.contains("connection reset")
+                || error_msg.contains("connection closed")
+                || error_msg.contains("broken pipe")
+                || error_msg.contains("transport error")
+                || error_msg.contains("failed to lookup")
+                || error_msg.contains("timeout")
+                || error_msg.contains("deadline exceeded"));
+
+        if is_transient {
+            log::warn!(
+                "Transient transport error when {}: {}, retrying...",
+                operation_name,
+                error_msg
+            );
+        } else {
+            log::error!("Permanent error when {}: {}", operation_name, error_msg);
+        }
+        is_transient
+    }
+}
+
+/// Returns the class of an error, used to label failed attempts in traces and metrics.
+fn error_class(e: &anyhow::Error) -> &'static str {
+    if e.downcast_ref::<tonic::Status>().is_some() {
+        "grpc_status"
+    } else if e.downcast_ref::<HttpStatusError>().is_some() {
+        "http_status"
+    } else if let Some(Error::ChecksumMismatch { .. }) = e.downcast_ref::<Error>() {
+        "checksum_mismatch"
+    } else if let Some(Error::CircuitOpen { .. }) = e.downcast_ref::<Error>() {
+        "circuit_open"
+    } else {
+        "transport"
+    }
+}
+
+/// How failed network operations are retried.
+///
+/// # Details
+/// Transient errors, as decided by [`RetryPolicy::retry_on`], are retried with exponential
+/// backoff until `max_elapsed` has passed or `max_attempts` attempts were made. The default
+/// policy retries for two minutes, starting with a one second backoff.
+///
+/// Rate limited calls are retried after the delay the server asks for. Without a hint, the
+/// first retry waits four times the initial backoff, doubling with each consecutive rate limit
+/// up to the maximum backoff, so that a throttled client does not make things worse.
+///
+/// Calls to an RPC endpoint that keeps failing at the transport level are short-circuited for a
+/// while, see [`CircuitBreakerPolicy`].
+///
+/// # Example
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use sp1_sdk::network::{NetworkClient, RetryPolicy};
+///
+/// // Fail fast in CI.
+/// let policy = RetryPolicy {
+///     max_elapsed: Duration::from_secs(10),
+///     max_attempts: Some(2),
+///     ..Default::default()
+/// };
+/// let client = NetworkClient::new("...", "...").unwrap().with_retry_policy(policy);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// The maximum time spent retrying an operation.
+    pub max_elapsed: Duration,
+    /// The maximum number of attempts, including the first one. Unlimited if `None`.
+    pub max_attempts: Option<u32>,
+    /// The backoff before the first retry.
+    pub initial_backoff: Duration,
+    /// The maximum backoff between two retries.
+    pub max_backoff: Duration,
+    /// The factor the backoff is multiplied by after each retry.
+    pub multiplier: f64,
+    /// The randomization factor of the backoff, e.g. `0.5` waits between 50% and 150% of the
+    /// backoff, so that clients failing at the same time do not retry in lockstep. Backoffs asked
+    /// for by a rate limiting server are not randomized.
+    pub jitter: f64,
+    /// Which errors are retried.
+    pub retry_on: RetryClassifier,
+    /// When calls to a failing RPC endpoint are short-circuited. Disabled if `None`.
+    pub circuit_breaker: Option<CircuitBreakerPolicy>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_elapsed: DEFAULT_RETRY_TIMEOUT,
+            max_attempts: None,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(120),
+            multiplier: backoff::default::MULTIPLIER,
+            jitter: backoff::default::RANDOMIZATION_FACTOR,
+            retry_on: RetryClassifier::default(),
+            circuit_breaker: Some(CircuitBreakerPolicy::default()),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The backoff after the given number of consecutive rate limits without a hint from the
+    /// server, starting at zero.
+    fn rate_limit_backoff(&self, consecutive: u32) -> Duration {
+        let backoff = self.initial_backoff.saturating_mul(RATE_LIMIT_BACKOFF_FACTOR);
+        backoff.saturating_mul(2u32.saturating_pow(consecutive)).min(self.max_backoff)
+    }
+
+    /// Builds the backoff schedule of the policy, measuring elapsed time with the given clock.
+    pub(crate) fn backoff<C: Clock>(&self, clock: C) -> ExponentialBackoff<C> {
+        ExponentialBackoff {
+            current_interval: self.initial_backoff,
+            initial_interval: self.initial_backoff,
+            randomization_factor: self.jitter,
+            multiplier: self.multiplier,
+            max_interval: self.max_backoff,
+            start_time: Instant::now(),
+            max_elapsed_time: Some(self.max_elapsed),
+            clock,
+        }
+    }
+}
+
+/// When calls to a failing RPC endpoint are short-circuited.
+///
+/// # Details
+/// After `failure_threshold` consecutive transport failures to an endpoint, e.g. refused
+/// connections or client-side timeouts, calls to it fail with [`Error::CircuitOpen`] instead of
+/// being sent, until `cool_down` has passed. A single probe call is then let through: if it
+/// reaches the endpoint the calls resume, and otherwise they are short-circuited for another
+/// cool-down. Application errors, e.g. `NotFound` or `InvalidArgument`, show that the endpoint is
+/// reachable and never count as failures.
+///
+/// By default, calls are short-circuited for 30 seconds after 5 consecutive failures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerPolicy {
+    /// The number of consecutive transport failures after which calls are short-circuited.
+    pub failure_threshold: u32,
+    /// How long calls are short-circuited before a probe call is let through.
+    pub cool_down: Duration,
+}
+
+impl Default for CircuitBreakerPolicy {
+    fn default() -> Self {
+        Self { failure_threshold: 5, cool_down: Duration::from_secs(30) }
+    }
+}
+
+/// The circuits of the RPC endpoints of a client, shared by its clones.
+#[derive(Debug, Default)]
+pub(crate) struct CircuitBreaker {
+    circuits: Mutex<HashMap<usize, Circuit>>,
+}
+
+/// The circuit of a single endpoint.
+#[derive(Debug, Default)]
+struct Circuit {
+    /// The number of consecutive transport failures.
+    consecutive_failures: u32,
+    /// Until when calls are short-circuited, if the circuit is open.
+    open_until: Option<Instant>,
+    /// When the probe call in flight was let through, if any.
+    probe: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    /// Calls the endpoint at the given index, unless its circuit is open.
+    pub(crate) async fn call<T>(
+        &self,
+        endpoint: usize,
+        url: &str,
+        policy: Option<&CircuitBreakerPolicy>,
+        operation: impl std::future::Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let Some(policy) = policy else {
+            return operation.await;
+        };
+        self.acquire(endpoint, url, policy)?;
+        let result = operation.await;
+        self.record(endpoint, url, policy, result.as_ref().err().is_some_and(is_endpoint_failure));
+        result
+    }
+
+    /// Checks that the endpoint may be called, letting a probe call through once the cool-down
+    /// has passed.
+    fn acquire(&self, endpoint: usize, url: &str, policy: &CircuitBreakerPolicy) -> Result<()> {
+        let mut circuits = self.circuits.lock().unwrap();
+        let circuit = circuits.entry(endpoint).or_default();
+        let Some(open_until) = circuit.open_until else {
+            return Ok(());
+        };
+
+        // A probe that never completed, e.g. because the call was dropped, does not keep the
+        // circuit open forever.
+        let now = Instant::now();
+        let retry_at = circuit.probe.map_or(open_until, |probe| probe + policy.cool_down);
+        if now >= retry_at {
+            log::info!("Probing RPC endpoint {} after repeated failures", url);
+            circuit.probe = Some(now);
+            return Ok(());
+        }
+        let retry_in = retry_at - now;
+        Err(Error::CircuitOpen { endpoint: url.to_string(), retry_in }.into())
+    }
+
+    /// Records the outcome of a call to the endpoint, opening its circuit after too many
+    /// consecutive failures or a failed probe.
+    fn record(&self, endpoint: usize, url: &str, policy: &CircuitBreakerPolicy, failed: bool) {
+        let mut circuits = self.circuits.lock().unwrap();
+        let circuit = circuits.entry(endpoint).or_default();
+        if !failed {
+            if circuit.open_until.is_some() {
+                log::info!("RPC endpoint {} is reachable again", url);
+            }
+            *circuit = Circuit::default();
+            return;
+        }
+
+        circuit.consecutive_failures = circuit.consecutive_failures.saturating_add(1);
+        if circuit.probe.is_some() || circuit.consecutive_failures >= policy.failure_threshold {
+            log::warn!(
+                "RPC endpoint {} failed {} times in a row, short-circuiting calls for {:?}",
+                url,
+                circuit.consecutive_failures,
+                policy.cool_down
+            );
+            circuit.open_until = Some(Instant::now() + policy.cool_down);
+            circuit.probe = None;
+        }
+    }
+}
+
+/// Whether the error shows that the endpoint could not be reached, as opposed to an error
+/// returned by the endpoint.
+fn is_endpoint_failure(e: &anyhow::Error) -> bool {
+    let timed_out = e.downcast_ref::<tonic::Status>().is_some_and(|status| {
+        status.code() == Code::Cancelled && status.message().to_lowercase().contains("timeout")
+    });
+    timed_out || grpc::is_connection_error(e)
+}
+
+/// Execute an async operation with exponential backoff retries, according to the policy.
 pub async fn retry_operation<T, F, Fut>(
     operation: F,
-    timeout: Option<Duration>,
+    policy: &RetryPolicy,
     operation_name: &str,
 ) -> Result<T>
 where
     F: Fn() -> Fut + Send + Sync,
     Fut: std::future::Future<Output = Result<T>> + Send,
 {
-    let backoff = ExponentialBackoff {
-        initial_interval: Duration::from_secs(1),
-        max_interval: Duration::from_secs(120),
-        max_elapsed_time: timeout,
-        ..Default::default()
-    };
-
-    retry(backoff, || async {
-        match operation().await {
-            Ok(result) => Ok(result),
-            Err(e) => {
-                // Check for tonic status errors.
-                if let Some(status) = e.downcast_ref::<tonic::Status>() {
-                    match status.code() {
-                        Code::Unavailable | Code::DeadlineExceeded | Code::Internal | Code::Aborted => {
-                            log::warn!(
-                                "Network temporarily unavailable when {} due to {}, retrying...",
-                                operation_name,
-                                status.message(),
-                            );
-                            Err(BackoffError::transient(e))
-                        }
-                        Code::NotFound => {
-                            log::error!(
-                                "{} not found due to {}",
-                                operation_name,
-                                status.message(),
-                            );
-                            Err(BackoffError::permanent(e))
-                        }
-                        _ => {
-                            log::error!(
-                                "Permanent error encountered when {}: {} ({})",
-                                operation_name,
-                                status.message(),
-                                status.code()
-                            );
-                            Err(BackoffError::permanent(e))
-                        }
-                    }
-                } else {
-                    // Check for common transport errors.
-                    let error_msg = e.to_string().to_lowercase();
-                    let is_transient = error_msg.contains("tls handshake") ||
-                        error_msg.contains("dns error") ||
-                        error_msg.contains("connection reset") ||
-                        error_msg.contains("broken pipe") ||
-                        error_msg.contains("transport error") ||
-                        error_msg.contains("failed to lookup") ||
-                        error_msg.contains("timeout") ||
-                        error_msg.contains("deadline exceeded");
-
-                    if is_transient {
-                        log::warn!(
-                            "Transient transport error when {}: {}, retrying...",
-                            operation_name,
-                            error_msg
-                        );
-                        Err(BackoffError::transient(e))
-                    } else {
-                        log::error!("Permanent error when {}: {}", operation_name, error_msg);
-                        Err(BackoffError::permanent(e))
-                    }
-                }
-            }
+    let attempts = AtomicU32::new(0);
+    let rate_limits = AtomicU32::new(0);
+    let start = Instant::now();
+    retry(policy.backoff(SystemClock {}), || async {
+        let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+        let span = tracing::debug_span!("sp1.network.attempt", operation = operation_name, attempt);
+        let e = match operation().instrument(span).await {
+            Ok(result) => return Ok(result),
+            Err(e) => e,
+        };
+
+        let error_class = error_class(&e);
+        tracing::debug!(operation = operation_name, attempt, error_class, "attempt failed: {e}");
+        if !policy.retry_on.is_transient(&e, operation_name) {
+            return Err(BackoffError::permanent(e));
+        }
+        if policy.max_attempts.is_some_and(|max| attempt >= max) {
+            log::error!("Giving up on {} after {} attempts", operation_name, attempt);
+            return Err(BackoffError::permanent(e));
+        }
+        telemetry::record_retry(operation_name, error_class);
+
+        let Some(limit) = rate_limit(&e).filter(|_| policy.retry_on.rate_limits) else {
+            rate_limits.store(0, Ordering::SeqCst);
+            return Err(BackoffError::transient(e));
+        };
+        let consecutive = rate_limits.fetch_add(1, Ordering::SeqCst);
+        let delay = limit.retry_after.unwrap_or_else(|| policy.rate_limit_backoff(consecutive));
+        if start.elapsed() + delay > policy.max_elapsed {
+            log::error!("Giving up on {} after being rate limited", operation_name);
+            return Err(BackoffError::permanent(e));
         }
+        Err(BackoffError::retry_after(e, delay))
     })
     .await
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use backoff::backoff::Backoff;
+    use tonic::Status;
+
+    use super::*;
+    use crate::network::artifact_transport::download_response;
+
+    /// A clock that only advances when told to.
+    #[derive(Clone)]
+    struct MockClock(Arc<Mutex<Instant>>);
+
+    impl MockClock {
+        fn advance(&self, duration: Duration) {
+            *self.0.lock().unwrap() += duration;
+        }
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> Instant {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    #[test]
+    fn test_default_backoff_schedule() {
+        let clock = MockClock(Arc::new(Mutex::new(Instant::now())));
+        let mut backoff = RetryPolicy::default().backoff(clock.clone());
+        backoff.reset();
+
+        // The interval grows by 1.5x per retry, with 50% jitter.
+        let mut expected = 1.0;
+        for _ in 0..10 {
+            let interval = backoff.next_backoff().unwrap().as_secs_f64();
+            assert!(interval >= expected * 0.5 - 1e-6 && interval <= expected * 1.5 + 1e-6);
+            expected *= 1.5;
+            assert!((backoff.current_interval.as_secs_f64() - expected).abs() < 1e-6);
+        }
+
+        // Retries stop once two minutes have elapsed.
+        clock.advance(Duration::from_secs(121));
+        assert_eq!(backoff.next_backoff(), None);
+    }
+
+    #[test]
+    fn test_custom_backoff_schedule() {
+        let clock = MockClock(Arc::new(Mutex::new(Instant::now())));
+        let policy = RetryPolicy {
+            max_elapsed: Duration::from_secs(30 * 60),
+            initial_backoff: Duration::from_secs(5),
+            max_backoff: Duration::from_secs(60),
+            multiplier: 2.0,
+            ..Default::default()
+        };
+        let mut backoff = policy.backoff(clock.clone());
+        backoff.reset();
+
+        let intervals = (0..5)
+            .map(|_| {
+                backoff.next_backoff().unwrap();
+                backoff.current_interval.as_secs()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(intervals, [10, 20, 40, 60, 60]);
+
+        clock.advance(Duration::from_secs(28 * 60));
+        assert!(backoff.next_backoff().is_some());
+        clock.advance(Duration::from_secs(3 * 60));
+        assert_eq!(backoff.next_backoff(), None);
+    }
+
+    #[test]
+    fn test_jitter() {
+        let clock = MockClock(Arc::new(Mutex::new(Instant::now())));
+        let policy = RetryPolicy { jitter: 0.0, ..Default::default() };
+        let mut backoff = policy.backoff(clock);
+        backoff.reset();
+
+        let intervals = (0..3).map(|_| backoff.next_backoff().unwrap()).collect::<Vec<_>>();
+        assert_eq!(intervals, [1000, 1500, 2250].map(Duration::from_millis));
+    }
+
+    #[test]
+    fn test_classifier() {
+        let default = RetryClassifier::default();
+        let transient =
+            |classifier: &RetryClassifier, e: anyhow::Error| classifier.is_transient(&e, "testing");
+        assert!(transient(&default, Status::unavailable("").into()));
+        assert!(transient(&default, Status::cancelled("Timeout expired").into()));
+        assert!(!transient(&default, Status::not_found("").into()));
+        assert!(transient(&default, anyhow::anyhow!("connection reset by peer")));
+        let reset = std::io::Error::from(std::io::ErrorKind::ConnectionReset);
+        assert!(transient(&default, anyhow::Error::new(reset).context("error sending request")));
+        let http = || {
+            anyhow::Error::new(HttpStatusError {
+                status: reqwest::StatusCode::BAD_GATEWAY,
+                retry_after: None,
+            })
+        };
+        assert!(!transient(&default, http()));
+
+        let custom = RetryClassifier::none().codes([Code::Unavailable]).http_statuses([502]);
+        assert!(transient(&custom, Status::unavailable("").into()));
+        assert!(!transient(&custom, Status::internal("").into()));
+        assert!(!transient(&custom, anyhow::anyhow!("connection reset by peer")));
+        assert!(transient(&custom, http()));
+
+        let circuit_open =
+            Error::CircuitOpen { endpoint: "primary".to_string(), retry_in: Duration::ZERO };
+        assert!(!transient(&default, circuit_open.into()));
+    }
+
+    #[test]
+    fn test_error_class() {
+        assert_eq!(error_class(&Status::unavailable("").into()), "grpc_status");
+        let http = anyhow::Error::new(HttpStatusError {
+            status: reqwest::StatusCode::BAD_GATEWAY,
+            retry_after: None,
+        })
+        .context("Failed to download artifact");
+        assert_eq!(error_class(&http), "http_status");
+        let checksum = Error::ChecksumMismatch { expected: vec![0; 32], actual: vec![1; 32] };
+        assert_eq!(error_class(&checksum.into()), "checksum_mismatch");
+        assert_eq!(error_class(&anyhow::anyhow!("connection reset by peer")), "transport");
+    }
+
+    #[tokio::test]
+    async fn test_max_attempts() {
+        let policy = RetryPolicy {
+            max_attempts: Some(2),
+            initial_backoff: Duration::from_millis(1),
+            ..Default::default()
+        };
+        let calls = AtomicU32::new(0);
+        let result: Result<()> = retry_operation(
+            || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(Status::unavailable("down").into())
+            },
+            &policy,
+            "testing",
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    /// An endpoint that answers calls with a script of results, then succeeds.
+    #[derive(Default)]
+    struct ScriptedEndpoint {
+        script: Mutex<std::collections::VecDeque<std::result::Result<(), Status>>>,
+        calls: AtomicU32,
+    }
+
+    impl ScriptedEndpoint {
+        fn new(script: impl IntoIterator<Item = Status>) -> Self {
+            let script = script.into_iter().map(Err).collect();
+            Self { script: Mutex::new(script), calls: AtomicU32::new(0) }
+        }
+
+        async fn call(&self) -> Result<()> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let next = self.script.lock().unwrap().pop_front();
+            Ok(next.unwrap_or(Ok(()))?)
+        }
+
+        fn calls(&self) -> u32 {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    fn is_circuit_open(result: &Result<()>) -> bool {
+        matches!(
+            result.as_ref().unwrap_err().downcast_ref::<Error>(),
+            Some(Error::CircuitOpen { .. })
+        )
+    }
+
+    const BREAKER: CircuitBreakerPolicy =
+        CircuitBreakerPolicy { failure_threshold: 3, cool_down: Duration::from_millis(50) };
+
+    #[tokio::test]
+    async fn test_circuit_breaker_opens_and_probes() {
+        let breaker = CircuitBreaker::default();
+        let endpoint = ScriptedEndpoint::new((0..4).map(|_| Status::unavailable("down")));
+        let call = || breaker.call(0, "primary", Some(&BREAKER), endpoint.call());
+
+        // The circuit opens after three consecutive failures, and calls are no longer sent.
+        for _ in 0..3 {
+            assert!(!is_circuit_open(&call().await));
+        }
+        assert!(is_circuit_open(&call().await));
+        assert_eq!(endpoint.calls(), 3);
+
+        // Other endpoints are not affected.
+        let backup = ScriptedEndpoint::default();
+        breaker.call(1, "backup", Some(&BREAKER), backup.call()).await.unwrap();
+
+        // After the cool-down, a failed probe opens the circuit again right away.
+        tokio::time::sleep(BREAKER.cool_down).await;
+        assert!(!is_circuit_open(&call().await));
+        assert!(is_circuit_open(&call().await));
+        assert_eq!(endpoint.calls(), 4);
+
+        // A successful probe closes the circuit.
+        tokio::time::sleep(BREAKER.cool_down).await;
+        call().await.unwrap();
+        call().await.unwrap();
+        assert_eq!(endpoint.calls(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_ignores_application_errors() {
+        let breaker = CircuitBreaker::default();
+        let endpoint = ScriptedEndpoint::new([
+            Status::unavailable("down"),
+            Status::unavailable("down"),
+            Status::not_found("request not found"),
+            Status::unavailable("down"),
+            Status::unavailable("down"),
+            Status::invalid_argument("invalid nonce"),
+            Status::not_found("request not found"),
+            Status::invalid_argument("invalid nonce"),
+        ]);
+        for _ in 0..8 {
+            let result = breaker.call(0, "primary", Some(&BREAKER), endpoint.call()).await;
+            assert!(!is_circuit_open(&result));
+        }
+        assert_eq!(endpoint.calls(), 8);
+
+        // Client-side timeouts count as failures.
+        let endpoint = ScriptedEndpoint::new((0..3).map(|_| Status::cancelled("Timeout expired")));
+        for _ in 0..3 {
+            assert!(breaker.call(0, "primary", Some(&BREAKER), endpoint.call()).await.is_err());
+        }
+        let result = breaker.call(0, "primary", Some(&BREAKER), endpoint.call()).await;
+        assert!(is_circuit_open(&result));
+
+        // The breaker can be disabled.
+        let disabled = CircuitBreaker::default();
+        let endpoint = ScriptedEndpoint::new((0..10).map(|_| Status::unavailable("down")));
+        for _ in 0..10 {
+            let result = disabled.call(0, "primary", None, endpoint.call()).await;
+            assert!(!is_circuit_open(&result));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_stops_at_open_circuit() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+            circuit_breaker: Some(BREAKER),
+            ..Default::default()
+        };
+        let breaker = CircuitBreaker::default();
+        let endpoint = ScriptedEndpoint::new((0..10).map(|_| Status::unavailable("down")));
+        let result = retry_operation(
+            || breaker.call(0, "primary", policy.circuit_breaker.as_ref(), endpoint.call()),
+            &policy,
+            "testing",
+        )
+        .await;
+        assert!(is_circuit_open(&result));
+        assert_eq!(endpoint.calls(), 3);
+    }
+
+    #[test]
+    fn test_retry_after_hint() {
+        let mut metadata = tonic::metadata::MetadataMap::new();
+        assert_eq!(retry_after_hint(&metadata), None);
+        metadata.insert(RETRY_AFTER_METADATA_KEY, "15".parse().unwrap());
+        assert_eq!(retry_after_hint(&metadata), Some(Duration::from_secs(15)));
+        metadata.insert(RETRY_AFTER_METADATA_KEY, "soon".parse().unwrap());
+        assert_eq!(retry_after_hint(&metadata), None);
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let mut status = Status::resource_exhausted("too many requests");
+        assert_eq!(rate_limit(&status.clone().into()), Some(RateLimit { retry_after: None }));
+        status.metadata_mut().insert(RETRY_AFTER_METADATA_KEY, "7".parse().unwrap());
+        let retry_after = Some(Duration::from_secs(7));
+        assert_eq!(rate_limit(&status.into()), Some(RateLimit { retry_after }));
+
+        let balance = Status::resource_exhausted("insufficient balance");
+        assert_eq!(rate_limit(&balance.into()), None);
+        assert_eq!(rate_limit(&Status::unavailable("").into()), None);
+        let http = HttpStatusError { status: reqwest::StatusCode::TOO_MANY_REQUESTS, retry_after };
+        assert_eq!(rate_limit(&anyhow::Error::new(http)), Some(RateLimit { retry_after }));
+    }
+
+    #[test]
+    fn test_rate_limit_backoff() {
+        let policy = RetryPolicy { max_backoff: Duration::from_secs(30), ..Default::default() };
+        let backoffs = (0..5).map(|n| policy.rate_limit_backoff(n).as_secs()).collect::<Vec<_>>();
+        assert_eq!(backoffs, [4, 8, 16, 30, 30]);
+    }
+
+    /// Serves HTTP requests on a local port, answering the first one with 429 and a
+    /// `Retry-After` header if given, and the next ones with 200.
+    async fn spawn_rate_limited_server(retry_after: Option<&'static str>) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/artifact", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            for i in 0.. {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = [0; 1024];
+                let _ = stream.read(&mut request).await.unwrap();
+                let response = match (i, retry_after) {
+                    (0, Some(secs)) => format!(
+                        "HTTP/1.1 429 Too Many Requests\r\nRetry-After: {secs}\r\n\
+                         Content-Length: 0\r\nConnection: close\r\n\r\n"
+                    ),
+                    (0, None) => "HTTP/1.1 429 Too Many Requests\r\nContent-Length: 0\r\n\
+                                  Connection: close\r\n\r\n"
+                        .to_string(),
+                    _ => "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok"
+                        .to_string(),
+                };
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        url
+    }
+
+    /// Downloads from the given URL with retries, returning how long it took.
+    async fn timed_download(url: &str, policy: &RetryPolicy) -> Duration {
+        let http = reqwest::Client::new();
+        let start = Instant::now();
+        let artifact = retry_operation(
+            || async {
+                let response = http.get(url).send().await?;
+                download_response(response).await
+            },
+            policy,
+            "downloading artifact",
+        )
+        .await
+        .unwrap();
+        assert_eq!(artifact.bytes, b"ok");
+        start.elapsed()
+    }
+
+    #[tokio::test]
+    async fn test_http_429_with_retry_after() {
+        let policy =
+            RetryPolicy { initial_backoff: Duration::from_millis(10), ..Default::default() };
+        let url = spawn_rate_limited_server(Some("1")).await;
+        assert!(timed_download(&url, &policy).await >= Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_http_429_without_retry_after() {
+        let policy =
+            RetryPolicy { initial_backoff: Duration::from_millis(10), ..Default::default() };
+        let url = spawn_rate_limited_server(None).await;
+        let elapsed = timed_download(&url, &policy).await;
+        // The rate limit backoff is four times the initial backoff, without jitter.
+        assert!(elapsed >= Duration::from_millis(40), "{elapsed:?}");
+        assert!(elapsed < Duration::from_secs(1), "{elapsed:?}");
+
+        // Rate limits are not retried if disabled.
+        let policy =
+            RetryPolicy { retry_on: RetryClassifier::default().rate_limits(false), ..policy };
+        let url = spawn_rate_limited_server(None).await;
+        let http = reqwest::Client::new();
+        let result = retry_operation(
+            || async {
+                let response = http.get(&url).send().await?;
+                download_response(response).await
+            },
+            &policy,
+            "downloading artifact",
+        )
+        .await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,173 @@
+//! # Proof Request Status
+//!
+//! This module provides a typed view of the status of a proof request on the network.
+
+use alloy_primitives::{Address, B256};
+
+use super::proto::network::{ExecutionStatus, FulfillmentStatus, GetProofRequestStatusResponse};
+use super::Error;
+
+/// The status of a proof request on the network.
+///
+/// # Details
+/// Parsed from the [`GetProofRequestStatusResponse`] returned by
+/// [`crate::network::NetworkClient::get_proof_request_status`] with
+/// [`ProofRequestStatus::try_from`]. Fields that the network only reports in some states, or
+/// that older versions of the network do not report, are `None` when missing.
+///
+/// A request fails if it becomes unexecutable, e.g. because it exceeded its cycle limit, or
+/// unfulfillable, e.g. because no prover fulfilled it before its deadline. In both cases the
+/// network reports the cause in [`ProofRequestStatus::failure_cause`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofRequestStatus {
+    /// The fulfillment status of the request.
+    pub fulfillment: FulfillmentStatus,
+    /// The execution status of the request.
+    pub execution: ExecutionStatus,
+    /// Why the request failed, if it is unexecutable or unfulfillable.
+    pub failure_cause: Option<String>,
+    /// The fulfiller the request is assigned to, once it is assigned.
+    pub fulfiller: Option<Address>,
+    /// The size of the proof in bytes, once the request is fulfilled.
+    pub proof_size: Option<u64>,
+    /// The URI of the proof, once the request is fulfilled.
+    pub proof_uri: Option<String>,
+    /// The hash of the public values, once the request is executed.
+    pub public_values_hash: Option<B256>,
+    /// The deadline of the request, as a unix timestamp.
+    pub deadline: u64,
+    /// When the request was created, as a unix timestamp.
+    pub created_at: Option<u64>,
+    /// When the request was last updated, as a unix timestamp.
+    pub updated_at: Option<u64>,
+    /// When the request was fulfilled, as a unix timestamp.
+    pub fulfilled_at: Option<u64>,
+}
+
+impl ProofRequestStatus {
+    /// Whether the request failed, i.e. it is unexecutable or unfulfillable.
+    #[must_use]
+    pub fn is_failed(&self) -> bool {
+        self.execution == ExecutionStatus::Unexecutable
+            || self.fulfillment == FulfillmentStatus::Unfulfillable
+    }
+
+    /// Whether the request is fulfilled, so its proof can be downloaded.
+    #[must_use]
+    pub fn is_fulfilled(&self) -> bool {
+        self.fulfillment == FulfillmentStatus::Fulfilled
+    }
+}
+
+impl TryFrom<&GetProofRequestStatusResponse> for ProofRequestStatus {
+    type Error = Error;
+
+    fn try_from(res: &GetProofRequestStatusResponse) -> Result<Self, Error> {
+        let fulfillment =
+            FulfillmentStatus::try_from(res.fulfillment_status).map_err(anyhow::Error::from)?;
+        let execution =
+            ExecutionStatus::try_from(res.execution_status).map_err(anyhow::Error::from)?;
+        Ok(Self {
+            fulfillment,
+            execution,
+            failure_cause: res.failure_reason.clone(),
+            fulfiller: res.fulfiller.as_deref().map(Address::from_slice),
+            proof_size: res.proof_size,
+            proof_uri: res.proof_uri.clone(),
+            public_values_hash: res.public_values_hash.as_deref().map(B256::from_slice),
+            deadline: res.deadline,
+            created_at: res.created_at,
+            updated_at: res.updated_at,
+            fulfilled_at: res.fulfilled_at,
+        })
+    }
+}
+
+impl TryFrom<GetProofRequestStatusResponse> for ProofRequestStatus {
+    type Error = Error;
+
+    fn try_from(res: GetProofRequestStatusResponse) -> Result<Self, Error> {
+        Self::try_from(&res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::client::ensure_request_not_failed;
+
+    fn response(
+        fulfillment: FulfillmentStatus,
+        execution: ExecutionStatus,
+    ) -> GetProofRequestStatusResponse {
+        GetProofRequestStatusResponse {
+            fulfillment_status: fulfillment.into(),
+            execution_status: execution.into(),
+            failure_reason: Some("cycle limit exceeded".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_status_combinations() {
+        use ExecutionStatus::{Executed, Unexecutable, Unexecuted};
+        use FulfillmentStatus::{Assigned, Fulfilled, Requested, Unfulfillable};
+
+        // The status combinations, and the error waiting for the request should stop with.
+        let cases = [
+            (Requested, Unexecuted, None),
+            (Assigned, Unexecuted, None),
+            (Assigned, Executed, None),
+            (Fulfilled, Executed, None),
+            (Requested, Unexecutable, Some("RequestUnexecutable")),
+            (Assigned, Unexecutable, Some("RequestUnexecutable")),
+            (Unfulfillable, Unexecutable, Some("RequestUnexecutable")),
+            (Unfulfillable, Unexecuted, Some("RequestUnfulfillable")),
+            (Unfulfillable, Executed, Some("RequestUnfulfillable")),
+        ];
+        for (fulfillment, execution, expected) in cases {
+            let res = response(fulfillment, execution);
+            let status = ProofRequestStatus::try_from(&res).unwrap();
+            assert_eq!(status.fulfillment, fulfillment);
+            assert_eq!(status.execution, execution);
+            assert_eq!(status.is_failed(), expected.is_some(), "{fulfillment:?} {execution:?}");
+            assert_eq!(status.is_fulfilled(), fulfillment == Fulfilled);
+
+            let error = match ensure_request_not_failed(B256::ZERO, &res) {
+                Ok(()) => None,
+                Err(Error::RequestUnexecutable { reason, .. }) => {
+                    assert_eq!(reason.as_deref(), Some("cycle limit exceeded"));
+                    Some("RequestUnexecutable")
+                }
+                Err(Error::RequestUnfulfillable { reason, .. }) => {
+                    assert_eq!(reason.as_deref(), Some("cycle limit exceeded"));
+                    Some("RequestUnfulfillable")
+                }
+                Err(e) => panic!("unexpected error for {fulfillment:?} {execution:?}: {e}"),
+            };
+            assert_eq!(error, expected, "{fulfillment:?} {execution:?}");
+        }
+    }
+
+    #[test]
+    fn test_optional_fields() {
+        let fulfiller = Address::repeat_byte(0xab);
+        let res = GetProofRequestStatusResponse {
+            fulfiller: Some(fulfiller.to_vec()),
+            proof_size: Some(1024),
+            fulfilled_at: Some(1_700_000_000),
+            ..response(FulfillmentStatus::Fulfilled, ExecutionStatus::Executed)
+        };
+        let status = ProofRequestStatus::try_from(res).unwrap();
+        assert_eq!(status.fulfiller, Some(fulfiller));
+        assert_eq!(status.proof_size, Some(1024));
+        assert_eq!(status.fulfilled_at, Some(1_700_000_000));
+        assert_eq!(status.created_at, None);
+    }
+
+    #[test]
+    fn test_unknown_status() {
+        let res = GetProofRequestStatusResponse { fulfillment_status: 42, ..Default::default() };
+        assert!(ProofRequestStatus::try_from(&res).is_err());
+    }
+}
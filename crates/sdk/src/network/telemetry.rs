@@ -0,0 +1,65 @@
+//! # Network Telemetry
+//!
+//! This module records metrics of network operations with the `metrics` crate, when the `metrics`
+//! feature is enabled. Without the feature, recording compiles to nothing.
+
+/// The number of retried attempts, labeled by operation and error class.
+#[cfg(feature = "metrics")]
+const RETRIES_TOTAL: &str = "sp1_network_retries_total";
+
+/// The number of artifact bytes uploaded, after compression.
+#[cfg(feature = "metrics")]
+const UPLOAD_BYTES: &str = "sp1_network_upload_bytes";
+
+/// The duration of proof request status polls, in seconds.
+#[cfg(feature = "metrics")]
+const STATUS_POLL_DURATION: &str = "sp1_network_status_poll_duration_seconds";
+
+/// Records a failed attempt of an operation that is about to be retried.
+#[inline]
+pub(crate) fn record_retry(operation_name: &str, error_class: &'static str) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(
+        RETRIES_TOTAL,
+        "operation" => operation_name.to_string(),
+        "error_class" => error_class,
+    )
+    .increment(1);
+    #[cfg(not(feature = "metrics"))]
+    let _ = (operation_name, error_class);
+}
+
+/// Records an uploaded artifact body, or part of it.
+#[inline]
+pub(crate) fn record_upload_bytes(bytes: u64) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(UPLOAD_BYTES).increment(bytes);
+    #[cfg(not(feature = "metrics"))]
+    let _ = bytes;
+}
+
+/// Measures the duration of a status poll, recording it when stopped.
+#[derive(Clone, Copy)]
+pub(crate) struct StatusPollTimer {
+    #[cfg(feature = "metrics")]
+    start: std::time::Instant,
+}
+
+impl StatusPollTimer {
+    /// Starts measuring a status poll.
+    #[inline]
+    pub(crate) fn start() -> Self {
+        Self {
+            #[cfg(feature = "metrics")]
+            start: std::time::Instant::now(),
+        }
+    }
+
+    /// Records the duration of the status poll.
+    #[inline]
+    #[cfg_attr(not(feature = "metrics"), allow(clippy::unused_self))]
+    pub(crate) fn stop(self) {
+        #[cfg(feature = "metrics")]
+        metrics::histogram!(STATUS_POLL_DURATION).record(self.start.elapsed().as_secs_f64());
+    }
+}
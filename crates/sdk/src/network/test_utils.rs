@@ -0,0 +1,1778 @@
+//! # Network Test Utilities
+//!
+//! This module provides a [`MockNetworkServer`], an in-process prover network for testing code
+//! that uses the [`crate::network::NetworkClient`] or the [`crate::NetworkProver`] without the
+//! real network.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use alloy_primitives::{Address, B256};
+use alloy_signer::Signature;
+use anyhow::anyhow;
+use futures::{Stream, StreamExt};
+use prost::Message;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tonic::codec::CompressionEncoding;
+use tonic::transport::server::TcpIncoming;
+use tonic::{Request, Response, Status};
+
+use super::auxiliary;
+use super::client::encode_artifact_content;
+use super::compression;
+use super::eip712::{signing_domain, TypedMessage, DEFAULT_DOMAIN_NAME, EIP712_CAPABILITY};
+use super::proto::artifact::artifact_store_server::{ArtifactStore, ArtifactStoreServer};
+use super::proto::artifact::{
+    CompleteMultipartArtifactRequest, CompleteMultipartArtifactResponse, CreateArtifactPartRequest,
+    CreateArtifactPartResponse, CreateArtifactRequest, CreateArtifactResponse,
+    CreateMultipartArtifactRequest, CreateMultipartArtifactResponse, GetArtifactByHashRequest,
+    GetArtifactByHashResponse, GetAuxiliaryArtifactRequest, GetAuxiliaryArtifactResponse,
+};
+use super::proto::network::prover_network_server::{ProverNetwork, ProverNetworkServer};
+use super::proto::network::*;
+use super::utils;
+use super::Error;
+use crate::{SP1Proof, SP1ProofWithPublicValues, SP1PublicValues, SP1_CIRCUIT_VERSION};
+
+/// How often subscriptions check whether the status of a request changed.
+const STATUS_TICK: Duration = Duration::from_millis(10);
+
+/// The balance of every account, in wei.
+const MOCK_BALANCE: &str = "1000000000000000000000";
+
+/// The fulfiller of every fulfilled request.
+const MOCK_FULFILLER: Address = Address::new([0xf1; 20]);
+
+/// The cycles used by every fulfilled request.
+const MOCK_CYCLES: u64 = 1_000_000;
+
+/// The amount paid for every fulfilled request, in wei.
+const MOCK_PRICE: &str = "1000000000000000";
+
+/// How a proof request submitted to a [`MockNetworkServer`] turns out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockOutcome {
+    /// The request is fulfilled with the canned proof after the delay.
+    Fulfilled {
+        /// How long the request stays requested before it is fulfilled.
+        delay: Duration,
+    },
+    /// The request becomes unfulfillable after the delay.
+    Unfulfillable {
+        /// How long the request stays requested before it fails.
+        delay: Duration,
+        /// The failure reason reported in the status of the request.
+        reason: String,
+    },
+    /// The program fails to execute on the stdin of the request after the delay.
+    Unexecutable {
+        /// How long the request stays requested before it fails.
+        delay: Duration,
+        /// The failure reason reported in the status of the request.
+        reason: String,
+    },
+    /// The request is never picked up.
+    Pending,
+}
+
+impl Default for MockOutcome {
+    fn default() -> Self {
+        Self::Fulfilled { delay: Duration::ZERO }
+    }
+}
+
+/// An in-process prover network, for end-to-end tests of code that uses the network.
+///
+/// # Details
+/// The server implements the prover network and artifact store RPCs on a local port, and serves
+/// artifacts over HTTP on another, storing everything in memory. Point a
+/// [`crate::network::NetworkClient`] or a [`crate::NetworkProver`] at [`Self::rpc_url`] with any
+/// private key. Signatures and nonces are checked like the real network does.
+///
+/// Requests are fulfilled with a canned proof, see [`Self::set_proof`], after the delay of their
+/// [`MockOutcome`]. Failure scenarios are scripted with [`Self::push_outcome`],
+/// [`Self::remove_program`], [`Self::drop_uploads`], [`Self::end_status_streams`] and
+/// [`Self::fail_status_streams`]. RPCs the SDK does not use return
+/// `UNIMPLEMENTED`. The server shuts down when it is dropped.
+///
+/// # Example
+/// Requires the `test-utils` feature.
+/// ```rust,ignore
+/// use sp1_sdk::network::test_utils::{MockNetworkServer, MockOutcome};
+/// use sp1_sdk::network::B256;
+/// use sp1_sdk::{NetworkProver, SP1Stdin};
+///
+/// tokio_test::block_on(async {
+///     let server = MockNetworkServer::start().await.unwrap();
+///     let vk_hash = B256::repeat_byte(1);
+///     server.insert_program(vk_hash);
+///     server.push_outcome(MockOutcome::Unfulfillable {
+///         delay: std::time::Duration::from_secs(1),
+///         reason: "out of capacity".to_string(),
+///     });
+///
+///     let prover = NetworkProver::new("0x...", server.rpc_url());
+///     let result = prover.prove_with_vk_hash(vk_hash, SP1Stdin::new()).run_async().await;
+///     assert!(result.is_err());
+/// })
+/// ```
+pub struct MockNetworkServer {
+    state: Arc<MockState>,
+    rpc_url: String,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl MockNetworkServer {
+    /// Starts a mock network on local ports.
+    pub async fn start() -> Result<Self, Error> {
+        let artifact_listener =
+            TcpListener::bind("127.0.0.1:0").await.map_err(anyhow::Error::from)?;
+        let artifact_url =
+            format!("http://{}", artifact_listener.local_addr().map_err(anyhow::Error::from)?);
+        let state = Arc::new(MockState::new(artifact_url));
+
+        let rpc_listener = TcpListener::bind("127.0.0.1:0").await.map_err(anyhow::Error::from)?;
+        let rpc_url = format!("http://{}", rpc_listener.local_addr().map_err(anyhow::Error::from)?);
+        let incoming =
+            TcpIncoming::from_listener(rpc_listener, true, None).map_err(|e| anyhow!(e))?;
+        let rpc = tonic::transport::Server::builder()
+            .add_service(
+                ProverNetworkServer::new(MockProverNetwork(state.clone()))
+                    .accept_compressed(CompressionEncoding::Gzip)
+                    .send_compressed(CompressionEncoding::Gzip),
+            )
+            .add_service(
+                ArtifactStoreServer::new(MockArtifactStore(state.clone()))
+                    .accept_compressed(CompressionEncoding::Gzip)
+                    .send_compressed(CompressionEncoding::Gzip),
+            )
+            .serve_with_incoming(incoming);
+
+        let tasks = vec![
+            tokio::spawn(async move {
+                if let Err(e) = rpc.await {
+                    log::warn!("Mock network stopped: {e}");
+                }
+            }),
+            tokio::spawn(serve_artifacts(state.clone(), artifact_listener)),
+        ];
+        Ok(Self { state, rpc_url, tasks })
+    }
+
+    /// The URL to point a [`crate::network::NetworkClient`] at.
+    #[must_use]
+    pub fn rpc_url(&self) -> &str {
+        &self.rpc_url
+    }
+
+    /// Sets the proof that fulfilled requests are fulfilled with.
+    ///
+    /// # Details
+    /// By default, requests are fulfilled with an empty core proof, which is not verified unless
+    /// a verifying key is given.
+    ///
+    /// # Panics
+    /// Panics if the proof cannot be serialized.
+    pub fn set_proof(&self, proof: &SP1ProofWithPublicValues) {
+        let proof = encode_artifact_content(proof).expect("failed to serialize proof");
+        self.state.lock().proof = proof;
+    }
+
+    /// Sets the outcome of requests that have no outcome pushed with [`Self::push_outcome`].
+    pub fn set_default_outcome(&self, outcome: MockOutcome) {
+        self.state.lock().default_outcome = outcome;
+    }
+
+    /// Pushes the outcome of a future request. Outcomes are assigned to requests in the order they
+    /// are submitted.
+    pub fn push_outcome(&self, outcome: MockOutcome) {
+        self.state.lock().outcomes.push_back(outcome);
+    }
+
+    /// Registers a program, so that requests for it are accepted without creating it first.
+    pub fn insert_program(&self, vk_hash: B256) {
+        let program = Program { vk_hash: vk_hash.to_vec(), ..Default::default() };
+        self.state.lock().programs.insert(vk_hash, program);
+    }
+
+    /// Removes a program, so that it is reported as not found and requests for it are rejected.
+    pub fn remove_program(&self, vk_hash: B256) {
+        self.state.lock().programs.remove(&vk_hash);
+    }
+
+    /// Sets the optional features the network reports to support, e.g. `eip712_signatures`.
+    ///
+    /// # Details
+    /// By default, no capabilities are reported. Once `eip712_signatures` is reported, requests
+    /// must be signed as EIP-712 typed data in the domain of [`DEFAULT_DOMAIN_NAME`], and the
+    /// legacy signatures are rejected.
+    pub fn set_capabilities(&self, capabilities: &[&str]) {
+        self.state.lock().capabilities = capabilities.iter().map(ToString::to_string).collect();
+    }
+
+    /// Drops the connection of the next `count` artifact uploads after part of the content is
+    /// received, as if the network failed mid-upload.
+    pub fn drop_uploads(&self, count: usize) {
+        self.state.lock().dropped_uploads += count;
+    }
+
+    /// Ends the next `count` status streams after their first status, as if the server closed
+    /// them.
+    pub fn end_status_streams(&self, count: usize) {
+        let mut inner = self.state.lock();
+        inner.status_interruptions.extend((0..count).map(|_| StatusInterruption::End));
+    }
+
+    /// Fails the next `count` status streams with `UNAVAILABLE` after their first status, as if
+    /// the connection dropped.
+    pub fn fail_status_streams(&self, count: usize) {
+        let mut inner = self.state.lock();
+        inner.status_interruptions.extend((0..count).map(|_| StatusInterruption::Fail));
+    }
+
+    /// The number of status streams subscribed to so far.
+    #[must_use]
+    pub fn status_subscriptions(&self) -> usize {
+        self.state.lock().status_subscriptions
+    }
+
+    /// The bodies of the proof requests submitted so far, in order.
+    #[must_use]
+    pub fn proof_requests(&self) -> Vec<RequestProofRequestBody> {
+        self.state.lock().requests.iter().map(|request| request.body.clone()).collect()
+    }
+
+    /// The decompressed content of the uploaded artifact with the given URI, if any.
+    #[must_use]
+    pub fn artifact(&self, uri: &str) -> Option<Vec<u8>> {
+        let path = uri.strip_prefix(&self.state.artifact_url)?;
+        let artifact = self.state.lock().artifacts.get(path)?.clone();
+        compression::decompress(artifact.bytes, artifact.content_encoding.as_deref()).ok()
+    }
+}
+
+impl Drop for MockNetworkServer {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+/// The state of a mock network, shared by its RPC and artifact servers.
+struct MockState {
+    artifact_url: String,
+    inner: Mutex<MockInner>,
+}
+
+#[derive(Default)]
+struct MockInner {
+    nonces: HashMap<Address, u64>,
+    programs: HashMap<B256, Program>,
+    requests: Vec<MockRequest>,
+    artifacts: HashMap<String, StoredArtifact>,
+    pending_artifacts: HashMap<String, PendingArtifact>,
+    artifact_hashes: HashMap<(i32, Vec<u8>), String>,
+    auxiliary_artifacts: HashMap<AuxiliaryKey, (String, Option<Vec<u8>>)>,
+    multipart_uploads: HashMap<String, MultipartUpload>,
+    outcomes: VecDeque<MockOutcome>,
+    default_outcome: MockOutcome,
+    proof: Vec<u8>,
+    dropped_uploads: usize,
+    status_interruptions: VecDeque<StatusInterruption>,
+    status_subscriptions: usize,
+    next_artifact: u64,
+    capabilities: Vec<String>,
+}
+
+/// A proof request submitted to a mock network.
+struct MockRequest {
+    request_id: B256,
+    requester: Address,
+    body: RequestProofRequestBody,
+    tx_hash: B256,
+    submitted: Instant,
+    created_at: u64,
+    outcome: MockOutcome,
+    canceled: bool,
+}
+
+/// How a status stream is interrupted after its first status.
+#[derive(Clone, Copy)]
+enum StatusInterruption {
+    End,
+    Fail,
+}
+
+/// The content of an uploaded artifact, as stored.
+#[derive(Clone)]
+struct StoredArtifact {
+    bytes: Vec<u8>,
+    content_encoding: Option<String>,
+}
+
+/// The request ID, artifact type and custom type an auxiliary artifact is attached under.
+type AuxiliaryKey = (B256, i32, Option<u32>);
+
+/// An artifact that was created but not uploaded yet.
+struct PendingArtifact {
+    artifact_type: i32,
+    sha256: Option<Vec<u8>>,
+    content_encoding: Option<String>,
+    attachment: Option<AuxiliaryKey>,
+}
+
+/// The parts uploaded so far of a multipart artifact.
+struct MultipartUpload {
+    path: String,
+    parts: BTreeMap<u32, Vec<u8>>,
+}
+
+impl MockInner {
+    /// Checks the nonce of a message signed by the given signer and consumes it.
+    fn use_nonce(&mut self, signer: Address, nonce: u64) -> Result<(), Status> {
+        let expected = self.nonces.entry(signer).or_default();
+        if nonce != *expected {
+            return Err(Status::invalid_argument(format!(
+                "invalid nonce: expected {expected}, got {nonce}"
+            )));
+        }
+        *expected += 1;
+        Ok(())
+    }
+}
+
+impl MockState {
+    fn new(artifact_url: String) -> Self {
+        let proof = SP1ProofWithPublicValues {
+            proof: SP1Proof::Core(Vec::new()),
+            public_values: SP1PublicValues::new(),
+            sp1_version: SP1_CIRCUIT_VERSION.to_string(),
+        };
+        let proof = encode_artifact_content(&proof).expect("failed to serialize proof");
+        Self { artifact_url, inner: Mutex::new(MockInner { proof, ..Default::default() }) }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockInner> {
+        self.inner.lock().unwrap()
+    }
+
+    /// Checks the signature of a request to create an artifact, returning the key the artifact is
+    /// attached under, if it is attached to a proof request.
+    ///
+    /// # Details
+    /// Only the requester of a proof request can attach artifacts to it.
+    fn authorize_artifact(
+        &self,
+        format: i32,
+        signature: &[u8],
+        message: &impl TypedMessage,
+        request_id: Option<&[u8]>,
+        artifact_type: i32,
+        custom_type: Option<u32>,
+    ) -> Result<Option<AuxiliaryKey>, Status> {
+        let Some(request_id) = request_id else {
+            self.recover_typed_signer(format, signature, message, b"create_artifact")?;
+            return Ok(None);
+        };
+        let request_id = parse_request_id(request_id)?;
+        let legacy_message = auxiliary::create_message(request_id);
+        let signer = self.recover_typed_signer(format, signature, message, &legacy_message)?;
+        if find_request(&self.lock(), request_id.as_slice())?.requester != signer {
+            return Err(Status::permission_denied(
+                "only the requester can attach artifacts to a request",
+            ));
+        }
+        Ok(Some((request_id, artifact_type, custom_type)))
+    }
+
+    /// Recovers the address that signed a message in the given format.
+    ///
+    /// # Details
+    /// EIP-712 signatures are verified in the default domain. Legacy signatures are verified over
+    /// `legacy_message`, unless the network reports EIP-712 signatures, in which case they are
+    /// rejected.
+    fn recover_typed_signer(
+        &self,
+        format: i32,
+        signature: &[u8],
+        message: &impl TypedMessage,
+        legacy_message: &[u8],
+    ) -> Result<Address, Status> {
+        let signature = parse_signature(signature)?;
+        let signer = if format == MessageFormat::Eip712 as i32 {
+            let hash = message.eip712_signing_hash(&signing_domain(DEFAULT_DOMAIN_NAME));
+            signature.recover_address_from_prehash(&hash)
+        } else if self.lock().capabilities.iter().any(|c| c == EIP712_CAPABILITY) {
+            return Err(Status::unauthenticated("requests must be signed as EIP-712 typed data"));
+        } else {
+            signature.recover_address_from_msg(legacy_message)
+        };
+        signer.map_err(|e| Status::invalid_argument(format!("invalid signature: {e}")))
+    }
+
+    /// Creates an artifact, returning its path on the artifact server.
+    fn create_artifact(
+        &self,
+        prefix: &str,
+        artifact_type: i32,
+        sha256: Option<Vec<u8>>,
+        content_encoding: Option<String>,
+        attachment: Option<AuxiliaryKey>,
+    ) -> String {
+        let mut inner = self.lock();
+        inner.next_artifact += 1;
+        let path = format!("/{prefix}/{}", inner.next_artifact);
+        let pending = PendingArtifact { artifact_type, sha256, content_encoding, attachment };
+        inner.pending_artifacts.insert(path.clone(), pending);
+        path
+    }
+
+    /// Stores the content of an artifact, making it available by its hash, or attaching it to
+    /// its proof request.
+    fn store_artifact(&self, inner: &mut MockInner, path: &str, bytes: Vec<u8>) {
+        let pending = inner.pending_artifacts.remove(path);
+        let content_encoding = pending.as_ref().and_then(|p| p.content_encoding.clone());
+        match pending {
+            Some(PendingArtifact { attachment: Some(key), sha256, .. }) => {
+                inner.auxiliary_artifacts.insert(key, (path.to_string(), sha256));
+            }
+            Some(PendingArtifact { artifact_type, sha256: Some(sha256), .. }) => {
+                inner.artifact_hashes.insert((artifact_type, sha256), self.url(path));
+            }
+            _ => {}
+        }
+        inner.artifacts.insert(path.to_string(), StoredArtifact { bytes, content_encoding });
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.artifact_url)
+    }
+
+    /// The current status of a request.
+    fn status(&self, request_id: &[u8]) -> Result<GetProofRequestStatusResponse, Status> {
+        let inner = self.lock();
+        let request = find_request(&inner, request_id)?;
+        let mut status = GetProofRequestStatusResponse {
+            fulfillment_status: FulfillmentStatus::Requested.into(),
+            execution_status: ExecutionStatus::Unexecuted.into(),
+            request_tx_hash: request.tx_hash.to_vec(),
+            deadline: request.body.deadline,
+            created_at: Some(request.created_at),
+            ..Default::default()
+        };
+        if request.canceled {
+            status.fulfillment_status = FulfillmentStatus::Unfulfillable.into();
+            status.failure_reason = Some("the request was canceled".to_string());
+            return Ok(status);
+        }
+
+        let elapsed = request.submitted.elapsed();
+        match &request.outcome {
+            MockOutcome::Fulfilled { delay } if elapsed >= *delay => {
+                let proof_sha256 = utils::sha256(&inner.proof);
+                status.fulfillment_status = FulfillmentStatus::Fulfilled.into();
+                status.execution_status = ExecutionStatus::Executed.into();
+                status.proof_uri = Some(self.url(&format!("/proofs/{}", request.request_id)));
+                status.proof_sha256 = Some(proof_sha256.to_vec());
+                status.proof_size = Some(inner.proof.len() as u64);
+                status.fulfill_tx_hash = Some(utils::sha256(proof_sha256.as_slice()).to_vec());
+            }
+            MockOutcome::Unfulfillable { delay, reason } if elapsed >= *delay => {
+                status.fulfillment_status = FulfillmentStatus::Unfulfillable.into();
+                status.execution_status = ExecutionStatus::Executed.into();
+                status.failure_reason = Some(reason.clone());
+            }
+            MockOutcome::Unexecutable { delay, reason } if elapsed >= *delay => {
+                status.fulfillment_status = FulfillmentStatus::Unfulfillable.into();
+                status.execution_status = ExecutionStatus::Unexecutable.into();
+                status.failure_reason = Some(reason.clone());
+            }
+            _ => {}
+        }
+        Ok(status)
+    }
+
+    /// The details of a request, as listed by the network.
+    fn details(&self, request_id: &[u8]) -> Result<ProofRequest, Status> {
+        let status = self.status(request_id)?;
+        let inner = self.lock();
+        let request = find_request(&inner, request_id)?;
+        let program_uri = B256::try_from(request.body.vk_hash.as_slice())
+            .ok()
+            .and_then(|vk_hash| inner.programs.get(&vk_hash))
+            .map(|program| program.program_uri.clone())
+            .unwrap_or_default();
+        let mut details = ProofRequest {
+            request_id: request.request_id.to_vec(),
+            vk_hash: request.body.vk_hash.clone(),
+            version: request.body.version.clone(),
+            mode: request.body.mode,
+            strategy: request.body.strategy,
+            program_uri,
+            stdin_uri: request.body.stdin_uri.clone(),
+            deadline: request.body.deadline,
+            cycle_limit: request.body.cycle_limit,
+            fulfillment_status: status.fulfillment_status,
+            execution_status: status.execution_status,
+            requester: request.requester.to_vec(),
+            created_at: request.created_at,
+            updated_at: now_secs(),
+            tx_hash: request.tx_hash.to_vec(),
+            metadata: request.body.metadata.clone(),
+            ..Default::default()
+        };
+        if status.fulfillment_status == FulfillmentStatus::Fulfilled as i32 {
+            details.fulfiller = Some(MOCK_FULFILLER.to_vec());
+            details.fulfilled_at = Some(details.updated_at);
+            details.cycles = Some(MOCK_CYCLES);
+            details.public_values_hash = Some(utils::sha256(&inner.proof).to_vec());
+            details.deduction_amount = Some(MOCK_PRICE.to_string());
+        }
+        Ok(details)
+    }
+}
+
+/// Whether the details of a request match a filter of the proof requests.
+fn matches_filter(details: &ProofRequest, filter: &GetFilteredProofRequestsRequest) -> bool {
+    let matches = |expected: &Option<Vec<u8>>, actual: &[u8]| {
+        expected.as_ref().map_or(true, |expected| expected == actual)
+    };
+    filter.version.as_ref().map_or(true, |version| *version == details.version)
+        && filter.fulfillment_status.map_or(true, |status| status == details.fulfillment_status)
+        && filter.execution_status.map_or(true, |status| status == details.execution_status)
+        && filter.minimum_deadline.map_or(true, |deadline| details.deadline >= deadline)
+        && matches(&filter.vk_hash, &details.vk_hash)
+        && matches(&filter.requester, &details.requester)
+        && matches(&filter.fulfiller, details.fulfiller.as_deref().unwrap_or_default())
+        && filter.from.map_or(true, |from| details.created_at >= from)
+        && filter.to.map_or(true, |to| details.created_at < to)
+        && filter.mode.map_or(true, |mode| mode == details.mode)
+        && filter.metadata.iter().all(|(key, value)| details.metadata.get(key) == Some(value))
+}
+
+/// Returns the request with the given ID.
+fn find_request<'a>(inner: &'a MockInner, request_id: &[u8]) -> Result<&'a MockRequest, Status> {
+    inner
+        .requests
+        .iter()
+        .find(|request| request.request_id.as_slice() == request_id)
+        .ok_or_else(|| Status::not_found("request not found"))
+}
+
+/// Whether a request with the given status can no longer change.
+fn is_terminal(status: &GetProofRequestStatusResponse) -> bool {
+    status.fulfillment_status == FulfillmentStatus::Fulfilled as i32
+        || status.fulfillment_status == FulfillmentStatus::Unfulfillable as i32
+}
+
+/// Recovers the address that signed the given message.
+fn recover_signer(signature: &[u8], message: &impl Message) -> Result<Address, Status> {
+    parse_signature(signature)?
+        .recover_address_from_msg(message.encode_to_vec())
+        .map_err(|e| Status::invalid_argument(format!("invalid signature: {e}")))
+}
+
+/// Parses the ID of a proof request.
+fn parse_request_id(request_id: &[u8]) -> Result<B256, Status> {
+    B256::try_from(request_id).map_err(|_| Status::invalid_argument("invalid request ID"))
+}
+
+/// Parses the signature of a request.
+fn parse_signature(signature: &[u8]) -> Result<Signature, Status> {
+    Signature::try_from(signature)
+        .map_err(|e| Status::invalid_argument(format!("invalid signature: {e}")))
+}
+
+/// The error returned by RPCs that the mock network does not implement.
+fn unsupported(rpc: &str) -> Status {
+    Status::unimplemented(format!("{rpc} is not supported by the mock network"))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// The prover network RPCs of a mock network.
+struct MockProverNetwork(Arc<MockState>);
+
+#[tonic::async_trait]
+impl ProverNetwork for MockProverNetwork {
+    async fn request_proof(
+        &self,
+        request: Request<RequestProofRequest>,
+    ) -> Result<Response<RequestProofResponse>, Status> {
+        let request = request.into_inner();
+        let body = request.body.ok_or_else(|| Status::invalid_argument("missing body"))?;
+        let requester = self.0.recover_typed_signer(
+            request.format,
+            &request.signature,
+            &body,
+            &body.encode_to_vec(),
+        )?;
+
+        let mut inner = self.0.lock();
+        let vk_hash = B256::try_from(body.vk_hash.as_slice())
+            .map_err(|_| Status::invalid_argument("invalid vk hash"))?;
+        if !inner.programs.contains_key(&vk_hash) {
+            return Err(Status::not_found(format!("program {vk_hash} not found")));
+        }
+        inner.use_nonce(requester, body.nonce)?;
+
+        let request_id = utils::sha256(&request.signature);
+        let tx_hash = utils::sha256(request_id.as_slice());
+        let outcome = match inner.outcomes.pop_front() {
+            Some(outcome) => outcome,
+            None => inner.default_outcome.clone(),
+        };
+        inner.requests.push(MockRequest {
+            request_id,
+            requester,
+            body,
+            tx_hash,
+            submitted: Instant::now(),
+            created_at: now_secs(),
+            outcome,
+            canceled: false,
+        });
+
+        Ok(Response::new(RequestProofResponse {
+            tx_hash: tx_hash.to_vec(),
+            body: Some(RequestProofResponseBody { request_id: request_id.to_vec() }),
+        }))
+    }
+
+    async fn validate_proof_request(
+        &self,
+        request: Request<RequestProofRequest>,
+    ) -> Result<Response<ValidateProofRequestResponse>, Status> {
+        let request = request.into_inner();
+        let body = request.body.ok_or_else(|| Status::invalid_argument("missing body"))?;
+        let requester = self.0.recover_typed_signer(
+            request.format,
+            &request.signature,
+            &body,
+            &body.encode_to_vec(),
+        )?;
+
+        let inner = self.0.lock();
+        let expected_nonce = inner.nonces.get(&requester).copied().unwrap_or_default();
+        let reason = if body.nonce != expected_nonce {
+            Some(format!("invalid nonce: expected {expected_nonce}, got {}", body.nonce))
+        } else if !inner.programs.keys().any(|vk_hash| vk_hash.as_slice() == body.vk_hash) {
+            Some("program not found".to_string())
+        } else {
+            None
+        };
+        Ok(Response::new(ValidateProofRequestResponse { valid: reason.is_none(), reason }))
+    }
+
+    async fn cancel_request(
+        &self,
+        request: Request<CancelRequestRequest>,
+    ) -> Result<Response<CancelRequestResponse>, Status> {
+        let request = request.into_inner();
+        let body = request.body.ok_or_else(|| Status::invalid_argument("missing body"))?;
+        let signer = recover_signer(&request.signature, &body)?;
+        let tx_hash = utils::sha256(&request.signature);
+
+        // Like the network, only requests that were not picked up yet can be cancelled.
+        if self.0.status(&body.request_id)?.fulfillment_status() != FulfillmentStatus::Requested {
+            return Err(Status::failed_precondition("the request can no longer be cancelled"));
+        }
+        let mut inner = self.0.lock();
+        let requester = find_request(&inner, &body.request_id)?.requester;
+        if requester != signer {
+            return Err(Status::permission_denied("only the requester can cancel a request"));
+        }
+        inner.use_nonce(signer, body.nonce)?;
+        let request = inner
+            .requests
+            .iter_mut()
+            .find(|request| request.request_id.as_slice() == body.request_id)
+            .expect("request exists");
+        request.canceled = true;
+
+        Ok(Response::new(CancelRequestResponse {
+            tx_hash: tx_hash.to_vec(),
+            body: Some(CancelRequestResponseBody {}),
+        }))
+    }
+
+    async fn get_proof_request_status(
+        &self,
+        request: Request<GetProofRequestStatusRequest>,
+    ) -> Result<Response<GetProofRequestStatusResponse>, Status> {
+        Ok(Response::new(self.0.status(&request.into_inner().request_id)?))
+    }
+
+    type SubscribeProofRequestStatusStream =
+        Pin<Box<dyn Stream<Item = Result<GetProofRequestStatusResponse, Status>> + Send + 'static>>;
+
+    async fn subscribe_proof_request_status(
+        &self,
+        request: Request<GetProofRequestStatusRequest>,
+    ) -> Result<Response<Self::SubscribeProofRequestStatusStream>, Status> {
+        let request_id = request.into_inner().request_id;
+        let initial = self.0.status(&request_id)?;
+        let state = self.0.clone();
+        let interruption = {
+            let mut inner = self.0.lock();
+            inner.status_subscriptions += 1;
+            inner.status_interruptions.pop_front()
+        };
+
+        // Send the current status, then every change until the request can no longer change.
+        let stream = futures::stream::unfold(Some(initial), move |status| {
+            let state = state.clone();
+            let request_id = request_id.clone();
+            async move {
+                let status = status?;
+                if is_terminal(&status) {
+                    return Some((Ok(status), None));
+                }
+                let next = loop {
+                    tokio::time::sleep(STATUS_TICK).await;
+                    match state.status(&request_id) {
+                        Ok(next)
+                            if next.fulfillment_status != status.fulfillment_status
+                                || next.execution_status != status.execution_status =>
+                        {
+                            break Some(next)
+                        }
+                        Ok(_) => {}
+                        Err(_) => break None,
+                    }
+                };
+                Some((Ok(status), next))
+            }
+        });
+        let stream = match interruption {
+            None => stream.boxed(),
+            Some(StatusInterruption::End) => stream.take(1).boxed(),
+            Some(StatusInterruption::Fail) => stream
+                .take(1)
+                .chain(futures::stream::once(async {
+                    Err(Status::unavailable("the connection was dropped"))
+                }))
+                .boxed(),
+        };
+        Ok(Response::new(stream))
+    }
+
+    async fn get_proof_request_details(
+        &self,
+        request: Request<GetProofRequestDetailsRequest>,
+    ) -> Result<Response<GetProofRequestDetailsResponse>, Status> {
+        let details = self.0.details(&request.into_inner().request_id)?;
+        Ok(Response::new(GetProofRequestDetailsResponse { request: Some(details) }))
+    }
+
+    async fn get_nonce(
+        &self,
+        request: Request<GetNonceRequest>,
+    ) -> Result<Response<GetNonceResponse>, Status> {
+        let address = Address::try_from(request.into_inner().address.as_slice())
+            .map_err(|_| Status::invalid_argument("invalid address"))?;
+        let nonce = self.0.lock().nonces.get(&address).copied().unwrap_or_default();
+        Ok(Response::new(GetNonceResponse { nonce }))
+    }
+
+    async fn get_program(
+        &self,
+        request: Request<GetProgramRequest>,
+    ) -> Result<Response<GetProgramResponse>, Status> {
+        let vk_hash = B256::try_from(request.into_inner().vk_hash.as_slice())
+            .map_err(|_| Status::invalid_argument("invalid vk hash"))?;
+        match self.0.lock().programs.get(&vk_hash) {
+            Some(program) => {
+                Ok(Response::new(GetProgramResponse { program: Some(program.clone()) }))
+            }
+            None => Err(Status::not_found(format!("program {vk_hash} not found"))),
+        }
+    }
+
+    async fn create_program(
+        &self,
+        request: Request<CreateProgramRequest>,
+    ) -> Result<Response<CreateProgramResponse>, Status> {
+        let request = request.into_inner();
+        let body = request.body.ok_or_else(|| Status::invalid_argument("missing body"))?;
+        let owner = self.0.recover_typed_signer(
+            request.format,
+            &request.signature,
+            &body,
+            &body.encode_to_vec(),
+        )?;
+        let vk_hash = B256::try_from(body.vk_hash.as_slice())
+            .map_err(|_| Status::invalid_argument("invalid vk hash"))?;
+
+        let mut inner = self.0.lock();
+        if inner.programs.contains_key(&vk_hash) {
+            return Err(Status::already_exists(format!("program {vk_hash} already exists")));
+        }
+        inner.use_nonce(owner, body.nonce)?;
+        let program = Program {
+            vk_hash: body.vk_hash,
+            vk: body.vk,
+            program_uri: body.program_uri,
+            name: body.name,
+            owner: owner.to_vec(),
+            created_at: now_secs(),
+        };
+        inner.programs.insert(vk_hash, program);
+
+        Ok(Response::new(CreateProgramResponse {
+            tx_hash: utils::sha256(&request.signature).to_vec(),
+            body: Some(CreateProgramResponseBody {}),
+        }))
+    }
+
+    async fn get_balance(
+        &self,
+        _: Request<GetBalanceRequest>,
+    ) -> Result<Response<GetBalanceResponse>, Status> {
+        Ok(Response::new(GetBalanceResponse {
+            amount: MOCK_BALANCE.to_string(),
+            reserved_amount: None,
+            pending_amount: None,
+        }))
+    }
+
+    async fn get_server_time(
+        &self,
+        _: Request<GetServerTimeRequest>,
+    ) -> Result<Response<GetServerTimeResponse>, Status> {
+        Ok(Response::new(GetServerTimeResponse { timestamp: now_secs() }))
+    }
+
+    async fn get_capabilities(
+        &self,
+        _: Request<GetCapabilitiesRequest>,
+    ) -> Result<Response<GetCapabilitiesResponse>, Status> {
+        Ok(Response::new(GetCapabilitiesResponse {
+            capabilities: self.0.lock().capabilities.clone(),
+        }))
+    }
+
+    async fn fulfill_proof(
+        &self,
+        _: Request<FulfillProofRequest>,
+    ) -> Result<Response<FulfillProofResponse>, Status> {
+        Err(unsupported("fulfill_proof"))
+    }
+
+    async fn execute_proof(
+        &self,
+        _: Request<ExecuteProofRequest>,
+    ) -> Result<Response<ExecuteProofResponse>, Status> {
+        Err(unsupported("execute_proof"))
+    }
+
+    async fn fail_fulfillment(
+        &self,
+        _: Request<FailFulfillmentRequest>,
+    ) -> Result<Response<FailFulfillmentResponse>, Status> {
+        Err(unsupported("fail_fulfillment"))
+    }
+
+    async fn fail_execution(
+        &self,
+        _: Request<FailExecutionRequest>,
+    ) -> Result<Response<FailExecutionResponse>, Status> {
+        Err(unsupported("fail_execution"))
+    }
+
+    async fn get_filtered_proof_requests(
+        &self,
+        request: Request<GetFilteredProofRequestsRequest>,
+    ) -> Result<Response<GetFilteredProofRequestsResponse>, Status> {
+        let filter = request.into_inner();
+        let request_ids: Vec<_> =
+            self.0.lock().requests.iter().map(|request| request.request_id).collect();
+        let mut matching = Vec::new();
+        for request_id in request_ids {
+            let details = self.0.details(request_id.as_slice())?;
+            if matches_filter(&details, &filter) {
+                matching.push(details);
+            }
+        }
+        let limit = filter.limit.unwrap_or(100).max(1) as usize;
+        let page = filter.page.unwrap_or(1).max(1) as usize;
+        let requests = matching.into_iter().skip((page - 1) * limit).take(limit).collect();
+        Ok(Response::new(GetFilteredProofRequestsResponse { requests }))
+    }
+
+    async fn get_search_results(
+        &self,
+        _: Request<GetSearchResultsRequest>,
+    ) -> Result<Response<GetSearchResultsResponse>, Status> {
+        Err(unsupported("get_search_results"))
+    }
+
+    async fn get_proof_request_metrics(
+        &self,
+        _: Request<GetProofRequestMetricsRequest>,
+    ) -> Result<Response<GetProofRequestMetricsResponse>, Status> {
+        Err(unsupported("get_proof_request_metrics"))
+    }
+
+    async fn get_proof_request_graph(
+        &self,
+        _: Request<GetProofRequestGraphRequest>,
+    ) -> Result<Response<GetProofRequestGraphResponse>, Status> {
+        Err(unsupported("get_proof_request_graph"))
+    }
+
+    async fn get_analytics_graphs(
+        &self,
+        _: Request<GetAnalyticsGraphsRequest>,
+    ) -> Result<Response<GetAnalyticsGraphsResponse>, Status> {
+        Err(unsupported("get_analytics_graphs"))
+    }
+
+    async fn get_filtered_delegations(
+        &self,
+        _: Request<GetFilteredDelegationsRequest>,
+    ) -> Result<Response<GetFilteredDelegationsResponse>, Status> {
+        Err(unsupported("get_filtered_delegations"))
+    }
+
+    async fn add_delegation(
+        &self,
+        _: Request<AddDelegationRequest>,
+    ) -> Result<Response<AddDelegationResponse>, Status> {
+        Err(unsupported("add_delegation"))
+    }
+
+    async fn remove_delegation(
+        &self,
+        _: Request<RemoveDelegationRequest>,
+    ) -> Result<Response<RemoveDelegationResponse>, Status> {
+        Err(unsupported("remove_delegation"))
+    }
+
+    async fn terminate_delegation(
+        &self,
+        _: Request<TerminateDelegationRequest>,
+    ) -> Result<Response<TerminateDelegationResponse>, Status> {
+        Err(unsupported("terminate_delegation"))
+    }
+
+    async fn accept_delegation(
+        &self,
+        _: Request<AcceptDelegationRequest>,
+    ) -> Result<Response<AcceptDelegationResponse>, Status> {
+        Err(unsupported("accept_delegation"))
+    }
+
+    async fn set_account_name(
+        &self,
+        _: Request<SetAccountNameRequest>,
+    ) -> Result<Response<SetAccountNameResponse>, Status> {
+        Err(unsupported("set_account_name"))
+    }
+
+    async fn get_account_name(
+        &self,
+        _: Request<GetAccountNameRequest>,
+    ) -> Result<Response<GetAccountNameResponse>, Status> {
+        Err(unsupported("get_account_name"))
+    }
+
+    async fn get_terms_signature(
+        &self,
+        _: Request<GetTermsSignatureRequest>,
+    ) -> Result<Response<GetTermsSignatureResponse>, Status> {
+        Err(unsupported("get_terms_signature"))
+    }
+
+    async fn set_terms_signature(
+        &self,
+        _: Request<SetTermsSignatureRequest>,
+    ) -> Result<Response<SetTermsSignatureResponse>, Status> {
+        Err(unsupported("set_terms_signature"))
+    }
+
+    async fn get_programs_by_owner(
+        &self,
+        _: Request<GetProgramsByOwnerRequest>,
+    ) -> Result<Response<GetProgramsByOwnerResponse>, Status> {
+        Err(unsupported("get_programs_by_owner"))
+    }
+
+    async fn set_program_name(
+        &self,
+        _: Request<SetProgramNameRequest>,
+    ) -> Result<Response<SetProgramNameResponse>, Status> {
+        Err(unsupported("set_program_name"))
+    }
+
+    async fn get_proof_cost_estimate(
+        &self,
+        _: Request<GetProofCostEstimateRequest>,
+    ) -> Result<Response<GetProofCostEstimateResponse>, Status> {
+        Err(unsupported("get_proof_cost_estimate"))
+    }
+
+    async fn get_filtered_balance_logs(
+        &self,
+        _: Request<GetFilteredBalanceLogsRequest>,
+    ) -> Result<Response<GetFilteredBalanceLogsResponse>, Status> {
+        Err(unsupported("get_filtered_balance_logs"))
+    }
+
+    async fn add_credit(
+        &self,
+        _: Request<AddCreditRequest>,
+    ) -> Result<Response<AddCreditResponse>, Status> {
+        Err(unsupported("add_credit"))
+    }
+
+    async fn get_latest_bridge_block(
+        &self,
+        _: Request<GetLatestBridgeBlockRequest>,
+    ) -> Result<Response<GetLatestBridgeBlockResponse>, Status> {
+        Err(unsupported("get_latest_bridge_block"))
+    }
+
+    async fn get_gas_price_estimate(
+        &self,
+        _: Request<GetGasPriceEstimateRequest>,
+    ) -> Result<Response<GetGasPriceEstimateResponse>, Status> {
+        Err(unsupported("get_gas_price_estimate"))
+    }
+
+    async fn get_transaction_details(
+        &self,
+        _: Request<GetTransactionDetailsRequest>,
+    ) -> Result<Response<GetTransactionDetailsResponse>, Status> {
+        Err(unsupported("get_transaction_details"))
+    }
+
+    async fn get_filtered_reservations(
+        &self,
+        _: Request<GetFilteredReservationsRequest>,
+    ) -> Result<Response<GetFilteredReservationsResponse>, Status> {
+        Err(unsupported("get_filtered_reservations"))
+    }
+
+    async fn add_reservation(
+        &self,
+        _: Request<AddReservationRequest>,
+    ) -> Result<Response<AddReservationResponse>, Status> {
+        Err(unsupported("add_reservation"))
+    }
+
+    async fn remove_reservation(
+        &self,
+        _: Request<RemoveReservationRequest>,
+    ) -> Result<Response<RemoveReservationResponse>, Status> {
+        Err(unsupported("remove_reservation"))
+    }
+
+    async fn bid(&self, _: Request<BidRequest>) -> Result<Response<BidResponse>, Status> {
+        Err(unsupported("bid"))
+    }
+
+    async fn settle(&self, _: Request<SettleRequest>) -> Result<Response<SettleResponse>, Status> {
+        Err(unsupported("settle"))
+    }
+}
+
+/// The artifact store RPCs of a mock network.
+struct MockArtifactStore(Arc<MockState>);
+
+#[tonic::async_trait]
+impl ArtifactStore for MockArtifactStore {
+    async fn create_artifact(
+        &self,
+        request: Request<CreateArtifactRequest>,
+    ) -> Result<Response<CreateArtifactResponse>, Status> {
+        let request = request.into_inner();
+        let attachment = self.0.authorize_artifact(
+            request.format,
+            &request.signature,
+            &request,
+            request.request_id.as_deref(),
+            request.artifact_type,
+            request.custom_type,
+        )?;
+        let content_encoding = request.content_encoding.clone();
+        let path = self.0.create_artifact(
+            "artifacts",
+            request.artifact_type,
+            request.sha256,
+            request.content_encoding,
+            attachment,
+        );
+        let artifact_uri = self.0.url(&path);
+        Ok(Response::new(CreateArtifactResponse {
+            artifact_presigned_url: artifact_uri.clone(),
+            artifact_uri,
+            content_encoding,
+        }))
+    }
+
+    async fn create_multipart_artifact(
+        &self,
+        request: Request<CreateMultipartArtifactRequest>,
+    ) -> Result<Response<CreateMultipartArtifactResponse>, Status> {
+        let request = request.into_inner();
+        let attachment = self.0.authorize_artifact(
+            request.format,
+            &request.signature,
+            &request,
+            request.request_id.as_deref(),
+            request.artifact_type,
+            request.custom_type,
+        )?;
+        let content_encoding = request.content_encoding.clone();
+        let path = self.0.create_artifact(
+            "artifacts",
+            request.artifact_type,
+            request.sha256,
+            request.content_encoding,
+            attachment,
+        );
+        let upload_id = utils::sha256(path.as_bytes()).to_string();
+        let upload = MultipartUpload { path: path.clone(), parts: BTreeMap::new() };
+        self.0.lock().multipart_uploads.insert(upload_id.clone(), upload);
+        Ok(Response::new(CreateMultipartArtifactResponse {
+            artifact_uri: self.0.url(&path),
+            upload_id,
+            content_encoding,
+        }))
+    }
+
+    async fn create_artifact_part(
+        &self,
+        request: Request<CreateArtifactPartRequest>,
+    ) -> Result<Response<CreateArtifactPartResponse>, Status> {
+        let request = request.into_inner();
+        if !self.0.lock().multipart_uploads.contains_key(&request.upload_id) {
+            return Err(Status::not_found("upload not found"));
+        }
+        let path = format!("/parts/{}/{}", request.upload_id, request.part_number);
+        Ok(Response::new(CreateArtifactPartResponse { part_presigned_url: self.0.url(&path) }))
+    }
+
+    async fn complete_multipart_artifact(
+        &self,
+        request: Request<CompleteMultipartArtifactRequest>,
+    ) -> Result<Response<CompleteMultipartArtifactResponse>, Status> {
+        let request = request.into_inner();
+        let mut inner = self.0.lock();
+        let upload = inner
+            .multipart_uploads
+            .remove(&request.upload_id)
+            .ok_or_else(|| Status::not_found("upload not found"))?;
+        if upload.parts.len() != request.part_etags.len() {
+            return Err(Status::invalid_argument(format!(
+                "expected {} parts, got {}",
+                upload.parts.len(),
+                request.part_etags.len()
+            )));
+        }
+        let bytes = upload.parts.into_values().flatten().collect();
+        self.0.store_artifact(&mut inner, &upload.path, bytes);
+        Ok(Response::new(CompleteMultipartArtifactResponse {}))
+    }
+
+    async fn get_artifact_by_hash(
+        &self,
+        request: Request<GetArtifactByHashRequest>,
+    ) -> Result<Response<GetArtifactByHashResponse>, Status> {
+        let request = request.into_inner();
+        let key = (request.artifact_type, request.sha256);
+        let artifact_uri = self.0.lock().artifact_hashes.get(&key).cloned();
+        Ok(Response::new(GetArtifactByHashResponse { artifact_uri }))
+    }
+
+    async fn get_auxiliary_artifact(
+        &self,
+        request: Request<GetAuxiliaryArtifactRequest>,
+    ) -> Result<Response<GetAuxiliaryArtifactResponse>, Status> {
+        let request = request.into_inner();
+        let request_id = parse_request_id(&request.request_id)?;
+        self.0.recover_typed_signer(
+            request.format,
+            &request.signature,
+            &request,
+            &auxiliary::get_message(request_id),
+        )?;
+        let key = (request_id, request.artifact_type, request.custom_type);
+        let artifact = self.0.lock().auxiliary_artifacts.get(&key).cloned();
+        let (artifact_uri, sha256) = match artifact {
+            Some((path, sha256)) => (Some(self.0.url(&path)), sha256),
+            None => (None, None),
+        };
+        Ok(Response::new(GetAuxiliaryArtifactResponse { artifact_uri, sha256 }))
+    }
+}
+
+/// Serves the artifacts of a mock network over HTTP.
+async fn serve_artifacts(state: Arc<MockState>, listener: TcpListener) {
+    loop {
+        let Ok((stream, _)) = listener.accept().await else {
+            continue;
+        };
+        let state = state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_artifact_request(&state, stream).await {
+                log::warn!("Mock artifact request failed: {e}");
+            }
+        });
+    }
+}
+
+/// Handles a single HTTP request to the artifact server, closing the connection afterwards.
+async fn handle_artifact_request(state: &MockState, stream: TcpStream) -> std::io::Result<()> {
+    let mut stream = BufReader::new(stream);
+    let mut line = String::new();
+    stream.read_line(&mut line).await?;
+    let mut parts = line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or_default(), parts.next().unwrap_or_default());
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        stream.read_line(&mut header).await?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or_default();
+            }
+        }
+    }
+
+    if method == "PUT" {
+        let dropped = {
+            let mut inner = state.lock();
+            let dropped = inner.dropped_uploads > 0;
+            inner.dropped_uploads = inner.dropped_uploads.saturating_sub(1);
+            dropped
+        };
+        if dropped {
+            // Receive part of the content, then reset the connection without responding.
+            let mut partial = vec![0; content_length / 2];
+            stream.read_exact(&mut partial).await?;
+            stream.get_ref().set_linger(Some(Duration::ZERO))?;
+            return Ok(());
+        }
+
+        let mut body = vec![0; content_length];
+        stream.read_exact(&mut body).await?;
+        let etag = format!("\"{}\"", utils::sha256(&body));
+        let stored = {
+            let mut inner = state.lock();
+            if let Some((upload_id, part_number)) =
+                path.strip_prefix("/parts/").and_then(|part| part.split_once('/'))
+            {
+                let part_number = part_number.parse().unwrap_or_default();
+                inner
+                    .multipart_uploads
+                    .get_mut(upload_id)
+                    .map(|upload| upload.parts.insert(part_number, body))
+                    .is_some()
+            } else if inner.pending_artifacts.contains_key(path) {
+                state.store_artifact(&mut inner, path, body);
+                true
+            } else {
+                false
+            }
+        };
+        return if stored {
+            respond(stream.get_mut(), "200 OK", &[("ETag", &etag)], &[]).await
+        } else {
+            respond(stream.get_mut(), "404 Not Found", &[], &[]).await
+        };
+    }
+
+    let artifact = {
+        let inner = state.lock();
+        if path.starts_with("/proofs/") {
+            Some(StoredArtifact { bytes: inner.proof.clone(), content_encoding: None })
+        } else {
+            inner.artifacts.get(path).cloned()
+        }
+    };
+    match artifact {
+        Some(StoredArtifact { bytes, content_encoding: Some(encoding) }) if method == "GET" => {
+            respond(stream.get_mut(), "200 OK", &[("Content-Encoding", &encoding)], &bytes).await
+        }
+        Some(StoredArtifact { bytes, content_encoding: None }) if method == "GET" => {
+            respond(stream.get_mut(), "200 OK", &[], &bytes).await
+        }
+        _ => respond(stream.get_mut(), "404 Not Found", &[], &[]).await,
+    }
+}
+
+/// Writes an HTTP response.
+async fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+) -> std::io::Result<()> {
+    let mut head =
+        format!("HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n", body.len());
+    for (name, value) in headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_signer_local::PrivateKeySigner;
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::network::{
+        AuxiliaryArtifactType, Compression, FulfillmentParams, NetworkClient, PollingConfig,
+        ProofRequestFilter, RetryPolicy,
+    };
+    use crate::{NetworkProver, SP1Stdin};
+
+    const PRIVATE_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+    #[tokio::test]
+    async fn test_request_proof() {
+        let server = MockNetworkServer::start().await.unwrap();
+        let vk_hash = B256::repeat_byte(1);
+        server.insert_program(vk_hash);
+        let canned = SP1ProofWithPublicValues {
+            proof: SP1Proof::Core(Vec::new()),
+            public_values: SP1PublicValues::from(&[1, 2, 3]),
+            sp1_version: SP1_CIRCUIT_VERSION.to_string(),
+        };
+        server.set_proof(&canned);
+        server.push_outcome(MockOutcome::Fulfilled { delay: Duration::from_millis(100) });
+
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&42u32);
+        let prover = NetworkProver::new(PRIVATE_KEY, server.rpc_url());
+        let proof = prover.prove_with_vk_hash(vk_hash, stdin.clone()).run_async().await.unwrap();
+        assert_eq!(proof.public_values, canned.public_values);
+        assert_eq!(proof.sp1_version, canned.sp1_version);
+
+        let requests = server.proof_requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].vk_hash, vk_hash.to_vec());
+        let uploaded = server.artifact(&requests[0].stdin_uri).unwrap();
+        assert_eq!(uploaded, encode_artifact_content(&stdin).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_proof_request() {
+        let server = MockNetworkServer::start().await.unwrap();
+        let vk_hash = B256::repeat_byte(1);
+        server.insert_program(vk_hash);
+        server.push_outcome(MockOutcome::Pending);
+
+        let prover = NetworkProver::new(PRIVATE_KEY, server.rpc_url());
+        let request_id =
+            prover.prove_with_vk_hash(vk_hash, SP1Stdin::new()).request_async().await.unwrap();
+        let client = NetworkClient::new(PRIVATE_KEY, server.rpc_url()).unwrap();
+        client.cancel_proof_request(request_id).await.unwrap();
+
+        let (status, _) = client
+            .get_proof_request_status::<SP1ProofWithPublicValues>(request_id, None)
+            .await
+            .unwrap();
+        assert_eq!(status.fulfillment_status(), FulfillmentStatus::Unfulfillable);
+        assert_eq!(status.failure_reason.as_deref(), Some("the request was canceled"));
+
+        // A cancelled request can not be cancelled again.
+        let err = client.cancel_proof_request(request_id).await;
+        assert!(matches!(err, Err(Error::RequestUnfulfillable { .. })), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn test_cancel_fulfilled_proof_request() {
+        let server = MockNetworkServer::start().await.unwrap();
+        let vk_hash = B256::repeat_byte(1);
+        server.insert_program(vk_hash);
+
+        let prover = NetworkProver::new(PRIVATE_KEY, server.rpc_url());
+        let request_id =
+            prover.prove_with_vk_hash(vk_hash, SP1Stdin::new()).request_async().await.unwrap();
+        let client = NetworkClient::new(PRIVATE_KEY, server.rpc_url()).unwrap();
+        let err = client.cancel_proof_request(request_id).await;
+        assert!(matches!(err, Err(Error::RequestAlreadyFulfilled { .. })), "{err:?}");
+
+        // The request is still fulfilled.
+        let (status, proof) = client
+            .get_proof_request_status::<SP1ProofWithPublicValues>(request_id, None)
+            .await
+            .unwrap();
+        assert_eq!(status.fulfillment_status(), FulfillmentStatus::Fulfilled);
+        assert!(proof.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_eip712_signatures() {
+        let server = MockNetworkServer::start().await.unwrap();
+        server.set_capabilities(&[EIP712_CAPABILITY]);
+        let vk_hash = B256::repeat_byte(1);
+        server.insert_program(vk_hash);
+
+        // The stdin and the request are signed as typed data.
+        let prover = NetworkProver::new(PRIVATE_KEY, server.rpc_url());
+        prover.prove_with_vk_hash(vk_hash, SP1Stdin::new()).run_async().await.unwrap();
+        assert_eq!(server.proof_requests().len(), 1);
+
+        let client = NetworkClient::new(PRIVATE_KEY, server.rpc_url()).unwrap();
+        let params = FulfillmentParams::default();
+        let simulated = client
+            .simulate_proof_request(
+                vk_hash,
+                &SP1Stdin::new(),
+                ProofMode::Core,
+                SP1_CIRCUIT_VERSION,
+                FulfillmentStrategy::Hosted,
+                3600,
+                1_000_000,
+                &params,
+            )
+            .await
+            .unwrap();
+        assert_eq!(simulated.request.format, MessageFormat::Eip712 as i32);
+        assert_eq!(simulated.signer().unwrap(), client.address());
+        assert!(simulated.validated);
+
+        // Legacy signatures are rejected once the network requires typed signatures.
+        let unsigned = client
+            .prepare_proof_request(
+                client.address(),
+                vk_hash,
+                &SP1Stdin::new(),
+                ProofMode::Core,
+                SP1_CIRCUIT_VERSION,
+                FulfillmentStrategy::Hosted,
+                3600,
+                1_000_000,
+                &params,
+            )
+            .await
+            .unwrap();
+        let signer: PrivateKeySigner = PRIVATE_KEY.parse().unwrap();
+        let err = client.submit_signed_proof_request(unsigned.sign(&signer).unwrap()).await;
+        assert!(matches!(err, Err(Error::SignatureRejected { .. })), "{err:?}");
+        let signed = unsigned.sign_typed(&signer, &signing_domain(DEFAULT_DOMAIN_NAME)).unwrap();
+        client.submit_signed_proof_request(signed).await.unwrap();
+        assert_eq!(server.proof_requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_eip712_domain_mismatch() {
+        let server = MockNetworkServer::start().await.unwrap();
+        server.set_capabilities(&[EIP712_CAPABILITY]);
+        let vk_hash = B256::repeat_byte(1);
+        server.insert_program(vk_hash);
+
+        // Requests signed for another network are attributed to another requester.
+        let client = NetworkClient::new(PRIVATE_KEY, server.rpc_url())
+            .unwrap()
+            .with_eip712_domain_name("Succinct Prover Network Testnet");
+        let params = FulfillmentParams::default();
+        let request = client
+            .simulate_proof_request(
+                vk_hash,
+                &SP1Stdin::new(),
+                ProofMode::Core,
+                SP1_CIRCUIT_VERSION,
+                FulfillmentStrategy::Hosted,
+                3600,
+                1_000_000,
+                &params,
+            )
+            .await
+            .unwrap()
+            .request;
+        let body = request.body.as_ref().unwrap();
+        let recovered = parse_signature(&request.signature)
+            .unwrap()
+            .recover_address_from_prehash(
+                &body.eip712_signing_hash(&signing_domain(DEFAULT_DOMAIN_NAME)),
+            )
+            .unwrap();
+        assert_ne!(recovered, client.address());
+    }
+
+    #[tokio::test]
+    async fn test_auxiliary_artifacts() {
+        let server = MockNetworkServer::start().await.unwrap();
+        server.set_capabilities(&[auxiliary::AUXILIARY_ARTIFACTS_CAPABILITY]);
+        let vk_hash = B256::repeat_byte(1);
+        server.insert_program(vk_hash);
+        server.set_default_outcome(MockOutcome::Pending);
+        let prover = NetworkProver::new(PRIVATE_KEY, server.rpc_url());
+        let request_id =
+            prover.prove_with_vk_hash(vk_hash, SP1Stdin::new()).request_async().await.unwrap();
+
+        // Large artifacts are uploaded in parts, and attached once complete.
+        let client =
+            NetworkClient::new(PRIVATE_KEY, server.rpc_url()).unwrap().with_artifact_chunk_size(4);
+        let report = b"cycles: 1000000".to_vec();
+        client
+            .upload_auxiliary(request_id, AuxiliaryArtifactType::ExecutionReport, &report)
+            .await
+            .unwrap();
+        client
+            .upload_auxiliary(request_id, AuxiliaryArtifactType::Custom(1), b"one")
+            .await
+            .unwrap();
+        let download = |artifact_type| client.download_auxiliary(request_id, artifact_type);
+        assert_eq!(download(AuxiliaryArtifactType::ExecutionReport).await.unwrap(), Some(report));
+        assert_eq!(
+            download(AuxiliaryArtifactType::Custom(1)).await.unwrap(),
+            Some(b"one".to_vec())
+        );
+        assert_eq!(download(AuxiliaryArtifactType::Custom(2)).await.unwrap(), None);
+        assert_eq!(download(AuxiliaryArtifactType::Trace).await.unwrap(), None);
+
+        // Only the requester can attach artifacts.
+        let other =
+            NetworkClient::with_signer(PrivateKeySigner::random(), server.rpc_url()).unwrap();
+        let err = other.upload_auxiliary(request_id, AuxiliaryArtifactType::Trace, b"forged").await;
+        assert!(matches!(err, Err(Error::SignatureRejected { .. })), "{err:?}");
+        assert_eq!(download(AuxiliaryArtifactType::Trace).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_auxiliary_artifacts_unsupported() {
+        let server = MockNetworkServer::start().await.unwrap();
+        let client = NetworkClient::new(PRIVATE_KEY, server.rpc_url()).unwrap();
+        let request_id = B256::repeat_byte(1);
+        let err = client.upload_auxiliary(request_id, AuxiliaryArtifactType::Trace, b"trace").await;
+        assert!(matches!(err, Err(Error::UnsupportedCapability { .. })), "{err:?}");
+        let err = client.download_auxiliary(request_id, AuxiliaryArtifactType::Trace).await;
+        assert!(matches!(err, Err(Error::UnsupportedCapability { .. })), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn test_unfulfillable_request() {
+        let server = MockNetworkServer::start().await.unwrap();
+        let vk_hash = B256::repeat_byte(1);
+        server.insert_program(vk_hash);
+        let reason = "out of capacity".to_string();
+        server.push_outcome(MockOutcome::Unfulfillable { delay: Duration::ZERO, reason });
+
+        let prover = NetworkProver::new(PRIVATE_KEY, server.rpc_url());
+        let err =
+            prover.prove_with_vk_hash(vk_hash, SP1Stdin::new()).run_async().await.unwrap_err();
+        match err.downcast_ref::<Error>() {
+            Some(Error::RequestUnfulfillable { reason, .. }) => {
+                assert_eq!(reason.as_deref(), Some("out of capacity"));
+            }
+            _ => panic!("unexpected error: {err:#}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_program_not_found() {
+        let server = MockNetworkServer::start().await.unwrap();
+        let vk_hash = B256::repeat_byte(1);
+        server.insert_program(vk_hash);
+        server.remove_program(vk_hash);
+
+        let client = NetworkClient::new(PRIVATE_KEY, server.rpc_url()).unwrap();
+        assert!(client.get_program(vk_hash).await.unwrap().is_none());
+
+        let prover = NetworkProver::new(PRIVATE_KEY, server.rpc_url());
+        let err =
+            prover.prove_with_vk_hash(vk_hash, SP1Stdin::new()).run_async().await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<Error>(), Some(Error::ProgramNotFound { .. })));
+        assert!(server.proof_requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dropped_upload_is_retried() {
+        let server = MockNetworkServer::start().await.unwrap();
+        let vk_hash = B256::repeat_byte(1);
+        server.insert_program(vk_hash);
+        server.drop_uploads(1);
+
+        let mut stdin = SP1Stdin::new();
+        stdin.write_vec(vec![7; 1 << 16]);
+        let prover = NetworkProver::new(PRIVATE_KEY, server.rpc_url());
+        prover.prove_with_vk_hash(vk_hash, stdin.clone()).run_async().await.unwrap();
+
+        let stdin_uri = &server.proof_requests()[0].stdin_uri;
+        let uploaded = server.artifact(stdin_uri).unwrap();
+        assert_eq!(uploaded, encode_artifact_content(&stdin).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_chunked_upload() {
+        let server = MockNetworkServer::start().await.unwrap();
+        let vk_hash = B256::repeat_byte(1);
+        server.insert_program(vk_hash);
+        server.drop_uploads(1);
+
+        // The stdin is uploaded in parts of 4KiB, and only the dropped part is uploaded again.
+        let progress = Arc::new(Mutex::new(Vec::new()));
+        let reported = progress.clone();
+        let client = NetworkClient::new(PRIVATE_KEY, server.rpc_url())
+            .unwrap()
+            .with_artifact_compression(Compression::None)
+            .with_artifact_chunk_size(1 << 12)
+            .with_upload_progress(move |uploaded, total| {
+                reported.lock().unwrap().push((uploaded, total));
+            });
+        let mut stdin = SP1Stdin::new();
+        stdin.write_vec(vec![7; 1 << 16]);
+        let prover = NetworkProver::with_client(client);
+        prover.prove_with_vk_hash(vk_hash, stdin.clone()).run_async().await.unwrap();
+
+        let stdin_uri = &server.proof_requests()[0].stdin_uri;
+        let uploaded = server.artifact(stdin_uri).unwrap();
+        assert_eq!(uploaded, encode_artifact_content(&stdin).unwrap());
+
+        // The progress is reported once before the upload and after each part.
+        let progress = progress.lock().unwrap();
+        let total = uploaded.len() as u64;
+        assert_eq!(progress.len(), 1 + total.div_ceil(1 << 12) as usize);
+        assert_eq!(progress.first(), Some(&(0, total)));
+        assert_eq!(progress.last(), Some(&(total, total)));
+        assert!(progress.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[tokio::test]
+    async fn test_chunked_upload_failure() {
+        let server = MockNetworkServer::start().await.unwrap();
+        let vk_hash = B256::repeat_byte(1);
+        server.insert_program(vk_hash);
+        server.drop_uploads(1);
+
+        // Without retries, a dropped part fails the upload, and the request is not submitted.
+        let client = NetworkClient::new(PRIVATE_KEY, server.rpc_url())
+            .unwrap()
+            .with_artifact_compression(Compression::None)
+            .with_artifact_chunk_size(1 << 12)
+            .with_retry_policy(RetryPolicy { max_attempts: Some(1), ..Default::default() });
+        let mut stdin = SP1Stdin::new();
+        stdin.write_vec(vec![7; 1 << 16]);
+        let prover = NetworkProver::with_client(client);
+        let result = prover.prove_with_vk_hash(vk_hash, stdin).run_async().await;
+        assert!(result.is_err());
+        assert!(server.proof_requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stream_filtered_proof_requests() {
+        let server = MockNetworkServer::start().await.unwrap();
+        let vk_hash = B256::repeat_byte(1);
+        server.insert_program(vk_hash);
+        server.set_default_outcome(MockOutcome::Pending);
+        let prover = NetworkProver::new(PRIVATE_KEY, server.rpc_url());
+        let mut request_ids = Vec::new();
+        for _ in 0..5 {
+            let request_id =
+                prover.prove_with_vk_hash(vk_hash, SP1Stdin::new()).request_async().await.unwrap();
+            request_ids.push(request_id.to_vec());
+        }
+
+        // The requests are fetched over three pages, the last one partial.
+        let client = NetworkClient::new(PRIVATE_KEY, server.rpc_url()).unwrap();
+        let filter = ProofRequestFilter::new().requester(client.address()).limit(2);
+        let requests: Vec<_> =
+            client.stream_filtered_proof_requests(filter).try_collect().await.unwrap();
+        let streamed: Vec<_> = requests.into_iter().map(|request| request.request_id).collect();
+        assert_eq!(streamed, request_ids);
+
+        // A full last page is followed by an empty one.
+        let filter = ProofRequestFilter::new().vk_hash(vk_hash).limit(5);
+        let requests: Vec<_> =
+            client.stream_filtered_proof_requests(filter).try_collect().await.unwrap();
+        assert_eq!(requests.len(), 5);
+
+        let filter = ProofRequestFilter::new().vk_hash(B256::repeat_byte(2));
+        let requests: Vec<_> =
+            client.stream_filtered_proof_requests(filter).try_collect().await.unwrap();
+        assert!(requests.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stream_filtered_proof_requests_error() {
+        let server = MockNetworkServer::start().await.unwrap();
+        let client = NetworkClient::new(PRIVATE_KEY, server.rpc_url()).unwrap();
+
+        // The stream yields the error of the failed page and ends.
+        let filter = ProofRequestFilter::new().label("team", "prover");
+        let results: Vec<_> = client.stream_filtered_proof_requests(filter).collect().await;
+        assert_eq!(results.len(), 1);
+        let err = results.into_iter().next().unwrap().map_err(Error::from);
+        assert!(matches!(err, Err(Error::UnsupportedCapability { .. })), "{err:?}");
+    }
+
+    /// Submits a request that is fulfilled after the given delay, and returns a client that
+    /// retries and polls quickly, with the given maximum number of attempts.
+    async fn delayed_request(
+        server: &MockNetworkServer,
+        delay: Duration,
+        max_attempts: Option<u32>,
+    ) -> (NetworkClient, B256) {
+        let vk_hash = B256::repeat_byte(1);
+        server.insert_program(vk_hash);
+        server.push_outcome(MockOutcome::Fulfilled { delay });
+        let prover = NetworkProver::new(PRIVATE_KEY, server.rpc_url());
+        let request_id =
+            prover.prove_with_vk_hash(vk_hash, SP1Stdin::new()).request_async().await.unwrap();
+        let client = NetworkClient::new(PRIVATE_KEY, server.rpc_url())
+            .unwrap()
+            .with_retry_policy(RetryPolicy {
+                max_attempts,
+                initial_backoff: Duration::from_millis(10),
+                ..Default::default()
+            })
+            .with_polling_config(PollingConfig {
+                initial_interval: Duration::from_millis(50),
+                ..Default::default()
+            });
+        (client, request_id)
+    }
+
+    #[tokio::test]
+    async fn test_status_stream_resubscribed() {
+        let server = MockNetworkServer::start().await.unwrap();
+        let (client, request_id) = delayed_request(&server, Duration::from_millis(500), None).await;
+
+        // The stream is resubscribed to after it ends and after it fails, and the repeated
+        // statuses are not yielded again.
+        server.end_status_streams(1);
+        server.fail_status_streams(1);
+        let statuses: Vec<_> =
+            client.subscribe_proof_request_status(request_id).try_collect().await.unwrap();
+        let statuses: Vec<_> = statuses.iter().map(|status| status.fulfillment_status()).collect();
+        assert_eq!(statuses, [FulfillmentStatus::Requested, FulfillmentStatus::Fulfilled]);
+        assert_eq!(server.status_subscriptions(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_status_stream_falls_back_to_polling() {
+        let server = MockNetworkServer::start().await.unwrap();
+        let (client, request_id) =
+            delayed_request(&server, Duration::from_millis(500), Some(3)).await;
+
+        // Once the retry policy gives up on the stream, the status is polled instead.
+        server.fail_status_streams(100);
+        let statuses: Vec<_> =
+            client.subscribe_proof_request_status(request_id).try_collect().await.unwrap();
+        let statuses: Vec<_> = statuses.iter().map(|status| status.fulfillment_status()).collect();
+        assert_eq!(statuses, [FulfillmentStatus::Requested, FulfillmentStatus::Fulfilled]);
+        assert_eq!(server.status_subscriptions(), 3);
+    }
+}
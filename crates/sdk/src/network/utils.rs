@@ -4,15 +4,215 @@
 
 #![allow(deprecated)]
 
-use alloy_signer::{Signature, SignerSync};
+use alloy_primitives::{Address, B256};
+use alloy_signer::{Signature, Signer, SignerSync};
+use alloy_sol_types::Eip712Domain;
+use p3_baby_bear::BabyBear;
 use prost::Message;
+use sha2::{Digest, Sha256};
+use sp1_core_executor::Program;
+use sp1_prover::SP1VerifyingKey;
+use sp1_stark::air::MachineProgram;
+
+use super::eip712::TypedMessage;
+use super::Error;
+
+/// A signer that can be used to sign requests sent to the network.
+///
+/// # Details
+/// This trait is implemented for every signer that implements both [`Signer`] and [`SignerSync`],
+/// such as [`alloy_signer_local::PrivateKeySigner`].
+pub trait NetworkSigner: SignerSync + Send + Sync {
+    /// The address of the signer.
+    fn address(&self) -> Address;
+}
+
+impl<S: Signer + SignerSync + Send + Sync> NetworkSigner for S {
+    fn address(&self) -> Address {
+        Signer::address(self)
+    }
+}
 
 pub(crate) trait Signable: Message {
-    fn sign<S: SignerSync>(&self, signer: &S) -> Signature;
+    fn sign<S: SignerSync + ?Sized>(&self, signer: &S) -> alloy_signer::Result<Signature>;
+
+    /// Signs the message as EIP-712 typed data in the given domain.
+    fn sign_typed<S: SignerSync + ?Sized>(
+        &self,
+        signer: &S,
+        domain: &Eip712Domain,
+    ) -> alloy_signer::Result<Signature>
+    where
+        Self: TypedMessage;
 }
 
 impl<T: Message> Signable for T {
-    fn sign<S: SignerSync>(&self, signer: &S) -> Signature {
-        signer.sign_message_sync(&self.encode_to_vec()).unwrap()
+    fn sign<S: SignerSync + ?Sized>(&self, signer: &S) -> alloy_signer::Result<Signature> {
+        signer.sign_message_sync(&self.encode_to_vec())
+    }
+
+    fn sign_typed<S: SignerSync + ?Sized>(
+        &self,
+        signer: &S,
+        domain: &Eip712Domain,
+    ) -> alloy_signer::Result<Signature>
+    where
+        Self: TypedMessage,
+    {
+        signer.sign_hash_sync(&self.eip712_signing_hash(domain))
+    }
+}
+
+/// Computes the SHA-256 hash of the given bytes.
+pub(crate) fn sha256(bytes: &[u8]) -> B256 {
+    B256::from_slice(&Sha256::digest(bytes))
+}
+
+/// Computes the SHA-256 hash of the concatenation of the given segments.
+pub(crate) fn sha256_segments(segments: &[&[u8]]) -> B256 {
+    let mut hasher = Sha256::new();
+    for segment in segments {
+        hasher.update(segment);
+    }
+    B256::from_slice(&hasher.finalize())
+}
+
+/// Computes the SHA-256 hash of the given bytes and checks it against the expected hash, if any.
+pub(crate) fn verify_sha256(bytes: &[u8], expected: Option<&[u8]>) -> Result<B256, Error> {
+    let actual = sha256(bytes);
+    match expected {
+        Some(expected) if expected != actual.as_slice() => {
+            Err(Error::ChecksumMismatch { expected: expected.to_vec(), actual: actual.to_vec() })
+        }
+        _ => Ok(actual),
+    }
+}
+
+/// Checks that the ELF of a program matches its verifying key.
+///
+/// # Details
+/// This compares the start pc and the commitment to the initial memory image in the verifying key
+/// with the ones derived from the ELF, which does not require running the setup.
+pub(crate) fn verify_program_vk(vk: &SP1VerifyingKey, elf: &[u8]) -> Result<(), Error> {
+    let program = Program::from(elf)
+        .map_err(|e| Error::InvalidRequest { reason: format!("failed to decode ELF: {e}") })?;
+
+    let pc_start = MachineProgram::<BabyBear>::pc_start(&program);
+    if pc_start != vk.vk.pc_start {
+        return Err(Error::ProgramVkMismatch {
+            reason: format!(
+                "the ELF starts at pc {}, but the verifying key starts at pc {}",
+                pc_start, vk.vk.pc_start
+            ),
+        });
+    }
+    let initial_global_cumulative_sum =
+        MachineProgram::<BabyBear>::initial_global_cumulative_sum(&program);
+    if initial_global_cumulative_sum != vk.vk.initial_global_cumulative_sum {
+        return Err(Error::ProgramVkMismatch {
+            reason: "the initial memory image of the ELF differs from the verifying key".into(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use alloy_primitives::{ChainId, B256};
+    use alloy_signer_local::PrivateKeySigner;
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::network::proto::network::RequestProofRequestBody;
+    use crate::{Prover, ProverClient};
+
+    /// A signer that delegates to a local key, standing in for a remote signer (e.g. a KMS).
+    struct MockSigner {
+        inner: PrivateKeySigner,
+        signatures: AtomicUsize,
+    }
+
+    impl SignerSync for MockSigner {
+        fn sign_hash_sync(&self, hash: &B256) -> alloy_signer::Result<Signature> {
+            self.signatures.fetch_add(1, Ordering::SeqCst);
+            self.inner.sign_hash_sync(hash)
+        }
+
+        fn chain_id_sync(&self) -> Option<ChainId> {
+            None
+        }
+    }
+
+    #[async_trait]
+    impl Signer for MockSigner {
+        async fn sign_hash(&self, hash: &B256) -> alloy_signer::Result<Signature> {
+            self.sign_hash_sync(hash)
+        }
+
+        fn address(&self) -> Address {
+            self.inner.address()
+        }
+
+        fn chain_id(&self) -> Option<ChainId> {
+            None
+        }
+
+        fn set_chain_id(&mut self, _chain_id: Option<ChainId>) {}
+    }
+
+    #[test]
+    fn test_sign_with_custom_signer() {
+        let signer: Arc<dyn NetworkSigner> = Arc::new(MockSigner {
+            inner: PrivateKeySigner::random(),
+            signatures: AtomicUsize::new(0),
+        });
+        let body = RequestProofRequestBody {
+            nonce: 1,
+            version: "sp1-v4.0.0-rc.3".to_string(),
+            stdin_uri: "s3://stdin".to_string(),
+            cycle_limit: 1_000_000,
+            ..Default::default()
+        };
+
+        let signature = body.sign(signer.as_ref()).unwrap();
+        let recovered = signature.recover_address_from_msg(body.encode_to_vec()).unwrap();
+        assert_eq!(recovered, signer.address());
+    }
+
+    #[test]
+    fn test_custom_signer_is_used() {
+        let signer =
+            MockSigner { inner: PrivateKeySigner::random(), signatures: AtomicUsize::new(0) };
+        let body = RequestProofRequestBody::default();
+        body.sign(&signer).unwrap();
+        body.sign(&signer).unwrap();
+        assert_eq!(signer.signatures.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_verify_sha256() {
+        let bytes = b"proof";
+        let hash = sha256(bytes);
+        assert_eq!(verify_sha256(bytes, None).unwrap(), hash);
+        assert_eq!(verify_sha256(bytes, Some(hash.as_slice())).unwrap(), hash);
+        assert!(matches!(
+            verify_sha256(b"proo", Some(hash.as_slice())),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_verify_program_vk() {
+        let client = ProverClient::builder().cpu().build();
+        let (_, vk) = client.setup(test_artifacts::FIBONACCI_ELF);
+
+        verify_program_vk(&vk, test_artifacts::FIBONACCI_ELF).unwrap();
+        assert!(matches!(
+            verify_program_vk(&vk, test_artifacts::PANIC_ELF),
+            Err(Error::ProgramVkMismatch { .. })
+        ));
     }
 }
@@ -0,0 +1,159 @@
+//! # Webhooks
+//!
+//! This module provides the payload the network sends to the callback URL of a proof request once
+//! it is fulfilled, and the verification of its signature, so that receivers can authenticate
+//! callbacks before acting on them.
+
+use alloy_primitives::{Address, Bytes, B256};
+use alloy_signer::Signature;
+use serde::{Deserialize, Serialize};
+
+use super::Error;
+
+/// The notification sent to the callback URL of a proof request once it is fulfilled.
+///
+/// # Details
+/// The network signs [`ProofFulfilledNotification::signing_message`] with its key, as an EIP-191
+/// personal message. Anyone can send a request to a callback URL, so receivers must check the
+/// signature with [`verify_notification`] before trusting the notification.
+///
+/// The notification is sent as a JSON body, with the hashes and the signature hex-encoded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofFulfilledNotification {
+    /// The ID of the fulfilled request.
+    pub request_id: B256,
+    /// The URI of the proof artifact.
+    pub proof_uri: String,
+    /// The hash of the public values of the proof.
+    pub public_values_hash: B256,
+    /// The signature of the network over the notification.
+    pub signature: Bytes,
+}
+
+impl ProofFulfilledNotification {
+    /// The message signed by the network: the request ID, the public values hash and the proof
+    /// URI, concatenated.
+    #[must_use]
+    pub fn signing_message(&self) -> Vec<u8> {
+        let mut message = Vec::with_capacity(64 + self.proof_uri.len());
+        message.extend_from_slice(self.request_id.as_slice());
+        message.extend_from_slice(self.public_values_hash.as_slice());
+        message.extend_from_slice(self.proof_uri.as_bytes());
+        message
+    }
+}
+
+/// Checks that a notification was signed by the network.
+///
+/// # Details
+/// `network_pubkey` is the address of the key the network signs notifications with. Returns
+/// [`Error::InvalidNotification`] if the signature is malformed or was made by another key, e.g.
+/// because the notification was forged or modified.
+///
+/// # Example
+/// ```rust,no_run
+/// use sp1_sdk::network::webhook::{verify_notification, ProofFulfilledNotification};
+/// use sp1_sdk::network::Error;
+///
+/// fn handle_callback(body: &[u8]) -> Result<(), Error> {
+///     let network = "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".parse().unwrap();
+///     let notification: ProofFulfilledNotification = serde_json::from_slice(body).unwrap();
+///     verify_notification(&notification, network)?;
+///     // The proof of `notification.request_id` can now be downloaded.
+///     Ok(())
+/// }
+/// ```
+pub fn verify_notification(
+    payload: &ProofFulfilledNotification,
+    network_pubkey: Address,
+) -> Result<(), Error> {
+    let signature = Signature::try_from(payload.signature.as_ref())
+        .map_err(|e| Error::InvalidNotification { reason: format!("malformed signature: {e}") })?;
+    let signer = signature.recover_address_from_msg(payload.signing_message()).map_err(|e| {
+        Error::InvalidNotification { reason: format!("failed to recover the signer: {e}") }
+    })?;
+    if signer != network_pubkey {
+        return Err(Error::InvalidNotification {
+            reason: format!("signed by {signer}, but expected {network_pubkey}"),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_signer::SignerSync;
+    use alloy_signer_local::PrivateKeySigner;
+
+    use super::*;
+
+    /// A notification signed by the key of the test account
+    /// 0x2c7536E3605D9C16a7a3D7b1898e529396a65c23.
+    const FIXTURE: &str = r#"{
+        "request_id": "0x1111111111111111111111111111111111111111111111111111111111111111",
+        "proof_uri": "s3://proofs/0x1111111111111111111111111111111111111111111111111111111111111111",
+        "public_values_hash": "0x2222222222222222222222222222222222222222222222222222222222222222",
+        "signature": "0xb0f504cccbba674d04f57065d7d8776f78c00928e40c4d354d42cb2225649b9414820f6f1e02f1c2297d0c6257f2014c4ba553594b491c0689521255b8c36d9c1c"
+    }"#;
+
+    const NETWORK_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+    fn network_address() -> Address {
+        "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".parse().unwrap()
+    }
+
+    fn fixture() -> ProofFulfilledNotification {
+        serde_json::from_str(FIXTURE).unwrap()
+    }
+
+    #[test]
+    fn test_verify_fixture() {
+        let notification = fixture();
+        assert_eq!(notification.request_id, B256::repeat_byte(0x11));
+        assert_eq!(notification.public_values_hash, B256::repeat_byte(0x22));
+        assert!(verify_notification(&notification, network_address()).is_ok());
+    }
+
+    #[test]
+    fn test_reject_wrong_key() {
+        let err = verify_notification(&fixture(), Address::repeat_byte(1)).unwrap_err();
+        assert!(matches!(err, Error::InvalidNotification { .. }), "{err}");
+    }
+
+    #[test]
+    fn test_reject_modified_notification() {
+        let mut notification = fixture();
+        notification.proof_uri = "s3://proofs/forged".to_string();
+        assert!(verify_notification(&notification, network_address()).is_err());
+
+        let mut notification = fixture();
+        notification.public_values_hash = B256::repeat_byte(0x33);
+        assert!(verify_notification(&notification, network_address()).is_err());
+    }
+
+    #[test]
+    fn test_reject_malformed_signature() {
+        let mut notification = fixture();
+        notification.signature = Bytes::from_static(&[1, 2, 3]);
+        let err = verify_notification(&notification, network_address()).unwrap_err();
+        assert!(matches!(err, Error::InvalidNotification { .. }), "{err}");
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let signer: PrivateKeySigner = NETWORK_KEY.parse().unwrap();
+        assert_eq!(signer.address(), network_address());
+        let mut notification = ProofFulfilledNotification {
+            request_id: B256::repeat_byte(5),
+            proof_uri: "s3://proofs/5".to_string(),
+            public_values_hash: B256::repeat_byte(6),
+            signature: Bytes::new(),
+        };
+        let signature = signer.sign_message_sync(&notification.signing_message()).unwrap();
+        notification.signature = Bytes::from(signature.as_bytes().to_vec());
+
+        let json = serde_json::to_string(&notification).unwrap();
+        let decoded: ProofFulfilledNotification = serde_json::from_str(&json).unwrap();
+        assert!(verify_notification(&decoded, signer.address()).is_ok());
+    }
+}
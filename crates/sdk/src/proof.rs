@@ -3,22 +3,30 @@
 //! A library of types and functions for SP1 proofs.
 #![allow(missing_docs)]
 
-use std::{fmt::Debug, fs::File, path::Path};
+use std::{fmt::Debug, fs, path::Path};
 
-use anyhow::Result;
 use hashbrown::HashMap;
 use p3_baby_bear::BabyBear;
 use p3_field::PrimeField;
 use p3_field::{extension::BinomialExtensionField, AbstractField};
 use p3_fri::{FriProof, TwoAdicFriPcsProof};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use sp1_core_executor::SP1ReduceProof;
 use sp1_primitives::io::SP1PublicValues;
-use sp1_prover::{CoreSC, Groth16Bn254Proof, HashableKey, InnerSC, PlonkBn254Proof, SP1ProvingKey};
+use sp1_prover::{
+    CoreSC, Groth16Bn254Proof, HashableKey, InnerSC, PlonkBn254Proof, SP1ProvingKey,
+    SP1_CIRCUIT_VERSION,
+};
 use sp1_stark::{
     septic_digest::SepticDigest, ShardCommitment, ShardOpenedValues, ShardProof, StarkVerifyingKey,
 };
+use sp1_verifier::{MockVerifier, MOCK_PROOF_SELECTOR};
 use strum_macros::{EnumDiscriminants, EnumTryAs};
+use thiserror::Error;
+
+/// The magic bytes at the start of a file written by [`SP1ProofWithPublicValues::save`].
+const PROOF_FILE_MAGIC: &[u8; 8] = b"SP1PROOF";
 
 /// A proof generated by the SP1 RISC-V zkVM.
 #[derive(Debug, Clone, Serialize, Deserialize, EnumDiscriminants, EnumTryAs)]
@@ -55,15 +63,147 @@ pub struct SP1ProofWithPublicValues {
 
 impl SP1ProofWithPublicValues {
     /// Saves the proof to a path.
-    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
-        bincode::serialize_into(File::create(path).expect("failed to open file"), self)
-            .map_err(Into::into)
+    ///
+    /// # Details
+    /// The proof is written in an envelope recording the version of SP1 that wrote it, the proof
+    /// mode and the SHA-256 hash of the bincode-encoded proof, so that [`Self::load`] can reject
+    /// files written by an incompatible version of SP1 or corrupted on disk.
+    ///
+    /// # Errors
+    /// Returns an error if the proof can't be encoded or the file can't be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ProofFileError> {
+        let payload = bincode::serialize(self)?;
+        let header = ProofFileHeader {
+            sp1_version: SP1_CIRCUIT_VERSION.to_string(),
+            mode: SP1ProofMode::from(&self.proof) as u8,
+            payload_hash: Sha256::digest(&payload).into(),
+        };
+        let mut bytes = PROOF_FILE_MAGIC.to_vec();
+        bincode::serialize_into(&mut bytes, &header)?;
+        bytes.extend_from_slice(&payload);
+        fs::write(path, bytes)?;
+        Ok(())
     }
 
     /// Loads a proof from a path.
-    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
-        bincode::deserialize_from(File::open(path).expect("failed to open file"))
-            .map_err(Into::into)
+    ///
+    /// # Details
+    /// Files written by versions of SP1 that predate the envelope written by [`Self::save`] are
+    /// still accepted, with the version recorded in the proof itself checked instead.
+    ///
+    /// # Errors
+    /// Returns [`ProofFileError::ProofFileVersionMismatch`] if the file was written by a version
+    /// of SP1 other than [`SP1_CIRCUIT_VERSION`], and [`ProofFileError::Corrupted`] if the proof
+    /// does not match the envelope.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ProofFileError> {
+        let bytes = fs::read(path)?;
+        let Some(mut rest) = bytes.strip_prefix(PROOF_FILE_MAGIC) else {
+            let proof: Self = bincode::deserialize(&bytes)?;
+            check_file_version(&proof.sp1_version)?;
+            return Ok(proof);
+        };
+
+        let header: ProofFileHeader = bincode::deserialize_from(&mut rest)?;
+        check_file_version(&header.sp1_version)?;
+        if <[u8; 32]>::from(Sha256::digest(rest)) != header.payload_hash {
+            return Err(ProofFileError::Corrupted("the payload hash does not match".to_string()));
+        }
+        let proof: Self = bincode::deserialize(rest)?;
+        if SP1ProofMode::from(&proof.proof) as u8 != header.mode {
+            return Err(ProofFileError::Corrupted("the proof mode does not match".to_string()));
+        }
+        Ok(proof)
+    }
+
+    /// Saves a [`SP1ProofMode::Groth16`] or [`SP1ProofMode::Plonk`] proof to a path as JSON.
+    ///
+    /// # Details
+    /// Besides the fields needed to load the proof back, the JSON contains the hex-encoded public
+    /// values and [`Self::bytes`], which are the arguments the onchain verifiers take.
+    ///
+    /// # Errors
+    /// Returns [`ProofFileError::UnsupportedJsonMode`] for the other proof modes, and an error if
+    /// the file can't be written.
+    pub fn save_json(&self, path: impl AsRef<Path>) -> Result<(), ProofFileError> {
+        let (mode, public_inputs, encoded_proof, raw_proof, vkey_hash) = match &self.proof {
+            SP1Proof::Plonk(proof) => (
+                OnchainProofMode::Plonk,
+                &proof.public_inputs,
+                &proof.encoded_proof,
+                &proof.raw_proof,
+                &proof.plonk_vkey_hash,
+            ),
+            SP1Proof::Groth16(proof) => (
+                OnchainProofMode::Groth16,
+                &proof.public_inputs,
+                &proof.encoded_proof,
+                &proof.raw_proof,
+                &proof.groth16_vkey_hash,
+            ),
+            proof => return Err(ProofFileError::UnsupportedJsonMode(proof.into())),
+        };
+        let json = OnchainProofJson {
+            mode,
+            sp1_version: SP1_CIRCUIT_VERSION.to_string(),
+            public_values: format!("0x{}", hex::encode(self.public_values.as_slice())),
+            proof: format!("0x{}", hex::encode(self.bytes())),
+            public_inputs: public_inputs.clone(),
+            encoded_proof: encoded_proof.clone(),
+            raw_proof: raw_proof.clone(),
+            vkey_hash: format!("0x{}", hex::encode(vkey_hash)),
+        };
+        fs::write(path, serde_json::to_string_pretty(&json)?)?;
+        Ok(())
+    }
+
+    /// Loads a proof saved with [`Self::save_json`] from a path.
+    ///
+    /// # Errors
+    /// Returns [`ProofFileError::ProofFileVersionMismatch`] if the file was written by a version
+    /// of SP1 other than [`SP1_CIRCUIT_VERSION`], and [`ProofFileError::Corrupted`] if the
+    /// hex-encoded fields are invalid or the proof bytes do not match the proof.
+    pub fn load_json(path: impl AsRef<Path>) -> Result<Self, ProofFileError> {
+        let OnchainProofJson {
+            mode,
+            sp1_version,
+            public_values,
+            proof: proof_bytes,
+            public_inputs,
+            encoded_proof,
+            raw_proof,
+            vkey_hash,
+        } = serde_json::from_slice(&fs::read(path)?)?;
+        check_file_version(&sp1_version)?;
+
+        let vkey_hash = decode_hex(&vkey_hash, "vkey_hash")?
+            .try_into()
+            .map_err(|_| ProofFileError::Corrupted("vkey_hash is not 32 bytes".to_string()))?;
+        if hex::decode(&encoded_proof).is_err() {
+            return Err(ProofFileError::Corrupted("encoded_proof is not hex-encoded".to_string()));
+        }
+        let proof = match mode {
+            OnchainProofMode::Plonk => SP1Proof::Plonk(PlonkBn254Proof {
+                public_inputs,
+                encoded_proof,
+                raw_proof,
+                plonk_vkey_hash: vkey_hash,
+            }),
+            OnchainProofMode::Groth16 => SP1Proof::Groth16(Groth16Bn254Proof {
+                public_inputs,
+                encoded_proof,
+                raw_proof,
+                groth16_vkey_hash: vkey_hash,
+            }),
+        };
+        let proof = Self {
+            proof,
+            public_values: SP1PublicValues::from(&decode_hex(&public_values, "public_values")?),
+            sp1_version,
+        };
+        if proof.bytes() != decode_hex(&proof_bytes, "proof")? {
+            return Err(ProofFileError::Corrupted("the proof bytes do not match".to_string()));
+        }
+        Ok(proof)
     }
 
     /// The proof in the byte encoding the onchain verifiers accepts for [`SP1ProofMode::Groth16`] and
@@ -105,8 +245,24 @@ impl SP1ProofWithPublicValues {
         }
     }
 
+    /// Whether this is a mock [`SP1ProofMode::Groth16`] or [`SP1ProofMode::Plonk`] proof, whose
+    /// [`Self::bytes`] are prefixed with [`MOCK_PROOF_SELECTOR`] instead of a vkey hash.
+    #[must_use]
+    pub fn is_mock(&self) -> bool {
+        match &self.proof {
+            SP1Proof::Plonk(proof) => proof.plonk_vkey_hash[..4] == MOCK_PROOF_SELECTOR,
+            SP1Proof::Groth16(proof) => proof.groth16_vkey_hash[..4] == MOCK_PROOF_SELECTOR,
+            _ => false,
+        }
+    }
+
     /// Creates a mock proof for the specified proof mode from the public values.
     ///
+    /// # Details
+    /// For [`SP1ProofMode::Groth16`] and [`SP1ProofMode::Plonk`], [`Self::bytes`] are the
+    /// deterministic bytes accepted by [`MockVerifier`], which commit to the vkey hash and the
+    /// public values, and are rejected by the real verifiers.
+    ///
     /// # Example
     /// ```rust,no_run
     /// use sp1_sdk::{ProverClient, SP1Stdin, SP1ProofMode, Prover, SP1ProofWithPublicValues, SP1_CIRCUIT_VERSION};
@@ -135,72 +291,160 @@ impl SP1ProofWithPublicValues {
                 public_values,
                 sp1_version,
             },
-            SP1ProofMode::Compressed => {
-                let shard_proof = ShardProof {
-                    commitment: ShardCommitment {
-                        main_commit: [BabyBear::zero(); 8].into(),
-                        permutation_commit: [BabyBear::zero(); 8].into(),
-                        quotient_commit: [BabyBear::zero(); 8].into(),
-                    },
-                    opened_values: ShardOpenedValues { chips: vec![] },
-                    opening_proof: TwoAdicFriPcsProof {
-                        fri_proof: FriProof {
-                            commit_phase_commits: vec![],
-                            query_proofs: vec![],
-                            final_poly: BinomialExtensionField::default(),
-                            pow_witness: BabyBear::zero(),
-                        },
-                        query_openings: vec![],
-                    },
-                    chip_ordering: HashMap::new(),
-                    public_values: vec![],
-                };
-
-                let reduce_vk = StarkVerifyingKey {
-                    commit: [BabyBear::zero(); 8].into(),
-                    pc_start: BabyBear::zero(),
-                    chip_information: vec![],
-                    chip_ordering: HashMap::new(),
-                    initial_global_cumulative_sum: SepticDigest::zero(),
-                };
-
-                let proof = SP1Proof::Compressed(Box::new(SP1ReduceProof {
-                    vk: reduce_vk,
-                    proof: shard_proof,
-                }));
-
-                SP1ProofWithPublicValues { proof, public_values, sp1_version }
-            }
-            SP1ProofMode::Plonk => SP1ProofWithPublicValues {
-                proof: SP1Proof::Plonk(PlonkBn254Proof {
-                    public_inputs: [
-                        pk.vk.hash_bn254().as_canonical_biguint().to_string(),
-                        public_values.hash_bn254().to_string(),
-                    ],
-                    encoded_proof: String::new(),
-                    raw_proof: String::new(),
-                    plonk_vkey_hash: [0; 32],
-                }),
-                public_values,
-                sp1_version,
-            },
-            SP1ProofMode::Groth16 => SP1ProofWithPublicValues {
-                proof: SP1Proof::Groth16(Groth16Bn254Proof {
-                    public_inputs: [
-                        pk.vk.hash_bn254().as_canonical_biguint().to_string(),
-                        public_values.hash_bn254().to_string(),
-                    ],
-                    encoded_proof: String::new(),
-                    raw_proof: String::new(),
-                    groth16_vkey_hash: [0; 32],
-                }),
+            SP1ProofMode::Compressed => SP1ProofWithPublicValues {
+                proof: SP1Proof::Compressed(Box::new(mock_reduce_proof())),
                 public_values,
                 sp1_version,
             },
+            SP1ProofMode::Plonk => {
+                let (vkey_hash, encoded_proof) = mock_onchain_proof(pk, &public_values);
+                SP1ProofWithPublicValues {
+                    proof: SP1Proof::Plonk(PlonkBn254Proof {
+                        public_inputs: [
+                            pk.vk.hash_bn254().as_canonical_biguint().to_string(),
+                            public_values.hash_bn254().to_string(),
+                        ],
+                        encoded_proof,
+                        raw_proof: String::new(),
+                        plonk_vkey_hash: vkey_hash,
+                    }),
+                    public_values,
+                    sp1_version,
+                }
+            }
+            SP1ProofMode::Groth16 => {
+                let (vkey_hash, encoded_proof) = mock_onchain_proof(pk, &public_values);
+                SP1ProofWithPublicValues {
+                    proof: SP1Proof::Groth16(Groth16Bn254Proof {
+                        public_inputs: [
+                            pk.vk.hash_bn254().as_canonical_biguint().to_string(),
+                            public_values.hash_bn254().to_string(),
+                        ],
+                        encoded_proof,
+                        raw_proof: String::new(),
+                        groth16_vkey_hash: vkey_hash,
+                    }),
+                    public_values,
+                    sp1_version,
+                }
+            }
         }
     }
 }
 
+/// An error that occurs when saving or loading a [`SP1ProofWithPublicValues`].
+#[derive(Debug, Error)]
+pub enum ProofFileError {
+    /// The proof file could not be read or written.
+    #[error("failed to access the proof file: {0}")]
+    Io(#[from] std::io::Error),
+    /// The proof could not be encoded or decoded.
+    #[error("failed to encode or decode the proof: {0}")]
+    Bincode(#[from] bincode::Error),
+    /// The JSON proof could not be encoded or decoded.
+    #[error("failed to encode or decode the JSON proof: {0}")]
+    Json(#[from] serde_json::Error),
+    /// The proof file was written by a version of SP1 whose proofs this version can't verify.
+    #[error("the proof file was written by SP1 {file_version}, but this is SP1 {crate_version}")]
+    ProofFileVersionMismatch { file_version: String, crate_version: String },
+    /// The proof file does not match its own envelope.
+    #[error("the proof file is corrupted: {0}")]
+    Corrupted(String),
+    /// Only [`SP1ProofMode::Groth16`] and [`SP1ProofMode::Plonk`] proofs can be saved as JSON.
+    #[error("{0:?} proofs can't be saved as JSON, only Plonk and Groth16 proofs can")]
+    UnsupportedJsonMode(SP1ProofMode),
+}
+
+/// The header written by [`SP1ProofWithPublicValues::save`] between the magic bytes and the
+/// bincode-encoded proof.
+#[derive(Serialize, Deserialize)]
+struct ProofFileHeader {
+    sp1_version: String,
+    mode: u8,
+    payload_hash: [u8; 32],
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum OnchainProofMode {
+    Plonk,
+    Groth16,
+}
+
+/// The JSON written by [`SP1ProofWithPublicValues::save_json`].
+///
+/// The byte fields are `0x`-prefixed hex, and `proof` is [`SP1ProofWithPublicValues::bytes`].
+#[derive(Serialize, Deserialize)]
+struct OnchainProofJson {
+    mode: OnchainProofMode,
+    sp1_version: String,
+    public_values: String,
+    proof: String,
+    public_inputs: [String; 2],
+    encoded_proof: String,
+    raw_proof: String,
+    vkey_hash: String,
+}
+
+/// A reduce proof with empty commitments and openings, for mock [`SP1ProofMode::Compressed`]
+/// proofs.
+fn mock_reduce_proof() -> SP1ReduceProof<InnerSC> {
+    let shard_proof = ShardProof {
+        commitment: ShardCommitment {
+            main_commit: [BabyBear::zero(); 8].into(),
+            permutation_commit: [BabyBear::zero(); 8].into(),
+            quotient_commit: [BabyBear::zero(); 8].into(),
+        },
+        opened_values: ShardOpenedValues { chips: vec![] },
+        opening_proof: TwoAdicFriPcsProof {
+            fri_proof: FriProof {
+                commit_phase_commits: vec![],
+                query_proofs: vec![],
+                final_poly: BinomialExtensionField::default(),
+                pow_witness: BabyBear::zero(),
+            },
+            query_openings: vec![],
+        },
+        chip_ordering: HashMap::new(),
+        public_values: vec![],
+    };
+
+    let reduce_vk = StarkVerifyingKey {
+        commit: [BabyBear::zero(); 8].into(),
+        pc_start: BabyBear::zero(),
+        chip_information: vec![],
+        chip_ordering: HashMap::new(),
+        initial_global_cumulative_sum: SepticDigest::zero(),
+    };
+
+    SP1ReduceProof { vk: reduce_vk, proof: shard_proof }
+}
+
+/// The vkey hash and encoded proof of a mock Plonk or Groth16 proof, such that the proof bytes are
+/// the ones accepted by [`MockVerifier`].
+fn mock_onchain_proof(pk: &SP1ProvingKey, public_values: &SP1PublicValues) -> ([u8; 32], String) {
+    let proof_bytes = MockVerifier::proof_bytes(public_values.as_slice(), &pk.vk.bytes32_raw());
+    let mut vkey_hash = [0; 32];
+    vkey_hash[..4].copy_from_slice(&MOCK_PROOF_SELECTOR);
+    (vkey_hash, hex::encode(&proof_bytes[MOCK_PROOF_SELECTOR.len()..]))
+}
+
+fn check_file_version(file_version: &str) -> Result<(), ProofFileError> {
+    if file_version == SP1_CIRCUIT_VERSION {
+        Ok(())
+    } else {
+        Err(ProofFileError::ProofFileVersionMismatch {
+            file_version: file_version.to_string(),
+            crate_version: SP1_CIRCUIT_VERSION.to_string(),
+        })
+    }
+}
+
+fn decode_hex(value: &str, field: &str) -> Result<Vec<u8>, ProofFileError> {
+    hex::decode(value.strip_prefix("0x").unwrap_or(value))
+        .map_err(|_| ProofFileError::Corrupted(format!("{field} is not hex-encoded")))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -279,4 +523,136 @@ mod tests {
         };
         println!("{:?}", core_proof.bytes());
     }
+
+    fn proof_with_mode(mode: SP1ProofMode) -> SP1ProofWithPublicValues {
+        let public_inputs = ["1".to_string(), "2".to_string()];
+        let proof = match mode {
+            SP1ProofMode::Core => SP1Proof::Core(vec![]),
+            SP1ProofMode::Compressed => SP1Proof::Compressed(Box::new(mock_reduce_proof())),
+            SP1ProofMode::Plonk => SP1Proof::Plonk(PlonkBn254Proof {
+                encoded_proof: "ab".to_string(),
+                plonk_vkey_hash: [1; 32],
+                public_inputs,
+                raw_proof: "cd".to_string(),
+            }),
+            SP1ProofMode::Groth16 => SP1Proof::Groth16(Groth16Bn254Proof {
+                encoded_proof: "ab".to_string(),
+                groth16_vkey_hash: [1; 32],
+                public_inputs,
+                raw_proof: "cd".to_string(),
+            }),
+        };
+        SP1ProofWithPublicValues {
+            proof,
+            public_values: SP1PublicValues::from(&[1, 2, 3]),
+            sp1_version: SP1_CIRCUIT_VERSION.to_string(),
+        }
+    }
+
+    fn assert_same_proof(a: &SP1ProofWithPublicValues, b: &SP1ProofWithPublicValues) {
+        assert_eq!(bincode::serialize(a).unwrap(), bincode::serialize(b).unwrap());
+    }
+
+    fn assert_version_mismatch(err: &ProofFileError, expected_file_version: &str) {
+        match err {
+            ProofFileError::ProofFileVersionMismatch { file_version, crate_version } => {
+                assert_eq!(file_version, expected_file_version);
+                assert_eq!(crate_version, SP1_CIRCUIT_VERSION);
+            }
+            err => panic!("expected a version mismatch, got {err:?}"),
+        }
+    }
+
+    #[test]
+    fn test_save_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        for mode in [
+            SP1ProofMode::Core,
+            SP1ProofMode::Compressed,
+            SP1ProofMode::Plonk,
+            SP1ProofMode::Groth16,
+        ] {
+            let path = dir.path().join("proof.bin");
+            let proof = proof_with_mode(mode);
+            proof.save(&path).unwrap();
+
+            let loaded = SP1ProofWithPublicValues::load(&path).unwrap();
+            assert_eq!(SP1ProofMode::from(&loaded.proof), mode);
+            assert_same_proof(&loaded, &proof);
+        }
+    }
+
+    #[test]
+    fn test_save_load_json_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        for mode in [SP1ProofMode::Plonk, SP1ProofMode::Groth16] {
+            let path = dir.path().join("proof.json");
+            let proof = proof_with_mode(mode);
+            proof.save_json(&path).unwrap();
+
+            let json: serde_json::Value =
+                serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+            assert_eq!(json["proof"], format!("0x{}", hex::encode(proof.bytes())));
+            assert_eq!(json["public_values"], "0x010203");
+
+            let loaded = SP1ProofWithPublicValues::load_json(&path).unwrap();
+            assert_same_proof(&loaded, &proof);
+        }
+    }
+
+    #[test]
+    fn test_save_json_unsupported_modes() {
+        let dir = tempfile::tempdir().unwrap();
+        for mode in [SP1ProofMode::Core, SP1ProofMode::Compressed] {
+            let err = proof_with_mode(mode).save_json(dir.path().join("proof.json")).unwrap_err();
+            assert!(matches!(err, ProofFileError::UnsupportedJsonMode(m) if m == mode));
+        }
+    }
+
+    #[test]
+    fn test_load_older_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut proof = proof_with_mode(SP1ProofMode::Groth16);
+        proof.sp1_version = "v3.0.0".to_string();
+
+        // Versions without the envelope wrote the bincode-encoded proof on its own.
+        let legacy_path = dir.path().join("legacy.bin");
+        fs::write(&legacy_path, bincode::serialize(&proof).unwrap()).unwrap();
+        let err = SP1ProofWithPublicValues::load(&legacy_path).unwrap_err();
+        assert_version_mismatch(&err, "v3.0.0");
+
+        let payload = bincode::serialize(&proof).unwrap();
+        let header = ProofFileHeader {
+            sp1_version: "v3.0.0".to_string(),
+            mode: SP1ProofMode::Groth16 as u8,
+            payload_hash: Sha256::digest(&payload).into(),
+        };
+        let mut bytes = PROOF_FILE_MAGIC.to_vec();
+        bincode::serialize_into(&mut bytes, &header).unwrap();
+        bytes.extend_from_slice(&payload);
+        let path = dir.path().join("proof.bin");
+        fs::write(&path, bytes).unwrap();
+        let err = SP1ProofWithPublicValues::load(&path).unwrap_err();
+        assert_version_mismatch(&err, "v3.0.0");
+
+        let json_path = dir.path().join("proof.json");
+        proof.save_json(&json_path).unwrap();
+        let json = fs::read_to_string(&json_path).unwrap();
+        fs::write(&json_path, json.replace(SP1_CIRCUIT_VERSION, "v3.0.0")).unwrap();
+        let err = SP1ProofWithPublicValues::load_json(&json_path).unwrap_err();
+        assert_version_mismatch(&err, "v3.0.0");
+    }
+
+    #[test]
+    fn test_load_corrupted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("proof.bin");
+        proof_with_mode(SP1ProofMode::Plonk).save(&path).unwrap();
+
+        let mut bytes = fs::read(&path).unwrap();
+        *bytes.last_mut().unwrap() ^= 1;
+        fs::write(&path, bytes).unwrap();
+        let err = SP1ProofWithPublicValues::load(&path).unwrap_err();
+        assert!(matches!(err, ProofFileError::Corrupted(_)));
+    }
 }
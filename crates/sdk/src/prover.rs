@@ -4,20 +4,21 @@
 
 use std::borrow::Borrow;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use itertools::Itertools;
 use p3_field::PrimeField32;
-use sp1_core_executor::{ExecutionReport, SP1Context};
+use sp1_core_executor::{ExecutionReport, SP1Context, SP1ReduceProof};
 use sp1_core_machine::io::SP1Stdin;
 use sp1_primitives::io::SP1PublicValues;
 use sp1_prover::{
-    components::SP1ProverComponents, CoreSC, InnerSC, SP1CoreProofData, SP1Prover, SP1ProvingKey,
-    SP1VerifyingKey, SP1_CIRCUIT_VERSION,
+    components::SP1ProverComponents, CoreSC, InnerSC, SP1CoreProof, SP1CoreProofData, SP1Prover,
+    SP1ProvingKey, SP1VerifyingKey, SP1VersionedVerifyingKey, SP1_CIRCUIT_VERSION,
 };
 use sp1_stark::{air::PublicValues, MachineVerificationError, Word};
 use thiserror::Error;
 
-use crate::install::try_install_circuit_artifacts;
+use crate::install::ensure_circuit_artifacts;
+use crate::setup_cache::SetupCache;
 use crate::{SP1Proof, SP1ProofMode, SP1ProofWithPublicValues};
 
 /// A basic set of primitives that each prover variant must implement.
@@ -33,6 +34,12 @@ pub trait Prover<C: SP1ProverComponents>: Send + Sync {
     /// Generate the proving and verifying keys for the given program.
     fn setup(&self, elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey);
 
+    /// Generate the proving and verifying keys for the given program, loading them from the
+    /// [`SetupCache`] in `~/.sp1/pk_cache` if they were computed before and storing them otherwise.
+    fn setup_cached(&self, elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey) {
+        SetupCache::default().get_or_setup(elf, |elf| self.setup(elf))
+    }
+
     /// Executes the program on the given input.
     fn execute(&self, elf: &[u8], stdin: &SP1Stdin) -> Result<(SP1PublicValues, ExecutionReport)> {
         Ok(self.inner().execute(elf, stdin, SP1Context::default())?)
@@ -46,6 +53,61 @@ pub trait Prover<C: SP1ProverComponents>: Send + Sync {
         mode: SP1ProofMode,
     ) -> Result<SP1ProofWithPublicValues>;
 
+    /// Compresses a [`SP1ProofMode::Core`] proof generated before, e.g. one loaded with
+    /// [`SP1ProofWithPublicValues::load`], into a [`SP1ProofMode::Compressed`] proof.
+    ///
+    /// The proof is verified against `vk` and the version of this prover before it is compressed.
+    /// `deferred_proofs` are the compressed proofs the program verified, in the order it verified
+    /// them. Only the CPU and CUDA provers support it.
+    fn compress(
+        &self,
+        _vk: &SP1VerifyingKey,
+        _proof: &SP1ProofWithPublicValues,
+        _deferred_proofs: Vec<SP1ReduceProof<InnerSC>>,
+    ) -> Result<SP1ProofWithPublicValues> {
+        bail!("compressing a proof generated before is not supported by this prover")
+    }
+
+    /// Shrinks a [`SP1ProofMode::Compressed`] proof generated before, e.g. with
+    /// [`Prover::compress`], into the proof of the shrink program that is wrapped into a Groth16
+    /// or Plonk proof.
+    ///
+    /// The proof is verified against `vk` and the version of this prover before it is shrunk.
+    /// Only the CPU and CUDA provers support it.
+    fn shrink(
+        &self,
+        _vk: &SP1VerifyingKey,
+        _proof: &SP1ProofWithPublicValues,
+    ) -> Result<SP1ReduceProof<InnerSC>> {
+        bail!("shrinking a proof generated before is not supported by this prover")
+    }
+
+    /// Shrinks and wraps a [`SP1ProofMode::Compressed`] proof generated before, e.g. with
+    /// [`Prover::compress`], into a [`SP1ProofMode::Groth16`] proof.
+    ///
+    /// The proof is verified against `vk` and the version of this prover before it is wrapped.
+    /// Only the CPU and CUDA provers support it.
+    fn wrap_groth16(
+        &self,
+        _vk: &SP1VerifyingKey,
+        _proof: &SP1ProofWithPublicValues,
+    ) -> Result<SP1ProofWithPublicValues> {
+        bail!("wrapping a proof generated before is not supported by this prover")
+    }
+
+    /// Shrinks and wraps a [`SP1ProofMode::Compressed`] proof generated before, e.g. with
+    /// [`Prover::compress`], into a [`SP1ProofMode::Plonk`] proof.
+    ///
+    /// The proof is verified against `vk` and the version of this prover before it is wrapped.
+    /// Only the CPU and CUDA provers support it.
+    fn wrap_plonk(
+        &self,
+        _vk: &SP1VerifyingKey,
+        _proof: &SP1ProofWithPublicValues,
+    ) -> Result<SP1ProofWithPublicValues> {
+        bail!("wrapping a proof generated before is not supported by this prover")
+    }
+
     /// Verify that an SP1 proof is valid given its vkey and metadata.
     /// For Plonk proofs, verifies that the public inputs of the `PlonkBn254` proof match
     /// the hash of the VK and the committed public values of the `SP1ProofWithPublicValues`.
@@ -56,6 +118,22 @@ pub trait Prover<C: SP1ProverComponents>: Send + Sync {
     ) -> Result<(), SP1VerificationError> {
         verify_proof(self.inner(), self.version(), bundle, vkey)
     }
+
+    /// Verify an SP1 proof like [`Prover::verify`], after checking that the verifying key was
+    /// generated by the version of the current circuit.
+    fn verify_versioned(
+        &self,
+        bundle: &SP1ProofWithPublicValues,
+        vkey: &SP1VersionedVerifyingKey,
+    ) -> Result<(), SP1VerificationError> {
+        check_version("verifying key", &vkey.sp1_version, self.version()).map_err(
+            |(vk_version, verifier_version)| SP1VerificationError::VerifyingKeyVersionMismatch {
+                vk_version,
+                verifier_version,
+            },
+        )?;
+        self.verify(bundle, &vkey.vk)
+    }
 }
 
 /// An error that occurs when calling [`Prover::verify`].
@@ -64,9 +142,24 @@ pub enum SP1VerificationError {
     /// An error that occurs when the public values are invalid.
     #[error("Invalid public values")]
     InvalidPublicValues,
-    /// An error that occurs when the SP1 version does not match the version of the circuit.
-    #[error("Version mismatch")]
-    VersionMismatch(String),
+    /// An error that occurs when the proof was generated by another version of SP1 than the
+    /// verifier.
+    #[error("Version mismatch: proof of {proof_version} verified with {verifier_version}")]
+    VersionMismatch {
+        /// The version of SP1 that generated the proof.
+        proof_version: String,
+        /// The version of SP1 of the verifier.
+        verifier_version: String,
+    },
+    /// An error that occurs when the verifying key was generated by another version of SP1 than
+    /// the verifier.
+    #[error("Version mismatch: verifying key of {vk_version} used with {verifier_version}")]
+    VerifyingKeyVersionMismatch {
+        /// The version of SP1 that generated the verifying key.
+        vk_version: String,
+        /// The version of SP1 of the verifier.
+        verifier_version: String,
+    },
     /// An error that occurs when the core machine verification fails.
     #[error("Core machine verification error: {0}")]
     Core(MachineVerificationError<CoreSC>),
@@ -79,6 +172,40 @@ pub enum SP1VerificationError {
     /// An error that occurs when the Groth16 verification fails.
     #[error("Groth16 verification error: {0}")]
     Groth16(anyhow::Error),
+    /// An error that occurs when a mock proof is verified by a prover that is not in mock mode.
+    #[error("Mock proofs can only be verified in mock mode")]
+    MockProof,
+}
+
+/// Returns the tag of a version of SP1, as used by the prover network, e.g. `sp1-v4.0.0`.
+pub(crate) fn sp1_version_tag(version: &str) -> String {
+    format!("sp1-{}", version.strip_prefix("sp1-").unwrap_or(version))
+}
+
+/// Checks that an artifact generated by SP1 `artifact_version` can be verified by SP1 `version`,
+/// returning the tags of both versions otherwise.
+///
+/// Artifacts serialized before the version was embedded in them have an empty version, and are
+/// verified as before with a warning.
+fn check_version(
+    artifact: &str,
+    artifact_version: &str,
+    version: &str,
+) -> Result<(), (String, String)> {
+    let verifier_version = sp1_version_tag(version);
+    if artifact_version.is_empty() {
+        tracing::warn!(
+            "the {artifact} does not record the SP1 version that generated it, verifying it with \
+             {verifier_version}"
+        );
+        return Ok(());
+    }
+    let artifact_version = sp1_version_tag(artifact_version);
+    if artifact_version == verifier_version {
+        Ok(())
+    } else {
+        Err((artifact_version, verifier_version))
+    }
 }
 
 pub(crate) fn verify_proof<C: SP1ProverComponents>(
@@ -87,9 +214,17 @@ pub(crate) fn verify_proof<C: SP1ProverComponents>(
     bundle: &SP1ProofWithPublicValues,
     vkey: &SP1VerifyingKey,
 ) -> Result<(), SP1VerificationError> {
-    // Check that the SP1 version matches the version of the currentcircuit.
-    if bundle.sp1_version != version {
-        return Err(SP1VerificationError::VersionMismatch(bundle.sp1_version.clone()));
+    // Check that the proof was generated by the version of the current circuit, before any
+    // cryptographic verification.
+    check_version("proof", &bundle.sp1_version, version).map_err(
+        |(proof_version, verifier_version)| SP1VerificationError::VersionMismatch {
+            proof_version,
+            verifier_version,
+        },
+    )?;
+
+    if bundle.is_mock() {
+        return Err(SP1VerificationError::MockProof);
     }
 
     match &bundle.proof {
@@ -136,29 +271,70 @@ pub(crate) fn verify_proof<C: SP1ProverComponents>(
 
             prover.verify_compressed(proof, vkey).map_err(SP1VerificationError::Recursion)
         }
-        SP1Proof::Plonk(proof) => prover
-            .verify_plonk_bn254(
-                proof,
-                vkey,
-                &bundle.public_values,
-                &if sp1_prover::build::sp1_dev_mode() {
-                    sp1_prover::build::plonk_bn254_artifacts_dev_dir()
-                } else {
-                    try_install_circuit_artifacts("plonk")
-                },
-            )
-            .map_err(SP1VerificationError::Plonk),
-        SP1Proof::Groth16(proof) => prover
-            .verify_groth16_bn254(
-                proof,
-                vkey,
-                &bundle.public_values,
-                &if sp1_prover::build::sp1_dev_mode() {
-                    sp1_prover::build::groth16_bn254_artifacts_dev_dir()
-                } else {
-                    try_install_circuit_artifacts("groth16")
-                },
-            )
-            .map_err(SP1VerificationError::Groth16),
+        SP1Proof::Plonk(proof) => {
+            let artifacts_dir = if sp1_prover::build::sp1_dev_mode() {
+                sp1_prover::build::plonk_bn254_artifacts_dev_dir()
+            } else {
+                ensure_circuit_artifacts("plonk").map_err(SP1VerificationError::Plonk)?
+            };
+            prover
+                .verify_plonk_bn254(proof, vkey, &bundle.public_values, &artifacts_dir)
+                .map_err(SP1VerificationError::Plonk)
+        }
+        SP1Proof::Groth16(proof) => {
+            let artifacts_dir = if sp1_prover::build::sp1_dev_mode() {
+                sp1_prover::build::groth16_bn254_artifacts_dev_dir()
+            } else {
+                ensure_circuit_artifacts("groth16").map_err(SP1VerificationError::Groth16)?
+            };
+            prover
+                .verify_groth16_bn254(proof, vkey, &bundle.public_values, &artifacts_dir)
+                .map_err(SP1VerificationError::Groth16)
+        }
     }
 }
+
+/// Returns the core proof of a Core proof generated before, to compress it.
+///
+/// The proof is verified first, so that a proof of another program or version is rejected before
+/// the recursion prover runs.
+pub(crate) fn core_proof_to_compress<C: SP1ProverComponents>(
+    prover: &SP1Prover<C>,
+    version: &str,
+    bundle: &SP1ProofWithPublicValues,
+    vkey: &SP1VerifyingKey,
+) -> Result<SP1CoreProof> {
+    let SP1Proof::Core(proof) = &bundle.proof else {
+        bail!(
+            "only Core proofs can be compressed, got a {:?} proof",
+            SP1ProofMode::from(&bundle.proof)
+        );
+    };
+    verify_proof(prover, version, bundle, vkey)?;
+    Ok(SP1CoreProof {
+        proof: SP1CoreProofData(proof.clone()),
+        stdin: SP1Stdin::new(),
+        public_values: bundle.public_values.clone(),
+        cycles: 0,
+    })
+}
+
+/// Returns the reduce proof of a Compressed proof generated before, to shrink and wrap it.
+///
+/// The proof is verified first, so that a proof of another program or version is rejected before
+/// the recursion prover runs.
+pub(crate) fn compressed_proof_to_wrap<C: SP1ProverComponents>(
+    prover: &SP1Prover<C>,
+    version: &str,
+    bundle: &SP1ProofWithPublicValues,
+    vkey: &SP1VerifyingKey,
+) -> Result<SP1ReduceProof<InnerSC>> {
+    let SP1Proof::Compressed(proof) = &bundle.proof else {
+        bail!(
+            "only Compressed proofs can be shrunk or wrapped, got a {:?} proof",
+            SP1ProofMode::from(&bundle.proof)
+        );
+    };
+    verify_proof(prover, version, bundle, vkey)?;
+    Ok(proof.as_ref().clone())
+}
@@ -0,0 +1,296 @@
+//! # SP1 Setup Cache
+//!
+//! A disk cache for the proving and verifying keys of programs, keyed by the hash of their ELF.
+
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sp1_prover::{SP1ProvingKey, SP1VerifyingKey};
+use tempfile::NamedTempFile;
+use thiserror::Error;
+
+/// The magic bytes at the start of a file written by [`SetupCache::store`].
+const SETUP_CACHE_MAGIC: &[u8; 8] = b"SP1SETUP";
+
+/// The name of the file holding the keys in the directory of a program.
+const KEYS_FILE_NAME: &str = "keys.bin";
+
+/// The version of SP1 that wrote the cached keys. Keys cached by any other version are ignored,
+/// since the machine and so the keys may have changed between versions.
+pub const SETUP_CACHE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The directory where the setup cache is stored by default.
+#[must_use]
+pub fn setup_cache_dir() -> PathBuf {
+    dirs::home_dir().unwrap().join(".sp1").join("pk_cache")
+}
+
+/// Removes every program from the setup cache in [`setup_cache_dir`].
+pub fn clear_cache() -> Result<(), SetupCacheError> {
+    SetupCache::default().clear()
+}
+
+/// A disk cache for the proving and verifying keys of programs.
+///
+/// The keys of a program are stored in `<dir>/<blake3(elf)>/`, together with the SP1 version
+/// that computed them and a hash of their encoding, which are checked when the keys are loaded.
+#[derive(Debug, Clone)]
+pub struct SetupCache {
+    dir: PathBuf,
+}
+
+impl SetupCache {
+    /// Creates a cache which stores the keys of programs in the given directory.
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The directory the keys are stored in.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Loads the keys of a program from the cache, or computes them with `setup` and stores them.
+    ///
+    /// # Details
+    /// Keys that can't be loaded because they were cached by another version of SP1 or the
+    /// cached file is corrupted are computed again and overwritten. Failing to store the keys
+    /// is logged and otherwise ignored, since the keys were computed anyway.
+    #[must_use]
+    pub fn get_or_setup(
+        &self,
+        elf: &[u8],
+        setup: impl FnOnce(&[u8]) -> (SP1ProvingKey, SP1VerifyingKey),
+    ) -> (SP1ProvingKey, SP1VerifyingKey) {
+        match self.load(elf) {
+            Ok(Some(keys)) => {
+                tracing::debug!("loaded the keys of the program from the setup cache");
+                return keys;
+            }
+            Ok(None) => {}
+            Err(err) => tracing::warn!("recomputing the keys of the program: {err}"),
+        }
+
+        let (pk, vk) = setup(elf);
+        if let Err(err) = self.store(elf, &pk, &vk) {
+            tracing::warn!("failed to store the keys of the program in the setup cache: {err}");
+        }
+        (pk, vk)
+    }
+
+    /// Loads the keys of a program from the cache.
+    ///
+    /// # Errors
+    /// Returns `Ok(None)` if the program isn't cached, [`SetupCacheError::VersionMismatch`] if
+    /// the keys were cached by a version of SP1 other than [`SETUP_CACHE_VERSION`], and
+    /// [`SetupCacheError::Corrupted`] if the cached file does not match its own header or the ELF.
+    pub fn load(
+        &self,
+        elf: &[u8],
+    ) -> Result<Option<(SP1ProvingKey, SP1VerifyingKey)>, SetupCacheError> {
+        let bytes = match fs::read(self.keys_path(elf)) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let Some(mut rest) = bytes.strip_prefix(SETUP_CACHE_MAGIC) else {
+            return Err(SetupCacheError::Corrupted("the magic bytes do not match".to_string()));
+        };
+
+        let header: SetupCacheHeader = bincode::deserialize_from(&mut rest)?;
+        if header.sp1_version != SETUP_CACHE_VERSION {
+            return Err(SetupCacheError::VersionMismatch {
+                cache_version: header.sp1_version,
+                crate_version: SETUP_CACHE_VERSION.to_string(),
+            });
+        }
+        if header.elf_hash != *blake3::hash(elf).as_bytes() {
+            return Err(SetupCacheError::Corrupted("the ELF hash does not match".to_string()));
+        }
+        if header.payload_hash != *blake3::hash(rest).as_bytes() {
+            return Err(SetupCacheError::Corrupted("the payload hash does not match".to_string()));
+        }
+
+        let (pk, vk): (SP1ProvingKey, SP1VerifyingKey) = bincode::deserialize(rest)?;
+        if pk.elf != elf {
+            return Err(SetupCacheError::Corrupted(
+                "the proving key is for another ELF".to_string(),
+            ));
+        }
+        Ok(Some((pk, vk)))
+    }
+
+    /// Stores the keys of a program in the cache, replacing any keys already cached for it.
+    pub fn store(
+        &self,
+        elf: &[u8],
+        pk: &SP1ProvingKey,
+        vk: &SP1VerifyingKey,
+    ) -> Result<(), SetupCacheError> {
+        let payload = bincode::serialize(&(pk, vk))?;
+        let header = SetupCacheHeader {
+            sp1_version: SETUP_CACHE_VERSION.to_string(),
+            elf_hash: *blake3::hash(elf).as_bytes(),
+            payload_hash: *blake3::hash(&payload).as_bytes(),
+        };
+
+        // Write to a temporary file that is then renamed, so that concurrent setups never read a
+        // partially written file.
+        let path = self.keys_path(elf);
+        let dir = path.parent().unwrap();
+        fs::create_dir_all(dir)?;
+        let mut file = NamedTempFile::new_in(dir)?;
+        file.write_all(SETUP_CACHE_MAGIC)?;
+        bincode::serialize_into(&mut file, &header)?;
+        file.write_all(&payload)?;
+        file.persist(path).map_err(|err| err.error)?;
+        Ok(())
+    }
+
+    /// Removes every program from the cache.
+    pub fn clear(&self) -> Result<(), SetupCacheError> {
+        match fs::remove_dir_all(&self.dir) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn keys_path(&self, elf: &[u8]) -> PathBuf {
+        self.dir.join(blake3::hash(elf).to_hex().as_str()).join(KEYS_FILE_NAME)
+    }
+}
+
+impl Default for SetupCache {
+    fn default() -> Self {
+        Self::new(setup_cache_dir())
+    }
+}
+
+/// An error that occurs when loading or storing keys in a [`SetupCache`].
+#[derive(Debug, Error)]
+pub enum SetupCacheError {
+    /// The cache could not be read or written.
+    #[error("failed to access the setup cache: {0}")]
+    Io(#[from] std::io::Error),
+    /// The keys could not be encoded or decoded.
+    #[error("failed to encode or decode the keys: {0}")]
+    Bincode(#[from] bincode::Error),
+    /// The keys were cached by another version of SP1.
+    #[error("the keys were cached by SP1 {cache_version}, but this is SP1 {crate_version}")]
+    VersionMismatch {
+        /// The version of SP1 that cached the keys.
+        cache_version: String,
+        /// The version of SP1 loading the keys.
+        crate_version: String,
+    },
+    /// The cached file does not match its own header or the ELF.
+    #[error("the cached keys are corrupted: {0}")]
+    Corrupted(String),
+}
+
+/// The header written by [`SetupCache::store`] between the magic bytes and the bincode-encoded
+/// keys.
+#[derive(Serialize, Deserialize)]
+struct SetupCacheHeader {
+    sp1_version: String,
+    elf_hash: [u8; 32],
+    payload_hash: [u8; 32],
+}
+
+#[cfg(test)]
+mod tests {
+    use sp1_prover::HashableKey;
+
+    use super::*;
+    use crate::{Prover, ProverClient};
+
+    fn setup(elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey) {
+        ProverClient::builder().cpu().build().setup(elf)
+    }
+
+    fn no_setup(_: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey) {
+        panic!("the keys should have been loaded from the cache")
+    }
+
+    #[test]
+    fn test_setup_cache_hit() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = SetupCache::new(dir.path());
+        let elf = test_artifacts::FIBONACCI_ELF;
+
+        assert!(cache.load(elf).unwrap().is_none());
+        let (pk, vk) = cache.get_or_setup(elf, setup);
+        let (cached_pk, cached_vk) = cache.get_or_setup(elf, no_setup);
+        assert_eq!(cached_vk.hash_u32(), vk.hash_u32());
+        assert_eq!(cached_pk.vk.hash_u32(), pk.vk.hash_u32());
+        assert_eq!(bincode::serialize(&cached_pk).unwrap(), bincode::serialize(&pk).unwrap());
+
+        // Other programs are not served the cached keys.
+        assert!(cache.load(test_artifacts::PANIC_ELF).unwrap().is_none());
+
+        cache.clear().unwrap();
+        assert!(cache.load(elf).unwrap().is_none());
+        cache.clear().unwrap();
+    }
+
+    #[test]
+    fn test_setup_cache_version_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = SetupCache::new(dir.path());
+        let elf = test_artifacts::FIBONACCI_ELF;
+        let (pk, vk) = setup(elf);
+        cache.store(elf, &pk, &vk).unwrap();
+
+        // Rewrite the header as if the keys were cached by another version.
+        let path = cache.keys_path(elf);
+        let bytes = fs::read(&path).unwrap();
+        let mut rest = &bytes[SETUP_CACHE_MAGIC.len()..];
+        let mut header: SetupCacheHeader = bincode::deserialize_from(&mut rest).unwrap();
+        header.sp1_version = "v0.0.0".to_string();
+        let mut stale = SETUP_CACHE_MAGIC.to_vec();
+        bincode::serialize_into(&mut stale, &header).unwrap();
+        stale.extend_from_slice(rest);
+        fs::write(&path, stale).unwrap();
+
+        assert!(matches!(
+            cache.load(elf),
+            Err(SetupCacheError::VersionMismatch { cache_version, .. }) if cache_version == "v0.0.0"
+        ));
+        let (_, recomputed_vk) = cache.get_or_setup(elf, setup);
+        assert_eq!(recomputed_vk.hash_u32(), vk.hash_u32());
+        assert!(cache.load(elf).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_setup_cache_corrupted() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = SetupCache::new(dir.path());
+        let elf = test_artifacts::FIBONACCI_ELF;
+        let (pk, vk) = setup(elf);
+        cache.store(elf, &pk, &vk).unwrap();
+
+        // Flip a byte of the encoded keys.
+        let path = cache.keys_path(elf);
+        let mut bytes = fs::read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        fs::write(&path, &bytes).unwrap();
+        assert!(matches!(cache.load(elf), Err(SetupCacheError::Corrupted(_))));
+
+        // Truncate the file.
+        fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+        assert!(cache.load(elf).is_err());
+
+        // The keys are computed again and the file is overwritten.
+        let (_, recomputed_vk) = cache.get_or_setup(elf, setup);
+        assert_eq!(recomputed_vk.hash_u32(), vk.hash_u32());
+        let (_, cached_vk) = cache.get_or_setup(elf, no_setup);
+        assert_eq!(cached_vk.hash_u32(), vk.hash_u32());
+    }
+}
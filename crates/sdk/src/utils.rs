@@ -18,10 +18,28 @@ pub(crate) fn sp1_dump(elf: &[u8], stdin: &SP1Stdin) {
     }
 }
 
+/// The runtime that the blocking methods of the SDK run their futures on, created on first use.
+#[cfg(feature = "network")]
+static RUNTIME: std::sync::OnceLock<tokio::runtime::Runtime> = std::sync::OnceLock::new();
+
+/// Returns the runtime shared by the blocking methods of the SDK, creating it if needed.
+#[cfg(feature = "network")]
+fn shared_runtime() -> std::io::Result<&'static tokio::runtime::Runtime> {
+    if let Some(rt) = RUNTIME.get() {
+        return Ok(rt);
+    }
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .thread_name("sp1-sdk-runtime")
+        .enable_all()
+        .build()?;
+    // If another thread created the runtime first, this one is dropped.
+    Ok(RUNTIME.get_or_init(|| rt))
+}
+
 /// Utility method for blocking on an async function.
 ///
-/// If we're already in a tokio runtime, we'll block in place. Otherwise, we'll create a new
-/// runtime.
+/// If we're already in a tokio runtime, we'll block in place. Otherwise, we'll block on the
+/// runtime shared by the blocking methods of the SDK.
 #[cfg(feature = "network")]
 pub(crate) fn block_on<T>(fut: impl std::future::Future<Output = T>) -> T {
     use tokio::task::block_in_place;
@@ -30,8 +48,76 @@ pub(crate) fn block_on<T>(fut: impl std::future::Future<Output = T>) -> T {
     if let Ok(handle) = tokio::runtime::Handle::try_current() {
         block_in_place(|| handle.block_on(fut))
     } else {
-        // Otherwise create a new runtime.
-        let rt = tokio::runtime::Runtime::new().expect("Failed to create a new runtime");
-        rt.block_on(fut)
+        // Otherwise use the shared runtime.
+        shared_runtime().expect("Failed to create a new runtime").block_on(fut)
+    }
+}
+
+/// Blocks on an async function for the `*_blocking` methods of the SDK.
+///
+/// Unlike [`block_on`], this never blocks a thread of an async runtime: if called from within
+/// one, it returns [`crate::network::Error::BlockingInAsyncContext`] naming `method`. Otherwise,
+/// the future runs on the runtime shared by the blocking methods, so that repeated calls do not
+/// create a runtime each.
+#[cfg(feature = "network")]
+pub(crate) fn block_on_blocking<T>(
+    method: &'static str,
+    fut: impl std::future::Future<Output = T>,
+) -> Result<T, crate::network::Error> {
+    ensure_not_in_async_context(method)?;
+    let rt = shared_runtime().map_err(|err| crate::network::Error::Other(err.into()))?;
+    Ok(rt.block_on(fut))
+}
+
+/// Returns [`crate::network::Error::BlockingInAsyncContext`] naming `method` if called from
+/// within an async runtime.
+#[cfg(feature = "network")]
+pub(crate) fn ensure_not_in_async_context(
+    method: &'static str,
+) -> Result<(), crate::network::Error> {
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return Err(crate::network::Error::BlockingInAsyncContext { method });
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "network"))]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::network::Error;
+
+    #[test]
+    fn test_block_on_blocking_from_threads() {
+        let threads: Vec<_> = (0..4)
+            .map(|i| {
+                std::thread::spawn(move || {
+                    block_on_blocking("test", async move {
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                        i
+                    })
+                })
+            })
+            .collect();
+        for (i, thread) in threads.into_iter().enumerate() {
+            assert_eq!(thread.join().unwrap().unwrap(), i);
+        }
+
+        // The calls share a single runtime.
+        assert!(std::ptr::eq(shared_runtime().unwrap(), shared_runtime().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_block_on_blocking_in_async_context() {
+        let err = block_on_blocking("test", async {}).unwrap_err();
+        assert!(matches!(err, Error::BlockingInAsyncContext { method: "test" }));
+        assert!(err.to_string().contains("`test`"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_block_on_blocking_in_multi_thread_runtime() {
+        let err = block_on_blocking("test", async {}).unwrap_err();
+        assert!(matches!(err, Error::BlockingInAsyncContext { method: "test" }));
     }
 }
@@ -2,7 +2,9 @@ use p3_air::BaseAir;
 use p3_field::Field;
 use p3_matrix::dense::RowMajorMatrix;
 
-use crate::{septic_digest::SepticDigest, MachineRecord};
+use crate::{
+    septic_digest::SepticDigest, ColMajorMatrix, MachineRecord, TraceBuffer, PROOF_MAX_NUM_PVS,
+};
 
 pub use sp1_derive::MachineAir;
 
@@ -41,6 +43,32 @@ pub trait MachineAir<F: Field>: BaseAir<F> + 'static + Send + Sync {
     ///   record such as byte lookup requests.
     fn generate_trace(&self, input: &Self::Record, output: &mut Self::Record) -> RowMajorMatrix<F>;
 
+    /// Generate the trace for a given execution record into a preallocated buffer.
+    ///
+    /// Chips that can write their rows in place should override this, so that the caller decides
+    /// where the trace is allocated. By default, the trace is generated with
+    /// [`MachineAir::generate_trace`] and moved into `target`.
+    fn generate_trace_into(
+        &self,
+        input: &Self::Record,
+        output: &mut Self::Record,
+        target: &mut TraceBuffer<F>,
+    ) {
+        target.set(self.generate_trace(input, output));
+    }
+
+    /// Generate the trace for a given execution record, in column-major order.
+    ///
+    /// Chips with a large trace should override this to write their columns directly. By default,
+    /// the trace is generated with [`MachineAir::generate_trace`] and transposed.
+    fn generate_trace_col_major(
+        &self,
+        input: &Self::Record,
+        output: &mut Self::Record,
+    ) -> ColMajorMatrix<F> {
+        ColMajorMatrix::from_row_major(&self.generate_trace(input, output))
+    }
+
     /// Generate the dependencies for a given execution record.
     fn generate_dependencies(&self, input: &Self::Record, output: &mut Self::Record) {
         self.generate_trace(input, output);
@@ -49,6 +77,21 @@ pub trait MachineAir<F: Field>: BaseAir<F> + 'static + Send + Sync {
     /// Whether this execution record contains events for this air.
     fn included(&self, shard: &Self::Record) -> bool;
 
+    /// Whether the air has nothing to prove in this execution record, so that a machine pruning
+    /// empty chips (see [`crate::StarkMachine::with_empty_chips_pruned`]) omits it from the shard.
+    ///
+    /// An air that overrides this must also override [`MachineAir::is_empty_program`], so that the
+    /// verifying key has no preprocessed trace for the airs omitted from the shards of the program.
+    fn is_empty_shard(&self, _shard: &Self::Record) -> bool {
+        false
+    }
+
+    /// Whether the program has no instructions for this air, so that a machine pruning empty chips
+    /// generates no preprocessed trace for it.
+    fn is_empty_program(&self, _program: &Self::Program) -> bool {
+        false
+    }
+
     /// The width of the preprocessed trace.
     fn preprocessed_width(&self) -> usize {
         0
@@ -73,6 +116,11 @@ pub trait MachineAir<F: Field>: BaseAir<F> + 'static + Send + Sync {
     fn local_only(&self) -> bool {
         false
     }
+
+    /// The number of public values that the air may read.
+    fn num_public_values(&self) -> usize {
+        PROOF_MAX_NUM_PVS
+    }
 }
 
 /// A program that defines the control flow of a machine through a program counter.
@@ -81,4 +129,9 @@ pub trait MachineProgram<F>: Send + Sync {
     fn pc_start(&self) -> F;
     /// Gets the initial global cumulative sum.
     fn initial_global_cumulative_sum(&self) -> SepticDigest<F>;
+    /// Gets a digest that identifies the preprocessed traces of the program, if they may be
+    /// cached across setups (see [`crate::PreprocessedTraceCache`]).
+    fn preprocessed_digest(&self) -> Option<[u8; 32]> {
+        None
+    }
 }
@@ -10,12 +10,10 @@ use crate::{
     air::{InteractionScope, MachineAir, MultiTableAirBuilder, SP1AirBuilder},
     local_permutation_trace_width,
     lookup::{Interaction, InteractionBuilder, InteractionKind},
+    ColMajorMatrix, TraceBuffer,
 };
 
-use super::{
-    eval_permutation_constraints, generate_permutation_trace, scoped_interactions,
-    PROOF_MAX_NUM_PVS,
-};
+use super::{eval_permutation_constraints, generate_permutation_trace, scoped_interactions};
 
 /// An Air that encodes lookups based on interactions.
 pub struct Chip<F: Field, A> {
@@ -56,6 +54,16 @@ impl<F: PrimeField32, A: MachineAir<F>> Chip<F, A> {
     pub fn included(&self, shard: &A::Record) -> bool {
         self.air.included(shard)
     }
+
+    /// Returns whether the chip has nothing to prove in the execution record of the shard.
+    pub fn is_empty_shard(&self, shard: &A::Record) -> bool {
+        self.air.is_empty_shard(shard)
+    }
+
+    /// Returns whether the program has no instructions for the chip.
+    pub fn is_empty_program(&self, program: &A::Program) -> bool {
+        self.air.is_empty_program(program)
+    }
 }
 
 impl<F, A> Chip<F, A>
@@ -68,7 +76,11 @@ where
     where
         A: MachineAir<F> + Air<InteractionBuilder<F>> + Air<SymbolicAirBuilder<F>>,
     {
-        let mut builder = InteractionBuilder::new(air.preprocessed_width(), air.width());
+        let mut builder = InteractionBuilder::with_num_public_values(
+            air.preprocessed_width(),
+            air.width(),
+            air.num_public_values(),
+        );
         air.eval(&mut builder);
         let (sends, receives) = builder.interactions();
 
@@ -81,7 +93,7 @@ where
         );
 
         let mut max_constraint_degree =
-            get_max_constraint_degree(&air, air.preprocessed_width(), PROOF_MAX_NUM_PVS);
+            get_max_constraint_degree(&air, air.preprocessed_width(), air.num_public_values());
 
         if !sends.is_empty() || !receives.is_empty() {
             max_constraint_degree = max_constraint_degree.max(3);
@@ -224,6 +236,23 @@ where
         self.air.generate_trace(input, output)
     }
 
+    fn generate_trace_into(
+        &self,
+        input: &A::Record,
+        output: &mut A::Record,
+        target: &mut TraceBuffer<F>,
+    ) {
+        self.air.generate_trace_into(input, output, target);
+    }
+
+    fn generate_trace_col_major(
+        &self,
+        input: &A::Record,
+        output: &mut A::Record,
+    ) -> ColMajorMatrix<F> {
+        self.air.generate_trace_col_major(input, output)
+    }
+
     fn generate_dependencies(&self, input: &A::Record, output: &mut A::Record) {
         self.air.generate_dependencies(input, output);
     }
@@ -239,6 +268,10 @@ where
     fn local_only(&self) -> bool {
         self.air.local_only()
     }
+
+    fn num_public_values(&self) -> usize {
+        self.air.num_public_values()
+    }
 }
 
 // Implement AIR directly on Chip, evaluating both execution and permutation constraints.
@@ -28,15 +28,19 @@ mod debug;
 mod folder;
 mod lookup;
 mod machine;
+mod observer;
 mod opts;
 mod permutation;
+mod preprocessed_cache;
 mod prover;
 mod quotient;
 mod record;
+mod report;
 pub mod septic_curve;
 pub mod septic_digest;
 pub mod septic_extension;
 pub mod shape;
+mod trace_gen;
 mod types;
 mod util;
 mod verifier;
@@ -49,11 +53,15 @@ pub use debug::*;
 pub use folder::*;
 pub use lookup::*;
 pub use machine::*;
+pub use observer::*;
 pub use opts::*;
 pub use permutation::*;
+pub use preprocessed_cache::*;
 pub use prover::*;
 pub use quotient::*;
 pub use record::*;
+pub use report::*;
+pub use trace_gen::*;
 pub use types::*;
 pub use verifier::*;
 pub use word::*;
@@ -23,6 +23,17 @@ impl<F: Field> InteractionBuilder<F> {
     /// Creates a new [`InteractionBuilder`] with the given width.
     #[must_use]
     pub fn new(preprocessed_width: usize, main_width: usize) -> Self {
+        Self::with_num_public_values(preprocessed_width, main_width, PROOF_MAX_NUM_PVS)
+    }
+
+    /// Creates a new [`InteractionBuilder`] with the given width, for an air reading the given
+    /// number of public values.
+    #[must_use]
+    pub fn with_num_public_values(
+        preprocessed_width: usize,
+        main_width: usize,
+        num_public_values: usize,
+    ) -> Self {
         let preprocessed_width = preprocessed_width.max(1);
         let prep_values = [0, 1]
             .into_iter()
@@ -46,7 +57,7 @@ impl<F: Field> InteractionBuilder<F> {
             main: RowMajorMatrix::new(main_values, main_width),
             sends: vec![],
             receives: vec![],
-            public_values: vec![F::zero(); PROOF_MAX_NUM_PVS],
+            public_values: vec![F::zero(); num_public_values],
         }
     }
 
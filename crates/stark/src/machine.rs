@@ -10,7 +10,7 @@ use p3_field::{AbstractExtensionField, AbstractField, Field, PrimeField32};
 use p3_matrix::{dense::RowMajorMatrix, Dimensions, Matrix};
 use p3_maybe_rayon::prelude::*;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use std::{cmp::Reverse, env, fmt::Debug, iter::once, time::Instant};
+use std::{cmp::Reverse, env, fmt::Debug, iter::once, sync::Arc, time::Instant};
 use tracing::instrument;
 
 use super::{debug_constraints, Dom};
@@ -18,7 +18,9 @@ use crate::{
     air::{InteractionScope, MachineAir, MachineProgram},
     lookup::{debug_interactions_with_all_chips, InteractionKind},
     record::MachineRecord,
-    DebugConstraintBuilder, ShardProof, VerifierConstraintFolder,
+    trace_gen::generate_concurrently,
+    ChipTraceGenTiming, DebugConstraintBuilder, PreprocessedTraceCache, ShardProof, TraceGenReport,
+    VerifierConstraintFolder,
 };
 
 use super::{
@@ -40,6 +42,15 @@ pub struct StarkMachine<SC: StarkGenericConfig, A> {
 
     /// Contains a global bus.  This should be true for the core machine and false otherwise.
     contains_global_bus: bool,
+
+    /// The cache of preprocessed traces, if enabled.
+    preprocessed_cache: Option<Arc<PreprocessedTraceCache<Val<SC>>>>,
+
+    /// The bound on the estimated bytes of the main traces generated concurrently, if any.
+    max_trace_gen_bytes: Option<usize>,
+
+    /// Whether chips with nothing to prove are omitted from the shards and the verifying key.
+    prune_empty_chips: bool,
 }
 
 impl<SC: StarkGenericConfig, A> StarkMachine<SC, A> {
@@ -50,7 +61,65 @@ impl<SC: StarkGenericConfig, A> StarkMachine<SC, A> {
         num_pv_elts: usize,
         contains_global_bus: bool,
     ) -> Self {
-        Self { config, chips, num_pv_elts, contains_global_bus }
+        Self {
+            config,
+            chips,
+            num_pv_elts,
+            contains_global_bus,
+            preprocessed_cache: None,
+            max_trace_gen_bytes: None,
+            prune_empty_chips: false,
+        }
+    }
+
+    /// Caches the preprocessed traces of up to `capacity` programs across setups.
+    ///
+    /// Only the traces of programs that have a digest are cached (see
+    /// [`MachineProgram::preprocessed_digest`]).
+    #[must_use]
+    pub fn with_preprocessed_cache(mut self, capacity: usize) -> Self {
+        self.preprocessed_cache = Some(Arc::new(PreprocessedTraceCache::new(capacity)));
+        self
+    }
+
+    /// Returns the cache of preprocessed traces, if enabled.
+    #[must_use]
+    pub fn preprocessed_cache(&self) -> Option<&PreprocessedTraceCache<Val<SC>>> {
+        self.preprocessed_cache.as_deref()
+    }
+
+    /// Bounds the estimated bytes of the main traces that are generated concurrently.
+    ///
+    /// The traces of a shard are then generated in batches, largest first, whose estimated sizes
+    /// add up to at most `max_bytes`. Only chips that know their number of rows before generating
+    /// (see [`MachineAir::num_rows`]) are accounted for.
+    #[must_use]
+    pub fn with_max_trace_gen_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_trace_gen_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Returns the bound on the estimated bytes of the main traces generated concurrently, if any.
+    #[must_use]
+    pub fn max_trace_gen_bytes(&self) -> Option<usize> {
+        self.max_trace_gen_bytes
+    }
+
+    /// Omits the chips with nothing to prove from the shards, and their preprocessed traces from
+    /// the verifying key (see [`MachineAir::is_empty_shard`] and [`MachineAir::is_empty_program`]).
+    ///
+    /// The set of chips of a shard then varies, so the prover and the verifier observe which chips
+    /// are present (see [`StarkMachine::chip_presence`]) before the main commitment.
+    #[must_use]
+    pub fn with_empty_chips_pruned(mut self) -> Self {
+        self.prune_empty_chips = true;
+        self
+    }
+
+    /// Returns whether chips with nothing to prove are omitted.
+    #[must_use]
+    pub fn prunes_empty_chips(&self) -> bool {
+        self.prune_empty_chips
     }
 }
 
@@ -156,7 +225,9 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
     where
         'a: 'b,
     {
-        self.chips.iter().filter(|chip| chip.included(shard))
+        self.chips.iter().filter(|chip| {
+            chip.included(shard) && !(self.prune_empty_chips && chip.is_empty_shard(shard))
+        })
     }
 
     /// Returns an iterator over the chips in the machine that are included in the given shard.
@@ -173,6 +244,19 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
             .sorted_by_key(|chip| chip_ordering.get(&chip.name()))
     }
 
+    /// Returns, for each chip of the machine in order, one if it is present in a shard with the
+    /// given chip ordering and zero otherwise.
+    #[must_use]
+    pub fn chip_presence<T: AbstractField>(
+        &self,
+        chip_ordering: &HashMap<String, usize>,
+    ) -> Vec<T> {
+        self.chips
+            .iter()
+            .map(|chip| T::from_bool(chip_ordering.contains_key(&chip.name())))
+            .collect()
+    }
+
     /// Returns the indices of the chips in the machine that are included in the given shard.
     pub fn chips_sorted_indices(&self, proof: &ShardProof<SC>) -> Vec<Option<usize>> {
         self.chips().iter().map(|chip| proof.chip_ordering.get(&chip.name()).copied()).collect()
@@ -184,31 +268,56 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
         program: &A::Program,
         initial_global_cumulative_sum: SepticDigest<Val<SC>>,
     ) -> (StarkProvingKey<SC>, StarkVerifyingKey<SC>) {
-        let parent_span = tracing::debug_span!("generate preprocessed traces");
-        let mut named_preprocessed_traces = parent_span.in_scope(|| {
-            self.chips()
-                .par_iter()
-                .filter_map(|chip| {
-                    let chip_name = chip.name();
-                    let begin = Instant::now();
-                    let prep_trace = chip.generate_preprocessed_trace(program);
-                    tracing::debug!(
-                        parent: &parent_span,
-                        "generated preprocessed trace for chip {} in {:?}",
-                        chip_name,
-                        begin.elapsed()
-                    );
-                    // Assert that the chip width data is correct.
-                    let expected_width = prep_trace.as_ref().map_or(0, p3_matrix::Matrix::width);
-                    assert_eq!(
-                        expected_width,
-                        chip.preprocessed_width(),
-                        "Incorrect number of preprocessed columns for chip {chip_name}"
-                    );
-                    prep_trace.map(move |t| (chip_name, chip.local_only(), t))
-                })
-                .collect::<Vec<_>>()
-        });
+        // Hash the program once for all the chips, and only if the traces can be cached.
+        let cache = self
+            .preprocessed_cache()
+            .and_then(|cache| Some((cache, program.preprocessed_digest()?)));
+
+        // The sizes of the preprocessed traces are not known before generating them, so they are
+        // all generated concurrently.
+        let begin = Instant::now();
+        let (prep_traces, batches) =
+            tracing::debug_span!("generate preprocessed traces").in_scope(|| {
+                generate_concurrently(
+                    self.chips(),
+                    None,
+                    |_| 0,
+                    |chip| match &cache {
+                        _ if self.prune_empty_chips && chip.is_empty_program(program) => None,
+                        Some((cache, digest)) => cache
+                            .get_or_generate(digest, &chip.name(), || {
+                                chip.generate_preprocessed_trace(program)
+                            })
+                            .map(Arc::unwrap_or_clone),
+                        None => chip.generate_preprocessed_trace(program),
+                    },
+                )
+            });
+
+        let mut report = TraceGenReport { batches, elapsed: begin.elapsed(), ..Default::default() };
+        let mut named_preprocessed_traces = Vec::new();
+        for (chip, (prep_trace, elapsed)) in self.chips().iter().zip(prep_traces) {
+            let chip_name = chip.name();
+            // Assert that the chip width data is correct.
+            let expected_width = prep_trace.as_ref().map_or(0, p3_matrix::Matrix::width);
+            if !(self.prune_empty_chips && chip.is_empty_program(program)) {
+                assert_eq!(
+                    expected_width,
+                    chip.preprocessed_width(),
+                    "Incorrect number of preprocessed columns for chip {chip_name}"
+                );
+            }
+            if let Some(trace) = prep_trace {
+                report.chips.push(ChipTraceGenTiming {
+                    chip: chip_name.clone(),
+                    height: trace.height(),
+                    width: trace.width(),
+                    elapsed,
+                });
+                named_preprocessed_traces.push((chip_name, chip.local_only(), trace));
+            }
+        }
+        tracing::debug!("generated preprocessed traces: {report:?}");
 
         // Order the chips and traces by trace size (biggest first), and get the ordering map.
         named_preprocessed_traces
@@ -341,6 +450,11 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
                     let mut shard_challenger = challenger.clone();
                     shard_challenger
                         .observe_slice(&shard_proof.public_values[0..self.num_pv_elts()]);
+                    if self.prune_empty_chips {
+                        shard_challenger.observe_slice(
+                            &self.chip_presence::<Val<SC>>(&shard_proof.chip_ordering),
+                        );
+                    }
                     Verifier::verify_shard(
                         &self.config,
                         vk,
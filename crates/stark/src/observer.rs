@@ -0,0 +1,268 @@
+use std::{
+    fmt::{self, Display},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ShardTiming;
+
+/// A stage of proving a shard.
+///
+/// The stages of a shard are reported in the order in which they are listed here. The names
+/// returned by [`ProverStage::name`] are stable, so that they can be used as keys by tools that
+/// aggregate the events of many proofs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ProverStage {
+    /// The generation of the main trace of a chip.
+    TraceGen {
+        /// The name of the chip.
+        chip: String,
+        /// The number of rows of the trace. When the stage starts, this is the number of rows
+        /// expected from the record.
+        rows: usize,
+    },
+    /// The commitment to the main traces.
+    Commit,
+    /// The generation of and the commitment to the permutation traces.
+    Permutation,
+    /// The computation of and the commitment to the quotient polynomials.
+    Quotient,
+    /// The opening of the commitments.
+    Open,
+}
+
+impl ProverStage {
+    /// The stable name of the stage.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            ProverStage::TraceGen { .. } => "trace_gen",
+            ProverStage::Commit => "commit",
+            ProverStage::Permutation => "permutation",
+            ProverStage::Quotient => "quotient",
+            ProverStage::Open => "open",
+        }
+    }
+}
+
+/// Whether a [`ProverEvent`] marks the start or the end of its stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StageStatus {
+    /// The stage has started.
+    Started,
+    /// The stage has finished after the given time.
+    Finished {
+        /// The time spent in the stage.
+        elapsed: Duration,
+    },
+}
+
+/// A stage of proving a shard starting or finishing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProverEvent {
+    /// The index of the shard, or of the recursion proof, being proven.
+    pub shard: usize,
+    /// The stage.
+    pub stage: ProverStage,
+    /// Whether the stage has started or finished.
+    pub status: StageStatus,
+    /// The wall-clock time of the event.
+    pub timestamp: SystemTime,
+}
+
+impl Display for ProverEvent {
+    /// Formats the event as a row of a table of stages.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let timestamp =
+            self.timestamp.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs_f64();
+        let (chip, rows) = match &self.stage {
+            ProverStage::TraceGen { chip, rows } => (chip.as_str(), rows.to_string()),
+            _ => ("", String::new()),
+        };
+        let status = match self.status {
+            StageStatus::Started => "started".to_string(),
+            StageStatus::Finished { elapsed } => format!("{elapsed:?}"),
+        };
+        write!(
+            f,
+            "{timestamp:>17.3} | {:>5} | {:<11} | {chip:<24} | {rows:>9} | {status}",
+            self.shard,
+            self.stage.name(),
+        )
+    }
+}
+
+/// An observer of the progress of a prover.
+///
+/// The prover calls [`ProverObserver::on_event`] from the threads generating the shard proofs, so
+/// events of different shards, and of the chips of a shard, may interleave. The default
+/// implementation does nothing.
+pub trait ProverObserver: Send + Sync {
+    /// Called when a stage of proving a shard starts or finishes.
+    fn on_event(&self, event: &ProverEvent) {
+        let _ = event;
+    }
+
+    /// Called when a shard of a core proof is proven, with the number of shards proven so far.
+    ///
+    /// The shards are proven concurrently, so they may complete out of order.
+    fn on_shard_proven(&self, timing: &ShardTiming, num_proven: usize) {
+        let _ = (timing, num_proven);
+    }
+}
+
+impl<T: ProverObserver + ?Sized> ProverObserver for &T {
+    fn on_event(&self, event: &ProverEvent) {
+        (**self).on_event(event);
+    }
+
+    fn on_shard_proven(&self, timing: &ShardTiming, num_proven: usize) {
+        (**self).on_shard_proven(timing, num_proven);
+    }
+}
+
+impl<T: ProverObserver + ?Sized> ProverObserver for Arc<T> {
+    fn on_event(&self, event: &ProverEvent) {
+        (**self).on_event(event);
+    }
+
+    fn on_shard_proven(&self, timing: &ShardTiming, num_proven: usize) {
+        (**self).on_shard_proven(timing, num_proven);
+    }
+}
+
+/// An observer that ignores all events.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopObserver;
+
+impl ProverObserver for NoopObserver {}
+
+/// An observer that logs the events with [`tracing`], finished stages at the info level and
+/// started stages at the debug level.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TracingObserver;
+
+impl ProverObserver for TracingObserver {
+    fn on_event(&self, event: &ProverEvent) {
+        match event.status {
+            StageStatus::Started => tracing::debug!("{event}"),
+            StageStatus::Finished { .. } => tracing::info!("{event}"),
+        }
+    }
+
+    fn on_shard_proven(&self, timing: &ShardTiming, num_proven: usize) {
+        tracing::info!(
+            "shard {} proven in {:?} ({num_proven} shards proven)",
+            timing.shard,
+            timing.prove_time
+        );
+    }
+}
+
+/// A [`ProverObserver`] bound to the shard whose stages it reports.
+#[derive(Clone, Copy)]
+pub struct ShardObserver<'a> {
+    observer: &'a dyn ProverObserver,
+    shard: usize,
+}
+
+impl<'a> ShardObserver<'a> {
+    /// Creates an observer reporting the stages of the given shard to `observer`.
+    #[must_use]
+    pub fn new(observer: &'a dyn ProverObserver, shard: usize) -> Self {
+        Self { observer, shard }
+    }
+
+    /// An observer that ignores all events.
+    #[must_use]
+    pub fn noop() -> Self {
+        Self { observer: &NoopObserver, shard: 0 }
+    }
+
+    /// The index of the shard.
+    #[must_use]
+    pub fn shard(&self) -> usize {
+        self.shard
+    }
+
+    /// Reports that a stage has started.
+    pub fn started(&self, stage: ProverStage) {
+        self.report(stage, StageStatus::Started);
+    }
+
+    /// Reports that a stage has finished after `elapsed`.
+    pub fn finished(&self, stage: ProverStage, elapsed: Duration) {
+        self.report(stage, StageStatus::Finished { elapsed });
+    }
+
+    /// Runs `f` as the given stage, reporting its start and end.
+    pub fn in_stage<R>(&self, stage: ProverStage, f: impl FnOnce() -> R) -> R {
+        self.started(stage.clone());
+        let begin = Instant::now();
+        let output = f();
+        self.finished(stage, begin.elapsed());
+        output
+    }
+
+    fn report(&self, stage: ProverStage, status: StageStatus) {
+        self.observer.on_event(&ProverEvent {
+            shard: self.shard,
+            stage,
+            status,
+            timestamp: SystemTime::now(),
+        });
+    }
+}
+
+impl fmt::Debug for ShardObserver<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShardObserver").field("shard", &self.shard).finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<ProverEvent>>);
+
+    impl ProverObserver for Recorder {
+        fn on_event(&self, event: &ProverEvent) {
+            self.0.lock().unwrap().push(event.clone());
+        }
+    }
+
+    #[test]
+    fn in_stage_reports_start_and_end() {
+        let recorder = Recorder::default();
+        let observer = ShardObserver::new(&recorder, 3);
+        let output = observer.in_stage(ProverStage::Commit, || 7);
+        assert_eq!(output, 7);
+
+        let events = recorder.0.into_inner().unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|event| event.shard == 3 && event.stage == ProverStage::Commit));
+        assert_eq!(events[0].status, StageStatus::Started);
+        assert!(matches!(events[1].status, StageStatus::Finished { .. }));
+        assert!(events[0].timestamp <= events[1].timestamp);
+    }
+
+    #[test]
+    fn stage_names() {
+        let trace_gen = ProverStage::TraceGen { chip: "Cpu".to_string(), rows: 8 };
+        let names = [
+            trace_gen,
+            ProverStage::Commit,
+            ProverStage::Permutation,
+            ProverStage::Quotient,
+            ProverStage::Open,
+        ]
+        .map(|stage| stage.name());
+        assert_eq!(names, ["trace_gen", "commit", "permutation", "quotient", "open"]);
+    }
+}
@@ -44,6 +44,7 @@ impl SP1ProverOpts {
         let mut opts = SP1ProverOpts::default();
         opts.core_opts.shard_size = 1 << log2_shard_size;
         opts.core_opts.shard_batch_size = shard_batch_size;
+        opts.core_opts.shard_concurrency = shard_concurrency_from_env().unwrap_or(shard_batch_size);
 
         opts.core_opts.records_and_traces_channel_capacity = 1;
         opts.core_opts.trace_gen_workers = 1;
@@ -53,6 +54,9 @@ impl SP1ProverOpts {
         opts.core_opts.split_opts.keccak /= divisor;
         opts.core_opts.split_opts.sha_extend /= divisor;
         opts.core_opts.split_opts.sha_compress /= divisor;
+        opts.core_opts.split_opts.sha512_extend /= divisor;
+        opts.core_opts.split_opts.sha512_compress /= divisor;
+        opts.core_opts.split_opts.bls12381_fp12_mul /= divisor;
         opts.core_opts.split_opts.memory /= divisor;
 
         opts.recursion_opts.shard_batch_size = 2;
@@ -109,6 +113,11 @@ pub struct SP1CoreOpts {
     pub checkpoints_channel_capacity: usize,
     /// The capacity of the channel for records and traces.
     pub records_and_traces_channel_capacity: usize,
+    /// The number of shards proven concurrently.
+    ///
+    /// Each shard is proven by the first idle worker once its traces are generated, so that a slow
+    /// shard does not hold up the others. The proofs are still output in shard order.
+    pub shard_concurrency: usize,
 }
 
 impl Default for SP1ProverOpts {
@@ -127,12 +136,14 @@ impl Default for SP1CoreOpts {
         let shard_size = env::var("SHARD_SIZE")
             .map_or_else(|_| MAX_SHARD_SIZE, |s| s.parse::<usize>().unwrap_or(MAX_SHARD_SIZE));
 
+        let shard_batch_size = env::var("SHARD_BATCH_SIZE").map_or_else(
+            |_| MAX_SHARD_BATCH_SIZE,
+            |s| s.parse::<usize>().unwrap_or(MAX_SHARD_BATCH_SIZE),
+        );
+
         Self {
             shard_size,
-            shard_batch_size: env::var("SHARD_BATCH_SIZE").map_or_else(
-                |_| MAX_SHARD_BATCH_SIZE,
-                |s| s.parse::<usize>().unwrap_or(MAX_SHARD_BATCH_SIZE),
-            ),
+            shard_batch_size,
             split_opts: SplitOpts::new(split_threshold),
             trace_gen_workers: env::var("TRACE_GEN_WORKERS").map_or_else(
                 |_| DEFAULT_TRACE_GEN_WORKERS,
@@ -147,10 +158,19 @@ impl Default for SP1CoreOpts {
                     |_| DEFAULT_RECORDS_AND_TRACES_CHANNEL_CAPACITY,
                     |s| s.parse::<usize>().unwrap_or(DEFAULT_RECORDS_AND_TRACES_CHANNEL_CAPACITY),
                 ),
+            shard_concurrency: shard_concurrency_from_env().unwrap_or(shard_batch_size),
         }
     }
 }
 
+/// The number of shards to prove concurrently set by `SP1_PROVER_SHARD_CONCURRENCY`, if any.
+fn shard_concurrency_from_env() -> Option<usize> {
+    env::var("SP1_PROVER_SHARD_CONCURRENCY")
+        .ok()
+        .and_then(|s| s.parse::<usize>().ok())
+        .filter(|&concurrency| concurrency > 0)
+}
+
 impl SP1CoreOpts {
     /// Get the default options for the recursion prover.
     #[must_use]
@@ -175,6 +195,12 @@ pub struct SplitOpts {
     pub sha_extend: usize,
     /// The threshold for sha compress events.
     pub sha_compress: usize,
+    /// The threshold for sha512 extend events.
+    pub sha512_extend: usize,
+    /// The threshold for sha512 compress events.
+    pub sha512_compress: usize,
+    /// The threshold for BLS12-381 Fp12 multiplication events.
+    pub bls12381_fp12_mul: usize,
     /// The threshold for memory events.
     pub memory: usize,
 }
@@ -192,6 +218,9 @@ impl SplitOpts {
             keccak: 8 * deferred_split_threshold / 24,
             sha_extend: 32 * deferred_split_threshold / 48,
             sha_compress: 32 * deferred_split_threshold / 80,
+            sha512_extend: 16 * deferred_split_threshold / 64,
+            sha512_compress: 16 * deferred_split_threshold / 96,
+            bls12381_fp12_mul: deferred_split_threshold / 8,
             memory: 64 * deferred_split_threshold,
         }
     }
@@ -0,0 +1,136 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use hashbrown::HashMap;
+use p3_matrix::dense::RowMajorMatrix;
+
+/// The preprocessed traces of a program, by chip name.
+type ProgramTraces<F> = HashMap<String, Arc<RowMajorMatrix<F>>>;
+
+/// A cache of the preprocessed traces of programs, so that repeated setups of the same program
+/// reuse the traces instead of regenerating them.
+///
+/// Traces are keyed by the digest of the program (see
+/// [`crate::air::MachineProgram::preprocessed_digest`]) and the name of the chip. The digest
+/// covers the whole program, including any fixed trace heights, so programs that differ in any
+/// way never share traces. Once more than `capacity` programs are cached, the traces of the
+/// program that was cached first are evicted.
+pub struct PreprocessedTraceCache<F> {
+    capacity: usize,
+    inner: Mutex<PreprocessedTraceCacheInner<F>>,
+}
+
+struct PreprocessedTraceCacheInner<F> {
+    programs: HashMap<[u8; 32], ProgramTraces<F>>,
+    /// The digests of the cached programs, in the order they were cached.
+    order: VecDeque<[u8; 32]>,
+}
+
+impl<F> PreprocessedTraceCache<F> {
+    /// Creates a cache that holds the traces of up to `capacity` programs.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(PreprocessedTraceCacheInner {
+                programs: HashMap::new(),
+                order: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// The number of programs whose traces are cached.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().programs.len()
+    }
+
+    /// Whether no traces are cached.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the cached trace of the chip for the program with the given digest, generating and
+    /// caching it if needed.
+    ///
+    /// The cache is not locked while the trace is generated, so the traces of different chips can
+    /// be generated in parallel.
+    #[must_use]
+    pub fn get_or_generate(
+        &self,
+        digest: &[u8; 32],
+        chip_name: &str,
+        generate: impl FnOnce() -> Option<RowMajorMatrix<F>>,
+    ) -> Option<Arc<RowMajorMatrix<F>>> {
+        if let Some(trace) = self.get(digest, chip_name) {
+            return Some(trace);
+        }
+        let trace = Arc::new(generate()?);
+        self.insert(*digest, chip_name, trace.clone());
+        Some(trace)
+    }
+
+    fn get(&self, digest: &[u8; 32], chip_name: &str) -> Option<Arc<RowMajorMatrix<F>>> {
+        let inner = self.inner.lock().unwrap();
+        inner.programs.get(digest)?.get(chip_name).cloned()
+    }
+
+    fn insert(&self, digest: [u8; 32], chip_name: &str, trace: Arc<RowMajorMatrix<F>>) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut inner = self.inner.lock().unwrap();
+        if !inner.programs.contains_key(&digest) {
+            if inner.order.len() == self.capacity {
+                if let Some(evicted) = inner.order.pop_front() {
+                    inner.programs.remove(&evicted);
+                }
+            }
+            inner.order.push_back(digest);
+        }
+        inner.programs.entry(digest).or_default().insert(chip_name.to_string(), trace);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trace(value: u32) -> Option<RowMajorMatrix<u32>> {
+        Some(RowMajorMatrix::new(vec![value; 4], 2))
+    }
+
+    #[test]
+    fn test_get_or_generate() {
+        let cache = PreprocessedTraceCache::new(2);
+        let first = cache.get_or_generate(&[1; 32], "Select", || trace(1)).unwrap();
+        let cached = cache.get_or_generate(&[1; 32], "Select", || panic!("not cached")).unwrap();
+        assert!(Arc::ptr_eq(&first, &cached));
+
+        // Other chips and programs are generated separately.
+        assert_eq!(cache.get_or_generate(&[1; 32], "BaseAlu", || trace(2)).unwrap().values[0], 2);
+        assert_eq!(cache.get_or_generate(&[2; 32], "Select", || trace(3)).unwrap().values[0], 3);
+        assert_eq!(cache.len(), 2);
+
+        // Chips without preprocessed traces are not cached.
+        assert!(cache.get_or_generate(&[1; 32], "Cpu", || None).is_none());
+    }
+
+    #[test]
+    fn test_eviction() {
+        let cache = PreprocessedTraceCache::new(2);
+        for program in 1..=3 {
+            let _ = cache.get_or_generate(&[program; 32], "Select", || trace(program.into()));
+        }
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get_or_generate(&[1; 32], "Select", || trace(4)).unwrap().values[0], 4);
+        assert_eq!(cache.get_or_generate(&[3; 32], "Select", || trace(5)).unwrap().values[0], 3);
+
+        let disabled = PreprocessedTraceCache::new(0);
+        let _ = disabled.get_or_generate(&[1; 32], "Select", || trace(1));
+        assert!(disabled.is_empty());
+    }
+}
@@ -4,7 +4,7 @@ use crate::septic_extension::SepticExtension;
 use crate::{air::InteractionScope, AirOpenedValues, ChipOpenedValues, ShardOpenedValues};
 use core::fmt::Display;
 use itertools::Itertools;
-use p3_air::Air;
+use p3_air::{Air, BaseAir};
 use p3_challenger::{CanObserve, FieldChallenger};
 use p3_commit::{Pcs, PolynomialSpace};
 use p3_field::{AbstractExtensionField, AbstractField, PrimeField32};
@@ -23,6 +23,10 @@ use crate::{
     Challenger, DebugConstraintBuilder, MachineChip, MachineProof, PackedChallenge, PcsProverData,
     ProverConstraintFolder, ShardCommitment, ShardMainData, ShardProof, StarkVerifyingKey,
 };
+use crate::{
+    trace_gen::generate_concurrently, ChipTraceGenTiming, ColMajorMatrix, NoopObserver,
+    ProverObserver, ProverStage, ShardObserver, TraceGenReport, TraceLayout, TraceMatrix,
+};
 
 /// An algorithmic & hardware independent prover implementation for any [`MachineAir`].
 pub trait MachineProver<SC: StarkGenericConfig, A: MachineAir<SC::Val>>:
@@ -65,27 +69,92 @@ pub trait MachineProver<SC: StarkGenericConfig, A: MachineAir<SC::Val>>:
 
     /// Generate the main traces.
     fn generate_traces(&self, record: &A::Record) -> Vec<(String, RowMajorMatrix<Val<SC>>)> {
-        let shard_chips = self.shard_chips(record).collect::<Vec<_>>();
+        self.generate_traces_with_observer(record, ShardObserver::noop())
+    }
 
-        // For each chip, generate the trace.
-        let parent_span = tracing::debug_span!("generate traces for shard");
-        parent_span.in_scope(|| {
-            shard_chips
-                .par_iter()
-                .map(|chip| {
-                    let chip_name = chip.name();
-                    let begin = Instant::now();
-                    let trace = chip.generate_trace(record, &mut A::Record::default());
-                    tracing::debug!(
-                        parent: &parent_span,
-                        "generated trace for chip {} in {:?}",
-                        chip_name,
-                        begin.elapsed()
-                    );
-                    (chip_name, trace)
-                })
-                .collect::<Vec<_>>()
-        })
+    /// Generate the main traces, reporting the generation of the trace of each chip to
+    /// `observer`.
+    fn generate_traces_with_observer(
+        &self,
+        record: &A::Record,
+        observer: ShardObserver<'_>,
+    ) -> Vec<(String, RowMajorMatrix<Val<SC>>)> {
+        let (traces, report) = generate_shard_traces(
+            self,
+            record,
+            |chip| chip.generate_trace(record, &mut A::Record::default()),
+            |trace| (trace.height(), trace.width()),
+            observer,
+        );
+        tracing::debug!("generated traces for shard: {report:?}");
+        traces
+    }
+
+    /// Generate the main traces, along with the time spent generating the trace of each chip.
+    ///
+    /// The traces of the chips are generated concurrently, largest first, and bounded by
+    /// [`StarkMachine::max_trace_gen_bytes`] if set.
+    fn generate_traces_with_report(
+        &self,
+        record: &A::Record,
+    ) -> (Vec<(String, RowMajorMatrix<Val<SC>>)>, TraceGenReport) {
+        generate_shard_traces(
+            self,
+            record,
+            |chip| chip.generate_trace(record, &mut A::Record::default()),
+            |trace| (trace.height(), trace.width()),
+            ShardObserver::noop(),
+        )
+    }
+
+    /// Generate the main traces in column-major order, along with the time spent generating the
+    /// trace of each chip.
+    ///
+    /// The traces are scheduled as in [`MachineProver::generate_traces_with_report`].
+    fn generate_traces_col_major_with_report(
+        &self,
+        record: &A::Record,
+    ) -> (Vec<(String, ColMajorMatrix<Val<SC>>)>, TraceGenReport) {
+        generate_shard_traces(
+            self,
+            record,
+            |chip| chip.generate_trace_col_major(record, &mut A::Record::default()),
+            |trace| (trace.height(), trace.width()),
+            ShardObserver::noop(),
+        )
+    }
+
+    /// The layout of the traces that this backend commits to.
+    ///
+    /// Backends that commit to the columns of the traces, such as GPU provers, return
+    /// [`TraceLayout::ColMajor`], so that the chips write their columns directly instead of the
+    /// traces being transposed before the commitment.
+    fn trace_layout(&self) -> TraceLayout {
+        TraceLayout::RowMajor
+    }
+
+    /// Generate the main traces in the [layout](MachineProver::trace_layout) of this backend.
+    fn generate_traces_in_layout(&self, record: &A::Record) -> Vec<(String, TraceMatrix<Val<SC>>)> {
+        let (traces, report) = match self.trace_layout() {
+            TraceLayout::RowMajor => {
+                let (traces, report) = self.generate_traces_with_report(record);
+                let traces = traces
+                    .into_iter()
+                    .map(|(name, trace)| (name, TraceMatrix::RowMajor(trace)))
+                    .collect::<Vec<_>>();
+                (traces, report)
+            }
+            TraceLayout::ColMajor => {
+                let (traces, report) = self.generate_traces_col_major_with_report(record);
+                let traces = traces
+                    .into_iter()
+                    .map(|(name, trace)| (name, TraceMatrix::ColMajor(trace)))
+                    .collect::<Vec<_>>();
+                (traces, report)
+            }
+        };
+        tracing::debug!("generated traces for shard: {report:?}");
+        traces
     }
 
     /// Commit to the main traces.
@@ -115,6 +184,18 @@ pub trait MachineProver<SC: StarkGenericConfig, A: MachineAir<SC::Val>>:
         pk: &Self::DeviceProvingKey,
         data: ShardMainData<SC, Self::DeviceMatrix, Self::DeviceProverData>,
         challenger: &mut SC::Challenger,
+    ) -> Result<ShardProof<SC>, Self::Error> {
+        self.open_with_observer(pk, data, challenger, ShardObserver::noop())
+    }
+
+    /// Compute the openings of the traces, reporting the permutation, quotient and opening stages
+    /// to `observer`.
+    fn open_with_observer(
+        &self,
+        pk: &Self::DeviceProvingKey,
+        data: ShardMainData<SC, Self::DeviceMatrix, Self::DeviceProverData>,
+        challenger: &mut SC::Challenger,
+        observer: ShardObserver<'_>,
     ) -> Result<ShardProof<SC>, Self::Error>;
 
     /// Generate a proof for the given records.
@@ -125,6 +206,23 @@ pub trait MachineProver<SC: StarkGenericConfig, A: MachineAir<SC::Val>>:
         challenger: &mut SC::Challenger,
         opts: <A::Record as MachineRecord>::Config,
     ) -> Result<MachineProof<SC>, Self::Error>
+    where
+        A: for<'a> Air<DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>,
+    {
+        self.prove_with_observer(pk, records, challenger, opts, &NoopObserver)
+    }
+
+    /// Generate a proof for the given records, reporting the stages of each shard to `observer`.
+    ///
+    /// The shards are indexed by the position of their record in `records`.
+    fn prove_with_observer(
+        &self,
+        pk: &Self::DeviceProvingKey,
+        records: Vec<A::Record>,
+        challenger: &mut SC::Challenger,
+        opts: <A::Record as MachineRecord>::Config,
+        observer: &dyn ProverObserver,
+    ) -> Result<MachineProof<SC>, Self::Error>
     where
         A: for<'a> Air<DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>;
 
@@ -179,6 +277,62 @@ pub trait MachineProvingKey<SC: StarkGenericConfig>: Send + Sync {
     fn observe_into(&self, challenger: &mut Challenger<SC>);
 }
 
+/// Generates the traces of the chips of a shard concurrently, largest first, and bounded by
+/// [`StarkMachine::max_trace_gen_bytes`] if set, reporting the time spent on each chip.
+fn generate_shard_traces<SC, A, P, T>(
+    prover: &P,
+    record: &A::Record,
+    generate: impl Fn(&MachineChip<SC, A>) -> T + Sync,
+    dimensions: impl Fn(&T) -> (usize, usize) + Sync,
+    observer: ShardObserver<'_>,
+) -> (Vec<(String, T)>, TraceGenReport)
+where
+    SC: StarkGenericConfig,
+    A: MachineAir<SC::Val>,
+    P: MachineProver<SC, A> + ?Sized,
+    T: Send,
+{
+    let shard_chips = prover.shard_chips(record).collect::<Vec<_>>();
+
+    let begin = Instant::now();
+    let (traces, batches) = tracing::debug_span!("generate traces for shard").in_scope(|| {
+        generate_concurrently(
+            &shard_chips,
+            prover.machine().max_trace_gen_bytes(),
+            |chip| {
+                chip.num_rows(record).unwrap_or(0) * chip.width() * std::mem::size_of::<Val<SC>>()
+            },
+            |chip| {
+                let rows = chip.num_rows(record).unwrap_or(0);
+                observer.started(ProverStage::TraceGen { chip: chip.name(), rows });
+                let begin = Instant::now();
+                let trace = generate(chip);
+                let stage = ProverStage::TraceGen { chip: chip.name(), rows: dimensions(&trace).0 };
+                observer.finished(stage, begin.elapsed());
+                trace
+            },
+        )
+    });
+
+    let mut report = TraceGenReport { batches, elapsed: begin.elapsed(), ..Default::default() };
+    let traces = shard_chips
+        .iter()
+        .zip(traces)
+        .map(|(chip, (trace, elapsed))| {
+            let chip_name = chip.name();
+            let (height, width) = dimensions(&trace);
+            report.chips.push(ChipTraceGenTiming {
+                chip: chip_name.clone(),
+                height,
+                width,
+                elapsed,
+            });
+            (chip_name, trace)
+        })
+        .collect();
+    (traces, report)
+}
+
 /// A prover implementation based on x86 and ARM CPUs.
 pub struct CpuProver<SC: StarkGenericConfig, A> {
     machine: StarkMachine<SC, A>,
@@ -275,11 +429,12 @@ where
     #[allow(clippy::too_many_lines)]
     #[allow(clippy::redundant_closure_for_method_calls)]
     #[allow(clippy::map_unwrap_or)]
-    fn open(
+    fn open_with_observer(
         &self,
         pk: &StarkProvingKey<SC>,
         data: ShardMainData<SC, Self::DeviceMatrix, Self::DeviceProverData>,
         challenger: &mut <SC as StarkGenericConfig>::Challenger,
+        observer: ShardObserver<'_>,
     ) -> Result<ShardProof<SC>, Self::Error> {
         let chips = self.machine().shard_chips_ordered(&data.chip_ordering).collect::<Vec<_>>();
         let traces = data.traces;
@@ -298,8 +453,12 @@ where
         let trace_domains =
             degrees.iter().map(|degree| pcs.natural_domain_for_degree(*degree)).collect::<Vec<_>>();
 
-        // Observe the public values and the main commitment.
+        // Observe the public values, the chips present if they vary, and the main commitment.
         challenger.observe_slice(&data.public_values[0..self.num_pv_elts()]);
+        if self.machine().prunes_empty_chips() {
+            let chip_presence = self.machine().chip_presence::<Val<SC>>(&data.chip_ordering);
+            challenger.observe_slice(&chip_presence);
+        }
         challenger.observe(data.main_commit.clone());
 
         // Obtain the challenges used for the local permutation argument.
@@ -314,6 +473,8 @@ where
             .collect::<Vec<_>>();
 
         // Generate the permutation traces.
+        observer.started(ProverStage::Permutation);
+        let begin = Instant::now();
         let ((permutation_traces, prep_traces), (global_cumulative_sums, local_cumulative_sums)): (
             (Vec<_>, Vec<_>),
             (Vec<_>, Vec<_>),
@@ -391,8 +552,11 @@ where
             challenger.observe_slice(&global_sum.0.x.0);
             challenger.observe_slice(&global_sum.0.y.0);
         }
+        observer.finished(ProverStage::Permutation, begin.elapsed());
 
         // Compute the quotient polynomial for all chips.
+        observer.started(ProverStage::Quotient);
+        let begin = Instant::now();
         let quotient_domains = trace_domains
             .iter()
             .zip_eq(log_degrees.iter())
@@ -464,8 +628,11 @@ where
         let (quotient_commit, quotient_data) = tracing::debug_span!("commit to quotient traces")
             .in_scope(|| pcs.commit(quotient_domains_and_chunks));
         challenger.observe(quotient_commit.clone());
+        observer.finished(ProverStage::Quotient, begin.elapsed());
 
         // Compute the quotient argument.
+        observer.started(ProverStage::Open);
+        let begin = Instant::now();
         let zeta: SC::Challenge = challenger.sample_ext_element();
 
         let preprocessed_opening_points =
@@ -606,6 +773,7 @@ where
                 },
             )
             .collect::<Vec<_>>();
+        observer.finished(ProverStage::Open, begin.elapsed());
 
         Ok(ShardProof::<SC> {
             commitment: ShardCommitment {
@@ -625,12 +793,13 @@ where
     /// Given a proving key `pk` and a matching execution record `record`, this function generates
     /// a STARK proof that the execution record is valid.
     #[allow(clippy::needless_for_each)]
-    fn prove(
+    fn prove_with_observer(
         &self,
         pk: &StarkProvingKey<SC>,
         mut records: Vec<A::Record>,
         challenger: &mut SC::Challenger,
         opts: <A::Record as MachineRecord>::Config,
+        observer: &dyn ProverObserver,
     ) -> Result<MachineProof<SC>, Self::Error>
     where
         A: for<'a> Air<DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>,
@@ -644,10 +813,13 @@ where
         let shard_proofs = tracing::info_span!("prove_shards").in_scope(|| {
             records
                 .into_par_iter()
-                .map(|record| {
-                    let named_traces = self.generate_traces(&record);
-                    let shard_data = self.commit(&record, named_traces);
-                    self.open(pk, shard_data, &mut challenger.clone())
+                .enumerate()
+                .map(|(shard, record)| {
+                    let observer = ShardObserver::new(observer, shard);
+                    let named_traces = self.generate_traces_with_observer(&record, observer);
+                    let shard_data = observer
+                        .in_stage(ProverStage::Commit, || self.commit(&record, named_traces));
+                    self.open_with_observer(pk, shard_data, &mut challenger.clone(), observer)
                 })
                 .collect::<Result<Vec<_>, _>>()
         })?;
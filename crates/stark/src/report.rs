@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{Process, System};
+
+/// The time spent proving a shard of a core proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardTiming {
+    /// The index of the shard.
+    pub shard: usize,
+    /// The time spent committing to and opening the traces of the shard, once they were generated.
+    pub prove_time: Duration,
+}
+
+/// A report of the proving of the shards of a core proof.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvingReport {
+    /// The time spent proving each shard, in shard order.
+    pub shard_timings: Vec<ShardTiming>,
+    /// The highest resident memory of the process in bytes, sampled when each shard was proven.
+    pub peak_memory: u64,
+    /// The time from the start of the execution to the last shard being proven.
+    pub total_time: Duration,
+}
+
+impl ProvingReport {
+    /// The total time spent proving the shards, summed over the shards proven concurrently.
+    #[must_use]
+    pub fn total_prove_time(&self) -> Duration {
+        self.shard_timings.iter().map(|timing| timing.prove_time).sum()
+    }
+
+    /// The fraction of `total_time` that `concurrency` workers spent proving shards.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn utilization(&self, concurrency: usize) -> f64 {
+        if self.total_time.is_zero() || concurrency == 0 {
+            return 0.0;
+        }
+        self.total_prove_time().as_secs_f64() / (self.total_time.as_secs_f64() * concurrency as f64)
+    }
+}
+
+/// Returns the resident memory of the process in bytes, or zero if it cannot be read.
+#[must_use]
+pub fn resident_memory() -> u64 {
+    let Ok(pid) = sysinfo::get_current_pid() else {
+        return 0;
+    };
+    let mut system = System::new();
+    system.refresh_process(pid);
+    system.process(pid).map_or(0, Process::memory)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utilization() {
+        let report = ProvingReport {
+            shard_timings: [3, 1, 2]
+                .map(|secs| ShardTiming { shard: 0, prove_time: Duration::from_secs(secs) })
+                .to_vec(),
+            peak_memory: 0,
+            total_time: Duration::from_secs(4),
+        };
+        assert_eq!(report.total_prove_time(), Duration::from_secs(6));
+        assert!((report.utilization(2) - 0.75).abs() < f64::EPSILON);
+        assert!(ProvingReport::default().utilization(2).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,511 @@
+use std::{
+    borrow::BorrowMut,
+    cmp::Reverse,
+    iter::zip,
+    mem,
+    time::{Duration, Instant},
+};
+
+use p3_field::Field;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_maybe_rayon::prelude::*;
+
+/// The time spent generating the trace of a chip.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChipTraceGenTiming {
+    /// The name of the chip.
+    pub chip: String,
+    /// The height of the generated trace.
+    pub height: usize,
+    /// The width of the generated trace.
+    pub width: usize,
+    /// The time spent generating the trace.
+    pub elapsed: Duration,
+}
+
+/// A report of the trace generation of a shard or of the preprocessed traces of a program.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraceGenReport {
+    /// The timings of the chips, in the order of the chips in the machine.
+    pub chips: Vec<ChipTraceGenTiming>,
+    /// The number of batches the chips were generated in. Chips in the same batch are generated
+    /// concurrently, and the batches one after the other.
+    pub batches: usize,
+    /// The wall-clock time of the whole generation.
+    pub elapsed: Duration,
+}
+
+impl TraceGenReport {
+    /// The sum of the generation times of the chips.
+    ///
+    /// This exceeds [`TraceGenReport::elapsed`] when chips are generated concurrently.
+    #[must_use]
+    pub fn total_chip_time(&self) -> Duration {
+        self.chips.iter().map(|chip| chip.elapsed).sum()
+    }
+
+    /// The chip whose trace took the longest to generate.
+    #[must_use]
+    pub fn slowest(&self) -> Option<&ChipTraceGenTiming> {
+        self.chips.iter().max_by_key(|chip| chip.elapsed)
+    }
+}
+
+/// A preallocated arena that a chip writes its trace into.
+///
+/// The allocation is kept across [`TraceBuffer::reset`] calls, so that a caller generating the
+/// traces of the chips one after the other can reuse a single buffer instead of holding an
+/// allocation per chip.
+#[derive(Debug, Clone, Default)]
+pub struct TraceBuffer<F> {
+    values: Vec<F>,
+    width: usize,
+}
+
+impl<F: Field> TraceBuffer<F> {
+    /// Creates an empty buffer with room for `capacity` field elements.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { values: Vec::with_capacity(capacity), width: 0 }
+    }
+
+    /// Zeroes the buffer and resizes it to a trace of `height` rows of `width` columns, returning
+    /// the values of the trace in row-major order.
+    pub fn reset(&mut self, height: usize, width: usize) -> &mut [F] {
+        self.values.clear();
+        self.values.resize(height * width, F::zero());
+        self.width = width;
+        &mut self.values
+    }
+
+    /// Replaces the contents of the buffer with an already generated trace.
+    pub fn set(&mut self, trace: RowMajorMatrix<F>) {
+        self.width = trace.width;
+        self.values = trace.values;
+    }
+
+    /// The number of field elements the buffer can hold without reallocating.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.values.capacity()
+    }
+
+    /// Takes the trace out of the buffer, leaving it empty.
+    pub fn take(&mut self) -> RowMajorMatrix<F> {
+        let width = std::mem::take(&mut self.width);
+        RowMajorMatrix::new(std::mem::take(&mut self.values), width)
+    }
+
+    /// Converts the buffer into the trace it holds.
+    #[must_use]
+    pub fn into_matrix(mut self) -> RowMajorMatrix<F> {
+        self.take()
+    }
+}
+
+/// How the rows of a trace after the ones holding events are filled.
+#[derive(Clone, Copy, Default)]
+pub enum RowPadding<'a, F> {
+    /// The padding rows are zero.
+    #[default]
+    Zero,
+    /// The padding rows repeat the last row holding events, or are zero if there are no events.
+    RepeatLast,
+    /// Each padding row is initialized by the function, given a zeroed row.
+    With(&'a (dyn Fn(&mut [F]) + Sync)),
+}
+
+/// Generates a row-major trace of `height` rows of `width` columns by writing each event into the
+/// columns `C` of its own chunk of the trace, in parallel.
+///
+/// The chunks are consecutive, so a chip packing several events per row passes the columns of a
+/// single event as `C`. The rows after the last chunk are filled according to `padding`.
+pub fn fill_trace<F, C, E>(
+    events: &[E],
+    height: usize,
+    width: usize,
+    padding: RowPadding<'_, F>,
+    fill: impl Fn(&E, &mut C) + Sync,
+) -> RowMajorMatrix<F>
+where
+    F: Field,
+    E: Sync,
+    [F]: BorrowMut<C>,
+{
+    let mut target = TraceBuffer::with_capacity(height * width);
+    fill_trace_into(&mut target, events, height, width, padding, fill);
+    target.into_matrix()
+}
+
+/// [`fill_trace`], writing the trace into a reused buffer.
+pub fn fill_trace_into<F, C, E>(
+    target: &mut TraceBuffer<F>,
+    events: &[E],
+    height: usize,
+    width: usize,
+    padding: RowPadding<'_, F>,
+    fill: impl Fn(&E, &mut C) + Sync,
+) where
+    F: Field,
+    E: Sync,
+    [F]: BorrowMut<C>,
+{
+    let chunk_width = mem::size_of::<C>() / mem::size_of::<F>();
+    let values = target.reset(height, width);
+
+    let populate_len = events.len() * chunk_width;
+    values[..populate_len]
+        .par_chunks_mut(chunk_width)
+        .zip_eq(events)
+        .for_each(|(chunk, event)| fill(event, chunk.borrow_mut()));
+
+    if width == 0 {
+        return;
+    }
+    let (rows, padding_rows) = values.split_at_mut(populate_len.div_ceil(width) * width);
+    match padding {
+        RowPadding::Zero => {}
+        RowPadding::RepeatLast => {
+            if let Some(last) = rows.rchunks_exact(width).next() {
+                padding_rows.par_chunks_mut(width).for_each(|row| row.copy_from_slice(last));
+            }
+        }
+        RowPadding::With(init) => padding_rows.par_chunks_mut(width).for_each(|row| init(row)),
+    }
+}
+
+/// The order in which the values of a trace are laid out in memory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TraceLayout {
+    /// The values of each row are contiguous, as in a [`RowMajorMatrix`].
+    #[default]
+    RowMajor,
+    /// The values of each column are contiguous, as in a [`ColMajorMatrix`].
+    ColMajor,
+}
+
+/// A dense matrix whose columns are stored one after the other.
+///
+/// Backends that commit to the columns of a trace, such as GPU provers, take this layout without
+/// transposing a [`RowMajorMatrix`] first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColMajorMatrix<F> {
+    /// The values of the matrix, column by column.
+    pub values: Vec<F>,
+    /// The number of rows of the matrix.
+    pub height: usize,
+}
+
+impl<F: Field> ColMajorMatrix<F> {
+    /// Creates a matrix from its values, column by column.
+    #[must_use]
+    pub fn new(values: Vec<F>, height: usize) -> Self {
+        assert!(
+            (height == 0 && values.is_empty()) || (height != 0 && values.len() % height == 0),
+            "{} values do not fill columns of height {height}",
+            values.len()
+        );
+        Self { values, height }
+    }
+
+    /// Creates a zero matrix of `height` rows and `width` columns.
+    #[must_use]
+    pub fn zeros(height: usize, width: usize) -> Self {
+        Self { values: vec![F::zero(); height * width], height }
+    }
+
+    /// The number of columns of the matrix.
+    #[must_use]
+    pub fn width(&self) -> usize {
+        if self.height == 0 {
+            0
+        } else {
+            self.values.len() / self.height
+        }
+    }
+
+    /// The number of rows of the matrix.
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The values of a column.
+    #[must_use]
+    pub fn column(&self, column: usize) -> &[F] {
+        &self.values[column * self.height..(column + 1) * self.height]
+    }
+
+    /// Transposes a row-major matrix into a column-major one.
+    #[must_use]
+    pub fn from_row_major(matrix: &RowMajorMatrix<F>) -> Self {
+        let height = if matrix.width == 0 { 0 } else { matrix.values.len() / matrix.width };
+        let mut values = vec![F::zero(); matrix.values.len()];
+        if height != 0 {
+            values.par_chunks_mut(height).enumerate().for_each(|(c, column)| {
+                for (r, value) in column.iter_mut().enumerate() {
+                    *value = matrix.values[r * matrix.width + c];
+                }
+            });
+        }
+        Self { values, height }
+    }
+
+    /// Transposes the matrix into a row-major one.
+    #[must_use]
+    pub fn to_row_major(&self) -> RowMajorMatrix<F> {
+        let width = self.width();
+        let mut values = vec![F::zero(); self.values.len()];
+        if width != 0 {
+            values.par_chunks_mut(width).enumerate().for_each(|(r, row)| {
+                for (c, value) in row.iter_mut().enumerate() {
+                    *value = self.values[c * self.height + r];
+                }
+            });
+        }
+        RowMajorMatrix::new(values, width)
+    }
+
+    /// Overwrites every row of the matrix with the values written by `fill`, which is given the
+    /// index of the row and a zeroed row.
+    ///
+    /// The rows are generated in parallel, in blocks of consecutive rows that are scattered into
+    /// the columns, so the matrix is never held in row-major order.
+    pub fn par_fill_rows(&mut self, fill: impl Fn(usize, &mut [F]) + Sync) {
+        const BLOCK_ROWS: usize = 1 << 10;
+
+        let (height, width) = (self.height, self.width());
+        if height == 0 || width == 0 {
+            return;
+        }
+        // Split every column at the same row boundaries, and gather the pieces of each block.
+        let mut blocks = (0..height.div_ceil(BLOCK_ROWS))
+            .map(|_| Vec::with_capacity(width))
+            .collect::<Vec<Vec<&mut [F]>>>();
+        for column in self.values.chunks_mut(height) {
+            for (block, piece) in zip(&mut blocks, column.chunks_mut(BLOCK_ROWS)) {
+                block.push(piece);
+            }
+        }
+        blocks.into_par_iter().enumerate().for_each(|(b, mut columns)| {
+            let mut row = vec![F::zero(); width];
+            for r in 0..columns[0].len() {
+                row.fill(F::zero());
+                fill(b * BLOCK_ROWS + r, &mut row);
+                for (column, &value) in zip(&mut columns, &row) {
+                    column[r] = value;
+                }
+            }
+        });
+    }
+}
+
+/// A trace in the layout of a prover backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceMatrix<F> {
+    RowMajor(RowMajorMatrix<F>),
+    ColMajor(ColMajorMatrix<F>),
+}
+
+impl<F: Field> TraceMatrix<F> {
+    /// The layout of the trace.
+    #[must_use]
+    pub fn layout(&self) -> TraceLayout {
+        match self {
+            Self::RowMajor(_) => TraceLayout::RowMajor,
+            Self::ColMajor(_) => TraceLayout::ColMajor,
+        }
+    }
+
+    /// Converts the trace into a row-major matrix, transposing it if needed.
+    #[must_use]
+    pub fn into_row_major(self) -> RowMajorMatrix<F> {
+        match self {
+            Self::RowMajor(trace) => trace,
+            Self::ColMajor(trace) => trace.to_row_major(),
+        }
+    }
+}
+
+/// Generates an output for each item, concurrently across items, returning the outputs in the
+/// order of the items along with the time each took and the number of batches.
+///
+/// Items are scheduled largest first by their estimated trace bytes, so that the biggest traces
+/// don't end up serialized behind the small ones. If `max_bytes` is set, the items are split into
+/// batches whose estimated bytes add up to at most `max_bytes` (an item over the budget gets a
+/// batch of its own), and the batches are generated one after the other. Items with an estimate of
+/// zero are never bounded.
+pub(crate) fn generate_concurrently<I, T>(
+    items: &[I],
+    max_bytes: Option<usize>,
+    estimate_bytes: impl Fn(&I) -> usize,
+    generate: impl Fn(&I) -> T + Sync,
+) -> (Vec<(T, Duration)>, usize)
+where
+    I: Sync,
+    T: Send,
+{
+    let estimates = items.iter().map(estimate_bytes).collect::<Vec<_>>();
+    let mut order = (0..items.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| Reverse(estimates[i]));
+
+    let batches = split_into_batches(&order, &estimates, max_bytes);
+    let mut outputs = Vec::with_capacity(items.len());
+    for batch in &batches {
+        outputs.extend(
+            batch
+                .par_iter()
+                .with_max_len(1)
+                .map(|&i| {
+                    let begin = Instant::now();
+                    let output = generate(&items[i]);
+                    (i, (output, begin.elapsed()))
+                })
+                .collect::<Vec<_>>(),
+        );
+    }
+    outputs.sort_by_key(|(i, _)| *i);
+    (outputs.into_iter().map(|(_, output)| output).collect(), batches.len())
+}
+
+/// Splits the items, given in scheduling order, into consecutive batches whose estimated bytes add
+/// up to at most `max_bytes`.
+fn split_into_batches(
+    order: &[usize],
+    estimates: &[usize],
+    max_bytes: Option<usize>,
+) -> Vec<Vec<usize>> {
+    let Some(max_bytes) = max_bytes else {
+        return vec![order.to_vec()];
+    };
+    let mut batches: Vec<Vec<usize>> = Vec::new();
+    let mut batch_bytes = 0;
+    for &i in order {
+        match batches.last_mut() {
+            Some(batch) if batch_bytes + estimates[i] <= max_bytes => {
+                batch.push(i);
+                batch_bytes += estimates[i];
+            }
+            _ => {
+                batches.push(vec![i]);
+                batch_bytes = estimates[i];
+            }
+        }
+    }
+    batches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_into_batches() {
+        let estimates = [8, 5, 3, 3, 0];
+        let order = [0, 1, 2, 3, 4];
+        assert_eq!(split_into_batches(&order, &estimates, None), vec![vec![0, 1, 2, 3, 4]]);
+        assert_eq!(
+            split_into_batches(&order, &estimates, Some(8)),
+            vec![vec![0], vec![1, 2], vec![3, 4]]
+        );
+        // An item over the budget is generated on its own.
+        assert_eq!(
+            split_into_batches(&order, &estimates, Some(4)),
+            vec![vec![0], vec![1], vec![2], vec![3, 4]]
+        );
+    }
+
+    #[test]
+    fn test_generate_concurrently() {
+        let items = [1usize, 4, 2, 3];
+        let (outputs, batches) =
+            generate_concurrently(&items, Some(5), |&item| item, |&item| item * 10);
+        assert_eq!(outputs.iter().map(|(output, _)| *output).collect::<Vec<_>>(), [10, 40, 20, 30]);
+        assert_eq!(batches, 3);
+
+        let (outputs, batches) = generate_concurrently(&items, None, |&item| item, |&item| item);
+        assert_eq!(outputs.len(), 4);
+        assert_eq!(batches, 1);
+    }
+
+    #[test]
+    fn test_col_major_matrix() {
+        use p3_baby_bear::BabyBear;
+        use p3_field::AbstractField;
+
+        let height = 3000;
+        let width = 7;
+        let row_major = RowMajorMatrix::new(
+            (0..height * width).map(BabyBear::from_canonical_usize).collect(),
+            width,
+        );
+        let col_major = ColMajorMatrix::from_row_major(&row_major);
+        assert_eq!((col_major.width(), col_major.height()), (width, height));
+        assert_eq!(col_major.column(2)[1], BabyBear::from_canonical_usize(width + 2));
+        assert_eq!(col_major.to_row_major(), row_major);
+
+        // Filling the rows over several blocks gives the transposed row-major matrix.
+        let mut filled = ColMajorMatrix::zeros(height, width);
+        filled.par_fill_rows(|r, row| {
+            for (c, value) in row.iter_mut().enumerate() {
+                *value = BabyBear::from_canonical_usize(r * width + c);
+            }
+        });
+        assert_eq!(filled, col_major);
+    }
+
+    #[test]
+    fn test_fill_trace() {
+        use p3_baby_bear::BabyBear;
+        use p3_field::AbstractField;
+        use sp1_derive::AlignedBorrow;
+
+        #[derive(AlignedBorrow)]
+        #[repr(C)]
+        struct PairCols<T> {
+            value: T,
+            square: T,
+        }
+
+        fn trace(events: &[u32], padding: RowPadding<'_, BabyBear>) -> Vec<BabyBear> {
+            let f = BabyBear::from_canonical_u32;
+            fill_trace(events, 4, 4, padding, |&event, cols: &mut PairCols<BabyBear>| {
+                *cols = PairCols { value: f(event), square: f(event * event) };
+            })
+            .values
+        }
+        let f = BabyBear::from_canonical_u32;
+
+        // Two events per row, the last row holding events being half full.
+        assert_eq!(
+            trace(&[1, 2, 3], RowPadding::Zero),
+            [1, 1, 2, 4, 3, 9, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0].map(f)
+        );
+        assert_eq!(
+            trace(&[1, 2, 3], RowPadding::RepeatLast),
+            [1, 1, 2, 4, 3, 9, 0, 0, 3, 9, 0, 0, 3, 9, 0, 0].map(f)
+        );
+        assert_eq!(trace(&[], RowPadding::RepeatLast), [0; 16].map(f));
+        assert_eq!(
+            trace(&[1, 2, 3], RowPadding::With(&|row| row[3] = f(7))),
+            [1, 1, 2, 4, 3, 9, 0, 0, 0, 0, 0, 7, 0, 0, 0, 7].map(f)
+        );
+    }
+
+    #[test]
+    fn test_trace_buffer_reuses_allocation() {
+        use p3_baby_bear::BabyBear;
+        use p3_field::AbstractField;
+
+        let mut buffer = TraceBuffer::<BabyBear>::with_capacity(16);
+        buffer.reset(4, 4).fill(BabyBear::one());
+        let capacity = buffer.capacity();
+
+        let values = buffer.reset(2, 3);
+        assert_eq!(values, [BabyBear::zero(); 6]);
+        assert_eq!(buffer.capacity(), capacity);
+
+        let trace = buffer.into_matrix();
+        assert_eq!((trace.width, trace.values.len()), (3, 6));
+    }
+}
@@ -0,0 +1,26 @@
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use sp1_zkvm::syscalls::syscall_bls12381_fp12_mulmod;
+
+/// Multiplies host-computed Fp12 elements, given as their 144 little-endian words, and checks the
+/// products against the host.
+pub fn main() {
+    let cases = sp1_zkvm::io::read::<Vec<(Vec<u32>, Vec<u32>, Vec<u32>)>>();
+    for (x, y, expected) in cases {
+        let mut x: [u32; 144] = x.try_into().unwrap();
+        let y: [u32; 144] = y.try_into().unwrap();
+
+        // Squaring with the same pointer for both operands must match squaring a copy.
+        let mut square = x;
+        let copy = x;
+        syscall_bls12381_fp12_mulmod(square.as_mut_ptr(), copy.as_ptr());
+        let mut square_in_place = x;
+        let ptr = square_in_place.as_mut_ptr();
+        syscall_bls12381_fp12_mulmod(ptr, ptr);
+        assert_eq!(square, square_in_place);
+
+        syscall_bls12381_fp12_mulmod(x.as_mut_ptr(), y.as_ptr());
+        assert_eq!(x.to_vec(), expected);
+    }
+}
@@ -0,0 +1,16 @@
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use sp1_zkvm::lib::bls12381::syscall_bls12381_pairing_check;
+
+/// Runs pairing checks on host-generated points, and checks the results against the host.
+pub fn main() {
+    let cases = sp1_zkvm::io::read::<Vec<(Vec<(Vec<u32>, Vec<u32>)>, bool)>>();
+    for (pairs, expected) in cases {
+        let pairs: Vec<([u32; 24], [u32; 48])> = pairs
+            .into_iter()
+            .map(|(p, q)| (p.try_into().unwrap(), q.try_into().unwrap()))
+            .collect();
+        assert_eq!(syscall_bls12381_pairing_check(&pairs), expected);
+    }
+}
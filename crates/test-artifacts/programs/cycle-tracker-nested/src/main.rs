@@ -0,0 +1,24 @@
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use std::hint::black_box;
+
+/// The recursive calls nest invocations of the `fib` span in each other.
+#[sp1_derive::cycle_tracker]
+pub fn fib(n: u32) -> u32 {
+    if n < 2 {
+        n
+    } else {
+        fib(n - 1) + fib(n - 2)
+    }
+}
+
+pub fn main() {
+    println!("cycle-tracker-report-start: outer");
+    for n in 2..5 {
+        println!("cycle-tracker-start: inner");
+        black_box(fib(black_box(n)));
+        println!("cycle-tracker-end: inner");
+    }
+    println!("cycle-tracker-report-end: outer");
+}
@@ -0,0 +1,21 @@
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+/// The file descriptor of the hook the host registers to look up the values of keys.
+const FD_LOOKUP: u32 = 100;
+
+pub fn main() {
+    let keys = sp1_zkvm::io::read::<Vec<u32>>();
+
+    // Request the value of each key, computed by the program, from the host.
+    let mut values = Vec::new();
+    for key in keys {
+        let key = key.wrapping_mul(31).wrapping_add(7);
+        sp1_zkvm::io::write(FD_LOOKUP, &key.to_le_bytes());
+        let value = sp1_zkvm::io::read_vec();
+        let label = String::from_utf8(sp1_zkvm::io::read_vec()).unwrap();
+        values.push((key, value, label));
+    }
+
+    sp1_zkvm::io::commit(&values);
+}
@@ -0,0 +1,15 @@
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use std::hint::black_box;
+
+pub fn main() {
+    let n = sp1_zkvm::io::read::<u32>();
+    sp1_zkvm::io::commit(&n);
+
+    // Loop forever, so the execution only ends when it reaches its cycle limit.
+    let mut i = 0u32;
+    loop {
+        i = black_box(i.wrapping_add(1));
+    }
+}
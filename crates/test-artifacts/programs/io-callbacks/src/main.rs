@@ -0,0 +1,16 @@
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use std::hint::black_box;
+
+pub fn main() {
+    let mut x: u32 = 1;
+    for i in 0..5 {
+        // Do some work between the progress reports.
+        for _ in 0..1000 {
+            x = black_box(x.wrapping_mul(31).wrapping_add(i));
+        }
+        println!("progress {i}");
+    }
+    eprintln!("done");
+}
@@ -0,0 +1,15 @@
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use std::hint::black_box;
+
+/// The number of bytes allocated on the heap.
+const ALLOC_BYTES: usize = 1 << 20;
+
+pub fn main() {
+    let mut buf = black_box(vec![0u8; ALLOC_BYTES]);
+    for (i, byte) in buf.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    black_box(&buf);
+}
@@ -0,0 +1,10 @@
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+pub fn main() {
+    let n = sp1_zkvm::io::read_named::<u32>("header");
+    let bytes = sp1_zkvm::io::read::<Vec<u8>>();
+    let (a, name) = sp1_zkvm::io::read_named::<(u64, String)>("body");
+
+    sp1_zkvm::io::commit(&(n, bytes, a, name));
+}
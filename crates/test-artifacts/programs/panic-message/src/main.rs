@@ -0,0 +1,13 @@
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+pub fn main() {
+    let len = sp1_zkvm::io::read::<u32>() as usize;
+    let replace_hook = sp1_zkvm::io::read::<bool>();
+
+    // Without the hook of the entrypoint, the panic is not reported to the host.
+    if replace_hook {
+        std::panic::set_hook(Box::new(|_| {}));
+    }
+    panic!("message of {len} bytes: {}", "a".repeat(len));
+}
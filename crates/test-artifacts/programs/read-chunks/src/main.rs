@@ -0,0 +1,24 @@
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use sha2::{Digest, Sha256};
+
+/// The size of the chunks the input is read in.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+pub fn main() {
+    let mut hasher = Sha256::new();
+    let mut num_chunks = 0u32;
+    for chunk in sp1_zkvm::io::read_chunks(CHUNK_SIZE) {
+        assert!(chunk.len() <= CHUNK_SIZE);
+        hasher.update(&chunk);
+        num_chunks += 1;
+    }
+
+    // The inputs after the streamed one are read as usual.
+    let trailer = sp1_zkvm::io::read::<u32>();
+
+    sp1_zkvm::io::commit(&num_chunks);
+    sp1_zkvm::io::commit(&trailer);
+    sp1_zkvm::io::commit_slice(&hasher.finalize());
+}
@@ -0,0 +1,15 @@
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use sp1_zkvm::syscalls::syscall_sha512_compress;
+
+pub fn main() {
+    let mut w = [1u64; 80];
+    let mut state = [1u64; 8];
+
+    for _ in 0..4 {
+        syscall_sha512_compress(&mut w, &mut state);
+    }
+
+    println!("{:?}", state);
+}
@@ -0,0 +1,12 @@
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use sp1_zkvm::syscalls::syscall_sha512_extend;
+
+pub fn main() {
+    let mut w = [1u64; 80];
+    syscall_sha512_extend(&mut w);
+    syscall_sha512_extend(&mut w);
+    syscall_sha512_extend(&mut w);
+    println!("{:?}", w);
+}
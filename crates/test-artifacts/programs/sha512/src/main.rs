@@ -0,0 +1,81 @@
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use hex_literal::hex;
+use sp1_zkvm::lib::sha512::compress;
+
+const IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+/// Hashes `message` with SHA-512, padding it with a one bit, zeros and its 128-bit big-endian bit
+/// length.
+fn sha512(message: &[u8]) -> [u8; 64] {
+    let mut padded = message.to_vec();
+    padded.push(0x80);
+    while padded.len() % 128 != 112 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&((message.len() as u128) * 8).to_be_bytes());
+
+    let blocks =
+        padded.chunks_exact(128).map(|block| block.try_into().unwrap()).collect::<Vec<[u8; 128]>>();
+    let mut state = IV;
+    compress(&mut state, &blocks);
+
+    let mut digest = [0u8; 64];
+    for (chunk, word) in digest.chunks_exact_mut(8).zip(state) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+pub fn main() {
+    let vectors: [(&[u8], [u8; 64]); 8] = [
+        (
+            b"",
+            hex!("cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e"),
+        ),
+        (
+            b"abc",
+            hex!("ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"),
+        ),
+        (
+            b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu",
+            hex!("8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909"),
+        ),
+        // The longest message whose padding fits in a single block.
+        (
+            &[b'a'; 111],
+            hex!("fa9121c7b32b9e01733d034cfc78cbf67f926c7ed83e82200ef86818196921760b4beff48404df811b953828274461673c68d04e297b0eb7b2b4d60fc6b566a2"),
+        ),
+        // The shortest message whose length encoding spills into a second block.
+        (
+            &[b'a'; 112],
+            hex!("c01d080efd492776a1c43bd23dd99d0a2e626d481e16782e75d54c2503b5dc32bd05f0f1ba33e568b88fd2d970929b719ecbb152f58f130a407c8830604b70ca"),
+        ),
+        (
+            &[b'a'; 127],
+            hex!("828613968b501dc00a97e08c73b118aa8876c26b8aac93df128502ab360f91bab50a51e088769a5c1eff4782ace147dce3642554199876374291f5d921629502"),
+        ),
+        (
+            &[b'a'; 128],
+            hex!("b73d1929aa615934e61a871596b3f3b33359f42b8175602e89f7e06e5f658a243667807ed300314b95cacdd579f3e33abdfbe351909519a846d465c59582f321"),
+        ),
+        (
+            &[b'a'; 1000],
+            hex!("67ba5535a46e3f86dbfbed8cbbaf0125c76ed549ff8b0b9e03e0c88cf90fa634fa7b12b47d77b694de488ace8d9a65967dc96df599727d3292a8d9d447709c97"),
+        ),
+    ];
+
+    for (message, expected) in vectors {
+        assert_eq!(sha512(message), expected);
+    }
+}
@@ -14,6 +14,16 @@ pub const ED25519_ELF: &[u8] = include_elf!("ed25519-program");
 
 pub const CYCLE_TRACKER_ELF: &[u8] = include_elf!("cycle-tracker-test");
 
+pub const CYCLE_TRACKER_NESTED_ELF: &[u8] = include_elf!("cycle-tracker-nested-test");
+
+pub const IO_CALLBACKS_ELF: &[u8] = include_elf!("io-callbacks-test");
+
+pub const MEMORY_STATS_ELF: &[u8] = include_elf!("memory-stats-test");
+
+pub const INFINITE_LOOP_ELF: &[u8] = include_elf!("infinite-loop-test");
+
+pub const READ_CHUNKS_ELF: &[u8] = include_elf!("read-chunks-test");
+
 pub const ED_ADD_ELF: &[u8] = include_elf!("ed-add-test");
 
 pub const ED_DECOMPRESS_ELF: &[u8] = include_elf!("ed-decompress-test");
@@ -40,6 +50,12 @@ pub const SHA_EXTEND_ELF: &[u8] = include_elf!("sha-extend-test");
 
 pub const SHA2_ELF: &[u8] = include_elf!("sha2-test");
 
+pub const SHA512_COMPRESS_ELF: &[u8] = include_elf!("sha512-compress-test");
+
+pub const SHA512_EXTEND_ELF: &[u8] = include_elf!("sha512-extend-test");
+
+pub const SHA512_ELF: &[u8] = include_elf!("sha512-test");
+
 pub const SSZ_WITHDRAWALS_ELF: &[u8] = include_elf!("ssz-withdrawals-test");
 
 pub const BN254_ADD_ELF: &[u8] = include_elf!("bn254-add-test");
@@ -64,12 +80,24 @@ pub const VERIFY_PROOF_ELF: &[u8] = include_elf!("verify-proof");
 
 pub const PANIC_ELF: &[u8] = include_elf!("panic-test");
 
+pub const PANIC_MESSAGE_ELF: &[u8] = include_elf!("panic-message-test");
+
+pub const TYPED_HINTS_ELF: &[u8] = include_elf!("typed-hints-test");
+
+pub const NAMED_INPUTS_ELF: &[u8] = include_elf!("named-inputs-test");
+
+pub const HOOK_REQUEST_ELF: &[u8] = include_elf!("hook-request-test");
+
 pub const BLS12381_FP_ELF: &[u8] = include_elf!("bls12381-fp-test");
 
 pub const BLS12381_FP2_MUL_ELF: &[u8] = include_elf!("bls12381-fp2-mul-test");
 
 pub const BLS12381_FP2_ADDSUB_ELF: &[u8] = include_elf!("bls12381-fp2-addsub-test");
 
+pub const BLS12381_FP12_MUL_ELF: &[u8] = include_elf!("bls12381-fp12-mul-test");
+
+pub const BLS12381_PAIRING_ELF: &[u8] = include_elf!("bls12381-pairing-test");
+
 pub const BN254_FP_ELF: &[u8] = include_elf!("bn254-fp-test");
 
 pub const BN254_FP2_ADDSUB_ELF: &[u8] = include_elf!("bn254-fp2-addsub-test");
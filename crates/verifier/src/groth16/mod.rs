@@ -8,7 +8,7 @@ pub(crate) use verify::*;
 
 use error::Groth16Error;
 
-use crate::{decode_sp1_vkey_hash, error::Error, hash_public_inputs};
+use crate::{decode_sp1_vkey_hash, error::Error, hash_public_inputs, mock::is_mock_proof};
 
 use alloc::vec::Vec;
 use sha2::{Digest, Sha256};
@@ -47,6 +47,12 @@ impl Groth16Verifier {
         sp1_vkey_hash: &str,
         groth16_vk: &[u8],
     ) -> Result<(), Groth16Error> {
+        // Mock proofs are prefixed with a selector of their own, which must never be accepted in
+        // place of a verifying key hash.
+        if is_mock_proof(proof) {
+            return Err(Groth16Error::MockProof);
+        }
+
         // Hash the vk and get the first 4 bytes.
         let groth16_vk_hash: [u8; 4] = Sha256::digest(groth16_vk)[..4]
             .try_into()
@@ -33,5 +33,8 @@ pub use plonk::error::PlonkError;
 pub use plonk::PlonkVerifier;
 mod plonk;
 
+pub use mock::{MockError, MockVerifier, MOCK_PROOF_LEN, MOCK_PROOF_SELECTOR};
+mod mock;
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,79 @@
+use alloc::vec::Vec;
+use thiserror::Error;
+
+use crate::{decode_sp1_vkey_hash, hash_public_inputs};
+
+/// The 4-byte selector that prefixes mock proofs.
+///
+/// Real proofs are prefixed with the first 4 bytes of the hash of the Groth16 or Plonk verifying
+/// key, which is also the selector the Solidity `SP1VerifierGateway` routes proofs by. Registering
+/// a mock verifier in the gateway under this selector lets mock proofs flow through the same path
+/// as real ones, and the real verifiers reject proofs with this selector explicitly.
+pub const MOCK_PROOF_SELECTOR: [u8; 4] = *b"MOCK";
+
+/// The length of a mock proof: the selector, the SP1 vkey hash and the public values digest.
+pub const MOCK_PROOF_LEN: usize = 4 + 32 + 32;
+
+#[derive(Debug, Error)]
+pub enum MockError {
+    #[error("Not a mock proof")]
+    NotAMockProof,
+    #[error("Mock proof vkey hash mismatch")]
+    VkeyHashMismatch,
+    #[error("Mock proof public values mismatch")]
+    PublicValuesMismatch,
+    #[error("General error")]
+    GeneralError(#[from] crate::error::Error),
+}
+
+/// A verifier for the mock proofs generated by the mock prover of the SP1 SDK.
+///
+/// Mock proofs are not zero-knowledge proofs: they only commit to the SP1 vkey hash and the public
+/// values, so that pipelines feeding proofs to verifiers can be tested without running a circuit.
+#[derive(Debug)]
+pub struct MockVerifier;
+
+impl MockVerifier {
+    /// The bytes of the mock proof of the given SP1 public inputs and vkey hash.
+    ///
+    /// The bytes consist of [`MOCK_PROOF_SELECTOR`], the SP1 vkey hash and the hash of the public
+    /// inputs, in the same format as the Plonk and Groth16 verifiers.
+    pub fn proof_bytes(sp1_public_inputs: &[u8], sp1_vkey_hash: &[u8; 32]) -> Vec<u8> {
+        [&MOCK_PROOF_SELECTOR[..], sp1_vkey_hash, &hash_public_inputs(sp1_public_inputs)].concat()
+    }
+
+    /// Verifies an SP1 mock proof, as generated by the mock prover of the SP1 SDK.
+    ///
+    /// # Arguments
+    ///
+    /// * `proof` - The proof bytes.
+    /// * `public_inputs` - The SP1 public inputs.
+    /// * `sp1_vkey_hash` - The SP1 vkey hash, as returned by `vk.bytes32()`.
+    ///
+    /// # Returns
+    ///
+    /// A success [`Result`] if verification succeeds, or a [`MockError`] if verification fails.
+    pub fn verify(
+        proof: &[u8],
+        sp1_public_inputs: &[u8],
+        sp1_vkey_hash: &str,
+    ) -> Result<(), MockError> {
+        if proof.len() != MOCK_PROOF_LEN || !is_mock_proof(proof) {
+            return Err(MockError::NotAMockProof);
+        }
+
+        let sp1_vkey_hash = decode_sp1_vkey_hash(sp1_vkey_hash)?;
+        if proof[4..36] != sp1_vkey_hash {
+            return Err(MockError::VkeyHashMismatch);
+        }
+        if proof[36..] != hash_public_inputs(sp1_public_inputs) {
+            return Err(MockError::PublicValuesMismatch);
+        }
+        Ok(())
+    }
+}
+
+/// Whether the proof bytes are prefixed with [`MOCK_PROOF_SELECTOR`].
+pub(crate) fn is_mock_proof(proof: &[u8]) -> bool {
+    proof.starts_with(&MOCK_PROOF_SELECTOR)
+}
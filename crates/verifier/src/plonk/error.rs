@@ -28,6 +28,8 @@ pub enum PlonkError {
     TranscriptError,
     #[error("Plonk vkey hash mismatch")]
     PlonkVkeyHashMismatch,
+    #[error("Mock proofs are rejected by the Plonk verifier")]
+    MockProof,
     #[error("General error")]
     GeneralError(#[from] crate::error::Error),
 }
@@ -22,7 +22,7 @@ use bn::Fr;
 use error::PlonkError;
 use sha2::{Digest, Sha256};
 
-use crate::{decode_sp1_vkey_hash, error::Error, hash_public_inputs};
+use crate::{decode_sp1_vkey_hash, error::Error, hash_public_inputs, mock::is_mock_proof};
 /// A verifier for Plonk zero-knowledge proofs.
 #[derive(Debug)]
 pub struct PlonkVerifier;
@@ -54,6 +54,12 @@ impl PlonkVerifier {
         sp1_vkey_hash: &str,
         plonk_vk: &[u8],
     ) -> Result<(), PlonkError> {
+        // Mock proofs are prefixed with a selector of their own, which must never be accepted in
+        // place of a verifying key hash.
+        if is_mock_proof(proof) {
+            return Err(PlonkError::MockProof);
+        }
+
         // Hash the vk and get the first 4 bytes.
         let plonk_vk_hash: [u8; 4] = Sha256::digest(plonk_vk)[..4]
             .try_into()
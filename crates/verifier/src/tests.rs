@@ -96,3 +96,63 @@ fn test_vkeys() {
     let s3_vkey_bytes = std::fs::read(s3_vkey_path).unwrap();
     assert_eq!(s3_vkey_bytes, *crate::PLONK_VK_BYTES);
 }
+
+#[test]
+fn test_verify_mock_proof() {
+    const VKEY_HASH: &str = "0x00aabbccddeeff00112233445566778899aabbccddeeff001122334455667788";
+
+    let vkey_hash = crate::decode_sp1_vkey_hash(VKEY_HASH).unwrap();
+    let proof = crate::MockVerifier::proof_bytes(&[1, 2, 3], &vkey_hash);
+    assert_eq!(proof.len(), crate::MOCK_PROOF_LEN);
+    assert_eq!(proof[..4], crate::MOCK_PROOF_SELECTOR);
+
+    crate::MockVerifier::verify(&proof, &[1, 2, 3], VKEY_HASH).expect("Mock proof is invalid");
+    assert!(matches!(
+        crate::MockVerifier::verify(&proof, &[1, 2, 4], VKEY_HASH),
+        Err(crate::MockError::PublicValuesMismatch)
+    ));
+    let other_vkey_hash = "0x00ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff";
+    assert!(matches!(
+        crate::MockVerifier::verify(&proof, &[1, 2, 3], other_vkey_hash),
+        Err(crate::MockError::VkeyHashMismatch)
+    ));
+    assert!(matches!(
+        crate::MockVerifier::verify(&proof[..proof.len() - 1], &[1, 2, 3], VKEY_HASH),
+        Err(crate::MockError::NotAMockProof)
+    ));
+}
+
+#[test]
+fn test_verify_mock_sdk_proofs() {
+    // Set up the pk and vk.
+    let client = ProverClient::builder().mock().build();
+    let (pk, vk) = client.setup(FIBONACCI_ELF);
+    let vkey_hash = vk.bytes32();
+
+    // Generate the mock Groth16 proof, which only the mock verifier accepts.
+    let groth16_proof = client.prove(&pk, &SP1Stdin::new()).groth16().run().unwrap();
+    let proof = groth16_proof.bytes();
+    let public_inputs = groth16_proof.public_values.to_vec();
+    crate::MockVerifier::verify(&proof, &public_inputs, &vkey_hash)
+        .expect("Mock Groth16 proof is invalid");
+    assert!(matches!(
+        crate::Groth16Verifier::verify(
+            &proof,
+            &public_inputs,
+            &vkey_hash,
+            &crate::GROTH16_VK_BYTES
+        ),
+        Err(crate::Groth16Error::MockProof)
+    ));
+
+    // Generate the mock Plonk proof, which only the mock verifier accepts.
+    let plonk_proof = client.prove(&pk, &SP1Stdin::new()).plonk().run().unwrap();
+    let proof = plonk_proof.bytes();
+    let public_inputs = plonk_proof.public_values.to_vec();
+    crate::MockVerifier::verify(&proof, &public_inputs, &vkey_hash)
+        .expect("Mock Plonk proof is invalid");
+    assert!(matches!(
+        crate::PlonkVerifier::verify(&proof, &public_inputs, &vkey_hash, &crate::PLONK_VK_BYTES),
+        Err(crate::PlonkError::MockProof)
+    ));
+}
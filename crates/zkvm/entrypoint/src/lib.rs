@@ -65,18 +65,29 @@ pub struct ReadVecResult {
 /// If the input stream is exhausted, the failed flag will be returned as true. In this case, the other outputs from the function are likely incorrect, which is fine as `sp1-lib` always panics in the case that the input stream is exhausted.
 #[no_mangle]
 pub extern "C" fn read_vec_raw() -> ReadVecResult {
+    read_vec_chunk_raw(usize::MAX)
+}
+
+/// Read at most `max_len` bytes of the next buffer of the input stream.
+///
+/// The bytes are read like with [`read_vec_raw`], which reads the whole buffer. The rest of the
+/// buffer is left in the input stream, so that the next call reads the following bytes. Reading
+/// the last bytes of the buffer removes it from the input stream.
+#[no_mangle]
+pub extern "C" fn read_vec_chunk_raw(max_len: usize) -> ReadVecResult {
     #[cfg(not(target_os = "zkvm"))]
-    unreachable!("read_vec_raw should only be called on the zkvm target.");
+    unreachable!("read_vec_chunk_raw should only be called on the zkvm target.");
 
     #[cfg(target_os = "zkvm")]
     {
-        // Get the length of the input buffer.
+        // Get the length of the rest of the input buffer.
         let len = syscall_hint_len();
 
         // If the length is u32::MAX, then the input stream is exhausted.
         if len == usize::MAX {
             return ReadVecResult { ptr: std::ptr::null_mut(), len: 0, capacity: 0 };
         }
+        let len = len.min(max_len);
 
         // Round up to multiple of 4 for whole-word alignment.
         let capacity = (len + 3) / 4 * 4;
@@ -155,6 +166,18 @@ mod zkvm {
             crate::allocators::init();
 
             PUBLIC_VALUES_HASHER = Some(Sha256::new());
+
+            // Report panics to the host, rather than only printing them to stderr.
+            std::panic::set_hook(Box::new(|info| {
+                let payload = info.payload();
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .copied()
+                    .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                    .unwrap_or("Box<dyn Any>");
+                crate::syscalls::report_panic(message, info.location());
+            }));
+
             #[cfg(feature = "verify")]
             {
                 DEFERRED_PROOFS_DIGEST = Some([BabyBear::zero(); 8]);
@@ -36,7 +36,7 @@ pub extern "C" fn syscall_write(fd: u32, write_buf: *const u8, nbytes: usize) {
     }
 }
 
-/// Returns the length of the next element in the hint stream.
+/// Returns the number of bytes left to read of the next element in the hint stream.
 #[allow(unused_variables)]
 #[no_mangle]
 pub extern "C" fn syscall_hint_len() -> usize {
@@ -55,7 +55,9 @@ pub extern "C" fn syscall_hint_len() -> usize {
     unreachable!()
 }
 
-/// Reads the next element in the hint stream into the given buffer.
+/// Reads the first `len` bytes of the next element in the hint stream into the given buffer.
+///
+/// The element is removed from the hint stream once all its bytes are read.
 #[allow(unused_variables)]
 #[no_mangle]
 pub extern "C" fn syscall_hint_read(ptr: *mut u8, len: usize) {
@@ -9,6 +9,8 @@ mod keccak_permute;
 mod memory;
 mod secp256k1;
 mod secp256r1;
+mod sha512_compress;
+mod sha512_extend;
 mod sha_compress;
 mod sha_extend;
 mod sys;
@@ -29,6 +31,8 @@ pub use keccak_permute::*;
 pub use memory::*;
 pub use secp256k1::*;
 pub use secp256r1::*;
+pub use sha512_compress::*;
+pub use sha512_extend::*;
 pub use sha_compress::*;
 pub use sha_extend::*;
 pub use sys::*;
@@ -157,3 +161,12 @@ pub const BN254_FP2_SUB: u32 = 0x00_01_01_2A;
 
 /// Executes the `BN254_FP2_MUL` precompile.
 pub const BN254_FP2_MUL: u32 = 0x00_01_01_2B;
+
+/// Executes `SHA512_EXTEND`.
+pub const SHA512_EXTEND: u32 = 0x00_40_01_30;
+
+/// Executes `SHA512_COMPRESS`.
+pub const SHA512_COMPRESS: u32 = 0x00_01_01_31;
+
+/// Executes the `BLS12381_FP12_MUL` precompile.
+pub const BLS12381_FP12_MUL: u32 = 0x00_01_01_32;
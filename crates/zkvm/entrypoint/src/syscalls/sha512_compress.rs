@@ -0,0 +1,25 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Executes the SHA-512 compress operation on the given word array and a given state.
+///
+/// ### Safety
+///
+/// The caller must ensure that `w` and `state` are valid pointers to data that is aligned along an
+/// eight byte boundary.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_sha512_compress(w: *mut [u64; 80], state: *mut [u64; 8]) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::SHA512_COMPRESS,
+            in("a0") w,
+            in("a1") state,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
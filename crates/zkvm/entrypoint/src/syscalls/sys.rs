@@ -2,6 +2,7 @@ use std::sync::Mutex;
 
 use lazy_static::lazy_static;
 use rand::{rngs::StdRng, Rng, SeedableRng};
+use sp1_primitives::consts::{fd::FD_PANIC, MAX_PANIC_MESSAGE_BYTES, PANIC_EXIT_CODE};
 
 use crate::syscalls::{syscall_halt, syscall_write};
 
@@ -42,6 +43,31 @@ pub unsafe extern "C" fn sys_panic(msg_ptr: *const u8, len: usize) -> ! {
     syscall_halt(1);
 }
 
+/// Reports a panic to the host by writing it to [`FD_PANIC`], and halts the program with
+/// [`PANIC_EXIT_CODE`].
+///
+/// The panic hook installed by the entrypoint calls this function, but programs replacing the
+/// hook can call it too. The message is truncated to [`MAX_PANIC_MESSAGE_BYTES`] bytes.
+pub fn report_panic(message: &str, location: Option<&core::panic::Location<'_>>) -> ! {
+    let mut len = message.len().min(MAX_PANIC_MESSAGE_BYTES);
+    while !message.is_char_boundary(len) {
+        len -= 1;
+    }
+    let message = &message[..len];
+    let (file, line, column) = location.map_or(("", 0, 0), |l| (l.file(), l.line(), l.column()));
+
+    // The report is written in parts, so that reporting a panic does not allocate.
+    let mut header = [0u8; 16];
+    let fields = [line, column, file.len() as u32, message.len() as u32];
+    for (bytes, field) in header.chunks_exact_mut(4).zip(fields) {
+        bytes.copy_from_slice(&field.to_le_bytes());
+    }
+    sys_write(FD_PANIC, header.as_ptr(), header.len());
+    sys_write(FD_PANIC, file.as_ptr(), file.len());
+    sys_write(FD_PANIC, message.as_ptr(), message.len());
+    syscall_halt(PANIC_EXIT_CODE);
+}
+
 #[allow(unused_variables)]
 #[no_mangle]
 pub const fn sys_getenv(
@@ -1,3 +1,5 @@
+pub mod pairing;
+
 use std::io::ErrorKind;
 
 use crate::{
@@ -5,6 +7,8 @@ use crate::{
     utils::{AffinePoint, WeierstrassAffinePoint, WeierstrassPoint},
 };
 
+pub use pairing::syscall_bls12381_pairing_check;
+
 /// The number of limbs in [Bls12381AffinePoint].
 pub const N: usize = 24;
 
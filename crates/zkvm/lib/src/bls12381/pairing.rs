@@ -0,0 +1,545 @@
+//! The BLS12-381 pairing check, built on the BLS12-381 field precompiles.
+//!
+//! The Miller loop and the final exponentiation follow the `bls12_381` crate. Fp12 products go
+//! through the `BLS12381_FP12_MUL` precompile, and everything else through the Fp and Fp2
+//! precompiles.
+
+use crate::{
+    syscall_bls12381_fp12_mulmod, syscall_bls12381_fp2_addmod, syscall_bls12381_fp2_mulmod,
+    syscall_bls12381_fp2_submod, syscall_bls12381_fp_addmod, syscall_bls12381_fp_mulmod,
+    syscall_bls12381_fp_submod,
+};
+
+/// The number of words of a base field element.
+const FP_WORDS: usize = 12;
+
+/// The absolute value of the BLS parameter `x`, which is negative.
+const BLS_X: u64 = 0xd201_0000_0001_0000;
+
+/// The modulus minus two, the exponent of the inverse of a base field element.
+const P_MINUS_2: [u32; FP_WORDS] = [
+    0xffffaaa9, 0xb9feffff, 0xb153ffff, 0x1eabfffe, 0xf6b0f624, 0x6730d2a0, 0xf38512bf, 0x64774b84,
+    0x434bacd7, 0x4b1ba7b6, 0x397fe69a, 0x1a0111ea,
+];
+
+/// `(u + 1)^(k (p - 1) / 6)` for the coefficient of `w^k`, used by the Frobenius map.
+const FROBENIUS_COEFFS_1: [Fp2; 6] = [
+    Fp2::ONE,
+    Fp2::new(
+        Fp([
+            0x92235fb8, 0x8d0775ed, 0x63e7813d, 0xf67ea53d, 0x84bab9c4, 0x7b2443d7, 0x3cbd5f4f,
+            0x0fd603fd, 0x202c0d1f, 0xc231beb4, 0x02bb0667, 0x1904d3bf,
+        ]),
+        Fp([
+            0x6ddc4af3, 0x2cf78a12, 0x4d6c7ec2, 0x282d5ac1, 0x71f63c5f, 0xec0c8ec9, 0xb6c7b36f,
+            0x54a14787, 0x231f9fb8, 0x88e9e902, 0x36c4e032, 0x00fc3e2b,
+        ]),
+    ),
+    Fp2::new(
+        Fp::ZERO,
+        Fp([
+            0x0000aaac, 0x8bfd0000, 0x4f49fffd, 0x409427eb, 0x0fb85f9b, 0x897d2965, 0x89759ad4,
+            0xaa0d857d, 0x63d4de85, 0xec024086, 0x397fe699, 0x1a0111ea,
+        ]),
+    ),
+    Fp2::new(
+        Fp([
+            0xede3cc09, 0xc81084fb, 0x72ec05f4, 0xee67992f, 0x009241c5, 0x77f76e17, 0xc2d3435e,
+            0x48395dab, 0x6bd17ffe, 0x6831e36d, 0x37ff400b, 0x06af0e04,
+        ]),
+        Fp([
+            0xede3cc09, 0xc81084fb, 0x72ec05f4, 0xee67992f, 0x009241c5, 0x77f76e17, 0xc2d3435e,
+            0x48395dab, 0x6bd17ffe, 0x6831e36d, 0x37ff400b, 0x06af0e04,
+        ]),
+    ),
+    Fp2::new(
+        Fp([
+            0x0000aaad, 0x8bfd0000, 0x4f49fffd, 0x409427eb, 0x0fb85f9b, 0x897d2965, 0x89759ad4,
+            0xaa0d857d, 0x63d4de85, 0xec024086, 0x397fe699, 0x1a0111ea,
+        ]),
+        Fp::ZERO,
+    ),
+    Fp2::new(
+        Fp([
+            0x80078116, 0x9b18fae9, 0x257f8732, 0xc63a3e6e, 0x8e9c0566, 0x8beadf4d, 0x0c0b8fee,
+            0xf3981624, 0x48b1e045, 0xdf47fa6b, 0x013a5fd8, 0x05b2cfd9,
+        ]),
+        Fp([
+            0x7ff82995, 0x1ee60516, 0x8bd478cd, 0x5871c190, 0x6814f0bd, 0xdb45f353, 0xe77982d0,
+            0x70df3560, 0xfa99cc91, 0x6bd3ad4a, 0x384586c1, 0x144e4211,
+        ]),
+    ),
+];
+
+/// `(u + 1)^(k (p^2 - 1) / 6)` for the coefficient of `w^k`, which all lie in the base field.
+const FROBENIUS_COEFFS_2: [Fp; 6] = [
+    Fp::ONE,
+    Fp([
+        0xfffeffff, 0x2e01ffff, 0x620a0002, 0xde17d813, 0xe6f89688, 0xddb3a93b, 0x6a0f77ea,
+        0xba69c607, 0xdf76ce51, 0x5f19672f, 0x00000000, 0x00000000,
+    ]),
+    Fp([
+        0xfffefffe, 0x2e01ffff, 0x620a0002, 0xde17d813, 0xe6f89688, 0xddb3a93b, 0x6a0f77ea,
+        0xba69c607, 0xdf76ce51, 0x5f19672f, 0x00000000, 0x00000000,
+    ]),
+    Fp([
+        0xffffaaaa, 0xb9feffff, 0xb153ffff, 0x1eabfffe, 0xf6b0f624, 0x6730d2a0, 0xf38512bf,
+        0x64774b84, 0x434bacd7, 0x4b1ba7b6, 0x397fe69a, 0x1a0111ea,
+    ]),
+    Fp([
+        0x0000aaac, 0x8bfd0000, 0x4f49fffd, 0x409427eb, 0x0fb85f9b, 0x897d2965, 0x89759ad4,
+        0xaa0d857d, 0x63d4de85, 0xec024086, 0x397fe699, 0x1a0111ea,
+    ]),
+    Fp([
+        0x0000aaad, 0x8bfd0000, 0x4f49fffd, 0x409427eb, 0x0fb85f9b, 0x897d2965, 0x89759ad4,
+        0xaa0d857d, 0x63d4de85, 0xec024086, 0x397fe699, 0x1a0111ea,
+    ]),
+];
+
+/// `(u + 1)^(k (p^3 - 1) / 6)` for the coefficient of `w^k`.
+const FROBENIUS_COEFFS_3: [Fp2; 6] = [
+    Fp2::ONE,
+    Fp2::new(
+        Fp([
+            0x121bdea2, 0xf1ee7b04, 0x3e67fa0a, 0x304466cf, 0xf61eb45e, 0xef396489, 0x30b1cf60,
+            0x1c3dedd9, 0xd77a2cd9, 0xe2e9c448, 0x0180a68e, 0x135203e6,
+        ]),
+        Fp([
+            0xede3cc09, 0xc81084fb, 0x72ec05f4, 0xee67992f, 0x009241c5, 0x77f76e17, 0xc2d3435e,
+            0x48395dab, 0x6bd17ffe, 0x6831e36d, 0x37ff400b, 0x06af0e04,
+        ]),
+    ),
+    Fp2::new(Fp::ZERO, Fp::ONE),
+    Fp2::new(
+        Fp([
+            0x121bdea2, 0xf1ee7b04, 0x3e67fa0a, 0x304466cf, 0xf61eb45e, 0xef396489, 0x30b1cf60,
+            0x1c3dedd9, 0xd77a2cd9, 0xe2e9c448, 0x0180a68e, 0x135203e6,
+        ]),
+        Fp([
+            0x121bdea2, 0xf1ee7b04, 0x3e67fa0a, 0x304466cf, 0xf61eb45e, 0xef396489, 0x30b1cf60,
+            0x1c3dedd9, 0xd77a2cd9, 0xe2e9c448, 0x0180a68e, 0x135203e6,
+        ]),
+    ),
+    Fp2::new(
+        Fp([
+            0xffffaaaa, 0xb9feffff, 0xb153ffff, 0x1eabfffe, 0xf6b0f624, 0x6730d2a0, 0xf38512bf,
+            0x64774b84, 0x434bacd7, 0x4b1ba7b6, 0x397fe69a, 0x1a0111ea,
+        ]),
+        Fp::ZERO,
+    ),
+    Fp2::new(
+        Fp([
+            0xede3cc09, 0xc81084fb, 0x72ec05f4, 0xee67992f, 0x009241c5, 0x77f76e17, 0xc2d3435e,
+            0x48395dab, 0x6bd17ffe, 0x6831e36d, 0x37ff400b, 0x06af0e04,
+        ]),
+        Fp([
+            0x121bdea2, 0xf1ee7b04, 0x3e67fa0a, 0x304466cf, 0xf61eb45e, 0xef396489, 0x30b1cf60,
+            0x1c3dedd9, 0xd77a2cd9, 0xe2e9c448, 0x0180a68e, 0x135203e6,
+        ]),
+    ),
+];
+
+/// Checks that the product of the pairings of the given points is one.
+///
+/// Each pair is a G1 point, as the little-endian words of its `x` and `y` coordinates, and a G2
+/// point, as the little-endian words of the `c0` and `c1` coefficients of its `x` coordinate and
+/// then of its `y` coordinate. A pair where either point is all zeros is the point at infinity,
+/// whose pairing is one, and is skipped.
+///
+/// The points must be on their curves and in the prime order subgroups, with their coordinates
+/// reduced modulo the base field modulus: this is not checked.
+pub fn syscall_bls12381_pairing_check(pairs: &[([u32; 24], [u32; 48])]) -> bool {
+    let pairs: Vec<(G1Affine, G2Affine)> = pairs
+        .iter()
+        .filter(|(p, q)| p.iter().any(|&word| word != 0) && q.iter().any(|&word| word != 0))
+        .map(|(p, q)| (G1Affine::from_words(p), G2Affine::from_words(q)))
+        .collect();
+    if pairs.is_empty() {
+        return true;
+    }
+    final_exponentiation(&miller_loop(&pairs)) == Fp12::ONE
+}
+
+/// An element of the base field.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Fp([u32; FP_WORDS]);
+
+impl Fp {
+    const ZERO: Self = Self([0; FP_WORDS]);
+
+    const ONE: Self = {
+        let mut words = [0; FP_WORDS];
+        words[0] = 1;
+        Self(words)
+    };
+
+    fn add(&self, rhs: &Self) -> Self {
+        let mut result = *self;
+        unsafe { syscall_bls12381_fp_addmod(result.0.as_mut_ptr(), rhs.0.as_ptr()) };
+        result
+    }
+
+    fn sub(&self, rhs: &Self) -> Self {
+        let mut result = *self;
+        unsafe { syscall_bls12381_fp_submod(result.0.as_mut_ptr(), rhs.0.as_ptr()) };
+        result
+    }
+
+    fn mul(&self, rhs: &Self) -> Self {
+        let mut result = *self;
+        unsafe { syscall_bls12381_fp_mulmod(result.0.as_mut_ptr(), rhs.0.as_ptr()) };
+        result
+    }
+
+    fn neg(&self) -> Self {
+        Self::ZERO.sub(self)
+    }
+
+    /// Inverts a nonzero element, as `self^(p - 2)` by Fermat's little theorem.
+    fn invert(&self) -> Self {
+        let mut result = Self::ONE;
+        for word in P_MINUS_2.iter().rev() {
+            for bit in (0..32).rev() {
+                result = result.mul(&result);
+                if (word >> bit) & 1 == 1 {
+                    result = result.mul(self);
+                }
+            }
+        }
+        result
+    }
+}
+
+/// An element of `Fp2 = Fp[u] / (u^2 + 1)`, as the words of its `c0` and `c1` coefficients.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Fp2([u32; 2 * FP_WORDS]);
+
+impl Fp2 {
+    const ZERO: Self = Self([0; 2 * FP_WORDS]);
+
+    const ONE: Self = Self::new(Fp::ONE, Fp::ZERO);
+
+    const fn new(c0: Fp, c1: Fp) -> Self {
+        let mut words = [0; 2 * FP_WORDS];
+        let mut i = 0;
+        while i < FP_WORDS {
+            words[i] = c0.0[i];
+            words[FP_WORDS + i] = c1.0[i];
+            i += 1;
+        }
+        Self(words)
+    }
+
+    fn c0(&self) -> Fp {
+        Fp(self.0[..FP_WORDS].try_into().unwrap())
+    }
+
+    fn c1(&self) -> Fp {
+        Fp(self.0[FP_WORDS..].try_into().unwrap())
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        let mut result = *self;
+        unsafe { syscall_bls12381_fp2_addmod(result.0.as_mut_ptr(), rhs.0.as_ptr()) };
+        result
+    }
+
+    fn sub(&self, rhs: &Self) -> Self {
+        let mut result = *self;
+        unsafe { syscall_bls12381_fp2_submod(result.0.as_mut_ptr(), rhs.0.as_ptr()) };
+        result
+    }
+
+    fn mul(&self, rhs: &Self) -> Self {
+        let mut result = *self;
+        unsafe { syscall_bls12381_fp2_mulmod(result.0.as_mut_ptr(), rhs.0.as_ptr()) };
+        result
+    }
+
+    fn square(&self) -> Self {
+        self.mul(self)
+    }
+
+    fn double(&self) -> Self {
+        self.add(self)
+    }
+
+    fn neg(&self) -> Self {
+        Self::ZERO.sub(self)
+    }
+
+    fn conjugate(&self) -> Self {
+        Self::new(self.c0(), self.c1().neg())
+    }
+
+    fn mul_by_fp(&self, rhs: &Fp) -> Self {
+        Self::new(self.c0().mul(rhs), self.c1().mul(rhs))
+    }
+
+    /// Multiplies by the nonresidue `u + 1`.
+    fn mul_by_nonresidue(&self) -> Self {
+        let (c0, c1) = (self.c0(), self.c1());
+        Self::new(c0.sub(&c1), c0.add(&c1))
+    }
+
+    fn invert(&self) -> Self {
+        let (c0, c1) = (self.c0(), self.c1());
+        let norm_inv = c0.mul(&c0).add(&c1.mul(&c1)).invert();
+        Self::new(c0.mul(&norm_inv), c1.neg().mul(&norm_inv))
+    }
+}
+
+/// An element of `Fp12 = Fp2[w] / (w^6 - (u + 1))`, as the words of its Fp2 coefficients of
+/// `1, w, ..., w^5`.
+///
+/// This is the layout of the `BLS12381_FP12_MUL` precompile. In the tower `Fp6 = Fp2[v] / (v^3 -
+/// (u + 1))`, `Fp12 = Fp6[w] / (w^2 - v)` of the `bls12_381` crate, the coefficient of `w^k` is
+/// `c(k % 2).c(k / 2)`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Fp12([u32; 12 * FP_WORDS]);
+
+impl Fp12 {
+    const ONE: Self =
+        Self::from_coeffs([Fp2::ONE, Fp2::ZERO, Fp2::ZERO, Fp2::ZERO, Fp2::ZERO, Fp2::ZERO]);
+
+    const fn from_coeffs(coeffs: [Fp2; 6]) -> Self {
+        let mut words = [0; 12 * FP_WORDS];
+        let mut i = 0;
+        while i < words.len() {
+            words[i] = coeffs[i / (2 * FP_WORDS)].0[i % (2 * FP_WORDS)];
+            i += 1;
+        }
+        Self(words)
+    }
+
+    fn coeffs(&self) -> [Fp2; 6] {
+        core::array::from_fn(|k| {
+            Fp2(self.0[2 * FP_WORDS * k..2 * FP_WORDS * (k + 1)].try_into().unwrap())
+        })
+    }
+
+    fn mul(&self, rhs: &Self) -> Self {
+        let mut result = *self;
+        unsafe { syscall_bls12381_fp12_mulmod(result.0.as_mut_ptr(), rhs.0.as_ptr()) };
+        result
+    }
+
+    fn square(&self) -> Self {
+        self.mul(self)
+    }
+
+    /// Raises to the power `p^6`, which negates the odd coefficients.
+    fn conjugate(&self) -> Self {
+        let mut coeffs = self.coeffs();
+        for coeff in coeffs.iter_mut().skip(1).step_by(2) {
+            *coeff = coeff.neg();
+        }
+        Self::from_coeffs(coeffs)
+    }
+
+    /// Raises to the power `p^power`, for `power` in `1..=3`.
+    fn frobenius_map(&self, power: usize) -> Self {
+        let coeffs = self.coeffs();
+        Self::from_coeffs(core::array::from_fn(|k| match power {
+            1 => coeffs[k].conjugate().mul(&FROBENIUS_COEFFS_1[k]),
+            2 => coeffs[k].mul_by_fp(&FROBENIUS_COEFFS_2[k]),
+            3 => coeffs[k].conjugate().mul(&FROBENIUS_COEFFS_3[k]),
+            _ => unreachable!(),
+        }))
+    }
+
+    /// Inverts a nonzero element.
+    ///
+    /// `self * self.conjugate()` lies in `Fp6 = Fp2[w^2]`, which is inverted by the norm to Fp2.
+    fn invert(&self) -> Self {
+        let conjugate = self.conjugate();
+        let [a0, _, a1, _, a2, _] = self.mul(&conjugate).coeffs();
+
+        let t0 = a0.square().sub(&a1.mul(&a2).mul_by_nonresidue());
+        let t1 = a2.square().mul_by_nonresidue().sub(&a0.mul(&a1));
+        let t2 = a1.square().sub(&a0.mul(&a2));
+        let norm = a0.mul(&t0).add(&a2.mul(&t1).add(&a1.mul(&t2)).mul_by_nonresidue());
+        let norm_inv = norm.invert();
+
+        let fp6_inv = Self::from_coeffs([
+            t0.mul(&norm_inv),
+            Fp2::ZERO,
+            t1.mul(&norm_inv),
+            Fp2::ZERO,
+            t2.mul(&norm_inv),
+            Fp2::ZERO,
+        ]);
+        conjugate.mul(&fp6_inv)
+    }
+
+    /// Raises an element of the cyclotomic subgroup to the power `x`.
+    fn cyclotomic_exp(&self) -> Self {
+        let mut result = Self::ONE;
+        let mut found_one = false;
+        for bit in (0..64).rev().map(|i| (BLS_X >> i) & 1 == 1) {
+            if found_one {
+                result = result.square();
+            } else {
+                found_one = bit;
+            }
+            if bit {
+                result = result.mul(self);
+            }
+        }
+        // x is negative, and the inverse of an element of the cyclotomic subgroup is its conjugate.
+        result.conjugate()
+    }
+
+    /// Multiplies by the line `c0 + c1 w^2 + c2 w^3`.
+    fn mul_by_line(&self, c0: &Fp2, c1: &Fp2, c2: &Fp2) -> Self {
+        self.mul(&Self::from_coeffs([*c0, Fp2::ZERO, *c1, *c2, Fp2::ZERO, Fp2::ZERO]))
+    }
+}
+
+/// An affine G1 point.
+struct G1Affine {
+    x: Fp,
+    y: Fp,
+}
+
+impl G1Affine {
+    fn from_words(words: &[u32; 24]) -> Self {
+        Self {
+            x: Fp(words[..FP_WORDS].try_into().unwrap()),
+            y: Fp(words[FP_WORDS..].try_into().unwrap()),
+        }
+    }
+}
+
+/// An affine G2 point.
+struct G2Affine {
+    x: Fp2,
+    y: Fp2,
+}
+
+impl G2Affine {
+    fn from_words(words: &[u32; 48]) -> Self {
+        Self {
+            x: Fp2(words[..2 * FP_WORDS].try_into().unwrap()),
+            y: Fp2(words[2 * FP_WORDS..].try_into().unwrap()),
+        }
+    }
+}
+
+/// A G2 point in Jacobian coordinates.
+struct G2Projective {
+    x: Fp2,
+    y: Fp2,
+    z: Fp2,
+}
+
+/// The coefficients of a line, to be evaluated at a G1 point with [`ell`].
+struct LineCoeffs(Fp2, Fp2, Fp2);
+
+/// Multiplies `f` by the line with the given coefficients evaluated at `p`.
+fn ell(f: &Fp12, coeffs: &LineCoeffs, p: &G1Affine) -> Fp12 {
+    let c0 = coeffs.0.mul_by_fp(&p.y);
+    let c1 = coeffs.1.mul_by_fp(&p.x);
+    f.mul_by_line(&coeffs.2, &c1, &c0)
+}
+
+/// Doubles `r`, and returns the line through it.
+fn doubling_step(r: &mut G2Projective) -> LineCoeffs {
+    let tmp0 = r.x.square();
+    let tmp1 = r.y.square();
+    let tmp2 = tmp1.square();
+    let tmp3 = tmp1.add(&r.x).square().sub(&tmp0).sub(&tmp2).double();
+    let tmp4 = tmp0.double().add(&tmp0);
+    let tmp6 = r.x.add(&tmp4);
+    let tmp5 = tmp4.square();
+    let zsquared = r.z.square();
+
+    r.x = tmp5.sub(&tmp3).sub(&tmp3);
+    r.z = r.z.add(&r.y).square().sub(&tmp1).sub(&zsquared);
+    r.y = tmp3.sub(&r.x).mul(&tmp4).sub(&tmp2.double().double().double());
+
+    let tmp3 = tmp4.mul(&zsquared).double().neg();
+    let tmp6 = tmp6.square().sub(&tmp0).sub(&tmp5).sub(&tmp1.double().double());
+    let tmp0 = r.z.mul(&zsquared).double();
+
+    LineCoeffs(tmp0, tmp3, tmp6)
+}
+
+/// Adds `q` to `r`, and returns the line through them.
+fn addition_step(r: &mut G2Projective, q: &G2Affine) -> LineCoeffs {
+    let zsquared = r.z.square();
+    let ysquared = q.y.square();
+    let t0 = zsquared.mul(&q.x);
+    let t1 = q.y.add(&r.z).square().sub(&ysquared).sub(&zsquared).mul(&zsquared);
+    let t2 = t0.sub(&r.x);
+    let t3 = t2.square();
+    let t4 = t3.double().double();
+    let t5 = t4.mul(&t2);
+    let t6 = t1.sub(&r.y).sub(&r.y);
+    let t9 = t6.mul(&q.x);
+    let t7 = t4.mul(&r.x);
+
+    r.x = t6.square().sub(&t5).sub(&t7).sub(&t7);
+    r.z = r.z.add(&t2).square().sub(&zsquared).sub(&t3);
+    let t8 = t7.sub(&r.x).mul(&t6);
+    r.y = t8.sub(&r.y.mul(&t5).double());
+
+    let t10 = q.y.add(&r.z).square().sub(&ysquared).sub(&r.z.square());
+    let t9 = t9.double().sub(&t10);
+    let t10 = r.z.double();
+    let t1 = t6.neg().double();
+
+    LineCoeffs(t10, t1, t9)
+}
+
+/// Computes the product of the Miller loops of the given pairs.
+fn miller_loop(pairs: &[(G1Affine, G2Affine)]) -> Fp12 {
+    let mut rs: Vec<G2Projective> =
+        pairs.iter().map(|(_, q)| G2Projective { x: q.x, y: q.y, z: Fp2::ONE }).collect();
+    let mut f = Fp12::ONE;
+
+    let mut found_one = false;
+    for bit in (0..64).rev().map(|i| ((BLS_X >> 1) >> i) & 1 == 1) {
+        if !found_one {
+            found_one = bit;
+            continue;
+        }
+
+        for ((p, _), r) in pairs.iter().zip(rs.iter_mut()) {
+            f = ell(&f, &doubling_step(r), p);
+        }
+        if bit {
+            for ((p, q), r) in pairs.iter().zip(rs.iter_mut()) {
+                f = ell(&f, &addition_step(r, q), p);
+            }
+        }
+        f = f.square();
+    }
+    for ((p, _), r) in pairs.iter().zip(rs.iter_mut()) {
+        f = ell(&f, &doubling_step(r), p);
+    }
+
+    // x is negative.
+    f.conjugate()
+}
+
+/// Raises the output of the Miller loop to the power `(p^12 - 1) / r`.
+fn final_exponentiation(f: &Fp12) -> Fp12 {
+    let t0 = f.conjugate();
+    let t1 = t0.mul(&f.invert());
+    let t2 = t1.frobenius_map(2).mul(&t1);
+    let t1 = t2.square().conjugate();
+    let t3 = t2.cyclotomic_exp();
+    let t4 = t3.square();
+    let t5 = t1.mul(&t3);
+    let t1 = t5.cyclotomic_exp();
+    let t0 = t1.cyclotomic_exp();
+    let t6 = t0.cyclotomic_exp().mul(&t4);
+    let t4 = t6.cyclotomic_exp();
+    let t4 = t4.mul(&t5.conjugate().mul(&t2));
+    let t5 = t2.conjugate();
+    let t1 = t1.mul(&t2).frobenius_map(3);
+    let t6 = t6.mul(&t5).frobenius_map(1);
+    let t3 = t3.mul(&t0).frobenius_map(2).mul(&t1).mul(&t6);
+    t3.mul(&t4)
+}
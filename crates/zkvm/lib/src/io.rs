@@ -1,6 +1,10 @@
 #![allow(unused_unsafe)]
-use crate::{read_vec_raw, syscall_write, ReadVecResult};
+use crate::{
+    read_vec_chunk_raw, read_vec_raw, syscall_hint_len, syscall_hint_read, syscall_write,
+    ReadVecResult,
+};
 use serde::{de::DeserializeOwned, Serialize};
+use sp1_primitives::io::{NamedInput, TypedHint, TypedHintError};
 use std::io::{Result, Write};
 
 pub use sp1_primitives::consts::fd::*;
@@ -87,6 +91,151 @@ pub fn read<T: DeserializeOwned>() -> T {
     bincode::deserialize(&vec).expect("deserialization failed")
 }
 
+/// The number of values read with [`read_hint`] so far.
+static mut HINT_INDEX: usize = 0;
+
+/// Read a value written with `SP1Stdin::write_hint` from the input stream, checking that it was
+/// written as a `T`.
+///
+/// If the value was written with another type, or without a type with `SP1Stdin::write`, the
+/// program aborts with a panic naming both types and the index of the hint among the values read
+/// with this function. See [`TypedHint`] for how the types are compared.
+///
+/// ### Examples
+/// ```ignore
+/// let data: (u64, String) = sp1_zkvm::io::read_hint();
+/// ```
+#[track_caller]
+pub fn read_hint<T: DeserializeOwned>() -> T {
+    let bytes = read_vec();
+    let index = unsafe { HINT_INDEX };
+    unsafe { HINT_INDEX = index + 1 };
+
+    let expected = std::any::type_name::<T>();
+    let Some(hint) = TypedHint::decode(&bytes) else {
+        panic!("expected hint type {expected}, found an untagged input at hint index {index}");
+    };
+    match hint.value() {
+        Ok(value) => value,
+        Err(TypedHintError::TypeMismatch { expected, found }) => {
+            panic!("expected hint type {expected}, found {found} at hint index {index}")
+        }
+        Err(TypedHintError::Deserialize) => panic!("deserialization failed"),
+    }
+}
+
+/// The number of values read with [`read_named`] so far.
+static mut NAMED_INPUT_INDEX: usize = 0;
+
+/// Read the value written with `SP1Stdin::write_named` under `name` from the input stream,
+/// checking that it was written as a `T`.
+///
+/// If the next input has another name, e.g. because the inputs were written in another order, has
+/// another type, or was written without a name, the program aborts with a panic naming the input
+/// and type expected by both sides and the index of the input among the values read with this
+/// function. Unnamed inputs can be read with the other functions in between.
+///
+/// ### Examples
+/// ```ignore
+/// let header: Header = sp1_zkvm::io::read_named("header");
+/// ```
+#[track_caller]
+pub fn read_named<T: DeserializeOwned>(name: &str) -> T {
+    let bytes = read_vec();
+    let index = unsafe { NAMED_INPUT_INDEX };
+    unsafe { NAMED_INPUT_INDEX = index + 1 };
+
+    let expected = std::any::type_name::<T>();
+    let Some(input) = NamedInput::decode(&bytes) else {
+        panic!(
+            "expected input `{name}` of type {expected}, found an unnamed input at named input \
+             index {index}"
+        );
+    };
+    if input.name != name {
+        panic!(
+            "expected input `{name}` of type {expected}, found input `{}` of type {} at named \
+             input index {index}",
+            input.name, input.hint.type_name
+        );
+    }
+    match input.hint.value() {
+        Ok(value) => value,
+        Err(TypedHintError::TypeMismatch { expected, found }) => panic!(
+            "expected input `{name}` of type {expected}, found input `{name}` of type {found} at \
+             named input index {index}"
+        ),
+        Err(TypedHintError::Deserialize) => panic!("deserialization failed"),
+    }
+}
+
+/// Read the next buffer of the input stream in chunks of at most `chunk_size` bytes.
+///
+/// Each chunk is read when the iterator is advanced, so that a large input can be streamed over
+/// without holding it in a single buffer. Like with [`read_vec`], the chunks are read into
+/// uninitialized memory, which is not reused once the chunks are dropped.
+///
+/// If the iterator is dropped before the end of the buffer, the next read from the input stream
+/// returns the rest of the buffer.
+///
+/// ### Examples
+/// ```ignore
+/// for chunk in sp1_zkvm::io::read_chunks(64 * 1024) {
+///     hasher.update(&chunk);
+/// }
+/// ```
+#[track_caller]
+pub fn read_chunks(chunk_size: usize) -> ReadChunks {
+    assert!(chunk_size > 0, "the chunk size must be positive");
+
+    let remaining = unsafe { syscall_hint_len() };
+    if remaining == usize::MAX {
+        panic!(
+            "Tried to read from the input stream, but it was empty @ {} \n
+            Was the correct data written into SP1Stdin?",
+            std::panic::Location::caller()
+        )
+    }
+
+    // An empty buffer has no chunks, but must still be removed from the input stream.
+    if remaining == 0 {
+        let ptr = std::ptr::NonNull::<u32>::dangling().as_ptr().cast();
+        unsafe { syscall_hint_read(ptr, 0) };
+    }
+
+    ReadChunks { remaining, chunk_size }
+}
+
+/// An iterator over the chunks of a buffer of the input stream, returned by [`read_chunks`].
+#[derive(Debug)]
+pub struct ReadChunks {
+    remaining: usize,
+    chunk_size: usize,
+}
+
+impl Iterator for ReadChunks {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Vec<u8>> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let ReadVecResult { ptr, len, capacity } = unsafe { read_vec_chunk_raw(self.chunk_size) };
+        self.remaining -= len;
+
+        // The chunk was allocated like the buffers returned by `read_vec`.
+        Some(unsafe { Vec::from_raw_parts(ptr, len, capacity) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let chunks = self.remaining.div_ceil(self.chunk_size);
+        (chunks, Some(chunks))
+    }
+}
+
+impl ExactSizeIterator for ReadChunks {}
+
 /// Commit a serializable object to the public values stream.
 ///
 /// ### Examples
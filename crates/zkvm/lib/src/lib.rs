@@ -9,6 +9,7 @@ pub mod ed25519;
 pub mod io;
 pub mod secp256k1;
 pub mod secp256r1;
+pub mod sha512;
 pub mod unconstrained;
 pub mod utils;
 #[cfg(feature = "verify")]
@@ -30,6 +31,12 @@ extern "C" {
     /// Executes the SHA-256 compress operation on the given word array and a given state.
     pub fn syscall_sha256_compress(w: *mut [u32; 64], state: *mut [u32; 8]);
 
+    /// Executes the SHA-512 extend operation on the given word array.
+    pub fn syscall_sha512_extend(w: *mut [u64; 80]);
+
+    /// Executes the SHA-512 compress operation on the given word array and a given state.
+    pub fn syscall_sha512_compress(w: *mut [u64; 80], state: *mut [u64; 8]);
+
     /// Executes an Ed25519 curve addition on the given points.
     pub fn syscall_ed_add(p: *mut [u32; 16], q: *const [u32; 16]);
 
@@ -127,6 +134,9 @@ extern "C" {
     /// Executes a BLS12-381 Fp2 multiplication on the given inputs.
     pub fn syscall_bls12381_fp2_mulmod(p: *mut u32, q: *const u32);
 
+    /// Executes a BLS12-381 Fp12 multiplication on the given inputs.
+    pub fn syscall_bls12381_fp12_mulmod(p: *mut u32, q: *const u32);
+
     /// Executes a BN254 field addition on the given inputs.
     pub fn syscall_bn254_fp_addmod(p: *mut u32, q: *const u32);
 
@@ -147,6 +157,9 @@ extern "C" {
 
     /// Reads a buffer from the input stream.
     pub fn read_vec_raw() -> ReadVecResult;
+
+    /// Reads at most `max_len` bytes of the next buffer of the input stream.
+    pub fn read_vec_chunk_raw(max_len: usize) -> ReadVecResult;
 }
 
 #[repr(C)]
@@ -0,0 +1,18 @@
+use crate::{syscall_sha512_compress, syscall_sha512_extend};
+
+/// Compresses the given 128-byte blocks into the SHA-512 state using the SHA-512 precompiles.
+///
+/// This has the same signature as `sha2::compress512`, which the patched `sha2` crate forwards to.
+/// Padding, including the 128-bit length encoding of the message, is left to the caller.
+pub fn compress(state: &mut [u64; 8], blocks: &[[u8; 128]]) {
+    let mut w = [0u64; 80];
+    for block in blocks {
+        for (word, chunk) in w.iter_mut().zip(block.chunks_exact(8)) {
+            *word = u64::from_be_bytes(chunk.try_into().unwrap());
+        }
+        unsafe {
+            syscall_sha512_extend(&mut w);
+            syscall_sha512_compress(&mut w, state);
+        }
+    }
+}
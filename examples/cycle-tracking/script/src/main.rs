@@ -16,9 +16,12 @@ fn main() {
     let (_, report) = client.execute(REPORT_ELF, &SP1Stdin::new()).run().expect("proving failed");
 
     // Get the "setup" cycle count from the report program.
-    let setup_cycles = report.cycle_tracker.get("setup").unwrap();
+    let setup = report.cycle_tracker.get("setup").unwrap();
     println!(
-        "Using cycle-tracker-report saves the number of cycles to the cycle-tracker mapping in the report.\nHere's the number of cycles used by the setup: {}",
-        setup_cycles
+        "The cycle tracker spans are saved to the cycle-tracker mapping in the report.\nHere's the number of cycles used by the setup: {}",
+        setup.total_cycles
     );
+
+    // Print the report, including a table of all the cycle tracker spans.
+    println!("{report}");
 }
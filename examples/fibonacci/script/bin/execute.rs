@@ -23,6 +23,10 @@ fn main() {
         "Executed program with {} cycles",
         execution_report.total_instruction_count() + execution_report.total_syscall_count()
     );
+    println!(
+        "Touched {} memory pages with a peak heap of {} bytes",
+        execution_report.memory_stats.touched_pages, execution_report.memory_stats.peak_heap_bytes
+    );
     println!("Full execution report:\n{:?}", execution_report);
 
     // Read and verify the output.
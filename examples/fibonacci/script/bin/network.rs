@@ -30,11 +30,11 @@ fn main() {
         Err(e) => {
             if let Some(network_error) = e.downcast_ref::<Error>() {
                 match network_error {
-                    Error::RequestUnexecutable { request_id: _ } => {
+                    Error::RequestUnexecutable { .. } => {
                         eprintln!("Program is unexecutable: {}", e);
                         std::process::exit(1);
                     }
-                    Error::RequestUnfulfillable { request_id: _ } => {
+                    Error::RequestUnfulfillable { .. } => {
                         eprintln!("Proof request cannot be fulfilled: {}", e);
                         std::process::exit(1);
                     }
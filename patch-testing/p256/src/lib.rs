@@ -24,6 +24,37 @@ pub fn test_verify_rand_lte_100(
     }
 }
 
+#[sp1_test::sp1_test("p256_verify", gpu, prove)]
+pub fn test_verify_fixed_signature(
+    stdin: &mut sp1_sdk::SP1Stdin,
+) -> impl FnOnce(sp1_sdk::SP1PublicValues) {
+    use ecdsa_core::signature::{Signer, Verifier};
+    use p256::ecdsa::{Signature, SigningKey};
+
+    // The key and message of the P-256, SHA-256 test vector of RFC 6979, section A.2.5. The
+    // signature is deterministic, so the guest verifies the same signature on every run.
+    let signing_key = SigningKey::from_bytes(
+        &hex_literal::hex!("C9AFA9D845BA75166B5C215767B1D6934E50C3DB36E89B127B8A622B120F6721")
+            .into(),
+    )
+    .unwrap();
+    let vkey = signing_key.verifying_key().to_sec1_bytes();
+    let message = b"sample".to_vec();
+    let sig: Signature = signing_key.sign(&message);
+    assert!(signing_key.verifying_key().verify(&message, &sig).is_ok());
+
+    let tampered = b"samplf".to_vec();
+
+    stdin.write(&2u8);
+    stdin.write(&(message, sig, vkey.clone()));
+    stdin.write(&(tampered, sig, vkey));
+
+    move |mut public| {
+        assert!(public.read::<bool>());
+        assert!(!public.read::<bool>());
+    }
+}
+
 #[sp1_test::sp1_test("p256_recover", gpu, prove)]
 pub fn test_recover_rand_lte_100(
     stdin: &mut sp1_sdk::SP1Stdin,